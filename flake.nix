@@ -29,6 +29,7 @@
           diskoPkg
           pkgs.bat
           pkgs.util-linux
+          pkgs.gptfdisk
           pkgs.gawk
           pkgs.gnugrep
           pkgs.gnused
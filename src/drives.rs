@@ -1,9 +1,14 @@
-use std::{process::Command, sync::atomic::AtomicU64};
-
-use ratatui::layout::Constraint;
+use std::{
+  path::Path,
+  process::{Command, Stdio},
+  sync::atomic::AtomicU64,
+  time::Instant,
+};
+
+use ratatui::{layout::Constraint, style::Color};
 use serde_json::Value;
 
-use crate::widget::TableWidget;
+use crate::widget::{BarSegment, LayoutBar, TableWidget};
 
 static NEXT_PART_ID: AtomicU64 = AtomicU64::new(1);
 
@@ -77,48 +82,124 @@ pub fn bytes_readable(bytes: u64) -> String {
 }
 
 /// Parse human-readable size strings into sector counts
-/// Supports various formats: "50 MiB", "500MB", "25%", "1024B"
+/// Supports various formats: "50 MiB", "500MB", "1.5gb", "25%", "1024B"
 /// Returns the equivalent number of sectors for the given sector size
-pub fn parse_sectors(s: &str, sector_size: u64, total_sectors: u64) -> Option<u64> {
+///
+/// Units are case-insensitive and may be separated from the number by spaces.
+/// SI units (KB, MB, GB, TB) are powers of 1000, binary units (KiB, MiB, GiB,
+/// TiB) powers of 1024. Bare letters like "G" are rejected since they could
+/// mean either. A number without a unit is a sector count.
+///
+/// Percentages are taken of `total_sectors`. Callers pass the size of the free
+/// space region being split, so "50%" always means half of what is left there
+pub fn parse_sectors(s: &str, sector_size: u64, total_sectors: u64) -> Result<u64, String> {
   let s = s.trim().to_lowercase();
+  let split = s
+    .find(|c: char| !c.is_ascii_digit() && c != '.')
+    .unwrap_or(s.len());
+  let (num_str, unit) = (&s[..split], s[split..].trim());
+  if num_str.is_empty() {
+    return Err(format!("'{s}' should start with a number"));
+  }
+  let value = num_str
+    .parse::<f64>()
+    .map_err(|_| format!("'{num_str}' is not a number"))?;
+
+  let multiplier = match unit {
+    "" => {
+      // Raw sector count
+      return num_str
+        .parse::<u64>()
+        .map_err(|_| "A sector count must be a whole number".to_string());
+    }
+    "%" => {
+      if value > 100.0 {
+        return Err(format!("{num_str}% is more than the space available"));
+      }
+      // Convert percentage to sectors (e.g., "50%" = half of total_sectors)
+      return Ok(((value / 100.0) * total_sectors as f64).round() as u64);
+    }
+    "b" => 1.0,
+    "kb" => 1_000.0,              // 10^3 bytes (decimal kilobyte)
+    "kib" => (1u64 << 10) as f64, // 2^10 bytes (binary kilobyte)
+    "mb" => 1_000_000.0,          // 10^6 bytes (decimal megabyte)
+    "mib" => (1u64 << 20) as f64, // 2^20 bytes (binary megabyte)
+    "gb" => 1_000_000_000.0,      // 10^9 bytes (decimal gigabyte)
+    "gib" => (1u64 << 30) as f64, // 2^30 bytes (binary gigabyte)
+    "tb" => 1_000_000_000_000.0,  // 10^12 bytes (decimal terabyte)
+    "tib" => (1u64 << 40) as f64, // 2^40 bytes (binary terabyte)
+    "k" | "m" | "g" | "t" => {
+      let unit = unit.to_uppercase();
+      return Err(format!("'{unit}' is ambiguous, use {unit}B or {unit}iB"));
+    }
+    _ => return Err(format!("Unknown unit '{unit}'")),
+  };
 
-  // Define multipliers for both binary (1024-based) and decimal (1000-based)
-  // units
-  let units: [(&str, f64); 10] = [
-    ("tib", (1u64 << 40) as f64), // 2^40 bytes (binary terabyte)
-    ("tb", 1_000_000_000_000.0),  // 10^12 bytes (decimal terabyte)
-    ("gib", (1u64 << 30) as f64), // 2^30 bytes (binary gigabyte)
-    ("gb", 1_000_000_000.0),      // 10^9 bytes (decimal gigabyte)
-    ("mib", (1u64 << 20) as f64), // 2^20 bytes (binary megabyte)
-    ("mb", 1_000_000.0),          // 10^6 bytes (decimal megabyte)
-    ("kib", (1u64 << 10) as f64), // 2^10 bytes (binary kilobyte)
-    ("kb", 1_000.0),              // 10^3 bytes (decimal kilobyte)
-    ("b", 1.0),                   // bytes
-    ("%", 0.0),                   // percentage (handled separately)
-  ];
-
-  for (unit, multiplier) in units.iter() {
-    if s.ends_with(unit) {
-      let num_str = s.trim_end_matches(unit).trim();
+  // Convert bytes to sectors by dividing by sector size
+  let sectors = (value * multiplier / sector_size as f64).round();
+  if sectors >= u64::MAX as f64 {
+    return Err(format!("'{s}' is too large"));
+  }
+  Ok(sectors as u64)
+}
 
-      if *unit == "%" {
-        // Convert percentage to sectors (e.g., "50%" = half of total_sectors)
-        return num_str
-          .parse::<f64>()
-          .ok()
-          .map(|v| ((v / 100.0) * total_sectors as f64).round() as u64);
-      } else {
-        // Convert bytes to sectors by dividing by sector size
-        return num_str
-          .parse::<f64>()
-          .ok()
-          .map(|v| ((v * multiplier) / sector_size as f64).round() as u64);
-      }
+/// Split a comma separated list of mount options like `noatime,compress=zstd`
+///
+/// Whitespace separates options too. Duplicates are dropped, keeping the
+/// first occurrence. An empty string means no extra options.
+pub fn parse_mount_options(s: &str) -> Result<Vec<String>, String> {
+  let is_valid = |c: char| c.is_ascii_alphanumeric() || "=-_.:/@+".contains(c);
+  let mut options: Vec<String> = vec![];
+  for opt in s.split(|c: char| c == ',' || c.is_whitespace()) {
+    if opt.is_empty() {
+      continue;
+    }
+    if opt.starts_with('=') || !opt.chars().all(is_valid) {
+      return Err(format!("'{opt}' is not a valid mount option"));
+    }
+    if !options.iter().any(|o| o == opt) {
+      options.push(opt.to_string());
     }
   }
+  Ok(options)
+}
+
+/// Smallest root partition the default layout will leave behind
+pub const MIN_ROOT_MIB: u64 = 8 * 1024;
+
+/// GRUB's core image fits comfortably in this, the partition holds no
+/// filesystem
+pub const BIOS_GRUB_MIB: u64 = 1;
 
-  // If no unit suffix found, interpret as raw sector count
-  s.parse::<u64>().ok()
+/// Whether the live system was booted through UEFI rather than legacy BIOS
+pub fn booted_with_uefi() -> bool {
+  Path::new("/sys/firmware/efi").exists()
+}
+
+/// Below this an ESP can't hold more than a couple of kernel generations
+pub const WARN_ESP_MIB: u64 = 256;
+/// Below this root won't even fit a minimal NixOS closure
+pub const WARN_ROOT_MIB: u64 = 4 * 1024;
+
+/// A warning if a partition mounted at `mount_point` is too small to be
+/// useful in that role
+///
+/// This is advice, not validation, nothing stops the partition from being
+/// created.
+pub fn small_partition_warning(mount_point: &str, size_bytes: u64) -> Option<String> {
+  let (role, min_mib) = match mount_point {
+    "/boot" | "/boot/efi" | "/efi" => ("boot partition", WARN_ESP_MIB),
+    "/" => ("root partition", WARN_ROOT_MIB),
+    _ => return None,
+  };
+  let min_bytes = min_mib * 1024 * 1024;
+  (size_bytes < min_bytes).then(|| {
+    format!(
+      "The {role} at {mount_point} is {}, at least {} is recommended",
+      bytes_readable(size_bytes),
+      bytes_readable(min_bytes)
+    )
+  })
 }
 
 /// Convert number of megabytes into sectors
@@ -138,39 +219,15 @@ pub fn mb_to_sectors(mb: u64, sector_size: u64) -> u64 {
 ///
 /// The installer assumes `lsblk` is available (provided by the Nix environment)
 pub fn lsblk() -> anyhow::Result<Vec<Disk>> {
-  /// Check if a device is safe to use for installation
-  ///
-  /// A device is considered unsafe if it or any of its partitions
-  /// are currently being used by the live system
-  fn is_safe_device(dev: &serde_json::Value) -> bool {
-    // Check if this device is mounted at critical mount points
-    if let Some(mount) = dev.get("mountpoint").and_then(|m| m.as_str()) {
-      if mount == "/" || mount == "/iso" {
-        // "/" is the root filesystem, "/iso" is common in live environments
-        return false;
-      }
-    }
-
-    // Recursively check all child partitions
-    if let Some(children) = dev.get("children").and_then(|c| c.as_array()) {
-      for child in children {
-        if !is_safe_device(child) {
-          return false;
-        }
-      }
-    }
-
-    true
-  }
   // Execute lsblk with specific options:
   // --json: JSON output format
   // -o: specify columns (name, size, type, mount, filesystem, label, start,
-  // physical sector size) -b: output sizes in bytes (not human-readable)
+  // physical sector size, model) -b: output sizes in bytes (not human-readable)
   let output = Command::new("lsblk")
     .args([
       "--json",
       "-o",
-      "NAME,SIZE,TYPE,MOUNTPOINT,FSTYPE,LABEL,START,PHY-SEC",
+      "NAME,SIZE,TYPE,MOUNTPOINT,FSTYPE,LABEL,START,PHY-SEC,MODEL,PARTTYPE",
       "-b",
     ])
     .output()?;
@@ -211,6 +268,243 @@ pub fn lsblk() -> anyhow::Result<Vec<Disk>> {
   Ok(disks)
 }
 
+/// Check if a device is safe to use for installation
+///
+/// A device is considered unsafe if it or any of its partitions
+/// are currently being used by the live system
+fn is_safe_device(dev: &serde_json::Value) -> bool {
+  // Check if this device is mounted at critical mount points
+  if let Some(mount) = dev.get("mountpoint").and_then(|m| m.as_str()) {
+    if mount == "/" || mount == "/iso" {
+      // "/" is the root filesystem, "/iso" is common in live environments
+      return false;
+    }
+  }
+
+  // Recursively check all child partitions
+  if let Some(children) = dev.get("children").and_then(|c| c.as_array()) {
+    for child in children {
+      if !is_safe_device(child) {
+        return false;
+      }
+    }
+  }
+
+  true
+}
+
+/// An existing filesystem outside the install layout
+#[derive(Clone, Debug)]
+pub struct DataPartition {
+  /// Device name without `/dev/`, e.g. `sdb1`
+  pub name: String,
+  pub uuid: String,
+  pub fs_type: String,
+  pub label: Option<String>,
+  pub size: u64,
+}
+
+/// Formatted partitions that could be mounted as extra data disks
+///
+/// Skips the disks in the layout `target_disks`, devices used by the live
+/// system, and anything that can't be mounted directly like swap, LUKS
+/// containers or LVM/RAID members.
+pub fn data_partitions(target_disks: &[&str]) -> anyhow::Result<Vec<DataPartition>> {
+  const NOT_MOUNTABLE: [&str; 5] = [
+    "swap",
+    "crypto_LUKS",
+    "LVM2_member",
+    "linux_raid_member",
+    "zfs_member",
+  ];
+  let output = Command::new("lsblk")
+    .args([
+      "--json",
+      "-o",
+      "NAME,SIZE,TYPE,MOUNTPOINT,FSTYPE,LABEL,UUID",
+      "-b",
+    ])
+    .output()?;
+  if !output.status.success() {
+    return Err(anyhow::anyhow!(
+      "lsblk command failed with status: {}",
+      output.status
+    ));
+  }
+  let lsblk_json: Value = serde_json::from_slice(&output.stdout)
+    .map_err(|e| anyhow::anyhow!("Failed to parse lsblk output as JSON: {}", e))?;
+
+  let mut found = vec![];
+  let disks = lsblk_json
+    .get("blockdevices")
+    .and_then(|v| v.as_array())
+    .ok_or_else(|| anyhow::anyhow!("lsblk output missing 'blockdevices' array"))?
+    .iter()
+    .filter(|dev| dev["type"].as_str() == Some("disk"))
+    .filter(|dev| {
+      dev["name"]
+        .as_str()
+        .is_none_or(|name| !target_disks.contains(&name))
+    })
+    .filter(|dev| is_safe_device(dev));
+  for disk in disks {
+    let Some(children) = disk["children"].as_array() else {
+      continue;
+    };
+    for part in children {
+      let (Some(name), Some(uuid), Some(fs_type)) = (
+        part["name"].as_str(),
+        part["uuid"].as_str(),
+        part["fstype"].as_str(),
+      ) else {
+        continue;
+      };
+      if NOT_MOUNTABLE.contains(&fs_type) {
+        continue;
+      }
+      found.push(DataPartition {
+        name: name.to_string(),
+        uuid: uuid.to_string(),
+        fs_type: fs_type.to_string(),
+        label: part["label"].as_str().map(|s| s.to_string()),
+        size: part["size"].as_u64().unwrap_or(0),
+      });
+    }
+  }
+  Ok(found)
+}
+
+/// Something on a disk that holds it busy: an LVM physical volume, a RAID
+/// member or a LUKS container
+#[derive(Clone, Debug)]
+pub struct StorageStack {
+  pub description: String,
+  /// Shell command that releases it, innermost layers first
+  pub teardown: Option<String>,
+}
+
+/// Find LVM, RAID and LUKS layers on `disk`
+///
+/// disko's destroy step can't wipe a partition that an open LUKS mapping, an
+/// active volume group or a running md array still holds, and the error it
+/// gives doesn't say why.
+pub fn storage_stacks(disk: &str) -> anyhow::Result<Vec<StorageStack>> {
+  fn walk(node: &Value, parent: Option<&str>, found: &mut Vec<StorageStack>) {
+    let name = node["name"].as_str().unwrap_or_default();
+    let dev_type = node["type"].as_str().unwrap_or_default();
+    let fs_type = node["fstype"].as_str().unwrap_or_default();
+    // Children first, so the layers above are released before this one
+    if let Some(children) = node["children"].as_array() {
+      for child in children {
+        walk(child, Some(name), found);
+      }
+    }
+    let mut push = |description: String, teardown: Option<String>| {
+      let seen = found
+        .iter()
+        .any(|s| s.description == description || (teardown.is_some() && s.teardown == teardown));
+      if !seen {
+        found.push(StorageStack {
+          description,
+          teardown,
+        });
+      }
+    };
+    match fs_type {
+      "LVM2_member" => push(
+        format!("{name} is an LVM physical volume"),
+        Some(format!(
+          "pvs --noheadings -o vg_name /dev/{name} | xargs -r vgremove -ff -y"
+        )),
+      ),
+      "crypto_LUKS" => push(format!("{name} is a LUKS container"), None),
+      "linux_raid_member" => push(format!("{name} is a RAID array member"), None),
+      _ => {}
+    }
+    let parent = parent.unwrap_or("?");
+    match dev_type {
+      "crypt" => push(
+        format!("LUKS mapping {name} is open on {parent}"),
+        Some(format!("cryptsetup close {name}")),
+      ),
+      t if t.starts_with("raid") => push(
+        format!("RAID array {name} is running on {parent}"),
+        Some(format!("mdadm --stop /dev/{name}")),
+      ),
+      _ => {}
+    }
+  }
+
+  let output = Command::new("lsblk")
+    .args(["--json", "-o", "NAME,TYPE,FSTYPE", &format!("/dev/{disk}")])
+    .output()?;
+  if !output.status.success() {
+    return Err(anyhow::anyhow!(
+      "lsblk command failed with status: {}",
+      output.status
+    ));
+  }
+  let lsblk_json: Value = serde_json::from_slice(&output.stdout)
+    .map_err(|e| anyhow::anyhow!("Failed to parse lsblk output as JSON: {}", e))?;
+  let mut found = vec![];
+  if let Some(devices) = lsblk_json["blockdevices"].as_array() {
+    for dev in devices {
+      walk(dev, None, &mut found);
+    }
+  }
+  Ok(found)
+}
+
+/// How much `read_speed` reads from the start of the disk
+const SPEED_TEST_BYTES: u64 = 256 << 20;
+/// Longest `read_speed` may take before it gives up
+const SPEED_TEST_TIMEOUT_SECS: u64 = 15;
+
+/// Measure the sequential read speed of `disk` in bytes per second
+///
+/// Only ever reads from the device, into /dev/null. O_DIRECT keeps the page
+/// cache from inflating the result, and `timeout` stops it on slow or failing
+/// drives. Small devices end before the full amount, so the speed comes from
+/// what dd reports having copied.
+pub fn read_speed(disk: &str) -> anyhow::Result<f64> {
+  let start = Instant::now();
+  let output = Command::new("timeout")
+    .args([
+      &SPEED_TEST_TIMEOUT_SECS.to_string(),
+      "dd",
+      &format!("if=/dev/{disk}"),
+      "of=/dev/null",
+      "bs=1M",
+      &format!("count={}", SPEED_TEST_BYTES >> 20),
+      "iflag=direct",
+    ])
+    .stdin(Stdio::null())
+    .stdout(Stdio::null())
+    .output()?;
+  let elapsed = start.elapsed().as_secs_f64();
+  let status = output.status;
+  match status.code() {
+    Some(0) => match dd_bytes_copied(&String::from_utf8_lossy(&output.stderr)) {
+      Some(bytes) if bytes > 0 => Ok(bytes as f64 / elapsed),
+      _ => Err(anyhow::anyhow!("dd didn't report reading anything")),
+    },
+    Some(124) => Err(anyhow::anyhow!(
+      "gave up after {SPEED_TEST_TIMEOUT_SECS}s, slower than {}/s",
+      bytes_readable(SPEED_TEST_BYTES / SPEED_TEST_TIMEOUT_SECS)
+    )),
+    _ => Err(anyhow::anyhow!("dd failed with {status}")),
+  }
+}
+
+/// Bytes dd copied, from the `268435456 bytes (268 MB, 256 MiB) copied, ...`
+/// line it ends with
+fn dd_bytes_copied(stderr: &str) -> Option<u64> {
+  stderr.lines().find_map(|line| {
+    let (bytes, rest) = line.trim().split_once(' ')?;
+    rest.starts_with("bytes").then(|| bytes.parse().ok())?
+  })
+}
+
 /// Parse a single disk entry from lsblk JSON output into our Disk structure
 ///
 /// Extracts disk metadata (name, size, sector size) and recursively parses
@@ -245,10 +539,20 @@ pub fn parse_disk(disk: Value) -> anyhow::Result<Disk> {
 
   // Convert byte size to sector count and create disk object
   let mut disk = Disk::new(name, size / sector_size, sector_size, layout);
+  disk.set_model(
+    obj
+      .get("model")
+      .and_then(|v| v.as_str())
+      .map(|m| m.trim().to_string())
+      .filter(|m| !m.is_empty()),
+  );
   disk.calculate_free_space(); // Calculate available free space between partitions
   Ok(disk)
 }
 
+/// GPT partition type GUID of an EFI system partition
+const ESP_PARTTYPE: &str = "c12a7328-f81f-11d2-ba4b-00a0c93ec93b";
+
 /// Parse a single partition entry from lsblk JSON output
 ///
 /// Converts lsblk partition data into our DiskItem::Partition structure
@@ -290,8 +594,12 @@ pub fn parse_partition(part: &Value) -> anyhow::Result<DiskItem> {
   // Note: lsblk doesn't provide read-only status in our query
   let ro = false;
 
-  // Partition flags (like "boot", "esp") would need additional detection
-  let flags = vec![];
+  // Only the ESP can be told from the partition type, other flags would need
+  // additional detection
+  let flags = match obj.get("parttype").and_then(|v| v.as_str()) {
+    Some(guid) if guid.eq_ignore_ascii_case(ESP_PARTTYPE) => vec!["esp".to_string()],
+    _ => vec![],
+  };
 
   // Existing partitions discovered by lsblk are marked as "Exists"
   let status = PartStatus::Exists;
@@ -335,6 +643,43 @@ pub fn part_table(disk_items: &[DiskItem], sector_size: u64) -> TableWidget {
   TableWidget::new("Partitions", widths, headers, rows)
 }
 
+/// Return a bar showing each partition and free region of a disk to scale
+///
+/// Deleted partitions are left out, their space shows up as free.
+pub fn part_bar(disk: &Disk) -> LayoutBar {
+  let mut items = disk
+    .layout()
+    .iter()
+    .filter(|item| !matches!(item, DiskItem::Partition(p) if p.status == PartStatus::Delete))
+    .collect::<Vec<_>>();
+  items.sort_by_key(|item| item.start());
+  let segments = items
+    .into_iter()
+    .map(|item| match item {
+      DiskItem::Partition(p) => BarSegment {
+        label: p
+          .mount_point()
+          .or(p.label())
+          .or(p.name())
+          .unwrap_or("?")
+          .to_string(),
+        size: p.size_bytes(disk.sector_size()),
+        color: match p.status() {
+          PartStatus::Create => Color::Green,
+          PartStatus::Modify => Color::Yellow,
+          _ => Color::Blue,
+        },
+      },
+      DiskItem::FreeSpace { size, .. } => BarSegment {
+        label: "free".into(),
+        size: size * disk.sector_size(),
+        color: Color::DarkGray,
+      },
+    })
+    .collect();
+  LayoutBar::new(format!("/dev/{}", disk.name()), segments)
+}
+
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 /// Represents a physical disk drive and its partition layout
 ///
@@ -352,6 +697,12 @@ pub struct Disk {
   /// Partitions use half-open ranges: [start, start+size)
   /// This means start sector is included, end sector is excluded
   layout: Vec<DiskItem>,
+  /// Give partitions without a label one derived from their mount point
+  #[serde(default)]
+  auto_labels: bool,
+  /// Model string reported by lsblk, for telling disks apart
+  #[serde(default)]
+  model: Option<String>,
 }
 
 impl Disk {
@@ -363,6 +714,8 @@ impl Disk {
       initial_layout: layout.clone(),
       total_used_sectors: 0,
       layout,
+      auto_labels: false,
+      model: None,
     };
     new.calculate_free_space();
     new
@@ -387,18 +740,34 @@ impl Disk {
       })
       .collect()
   }
-  /// Convert the disk into a `disko` config
-  pub fn as_disko_cfg(&mut self) -> serde_json::Value {
+  /// Convert the disk into a `disko` config, `disk_name` being its key under
+  /// `disko.devices.disk`
+  ///
+  /// When partitions are kept, disko only adds the missing ones next to them.
+  /// Every partition then gets its exact sectors and on-disk order, otherwise
+  /// disko would lay them out by size from the start of the disk.
+  pub fn as_disko_cfg(&mut self, disk_name: &str) -> serde_json::Value {
     let mut partitions = serde_json::Map::new();
+    let pinned = self.partitions().any(|p| *p.status() == PartStatus::Exists);
+    let mut priority = 0;
     for item in &self.layout {
       if let DiskItem::Partition(p) = item {
         if *p.status() == PartStatus::Delete {
           continue;
         }
-        let name = p
+        let label = p
           .label()
           .map(|s| s.to_string())
-          .unwrap_or_else(|| format!("part{}", p.id()));
+          .or_else(|| self.auto_labels.then(|| p.auto_label()).flatten());
+        let mut name = label.clone().unwrap_or_else(|| format!("part{}", p.id()));
+        // Partition names become GPT partition labels, which disko looks
+        // partitions up by, so two partitions can't share one
+        let base = name.clone();
+        let mut suffix = 2;
+        while partitions.contains_key(&name) {
+          name = format!("{base}-{suffix}");
+          suffix += 1;
+        }
         let size = bytes_disko_cfg(
           p.size_bytes(p.sector_size),
           self.total_used_sectors,
@@ -406,26 +775,74 @@ impl Disk {
           self.size,
         );
 
-        if p.flags.contains(&"esp".to_string()) {
-          partitions.insert(
-            name,
-            serde_json::json!({
-              "size": size,
-              "type": p.fs_gpt_code(p.flags.contains(&"esp".to_string())),
-              "format": p.disko_fs_type(),
-              "mountpoint": p.mount_point(),
-            }),
-          );
+        let mut part_cfg = if p.has_flag("bios_grub") {
+          // GRUB embeds its core image here on GPT disks booted in BIOS mode
+          serde_json::json!({
+            "size": size,
+            "type": "EF02",
+          })
+        } else if p.disko_fs_type() == Some("swap") {
+          serde_json::json!({
+            "size": size,
+            "type": p.fs_gpt_code(false),
+            "content": { "type": "swap" },
+          })
+        } else if p.has_flag("esp") {
+          serde_json::json!({
+            "size": size,
+            "type": p.fs_gpt_code(true),
+            "format": p.disko_fs_type(),
+            "mountpoint": p.mount_point(),
+          })
         } else {
-          partitions.insert(
-            name,
-            serde_json::json!({
-              "size": size,
-              "format": p.disko_fs_type(),
-              "mountpoint": p.mount_point(),
-            }),
-          );
+          serde_json::json!({
+            "size": size,
+            "format": p.disko_fs_type(),
+            "mountpoint": p.mount_point(),
+          })
+        };
+        if pinned {
+          // sgdisk takes bare numbers as sectors, the end one is inclusive
+          priority += 1;
+          part_cfg["start"] = p.start().to_string().into();
+          part_cfg["end"] = (p.end() - 1).to_string().into();
+          part_cfg["priority"] = priority.into();
         }
+        if *p.status() == PartStatus::Exists {
+          // Kept partitions must not be given filesystem content, or disko
+          // would format them
+          part_cfg["keep"] = true.into();
+          part_cfg["device"] = p.name().map(|n| format!("/dev/{n}")).into();
+          part_cfg["fs_type"] = p.fs_type().into();
+        } else if let Some(label) = label {
+          part_cfg["label"] = label.into();
+        }
+        if !p.mount_options().is_empty() && !p.has_flag("bios_grub") {
+          part_cfg["mount_options"] = p.mount_options().into();
+        }
+        // The firmware can't read an encrypted ESP, and a BIOS boot partition
+        // has no filesystem to encrypt
+        if let Some(luks) = p.luks()
+          && *p.status() != PartStatus::Exists
+          && !p.has_flag("esp")
+          && !p.has_flag("bios_grub")
+        {
+          let mut luks_cfg = serde_json::json!({
+            "name": luks.name,
+            "cipher": luks.cipher,
+            "device": format!("/dev/disk/by-partlabel/disk-{disk_name}-{name}"),
+          });
+          match &luks.key {
+            LuksKey::Passphrase { .. } => {
+              luks_cfg["password_file"] = luks.passphrase_path().into();
+            }
+            LuksKey::KeyFile { path } => {
+              luks_cfg["key_file"] = path.clone().into();
+            }
+          }
+          part_cfg["luks"] = luks_cfg;
+        }
+        partitions.insert(name, part_cfg);
         self.total_used_sectors += p.size();
       }
     }
@@ -461,6 +878,39 @@ impl Disk {
   pub fn layout(&self) -> &[DiskItem] {
     &self.layout
   }
+  pub fn auto_labels(&self) -> bool {
+    self.auto_labels
+  }
+  pub fn model(&self) -> Option<&str> {
+    self.model.as_deref()
+  }
+  pub fn set_model(&mut self, model: Option<String>) {
+    self.model = model;
+  }
+  pub fn set_auto_labels(&mut self, auto_labels: bool) {
+    self.auto_labels = auto_labels;
+  }
+  /// Partitions to be encrypted with a passphrase that isn't known, e.g.
+  /// after resuming from saved settings
+  pub fn luks_missing_passphrase(&self) -> bool {
+    self
+      .partitions()
+      .filter(|p| matches!(p.status, PartStatus::Create | PartStatus::Modify))
+      .filter_map(|p| p.luks())
+      .any(|luks| matches!(&luks.key, LuksKey::Passphrase { passphrase } if passphrase.is_empty()))
+  }
+  /// Whether a partition that is not being deleted is formatted as swap
+  pub fn has_swap_partition(&self) -> bool {
+    self
+      .partitions()
+      .any(|p| p.status != PartStatus::Delete && p.disko_fs_type() == Some("swap"))
+  }
+  /// Whether a partition that is not being deleted is flagged `bios_grub`
+  pub fn has_bios_grub(&self) -> bool {
+    self
+      .partitions()
+      .any(|p| p.status != PartStatus::Delete && p.has_flag("bios_grub"))
+  }
   pub fn partitions(&self) -> impl Iterator<Item = &Partition> {
     self.layout.iter().filter_map(|item| {
       if let DiskItem::Partition(p) = item {
@@ -513,6 +963,109 @@ impl Disk {
     self.calculate_free_space();
     Ok(())
   }
+  /// Give every partition and free space entry a fresh id
+  ///
+  /// Ids come from a per-process counter, so a layout read back from a file
+  /// can collide with entries created in this session.
+  pub fn refresh_ids(&mut self) {
+    for item in self.layout.iter_mut().chain(self.initial_layout.iter_mut()) {
+      match item {
+        DiskItem::Partition(p) => p.id = get_entry_id(),
+        DiskItem::FreeSpace { id, .. } => *id = get_entry_id(),
+      }
+    }
+  }
+  /// Commands that take partitions marked for deletion or formatting off the
+  /// partition table before disko runs
+  ///
+  /// When partitions are kept disko only runs in format mode, which adds
+  /// missing partitions but never removes any. Formatted partitions are wiped
+  /// and removed as well, disko then recreates them in the same place.
+  pub fn removal_commands(&self) -> Vec<String> {
+    let mut cmds = vec![];
+    for part in self.partitions() {
+      if !matches!(part.status(), PartStatus::Delete | PartStatus::Modify) {
+        continue;
+      }
+      let (Some(name), Some(number)) = (part.name(), part.number()) else {
+        continue;
+      };
+      cmds.push(format!("wipefs -a /dev/{name}"));
+      cmds.push(format!("sgdisk --delete={number} /dev/{}", self.name));
+    }
+    if !cmds.is_empty() {
+      cmds.push(format!("partx -u /dev/{}", self.name));
+      cmds.push("udevadm settle".to_string());
+    }
+    cmds
+  }
+  /// Recreate the partitions of a saved layout on this disk
+  ///
+  /// Everything currently on this disk is marked for deletion. Only layouts
+  /// made entirely of new partitions can be moved to another disk, existing
+  /// partitions only make sense on the disk they came from.
+  pub fn apply_layout(&mut self, saved: &Disk) -> anyhow::Result<()> {
+    if saved.sector_size != self.sector_size {
+      return Err(anyhow::anyhow!(
+        "Saved layout uses {} byte sectors but /dev/{} uses {}",
+        saved.sector_size,
+        self.name,
+        self.sector_size
+      ));
+    }
+    let parts = saved
+      .partitions()
+      .filter(|p| p.status != PartStatus::Delete)
+      .cloned()
+      .collect::<Vec<_>>();
+    if parts.iter().any(|p| p.status != PartStatus::Create) {
+      return Err(anyhow::anyhow!(
+        "Saved layout keeps existing partitions from /dev/{}, it can only be used on that disk",
+        saved.name
+      ));
+    }
+    if parts.iter().any(|p| p.end() > self.size) {
+      return Err(anyhow::anyhow!(
+        "Saved layout does not fit on /dev/{} ({})",
+        self.name,
+        bytes_readable(self.size_bytes())
+      ));
+    }
+    self.layout.retain(|item| match item {
+      DiskItem::FreeSpace { .. } => false,
+      DiskItem::Partition(part) => part.status != PartStatus::Create,
+    });
+    for part in self.partitions_mut() {
+      part.status = PartStatus::Delete;
+    }
+    for mut part in parts {
+      part.id = get_entry_id();
+      self.new_partition(part)?;
+    }
+    Ok(())
+  }
+  /// Bring a partition marked for deletion back as either kept or formatted
+  ///
+  /// Fails if the space it occupied has since been given to a new partition.
+  pub fn restore_partition(&mut self, id: u64, status: PartStatus) -> anyhow::Result<()> {
+    let Some(part) = self.partition_by_id(id) else {
+      return Err(anyhow::anyhow!("No partition found with id {}", id));
+    };
+    let (start, end) = (part.start(), part.end());
+    let overlaps = self.partitions().any(|p| {
+      p.id() != id && p.status != PartStatus::Delete && start < p.end() && end > p.start()
+    });
+    if overlaps {
+      return Err(anyhow::anyhow!(
+        "The space used by this partition has been reused, reset the layout to restore it"
+      ));
+    }
+    if let Some(part) = self.partition_by_id_mut(id) {
+      part.set_status(status);
+    }
+    self.calculate_free_space();
+    Ok(())
+  }
   pub fn new_partition(&mut self, part: Partition) -> anyhow::Result<()> {
     // Ensure the new partition does not overlap existing partitions
     self.clear_free_space();
@@ -675,8 +1228,48 @@ impl Disk {
   /// - 500MB FAT32 boot partition (ESP) at the beginning
   /// - Remaining space for root filesystem (specified fs_type or default)
   ///
+  /// With `bios_grub` a 1MiB BIOS boot partition goes in front of the ESP,
+  /// for GRUB on a machine without UEFI.
+  ///
   /// All existing partitions are marked for deletion
-  pub fn use_default_layout(&mut self, fs_type: Option<String>) {
+  pub fn use_default_layout(&mut self, fs_type: Option<String>, bios_grub: bool) {
+    self.use_default_layout_with_mounts(fs_type, &[], bios_grub);
+  }
+
+  /// Make sure the extra mounts fit next to the boot partition while leaving
+  /// root at least `MIN_ROOT_MIB`
+  pub fn check_extra_mounts(&self, extra_mounts: &[(String, u64)]) -> anyhow::Result<()> {
+    // Counts a BIOS boot partition even when there won't be one
+    let boot = 2048 + mb_to_sectors(BIOS_GRUB_MIB + 500, self.sector_size);
+    let extra: u64 = extra_mounts.iter().map(|(_, size)| size).sum();
+    let min_root = mb_to_sectors(MIN_ROOT_MIB, self.sector_size);
+    if boot + extra + min_root > self.size {
+      return Err(anyhow::anyhow!(
+        "Requested partitions need {} but only {} is available after leaving {} for root",
+        bytes_readable(extra * self.sector_size),
+        bytes_readable(
+          self
+            .size
+            .saturating_sub(boot + min_root)
+            .saturating_mul(self.sector_size)
+        ),
+        bytes_readable(min_root * self.sector_size)
+      ));
+    }
+    Ok(())
+  }
+
+  /// The default layout with extra partitions for mounts like `/var` or
+  /// `/nix`, given as (mount point, size in sectors)
+  ///
+  /// The extra partitions sit between boot and root, so root still takes
+  /// whatever space is left at the end of the disk.
+  pub fn use_default_layout_with_mounts(
+    &mut self,
+    fs_type: Option<String>,
+    extra_mounts: &[(String, u64)],
+    bios_grub: bool,
+  ) {
     // Remove all free space and newly created partitions
     // Keep existing partitions so user can see what will be deleted
     self.layout.retain(|item| match item {
@@ -690,10 +1283,27 @@ impl Disk {
       };
       part.status = PartStatus::Delete
     }
-    // Create 500MB FAT32 boot partition starting at sector 2048 (1MB aligned)
+    let mut cursor = 2048; // Start at 1MB boundary
+    let bios_part = bios_grub.then(|| {
+      let part = Partition::new(
+        cursor,
+        mb_to_sectors(BIOS_GRUB_MIB, self.sector_size),
+        self.sector_size,
+        PartStatus::Create,
+        None,
+        None,
+        None,
+        None,
+        false,
+        vec!["bios_grub".into()],
+      );
+      cursor = part.end();
+      part
+    });
+    // Create 500MB FAT32 boot partition, 1MB aligned
     // This serves as the EFI System Partition (ESP)
     let boot_part = Partition::new(
-      2048,                                 // Start at 1MB boundary
+      cursor,
       mb_to_sectors(500, self.sector_size), // 500MB size
       self.sector_size,
       PartStatus::Create,
@@ -704,10 +1314,31 @@ impl Disk {
       false,
       vec!["boot".into(), "esp".into()], // Mark as bootable ESP
     );
+    cursor = boot_part.end();
+    let mut extra_parts = vec![];
+    for (mount_point, size) in extra_mounts {
+      let label = mount_point
+        .trim_matches('/')
+        .replace('/', "")
+        .to_uppercase();
+      extra_parts.push(Partition::new(
+        cursor,
+        *size,
+        self.sector_size,
+        PartStatus::Create,
+        None,
+        fs_type.clone(),
+        Some(mount_point.clone()),
+        Some(label),
+        false,
+        vec![],
+      ));
+      cursor += size;
+    }
     // Create root partition using all remaining space
     let root_part = Partition::new(
-      boot_part.end(),               // Start immediately after boot partition
-      self.size - (boot_part.end()), // Use all remaining disk space
+      cursor,             // Start immediately after the previous partition
+      self.size - cursor, // Use all remaining disk space
       self.sector_size,
       PartStatus::Create,
       None,
@@ -718,7 +1349,11 @@ impl Disk {
       vec![], // No special flags
     );
     // Add the new partitions to the layout
+    self.layout.extend(bios_part.map(DiskItem::Partition));
     self.layout.push(DiskItem::Partition(boot_part));
+    self
+      .layout
+      .extend(extra_parts.into_iter().map(DiskItem::Partition));
     self.layout.push(DiskItem::Partition(root_part));
   }
 }
@@ -757,8 +1392,8 @@ impl DiskItem {
             match h {
               DiskTableHeader::Status => match p.status() {
                 PartStatus::Delete => "delete".into(),
-                PartStatus::Modify => "modify".into(),
-                PartStatus::Exists => "existing".into(),
+                PartStatus::Modify => "format".into(),
+                PartStatus::Exists => "keep".into(),
                 PartStatus::Create => "create".into(),
                 PartStatus::Unknown => "unknown".into(),
               },
@@ -769,7 +1404,13 @@ impl DiskItem {
               DiskTableHeader::Size => bytes_readable(p.size_bytes(sector_size)),
               DiskTableHeader::FSType => p.fs_type().unwrap_or("").into(),
               DiskTableHeader::MountPoint => p.mount_point().unwrap_or("").into(),
-              DiskTableHeader::Flags => p.flags().join(","),
+              DiskTableHeader::Flags => {
+                let mut flags = p.flags().to_vec();
+                if p.luks().is_some() {
+                  flags.push("luks".to_string());
+                }
+                flags.join(",")
+              }
               DiskTableHeader::ReadOnly => "".into(), // Not applicable for partitions
             }
           })
@@ -800,13 +1441,47 @@ impl DiskItem {
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub enum PartStatus {
+  /// Existing partition, removed on install
   Delete,
+  /// Existing partition, reformatted on install
   Modify,
   Create,
+  /// Existing partition, kept as is with its data intact
   Exists,
   Unknown,
 }
 
+/// How a LUKS volume is unlocked
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub enum LuksKey {
+  /// Typed at boot. It stays in memory until the install and is never saved
+  /// with the rest of the settings.
+  Passphrase {
+    #[serde(skip)]
+    passphrase: String,
+  },
+  /// A key file on the live system, used to format the volume and by the
+  /// initrd to unlock it, so it has to be reachable at boot
+  KeyFile { path: String },
+}
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct LuksConfig {
+  /// Device mapper name, the unlocked volume is `/dev/mapper/<name>`
+  pub name: String,
+  pub cipher: String,
+  pub key: LuksKey,
+}
+
+impl LuksConfig {
+  pub const DEFAULT_CIPHER: &str = "aes-xts-plain64";
+
+  /// Where the passphrase is written for disko to read while formatting
+  pub fn passphrase_path(&self) -> String {
+    format!("/tmp/nixos-wizard-luks-{}.key", self.name)
+  }
+}
+
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct Partition {
   id: u64,
@@ -820,6 +1495,12 @@ pub struct Partition {
   ro: bool,
   label: Option<String>,
   flags: Vec<String>,
+  /// Extra options passed to `mount`, e.g. `noatime`
+  #[serde(default)]
+  mount_options: Vec<String>,
+  /// Boxed so every `DiskItem` doesn't pay for it
+  #[serde(default)]
+  luks: Option<Box<LuksConfig>>,
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -848,6 +1529,8 @@ impl Partition {
       label,
       ro,
       flags,
+      mount_options: vec![],
+      luks: None,
     }
   }
   pub fn id(&self) -> u64 {
@@ -859,6 +1542,13 @@ impl Partition {
   pub fn set_name<S: Into<String>>(&mut self, name: S) {
     self.name = Some(name.into());
   }
+  /// The partition's number in the partition table, taken from the end of its
+  /// device name, e.g. 3 for `sda3` or `nvme0n1p3`
+  pub fn number(&self) -> Option<u32> {
+    let name = self.name()?;
+    let digits = name.len() - name.trim_end_matches(|c: char| c.is_ascii_digit()).len();
+    name[name.len() - digits..].parse().ok()
+  }
   pub fn start(&self) -> u64 {
     self.start
   }
@@ -884,6 +1574,12 @@ impl Partition {
     self.fs_type.as_deref()
   }
   /// Disko expects `vfat` for any fat fs types
+  /// The label this partition gets when auto labelling is on, see
+  /// [`label_for_mount_point`]
+  pub fn auto_label(&self) -> Option<String> {
+    let mount_point = self.mount_point()?;
+    Some(label_for_mount_point(mount_point, self.disko_fs_type()))
+  }
   pub fn disko_fs_type(&self) -> Option<&'static str> {
     match self.fs_type.as_deref()? {
       "ext4" => Some("ext4"),
@@ -895,7 +1591,7 @@ impl Partition {
       "fat16" => Some("vfat"),
       "fat32" => Some("vfat"),
       "ntfs" => Some("ntfs"),
-      "swap" => Some("swap"),
+      "swap" | "linux-swap" => Some("swap"),
       _ => None,
     }
   }
@@ -910,7 +1606,7 @@ impl Partition {
         }
       }
       "ntfs" => Some("0700"),
-      "swap" => Some("8200"),
+      "swap" | "linux-swap" => Some("8200"),
       _ => None,
     }
   }
@@ -932,6 +1628,21 @@ impl Partition {
   pub fn flags(&self) -> &[String] {
     &self.flags
   }
+  pub fn has_flag(&self, flag: &str) -> bool {
+    self.flags.iter().any(|f| f == flag)
+  }
+  pub fn mount_options(&self) -> &[String] {
+    &self.mount_options
+  }
+  pub fn set_mount_options(&mut self, options: Vec<String>) {
+    self.mount_options = options;
+  }
+  pub fn luks(&self) -> Option<&LuksConfig> {
+    self.luks.as_deref()
+  }
+  pub fn set_luks(&mut self, luks: Option<LuksConfig>) {
+    self.luks = luks.map(Box::new);
+  }
   pub fn add_flag<S: Into<String>>(&mut self, flag: S) {
     let flag_str = flag.into();
     if !self.flags.contains(&flag_str) {
@@ -958,6 +1669,30 @@ impl Partition {
   }
 }
 
+/// Derive a filesystem label from a mount point
+///
+/// `/` becomes `nixos`, anything else is the path in upper case with the
+/// slashes dropped, so `/boot` is `BOOT` and `/var/log` is `VARLOG`. vfat only
+/// stores upper case labels, and every filesystem has its own length limit, so
+/// the result is adjusted to fit `format` (a disko format like `vfat`).
+pub fn label_for_mount_point(mount_point: &str, format: Option<&str>) -> String {
+  let mut label = match mount_point.trim_matches('/') {
+    "" => "nixos".to_string(),
+    path => path.replace('/', "").to_uppercase(),
+  };
+  let max_len = match format {
+    Some("vfat") => {
+      label = label.to_uppercase();
+      11
+    }
+    Some("xfs") => 12,
+    Some("ext2" | "ext3" | "ext4") => 16,
+    Some("ntfs") => 32,
+    _ => 36,
+  };
+  label.chars().take(max_len).collect()
+}
+
 pub struct PartitionBuilder {
   start: Option<u64>,
   size: Option<u64>,
@@ -1053,6 +1788,8 @@ impl PartitionBuilder {
       label: self.label,
       ro,
       flags: self.flags,
+      mount_options: vec![],
+      luks: None,
     })
   }
 }
@@ -1145,3 +1882,313 @@ impl DiskTableHeader {
       .collect()
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn dd_bytes_copied_reads_the_summary_line() {
+    let full = "256+0 records in\n256+0 records out\n268435456 bytes (268 MB, 256 MiB) copied, 0.52 s, 516 MB/s\n";
+    assert_eq!(dd_bytes_copied(full), Some(268435456));
+    let short =
+      "64+0 records in\n64+0 records out\n67108864 bytes (67 MB, 64 MiB) copied, 0.1 s, 671 MB/s\n";
+    assert_eq!(dd_bytes_copied(short), Some(67108864));
+    assert_eq!(dd_bytes_copied("0+0 records in\n1 byte copied"), None);
+    assert_eq!(dd_bytes_copied(""), None);
+  }
+
+  /// A 10 GiB disk with 512 byte sectors and nothing on it
+  fn empty_disk() -> Disk {
+    Disk::new("sda".into(), 20 * 1024 * 1024, 512, vec![])
+  }
+
+  /// Create a partition filling `size` of the first free region, the way the
+  /// new partition page does
+  fn split_first_free(disk: &mut Disk, size: &str) -> u64 {
+    let (start, free) = disk.free_spaces().next().unwrap();
+    let sectors = parse_sectors(size, disk.sector_size(), free).unwrap();
+    let part = PartitionBuilder::new()
+      .start(start)
+      .size(sectors)
+      .sector_size(disk.sector_size())
+      .status(PartStatus::Create)
+      .mount_point("/data")
+      .build()
+      .unwrap();
+    disk.new_partition(part).unwrap();
+    sectors
+  }
+
+  #[test]
+  fn sequential_percent_splits_use_remaining_space() {
+    let mut disk = empty_disk();
+    let (_, free) = disk.free_spaces().next().unwrap();
+
+    let half = split_first_free(&mut disk, "50%");
+    assert_eq!(half, free / 2);
+    let (_, rest) = disk.free_spaces().next().unwrap();
+    assert_eq!(rest, free - half);
+
+    let quarter = split_first_free(&mut disk, "50%");
+    assert_eq!(quarter, free / 4);
+  }
+
+  #[test]
+  fn parse_sectors_plain_numbers_are_sectors() {
+    assert_eq!(parse_sectors("0", 512, 1000), Ok(0));
+    assert_eq!(parse_sectors("2048", 512, 1000), Ok(2048));
+    assert!(parse_sectors("1.5", 512, 1000).is_err());
+  }
+
+  #[test]
+  fn parse_sectors_percentages_of_free_space() {
+    assert_eq!(parse_sectors("100%", 512, 1000), Ok(1000));
+    assert_eq!(parse_sectors("50%", 512, 1000), Ok(500));
+    assert_eq!(parse_sectors("0%", 512, 1000), Ok(0));
+    assert!(parse_sectors("100.5%", 512, 1000).is_err());
+    assert!(parse_sectors("150%", 512, 1000).is_err());
+  }
+
+  #[test]
+  fn parse_sectors_units() {
+    assert_eq!(parse_sectors("1.5GB", 1000, 0), Ok(1_500_000));
+    assert_eq!(parse_sectors("10 GB", 1000, 0), Ok(10_000_000));
+    assert_eq!(parse_sectors("  10 GB  ", 1000, 0), Ok(10_000_000));
+    // Partial sectors round to the nearest whole one
+    assert_eq!(parse_sectors("1.5GB", 512, 0), Ok(2_929_688));
+    assert_eq!(parse_sectors("4096b", 4096, 0), Ok(1));
+  }
+
+  #[test]
+  fn parse_sectors_units_ignore_case() {
+    for s in ["500mib", "500MiB", "500MIB", "500 mib"] {
+      assert_eq!(parse_sectors(s, 512, 0), Ok(500 * 2048), "{s}");
+    }
+    assert_eq!(parse_sectors("1gb", 512, 0), parse_sectors("1GB", 512, 0));
+  }
+
+  #[test]
+  fn parse_sectors_si_and_binary_units_differ() {
+    assert_eq!(parse_sectors("1 KB", 1, 0), Ok(1_000));
+    assert_eq!(parse_sectors("1 KiB", 1, 0), Ok(1_024));
+    assert_eq!(parse_sectors("1 MB", 1, 0), Ok(1_000_000));
+    assert_eq!(parse_sectors("1 MiB", 1, 0), Ok(1 << 20));
+    assert_eq!(parse_sectors("1 GB", 1, 0), Ok(1_000_000_000));
+    assert_eq!(parse_sectors("1 GiB", 1, 0), Ok(1 << 30));
+    assert_eq!(parse_sectors("1 TB", 1, 0), Ok(1_000_000_000_000));
+    assert_eq!(parse_sectors("1 TiB", 1, 0), Ok(1 << 40));
+  }
+
+  #[test]
+  fn parse_sectors_rejects_bad_input() {
+    assert!(parse_sectors("", 512, 0).is_err());
+    assert!(parse_sectors("GB", 512, 0).is_err());
+    assert!(parse_sectors("10 G", 512, 0).is_err());
+    assert!(parse_sectors("10 parsecs", 512, 0).is_err());
+    assert!(parse_sectors("1.2.3 GB", 512, 0).is_err());
+  }
+
+  /// A disk that had three partitions, one of each status an existing
+  /// partition can get, and a new one in the space freed up at the end
+  fn mixed_disk() -> Disk {
+    let gib = 2 * 1024 * 1024;
+    let existing = |n: u64, status, fs_type: &str, mount_point: &str| {
+      Partition::new(
+        2048 + (n - 1) * gib,
+        gib,
+        512,
+        status,
+        Some(format!("sda{n}")),
+        Some(fs_type.into()),
+        Some(mount_point.into()),
+        None,
+        false,
+        vec![],
+      )
+    };
+    let layout = vec![
+      DiskItem::Partition(existing(1, PartStatus::Exists, "ext4", "/home")),
+      DiskItem::Partition(existing(2, PartStatus::Modify, "ext4", "/")),
+      DiskItem::Partition(existing(3, PartStatus::Delete, "ntfs", "/data")),
+    ];
+    let mut disk = Disk::new("sda".into(), 8 * gib, 512, layout);
+    let new = PartitionBuilder::new()
+      .start(2048 + 3 * gib)
+      .size(gib)
+      .status(PartStatus::Create)
+      .fs_type("ext4")
+      .mount_point("/var")
+      .label("VAR")
+      .build()
+      .unwrap();
+    disk.new_partition(new).unwrap();
+    disk
+  }
+
+  fn disko_partitions(disk: &mut Disk) -> serde_json::Map<String, Value> {
+    let cfg = disk.as_disko_cfg("main");
+    cfg["content"]["partitions"].as_object().unwrap().clone()
+  }
+
+  fn by_mountpoint<'a>(parts: &'a serde_json::Map<String, Value>, mp: &str) -> Option<&'a Value> {
+    parts.values().find(|p| p["mountpoint"] == mp)
+  }
+
+  #[test]
+  fn kept_partition_is_left_untouched() {
+    let parts = disko_partitions(&mut mixed_disk());
+    let home = by_mountpoint(&parts, "/home").unwrap();
+    assert_eq!(home["keep"], true);
+    assert_eq!(home["device"], "/dev/sda1");
+    assert!(home.get("label").is_none());
+  }
+
+  #[test]
+  fn formatted_and_new_partitions_get_filesystems() {
+    let parts = disko_partitions(&mut mixed_disk());
+    let root = by_mountpoint(&parts, "/").unwrap();
+    assert!(root.get("keep").is_none());
+    assert_eq!(root["format"], "ext4");
+    let var = by_mountpoint(&parts, "/var").unwrap();
+    assert!(var.get("keep").is_none());
+    assert_eq!(var["format"], "ext4");
+    assert_eq!(var["label"], "VAR");
+  }
+
+  #[test]
+  fn partitions_next_to_kept_ones_are_pinned_in_place() {
+    let gib = 2 * 1024 * 1024;
+    let parts = disko_partitions(&mut mixed_disk());
+    let placement = |mp: &str| {
+      let p = by_mountpoint(&parts, mp).unwrap();
+      (p["start"].clone(), p["end"].clone(), p["priority"].clone())
+    };
+    assert_eq!(
+      placement("/home"),
+      ("2048".into(), (2047 + gib).to_string().into(), 1.into())
+    );
+    assert_eq!(
+      placement("/"),
+      (
+        (2048 + gib).to_string().into(),
+        (2047 + 2 * gib).to_string().into(),
+        2.into()
+      )
+    );
+    assert_eq!(placement("/var").2, 3);
+  }
+
+  #[test]
+  fn partitions_are_not_pinned_without_kept_ones() {
+    let parts = disko_partitions(&mut labelled_disk(None));
+    assert!(parts.values().all(|p| p.get("start").is_none()));
+  }
+
+  #[test]
+  fn deleted_partition_is_left_out() {
+    let parts = disko_partitions(&mut mixed_disk());
+    assert_eq!(parts.len(), 3);
+    assert!(by_mountpoint(&parts, "/data").is_none());
+  }
+
+  #[test]
+  fn deleted_and_formatted_partitions_are_removed_first() {
+    assert_eq!(
+      mixed_disk().removal_commands(),
+      vec![
+        "wipefs -a /dev/sda3",
+        "sgdisk --delete=3 /dev/sda",
+        "wipefs -a /dev/sda2",
+        "sgdisk --delete=2 /dev/sda",
+        "partx -u /dev/sda",
+        "udevadm settle",
+      ]
+    );
+    assert!(empty_disk().removal_commands().is_empty());
+  }
+
+  #[test]
+  fn partition_number_comes_from_the_device_name() {
+    let named = |name: &str| {
+      let mut part = PartitionBuilder::new()
+        .start(2048)
+        .size(2048)
+        .mount_point("/")
+        .build()
+        .unwrap();
+      part.set_name(name);
+      part
+    };
+    assert_eq!(named("sda3").number(), Some(3));
+    assert_eq!(named("nvme0n1p12").number(), Some(12));
+    assert_eq!(named("mmcblk0p1").number(), Some(1));
+    assert_eq!(named("sda").number(), None);
+  }
+
+  /// Root, /boot and /home on an empty disk, `boot_label` set on /boot
+  fn labelled_disk(boot_label: Option<&str>) -> Disk {
+    let mut disk = empty_disk();
+    disk.set_auto_labels(true);
+    let gib = 2 * 1024 * 1024;
+    for (idx, (mp, fs)) in [("/boot", "fat32"), ("/home", "ext4"), ("/", "btrfs")]
+      .into_iter()
+      .enumerate()
+    {
+      let mut part = PartitionBuilder::new()
+        .start(2048 + idx as u64 * gib)
+        .size(gib)
+        .status(PartStatus::Create)
+        .fs_type(fs)
+        .mount_point(mp);
+      if mp == "/boot"
+        && let Some(label) = boot_label
+      {
+        part = part.label(label);
+      }
+      disk.new_partition(part.build().unwrap()).unwrap();
+    }
+    disk
+  }
+
+  #[test]
+  fn labels_are_derived_from_mount_points() {
+    let parts = disko_partitions(&mut labelled_disk(None));
+    assert_eq!(by_mountpoint(&parts, "/").unwrap()["label"], "nixos");
+    assert_eq!(by_mountpoint(&parts, "/boot").unwrap()["label"], "BOOT");
+    assert_eq!(by_mountpoint(&parts, "/home").unwrap()["label"], "HOME");
+  }
+
+  #[test]
+  fn explicit_labels_are_not_overwritten() {
+    let parts = disko_partitions(&mut labelled_disk(Some("EFI")));
+    assert_eq!(by_mountpoint(&parts, "/boot").unwrap()["label"], "EFI");
+    assert_eq!(by_mountpoint(&parts, "/home").unwrap()["label"], "HOME");
+  }
+
+  #[test]
+  fn vfat_labels_are_upper_case_and_short() {
+    assert_eq!(label_for_mount_point("/boot/efi", Some("vfat")), "BOOTEFI");
+    assert_eq!(
+      label_for_mount_point("/boot/efi/loader", Some("vfat")),
+      "BOOTEFILOAD"
+    );
+  }
+
+  #[test]
+  fn duplicate_labels_get_unique_partition_names() {
+    let mut disk = labelled_disk(Some("HOME"));
+    let parts = disko_partitions(&mut disk);
+    assert_eq!(parts.len(), 3);
+    assert!(parts.contains_key("HOME"));
+    assert!(parts.contains_key("HOME-2"));
+  }
+
+  #[test]
+  fn percent_split_of_full_remainder_leaves_no_free_space() {
+    let mut disk = empty_disk();
+    split_first_free(&mut disk, "25%");
+    split_first_free(&mut disk, "100%");
+    assert_eq!(disk.free_spaces().count(), 0);
+  }
+}
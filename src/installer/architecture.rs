@@ -0,0 +1,269 @@
+use std::{fs, process::Stdio};
+
+use ratatui::{
+  Frame,
+  crossterm::event::{KeyCode, KeyEvent},
+  layout::Rect,
+  style::{Color, Modifier},
+  text::Line,
+};
+
+use crate::{
+  command,
+  installer::{HIGHLIGHT, Installer, Page, Signal, StagedConfigs},
+  keys, split_hor, split_vert, styled_block, tools, ui_back, ui_close, ui_down, ui_up,
+  widget::{ConfigWidget, HelpModal, InfoBox, StrList},
+};
+
+pub const ARCHITECTURES: &[&str] = &["x86_64-linux", "aarch64-linux"];
+
+/// Stands in for the hardware-configuration.nix that nixos-generate-config
+/// writes during the install, so the config can be evaluated before then.
+/// The filesystems come from disko-config.nix
+const STUB_HARDWARE_CONFIG: &str = "{ }\n";
+
+/// Evaluated in place of configuration.nix when the generated flake would
+/// normally provide the disko module
+const FLAKE_EVAL_WRAPPER: &str = r#"{
+  imports = [
+    "${builtins.fetchTarball https://github.com/nix-community/disko/archive/master.tar.gz}/module.nix"
+    ./configuration.nix
+  ];
+}
+"#;
+
+/// The Nix system double of the machine the installer runs on
+pub fn host_architecture() -> String {
+  format!("{}-linux", std::env::consts::ARCH)
+}
+
+/// Evaluate the staged system config for `arch` without building anything
+///
+/// Catches options that don't exist or packages that aren't available on the
+/// target, e.g. x86-only software in an aarch64 config.
+pub fn evaluate_for(staged: &StagedConfigs, arch: &str) -> anyhow::Result<()> {
+  tools::require("nix-instantiate")?;
+  let dir = tempfile::tempdir()?;
+  fs::copy(staged.system.path(), dir.path().join("configuration.nix"))?;
+  fs::write(
+    dir.path().join("hardware-configuration.nix"),
+    STUB_HARDWARE_CONFIG,
+  )?;
+  fs::copy(staged.disko.path(), dir.path().join("disko-config.nix"))?;
+  if let Some(ref overlays) = staged.overlays {
+    fs::create_dir(dir.path().join("overlays"))?;
+    fs::copy(overlays.path(), dir.path().join("overlays/default.nix"))?;
+  }
+  let mut config_path = dir.path().join("configuration.nix");
+  if staged.flake.is_some() {
+    // The flake normally supplies the disko module that disko-config.nix needs
+    config_path = dir.path().join("eval.nix");
+    fs::write(&config_path, FLAKE_EVAL_WRAPPER)?;
+  }
+  let output = command!(
+    "nix-instantiate",
+    "<nixpkgs/nixos>",
+    "-A",
+    "system",
+    "--arg",
+    "configuration",
+    config_path.display(),
+    "--argstr",
+    "system",
+    arch
+  )
+  .stdout(Stdio::null())
+  .stderr(Stdio::piped())
+  .output()?;
+  if output.status.success() {
+    return Ok(());
+  }
+  let stderr = String::from_utf8_lossy(&output.stderr);
+  log::error!("Evaluating the config for {arch} failed:\n{stderr}");
+  // The first error line says what went wrong, the trace before it is noise
+  let error = stderr
+    .lines()
+    .find(|line| line.trim_start().starts_with("error:"))
+    .unwrap_or("evaluation failed, see the log for details")
+    .trim()
+    .to_string();
+  Err(anyhow::anyhow!(error))
+}
+
+pub struct Architecture {
+  architectures: StrList,
+  help_modal: HelpModal<'static>,
+}
+
+impl Architecture {
+  pub fn new(current: &str) -> Self {
+    let architectures = ARCHITECTURES
+      .iter()
+      .map(|s| s.to_string())
+      .collect::<Vec<_>>();
+    let mut architectures = StrList::new("Select Architecture", architectures);
+    architectures.commit_value(Some(current));
+    architectures.focus();
+    let help_content = styled_block(vec![
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Navigate architectures"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
+        (None, " - Select architecture and return"),
+      ],
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().back_label,
+        ),
+        (None, " - Cancel and return to menu"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "?"),
+        (None, " - Show this help"),
+      ],
+      vec![(None, "")],
+      vec![(
+        None,
+        "Use 'Verify Build' in the config preview to check the config evaluates for it.",
+      )],
+    ]);
+    let help_modal = HelpModal::new("Architecture", help_content);
+    Self {
+      architectures,
+      help_modal,
+    }
+  }
+  pub fn display_widget(installer: &mut Installer) -> Option<Box<dyn ConfigWidget>> {
+    installer.architecture.clone().map(|arch| {
+      let ib = InfoBox::new(
+        "",
+        styled_block(vec![
+          vec![(None, "Target architecture set to:")],
+          vec![(HIGHLIGHT, &arch)],
+        ]),
+      );
+      Box::new(ib) as Box<dyn ConfigWidget>
+    })
+  }
+  pub fn page_info<'a>() -> (String, Vec<Line<'a>>) {
+    (
+      "Architecture".to_string(),
+      styled_block(vec![
+        vec![(
+          None,
+          "Advanced: the system the config is for, when it differs from this machine.",
+        )],
+        vec![(
+          None,
+          "Sets nixpkgs.hostPlatform. Defaults to the architecture the installer runs on.",
+        )],
+      ]),
+    )
+  }
+}
+
+impl Default for Architecture {
+  fn default() -> Self {
+    Self::new(&host_architecture())
+  }
+}
+
+impl Page for Architecture {
+  fn render(&mut self, _installer: &mut Installer, f: &mut Frame, area: Rect) {
+    let chunks = split_vert!(
+      area,
+      1,
+      [Constraint::Percentage(40), Constraint::Percentage(60)]
+    );
+    let hor_chunks = split_hor!(
+      chunks[1],
+      1,
+      [
+        Constraint::Percentage(35),
+        Constraint::Percentage(30),
+        Constraint::Percentage(35),
+      ]
+    );
+    let (_, info) = Self::page_info();
+    InfoBox::new("", info).render(f, chunks[0]);
+    self.architectures.render(f, hor_chunks[1]);
+    self.help_modal.render(f, area);
+  }
+
+  fn get_help_content(&self) -> (String, Vec<Line<'_>>) {
+    let help_content = styled_block(vec![
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Navigate architectures"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
+        (None, " - Select architecture and return"),
+      ],
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().back_label,
+        ),
+        (None, " - Cancel and return to menu"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "?"),
+        (None, " - Show this help"),
+      ],
+      vec![(None, "")],
+      vec![(
+        None,
+        "Use 'Verify Build' in the config preview to check the config evaluates for it.",
+      )],
+    ]);
+    ("Architecture".to_string(), help_content)
+  }
+
+  fn handle_input(&mut self, installer: &mut Installer, event: KeyEvent) -> Signal {
+    match event.code {
+      KeyCode::Char('?') => {
+        self.help_modal.toggle();
+        Signal::Wait
+      }
+      code if ui_close!(code) && self.help_modal.visible => {
+        self.help_modal.hide();
+        Signal::Wait
+      }
+      _ if self.help_modal.visible => Signal::Wait,
+      code if ui_back!(code) => Signal::Pop,
+      KeyCode::Enter => {
+        // Picking the host again goes back to leaving it to
+        // hardware-configuration.nix
+        installer.architecture = self
+          .architectures
+          .selected_item()
+          .filter(|arch| **arch != host_architecture())
+          .cloned();
+        Signal::Pop
+      }
+      code if ui_up!(code) => {
+        if !self.architectures.previous_item() {
+          self.architectures.last_item();
+        }
+        Signal::Wait
+      }
+      code if ui_down!(code) => {
+        if !self.architectures.next_item() {
+          self.architectures.first_item();
+        }
+        Signal::Wait
+      }
+      _ => self.architectures.handle_input(event),
+    }
+  }
+}
@@ -0,0 +1,255 @@
+use ratatui::{
+  Frame,
+  crossterm::event::{KeyCode, KeyEvent},
+  layout::Rect,
+  style::{Color, Modifier},
+  text::Line,
+};
+
+use crate::{
+  installer::{HIGHLIGHT, Installer, Page, Signal},
+  keys, split_hor, split_vert, styled_block, ui_back, ui_close, ui_down, ui_up,
+  widget::{ConfigWidget, HelpModal, InfoBox, StrList},
+};
+
+/// Channels offered on the page, newest first
+pub const CHANNELS: &[&str] = &[
+  "nixos-unstable",
+  "nixos-25.05",
+  "nixos-24.11",
+  "nixos-24.05",
+];
+
+pub const DEFAULT_CHANNEL: &str = "nixos-unstable";
+
+/// stateVersion for a system installed from unstable, the release currently
+/// in development
+const UNSTABLE_STATE_VERSION: &str = "25.11";
+
+/// URL that `nix-channel --add` registers as `nixos` on the new system
+pub fn channel_url(channel: &str) -> String {
+  format!("https://nixos.org/channels/{channel}")
+}
+
+/// Tarball behind `channel`, which `nixos-install -I nixpkgs=` builds from
+pub fn nixexprs_url(channel: &str) -> String {
+  format!("{}/nixexprs.tar.xz", channel_url(channel))
+}
+
+/// `nixpkgs.url` of the generated flake.nix, pinned to the same branch
+pub fn flake_url(channel: &str) -> String {
+  format!("github:NixOS/nixpkgs/{channel}")
+}
+
+/// `system.stateVersion` for a fresh install from `channel`
+///
+/// A release channel like `nixos-25.05` maps to its release number, anything
+/// else is treated as unstable.
+pub fn state_version(channel: &str) -> &str {
+  channel
+    .strip_prefix("nixos-")
+    .filter(|release| {
+      release.split_once('.').is_some_and(|(year, month)| {
+        !year.is_empty()
+          && !month.is_empty()
+          && year.chars().all(|c| c.is_ascii_digit())
+          && month.chars().all(|c| c.is_ascii_digit())
+      })
+    })
+    .unwrap_or(UNSTABLE_STATE_VERSION)
+}
+
+/// Whether `state_version` is an older release than `release`, both in
+/// `YY.MM` form
+pub fn release_before(state_version: &str, release: &str) -> bool {
+  let parse = |v: &str| {
+    let (year, month) = v.split_once('.')?;
+    Some((year.parse::<u32>().ok()?, month.parse::<u32>().ok()?))
+  };
+  match (parse(state_version), parse(release)) {
+    (Some(version), Some(release)) => version < release,
+    _ => false,
+  }
+}
+
+pub struct Channel {
+  channels: StrList,
+  flakes: bool,
+  help_modal: HelpModal<'static>,
+}
+
+impl Channel {
+  pub fn new(current: &str, flakes: bool) -> Self {
+    let channels = CHANNELS.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+    let mut channels = StrList::new("Select Release Channel", channels);
+    channels.commit_value(Some(current));
+    channels.focus();
+    let help_content = styled_block(vec![
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Navigate channels"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
+        (None, " - Select channel and return"),
+      ],
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().back_label,
+        ),
+        (None, " - Cancel and return to menu"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "?"),
+        (None, " - Show this help"),
+      ],
+      vec![(None, "")],
+      vec![(
+        None,
+        "The channel also decides system.stateVersion of the new system.",
+      )],
+    ]);
+    let help_modal = HelpModal::new("Release Channel", help_content);
+    Self {
+      channels,
+      flakes,
+      help_modal,
+    }
+  }
+  /// With flakes on the channel is only used to pin the flake's nixpkgs
+  fn channel_info<'a>(channel: &str, flakes: bool) -> InfoBox<'a> {
+    let (label, url) = if flakes {
+      ("Flake nixpkgs input:", flake_url(channel))
+    } else {
+      ("Channel URL:", channel_url(channel))
+    };
+    InfoBox::new(
+      "",
+      styled_block(vec![
+        vec![(None, label.to_string())],
+        vec![(HIGHLIGHT, url)],
+        vec![(None, "stateVersion:".to_string())],
+        vec![(HIGHLIGHT, state_version(channel).to_string())],
+      ]),
+    )
+  }
+  pub fn display_widget(installer: &mut Installer) -> Option<Box<dyn ConfigWidget>> {
+    installer.channel.as_deref().map(|channel| {
+      Box::new(Self::channel_info(channel, installer.enable_flakes)) as Box<dyn ConfigWidget>
+    })
+  }
+  pub fn page_info<'a>() -> (String, Vec<Line<'a>>) {
+    (
+      "Release Channel".to_string(),
+      styled_block(vec![
+        vec![(
+          None,
+          "Choose the NixOS channel the new system follows for updates.",
+        )],
+        vec![(
+          None,
+          "system.stateVersion is set to the matching release. Unstable is used if nothing is chosen.",
+        )],
+      ]),
+    )
+  }
+}
+
+impl Default for Channel {
+  fn default() -> Self {
+    Self::new(DEFAULT_CHANNEL, false)
+  }
+}
+
+impl Page for Channel {
+  fn render(&mut self, _installer: &mut Installer, f: &mut Frame, area: Rect) {
+    let chunks = split_vert!(
+      area,
+      1,
+      [Constraint::Percentage(50), Constraint::Percentage(50)]
+    );
+    let hor_chunks = split_hor!(
+      chunks[0],
+      1,
+      [
+        Constraint::Percentage(35),
+        Constraint::Percentage(30),
+        Constraint::Percentage(35),
+      ]
+    );
+    self.channels.render(f, hor_chunks[1]);
+    if let Some(channel) = self.channels.selected_item() {
+      Self::channel_info(channel, self.flakes).render(f, chunks[1]);
+    }
+    self.help_modal.render(f, area);
+  }
+
+  fn get_help_content(&self) -> (String, Vec<Line<'_>>) {
+    let help_content = styled_block(vec![
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Navigate channels"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
+        (None, " - Select channel and return"),
+      ],
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().back_label,
+        ),
+        (None, " - Cancel and return to menu"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "?"),
+        (None, " - Show this help"),
+      ],
+      vec![(None, "")],
+      vec![(
+        None,
+        "The channel also decides system.stateVersion of the new system.",
+      )],
+    ]);
+    ("Release Channel".to_string(), help_content)
+  }
+
+  fn handle_input(&mut self, installer: &mut Installer, event: KeyEvent) -> Signal {
+    match event.code {
+      KeyCode::Char('?') => {
+        self.help_modal.toggle();
+        Signal::Wait
+      }
+      code if ui_close!(code) && self.help_modal.visible => {
+        self.help_modal.hide();
+        Signal::Wait
+      }
+      _ if self.help_modal.visible => Signal::Wait,
+      code if ui_back!(code) => Signal::Pop,
+      KeyCode::Enter => {
+        installer.channel = self.channels.selected_item().cloned();
+        Signal::Pop
+      }
+      code if ui_up!(code) => {
+        if !self.channels.previous_item() {
+          self.channels.last_item();
+        }
+        Signal::Wait
+      }
+      code if ui_down!(code) => {
+        if !self.channels.next_item() {
+          self.channels.first_item();
+        }
+        Signal::Wait
+      }
+      _ => self.channels.handle_input(event),
+    }
+  }
+}
@@ -0,0 +1,217 @@
+use ratatui::{
+  Frame,
+  crossterm::event::{KeyCode, KeyEvent},
+  layout::Rect,
+  style::{Color, Modifier},
+  text::Line,
+};
+
+use crate::{
+  installer::{HIGHLIGHT, Installer, Page, Signal},
+  keys,
+  nixgen::NixWriter,
+  split_hor, split_vert, styled_block, ui_back, ui_close, ui_down, ui_up,
+  widget::{ConfigWidget, HelpModal, InfoBox, StrList},
+};
+
+/// (font, description). The `ter-` fonts come from the terminus_font package,
+/// the rest ship with kbd
+pub const CONSOLE_FONTS: [(&str, &str); 6] = [
+  ("Default", "Leave the kernel's font"),
+  ("Lat2-Terminus16", "Terminus, covers most Latin scripts"),
+  ("eurlatgr", "Latin and Greek"),
+  ("ter-v16n", "Terminus 16px"),
+  ("ter-v24n", "Terminus 24px, for HiDPI screens"),
+  ("ter-v32n", "Terminus 32px, for very dense screens"),
+];
+
+/// Whether `font` needs the terminus_font package
+pub fn is_terminus_font(font: &str) -> bool {
+  font.starts_with("ter-")
+}
+
+/// Font and keymap of the TTY, which the keyboard layout page doesn't reach
+/// when there is no graphical session
+pub struct ConsoleSettings {
+  fonts: StrList,
+  help_modal: HelpModal<'static>,
+}
+
+impl ConsoleSettings {
+  pub fn new(current: Option<&str>) -> Self {
+    let mut fonts = StrList::new(
+      "Console Font",
+      CONSOLE_FONTS.iter().map(|(f, _)| f.to_string()).collect(),
+    );
+    fonts.commit_value(Some(current.unwrap_or("Default")));
+    fonts.focus();
+    let help_content = styled_block(vec![
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Navigate fonts"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
+        (None, " - Select font and return"),
+      ],
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().back_label,
+        ),
+        (None, " - Cancel and return to menu"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "?"),
+        (None, " - Show this help"),
+      ],
+      vec![(None, "")],
+      vec![(
+        None,
+        "The console keymap follows the keyboard layout, set that on the Keyboard Layout page.",
+      )],
+    ]);
+    let help_modal = HelpModal::new("Console", help_content);
+    Self { fonts, help_modal }
+  }
+  /// What the TTY keymap will be, given the chosen keyboard layout
+  fn keymap(installer: &Installer) -> String {
+    let Some(ref layout) = installer.keyboard_layout else {
+      return "us (no keyboard layout set)".to_string();
+    };
+    let variant = installer.keyboard_variant.as_deref();
+    match NixWriter::console_keymap(layout, variant) {
+      Some(keymap) => keymap.to_string(),
+      None => "generated from the keyboard layout".to_string(),
+    }
+  }
+  pub fn display_widget(installer: &mut Installer) -> Option<Box<dyn ConfigWidget>> {
+    let font = installer.console_font.clone()?;
+    let ib = InfoBox::new(
+      "",
+      styled_block(vec![
+        vec![(None, "Console font: ".to_string()), (HIGHLIGHT, font)],
+        vec![
+          (None, "Console keymap: ".to_string()),
+          (HIGHLIGHT, Self::keymap(installer)),
+        ],
+      ]),
+    );
+    Some(Box::new(ib) as Box<dyn ConfigWidget>)
+  }
+  pub fn page_info<'a>() -> (String, Vec<Line<'a>>) {
+    (
+      "Console".to_string(),
+      styled_block(vec![
+        vec![(
+          None,
+          "The font used on text consoles, before or without a graphical session.",
+        )],
+        vec![(
+          None,
+          "The console keymap is derived from the keyboard layout, so TTY-only servers type the same as desktops.",
+        )],
+      ]),
+    )
+  }
+}
+
+impl Page for ConsoleSettings {
+  fn render(&mut self, installer: &mut Installer, f: &mut Frame, area: Rect) {
+    let chunks = split_vert!(area, 1, [Constraint::Length(6), Constraint::Min(0)]);
+    let hor_chunks = split_hor!(
+      chunks[1],
+      1,
+      [
+        Constraint::Percentage(25),
+        Constraint::Percentage(50),
+        Constraint::Percentage(25),
+      ]
+    );
+    let description = CONSOLE_FONTS
+      .get(self.fonts.selected_idx)
+      .map_or("", |(_, d)| d);
+    InfoBox::new(
+      "",
+      styled_block(vec![
+        vec![(None, description.to_string())],
+        vec![(None, String::new())],
+        vec![
+          (None, "Console keymap: ".to_string()),
+          (HIGHLIGHT, Self::keymap(installer)),
+        ],
+      ]),
+    )
+    .render(f, chunks[0]);
+    self.fonts.render(f, hor_chunks[1]);
+    self.help_modal.render(f, area);
+  }
+
+  fn get_help_content(&self) -> (String, Vec<Line<'_>>) {
+    let help_content = styled_block(vec![
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Navigate fonts"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
+        (None, " - Select font and return"),
+      ],
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().back_label,
+        ),
+        (None, " - Cancel and return to menu"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "?"),
+        (None, " - Show this help"),
+      ],
+      vec![(None, "")],
+      vec![(
+        None,
+        "The console keymap follows the keyboard layout, set that on the Keyboard Layout page.",
+      )],
+    ]);
+    ("Console".to_string(), help_content)
+  }
+
+  fn handle_input(&mut self, installer: &mut Installer, event: KeyEvent) -> Signal {
+    match event.code {
+      KeyCode::Char('?') => {
+        self.help_modal.toggle();
+        Signal::Wait
+      }
+      code if ui_close!(code) && self.help_modal.visible => {
+        self.help_modal.hide();
+        Signal::Wait
+      }
+      _ if self.help_modal.visible => Signal::Wait,
+      code if ui_back!(code) => Signal::Pop,
+      code if ui_up!(code) => {
+        self.fonts.previous_item();
+        Signal::Wait
+      }
+      code if ui_down!(code) => {
+        self.fonts.next_item();
+        Signal::Wait
+      }
+      KeyCode::Enter => {
+        installer.console_font = self
+          .fonts
+          .selected_item()
+          .filter(|font| *font != "Default")
+          .cloned();
+        Signal::Pop
+      }
+      _ => Signal::Wait,
+    }
+  }
+}
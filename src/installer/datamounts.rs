@@ -0,0 +1,382 @@
+use ratatui::{
+  Frame,
+  crossterm::event::{KeyCode, KeyEvent},
+  layout::Rect,
+  style::{Color, Modifier},
+  text::Line,
+};
+
+use crate::{
+  drives::{DataPartition, bytes_readable, data_partitions},
+  installer::{HIGHLIGHT, Installer, Page, Signal, drivepages::SetMountPoint},
+  keys, split_hor, split_vert, styled_block, ui_close, ui_down, ui_up,
+  widget::{ConfigWidget, HelpModal, InfoBox, LineEditor, StrList},
+};
+
+/// An existing partition mounted on the installed system without formatting
+///
+/// These live outside the disk layout so disko never touches them.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct DataMount {
+  pub uuid: String,
+  pub fs_type: String,
+  pub mount_point: String,
+  pub read_only: bool,
+  /// Device name at the time it was picked, only for display
+  pub device: String,
+}
+
+impl DataMount {
+  fn summary(&self) -> String {
+    let mode = if self.read_only { "ro" } else { "rw" };
+    format!(
+      "{} <- {} ({}, {mode})",
+      self.mount_point, self.device, self.fs_type
+    )
+  }
+}
+
+enum DataMountsMode {
+  List,
+  Pick,
+  MountPoint,
+}
+
+pub struct DataMounts {
+  mounts: StrList,
+  available: Vec<DataPartition>,
+  available_list: StrList,
+  mount_input: LineEditor,
+  mode: DataMountsMode,
+  /// Partition picked in `Pick` mode, waiting for a mount point
+  picked: Option<DataPartition>,
+  help_modal: HelpModal<'static>,
+}
+
+impl DataMounts {
+  pub fn new(installer: &Installer) -> Self {
+    let mut mounts = StrList::new("Data Mounts", Self::mount_lines(installer));
+    mounts.focus();
+    let mount_input = LineEditor::new("Mount Point", Some("e.g. /data"));
+    let help_content = styled_block(vec![
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Navigate mounts"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "a"),
+        (None, " - Add an existing partition"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "r"),
+        (None, " - Toggle read-only for the selected mount"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "d"),
+        (None, " - Remove the selected mount"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Esc, q"),
+        (None, " - Return to menu"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "?"),
+        (None, " - Show this help"),
+      ],
+      vec![(None, "")],
+      vec![(
+        None,
+        "Partitions are mounted by UUID and are never formatted.",
+      )],
+    ]);
+    let help_modal = HelpModal::new("Data Mounts", help_content);
+    Self {
+      mounts,
+      available: vec![],
+      available_list: StrList::new("Existing Partitions", vec![]),
+      mount_input,
+      mode: DataMountsMode::List,
+      picked: None,
+      help_modal,
+    }
+  }
+  fn mount_lines(installer: &Installer) -> Vec<String> {
+    installer
+      .data_mounts
+      .iter()
+      .map(DataMount::summary)
+      .collect()
+  }
+  fn refresh_mounts(&mut self, installer: &Installer) {
+    let selected = self.mounts.selected_idx;
+    self.mounts.set_items(Self::mount_lines(installer));
+    self.mounts.selected_idx = selected.min(self.mounts.len().saturating_sub(1));
+  }
+  fn back_to_list(&mut self) {
+    self.available_list.unfocus();
+    self.mount_input.clear();
+    self.mount_input.unfocus();
+    self.mounts.focus();
+    self.picked = None;
+    self.mode = DataMountsMode::List;
+  }
+  fn open_picker(&mut self, installer: &Installer) -> Signal {
+    let targets = installer.all_disks().map(|d| d.name()).collect::<Vec<_>>();
+    let available = match data_partitions(&targets) {
+      Ok(parts) => parts,
+      Err(e) => return Signal::Error(anyhow::anyhow!("Failed to list partitions: {e}")),
+    };
+    // Hide partitions that are already mounted
+    self.available = available
+      .into_iter()
+      .filter(|p| !installer.data_mounts.iter().any(|m| m.uuid == p.uuid))
+      .collect();
+    let lines = self
+      .available
+      .iter()
+      .map(|p| {
+        let label = p.label.as_deref().unwrap_or("no label");
+        format!(
+          "{}  {}  {}  ({label})",
+          p.name,
+          p.fs_type,
+          bytes_readable(p.size)
+        )
+      })
+      .collect();
+    self.available_list.set_items(lines);
+    self.available_list.selected_idx = 0;
+    self.mounts.unfocus();
+    self.available_list.focus();
+    self.mode = DataMountsMode::Pick;
+    Signal::Wait
+  }
+  fn taken_mount_points(installer: &Installer) -> Vec<String> {
+    let layout_mounts = installer
+      .all_disks()
+      .flat_map(|d| d.layout())
+      .filter_map(|item| item.mount_point().map(|s| s.to_string()));
+    let data_mounts = installer.data_mounts.iter().map(|m| m.mount_point.clone());
+    layout_mounts.chain(data_mounts).collect()
+  }
+  pub fn display_widget(installer: &mut Installer) -> Option<Box<dyn ConfigWidget>> {
+    if installer.data_mounts.is_empty() {
+      return None;
+    }
+    let mut lines = vec![vec![(None, "Data mounts:".to_string())]];
+    for mount in &installer.data_mounts {
+      lines.push(vec![(HIGHLIGHT, mount.summary())]);
+    }
+    Some(Box::new(InfoBox::new("", styled_block(lines))) as Box<dyn ConfigWidget>)
+  }
+  pub fn page_info<'a>() -> (String, Vec<Line<'a>>) {
+    (
+      "Data Mounts".to_string(),
+      styled_block(vec![
+        vec![(
+          None,
+          "Mount existing partitions from other disks, like a data drive at /data.",
+        )],
+        vec![(
+          None,
+          "They are left exactly as they are. Mounts are read-only unless you change it.",
+        )],
+      ]),
+    )
+  }
+}
+
+impl Page for DataMounts {
+  fn render(&mut self, _installer: &mut Installer, f: &mut Frame, area: Rect) {
+    let chunks = split_vert!(area, 1, [Constraint::Min(0), Constraint::Length(3)]);
+    let hor_chunks = split_hor!(
+      chunks[0],
+      1,
+      [Constraint::Percentage(50), Constraint::Percentage(50)]
+    );
+    self.mounts.render(f, hor_chunks[0]);
+    match self.mode {
+      DataMountsMode::List => {}
+      DataMountsMode::Pick => {
+        if self.available.is_empty() {
+          let info = InfoBox::new(
+            "Existing Partitions",
+            styled_block(vec![vec![(
+              None,
+              "No other formatted partitions were found.",
+            )]]),
+          );
+          info.render(f, hor_chunks[1]);
+        } else {
+          self.available_list.render(f, hor_chunks[1]);
+        }
+      }
+      DataMountsMode::MountPoint => {
+        self.available_list.render(f, hor_chunks[1]);
+        self.mount_input.render(f, chunks[1]);
+      }
+    }
+    self.help_modal.render(f, area);
+  }
+
+  fn get_help_content(&self) -> (String, Vec<Line<'_>>) {
+    let help_content = styled_block(vec![
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Navigate mounts"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "a"),
+        (None, " - Add an existing partition"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "r"),
+        (None, " - Toggle read-only for the selected mount"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "d"),
+        (None, " - Remove the selected mount"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Esc, q"),
+        (None, " - Return to menu"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "?"),
+        (None, " - Show this help"),
+      ],
+      vec![(None, "")],
+      vec![(
+        None,
+        "Partitions are mounted by UUID and are never formatted.",
+      )],
+    ]);
+    ("Data Mounts".to_string(), help_content)
+  }
+
+  fn handle_paste(&mut self, _installer: &mut Installer, text: &str) -> Signal {
+    if let DataMountsMode::MountPoint = self.mode {
+      self.mount_input.paste(text);
+    }
+    Signal::Wait
+  }
+
+  fn handle_input(&mut self, installer: &mut Installer, event: KeyEvent) -> Signal {
+    match self.mode {
+      DataMountsMode::Pick => {
+        return match event.code {
+          code if ui_close!(code) => {
+            self.back_to_list();
+            Signal::Wait
+          }
+          code if ui_up!(code) => {
+            self.available_list.previous_item();
+            Signal::Wait
+          }
+          code if ui_down!(code) => {
+            self.available_list.next_item();
+            Signal::Wait
+          }
+          KeyCode::Enter => {
+            let Some(part) = self.available.get(self.available_list.selected_idx) else {
+              return Signal::Wait;
+            };
+            self.picked = Some(part.clone());
+            let taken = Self::taken_mount_points(installer);
+            if !taken.iter().any(|m| m == "/data") {
+              self.mount_input.set_value("/data");
+            }
+            self.available_list.unfocus();
+            self.mount_input.focus();
+            self.mode = DataMountsMode::MountPoint;
+            Signal::Wait
+          }
+          _ => Signal::Wait,
+        };
+      }
+      DataMountsMode::MountPoint => {
+        return match event.code {
+          KeyCode::Esc => {
+            self.back_to_list();
+            Signal::Wait
+          }
+          KeyCode::Enter => {
+            let input = self
+              .mount_input
+              .get_value()
+              .and_then(|v| v.as_str().map(|s| s.trim().to_string()))
+              .unwrap_or_default();
+            let taken = Self::taken_mount_points(installer);
+            if let Err(e) = SetMountPoint::validate_mount_point(&input, &taken) {
+              self.mount_input.error(e);
+              return Signal::Wait;
+            }
+            if input == "/" {
+              self
+                .mount_input
+                .error("Data partitions cannot be mounted at /");
+              return Signal::Wait;
+            }
+            if let Some(part) = self.picked.take() {
+              installer.data_mounts.push(DataMount {
+                uuid: part.uuid,
+                fs_type: part.fs_type,
+                mount_point: input,
+                read_only: true,
+                device: part.name,
+              });
+            }
+            self.back_to_list();
+            self.refresh_mounts(installer);
+            Signal::Wait
+          }
+          _ => self.mount_input.handle_input(event),
+        };
+      }
+      DataMountsMode::List => {}
+    }
+
+    match event.code {
+      KeyCode::Char('?') => {
+        self.help_modal.toggle();
+        Signal::Wait
+      }
+      code if ui_close!(code) && self.help_modal.visible => {
+        self.help_modal.hide();
+        Signal::Wait
+      }
+      _ if self.help_modal.visible => Signal::Wait,
+      code if ui_close!(code) => Signal::Pop,
+      KeyCode::Char('a') => self.open_picker(installer),
+      KeyCode::Char('r') => {
+        if let Some(mount) = installer.data_mounts.get_mut(self.mounts.selected_idx) {
+          mount.read_only = !mount.read_only;
+          self.refresh_mounts(installer);
+        }
+        Signal::Wait
+      }
+      KeyCode::Char('d') => {
+        if self.mounts.selected_idx < installer.data_mounts.len() {
+          installer.data_mounts.remove(self.mounts.selected_idx);
+          self.refresh_mounts(installer);
+        }
+        Signal::Wait
+      }
+      code if ui_up!(code) => {
+        self.mounts.previous_item();
+        Signal::Wait
+      }
+      code if ui_down!(code) => {
+        self.mounts.next_item();
+        Signal::Wait
+      }
+      _ => Signal::Wait,
+    }
+  }
+}
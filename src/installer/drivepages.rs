@@ -1,3 +1,8 @@
+use std::{
+  sync::{Arc, Mutex},
+  thread,
+};
+
 use ratatui::{
   Frame,
   crossterm::event::{KeyCode, KeyEvent},
@@ -7,13 +12,19 @@ use ratatui::{
 use serde_json::Value;
 
 use crate::{
+  LineStyle,
   drives::{
-    DiskItem, PartStatus, Partition, bytes_readable, disk_table, lsblk, parse_sectors, part_table,
+    Disk, DiskItem, LuksConfig, LuksKey, MIN_ROOT_MIB, PartStatus, Partition, StorageStack,
+    bytes_readable, disk_table, lsblk, parse_mount_options, parse_sectors, part_bar, part_table,
+    read_speed, small_partition_warning, storage_stacks,
   },
-  installer::{Installer, Page, Signal},
-  split_hor, split_vert, styled_block, ui_back, ui_close, ui_down, ui_enter, ui_up,
+  installer::{
+    Installer, Page, Signal,
+    resume::{Mismatch, reconcile_disk},
+  },
+  keys, split_hor, split_vert, styled_block, ui_back, ui_close, ui_down, ui_enter, ui_up,
   widget::{
-    Button, CheckBox, ConfigWidget, HelpModal, InfoBox, LineEditor, TableWidget, WidgetBox,
+    Button, CheckBox, ConfigWidget, HelpModal, InfoBox, LineEditor, StrList, TableWidget, WidgetBox,
   },
 };
 
@@ -30,6 +41,7 @@ impl<'a> Drives<'a> {
     let buttons = vec![
       Box::new(Button::new("Use a best-effort default partition layout")) as Box<dyn ConfigWidget>,
       Box::new(Button::new("Configure partitions manually")) as Box<dyn ConfigWidget>,
+      Box::new(Button::new("Load a saved partition layout")) as Box<dyn ConfigWidget>,
       Box::new(Button::new("Back")) as Box<dyn ConfigWidget>,
     ];
     let mut button_row = WidgetBox::button_menu(buttons);
@@ -68,6 +80,17 @@ impl<'a> Drives<'a> {
             "This is recommended for advanced users who have specific requirements.",
           ),
         ],
+        vec![
+          (None, "- "),
+          (
+            Some((Color::Green, Modifier::BOLD)),
+            "'Load a saved partition layout'",
+          ),
+          (
+            None,
+            " reuses a layout exported from the manual partitioning screen.",
+          ),
+        ],
         vec![
           (Some((Color::Red, Modifier::BOLD)), "NOTE: "),
           (None, "When the installer is run, "),
@@ -85,7 +108,10 @@ impl<'a> Drives<'a> {
 
     let help_content = styled_block(vec![
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "↑/↓, j/k"),
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
         (None, " - Navigate options"),
       ],
       vec![
@@ -151,21 +177,21 @@ impl<'a> Page for Drives<'a> {
         self.help_modal.toggle();
         Signal::Wait
       }
-      ui_close!() if self.help_modal.visible => {
+      code if ui_close!(code) && self.help_modal.visible => {
         self.help_modal.hide();
         Signal::Wait
       }
       _ if self.help_modal.visible => Signal::Wait,
-      ui_back!() => Signal::Pop,
-      ui_up!() => {
+      code if ui_back!(code) => Signal::Pop,
+      code if ui_up!(code) => {
         self.buttons.prev_child();
         Signal::Wait
       }
-      ui_down!() => {
+      code if ui_down!(code) => {
         self.buttons.next_child();
         Signal::Wait
       }
-      ui_enter!() => {
+      code if ui_enter!(code) => {
         let Some(idx) = self.buttons.selected_child() else {
           return Signal::Wait;
         };
@@ -175,6 +201,7 @@ impl<'a> Page for Drives<'a> {
         };
         let table = disk_table(&disks);
         installer.drives = disks;
+        installer.editing_disk = None;
         match idx {
           0 => {
             installer.use_auto_drive_config = true;
@@ -184,7 +211,11 @@ impl<'a> Page for Drives<'a> {
             installer.use_auto_drive_config = false;
             Signal::Push(Box::new(SelectDrive::new(table)))
           }
-          2 => Signal::Pop,
+          2 => {
+            installer.use_auto_drive_config = false;
+            Signal::Push(Box::new(LayoutFile::import()))
+          }
+          3 => Signal::Pop,
           _ => Signal::Wait,
         }
       }
@@ -195,7 +226,10 @@ impl<'a> Page for Drives<'a> {
   fn get_help_content(&self) -> (String, Vec<ratatui::text::Line<'_>>) {
     let help_content = styled_block(vec![
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "↑/↓, j/k"),
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
         (None, " - Navigate options"),
       ],
       vec![
@@ -230,8 +264,17 @@ impl<'a> Page for Drives<'a> {
   }
 }
 
+/// Read speed of a drive in bytes per second, `None` while still measuring
+type SpeedResult = Arc<Mutex<Option<Result<f64, String>>>>;
+
 pub struct SelectDrive {
   table: TableWidget,
+  /// Read speed tests started from this page, by drive name
+  speed_tests: Vec<(String, SpeedResult)>,
+  /// Picking a disk to add next to the install target, not the target itself
+  adding: bool,
+  /// Why the last pick was refused
+  notice: Option<String>,
   help_modal: HelpModal<'static>,
 }
 
@@ -240,13 +283,23 @@ impl SelectDrive {
     table.focus();
     let help_content = styled_block(vec![
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "↑/↓, j/k"),
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
         (None, " - Navigate drive list"),
       ],
       vec![
         (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
         (None, " - Select drive for installation"),
       ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "b"),
+        (
+          None,
+          " - Measure read speed (read-only, nothing is written)",
+        ),
+      ],
       vec![
         (Some((Color::Yellow, Modifier::BOLD)), "Esc"),
         (None, " - Return to previous menu"),
@@ -270,13 +323,325 @@ impl SelectDrive {
       ],
     ]);
     let help_modal = HelpModal::new("Select Drive", help_content);
-    Self { table, help_modal }
+    Self {
+      table,
+      speed_tests: vec![],
+      adding: false,
+      notice: None,
+      help_modal,
+    }
+  }
+  /// Pick an additional disk, reached from `ManualPartition`
+  pub fn another(table: TableWidget) -> Self {
+    Self {
+      adding: true,
+      ..Self::new(table)
+    }
+  }
+
+  /// Start a read speed test of `disk` on a background thread
+  ///
+  /// Only one runs at a time, two tests at once would slow each other down.
+  fn start_speed_test(&mut self, disk: &str) {
+    let running = self
+      .speed_tests
+      .iter()
+      .any(|(_, result)| result.lock().is_ok_and(|r| r.is_none()));
+    if running {
+      return;
+    }
+    self.speed_tests.retain(|(name, _)| name != disk);
+    let result: SpeedResult = Arc::new(Mutex::new(None));
+    self.speed_tests.push((disk.to_string(), result.clone()));
+    let disk = disk.to_string();
+    thread::spawn(move || {
+      let speed = read_speed(&disk).map_err(|e| e.to_string());
+      if let Ok(mut guard) = result.lock() {
+        *guard = Some(speed);
+      }
+    });
+  }
+
+  fn speed_lines(&self) -> Vec<Vec<(LineStyle, String)>> {
+    self
+      .speed_tests
+      .iter()
+      .map(|(name, result)| {
+        let status = match result.lock().ok().and_then(|r| r.clone()) {
+          None => (None, "measuring...".to_string()),
+          Some(Ok(speed)) => (HIGHLIGHT, format!("{:.0} MB/s", speed / 1_000_000.0)),
+          Some(Err(e)) => (Some((Color::Red, Modifier::BOLD)), e),
+        };
+        vec![(None, format!("/dev/{name}: ")), status]
+      })
+      .collect()
+  }
+
+  /// Add `disk` to the layout next to the install target and partition it
+  ///
+  /// Picking a disk that was already added goes back to editing it.
+  fn add_disk(&mut self, installer: &mut Installer, disk: Disk) -> Signal {
+    if installer
+      .drive_config
+      .as_ref()
+      .is_some_and(|d| d.name() == disk.name())
+    {
+      self.notice = Some(format!(
+        "/dev/{} is already the install target",
+        disk.name()
+      ));
+      return Signal::Wait;
+    }
+    let idx = match installer
+      .extra_disks
+      .iter()
+      .position(|d| d.name() == disk.name())
+    {
+      Some(idx) => idx,
+      None => {
+        // Teardown commands only cover the install target
+        match storage_stacks(disk.name()) {
+          Ok(stacks) if !stacks.is_empty() => {
+            self.notice = Some(format!(
+              "/dev/{} still has LVM, RAID or LUKS layers, release them first",
+              disk.name()
+            ));
+            return Signal::Wait;
+          }
+          Ok(_) => {}
+          Err(e) => log::warn!("Failed to check {} for LVM/RAID/LUKS: {e}", disk.name()),
+        }
+        installer.extra_disks.push(disk);
+        installer.extra_disks.len() - 1
+      }
+    };
+    self.notice = None;
+    installer.editing_disk = Some(idx);
+    let disk = &installer.extra_disks[idx];
+    let table = part_table(disk.layout(), disk.sector_size());
+    Signal::Push(Box::new(ManualPartition::new(table, disk.auto_labels())))
+  }
+
+  /// Where to go once a drive is picked
+  fn next_page(installer: &Installer) -> Signal {
+    if installer.use_auto_drive_config {
+      Signal::Push(Box::new(SelectFilesystem::new(
+        None,
+        installer.preferred_fs(),
+      )))
+    } else {
+      let Some(drive) = installer.active_disk() else {
+        return Signal::Error(anyhow::anyhow!("No drive config available"));
+      };
+      let table = part_table(drive.layout(), drive.sector_size());
+      Signal::Push(Box::new(ManualPartition::new(table, drive.auto_labels())))
+    }
+  }
+}
+
+/// Shown when the selected drive still has LVM, RAID or LUKS layers on it
+pub struct StorageStackWarning {
+  stacks: Vec<StorageStack>,
+  buttons: WidgetBox,
+  help_modal: HelpModal<'static>,
+}
+
+impl StorageStackWarning {
+  pub fn new(stacks: Vec<StorageStack>) -> Self {
+    let mut buttons = WidgetBox::button_menu(vec![
+      Box::new(Button::new("Tear them down before partitioning")),
+      Box::new(Button::new("Continue without tearing down")),
+      Box::new(Button::new("Back")),
+    ]);
+    buttons.focus();
+    let help_content = styled_block(vec![
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Navigate options"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
+        (None, " - Select option"),
+      ],
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().back_label,
+        ),
+        (None, " - Pick a different drive"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "?"),
+        (None, " - Show this help"),
+      ],
+      vec![(None, "")],
+      vec![(
+        None,
+        "Tearing down adds an install step before partitioning that closes LUKS mappings, stops RAID arrays and removes volume groups on this drive.",
+      )],
+    ]);
+    let help_modal = HelpModal::new("Existing Storage Layers", help_content);
+    Self {
+      stacks,
+      buttons,
+      help_modal,
+    }
+  }
+}
+
+impl Page for StorageStackWarning {
+  fn render(&mut self, _installer: &mut Installer, f: &mut Frame, area: Rect) {
+    let chunks = split_vert!(
+      area,
+      1,
+      [Constraint::Percentage(60), Constraint::Percentage(40)]
+    );
+    let hor_chunks = split_hor!(
+      chunks[1],
+      1,
+      [
+        Constraint::Percentage(25),
+        Constraint::Percentage(50),
+        Constraint::Percentage(25),
+      ]
+    );
+    let mut lines = vec![
+      vec![(
+        None,
+        "This drive still has storage layers on it that can make partitioning fail:".to_string(),
+      )],
+      vec![(None, String::new())],
+    ];
+    for stack in &self.stacks {
+      lines.push(vec![
+        (None, " - ".to_string()),
+        (HIGHLIGHT, stack.description.clone()),
+      ]);
+    }
+    lines.push(vec![(None, String::new())]);
+    let teardown = self.stacks.iter().filter_map(|s| s.teardown.as_deref());
+    let mut any_teardown = false;
+    for cmd in teardown {
+      if !any_teardown {
+        lines.push(vec![(None, "Tearing down runs:".to_string())]);
+        any_teardown = true;
+      }
+      lines.push(vec![(None, format!("  $ {cmd}"))]);
+    }
+    if !any_teardown {
+      lines.push(vec![(
+        None,
+        "Nothing is active, so the disk can be wiped as-is.".to_string(),
+      )]);
+    }
+    let info_box = InfoBox::new("Existing Storage Layers", styled_block(lines));
+    info_box.render(f, chunks[0]);
+    self.buttons.render(f, hor_chunks[1]);
+    self.help_modal.render(f, area);
+  }
+
+  fn get_help_content(&self) -> (String, Vec<ratatui::text::Line<'_>>) {
+    let help_content = styled_block(vec![
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Navigate options"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
+        (None, " - Select option"),
+      ],
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().back_label,
+        ),
+        (None, " - Pick a different drive"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "?"),
+        (None, " - Show this help"),
+      ],
+      vec![(None, "")],
+      vec![(
+        None,
+        "Tearing down adds an install step before partitioning that closes LUKS mappings, stops RAID arrays and removes volume groups on this drive.",
+      )],
+    ]);
+    ("Existing Storage Layers".to_string(), help_content)
+  }
+
+  fn handle_input(&mut self, installer: &mut Installer, event: KeyEvent) -> Signal {
+    match event.code {
+      KeyCode::Char('?') => {
+        self.help_modal.toggle();
+        Signal::Wait
+      }
+      code if ui_close!(code) && self.help_modal.visible => {
+        self.help_modal.hide();
+        Signal::Wait
+      }
+      _ if self.help_modal.visible => Signal::Wait,
+      code if ui_back!(code) => Signal::Pop,
+      code if ui_up!(code) => {
+        self.buttons.prev_child();
+        Signal::Wait
+      }
+      code if ui_down!(code) => {
+        self.buttons.next_child();
+        Signal::Wait
+      }
+      KeyCode::Enter => match self.buttons.selected_child() {
+        Some(0) => {
+          installer.storage_teardown = self
+            .stacks
+            .iter()
+            .filter_map(|s| s.teardown.clone())
+            .collect();
+          SelectDrive::next_page(installer)
+        }
+        Some(1) => {
+          installer.storage_teardown.clear();
+          SelectDrive::next_page(installer)
+        }
+        Some(2) => Signal::Pop,
+        _ => Signal::Wait,
+      },
+      _ => Signal::Wait,
+    }
   }
 }
 
 impl Page for SelectDrive {
   fn render(&mut self, _installer: &mut Installer, f: &mut Frame, area: Rect) {
-    self.table.render(f, area);
+    let mut lines = self.speed_lines();
+    if let Some(ref notice) = self.notice {
+      lines.push(vec![(Some((Color::Red, Modifier::BOLD)), notice.clone())]);
+    }
+    if lines.is_empty() {
+      self.table.render(f, area);
+    } else {
+      let chunks = split_vert!(
+        area,
+        0,
+        [
+          Constraint::Min(0),
+          Constraint::Length(lines.len() as u16 + 2)
+        ]
+      );
+      self.table.render(f, chunks[0]);
+      let title = if self.speed_tests.is_empty() {
+        ""
+      } else {
+        "Read Speed (read-only test)"
+      };
+      InfoBox::new(title, styled_block(lines)).render(f, chunks[1]);
+    }
 
     // Render help modal on top
     self.help_modal.render(f, area);
@@ -287,35 +652,55 @@ impl Page for SelectDrive {
         self.help_modal.toggle();
         Signal::Wait
       }
-      ui_close!() if self.help_modal.visible => {
+      code if ui_close!(code) && self.help_modal.visible => {
         self.help_modal.hide();
         Signal::Wait
       }
       _ if self.help_modal.visible => Signal::Wait,
-      ui_back!() => Signal::Pop,
-      ui_up!() => {
+      code if ui_back!(code) => Signal::Pop,
+      code if ui_up!(code) => {
         self.table.previous_row();
         Signal::Wait
       }
-      ui_down!() => {
+      code if ui_down!(code) => {
         self.table.next_row();
         Signal::Wait
       }
-      ui_enter!() => {
+      KeyCode::Char('b') => {
+        if let Some(disk) = self
+          .table
+          .selected_row()
+          .and_then(|row| installer.drives.get(row))
+        {
+          let name = disk.name().to_string();
+          self.start_speed_test(&name);
+        }
+        Signal::Wait
+      }
+      code if ui_enter!(code) => {
         if let Some(row) = self.table.selected_row() {
           let Some(disk) = installer.drives.get(row) else {
             return Signal::Error(anyhow::anyhow!("Failed to find drive info'"));
           };
+          if self.adding {
+            let disk = disk.clone();
+            return self.add_disk(installer, disk);
+          }
 
           installer.drive_config = Some(disk.clone());
-          if installer.use_auto_drive_config {
-            Signal::Push(Box::new(SelectFilesystem::new(None)))
+          installer.extra_disks.clear();
+          installer.storage_teardown.clear();
+          let stacks = match storage_stacks(disk.name()) {
+            Ok(stacks) => stacks,
+            Err(e) => {
+              log::warn!("Failed to check {} for LVM/RAID/LUKS: {e}", disk.name());
+              vec![]
+            }
+          };
+          if stacks.is_empty() {
+            Self::next_page(installer)
           } else {
-            let Some(ref drive) = installer.drive_config else {
-              return Signal::Error(anyhow::anyhow!("No drive config available"));
-            };
-            let table = part_table(drive.layout(), drive.sector_size());
-            Signal::Push(Box::new(ManualPartition::new(table)))
+            Signal::Push(Box::new(StorageStackWarning::new(stacks)))
           }
         } else {
           Signal::Wait
@@ -328,13 +713,23 @@ impl Page for SelectDrive {
   fn get_help_content(&self) -> (String, Vec<ratatui::text::Line<'_>>) {
     let help_content = styled_block(vec![
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "↑/↓, j/k"),
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
         (None, " - Navigate drive list"),
       ],
       vec![
         (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
         (None, " - Select drive for installation"),
       ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "b"),
+        (
+          None,
+          " - Measure read speed (read-only, nothing is written)",
+        ),
+      ],
       vec![
         (Some((Color::Yellow, Modifier::BOLD)), "Esc"),
         (None, " - Return to previous menu"),
@@ -361,6 +756,35 @@ impl Page for SelectDrive {
   }
 }
 
+/// Filesystems offered when picking one, in button order
+const FILESYSTEMS: [&str; 9] = [
+  "ext4", "ext3", "ext2", "btrfs", "xfs", "fat12", "fat16", "fat32", "ntfs",
+];
+
+/// Offered only when creating a partition, the partition becomes swap space
+/// instead of being mounted
+const SWAP_FS: &str = "linux-swap";
+
+/// A button for each of [`FILESYSTEMS`], with the preferred one marked
+fn fs_buttons(preferred: &str) -> Vec<Box<dyn ConfigWidget>> {
+  FILESYSTEMS
+    .iter()
+    .map(|fs| {
+      let label = if *fs == preferred {
+        format!("{fs} (default)")
+      } else {
+        fs.to_string()
+      };
+      Box::new(Button::new(label)) as Box<dyn ConfigWidget>
+    })
+    .collect()
+}
+
+/// Button index of `fs`, the first one if it isn't offered
+fn fs_button_idx(fs: &str) -> usize {
+  FILESYSTEMS.iter().position(|f| *f == fs).unwrap_or(0)
+}
+
 pub struct SelectFilesystem {
   pub buttons: WidgetBox,
   pub dev_id: Option<u64>,
@@ -368,30 +792,31 @@ pub struct SelectFilesystem {
 }
 
 impl SelectFilesystem {
-  pub fn new(dev_id: Option<u64>) -> Self {
-    let buttons = vec![
-      Box::new(Button::new("ext4")) as Box<dyn ConfigWidget>,
-      Box::new(Button::new("ext3")) as Box<dyn ConfigWidget>,
-      Box::new(Button::new("ext2")) as Box<dyn ConfigWidget>,
-      Box::new(Button::new("btrfs")) as Box<dyn ConfigWidget>,
-      Box::new(Button::new("xfs")) as Box<dyn ConfigWidget>,
-      Box::new(Button::new("fat12")) as Box<dyn ConfigWidget>,
-      Box::new(Button::new("fat16")) as Box<dyn ConfigWidget>,
-      Box::new(Button::new("fat32")) as Box<dyn ConfigWidget>,
-      Box::new(Button::new("ntfs")) as Box<dyn ConfigWidget>,
-      Box::new(Button::new("Back")) as Box<dyn ConfigWidget>,
-    ];
-    let mut button_row = WidgetBox::button_menu(buttons);
+  fn button_row(preferred: &str) -> Vec<Box<dyn ConfigWidget>> {
+    let mut buttons = fs_buttons(preferred);
+    buttons.push(Box::new(Button::new("Back")));
+    buttons
+  }
+  pub fn new(dev_id: Option<u64>, preferred: &str) -> Self {
+    let mut button_row = WidgetBox::button_menu(Self::button_row(preferred));
+    button_row.select_child(fs_button_idx(preferred));
     button_row.focus();
     let help_content = styled_block(vec![
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "↑/↓, j/k"),
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
         (None, " - Navigate filesystem options"),
       ],
       vec![
         (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
         (None, " - Select filesystem type"),
       ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "d"),
+        (None, " - Pre-select this filesystem from now on"),
+      ],
       vec![
         (Some((Color::Yellow, Modifier::BOLD)), "Esc"),
         (None, " - Return to previous menu"),
@@ -725,47 +1150,46 @@ impl Page for SelectFilesystem {
         self.help_modal.toggle();
         Signal::Wait
       }
-      ui_close!() if self.help_modal.visible => {
+      code if ui_close!(code) && self.help_modal.visible => {
         self.help_modal.hide();
         Signal::Wait
       }
       _ if self.help_modal.visible => Signal::Wait,
-      ui_back!() => Signal::Pop,
-      ui_up!() => {
+      code if ui_back!(code) => Signal::Pop,
+      code if ui_up!(code) => {
         self.buttons.prev_child();
         Signal::Wait
       }
-      ui_down!() => {
+      code if ui_down!(code) => {
         self.buttons.next_child();
         Signal::Wait
       }
-      ui_enter!() => {
+      KeyCode::Char('d') => {
+        let Some(fs) = self
+          .buttons
+          .selected_child()
+          .and_then(|idx| FILESYSTEMS.get(idx))
+        else {
+          return Signal::Wait;
+        };
+        installer.preferred_fs = Some(fs.to_string());
+        self.buttons.set_children_inplace(Self::button_row(fs));
+        Signal::Wait
+      }
+      code if ui_enter!(code) => {
         let Some(idx) = self.buttons.selected_child() else {
           return Signal::Wait;
         };
-        let fs = match idx {
-          0 => "ext4",
-          1 => "ext3",
-          2 => "ext2",
-          3 => "btrfs",
-          4 => "xfs",
-          5 => "fat12",
-          6 => "fat16",
-          7 => "fat32",
-          8 => "ntfs",
-          9 => return Signal::Pop,
-          _ => return Signal::Wait,
-        }
-        .to_string();
+        let fs = match FILESYSTEMS.get(idx) {
+          Some(fs) => fs.to_string(),
+          None if idx == FILESYSTEMS.len() => return Signal::Pop,
+          None => return Signal::Wait,
+        };
 
         if installer.use_auto_drive_config {
-          if let Some(config) = installer.drive_config.as_mut() {
-            config.use_default_layout(Some(fs));
-          }
-          installer.make_drive_config_display();
-          return Signal::PopCount(3);
+          return Signal::Push(Box::new(DefaultLayoutMounts::new(fs)));
         } else {
-          let Some(config) = installer.drive_config.as_mut() else {
+          let Some(config) = installer.active_disk_mut() else {
             return Signal::Error(anyhow::anyhow!("No drive config available"));
           };
           let Some(id) = self.dev_id else {
@@ -788,13 +1212,20 @@ impl Page for SelectFilesystem {
   fn get_help_content(&self) -> (String, Vec<ratatui::text::Line<'_>>) {
     let help_content = styled_block(vec![
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "↑/↓, j/k"),
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
         (None, " - Navigate filesystem options"),
       ],
       vec![
         (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
         (None, " - Select filesystem type"),
       ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "d"),
+        (None, " - Pre-select this filesystem from now on"),
+      ],
       vec![
         (Some((Color::Yellow, Modifier::BOLD)), "Esc"),
         (None, " - Return to previous menu"),
@@ -815,35 +1246,53 @@ impl Page for SelectFilesystem {
   }
 }
 
-pub struct ManualPartition {
-  disk_config: TableWidget,
+/// Optional extra partitions for the default layout, shown after picking a
+/// filesystem
+pub struct DefaultLayoutMounts {
+  fs: String,
   buttons: WidgetBox,
-  confirming_reset: bool,
+  size_input: LineEditor,
+  /// (mount point, size as typed by the user)
+  sizes: Vec<(&'static str, String)>,
+  editing: Option<usize>,
+  /// Why the layout couldn't be made with the chosen mounts
+  notice: Option<String>,
   help_modal: HelpModal<'static>,
 }
 
-impl ManualPartition {
-  pub fn new(mut disk_config: TableWidget) -> Self {
-    let buttons = vec![
-      Box::new(Button::new("Suggest Partition Layout")) as Box<dyn ConfigWidget>,
-      Box::new(Button::new("Confirm and Exit")) as Box<dyn ConfigWidget>,
-      Box::new(Button::new("Reset Partition Layout")) as Box<dyn ConfigWidget>,
-      Box::new(Button::new("Abort")) as Box<dyn ConfigWidget>,
-    ];
-    let buttons = WidgetBox::button_menu(buttons);
-    disk_config.focus();
+impl DefaultLayoutMounts {
+  const MOUNTS: [(&'static str, &'static str); 3] = [
+    ("/var", "10 GiB"),
+    ("/var/log", "4 GiB"),
+    ("/nix", "50 GiB"),
+  ];
+
+  pub fn new(fs: String) -> Self {
+    let mut children = Self::MOUNTS
+      .iter()
+      .map(|(mount, _)| {
+        Box::new(CheckBox::new(format!("Separate {mount}"), false)) as Box<dyn ConfigWidget>
+      })
+      .collect::<Vec<_>>();
+    children.push(Box::new(Button::new("Continue")));
+    children.push(Box::new(Button::new("Back")));
+    let mut buttons = WidgetBox::button_menu(children);
+    buttons.focus();
     let help_content = styled_block(vec![
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "↑/↓, j/k"),
-        (None, " - Navigate partitions and buttons"),
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Navigate options"),
       ],
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "Tab"),
-        (None, " - Switch between partition table and buttons"),
+        (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
+        (None, " - Toggle a mount or select an action"),
       ],
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
-        (None, " - Select partition or button action"),
+        (Some((Color::Yellow, Modifier::BOLD)), "Tab"),
+        (None, " - Edit the size of the highlighted mount"),
       ],
       vec![
         (Some((Color::Yellow, Modifier::BOLD)), "Esc"),
@@ -856,69 +1305,401 @@ impl ManualPartition {
       vec![(None, "")],
       vec![(
         None,
-        "Manually configure drive partitions. Select partitions to",
-      )],
-      vec![(
-        None,
-        "modify them or select free space to create new partitions.",
+        "Root always gets whatever space is left after these partitions.",
       )],
-      vec![(None, "Use buttons at bottom for additional actions.")],
     ]);
-    let help_modal = HelpModal::new("Manual Partitioning", help_content);
+    let help_modal = HelpModal::new("Extra Mounts", help_content);
     Self {
-      disk_config,
+      fs,
       buttons,
-      confirming_reset: false,
+      size_input: LineEditor::new("Partition Size", Some("e.g. 10 GiB")),
+      sizes: Self::MOUNTS
+        .iter()
+        .map(|(mount, size)| (*mount, size.to_string()))
+        .collect(),
+      editing: None,
+      notice: None,
       help_modal,
     }
   }
+  fn is_checked(&self, idx: usize) -> bool {
+    self
+      .buttons
+      .widgets
+      .get(idx)
+      .and_then(|w| w.get_value())
+      .is_some_and(|v| v == Value::Bool(true))
+  }
+  /// The checked mounts with their sizes in sectors
+  fn selected_mounts(&self, sector_size: u64, total: u64) -> Result<Vec<(String, u64)>, String> {
+    let mut mounts = vec![];
+    for (idx, (mount, size)) in self.sizes.iter().enumerate() {
+      if !self.is_checked(idx) {
+        continue;
+      }
+      let sectors = parse_sectors(size, sector_size, total)
+        .map_err(|e| format!("Invalid size '{size}' for {mount}: {e}"))?;
+      if sectors == 0 {
+        return Err(format!("Invalid size '{size}' for {mount}"));
+      }
+      mounts.push((mount.to_string(), sectors));
+    }
+    Ok(mounts)
+  }
 }
 
-impl Page for ManualPartition {
-  fn render(&mut self, installer: &mut Installer, f: &mut Frame, area: Rect) {
-    let Some(ref config) = installer.drive_config else {
-      log::error!("No drive config available for manual partitioning");
-      return;
-    };
-    let rows = part_table(config.layout(), config.sector_size())
-      .rows()
-      .to_vec();
-    self.disk_config.set_rows(rows);
-    let len = self.disk_config.len();
-    let table_constraint = 20 + (5u16 * len as u16);
-    let padding = 70u16.saturating_sub(table_constraint);
+impl Page for DefaultLayoutMounts {
+  fn render(&mut self, _installer: &mut Installer, f: &mut Frame, area: Rect) {
     let chunks = split_vert!(
       area,
       1,
       [
-        Constraint::Percentage(table_constraint),
-        Constraint::Percentage(30),
-        Constraint::Percentage(padding),
+        Constraint::Percentage(40),
+        Constraint::Percentage(40),
+        Constraint::Length(5),
       ]
     );
     let hor_chunks = split_hor!(
       chunks[1],
       1,
       [
-        Constraint::Percentage(33),
-        Constraint::Percentage(33),
-        Constraint::Percentage(33),
+        Constraint::Percentage(30),
+        Constraint::Percentage(40),
+        Constraint::Percentage(30),
       ]
     );
-
-    self.disk_config.render(f, chunks[0]);
-    self.buttons.render(f, hor_chunks[1]);
-
-    // Render help modal on top
-    self.help_modal.render(f, area);
-  }
-  fn handle_input(&mut self, installer: &mut Installer, event: KeyEvent) -> Signal {
-    match event.code {
-      KeyCode::Char('?') => {
-        self.help_modal.toggle();
+    let input_chunks = split_hor!(
+      chunks[2],
+      1,
+      [
+        Constraint::Percentage(30),
+        Constraint::Percentage(40),
+        Constraint::Percentage(30),
+      ]
+    );
+    let mut lines = vec![
+      vec![(
+        None,
+        "Optionally put some standard mounts on their own partitions.".to_string(),
+      )],
+      vec![(
+        None,
+        format!(
+          "Root keeps the rest of the disk and needs at least {}.",
+          bytes_readable(MIN_ROOT_MIB * 1024 * 1024)
+        ),
+      )],
+      vec![(None, String::new())],
+    ];
+    for (idx, (mount, size)) in self.sizes.iter().enumerate() {
+      let state = if self.is_checked(idx) {
+        size.clone()
+      } else {
+        "not separate".to_string()
+      };
+      lines.push(vec![(None, format!(" - {mount}: ")), (HIGHLIGHT, state)]);
+    }
+    if let Some(ref notice) = self.notice {
+      lines.push(vec![(None, String::new())]);
+      lines.push(vec![(Some((Color::Red, Modifier::BOLD)), notice.clone())]);
+    }
+    let info_box = InfoBox::new("Extra Mounts", styled_block(lines));
+    info_box.render(f, chunks[0]);
+    self.buttons.render(f, hor_chunks[1]);
+    if self.editing.is_some() {
+      self.size_input.render(f, input_chunks[1]);
+    }
+    self.help_modal.render(f, area);
+  }
+
+  fn get_help_content(&self) -> (String, Vec<ratatui::text::Line<'_>>) {
+    let help_content = styled_block(vec![
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Navigate options"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
+        (None, " - Toggle a mount or select an action"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Tab"),
+        (None, " - Edit the size of the highlighted mount"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Esc"),
+        (None, " - Return to previous menu"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "?"),
+        (None, " - Show this help"),
+      ],
+      vec![(None, "")],
+      vec![(
+        None,
+        "Root always gets whatever space is left after these partitions.",
+      )],
+    ]);
+    ("Extra Mounts".to_string(), help_content)
+  }
+
+  fn handle_paste(&mut self, _installer: &mut Installer, text: &str) -> Signal {
+    if !self.help_modal.visible && self.editing.is_some() {
+      self.size_input.paste(text);
+    }
+    Signal::Wait
+  }
+
+  fn handle_input(&mut self, installer: &mut Installer, event: KeyEvent) -> Signal {
+    match event.code {
+      KeyCode::Char('?') => {
+        self.help_modal.toggle();
+        return Signal::Wait;
+      }
+      code if ui_close!(code) && self.help_modal.visible => {
+        self.help_modal.hide();
+        return Signal::Wait;
+      }
+      _ if self.help_modal.visible => return Signal::Wait,
+      _ => {}
+    }
+    if let Some(idx) = self.editing {
+      return match event.code {
+        KeyCode::Esc => {
+          self.editing = None;
+          self.size_input.unfocus();
+          self.buttons.focus();
+          Signal::Wait
+        }
+        KeyCode::Enter => {
+          let size = self
+            .size_input
+            .get_value()
+            .and_then(|v| v.as_str().map(|s| s.trim().to_string()))
+            .unwrap_or_default();
+          if !matches!(parse_sectors(&size, 512, 1), Ok(s) if s > 0) || size.ends_with('%') {
+            self
+              .size_input
+              .error("Enter a size like '10 GiB' or '500 MiB'");
+            return Signal::Wait;
+          }
+          self.sizes[idx].1 = size;
+          self.editing = None;
+          self.size_input.unfocus();
+          self.buttons.focus();
+          Signal::Wait
+        }
+        _ => self.size_input.handle_input(event),
+      };
+    }
+    match event.code {
+      code if ui_back!(code) => Signal::Pop,
+      code if ui_up!(code) => {
+        self.buttons.prev_child();
+        Signal::Wait
+      }
+      code if ui_down!(code) => {
+        self.buttons.next_child();
+        Signal::Wait
+      }
+      KeyCode::Tab => {
+        let Some(idx) = self
+          .buttons
+          .selected_child()
+          .filter(|i| *i < self.sizes.len())
+        else {
+          return Signal::Wait;
+        };
+        self.size_input.set_value(&self.sizes[idx].1);
+        self.editing = Some(idx);
+        self.buttons.unfocus();
+        self.size_input.focus();
+        Signal::Wait
+      }
+      KeyCode::Enter => match self.buttons.selected_child() {
+        Some(idx) if idx < self.sizes.len() => {
+          if let Some(chkbox) = self.buttons.focused_child_mut() {
+            chkbox.interact();
+          }
+          self.notice = None;
+          Signal::Wait
+        }
+        Some(idx) if idx == self.sizes.len() => {
+          let bios_grub = installer.needs_bios_grub();
+          let Some(config) = installer.active_disk_mut() else {
+            return Signal::Error(anyhow::anyhow!("No drive config available"));
+          };
+          let mounts = match self.selected_mounts(config.sector_size(), config.size()) {
+            Ok(mounts) => mounts,
+            Err(e) => {
+              self.notice = Some(e);
+              return Signal::Wait;
+            }
+          };
+          if let Err(e) = config.check_extra_mounts(&mounts) {
+            self.notice = Some(e.to_string());
+            return Signal::Wait;
+          }
+          config.use_default_layout_with_mounts(Some(self.fs.clone()), &mounts, bios_grub);
+          installer.make_drive_config_display();
+          Signal::Unwind
+        }
+        Some(_) => Signal::Pop,
+        None => Signal::Wait,
+      },
+      _ => Signal::Wait,
+    }
+  }
+}
+
+pub struct ManualPartition {
+  disk_config: TableWidget,
+  buttons: WidgetBox,
+  confirming_reset: bool,
+  auto_labels: bool,
+  help_modal: HelpModal<'static>,
+}
+
+impl ManualPartition {
+  fn button_row(reset_label: &str, auto_labels: bool) -> Vec<Box<dyn ConfigWidget>> {
+    vec![
+      Box::new(Button::new("Suggest Partition Layout")) as Box<dyn ConfigWidget>,
+      Box::new(Button::new("Confirm and Exit")) as Box<dyn ConfigWidget>,
+      Box::new(Button::new(reset_label)) as Box<dyn ConfigWidget>,
+      Box::new(Button::new("Export Layout")) as Box<dyn ConfigWidget>,
+      Box::new(Button::new("Import Layout")) as Box<dyn ConfigWidget>,
+      Box::new(CheckBox::new("Label from mount points", auto_labels)) as Box<dyn ConfigWidget>,
+      Box::new(Button::new("Add Another Disk")) as Box<dyn ConfigWidget>,
+      Box::new(Button::new("Abort")) as Box<dyn ConfigWidget>,
+    ]
+  }
+  /// Leave an additional disk for the install target's partitioning page
+  ///
+  /// Only `Esc` keeps the disk, `Abort` takes it out of the layout again.
+  fn leave_extra_disk(installer: &mut Installer, keep: bool) -> Signal {
+    if let Some(idx) = installer.editing_disk.take()
+      && !keep
+    {
+      installer.extra_disks.remove(idx);
+    }
+    Signal::PopCount(2)
+  }
+  pub fn new(mut disk_config: TableWidget, auto_labels: bool) -> Self {
+    let buttons = WidgetBox::button_menu(Self::button_row("Reset Partition Layout", auto_labels));
+    disk_config.focus();
+    let help_content = styled_block(vec![
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Navigate partitions and buttons"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Tab"),
+        (None, " - Switch between partition table and buttons"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
+        (None, " - Select partition or button action"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Esc"),
+        (None, " - Return to previous menu"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "?"),
+        (None, " - Show this help"),
+      ],
+      vec![(None, "")],
+      vec![(
+        None,
+        "Manually configure drive partitions. Select partitions to",
+      )],
+      vec![(
+        None,
+        "modify them or select free space to create new partitions.",
+      )],
+      vec![(None, "Use buttons at bottom for additional actions.")],
+      vec![(
+        None,
+        "'Label from mount points' names unlabelled partitions after where they are mounted, e.g. / is nixos and /boot is BOOT.",
+      )],
+      vec![(
+        None,
+        "'Add Another Disk' partitions a second drive, e.g. for /home. Abort on an added disk removes it again.",
+      )],
+    ]);
+    let help_modal = HelpModal::new("Manual Partitioning", help_content);
+    Self {
+      disk_config,
+      buttons,
+      confirming_reset: false,
+      auto_labels,
+      help_modal,
+    }
+  }
+}
+
+impl Page for ManualPartition {
+  fn render(&mut self, installer: &mut Installer, f: &mut Frame, area: Rect) {
+    let Some(config) = installer.active_disk() else {
+      log::error!("No drive config available for manual partitioning");
+      return;
+    };
+    let rows = part_table(config.layout(), config.sector_size())
+      .rows()
+      .to_vec();
+    self.disk_config.set_rows(rows);
+    // An imported layout brings its own setting
+    if config.auto_labels() != self.auto_labels {
+      self.auto_labels = config.auto_labels();
+      self
+        .buttons
+        .set_children_inplace(Self::button_row("Reset Partition Layout", self.auto_labels));
+    }
+    let bar = part_bar(config);
+    let len = self.disk_config.len();
+    let table_constraint = 20 + (5u16 * len as u16);
+    let padding = 70u16.saturating_sub(table_constraint);
+    let outer = split_vert!(area, 0, [Constraint::Length(6), Constraint::Min(0)]);
+    let bar_area = split_hor!(outer[0], 1, [Constraint::Percentage(100)]);
+    bar.render(f, bar_area[0]);
+    let chunks = split_vert!(
+      outer[1],
+      1,
+      [
+        Constraint::Percentage(table_constraint),
+        Constraint::Percentage(30),
+        Constraint::Percentage(padding),
+      ]
+    );
+    let hor_chunks = split_hor!(
+      chunks[1],
+      1,
+      [
+        Constraint::Percentage(33),
+        Constraint::Percentage(33),
+        Constraint::Percentage(33),
+      ]
+    );
+
+    self.disk_config.render(f, chunks[0]);
+    self.buttons.render(f, hor_chunks[1]);
+
+    // Render help modal on top
+    self.help_modal.render(f, area);
+  }
+  fn handle_input(&mut self, installer: &mut Installer, event: KeyEvent) -> Signal {
+    match event.code {
+      KeyCode::Char('?') => {
+        self.help_modal.toggle();
         return Signal::Wait;
       }
-      ui_close!() if self.help_modal.visible => {
+      code if ui_close!(code) && self.help_modal.visible => {
         self.help_modal.hide();
         return Signal::Wait;
       }
@@ -930,17 +1711,14 @@ impl Page for ManualPartition {
 
     if self.confirming_reset && event.code != KeyCode::Enter {
       self.confirming_reset = false;
-      self.buttons.set_children_inplace(vec![
-        Box::new(Button::new("Suggest Partition Layout")) as Box<dyn ConfigWidget>,
-        Box::new(Button::new("Confirm and Exit")) as Box<dyn ConfigWidget>,
-        Box::new(Button::new("Reset Partition Layout")) as Box<dyn ConfigWidget>,
-        Box::new(Button::new("Abort")) as Box<dyn ConfigWidget>,
-      ]);
+      self
+        .buttons
+        .set_children_inplace(Self::button_row("Reset Partition Layout", self.auto_labels));
     }
     if self.disk_config.is_focused() {
       match event.code {
-        ui_back!() => Signal::PopCount(2),
-        ui_up!() => {
+        code if ui_back!(code) => Self::leave_extra_disk(installer, true),
+        code if ui_up!(code) => {
           if !self.disk_config.previous_row() {
             self.disk_config.unfocus();
             self.buttons.last_child();
@@ -948,7 +1726,7 @@ impl Page for ManualPartition {
           }
           Signal::Wait
         }
-        ui_down!() => {
+        code if ui_down!(code) => {
           if !self.disk_config.next_row() {
             self.disk_config.unfocus();
             self.buttons.first_child();
@@ -969,7 +1747,7 @@ impl Page for ManualPartition {
               row
             ));
           };
-          let Some(ref drive) = installer.drive_config else {
+          let Some(drive) = installer.active_disk() else {
             return Signal::Error(anyhow::anyhow!("No drive config available"));
           };
           let layout = drive.layout();
@@ -987,6 +1765,7 @@ impl Page for ManualPartition {
               *start,
               drive.sector_size(),
               *size,
+              installer.preferred_fs(),
             ))),
           }
         }
@@ -994,8 +1773,8 @@ impl Page for ManualPartition {
       }
     } else if self.buttons.is_focused() {
       match event.code {
-        ui_back!() => Signal::PopCount(2),
-        ui_up!() => {
+        code if ui_back!(code) => Self::leave_extra_disk(installer, true),
+        code if ui_up!(code) => {
           if !self.buttons.prev_child() {
             self.buttons.unfocus();
             self.disk_config.last_row();
@@ -1003,7 +1782,7 @@ impl Page for ManualPartition {
           }
           Signal::Wait
         }
-        ui_down!() => {
+        code if ui_down!(code) => {
           if !self.buttons.next_child() {
             self.buttons.unfocus();
             self.disk_config.first_row();
@@ -1022,22 +1801,19 @@ impl Page for ManualPartition {
             }
             1 => {
               // Confirm and Exit
+              installer.editing_disk = None;
               installer.make_drive_config_display();
               return Signal::Unwind;
             }
             2 => {
               if !self.confirming_reset {
                 self.confirming_reset = true;
-                let new_buttons = vec![
-                  Box::new(Button::new("Suggest Partition Layout")) as Box<dyn ConfigWidget>,
-                  Box::new(Button::new("Confirm and Exit")) as Box<dyn ConfigWidget>,
-                  Box::new(Button::new("Really?")) as Box<dyn ConfigWidget>,
-                  Box::new(Button::new("Abort")) as Box<dyn ConfigWidget>,
-                ];
-                self.buttons.set_children_inplace(new_buttons);
+                self
+                  .buttons
+                  .set_children_inplace(Self::button_row("Really?", self.auto_labels));
                 Signal::Wait
               } else {
-                let Some(ref mut device) = installer.drive_config else {
+                let Some(device) = installer.active_disk_mut() else {
                   return Signal::Wait;
                 };
                 device.reset_layout();
@@ -1045,18 +1821,35 @@ impl Page for ManualPartition {
                 self.disk_config.first_row();
                 self.disk_config.focus();
                 self.confirming_reset = false;
-                self.buttons.set_children_inplace(vec![
-                  Box::new(Button::new("Suggest Partition Layout")) as Box<dyn ConfigWidget>,
-                  Box::new(Button::new("Confirm and Exit")) as Box<dyn ConfigWidget>,
-                  Box::new(Button::new("Reset Partition Layout")) as Box<dyn ConfigWidget>,
-                  Box::new(Button::new("Abort")) as Box<dyn ConfigWidget>,
-                ]);
+                self.buttons.set_children_inplace(Self::button_row(
+                  "Reset Partition Layout",
+                  self.auto_labels,
+                ));
                 Signal::Wait
               }
             }
-            3 => {
+            3 => Signal::Push(Box::new(LayoutFile::export())),
+            4 => Signal::Push(Box::new(LayoutFile::import_over_manual())),
+            5 => {
+              let Some(device) = installer.active_disk_mut() else {
+                return Signal::Wait;
+              };
+              self.auto_labels = !self.auto_labels;
+              device.set_auto_labels(self.auto_labels);
+              if let Some(chkbox) = self.buttons.focused_child_mut() {
+                chkbox.interact();
+              }
+              Signal::Wait
+            }
+            6 => {
+              // Add Another Disk
+              Signal::Push(Box::new(SelectDrive::another(disk_table(
+                &installer.drives,
+              ))))
+            }
+            7 => {
               // Abort
-              return Signal::PopCount(2);
+              Self::leave_extra_disk(installer, false)
             }
             _ => Signal::Wait,
           }
@@ -1072,7 +1865,10 @@ impl Page for ManualPartition {
   fn get_help_content(&self) -> (String, Vec<ratatui::text::Line<'_>>) {
     let help_content = styled_block(vec![
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "↑/↓, j/k"),
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
         (None, " - Navigate partitions and buttons"),
       ],
       vec![
@@ -1101,6 +1897,14 @@ impl Page for ManualPartition {
         "modify them or select free space to create new partitions.",
       )],
       vec![(None, "Use buttons at bottom for additional actions.")],
+      vec![(
+        None,
+        "'Label from mount points' names unlabelled partitions after where they are mounted, e.g. / is nixos and /boot is BOOT.",
+      )],
+      vec![(
+        None,
+        "'Add Another Disk' partitions a second drive, e.g. for /home. Abort on an added disk removes it again.",
+      )],
     ]);
     ("Manual Partitioning".to_string(), help_content)
   }
@@ -1121,7 +1925,10 @@ impl SuggestPartition {
     button_row.focus();
     let help_content = styled_block(vec![
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "↑/↓, j/k"),
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
         (None, " - Navigate yes/no options"),
       ],
       vec![
@@ -1209,17 +2016,17 @@ impl Page for SuggestPartition {
         self.help_modal.toggle();
         Signal::Wait
       }
-      ui_close!() if self.help_modal.visible => {
+      code if ui_close!(code) && self.help_modal.visible => {
         self.help_modal.hide();
         Signal::Wait
       }
       _ if self.help_modal.visible => Signal::Wait,
-      ui_back!() => Signal::Pop,
-      ui_up!() => {
+      code if ui_back!(code) => Signal::Pop,
+      code if ui_up!(code) => {
         self.buttons.prev_child();
         Signal::Wait
       }
-      ui_down!() => {
+      code if ui_down!(code) => {
         self.buttons.next_child();
         Signal::Wait
       }
@@ -1230,8 +2037,10 @@ impl Page for SuggestPartition {
         match idx {
           0 => {
             // Yes
-            if let Some(ref mut config) = installer.drive_config {
-              config.use_default_layout(Some("ext4".into()));
+            let fs = installer.preferred_fs().to_string();
+            let bios_grub = installer.needs_bios_grub();
+            if let Some(config) = installer.active_disk_mut() {
+              config.use_default_layout(Some(fs), bios_grub);
             } else {
               return Signal::Error(anyhow::anyhow!(
                 "No drive config available for suggested partition layout"
@@ -1253,7 +2062,10 @@ impl Page for SuggestPartition {
   fn get_help_content(&self) -> (String, Vec<ratatui::text::Line<'_>>) {
     let help_content = styled_block(vec![
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "↑/↓, j/k"),
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
         (None, " - Navigate yes/no options"),
       ],
       vec![
@@ -1301,23 +2113,21 @@ pub struct NewPartition {
 }
 
 impl NewPartition {
-  pub fn new(fs_id: u64, part_start: u64, sector_size: u64, total_size: u64) -> Self {
+  pub fn new(
+    fs_id: u64,
+    part_start: u64,
+    sector_size: u64,
+    total_size: u64,
+    preferred_fs: &str,
+  ) -> Self {
     let bytes = total_size * sector_size;
     let sectors = bytes.div_ceil(sector_size); // round up
     let part_end = part_start + sectors - 1;
     let fs_buttons = {
-      let buttons = vec![
-        Box::new(Button::new("ext4")) as Box<dyn ConfigWidget>,
-        Box::new(Button::new("ext3")) as Box<dyn ConfigWidget>,
-        Box::new(Button::new("ext2")) as Box<dyn ConfigWidget>,
-        Box::new(Button::new("btrfs")) as Box<dyn ConfigWidget>,
-        Box::new(Button::new("xfs")) as Box<dyn ConfigWidget>,
-        Box::new(Button::new("fat12")) as Box<dyn ConfigWidget>,
-        Box::new(Button::new("fat16")) as Box<dyn ConfigWidget>,
-        Box::new(Button::new("fat32")) as Box<dyn ConfigWidget>,
-        Box::new(Button::new("ntfs")) as Box<dyn ConfigWidget>,
-      ];
+      let mut buttons = fs_buttons(preferred_fs);
+      buttons.push(Box::new(Button::new(SWAP_FS)));
       let mut button_row = WidgetBox::button_menu(buttons);
+      button_row.select_child(fs_button_idx(preferred_fs));
       button_row.focus();
       button_row
     };
@@ -1347,14 +2157,34 @@ impl NewPartition {
   pub fn total_size_bytes(&self) -> u64 {
     self.total_size * self.sector_size
   }
+  /// The size the current input resolves to, shown live while typing
+  fn size_preview(&self) -> String {
+    let input = self
+      .size_input
+      .get_value()
+      .and_then(|v| v.as_str().map(|s| s.trim().to_string()))
+      .unwrap_or_default();
+    let input = if input.is_empty() { "100%" } else { &input };
+    match parse_sectors(input, self.sector_size, self.total_size) {
+      Ok(sectors) if sectors > self.total_size => format!(
+        "Too large, {} free",
+        bytes_readable(self.total_size_bytes())
+      ),
+      Ok(sectors) => format!(
+        "{} ({sectors} sectors)",
+        bytes_readable(sectors.saturating_mul(self.sector_size))
+      ),
+      Err(e) => format!("Invalid size: {e}"),
+    }
+  }
   pub fn render_size_input(&mut self, f: &mut Frame, area: Rect) {
     let chunks = split_vert!(
       area,
       1,
       [
-        Constraint::Percentage(40),
+        Constraint::Percentage(50),
         Constraint::Length(7),
-        Constraint::Percentage(40),
+        Constraint::Percentage(30),
       ]
     );
     let hor_chunks = split_hor!(
@@ -1386,10 +2216,18 @@ impl NewPartition {
           (HIGHLIGHT, "Total Free Space: "),
           (None, &bytes_readable(self.total_size_bytes())),
         ],
+        vec![
+          (HIGHLIGHT, "Resulting Size: "),
+          (None, &self.size_preview()),
+        ],
         vec![(None, "")],
         vec![(
           None,
-          "Enter the desired size for the new partition. You can specify sizes in bytes (B), kilobytes (KB), megabytes (MB), gigabytes (GB), terabytes (TB), or as a percentage of the total free space (e.g., 50%). A number given without a unit is counted in sectors.",
+          "Enter the desired size for the new partition. You can specify sizes in bytes (B), kilobytes (KB), megabytes (MB), gigabytes (GB), terabytes (TB), or as a percentage of this free space region (e.g., 50%). A number given without a unit is counted in sectors.",
+        )],
+        vec![(
+          None,
+          "Percentages are relative to the free space remaining here, not the whole disk. Creating a 50% partition and then another 50% partition in the space left over gives you a half and a quarter.",
         )],
         vec![
           (None, "Examples: "),
@@ -1413,20 +2251,31 @@ impl NewPartition {
         if input.is_empty() {
           input = "100%";
         }
-        let Some(ref device) = installer.drive_config else {
+        let Some(device) = installer.active_disk() else {
           return Signal::Error(anyhow::anyhow!(
             "No drive config available for new partition size input"
           ));
         };
         match parse_sectors(input, device.sector_size(), self.total_size) {
-          Some(size) => {
+          Ok(0) => {
+            self.size_input.error("Size must be larger than zero");
+            Signal::Wait
+          }
+          Ok(size) if size > self.total_size => {
+            self.size_input.error(format!(
+              "Size exceeds available free space (max: {})",
+              bytes_readable(self.total_size_bytes())
+            ));
+            Signal::Wait
+          }
+          Ok(size) => {
             self.new_part_size = Some(size);
             self.size_input.unfocus();
             self.fs_buttons.focus();
             Signal::Wait
           }
-          None => {
-            self.size_input.error("Invalid size input");
+          Err(e) => {
+            self.size_input.error(e);
             Signal::Wait
           }
         }
@@ -1451,20 +2300,41 @@ impl NewPartition {
     );
 
     let idx = self.fs_buttons.selected_child().unwrap_or(9);
-    let info_box = SelectFilesystem::get_fs_info(self.fs_buttons.selected_child().unwrap_or(9));
+    let info_box = if idx == FILESYSTEMS.len() {
+      Self::swap_info()
+    } else {
+      SelectFilesystem::get_fs_info(idx)
+    };
     self.fs_buttons.render(f, hor_chunks[1]);
-    if idx < 9 {
+    if idx <= FILESYSTEMS.len() {
       info_box.render(f, vert_chunks[1]);
     }
   }
-  pub fn handle_input_fs_select(&mut self, _installer: &mut Installer, event: KeyEvent) -> Signal {
+  fn swap_info<'a>() -> InfoBox<'a> {
+    InfoBox::new(
+      SWAP_FS,
+      styled_block(vec![
+        vec![
+          (HIGHLIGHT, "linux-swap"),
+          (None, " makes this partition "),
+          (HIGHLIGHT, "swap space"),
+          (None, " instead of a filesystem, so it gets no mount point."),
+        ],
+        vec![(
+          None,
+          "Set Swap to 'Swap partition' in the main menu to go without a swapfile or zram.",
+        )],
+      ]),
+    )
+  }
+  pub fn handle_input_fs_select(&mut self, installer: &mut Installer, event: KeyEvent) -> Signal {
     match event.code {
-      ui_back!() => Signal::Pop,
-      ui_up!() => {
+      code if ui_back!(code) => Signal::Pop,
+      code if ui_up!(code) => {
         self.fs_buttons.prev_child();
         Signal::Wait
       }
-      ui_down!() => {
+      code if ui_down!(code) => {
         self.fs_buttons.next_child();
         Signal::Wait
       }
@@ -1472,25 +2342,15 @@ impl NewPartition {
         let Some(idx) = self.fs_buttons.selected_child() else {
           return Signal::Wait;
         };
-        let fs = match idx {
-          0 => "ext4",
-          1 => "ext3",
-          2 => "ext2",
-          3 => "btrfs",
-          4 => "xfs",
-          5 => "fat12",
-          6 => "fat16",
-          7 => "fat32",
-          8 => "ntfs",
-          9 => {
-            self.new_part_size = None;
-            self.size_input.focus();
-            self.fs_buttons.unfocus();
-            return Signal::Wait;
-          }
-          _ => return Signal::Wait,
+        if idx == FILESYSTEMS.len() {
+          // Swap has nothing to mount, so the partition is done
+          self.new_part_fs = Some(SWAP_FS.to_string());
+          return self.create_partition(installer, None);
         }
-        .to_string();
+        let Some(fs) = FILESYSTEMS.get(idx) else {
+          return Signal::Wait;
+        };
+        let fs = fs.to_string();
 
         self.new_part_fs = Some(fs);
         self.fs_buttons.unfocus();
@@ -1520,36 +2380,47 @@ impl NewPartition {
       ]
     );
 
-    let info_box = InfoBox::new(
-      "Mount Point Info",
-      styled_block(vec![
-        vec![(
-          None,
-          "Enter the mount point for the new partition. This is the directory where the partition will be mounted in the filesystem.",
-        )],
-        vec![
-          (None, "Common mount points include "),
-          (Some((Color::Green, Modifier::BOLD)), "/"),
-          (None, " for root, "),
-          (Some((Color::Green, Modifier::BOLD)), "/home"),
-          (None, " for user data, "),
-          (Some((Color::Green, Modifier::BOLD)), "/boot"),
-          (None, " for boot files, and "),
-          (Some((Color::Green, Modifier::BOLD)), "/var"),
-          (None, " for variable data."),
-        ],
-        vec![(None, "You can also specify other mount points as needed.")],
-        vec![(None, "")],
-        vec![
-          (None, "Examples: "),
-          (Some((Color::Green, Modifier::BOLD)), "/"),
-          (None, ", "),
-          (Some((Color::Green, Modifier::BOLD)), "/home"),
-          (None, ", "),
-          (Some((Color::Green, Modifier::BOLD)), "/mnt/data"),
-        ],
-      ]),
-    );
+    let mount_point = self
+      .mount_input
+      .get_value()
+      .and_then(|v| v.as_str().map(|s| s.trim().to_string()))
+      .unwrap_or_default();
+    let size_bytes = self.new_part_size.unwrap_or(0) * self.sector_size;
+    let mut lines = styled_block(vec![
+      vec![(
+        None,
+        "Enter the mount point for the new partition. This is the directory where the partition will be mounted in the filesystem.",
+      )],
+      vec![
+        (None, "Common mount points include "),
+        (Some((Color::Green, Modifier::BOLD)), "/"),
+        (None, " for root, "),
+        (Some((Color::Green, Modifier::BOLD)), "/home"),
+        (None, " for user data, "),
+        (Some((Color::Green, Modifier::BOLD)), "/boot"),
+        (None, " for boot files, and "),
+        (Some((Color::Green, Modifier::BOLD)), "/var"),
+        (None, " for variable data."),
+      ],
+      vec![(None, "You can also specify other mount points as needed.")],
+      vec![(None, "")],
+      vec![
+        (None, "Examples: "),
+        (Some((Color::Green, Modifier::BOLD)), "/"),
+        (None, ", "),
+        (Some((Color::Green, Modifier::BOLD)), "/home"),
+        (None, ", "),
+        (Some((Color::Green, Modifier::BOLD)), "/mnt/data"),
+      ],
+    ]);
+    if let Some(warning) = small_partition_warning(&mount_point, size_bytes) {
+      lines.push(ratatui::text::Line::from(""));
+      lines.extend(styled_block(vec![vec![(
+        Some((Color::Yellow, Modifier::BOLD)),
+        warning,
+      )]]));
+    }
+    let info_box = InfoBox::new("Mount Point Info", lines);
     info_box.render(f, chunks[0]);
     self.mount_input.render(f, hor_chunks[1]);
   }
@@ -1564,65 +2435,54 @@ impl NewPartition {
       KeyCode::Enter => {
         let input = self.mount_input.get_value().unwrap();
         let input = input.as_str().unwrap().trim(); // TODO: handle these unwraps
-        let Some(ref mut device) = installer.drive_config else {
-          return Signal::Error(anyhow::anyhow!(
-            "No drive config available for new partition mount point input"
-          ));
-        };
-        let taken_mounts: Vec<String> = device
-          .layout()
-          .iter()
-          .filter(|di| {
-            let DiskItem::Partition(p) = di else {
-              return true;
-            };
-            let PartStatus::Delete = *p.status() else {
-              return true;
-            };
-            false // The partition is deleted, so we filter it out
-          })
-          .filter_map(|d| d.mount_point().map(|s| s.to_string()))
-          .collect();
-
+        // Mount points have to be unique across every disk in the layout
+        let taken_mounts = installer.taken_mount_points(None);
         if let Err(err) = SetMountPoint::validate_mount_point(input, &taken_mounts) {
           self.mount_input.error(&err);
           return Signal::Wait;
         }
         self.new_part_mount_point = Some(input.to_string());
         self.mount_input.unfocus();
-
-        let flags = if self.new_part_mount_point.as_deref() == Some("/boot") {
-          vec!["boot".to_string(), "esp".to_string()]
-        } else {
-          vec![]
-        };
-        let Some(size) = self.new_part_size else {
-          return Signal::Error(anyhow::anyhow!(
-            "No new partition size specified when finalizing new partition"
-          ));
-        };
-
-        let new_part = Partition::new(
-          self.part_start,
-          size,
-          self.sector_size,
-          PartStatus::Create,
-          None,
-          self.new_part_fs.clone(),
-          self.new_part_mount_point.clone(),
-          None,
-          false,
-          flags,
-        );
-        if let Err(e) = device.new_partition(new_part) {
-          return Signal::Error(anyhow::anyhow!("Failed to create new partition: {}", e));
-        };
-
-        Signal::Pop
+        self.create_partition(installer, self.new_part_mount_point.clone())
       }
       _ => self.mount_input.handle_input(event),
     }
   }
+  fn create_partition(&mut self, installer: &mut Installer, mount_point: Option<String>) -> Signal {
+    let Some(device) = installer.active_disk_mut() else {
+      return Signal::Error(anyhow::anyhow!(
+        "No drive config available when finalizing new partition"
+      ));
+    };
+    let flags = if mount_point.as_deref() == Some("/boot") {
+      vec!["boot".to_string(), "esp".to_string()]
+    } else {
+      vec![]
+    };
+    let Some(size) = self.new_part_size else {
+      return Signal::Error(anyhow::anyhow!(
+        "No new partition size specified when finalizing new partition"
+      ));
+    };
+
+    let new_part = Partition::new(
+      self.part_start,
+      size,
+      self.sector_size,
+      PartStatus::Create,
+      None,
+      self.new_part_fs.clone(),
+      mount_point,
+      None,
+      false,
+      flags,
+    );
+    if let Err(e) = device.new_partition(new_part) {
+      return Signal::Error(anyhow::anyhow!("Failed to create new partition: {}", e));
+    };
+
+    Signal::Pop
+  }
 }
 
 impl Page for NewPartition {
@@ -1635,6 +2495,19 @@ impl Page for NewPartition {
       self.render_mount_point_input(f, area);
     }
   }
+  fn has_help(&self) -> bool {
+    false
+  }
+
+  fn handle_paste(&mut self, _installer: &mut Installer, text: &str) -> Signal {
+    if self.new_part_size.is_none() {
+      self.size_input.paste(text);
+    } else if self.new_part_fs.is_some() && self.new_part_mount_point.is_none() {
+      self.mount_input.paste(text);
+    }
+    Signal::Wait
+  }
+
   fn handle_input(&mut self, installer: &mut Installer, event: KeyEvent) -> Signal {
     if self.new_part_size.is_none() {
       self.handle_input_size(installer, event)
@@ -1652,26 +2525,69 @@ pub struct AlterPartition {
   pub buttons: WidgetBox,
   pub part_id: u64,
   pub part_status: PartStatus,
+  /// An existing FAT ESP, offered for reuse as /boot, e.g. when dual booting
+  reusable_esp: bool,
+  /// Why the last action was refused, shown under the options
+  notice: Option<String>,
 }
 
 impl AlterPartition {
   pub fn new(part: Partition) -> Self {
     let part_status = part.status();
-    let buttons = Self::buttons_by_status(*part_status, part.flags());
+    let reusable_esp =
+      *part_status == PartStatus::Exists && part.has_flag("esp") && part.fs_type() == Some("vfat");
+    let mut buttons = Self::buttons_by_status(*part_status, part.flags(), part.luks().is_some());
+    if reusable_esp {
+      buttons.insert(
+        0,
+        Box::new(Button::new(
+          "Use existing ESP (mount at /boot, do not format)",
+        )),
+      );
+    }
     let mut button_row = WidgetBox::button_menu(buttons);
     button_row.focus();
     Self {
       buttons: button_row,
       part_id: part.id(),
       part_status: *part_status,
+      reusable_esp,
+      notice: None,
+    }
+  }
+  /// Mount the existing ESP at /boot and keep its contents
+  fn reuse_esp(&mut self, installer: &mut Installer) -> Signal {
+    if installer
+      .taken_mount_points(Some(self.part_id))
+      .iter()
+      .any(|mp| mp == "/boot")
+    {
+      self.notice = Some("/boot is already used by another partition".to_string());
+      return Signal::Wait;
     }
+    let Some(part) = installer
+      .active_disk_mut()
+      .and_then(|d| d.partition_by_id_mut(self.part_id))
+    else {
+      return Signal::Error(anyhow::anyhow!(
+        "No partition found with id {}",
+        self.part_id
+      ));
+    };
+    part.set_mount_point("/boot");
+    Signal::Pop
   }
-  pub fn buttons_by_status(status: PartStatus, flags: &[String]) -> Vec<Box<dyn ConfigWidget>> {
+  pub fn buttons_by_status(
+    status: PartStatus,
+    flags: &[String],
+    encrypted: bool,
+  ) -> Vec<Box<dyn ConfigWidget>> {
     match status {
       PartStatus::Exists => vec![
         Box::new(Button::new("Set Mount Point")),
+        Box::new(Button::new("Set Mount Options")),
         Box::new(Button::new(
-          "Mark For Modification (data will be wiped on install)",
+          "Format Partition (data will be wiped on install)",
         )),
         Box::new(Button::new("Delete Partition")),
         Box::new(Button::new("Back")),
@@ -1690,9 +2606,15 @@ impl AlterPartition {
           "Mark as XBOOTLDR partition",
           flags.contains(&"bls_boot".into()),
         )),
+        Box::new(CheckBox::new(
+          "Mark as BIOS boot partition (bios_grub)",
+          flags.contains(&"bios_grub".into()),
+        )),
+        Box::new(CheckBox::new("Encrypt with LUKS", encrypted)),
         Box::new(Button::new("Change Filesystem")),
         Box::new(Button::new("Set Label")),
-        Box::new(Button::new("Unmark for modification")),
+        Box::new(Button::new("Set Mount Options")),
+        Box::new(Button::new("Keep Partition (do not format)")),
         Box::new(Button::new("Delete Partition")),
         Box::new(Button::new("Back")),
       ],
@@ -1710,14 +2632,84 @@ impl AlterPartition {
           "Mark as XBOOTLDR partition",
           flags.contains(&"bls_boot".into()),
         )),
+        Box::new(CheckBox::new(
+          "Mark as BIOS boot partition (bios_grub)",
+          flags.contains(&"bios_grub".into()),
+        )),
+        Box::new(CheckBox::new("Encrypt with LUKS", encrypted)),
         Box::new(Button::new("Change Filesystem")),
         Box::new(Button::new("Set Label")),
+        Box::new(Button::new("Set Mount Options")),
         Box::new(Button::new("Delete Partition")),
         Box::new(Button::new("Back")),
       ],
+      PartStatus::Delete => vec![
+        Box::new(Button::new("Keep Partition")),
+        Box::new(Button::new(
+          "Format Partition (data will be wiped on install)",
+        )),
+        Box::new(Button::new("Back")),
+      ],
       _ => vec![Box::new(Button::new("Back"))],
     }
   }
+  /// Flip the focused flag checkbox and mirror it onto the partition
+  fn toggle_flag(&mut self, device: &mut Disk, flag: &str) -> Signal {
+    self.notice = None;
+    if let Some(child) = self.buttons.focused_child_mut() {
+      child.interact();
+      if let Some(Value::Bool(checked)) = child.get_value()
+        && let Some(part) = device.partition_by_id_mut(self.part_id)
+      {
+        if checked {
+          part.add_flag(flag);
+        } else {
+          part.remove_flag(flag);
+        }
+        if checked && matches!(flag, "esp" | "bios_grub") && part.luks().is_some() {
+          part.set_luks(None);
+          self.notice = Some(format!(
+            "Encryption was turned off, a {flag} partition can't be encrypted"
+          ));
+          let buttons = Self::buttons_by_status(self.part_status, part.flags(), false);
+          self.buttons.set_children_inplace(buttons);
+        }
+      }
+    }
+    Signal::Wait
+  }
+  /// Turning encryption on goes through `LuksSetup`, turning it off is
+  /// immediate
+  fn toggle_luks(&mut self, device: &mut Disk) -> Signal {
+    self.notice = None;
+    let Some(part) = device.partition_by_id_mut(self.part_id) else {
+      return Signal::Wait;
+    };
+    if part.luks().is_some() {
+      part.set_luks(None);
+      if let Some(child) = self.buttons.focused_child_mut() {
+        child.interact();
+      }
+      return Signal::Wait;
+    }
+    if part.has_flag("esp") || part.has_flag("bios_grub") {
+      self.notice = Some(
+        "The firmware has to read this partition before anything is unlocked, it can't be encrypted"
+          .to_string(),
+      );
+      return Signal::Wait;
+    }
+    Signal::Push(Box::new(LuksSetup::new(part)))
+  }
+  fn set_mount_options(&self, device: &Disk) -> Signal {
+    match device.partitions().find(|p| p.id() == self.part_id) {
+      Some(part) => Signal::Push(Box::new(SetMountOptions::new(part))),
+      None => Signal::Error(anyhow::anyhow!(
+        "No partition found with id {}",
+        self.part_id
+      )),
+    }
+  }
   pub fn render_existing_part(&self, f: &mut Frame, area: Rect) {
     let chunks = split_vert!(
       area,
@@ -1725,47 +2717,63 @@ impl AlterPartition {
       [Constraint::Percentage(70), Constraint::Percentage(30)]
     );
 
-    let info_box = InfoBox::new(
-      "Alter Existing Partition",
-      styled_block(vec![
-        vec![(
+    let mut content = styled_block(vec![
+      vec![(
+        None,
+        "This partition will be kept as is, its data will not be touched during installation.",
+      )],
+      vec![
+        (None, "- "),
+        (Some((Color::Green, Modifier::BOLD)), "'Set Mount Point'"),
+        (
           None,
-          "Choose an action to perform on the selected partition.",
-        )],
-        vec![
-          (None, "- "),
-          (Some((Color::Green, Modifier::BOLD)), "'Set Mount Point'"),
-          (
-            None,
-            " allows you to specify where this partition will be mounted in the filesystem.",
-          ),
-        ],
-        vec![
-          (None, "- "),
-          (
-            Some((Color::Green, Modifier::BOLD)),
-            "'Mark For Modification'",
-          ),
-          (
-            None,
-            " will flag this partition to be reformatted during installation (all data will be lost on installation). Partitions marked for modification have more options available in this menu.",
-          ),
-        ],
-        vec![
-          (None, "- "),
-          (Some((Color::Green, Modifier::BOLD)), "'Delete Partition'"),
-          (
-            None,
-            " Mark this existing partition for deletion. The space it occupies will be freed for replacement.",
-          ),
-        ],
-        vec![
-          (None, "- "),
-          (Some((Color::Green, Modifier::BOLD)), "'Back'"),
-          (None, " return to the previous menu without making changes."),
-        ],
-      ]),
-    );
+          " allows you to specify where this partition will be mounted in the filesystem.",
+        ),
+      ],
+      vec![
+        (None, "- "),
+        (Some((Color::Green, Modifier::BOLD)), "'Set Mount Options'"),
+        (None, " adds options like noatime to its fileSystems entry."),
+      ],
+      vec![
+        (None, "- "),
+        (Some((Color::Green, Modifier::BOLD)), "'Format Partition'"),
+        (
+          None,
+          " will reformat this partition during installation (all data will be lost on installation). Partitions marked for formatting have more options available in this menu.",
+        ),
+      ],
+      vec![
+        (None, "- "),
+        (Some((Color::Green, Modifier::BOLD)), "'Delete Partition'"),
+        (
+          None,
+          " Mark this existing partition for deletion. The space it occupies will be freed for replacement.",
+        ),
+      ],
+      vec![
+        (None, "- "),
+        (Some((Color::Green, Modifier::BOLD)), "'Back'"),
+        (None, " return to the previous menu without making changes."),
+      ],
+    ]);
+    if self.reusable_esp {
+      content.extend(styled_block(vec![vec![
+        (None, "- "),
+        (Some((Color::Green, Modifier::BOLD)), "'Use existing ESP'"),
+        (
+          None,
+          " keeps the boot files already on it, e.g. Windows' bootloader, and installs NixOS' next to them.",
+        ),
+      ]]));
+    }
+    if let Some(ref notice) = self.notice {
+      content.extend(styled_block(vec![
+        vec![(None, String::new())],
+        vec![(Some((Color::Red, Modifier::BOLD)), notice.clone())],
+      ]));
+    }
+    let info_box = InfoBox::new("Alter Existing Partition (Keep)", content);
     info_box.render(f, chunks[0]);
     self.buttons.render(f, chunks[1]);
   }
@@ -1776,33 +2784,64 @@ impl AlterPartition {
       [Constraint::Percentage(70), Constraint::Percentage(30)]
     );
 
-    let info_box = InfoBox::new(
-      "Alter Partition (Marked for Modification)",
-      styled_block(vec![
-        vec![(
+    let mut content = styled_block(vec![
+      vec![(
+        None,
+        "This partition will be formatted during installation. You can change its mount point, filesystem and flags, or delete it.",
+      )],
+      vec![
+        (None, "- "),
+        (Some((Color::Green, Modifier::BOLD)), "'Set Mount Point'"),
+        (
           None,
-          "This partition is marked for modification. You can change its mount point or delete it.",
-        )],
-        vec![
-          (None, "- "),
-          (Some((Color::Green, Modifier::BOLD)), "'Set Mount Point'"),
-          (
-            None,
-            " allows you to specify where this partition will be mounted in the filesystem.",
-          ),
-        ],
-        vec![
-          (None, "- "),
-          (Some((Color::Green, Modifier::BOLD)), "'Delete Partition'"),
-          (None, " will remove this partition from the configuration."),
-        ],
-        vec![
-          (None, "- "),
-          (Some((Color::Green, Modifier::BOLD)), "'Back'"),
-          (None, " return to the previous menu without making changes."),
-        ],
-      ]),
-    );
+          " allows you to specify where this partition will be mounted in the filesystem.",
+        ),
+      ],
+      vec![
+        (None, "- "),
+        (Some((Color::Green, Modifier::BOLD)), "'Set Mount Options'"),
+        (
+          None,
+          " sets extra mount options, e.g. noatime or compress=zstd on btrfs.",
+        ),
+      ],
+      vec![
+        (None, "- "),
+        (Some((Color::Green, Modifier::BOLD)), "'Delete Partition'"),
+        (None, " will remove this partition from the configuration."),
+      ],
+      vec![
+        (None, "- "),
+        (
+          Some((Color::Green, Modifier::BOLD)),
+          "'BIOS boot partition'",
+        ),
+        (
+          None,
+          " is needed by GRUB on GPT disks without UEFI. It gets no filesystem, 1MiB is enough.",
+        ),
+      ],
+      vec![
+        (None, "- "),
+        (Some((Color::Green, Modifier::BOLD)), "'Encrypt with LUKS'"),
+        (
+          None,
+          " asks for a passphrase or key file that unlocks the partition at boot.",
+        ),
+      ],
+      vec![
+        (None, "- "),
+        (Some((Color::Green, Modifier::BOLD)), "'Back'"),
+        (None, " return to the previous menu without making changes."),
+      ],
+    ]);
+    if let Some(ref notice) = self.notice {
+      content.extend(styled_block(vec![
+        vec![(None, String::new())],
+        vec![(Some((Color::Red, Modifier::BOLD)), notice.clone())],
+      ]));
+    }
+    let info_box = InfoBox::new("Alter Partition (Format)", content);
     info_box.render(f, chunks[0]);
     self.buttons.render(f, chunks[1]);
   }
@@ -1813,16 +2852,20 @@ impl AlterPartition {
       [Constraint::Percentage(70), Constraint::Percentage(30)]
     );
 
-    let info_box = InfoBox::new(
-      "Deleted Partition",
-      styled_block(vec![
-        vec![(None, "This partition has been marked for deletion.")],
-        vec![(
-          None,
-          "Reclaiming the freed space can cause unpredictable behavior, so if you wish to reclaim the space freed by marking this partition for deletion, please return to the previous menu and reset the partition layout.",
-        )],
-      ]),
-    );
+    let mut content = styled_block(vec![
+      vec![(None, "This partition has been marked for deletion.")],
+      vec![(
+        None,
+        "It can be restored as a kept or formatted partition, as long as its space has not been used by a new partition.",
+      )],
+    ]);
+    if let Some(ref notice) = self.notice {
+      content.extend(styled_block(vec![
+        vec![(None, String::new())],
+        vec![(Some((Color::Red, Modifier::BOLD)), notice.clone())],
+      ]));
+    }
+    let info_box = InfoBox::new("Deleted Partition", content);
     info_box.render(f, chunks[0]);
     self.buttons.render(f, chunks[1]);
   }
@@ -1852,25 +2895,32 @@ impl Page for AlterPartition {
       }
     }
   }
+  fn has_help(&self) -> bool {
+    false
+  }
+
   fn handle_input(&mut self, installer: &mut Installer, event: KeyEvent) -> Signal {
     match event.code {
-      ui_back!() => Signal::Pop,
-      ui_up!() => {
+      code if ui_back!(code) => Signal::Pop,
+      code if ui_up!(code) => {
         self.buttons.prev_child();
         Signal::Wait
       }
-      ui_down!() => {
+      code if ui_down!(code) => {
         self.buttons.next_child();
         Signal::Wait
       }
-      ui_enter!() => {
-        if self.part_status == PartStatus::Delete {
-          return Signal::Pop;
-        }
-        let Some(idx) = self.buttons.selected_child() else {
+      code if ui_enter!(code) => {
+        let Some(mut idx) = self.buttons.selected_child() else {
           return Signal::Wait;
         };
-        let Some(ref mut device) = installer.drive_config else {
+        if self.part_status == PartStatus::Exists && self.reusable_esp {
+          if idx == 0 {
+            return self.reuse_esp(installer);
+          }
+          idx -= 1;
+        }
+        let Some(device) = installer.active_disk_mut() else {
           return Signal::Error(anyhow::anyhow!(
             "No drive config available for altering partition"
           ));
@@ -1889,17 +2939,21 @@ impl Page for AlterPartition {
                 Signal::Push(Box::new(SetMountPoint::new(self.part_id)))
               }
               1 => {
-                // Mark For Modification
+                // Set Mount Options
+                Signal::Push(Box::new(SetMountOptions::new(part)))
+              }
+              2 => {
+                // Format
                 part.set_status(PartStatus::Modify);
                 Signal::Pop
               }
-              2 => {
+              3 => {
                 // Delete Partition
                 part.set_status(PartStatus::Delete);
                 device.calculate_free_space();
                 Signal::Pop
               }
-              3 => {
+              4 => {
                 // Back
                 Signal::Pop
               }
@@ -1912,83 +2966,38 @@ impl Page for AlterPartition {
                 // Set Mount Point
                 Signal::Push(Box::new(SetMountPoint::new(self.part_id)))
               }
-              1 => {
-                if let Some(child) = self.buttons.focused_child_mut() {
-                  child.interact();
-                  if let Some(value) = child.get_value() {
-                    let Value::Bool(checked) = value else {
-                      return Signal::Wait;
-                    };
-                    if let Some(part) = device.partition_by_id_mut(self.part_id) {
-                      if checked {
-                        part.add_flag("boot");
-                      } else {
-                        part.remove_flag("boot");
-                      }
-                    }
-                  }
-                }
-                Signal::Wait
-              }
-              2 => {
-                if let Some(child) = self.buttons.focused_child_mut() {
-                  child.interact();
-                  if let Some(value) = child.get_value() {
-                    let Value::Bool(checked) = value else {
-                      return Signal::Wait;
-                    };
-                    if let Some(part) = device.partition_by_id_mut(self.part_id) {
-                      if checked {
-                        part.add_flag("esp");
-                      } else {
-                        part.remove_flag("esp");
-                      }
-                    }
-                  }
-                }
-                Signal::Wait
-              }
-              3 => {
-                if let Some(child) = self.buttons.focused_child_mut() {
-                  child.interact();
-                  if let Some(value) = child.get_value() {
-                    let Value::Bool(checked) = value else {
-                      return Signal::Wait;
-                    };
-                    if let Some(part) = device.partition_by_id_mut(self.part_id) {
-                      if checked {
-                        part.add_flag("bls_boot");
-                      } else {
-                        part.remove_flag("bls_boot");
-                      }
-                    }
-                  }
-                }
-                Signal::Wait
-              }
-              4 => {
+              1 => self.toggle_flag(device, "boot"),
+              2 => self.toggle_flag(device, "esp"),
+              3 => self.toggle_flag(device, "bls_boot"),
+              4 => self.toggle_flag(device, "bios_grub"),
+              5 => self.toggle_luks(device),
+              6 => {
                 // Change Filesystem
-                Signal::Push(Box::new(SelectFilesystem::new(Some(self.part_id))))
+                Signal::Push(Box::new(SelectFilesystem::new(
+                  Some(self.part_id),
+                  installer.preferred_fs(),
+                )))
               }
-              5 => {
+              7 => {
                 // Set Label
                 Signal::Push(Box::new(SetLabel::new(self.part_id)))
               }
-              6 => {
-                // Unmark for modification
+              8 => self.set_mount_options(device),
+              9 => {
+                // Keep
                 if let Some(part) = device.partition_by_id_mut(self.part_id) {
                   part.set_status(PartStatus::Exists);
                 }
                 Signal::Pop
               }
-              7 => {
+              10 => {
                 // Delete Partition
                 if let Some(part) = device.partition_by_id_mut(self.part_id) {
                   part.set_status(PartStatus::Delete);
                 }
                 Signal::Pop
               }
-              8 => {
+              11 => {
                 // Back
                 Signal::Pop
               }
@@ -2001,69 +3010,24 @@ impl Page for AlterPartition {
                 // Set Mount Point
                 Signal::Push(Box::new(SetMountPoint::new(self.part_id)))
               }
-              1 => {
-                if let Some(child) = self.buttons.focused_child_mut() {
-                  child.interact();
-                  if let Some(value) = child.get_value() {
-                    let Value::Bool(checked) = value else {
-                      return Signal::Wait;
-                    };
-                    if let Some(part) = device.partition_by_id_mut(self.part_id) {
-                      if checked {
-                        part.add_flag("boot");
-                      } else {
-                        part.remove_flag("boot");
-                      }
-                    }
-                  }
-                }
-                Signal::Wait
-              }
-              2 => {
-                if let Some(child) = self.buttons.focused_child_mut() {
-                  child.interact();
-                  if let Some(value) = child.get_value() {
-                    let Value::Bool(checked) = value else {
-                      return Signal::Wait;
-                    };
-                    if let Some(part) = device.partition_by_id_mut(self.part_id) {
-                      if checked {
-                        part.add_flag("esp");
-                      } else {
-                        part.remove_flag("esp");
-                      }
-                    }
-                  }
-                }
-                Signal::Wait
-              }
-              3 => {
-                if let Some(child) = self.buttons.focused_child_mut() {
-                  child.interact();
-                  if let Some(value) = child.get_value() {
-                    let Value::Bool(checked) = value else {
-                      return Signal::Wait;
-                    };
-                    if let Some(part) = device.partition_by_id_mut(self.part_id) {
-                      if checked {
-                        part.add_flag("bls_boot");
-                      } else {
-                        part.remove_flag("bls_boot");
-                      }
-                    }
-                  }
-                }
-                Signal::Wait
-              }
-              4 => {
+              1 => self.toggle_flag(device, "boot"),
+              2 => self.toggle_flag(device, "esp"),
+              3 => self.toggle_flag(device, "bls_boot"),
+              4 => self.toggle_flag(device, "bios_grub"),
+              5 => self.toggle_luks(device),
+              6 => {
                 // Change Filesystem
-                Signal::Push(Box::new(SelectFilesystem::new(Some(self.part_id))))
+                Signal::Push(Box::new(SelectFilesystem::new(
+                  Some(self.part_id),
+                  installer.preferred_fs(),
+                )))
               }
-              5 => {
+              7 => {
                 // Set Label
                 Signal::Push(Box::new(SetLabel::new(self.part_id)))
               }
-              6 => {
+              8 => self.set_mount_options(device),
+              9 => {
                 // Delete Partition
                 if let Some(part) = device.partition_by_id_mut(self.part_id) {
                   part.set_status(PartStatus::Delete);
@@ -2073,13 +3037,27 @@ impl Page for AlterPartition {
                 };
                 Signal::Pop
               }
-              7 => {
+              10 => {
                 // Back
                 Signal::Pop
               }
               _ => Signal::Wait,
             }
           }
+          PartStatus::Delete => {
+            let status = match idx {
+              0 => PartStatus::Exists,
+              1 => PartStatus::Modify,
+              _ => return Signal::Pop,
+            };
+            match device.restore_partition(self.part_id, status) {
+              Ok(()) => Signal::Pop,
+              Err(e) => {
+                self.notice = Some(e.to_string());
+                Signal::Wait
+              }
+            }
+          }
           _ => Signal::Wait,
         }
       }
@@ -2099,7 +3077,7 @@ impl SetMountPoint {
     editor.focus();
     Self { editor, dev_id }
   }
-  fn validate_mount_point(mount_point: &str, taken: &[String]) -> Result<(), String> {
+  pub fn validate_mount_point(mount_point: &str, taken: &[String]) -> Result<(), String> {
     if mount_point.is_empty() {
       return Err("Mount point cannot be empty.".to_string());
     }
@@ -2155,6 +3133,15 @@ impl Page for SetMountPoint {
     info_box.render(f, chunks[0]);
     self.editor.render(f, hor_chunks[1]);
   }
+  fn has_help(&self) -> bool {
+    false
+  }
+
+  fn handle_paste(&mut self, _installer: &mut Installer, text: &str) -> Signal {
+    self.editor.paste(text);
+    Signal::Wait
+  }
+
   fn handle_input(&mut self, installer: &mut Installer, event: KeyEvent) -> Signal {
     match event.code {
       KeyCode::Esc => Signal::Pop,
@@ -2167,28 +3154,16 @@ impl Page for SetMountPoint {
           .unwrap()
           .trim()
           .to_string();
-        let Some(device) = installer.drive_config.as_mut() else {
-          return Signal::Error(anyhow::anyhow!(
-            "No drive config available for setting mount point"
-          ));
-        };
-        let current_mount = device
-          .partitions()
-          .find(|p| p.id() == self.dev_id)
-          .and_then(|p| p.mount_point());
-
-        let mut taken_mounts: Vec<String> = device
-          .partitions()
-          .filter_map(|d| d.mount_point().map(|mp| mp.to_string()))
-          .collect();
-
-        if let Some(current_mount) = current_mount {
-          taken_mounts.retain(|mp| mp != current_mount);
-        }
+        let taken_mounts = installer.taken_mount_points(Some(self.dev_id));
         if let Err(err) = Self::validate_mount_point(&mount_point, &taken_mounts) {
           self.editor.error(&err);
           return Signal::Wait;
         }
+        let Some(device) = installer.active_disk_mut() else {
+          return Signal::Error(anyhow::anyhow!(
+            "No drive config available for setting mount point"
+          ));
+        };
 
         if let Some(part) = device.partition_by_id_mut(self.dev_id) {
           part.set_mount_point(&mount_point);
@@ -2256,6 +3231,15 @@ impl Page for SetLabel {
     info_box.render(f, chunks[0]);
     self.editor.render(f, hor_chunks[1]);
   }
+  fn has_help(&self) -> bool {
+    false
+  }
+
+  fn handle_paste(&mut self, _installer: &mut Installer, text: &str) -> Signal {
+    self.editor.paste(text);
+    Signal::Wait
+  }
+
   fn handle_input(&mut self, installer: &mut Installer, event: KeyEvent) -> Signal {
     match event.code {
       KeyCode::Esc => Signal::Pop,
@@ -2280,7 +3264,7 @@ impl Page for SetLabel {
           self.editor.error("Label cannot contain spaces.");
           return Signal::Wait;
         }
-        let Some(drive_config) = installer.drive_config.as_mut() else {
+        let Some(drive_config) = installer.active_disk_mut() else {
           return Signal::Error(anyhow::anyhow!(
             "No drive config available for setting partition label"
           ));
@@ -2299,3 +3283,820 @@ impl Page for SetLabel {
     }
   }
 }
+
+pub struct SetMountOptions {
+  editor: LineEditor,
+  dev_id: u64,
+}
+
+impl SetMountOptions {
+  pub fn new(part: &Partition) -> Self {
+    let mut editor = LineEditor::new("Mount Options", Some("e.g. noatime,compress=zstd"));
+    if !part.mount_options().is_empty() {
+      editor.set_value(part.mount_options().join(","));
+    }
+    editor.focus();
+    Self {
+      editor,
+      dev_id: part.id(),
+    }
+  }
+}
+
+impl Page for SetMountOptions {
+  fn render(&mut self, _installer: &mut Installer, f: &mut Frame, area: Rect) {
+    let chunks = split_vert!(
+      area,
+      1,
+      [
+        Constraint::Percentage(40),
+        Constraint::Length(7),
+        Constraint::Percentage(40),
+      ]
+    );
+    let hor_chunks = split_hor!(
+      chunks[1],
+      1,
+      [
+        Constraint::Percentage(15),
+        Constraint::Percentage(70),
+        Constraint::Percentage(15),
+      ]
+    );
+
+    let info_box = InfoBox::new(
+      "Set Mount Options",
+      styled_block(vec![
+        vec![(
+          None,
+          "Extra options used when mounting the selected partition, separated by commas.",
+        )],
+        vec![(None, "Common choices include:")],
+        vec![
+          (None, "- "),
+          (HIGHLIGHT, "noatime"),
+          (None, " skips updating access times on every read"),
+        ],
+        vec![
+          (None, "- "),
+          (HIGHLIGHT, "compress=zstd"),
+          (None, " compresses data on btrfs"),
+        ],
+        vec![
+          (None, "- "),
+          (HIGHLIGHT, "discard"),
+          (None, " trims freed blocks on SSDs"),
+        ],
+        vec![(None, "Leave empty to use the defaults.")],
+      ]),
+    );
+    info_box.render(f, chunks[0]);
+    self.editor.render(f, hor_chunks[1]);
+  }
+  fn has_help(&self) -> bool {
+    false
+  }
+
+  fn handle_paste(&mut self, _installer: &mut Installer, text: &str) -> Signal {
+    self.editor.paste(text);
+    Signal::Wait
+  }
+
+  fn handle_input(&mut self, installer: &mut Installer, event: KeyEvent) -> Signal {
+    match event.code {
+      KeyCode::Esc => Signal::Pop,
+      KeyCode::Enter => {
+        let input = self
+          .editor
+          .get_value()
+          .and_then(|v| v.as_str().map(|s| s.to_string()))
+          .unwrap_or_default();
+        let options = match parse_mount_options(&input) {
+          Ok(options) => options,
+          Err(e) => {
+            self.editor.error(e);
+            return Signal::Wait;
+          }
+        };
+        let Some(drive_config) = installer.active_disk_mut() else {
+          return Signal::Error(anyhow::anyhow!(
+            "No drive config available for setting mount options"
+          ));
+        };
+        let Some(part) = drive_config.partition_by_id_mut(self.dev_id) else {
+          return Signal::Error(anyhow::anyhow!(
+            "No partition found with id {}",
+            self.dev_id
+          ));
+        };
+
+        part.set_mount_options(options);
+        Signal::PopCount(2)
+      }
+      _ => self.editor.handle_input(event),
+    }
+  }
+}
+
+/// Turn on LUKS for a partition, reached from `AlterPartition`
+pub struct LuksSetup {
+  part_id: u64,
+  name: LineEditor,
+  use_key_file: CheckBox,
+  passphrase: LineEditor,
+  confirm: LineEditor,
+  key_file: LineEditor,
+}
+
+impl LuksSetup {
+  pub fn new(part: &Partition) -> Self {
+    let default_name = match part.mount_point().map(|mp| mp.trim_matches('/')) {
+      Some("") => "cryptroot".to_string(),
+      Some(path) => format!("crypt{}", path.replace('/', "")),
+      None => format!("crypt{}", part.id()),
+    };
+    let mut name = LineEditor::new("Mapper Name", Some("e.g. cryptroot"));
+    name.set_value(default_name);
+    name.focus();
+    let use_key_file = CheckBox::new("Unlock with a key file instead", false);
+    let passphrase = LineEditor::new("Passphrase", Some("Passphrase will be hidden")).secret(true);
+    let confirm =
+      LineEditor::new("Confirm Passphrase", Some("Passphrase will be hidden")).secret(true);
+    let key_file = LineEditor::new("Key File", Some("Absolute path on this system"));
+    Self {
+      part_id: part.id(),
+      name,
+      use_key_file,
+      passphrase,
+      confirm,
+      key_file,
+    }
+  }
+  fn value(editor: &LineEditor) -> String {
+    editor
+      .get_value()
+      .and_then(|v| v.as_str().map(|s| s.trim().to_string()))
+      .unwrap_or_default()
+  }
+  /// The fields in Tab order, skipping the ones for the other key source
+  fn focus_next(&mut self) {
+    let key_file = self.use_key_file.is_checked();
+    if self.name.is_focused() {
+      self.name.unfocus();
+      self.use_key_file.focus();
+    } else if self.use_key_file.is_focused() {
+      self.use_key_file.unfocus();
+      if key_file {
+        self.key_file.focus();
+      } else {
+        self.passphrase.focus();
+      }
+    } else if self.passphrase.is_focused() {
+      self.passphrase.unfocus();
+      self.confirm.focus();
+    } else {
+      self.confirm.unfocus();
+      self.key_file.unfocus();
+      self.name.focus();
+    }
+  }
+  fn save(&mut self, installer: &mut Installer) -> Signal {
+    let name = Self::value(&self.name);
+    if name.is_empty() {
+      self.name.error("Mapper name cannot be empty");
+      return Signal::Wait;
+    }
+    if !name
+      .chars()
+      .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+      self
+        .name
+        .error("Only letters, digits, '-' and '_' are allowed");
+      return Signal::Wait;
+    }
+    let taken = installer
+      .all_disks()
+      .flat_map(|d| d.partitions())
+      .filter(|p| p.id() != self.part_id)
+      .filter_map(|p| p.luks())
+      .any(|luks| luks.name == name);
+    if taken {
+      self
+        .name
+        .error(format!("'{name}' is already used by another partition"));
+      return Signal::Wait;
+    }
+    let key = if self.use_key_file.is_checked() {
+      let path = Self::value(&self.key_file);
+      if !path.starts_with('/') {
+        self.key_file.error("Key file path must be absolute");
+        return Signal::Wait;
+      }
+      if !std::path::Path::new(&path).is_file() {
+        self.key_file.error(format!("{path} does not exist"));
+        return Signal::Wait;
+      }
+      LuksKey::KeyFile { path }
+    } else {
+      let passphrase = Self::value(&self.passphrase);
+      let confirm = Self::value(&self.confirm);
+      if passphrase.is_empty() {
+        self.passphrase.error("Passphrase cannot be empty");
+        return Signal::Wait;
+      }
+      if passphrase != confirm {
+        self.passphrase.clear();
+        self.confirm.clear();
+        self.confirm.unfocus();
+        self.passphrase.focus();
+        self.passphrase.error("Passphrases do not match");
+        return Signal::Wait;
+      }
+      LuksKey::Passphrase { passphrase }
+    };
+    let Some(device) = installer.active_disk_mut() else {
+      return Signal::Error(anyhow::anyhow!(
+        "No drive config available for setting up encryption"
+      ));
+    };
+    let Some(part) = device.partition_by_id_mut(self.part_id) else {
+      return Signal::Error(anyhow::anyhow!(
+        "No partition found with id {}",
+        self.part_id
+      ));
+    };
+    part.set_luks(Some(LuksConfig {
+      name,
+      cipher: LuksConfig::DEFAULT_CIPHER.to_string(),
+      key,
+    }));
+    Signal::PopCount(2)
+  }
+}
+
+impl Page for LuksSetup {
+  fn render(&mut self, _installer: &mut Installer, f: &mut Frame, area: Rect) {
+    let chunks = split_vert!(
+      area,
+      1,
+      [
+        Constraint::Percentage(30),
+        Constraint::Length(17),
+        Constraint::Min(0),
+      ]
+    );
+    let hor_chunks = split_hor!(
+      chunks[1],
+      1,
+      [
+        Constraint::Percentage(20),
+        Constraint::Percentage(60),
+        Constraint::Percentage(20),
+      ]
+    );
+    let field_chunks = split_vert!(
+      hor_chunks[1],
+      0,
+      [
+        Constraint::Length(5),
+        Constraint::Length(2),
+        Constraint::Length(5),
+        Constraint::Length(5),
+      ]
+    );
+
+    let info_box = InfoBox::new(
+      "Encrypt with LUKS",
+      styled_block(vec![
+        vec![(
+          None,
+          "The partition is formatted as a LUKS volume and its filesystem goes inside it.",
+        )],
+        vec![(
+          None,
+          "It is unlocked at boot, so the passphrase will be asked for every time the system starts.",
+        )],
+        vec![(
+          None,
+          "A key file has to be reachable from the initrd at boot, e.g. on a USB stick.",
+        )],
+        vec![(None, "")],
+        vec![(
+          HIGHLIGHT,
+          "The passphrase is not saved with your settings. Losing it means losing the data.",
+        )],
+      ]),
+    );
+    info_box.render(f, chunks[0]);
+    self.name.render(f, field_chunks[0]);
+    self.use_key_file.render(f, field_chunks[1]);
+    if self.use_key_file.is_checked() {
+      self.key_file.render(f, field_chunks[2]);
+    } else {
+      self.passphrase.render(f, field_chunks[2]);
+      self.confirm.render(f, field_chunks[3]);
+    }
+  }
+  fn has_help(&self) -> bool {
+    false
+  }
+
+  fn handle_paste(&mut self, _installer: &mut Installer, text: &str) -> Signal {
+    if self.name.is_focused() {
+      self.name.paste(text);
+    } else if self.passphrase.is_focused() {
+      self.passphrase.paste(text);
+    } else if self.confirm.is_focused() {
+      self.confirm.paste(text);
+    } else if self.key_file.is_focused() {
+      self.key_file.paste(text);
+    }
+    Signal::Wait
+  }
+
+  fn handle_input(&mut self, installer: &mut Installer, event: KeyEvent) -> Signal {
+    match event.code {
+      KeyCode::Esc => Signal::Pop,
+      KeyCode::Tab => {
+        self.focus_next();
+        Signal::Wait
+      }
+      KeyCode::Enter | KeyCode::Char(' ') if self.use_key_file.is_focused() => {
+        self.use_key_file.toggle();
+        Signal::Wait
+      }
+      KeyCode::Enter => {
+        if self.confirm.is_focused() || self.key_file.is_focused() {
+          self.save(installer)
+        } else {
+          self.focus_next();
+          Signal::Wait
+        }
+      }
+      _ => {
+        if self.name.is_focused() {
+          self.name.handle_input(event)
+        } else if self.passphrase.is_focused() {
+          self.passphrase.handle_input(event)
+        } else if self.confirm.is_focused() {
+          self.confirm.handle_input(event)
+        } else if self.key_file.is_focused() {
+          self.key_file.handle_input(event)
+        } else {
+          Signal::Wait
+        }
+      }
+    }
+  }
+}
+
+enum LayoutFileMode {
+  Export,
+  /// Opened from the Drives page, unwind to the menu once loaded
+  Import,
+  /// Opened from manual partitioning, return there to review the layout
+  ImportOverManual,
+}
+
+/// Save or load a drive layout so it can be reused across reinstalls
+pub struct LayoutFile {
+  editor: LineEditor,
+  mode: LayoutFileMode,
+}
+
+impl LayoutFile {
+  const DEFAULT_PATH: &'static str = "disk-layout.json";
+
+  fn new(mode: LayoutFileMode) -> Self {
+    let mut editor = LineEditor::new("Layout File", Some("Path to a layout file..."));
+    editor.set_value(Self::DEFAULT_PATH);
+    editor.focus();
+    Self { editor, mode }
+  }
+  pub fn export() -> Self {
+    Self::new(LayoutFileMode::Export)
+  }
+  pub fn import() -> Self {
+    Self::new(LayoutFileMode::Import)
+  }
+  pub fn import_over_manual() -> Self {
+    Self::new(LayoutFileMode::ImportOverManual)
+  }
+  fn write_layout(installer: &Installer, path: &str) -> anyhow::Result<()> {
+    let Some(disk) = installer.active_disk() else {
+      return Err(anyhow::anyhow!("There is no drive layout to export"));
+    };
+    let json = serde_json::to_string_pretty(disk)?;
+    std::fs::write(path, json)?;
+    Ok(())
+  }
+  fn read_layout(path: &str) -> anyhow::Result<Disk> {
+    let contents = std::fs::read_to_string(path)?;
+    let mut disk: Disk = serde_json::from_str(&contents)?;
+    disk.refresh_ids();
+    Ok(disk)
+  }
+  /// How to leave the import flow once a layout is in place
+  fn done_signal(&self, extra_pages: usize) -> Signal {
+    match self.mode {
+      LayoutFileMode::ImportOverManual => Signal::PopCount(1 + extra_pages),
+      _ => Signal::Unwind,
+    }
+  }
+}
+
+impl Page for LayoutFile {
+  fn render(&mut self, _installer: &mut Installer, f: &mut Frame, area: Rect) {
+    let chunks = split_vert!(
+      area,
+      1,
+      [
+        Constraint::Percentage(40),
+        Constraint::Length(7),
+        Constraint::Percentage(40),
+      ]
+    );
+    let hor_chunks = split_hor!(
+      chunks[1],
+      1,
+      [
+        Constraint::Percentage(15),
+        Constraint::Percentage(70),
+        Constraint::Percentage(15),
+      ]
+    );
+    let info_box = match self.mode {
+      LayoutFileMode::Export => InfoBox::new(
+        "Export Partition Layout",
+        styled_block(vec![
+          vec![(None, "Save the current partition layout to a file.")],
+          vec![(
+            None,
+            "Keep it somewhere that survives a reinstall, such as a USB stick, to skip manual partitioning next time.",
+          )],
+        ]),
+      ),
+      LayoutFileMode::Import | LayoutFileMode::ImportOverManual => InfoBox::new(
+        "Import Partition Layout",
+        styled_block(vec![
+          vec![(None, "Load a partition layout exported by this installer.")],
+          vec![(
+            None,
+            "If the disk it was made for is missing or has changed, you will be asked to pick another disk.",
+          )],
+          vec![(None, "")],
+          vec![(
+            HIGHLIGHT,
+            "NOTE: This replaces any partition changes you have made so far.",
+          )],
+        ]),
+      ),
+    };
+    info_box.render(f, chunks[0]);
+    self.editor.render(f, hor_chunks[1]);
+  }
+  fn has_help(&self) -> bool {
+    false
+  }
+
+  fn handle_paste(&mut self, _installer: &mut Installer, text: &str) -> Signal {
+    self.editor.paste(text);
+    Signal::Wait
+  }
+
+  fn handle_input(&mut self, installer: &mut Installer, event: KeyEvent) -> Signal {
+    match event.code {
+      KeyCode::Esc => Signal::Pop,
+      KeyCode::Enter => {
+        let path = self
+          .editor
+          .get_value()
+          .and_then(|v| v.as_str().map(|s| s.trim().to_string()))
+          .unwrap_or_default();
+        if path.is_empty() {
+          self.editor.error("Path cannot be empty.");
+          return Signal::Wait;
+        }
+        if let LayoutFileMode::Export = self.mode {
+          return match Self::write_layout(installer, &path) {
+            Ok(()) => Signal::Pop,
+            Err(e) => {
+              self.editor.error(format!("Failed to write layout: {e}"));
+              Signal::Wait
+            }
+          };
+        }
+        let saved = match Self::read_layout(&path) {
+          Ok(disk) => disk,
+          Err(e) => {
+            self.editor.error(format!("Failed to read layout: {e}"));
+            return Signal::Wait;
+          }
+        };
+        if installer.drives.is_empty() {
+          match lsblk() {
+            Ok(disks) => installer.drives = disks,
+            Err(e) => {
+              self
+                .editor
+                .error(format!("Failed to list block devices: {e}"));
+              return Signal::Wait;
+            }
+          }
+        }
+        let mismatches = reconcile_disk(&saved, &installer.drives);
+        if mismatches.is_empty() {
+          installer.set_active_disk(saved);
+          installer.storage_teardown.clear();
+          installer.make_drive_config_display();
+          self.done_signal(0)
+        } else {
+          let done = self.done_signal(1);
+          Signal::Push(Box::new(RetargetLayout::new(
+            saved,
+            mismatches,
+            &installer.drives,
+            done,
+          )))
+        }
+      }
+      _ => self.editor.handle_input(event),
+    }
+  }
+}
+
+/// Offered when an imported layout doesn't match its original disk anymore
+pub struct RetargetLayout {
+  saved: Disk,
+  mismatches: Vec<Mismatch>,
+  disks: StrList,
+  done: Option<Signal>,
+  /// Why the layout couldn't go on the picked disk
+  notice: Option<String>,
+}
+
+impl RetargetLayout {
+  pub fn new(saved: Disk, mismatches: Vec<Mismatch>, disks: &[Disk], done: Signal) -> Self {
+    let mut disks = StrList::new(
+      "Apply Layout To",
+      disks
+        .iter()
+        .map(|d| format!("{} ({})", d.name(), bytes_readable(d.size_bytes())))
+        .collect(),
+    );
+    disks.focus();
+    Self {
+      saved,
+      mismatches,
+      disks,
+      done: Some(done),
+      notice: None,
+    }
+  }
+}
+
+impl Page for RetargetLayout {
+  fn render(&mut self, _installer: &mut Installer, f: &mut Frame, area: Rect) {
+    let chunks = split_vert!(
+      area,
+      1,
+      [Constraint::Percentage(50), Constraint::Percentage(50)]
+    );
+    let hor_chunks = split_hor!(
+      chunks[1],
+      1,
+      [
+        Constraint::Percentage(25),
+        Constraint::Percentage(50),
+        Constraint::Percentage(25),
+      ]
+    );
+    let mut lines = vec![
+      vec![(
+        None,
+        format!(
+          "The layout was made for /dev/{}, which no longer matches:",
+          self.saved.name()
+        ),
+      )],
+      vec![(None, String::new())],
+    ];
+    for mismatch in &self.mismatches {
+      lines.push(vec![
+        (None, " - ".to_string()),
+        (HIGHLIGHT, mismatch.to_string()),
+      ]);
+    }
+    lines.push(vec![(None, String::new())]);
+    lines.push(vec![(
+      None,
+      "Pick a disk to recreate the layout on. Everything on that disk will be deleted.".to_string(),
+    )]);
+    if let Some(ref notice) = self.notice {
+      lines.push(vec![(None, String::new())]);
+      lines.push(vec![(Some((Color::Red, Modifier::BOLD)), notice.clone())]);
+    }
+    let info_box = InfoBox::new("Saved Layout Mismatch", styled_block(lines));
+    info_box.render(f, chunks[0]);
+    self.disks.render(f, hor_chunks[1]);
+  }
+  fn has_help(&self) -> bool {
+    false
+  }
+
+  fn handle_input(&mut self, installer: &mut Installer, event: KeyEvent) -> Signal {
+    match event.code {
+      code if ui_back!(code) => Signal::Pop,
+      code if ui_up!(code) => {
+        if !self.disks.previous_item() {
+          self.disks.last_item();
+        }
+        Signal::Wait
+      }
+      code if ui_down!(code) => {
+        if !self.disks.next_item() {
+          self.disks.first_item();
+        }
+        Signal::Wait
+      }
+      KeyCode::Enter => {
+        let Some(mut target) = installer.drives.get(self.disks.selected_idx).cloned() else {
+          return Signal::Wait;
+        };
+        if let Err(e) = target.apply_layout(&self.saved) {
+          self.notice = Some(format!("{e}. Pick another disk."));
+          return Signal::Wait;
+        }
+        installer.set_active_disk(target);
+        installer.storage_teardown.clear();
+        installer.make_drive_config_display();
+        self.done.take().unwrap_or(Signal::Unwind)
+      }
+      _ => Signal::Wait,
+    }
+  }
+}
+
+/// Last stop before the install wipes the target disk
+///
+/// Shows the device with its size and model and only continues to `next`
+/// once the device path has been typed in exactly.
+pub struct WipeConfirm {
+  device: String,
+  details: Vec<(String, String)>,
+  editor: LineEditor,
+  next: Option<Box<dyn Page>>,
+  help_modal: HelpModal<'static>,
+}
+
+impl WipeConfirm {
+  pub fn new(disk: &Disk, next: Box<dyn Page>) -> Self {
+    let device = format!("/dev/{}", disk.name());
+    let details = vec![
+      ("Device".to_string(), device.clone()),
+      (
+        "Model".to_string(),
+        disk.model().unwrap_or("unknown").to_string(),
+      ),
+      ("Size".to_string(), bytes_readable(disk.size_bytes())),
+    ];
+    let mut editor = LineEditor::new("Type the device path to confirm", Some(device.as_str()));
+    editor.focus();
+    let help_content = styled_block(vec![
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
+        (None, " - Start the installation once the path matches"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Esc"),
+        (None, " - Go back without touching the disk"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "?"),
+        (None, " - Show this help"),
+      ],
+      vec![(None, "")],
+      vec![(
+        None,
+        "Partitions kept in the layout are left alone, everything else on the device is erased.",
+      )],
+    ]);
+    let help_modal = HelpModal::new("Confirm Disk Wipe", help_content);
+    Self {
+      device,
+      details,
+      editor,
+      next: Some(next),
+      help_modal,
+    }
+  }
+}
+
+impl Page for WipeConfirm {
+  fn render(&mut self, _installer: &mut Installer, f: &mut Frame, area: Rect) {
+    let chunks = split_vert!(
+      area,
+      1,
+      [
+        Constraint::Percentage(40),
+        Constraint::Length(5),
+        Constraint::Percentage(40),
+      ]
+    );
+    let hor_chunks = split_hor!(
+      chunks[1],
+      0,
+      [
+        Constraint::Percentage(20),
+        Constraint::Percentage(60),
+        Constraint::Percentage(20),
+      ]
+    );
+    let mut lines = vec![
+      vec![(
+        Some((Color::Red, Modifier::BOLD)),
+        "ALL DATA ON THIS DISK WILL BE ERASED".to_string(),
+      )],
+      vec![(None, String::new())],
+    ];
+    for (label, value) in &self.details {
+      lines.push(vec![
+        (None, format!("{label}: ")),
+        (HIGHLIGHT, value.clone()),
+      ]);
+    }
+    lines.push(vec![(None, String::new())]);
+    lines.push(vec![(
+      None,
+      format!("Type {} below to start the installation.", self.device),
+    )]);
+    InfoBox::new("Confirm Disk Wipe", styled_block(lines)).render(f, chunks[0]);
+    self.editor.render(f, hor_chunks[1]);
+    self.help_modal.render(f, area);
+  }
+
+  fn get_help_content(&self) -> (String, Vec<ratatui::text::Line<'_>>) {
+    let help_content = styled_block(vec![
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
+        (None, " - Start the installation once the path matches"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Esc"),
+        (None, " - Go back without touching the disk"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "?"),
+        (None, " - Show this help"),
+      ],
+      vec![(None, "")],
+      vec![(
+        None,
+        "Partitions kept in the layout are left alone, everything else on the device is erased.",
+      )],
+    ]);
+    ("Confirm Disk Wipe".to_string(), help_content)
+  }
+
+  fn handle_paste(&mut self, _installer: &mut Installer, text: &str) -> Signal {
+    if !self.help_modal.visible {
+      self.editor.paste(text);
+    }
+    Signal::Wait
+  }
+
+  fn handle_input(&mut self, _installer: &mut Installer, event: KeyEvent) -> Signal {
+    match event.code {
+      KeyCode::Char('?') => {
+        self.help_modal.toggle();
+        Signal::Wait
+      }
+      code if ui_close!(code) && self.help_modal.visible => {
+        self.help_modal.hide();
+        Signal::Wait
+      }
+      _ if self.help_modal.visible => Signal::Wait,
+      KeyCode::Esc => Signal::Pop,
+      KeyCode::Enter => {
+        let typed = self
+          .editor
+          .get_value()
+          .and_then(|v| v.as_str().map(|s| s.trim().to_string()))
+          .unwrap_or_default();
+        if typed != self.device {
+          self.editor.clear();
+          self
+            .editor
+            .error(format!("Doesn't match, type {} exactly", self.device));
+          return Signal::Wait;
+        }
+        match self.next.take() {
+          Some(next) => Signal::Replace(next),
+          None => Signal::Pop,
+        }
+      }
+      _ => self.editor.handle_input(event),
+    }
+  }
+}
@@ -0,0 +1,325 @@
+use std::io::Write;
+
+use ratatui::{
+  Frame,
+  crossterm::event::{KeyCode, KeyEvent},
+  layout::Rect,
+  style::{Color, Modifier},
+  text::Line,
+};
+use tempfile::NamedTempFile;
+
+use crate::{
+  command,
+  installer::{HIGHLIGHT, Installer, Page, Signal},
+  keys, split_hor, split_vert, styled_block, ui_close, ui_down, ui_up,
+  widget::{ConfigWidget, HelpModal, InfoBox, LineEditor, StrList},
+};
+
+/// Normalize a user supplied path to one relative to /etc
+///
+/// Both `wireguard/wg0.conf` and `/etc/wireguard/wg0.conf` are accepted.
+pub fn etc_relative_path(input: &str) -> Result<String, String> {
+  let input = input.trim();
+  let path = match input.strip_prefix("/etc/") {
+    Some(rest) => rest,
+    None if input.starts_with('/') => return Err("Path must be under /etc".to_string()),
+    None => input,
+  };
+  if path.is_empty() {
+    return Err("Path cannot be empty".to_string());
+  }
+  if path
+    .split('/')
+    .any(|part| part.is_empty() || part == "." || part == "..")
+  {
+    return Err(format!("'{path}' is not a valid path under /etc"));
+  }
+  Ok(path.to_string())
+}
+
+enum EtcFilesMode {
+  List,
+  Path,
+}
+
+/// Extra files placed in /etc through `environment.etc`
+pub struct EtcFiles {
+  files: StrList,
+  path_input: LineEditor,
+  mode: EtcFilesMode,
+  /// File being edited in $EDITOR, read back on the next render
+  editing: Option<(String, NamedTempFile)>,
+  help_modal: HelpModal<'static>,
+}
+
+impl EtcFiles {
+  pub fn new(installer: &Installer) -> Self {
+    let mut files = StrList::new("Files", installer.etc_files.keys().cloned().collect());
+    files.focus();
+    let path_input = LineEditor::new("Path under /etc", Some("e.g. wireguard/wg0.conf"));
+    let help_content = styled_block(vec![
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Navigate files"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "a"),
+        (None, " - Add a new file"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
+        (None, " - Edit the selected file in $EDITOR"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "d"),
+        (None, " - Delete the selected file"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Esc, q"),
+        (None, " - Return to menu"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "?"),
+        (None, " - Show this help"),
+      ],
+      vec![(None, "")],
+      vec![(
+        None,
+        "Files are written to the installed system through environment.etc.",
+      )],
+    ]);
+    let help_modal = HelpModal::new("Extra /etc Files", help_content);
+    Self {
+      files,
+      path_input,
+      mode: EtcFilesMode::List,
+      editing: None,
+      help_modal,
+    }
+  }
+  pub fn display_widget(installer: &mut Installer) -> Option<Box<dyn ConfigWidget>> {
+    if installer.etc_files.is_empty() {
+      return None;
+    }
+    let mut lines = vec![vec![(None, "Extra files:".to_string())]];
+    for path in installer.etc_files.keys() {
+      lines.push(vec![(HIGHLIGHT, format!("/etc/{path}"))]);
+    }
+    Some(Box::new(InfoBox::new("", styled_block(lines))) as Box<dyn ConfigWidget>)
+  }
+  pub fn page_info<'a>() -> (String, Vec<Line<'a>>) {
+    (
+      "Extra /etc Files".to_string(),
+      styled_block(vec![
+        vec![(
+          None,
+          "Add files to /etc on the installed system, like a WireGuard config or extra hosts entries.",
+        )],
+        vec![(
+          None,
+          "Their content is stored in configuration.nix, so avoid putting secrets here.",
+        )],
+      ]),
+    )
+  }
+
+  /// Open `path` in the user's editor, starting from `content`
+  fn edit(&mut self, path: String, content: &str) -> Signal {
+    let file = NamedTempFile::new().and_then(|mut file| {
+      file.write_all(content.as_bytes())?;
+      Ok(file)
+    });
+    let file = match file {
+      Ok(file) => file,
+      Err(e) => return Signal::Error(anyhow::anyhow!("Failed to create temp file: {e}")),
+    };
+    // Through sh so an $EDITOR with arguments, like "code --wait", still works
+    let cmd = command!(
+      "sh",
+      "-c",
+      "${EDITOR:-nano} \"$1\"",
+      "sh",
+      file.path().display()
+    );
+    self.editing = Some((path, file));
+    Signal::Suspend(cmd)
+  }
+
+  fn finish_edit(&mut self, installer: &mut Installer) {
+    let Some((path, file)) = self.editing.take() else {
+      return;
+    };
+    let content = std::fs::read_to_string(file.path()).unwrap_or_default();
+    if content.trim().is_empty() {
+      installer.etc_files.remove(&path);
+    } else {
+      installer.etc_files.insert(path, content);
+    }
+    let selected = self.files.selected_idx;
+    self
+      .files
+      .set_items(installer.etc_files.keys().cloned().collect());
+    self.files.selected_idx = selected.min(self.files.len().saturating_sub(1));
+  }
+
+  fn preview(&self, installer: &Installer) -> InfoBox<'static> {
+    let Some(path) = self.files.selected_item() else {
+      return InfoBox::new(
+        "",
+        styled_block(vec![vec![(None, "No files yet, press 'a' to add one.")]]),
+      );
+    };
+    let content = installer.etc_files.get(path).cloned().unwrap_or_default();
+    let lines = content
+      .lines()
+      .map(|line| Line::from(line.to_string()))
+      .collect();
+    InfoBox::new(format!("/etc/{path}"), lines)
+  }
+}
+
+impl Page for EtcFiles {
+  fn render(&mut self, installer: &mut Installer, f: &mut Frame, area: Rect) {
+    self.finish_edit(installer);
+
+    let chunks = split_hor!(
+      area,
+      1,
+      [Constraint::Percentage(35), Constraint::Percentage(65)]
+    );
+    let left = split_vert!(chunks[0], 0, [Constraint::Min(0), Constraint::Length(3)]);
+    self.files.render(f, left[0]);
+    if let EtcFilesMode::Path = self.mode {
+      self.path_input.render(f, left[1]);
+    }
+    self.preview(installer).render(f, chunks[1]);
+    self.help_modal.render(f, area);
+  }
+
+  fn get_help_content(&self) -> (String, Vec<Line<'_>>) {
+    let help_content = styled_block(vec![
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Navigate files"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "a"),
+        (None, " - Add a new file"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
+        (None, " - Edit the selected file in $EDITOR"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "d"),
+        (None, " - Delete the selected file"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Esc, q"),
+        (None, " - Return to menu"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "?"),
+        (None, " - Show this help"),
+      ],
+      vec![(None, "")],
+      vec![(
+        None,
+        "Files are written to the installed system through environment.etc.",
+      )],
+    ]);
+    ("Extra /etc Files".to_string(), help_content)
+  }
+
+  fn handle_paste(&mut self, _installer: &mut Installer, text: &str) -> Signal {
+    if let EtcFilesMode::Path = self.mode {
+      self.path_input.paste(text);
+    }
+    Signal::Wait
+  }
+
+  fn handle_input(&mut self, installer: &mut Installer, event: KeyEvent) -> Signal {
+    if let EtcFilesMode::Path = self.mode {
+      return match event.code {
+        KeyCode::Esc => {
+          self.path_input.clear();
+          self.path_input.unfocus();
+          self.files.focus();
+          self.mode = EtcFilesMode::List;
+          Signal::Wait
+        }
+        KeyCode::Enter => {
+          let input = self
+            .path_input
+            .get_value()
+            .and_then(|v| v.as_str().map(|s| s.to_string()))
+            .unwrap_or_default();
+          match etc_relative_path(&input) {
+            Ok(path) => {
+              self.path_input.clear();
+              self.path_input.unfocus();
+              self.files.focus();
+              self.mode = EtcFilesMode::List;
+              let content = installer.etc_files.get(&path).cloned().unwrap_or_default();
+              self.edit(path, &content)
+            }
+            Err(e) => {
+              self.path_input.error(e);
+              Signal::Wait
+            }
+          }
+        }
+        _ => self.path_input.handle_input(event),
+      };
+    }
+
+    match event.code {
+      KeyCode::Char('?') => {
+        self.help_modal.toggle();
+        Signal::Wait
+      }
+      code if ui_close!(code) && self.help_modal.visible => {
+        self.help_modal.hide();
+        Signal::Wait
+      }
+      _ if self.help_modal.visible => Signal::Wait,
+      code if ui_close!(code) => Signal::Pop,
+      KeyCode::Char('a') => {
+        self.files.unfocus();
+        self.path_input.focus();
+        self.mode = EtcFilesMode::Path;
+        Signal::Wait
+      }
+      KeyCode::Char('d') => {
+        if let Some(path) = self.files.selected_item().cloned() {
+          installer.etc_files.remove(&path);
+          self.files.remove_selected();
+        }
+        Signal::Wait
+      }
+      KeyCode::Enter => {
+        let Some(path) = self.files.selected_item().cloned() else {
+          return Signal::Wait;
+        };
+        let content = installer.etc_files.get(&path).cloned().unwrap_or_default();
+        self.edit(path, &content)
+      }
+      code if ui_up!(code) => {
+        self.files.previous_item();
+        Signal::Wait
+      }
+      code if ui_down!(code) => {
+        self.files.next_item();
+        Signal::Wait
+      }
+      _ => Signal::Wait,
+    }
+  }
+}
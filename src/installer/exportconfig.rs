@@ -0,0 +1,243 @@
+use std::path::Path;
+
+use ratatui::{
+  Frame,
+  crossterm::event::{KeyCode, KeyEvent},
+  layout::Rect,
+  style::{Color, Modifier},
+  text::Line,
+};
+
+use crate::{
+  installer::{Installer, Page, Signal},
+  split_hor, split_vert, styled_block, ui_close,
+  widget::{ConfigWidget, ConfirmModal, HelpModal, InfoBox, LineEditor, MessageModal},
+};
+
+/// Write each `(name, contents)` pair under `dir`, creating it and any
+/// subdirectories as needed
+pub fn export_files(dir: &Path, files: &[(&str, String)]) -> anyhow::Result<()> {
+  for (name, contents) in files {
+    let path = dir.join(name);
+    if let Some(parent) = path.parent() {
+      std::fs::create_dir_all(parent)
+        .map_err(|e| anyhow::anyhow!("Failed to create '{}': {e}", parent.display()))?;
+    }
+    std::fs::write(&path, contents)
+      .map_err(|e| anyhow::anyhow!("Failed to write '{}': {e}", path.display()))?;
+  }
+  Ok(())
+}
+
+/// Asks for a directory and writes the generated config files there, for
+/// installing by hand later
+pub struct ExportConfig {
+  input: LineEditor,
+  /// File names relative to the export directory, and their contents
+  files: Vec<(&'static str, String)>,
+  overwrite_modal: ConfirmModal,
+  quit_modal: ConfirmModal,
+  message: MessageModal,
+  help_modal: HelpModal<'static>,
+}
+
+impl ExportConfig {
+  pub fn new(files: Vec<(&'static str, String)>) -> Self {
+    let mut input = LineEditor::new("Export To", Some("e.g. /mnt/usb/nixos-config"));
+    input.focus();
+    let help_content = styled_block(vec![
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
+        (None, " - Write the files"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Esc"),
+        (None, " - Cancel and return to the preview"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "?"),
+        (None, " - Show this help"),
+      ],
+      vec![(None, "")],
+      vec![(
+        None,
+        "Nothing is partitioned or installed. Run disko and nixos-install yourself once the config looks right.",
+      )],
+    ]);
+    let help_modal = HelpModal::new("Export Config", help_content);
+    Self {
+      input,
+      files,
+      overwrite_modal: ConfirmModal::new("Overwrite?", ""),
+      quit_modal: ConfirmModal::new("Exported", ""),
+      message: MessageModal::new(),
+      help_modal,
+    }
+  }
+  fn file_names(&self) -> String {
+    self
+      .files
+      .iter()
+      .map(|(name, _)| *name)
+      .collect::<Vec<_>>()
+      .join(", ")
+  }
+  fn target_dir(&self) -> String {
+    self
+      .input
+      .get_value()
+      .and_then(|v| v.as_str().map(|s| s.trim().to_string()))
+      .unwrap_or_default()
+  }
+  fn export(&mut self) {
+    let dir = self.target_dir();
+    match export_files(Path::new(&dir), &self.files) {
+      Ok(()) => {
+        log::info!("Exported {} to {dir}", self.file_names());
+        self.quit_modal.message = format!(
+          "Wrote {} to {dir}. Quit the installer now?",
+          self.file_names()
+        );
+        self.quit_modal.show();
+      }
+      Err(e) => {
+        log::error!("Config export failed: {e}");
+        self
+          .message
+          .show("Export Failed", e.to_string(), Color::Red);
+      }
+    }
+  }
+  fn info<'a>(&self) -> Vec<Line<'a>> {
+    styled_block(vec![
+      vec![(
+        None,
+        "Write the generated config to a directory instead of installing.".to_string(),
+      )],
+      vec![(None, format!("Files: {}", self.file_names()))],
+      vec![(
+        None,
+        "Review or commit them, then partition with disko and run nixos-install yourself."
+          .to_string(),
+      )],
+    ])
+  }
+}
+
+impl Page for ExportConfig {
+  fn render(&mut self, _installer: &mut Installer, f: &mut Frame, area: Rect) {
+    let chunks = split_vert!(
+      area,
+      1,
+      [
+        Constraint::Percentage(40),
+        Constraint::Length(5),
+        Constraint::Percentage(40),
+      ]
+    );
+    let hor_chunks = split_hor!(
+      chunks[1],
+      0,
+      [
+        Constraint::Percentage(10),
+        Constraint::Percentage(80),
+        Constraint::Percentage(10),
+      ]
+    );
+    InfoBox::new("", self.info()).render(f, chunks[0]);
+    self.input.render(f, hor_chunks[1]);
+    self.overwrite_modal.render(f, area);
+    self.quit_modal.render(f, area);
+    self.message.render(f, area);
+    self.help_modal.render(f, area);
+  }
+
+  fn get_help_content(&self) -> (String, Vec<Line<'_>>) {
+    let help_content = styled_block(vec![
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
+        (None, " - Write the files"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Esc"),
+        (None, " - Cancel and return to the preview"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "?"),
+        (None, " - Show this help"),
+      ],
+      vec![(None, "")],
+      vec![(
+        None,
+        "Nothing is partitioned or installed. Run disko and nixos-install yourself once the config looks right.",
+      )],
+    ]);
+    ("Export Config".to_string(), help_content)
+  }
+
+  fn handle_paste(&mut self, _installer: &mut Installer, text: &str) -> Signal {
+    let modal_open = self.help_modal.visible
+      || self.message.visible
+      || self.quit_modal.visible
+      || self.overwrite_modal.visible;
+    if !modal_open {
+      self.input.paste(text);
+    }
+    Signal::Wait
+  }
+
+  fn handle_input(&mut self, _installer: &mut Installer, event: KeyEvent) -> Signal {
+    if self.message.visible {
+      self.message.handle_input(event);
+      return Signal::Wait;
+    }
+    if self.quit_modal.visible {
+      return match self.quit_modal.handle_input(event) {
+        Some(true) => Signal::Quit,
+        Some(false) => Signal::Pop,
+        None => Signal::Wait,
+      };
+    }
+    if self.overwrite_modal.visible {
+      if let Some(true) = self.overwrite_modal.handle_input(event) {
+        self.export();
+      }
+      return Signal::Wait;
+    }
+
+    match event.code {
+      KeyCode::Char('?') => {
+        self.help_modal.toggle();
+        Signal::Wait
+      }
+      code if ui_close!(code) && self.help_modal.visible => {
+        self.help_modal.hide();
+        Signal::Wait
+      }
+      _ if self.help_modal.visible => Signal::Wait,
+      KeyCode::Esc => Signal::Pop,
+      KeyCode::Enter => {
+        let dir = self.target_dir();
+        if dir.is_empty() {
+          self.input.error("Enter a directory to export to");
+          return Signal::Wait;
+        }
+        let existing = self
+          .files
+          .iter()
+          .filter(|(name, _)| Path::new(&dir).join(name).exists())
+          .map(|(name, _)| *name)
+          .collect::<Vec<_>>();
+        if existing.is_empty() {
+          self.export();
+        } else {
+          self.overwrite_modal.message =
+            format!("{dir} already has {}. Overwrite?", existing.join(", "));
+          self.overwrite_modal.show();
+        }
+        Signal::Wait
+      }
+      _ => self.input.handle_input(event),
+    }
+  }
+}
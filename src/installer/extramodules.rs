@@ -0,0 +1,332 @@
+use ratatui::{
+  Frame,
+  crossterm::event::{KeyCode, KeyEvent},
+  layout::Rect,
+  style::{Color, Modifier},
+  text::Line,
+};
+
+use crate::{
+  installer::{HIGHLIGHT, Installer, Page, Signal},
+  keys, split_hor, split_vert, styled_block, ui_close, ui_down, ui_up,
+  widget::{ConfigWidget, HelpModal, InfoBox, LineEditor, StrList},
+};
+
+/// Whether `entry` is a flake reference like `github:owner/repo#attr` rather
+/// than a path
+pub fn is_flake_ref(entry: &str) -> bool {
+  !entry.starts_with('/') && !entry.starts_with("./") && !entry.starts_with("../")
+}
+
+/// Check a module or overlay entry is a plausible path or flake reference
+///
+/// Paths end up as Nix path literals, so they can't contain spaces or quotes.
+/// Flake references need a scheme like `github:` or `git+https:`.
+pub fn parse_module_ref(entry: &str) -> Result<String, String> {
+  let entry = entry.trim();
+  if entry.is_empty() {
+    return Err("Enter a path or flake reference".to_string());
+  }
+  if !is_flake_ref(entry) {
+    let is_path_char = |c: char| c.is_ascii_alphanumeric() || "/._-+".contains(c);
+    if !entry.chars().all(is_path_char) || entry.ends_with('/') {
+      return Err(format!("'{entry}' is not a valid path"));
+    }
+    return Ok(entry.to_string());
+  }
+  let Some((scheme, rest)) = entry.split_once(':') else {
+    return Err(
+      "Start with / or ./ for a path, or give a flake reference like github:owner/repo".to_string(),
+    );
+  };
+  let is_scheme_char = |c: char| c.is_ascii_alphanumeric() || c == '+';
+  if scheme.is_empty()
+    || !scheme.chars().all(is_scheme_char)
+    || rest.is_empty()
+    || entry.contains(char::is_whitespace)
+    || entry.contains('"')
+  {
+    return Err(format!("'{entry}' is not a valid flake reference"));
+  }
+  Ok(entry.to_string())
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum ModuleList {
+  Imports,
+  Overlays,
+}
+
+/// Extra modules for `imports` and overlay files for `nixpkgs.overlays`
+pub struct ExtraModules {
+  imports: StrList,
+  overlays: StrList,
+  input: LineEditor,
+  focused: ModuleList,
+  adding: bool,
+  help_modal: HelpModal<'static>,
+}
+
+impl ExtraModules {
+  pub fn new(installer: &Installer) -> Self {
+    let mut imports = StrList::new("Imports", installer.extra_imports.clone());
+    imports.focus();
+    let overlays = StrList::new("Overlays", installer.overlays.clone());
+    let input = LineEditor::new("Path or flake", Some("e.g. /mnt/usb/modules/default.nix"));
+    let help_content = styled_block(vec![
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Navigate entries"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Tab"),
+        (None, " - Switch between imports and overlays"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "a"),
+        (None, " - Add to the selected list"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "d"),
+        (None, " - Remove the selected entry"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Esc, q"),
+        (None, " - Return to menu"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "?"),
+        (None, " - Show this help"),
+      ],
+      vec![(None, "")],
+      vec![(
+        None,
+        "Flake references use nixosModules.default and overlays.default unless an attribute is given after #.",
+      )],
+    ]);
+    let help_modal = HelpModal::new("Extra Modules", help_content);
+    Self {
+      imports,
+      overlays,
+      input,
+      focused: ModuleList::Imports,
+      adding: false,
+      help_modal,
+    }
+  }
+  fn switch_list(&mut self) {
+    match self.focused {
+      ModuleList::Imports => {
+        self.imports.unfocus();
+        self.overlays.focus();
+        self.focused = ModuleList::Overlays;
+      }
+      ModuleList::Overlays => {
+        self.overlays.unfocus();
+        self.imports.focus();
+        self.focused = ModuleList::Imports;
+      }
+    }
+  }
+  fn close_input(&mut self) {
+    self.input.clear();
+    self.input.unfocus();
+    self.adding = false;
+  }
+  fn add_entry(&mut self, installer: &mut Installer) -> Signal {
+    let value = self
+      .input
+      .get_value()
+      .and_then(|v| v.as_str().map(|s| s.to_string()))
+      .unwrap_or_default();
+    let entry = match parse_module_ref(&value) {
+      Ok(entry) => entry,
+      Err(e) => {
+        self.input.error(e);
+        return Signal::Wait;
+      }
+    };
+    let (entries, list) = match self.focused {
+      ModuleList::Imports => (&mut installer.extra_imports, &mut self.imports),
+      ModuleList::Overlays => (&mut installer.overlays, &mut self.overlays),
+    };
+    if entries.contains(&entry) {
+      self.input.error("Already in the list");
+      return Signal::Wait;
+    }
+    entries.push(entry);
+    list.set_items(entries.clone());
+    self.close_input();
+    Signal::Wait
+  }
+  pub fn display_widget(installer: &mut Installer) -> Option<Box<dyn ConfigWidget>> {
+    if installer.extra_imports.is_empty() && installer.overlays.is_empty() {
+      return None;
+    }
+    let mut lines = vec![];
+    for entry in &installer.extra_imports {
+      lines.push(vec![
+        (None, "Import: ".to_string()),
+        (HIGHLIGHT, entry.clone()),
+      ]);
+    }
+    for entry in &installer.overlays {
+      lines.push(vec![
+        (None, "Overlay: ".to_string()),
+        (HIGHLIGHT, entry.clone()),
+      ]);
+    }
+    Some(Box::new(InfoBox::new("", styled_block(lines))) as Box<dyn ConfigWidget>)
+  }
+  pub fn page_info<'a>() -> (String, Vec<Line<'a>>) {
+    (
+      "Extra Modules".to_string(),
+      styled_block(vec![
+        vec![(
+          None,
+          "Bolt your own NixOS modules and nixpkgs overlays onto the generated config.",
+        )],
+        vec![(
+          None,
+          "Paths must still exist when the system is rebuilt later, flake references need flakes enabled.",
+        )],
+      ]),
+    )
+  }
+}
+
+impl Page for ExtraModules {
+  fn render(&mut self, _installer: &mut Installer, f: &mut Frame, area: Rect) {
+    let chunks = split_vert!(area, 1, [Constraint::Min(0), Constraint::Length(3)]);
+    let hor_chunks = split_hor!(
+      chunks[0],
+      1,
+      [Constraint::Percentage(50), Constraint::Percentage(50)]
+    );
+    self.imports.render(f, hor_chunks[0]);
+    self.overlays.render(f, hor_chunks[1]);
+    if self.adding {
+      self.input.render(f, chunks[1]);
+    } else {
+      let hint = match self.focused {
+        ModuleList::Imports => {
+          "Modules are added to imports, e.g. /mnt/usb/modules or github:me/dotfiles"
+        }
+        ModuleList::Overlays => {
+          "Overlay files are added to nixpkgs.overlays, e.g. ./overlays/mine.nix"
+        }
+      };
+      InfoBox::new("", styled_block(vec![vec![(None, hint)]])).render(f, chunks[1]);
+    }
+    self.help_modal.render(f, area);
+  }
+
+  fn get_help_content(&self) -> (String, Vec<Line<'_>>) {
+    let help_content = styled_block(vec![
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Navigate entries"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Tab"),
+        (None, " - Switch between imports and overlays"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "a"),
+        (None, " - Add to the selected list"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "d"),
+        (None, " - Remove the selected entry"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Esc, q"),
+        (None, " - Return to menu"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "?"),
+        (None, " - Show this help"),
+      ],
+      vec![(None, "")],
+      vec![(
+        None,
+        "Flake references use nixosModules.default and overlays.default unless an attribute is given after #.",
+      )],
+    ]);
+    ("Extra Modules".to_string(), help_content)
+  }
+
+  fn handle_paste(&mut self, _installer: &mut Installer, text: &str) -> Signal {
+    if self.adding {
+      self.input.paste(text);
+    }
+    Signal::Wait
+  }
+
+  fn handle_input(&mut self, installer: &mut Installer, event: KeyEvent) -> Signal {
+    if self.adding {
+      return match event.code {
+        KeyCode::Esc => {
+          self.close_input();
+          Signal::Wait
+        }
+        KeyCode::Enter => self.add_entry(installer),
+        _ => self.input.handle_input(event),
+      };
+    }
+
+    match event.code {
+      KeyCode::Char('?') => {
+        self.help_modal.toggle();
+        Signal::Wait
+      }
+      code if ui_close!(code) && self.help_modal.visible => {
+        self.help_modal.hide();
+        Signal::Wait
+      }
+      _ if self.help_modal.visible => Signal::Wait,
+      code if ui_close!(code) => Signal::Pop,
+      KeyCode::Tab | KeyCode::BackTab => {
+        self.switch_list();
+        Signal::Wait
+      }
+      KeyCode::Char('a') => {
+        self.input.clear();
+        self.input.focus();
+        self.adding = true;
+        Signal::Wait
+      }
+      KeyCode::Char('d') => {
+        let (entries, list) = match self.focused {
+          ModuleList::Imports => (&mut installer.extra_imports, &mut self.imports),
+          ModuleList::Overlays => (&mut installer.overlays, &mut self.overlays),
+        };
+        if let Some(entry) = list.remove_selected() {
+          entries.retain(|e| *e != entry);
+        }
+        Signal::Wait
+      }
+      code if ui_up!(code) => {
+        match self.focused {
+          ModuleList::Imports => self.imports.previous_item(),
+          ModuleList::Overlays => self.overlays.previous_item(),
+        };
+        Signal::Wait
+      }
+      code if ui_down!(code) => {
+        match self.focused {
+          ModuleList::Imports => self.imports.next_item(),
+          ModuleList::Overlays => self.overlays.next_item(),
+        };
+        Signal::Wait
+      }
+      _ => Signal::Wait,
+    }
+  }
+}
@@ -0,0 +1,306 @@
+use ratatui::{
+  Frame,
+  crossterm::event::{KeyCode, KeyEvent},
+  layout::Rect,
+  style::{Color, Modifier},
+  text::Line,
+};
+
+use crate::{
+  installer::{HIGHLIGHT, Installer, Page, Signal},
+  keys, split_hor, split_vert, styled_block, ui_close, ui_down, ui_up,
+  widget::{ConfigWidget, HelpModal, InfoBox, LineEditor, StrList},
+};
+
+/// Check that `input` looks like a `name-version` store name
+///
+/// nixpkgs matches `permittedInsecurePackages` against the full name of the
+/// derivation, so a bare `openssl` would never match anything. The version is
+/// the part after the last `-` that is followed by a digit.
+pub fn parse_insecure_package(input: &str) -> Result<String, String> {
+  let input = input.trim();
+  if input.is_empty() {
+    return Err("Package name cannot be empty".to_string());
+  }
+  if !input
+    .chars()
+    .all(|c| c.is_ascii_alphanumeric() || "-_.+".contains(c))
+  {
+    return Err(format!(
+      "'{input}' contains characters not allowed in a package name"
+    ));
+  }
+  let version_start = input
+    .match_indices('-')
+    .map(|(idx, _)| idx)
+    .find(|&idx| input[idx + 1..].starts_with(|c: char| c.is_ascii_digit()));
+  match version_start {
+    Some(idx) if idx > 0 => Ok(input.to_string()),
+    _ => Err(format!(
+      "'{input}' has no version, expected name-version, e.g. openssl-1.1.1w"
+    )),
+  }
+}
+
+enum InsecureMode {
+  List,
+  Entry,
+}
+
+/// Packages allowed through `nixpkgs.config.permittedInsecurePackages`
+pub struct InsecurePackages {
+  entries: StrList,
+  input: LineEditor,
+  mode: InsecureMode,
+  /// Index of the entry being edited, replaced when the edit is saved
+  editing: Option<usize>,
+  help_modal: HelpModal<'static>,
+}
+
+impl InsecurePackages {
+  pub fn new(installer: &Installer) -> Self {
+    let mut entries = StrList::new("Permitted Packages", installer.permitted_insecure.clone());
+    entries.focus();
+    let input = LineEditor::new("name-version", Some("e.g. openssl-1.1.1w"));
+    let help_content = styled_block(vec![
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Navigate packages"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "a"),
+        (None, " - Add a package"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
+        (None, " - Edit the selected package"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "d"),
+        (None, " - Delete the selected package"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Esc, q"),
+        (None, " - Return to menu"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "?"),
+        (None, " - Show this help"),
+      ],
+      vec![(None, "")],
+      vec![(
+        None,
+        "Use the exact name and version from the error of the failed build.",
+      )],
+    ]);
+    let help_modal = HelpModal::new("Insecure Packages", help_content);
+    Self {
+      entries,
+      input,
+      mode: InsecureMode::List,
+      editing: None,
+      help_modal,
+    }
+  }
+  fn open_input(&mut self, editing: Option<(usize, String)>) {
+    match editing {
+      Some((idx, name)) => {
+        self.input.set_value(name);
+        self.editing = Some(idx);
+      }
+      None => {
+        self.input.clear();
+        self.editing = None;
+      }
+    }
+    self.entries.unfocus();
+    self.input.focus();
+    self.mode = InsecureMode::Entry;
+  }
+  fn close_input(&mut self) {
+    self.input.clear();
+    self.input.unfocus();
+    self.entries.focus();
+    self.editing = None;
+    self.mode = InsecureMode::List;
+  }
+  pub fn display_widget(installer: &mut Installer) -> Option<Box<dyn ConfigWidget>> {
+    if installer.permitted_insecure.is_empty() {
+      return None;
+    }
+    let mut lines = vec![vec![(None, "Permitted insecure packages:".to_string())]];
+    for name in &installer.permitted_insecure {
+      lines.push(vec![(HIGHLIGHT, name.clone())]);
+    }
+    Some(Box::new(InfoBox::new("", styled_block(lines))) as Box<dyn ConfigWidget>)
+  }
+  pub fn page_info<'a>() -> (String, Vec<Line<'a>>) {
+    (
+      "Insecure Packages".to_string(),
+      styled_block(vec![
+        vec![(
+          None,
+          "Advanced: allow specific packages that nixpkgs marks as insecure.",
+        )],
+        vec![(
+          None,
+          "Only needed when a build fails asking for permittedInsecurePackages.",
+        )],
+      ]),
+    )
+  }
+}
+
+impl Page for InsecurePackages {
+  fn render(&mut self, _installer: &mut Installer, f: &mut Frame, area: Rect) {
+    let chunks = split_vert!(area, 1, [Constraint::Min(0), Constraint::Length(3)]);
+    let hor_chunks = split_hor!(
+      chunks[0],
+      1,
+      [
+        Constraint::Percentage(20),
+        Constraint::Percentage(60),
+        Constraint::Percentage(20),
+      ]
+    );
+    self.entries.render(f, hor_chunks[1]);
+    if let InsecureMode::Entry = self.mode {
+      let input_chunks = split_hor!(
+        chunks[1],
+        0,
+        [
+          Constraint::Percentage(20),
+          Constraint::Percentage(60),
+          Constraint::Percentage(20),
+        ]
+      );
+      self.input.render(f, input_chunks[1]);
+    }
+    self.help_modal.render(f, area);
+  }
+
+  fn get_help_content(&self) -> (String, Vec<Line<'_>>) {
+    let help_content = styled_block(vec![
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Navigate packages"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "a"),
+        (None, " - Add a package"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
+        (None, " - Edit the selected package"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "d"),
+        (None, " - Delete the selected package"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Esc, q"),
+        (None, " - Return to menu"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "?"),
+        (None, " - Show this help"),
+      ],
+      vec![(None, "")],
+      vec![(
+        None,
+        "Use the exact name and version from the error of the failed build.",
+      )],
+    ]);
+    ("Insecure Packages".to_string(), help_content)
+  }
+
+  fn handle_paste(&mut self, _installer: &mut Installer, text: &str) -> Signal {
+    if let InsecureMode::Entry = self.mode {
+      self.input.paste(text);
+    }
+    Signal::Wait
+  }
+
+  fn handle_input(&mut self, installer: &mut Installer, event: KeyEvent) -> Signal {
+    if let InsecureMode::Entry = self.mode {
+      return match event.code {
+        KeyCode::Esc => {
+          self.close_input();
+          Signal::Wait
+        }
+        KeyCode::Enter => {
+          let input = self
+            .input
+            .get_value()
+            .and_then(|v| v.as_str().map(|s| s.to_string()))
+            .unwrap_or_default();
+          match parse_insecure_package(&input) {
+            Ok(name) => {
+              if let Some(idx) = self.editing.take()
+                && idx < installer.permitted_insecure.len()
+              {
+                installer.permitted_insecure.remove(idx);
+              }
+              if !installer.permitted_insecure.contains(&name) {
+                installer.permitted_insecure.push(name);
+              }
+              self.entries.set_items(installer.permitted_insecure.clone());
+              self.close_input();
+              Signal::Wait
+            }
+            Err(e) => {
+              self.input.error(e);
+              Signal::Wait
+            }
+          }
+        }
+        _ => self.input.handle_input(event),
+      };
+    }
+
+    match event.code {
+      KeyCode::Char('?') => {
+        self.help_modal.toggle();
+        Signal::Wait
+      }
+      code if ui_close!(code) && self.help_modal.visible => {
+        self.help_modal.hide();
+        Signal::Wait
+      }
+      _ if self.help_modal.visible => Signal::Wait,
+      code if ui_close!(code) => Signal::Pop,
+      KeyCode::Char('a') => {
+        self.open_input(None);
+        Signal::Wait
+      }
+      KeyCode::Char('d') => {
+        if let Some(name) = self.entries.remove_selected() {
+          installer.permitted_insecure.retain(|n| *n != name);
+        }
+        Signal::Wait
+      }
+      KeyCode::Enter => {
+        if let Some(name) = self.entries.selected_item().cloned() {
+          let idx = self.entries.selected_idx;
+          self.open_input(Some((idx, name)));
+        }
+        Signal::Wait
+      }
+      code if ui_up!(code) => {
+        self.entries.previous_item();
+        Signal::Wait
+      }
+      code if ui_down!(code) => {
+        self.entries.next_item();
+        Signal::Wait
+      }
+      _ => Signal::Wait,
+    }
+  }
+}
@@ -0,0 +1,166 @@
+//! Read regional settings from the live system the installer is running on,
+//! so users whose live environment already matches can reuse them
+
+use std::{fs, path::Path};
+
+use crate::command;
+
+/// Parse `localectl status` output into (key, value) pairs
+fn localectl_fields() -> anyhow::Result<Vec<(String, String)>> {
+  let output = command!("localectl", "status").output()?;
+  if !output.status.success() {
+    return Err(anyhow::anyhow!("localectl exited with {}", output.status));
+  }
+  let stdout = String::from_utf8_lossy(&output.stdout);
+  Ok(
+    stdout
+      .lines()
+      .filter_map(|line| line.split_once(':'))
+      .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+      .collect(),
+  )
+}
+
+/// The `LANG` of the running system, e.g. "en_US.UTF-8"
+pub fn current_locale() -> anyhow::Result<String> {
+  localectl_fields()?
+    .into_iter()
+    .find(|(key, _)| key == "System Locale")
+    .and_then(|(_, value)| {
+      value
+        .split_whitespace()
+        .find_map(|var| var.strip_prefix("LANG=").map(|l| l.to_string()))
+    })
+    .ok_or_else(|| anyhow::anyhow!("The running system has no LANG set"))
+}
+
+/// The running system's timezone, e.g. "Europe/Berlin"
+pub fn current_timezone() -> anyhow::Result<String> {
+  let output = command!("timedatectl", "show", "--property=Timezone", "--value").output()?;
+  if !output.status.success() {
+    return Err(anyhow::anyhow!("timedatectl exited with {}", output.status));
+  }
+  let timezone = String::from_utf8_lossy(&output.stdout).trim().to_string();
+  if timezone.is_empty() {
+    return Err(anyhow::anyhow!("The running system has no timezone set"));
+  }
+  Ok(timezone)
+}
+
+/// Lines printed by one of the `localectl list-*` commands
+fn localectl_list(args: &[&str]) -> anyhow::Result<Vec<String>> {
+  let output = command!("localectl").args(args).output()?;
+  if !output.status.success() {
+    return Err(anyhow::anyhow!("localectl exited with {}", output.status));
+  }
+  let items = String::from_utf8_lossy(&output.stdout)
+    .lines()
+    .map(|line| line.trim().to_string())
+    .filter(|line| !line.is_empty())
+    .collect::<Vec<_>>();
+  if items.is_empty() {
+    return Err(anyhow::anyhow!(
+      "localectl {} listed nothing",
+      args.join(" ")
+    ));
+  }
+  Ok(items)
+}
+
+/// XKB layout names known to the live system, e.g. "us" or "gb"
+pub fn list_keyboard_layouts() -> anyhow::Result<Vec<String>> {
+  localectl_list(&["list-x11-keymap-layouts"])
+}
+
+/// XKB variants of `layout`, e.g. "dvorak" for "us"
+pub fn list_keyboard_variants(layout: &str) -> anyhow::Result<Vec<String>> {
+  localectl_list(&["list-x11-keymap-variants", layout])
+}
+
+/// The running system's keyboard layout and variant, if the layout is one
+/// of `known`
+pub fn current_keyboard_layout(known: &[String]) -> anyhow::Result<(String, Option<String>)> {
+  let fields = localectl_fields()?;
+  let get = |name: &str| {
+    fields
+      .iter()
+      .find(|(key, _)| key == name)
+      .map(|(_, value)| value.as_str())
+  };
+  let layout = get("X11 Layout")
+    .or(get("VC Keymap"))
+    .and_then(|l| l.split(',').next())
+    .filter(|l| !l.is_empty() && *l != "n/a")
+    .ok_or_else(|| anyhow::anyhow!("The running system has no keyboard layout set"))?;
+  let variant = get("X11 Variant")
+    .and_then(|v| v.split(',').next())
+    .filter(|v| !v.is_empty() && *v != "n/a");
+  // Without X11 settings the console keymap is all there is, and a few of
+  // those are named after a variant rather than a layout
+  let (layout, variant) = match (layout, variant) {
+    ("dvorak" | "colemak", None) => ("us", Some(layout)),
+    ("uk", None) => ("gb", None),
+    other => other,
+  };
+  if known.iter().any(|k| k == layout) {
+    Ok((layout.to_string(), variant.map(|v| v.to_string())))
+  } else {
+    Err(anyhow::anyhow!(
+      "The running system's keyboard layout '{layout}' is not one of the supported layouts"
+    ))
+  }
+}
+
+/// Every timezone tzdata knows about, e.g. for the timezone page
+///
+/// Asks timedatectl first, and falls back to walking the zoneinfo directory
+/// when it isn't available. Empty if neither works.
+pub fn list_timezones() -> Vec<String> {
+  let from_timedatectl = command!("timedatectl", "list-timezones")
+    .output()
+    .ok()
+    .filter(|output| output.status.success())
+    .map(|output| {
+      String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|line| line.trim().to_string())
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+    })
+    .unwrap_or_default();
+  if !from_timedatectl.is_empty() {
+    return from_timedatectl;
+  }
+  // NixOS has no /usr/share/zoneinfo, /etc/zoneinfo points into tzdata instead
+  let mut timezones = vec![];
+  for root in ["/etc/zoneinfo", "/usr/share/zoneinfo"] {
+    walk_zoneinfo(Path::new(root), "", &mut timezones);
+    if !timezones.is_empty() {
+      break;
+    }
+  }
+  timezones.sort();
+  timezones
+}
+
+/// Collect zone names below `dir`. Zone files are capitalized and have no
+/// extension, which leaves out tables like `zone.tab` as well as the
+/// `posix/` and `right/` copies of the whole tree.
+fn walk_zoneinfo(dir: &Path, prefix: &str, out: &mut Vec<String>) {
+  let Ok(entries) = fs::read_dir(dir) else {
+    return;
+  };
+  for entry in entries.flatten() {
+    let name = entry.file_name().to_string_lossy().to_string();
+    if !name.starts_with(|c: char| c.is_ascii_uppercase()) || name.contains('.') {
+      continue;
+    }
+    let zone = format!("{prefix}{name}");
+    let path = entry.path();
+    if path.is_dir() {
+      walk_zoneinfo(&path, &format!("{zone}/"), out);
+    } else {
+      out.push(zone);
+    }
+  }
+}
@@ -0,0 +1,122 @@
+use std::{
+  sync::{
+    Arc, Mutex,
+    atomic::{AtomicBool, Ordering},
+  },
+  thread,
+  time::{Duration, Instant},
+};
+
+use ratatui::{Frame, crossterm::event::KeyEvent, layout::Rect};
+
+use crate::{
+  installer::{HIGHLIGHT, Installer, Page, SPINNER, Signal},
+  split_hor, split_vert, styled_block, ui_close,
+  widget::{ConfigWidget, InfoBox},
+};
+
+/// After this long the page points out that Esc gives up on the wait
+const SLOW_AFTER: Duration = Duration::from_secs(3);
+
+type Build<T> = Box<dyn FnOnce(T) -> Box<dyn Page>>;
+
+/// Stands in for a page whose contents need blocking work first
+///
+/// The work runs on its own thread. Once it's done this page is replaced by
+/// the one `build` makes from the result. Esc returns to the previous page
+/// straight away, the work is told to stop through the flag it's given and
+/// whatever it still returns is dropped.
+pub struct Loading<T> {
+  title: &'static str,
+  result: Arc<Mutex<Option<T>>>,
+  cancel: Arc<AtomicBool>,
+  /// Behind a mutex because `signal` only gets `&self`
+  build: Mutex<Option<Build<T>>>,
+  started: Instant,
+  spinner_frame: usize,
+}
+
+impl<T: Send + 'static> Loading<T> {
+  pub fn new(
+    title: &'static str,
+    work: impl FnOnce(&AtomicBool) -> T + Send + 'static,
+    build: impl FnOnce(T) -> Box<dyn Page> + 'static,
+  ) -> Self {
+    let result = Arc::new(Mutex::new(None));
+    let cancel = Arc::new(AtomicBool::new(false));
+    let (result_ref, cancel_ref) = (result.clone(), cancel.clone());
+    thread::spawn(move || {
+      let value = work(&cancel_ref);
+      if cancel_ref.load(Ordering::Relaxed) {
+        return;
+      }
+      if let Ok(mut guard) = result_ref.lock() {
+        *guard = Some(value);
+      }
+    });
+    Self {
+      title,
+      result,
+      cancel,
+      build: Mutex::new(Some(Box::new(build))),
+      started: Instant::now(),
+      spinner_frame: 0,
+    }
+  }
+}
+
+impl<T: Send + 'static> Page for Loading<T> {
+  fn render(&mut self, _installer: &mut Installer, f: &mut Frame, area: Rect) {
+    self.spinner_frame = self.spinner_frame.wrapping_add(1);
+    let mut lines = vec![vec![
+      (
+        HIGHLIGHT,
+        SPINNER[self.spinner_frame % SPINNER.len()].to_string(),
+      ),
+      (None, format!(" {}", self.title)),
+    ]];
+    if self.started.elapsed() >= SLOW_AFTER {
+      lines.push(vec![(
+        None,
+        "This is taking a while, press Esc to stop waiting.".to_string(),
+      )]);
+    }
+    let chunks = split_vert!(
+      area,
+      1,
+      [
+        Constraint::Percentage(40),
+        Constraint::Length(4),
+        Constraint::Min(0),
+      ]
+    );
+    let hor_chunks = split_hor!(
+      chunks[1],
+      1,
+      [
+        Constraint::Percentage(25),
+        Constraint::Percentage(50),
+        Constraint::Percentage(25),
+      ]
+    );
+    InfoBox::new("", styled_block(lines)).render(f, hor_chunks[1]);
+  }
+
+  fn has_help(&self) -> bool {
+    false
+  }
+
+  fn handle_input(&mut self, _installer: &mut Installer, event: KeyEvent) -> Signal {
+    if ui_close!(event.code) {
+      self.cancel.store(true, Ordering::Relaxed);
+      return Signal::Pop;
+    }
+    Signal::Wait
+  }
+
+  fn signal(&self) -> Option<Signal> {
+    let value = self.result.lock().ok()?.take()?;
+    let build = self.build.lock().ok()?.take()?;
+    Some(Signal::Replace(build(value)))
+  }
+}
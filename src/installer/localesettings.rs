@@ -0,0 +1,308 @@
+use ratatui::{
+  Frame,
+  crossterm::event::{KeyCode, KeyEvent},
+  layout::Rect,
+  style::{Color, Modifier},
+  text::Line,
+};
+
+use crate::{
+  installer::{HIGHLIGHT, Installer, LOCALES, Page, Signal},
+  keys, split_hor, split_vert, styled_block, ui_close, ui_down, ui_up,
+  widget::{ConfigWidget, HelpModal, InfoBox, MultiSelectList, StrList},
+};
+
+/// `LC_*` categories that can differ from the default locale, with what they
+/// affect
+pub const LOCALE_CATEGORIES: [(&str, &str); 9] = [
+  ("LC_ADDRESS", "Postal addresses"),
+  ("LC_IDENTIFICATION", "Locale metadata"),
+  ("LC_MEASUREMENT", "Metric or imperial units"),
+  ("LC_MONETARY", "Currency"),
+  ("LC_NAME", "Personal names"),
+  ("LC_NUMERIC", "Number formatting"),
+  ("LC_PAPER", "Paper size"),
+  ("LC_TELEPHONE", "Phone numbers"),
+  ("LC_TIME", "Dates and times"),
+];
+
+const SAME_AS_DEFAULT: &str = "Same as default";
+
+enum LocaleFocus {
+  Categories,
+  /// Picking a locale for the category at this index
+  Locales(usize),
+  Extras,
+}
+
+/// Per-category overrides of the default locale, and extra locales to install
+pub struct LocaleSettings {
+  categories: StrList,
+  locales: StrList,
+  extras: MultiSelectList,
+  focus: LocaleFocus,
+  help_modal: HelpModal<'static>,
+}
+
+impl LocaleSettings {
+  pub fn new(installer: &Installer) -> Self {
+    let mut categories = StrList::new("Categories", Self::category_labels(installer));
+    categories.focus();
+    let locales = std::iter::once(SAME_AS_DEFAULT)
+      .chain(LOCALES.iter().copied())
+      .map(String::from)
+      .collect();
+    let locales = StrList::new("Locale", locales);
+    let mut extras = MultiSelectList::new(
+      "Extra Locales",
+      LOCALES.iter().map(|s| s.to_string()).collect(),
+    );
+    extras.set_checked(&installer.extra_locales);
+    extras.first_item();
+    let help_content = styled_block(vec![
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Navigate"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
+        (None, " - Pick a locale for the category"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Space"),
+        (None, " - Toggle an extra locale"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Tab"),
+        (None, " - Switch between categories and extra locales"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Esc, q"),
+        (None, " - Return"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "?"),
+        (None, " - Show this help"),
+      ],
+      vec![(None, "")],
+      vec![(
+        None,
+        "Categories left at the default follow the locale chosen on the previous page.",
+      )],
+    ]);
+    let help_modal = HelpModal::new("Locale Settings", help_content);
+    Self {
+      categories,
+      locales,
+      extras,
+      focus: LocaleFocus::Categories,
+      help_modal,
+    }
+  }
+  fn category_labels(installer: &Installer) -> Vec<String> {
+    LOCALE_CATEGORIES
+      .iter()
+      .map(|(category, _)| {
+        let value = installer
+          .locale_settings
+          .get(*category)
+          .map_or("default", |v| v.as_str());
+        format!("{category}: {value}")
+      })
+      .collect()
+  }
+  fn open_locales(&mut self, installer: &Installer, idx: usize) {
+    let (category, _) = LOCALE_CATEGORIES[idx];
+    self.locales.commit_value(Some(
+      installer
+        .locale_settings
+        .get(category)
+        .map_or(SAME_AS_DEFAULT, |v| v.as_str()),
+    ));
+    self.locales.title = format!("Locale for {category}");
+    self.categories.unfocus();
+    self.locales.focus();
+    self.focus = LocaleFocus::Locales(idx);
+  }
+  fn close_locales(&mut self) {
+    self.locales.unfocus();
+    self.categories.focus();
+    self.focus = LocaleFocus::Categories;
+  }
+  fn switch_focus(&mut self) {
+    match self.focus {
+      LocaleFocus::Categories => {
+        self.categories.unfocus();
+        self.extras.focus();
+        self.focus = LocaleFocus::Extras;
+      }
+      LocaleFocus::Extras => {
+        self.extras.unfocus();
+        self.categories.focus();
+        self.focus = LocaleFocus::Categories;
+      }
+      LocaleFocus::Locales(_) => {}
+    }
+  }
+  fn info_lines(&self) -> Vec<Line<'static>> {
+    let idx = match self.focus {
+      LocaleFocus::Locales(idx) => idx,
+      _ => self.categories.selected_idx,
+    };
+    let (category, description) = LOCALE_CATEGORIES[idx.min(LOCALE_CATEGORIES.len() - 1)];
+    styled_block(vec![
+      vec![
+        (HIGHLIGHT, category.to_string()),
+        (None, format!(": {description}")),
+      ],
+      vec![(None, String::new())],
+      vec![(
+        None,
+        "Extra locales are installed alongside the ones in use, so programs or users can switch to them."
+          .to_string(),
+      )],
+    ])
+  }
+}
+
+impl Page for LocaleSettings {
+  fn render(&mut self, _installer: &mut Installer, f: &mut Frame, area: Rect) {
+    let chunks = split_vert!(area, 1, [Constraint::Length(6), Constraint::Min(0)]);
+    let hor_chunks = split_hor!(
+      chunks[1],
+      1,
+      [Constraint::Percentage(50), Constraint::Percentage(50)]
+    );
+    InfoBox::new("", self.info_lines()).render(f, chunks[0]);
+    self.categories.render(f, hor_chunks[0]);
+    match self.focus {
+      LocaleFocus::Locales(_) => self.locales.render(f, hor_chunks[1]),
+      _ => self.extras.render(f, hor_chunks[1]),
+    }
+    self.help_modal.render(f, area);
+  }
+
+  fn get_help_content(&self) -> (String, Vec<Line<'_>>) {
+    let help_content = styled_block(vec![
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Navigate"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
+        (None, " - Pick a locale for the category"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Space"),
+        (None, " - Toggle an extra locale"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Tab"),
+        (None, " - Switch between categories and extra locales"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Esc, q"),
+        (None, " - Return"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "?"),
+        (None, " - Show this help"),
+      ],
+      vec![(None, "")],
+      vec![(
+        None,
+        "Categories left at the default follow the locale chosen on the previous page.",
+      )],
+    ]);
+    ("Locale Settings".to_string(), help_content)
+  }
+
+  fn handle_input(&mut self, installer: &mut Installer, event: KeyEvent) -> Signal {
+    match event.code {
+      KeyCode::Char('?') => {
+        self.help_modal.toggle();
+        return Signal::Wait;
+      }
+      code if ui_close!(code) && self.help_modal.visible => {
+        self.help_modal.hide();
+        return Signal::Wait;
+      }
+      _ if self.help_modal.visible => return Signal::Wait,
+      _ => {}
+    }
+
+    match self.focus {
+      LocaleFocus::Locales(idx) => match event.code {
+        code if ui_close!(code) => {
+          self.close_locales();
+          Signal::Wait
+        }
+        KeyCode::Enter => {
+          let (category, _) = LOCALE_CATEGORIES[idx];
+          match self.locales.selected_item() {
+            Some(locale) if locale != SAME_AS_DEFAULT => {
+              installer
+                .locale_settings
+                .insert(category.to_string(), locale.clone());
+            }
+            _ => {
+              installer.locale_settings.remove(category);
+            }
+          }
+          self.categories.set_items(Self::category_labels(installer));
+          self.close_locales();
+          Signal::Wait
+        }
+        code if ui_up!(code) => {
+          self.locales.previous_item();
+          Signal::Wait
+        }
+        code if ui_down!(code) => {
+          self.locales.next_item();
+          Signal::Wait
+        }
+        _ => Signal::Wait,
+      },
+      LocaleFocus::Categories | LocaleFocus::Extras => match event.code {
+        code if ui_close!(code) => Signal::Pop,
+        KeyCode::Tab | KeyCode::BackTab => {
+          self.switch_focus();
+          Signal::Wait
+        }
+        KeyCode::Enter | KeyCode::Char(' ') if matches!(self.focus, LocaleFocus::Extras) => {
+          self.extras.toggle_selected();
+          installer.extra_locales = self.extras.checked_items();
+          Signal::Wait
+        }
+        KeyCode::Enter => {
+          let idx = self.categories.selected_idx;
+          if idx < LOCALE_CATEGORIES.len() {
+            self.open_locales(installer, idx);
+          }
+          Signal::Wait
+        }
+        code if ui_up!(code) => {
+          match self.focus {
+            LocaleFocus::Extras => self.extras.previous_item(),
+            _ => self.categories.previous_item(),
+          };
+          Signal::Wait
+        }
+        code if ui_down!(code) => {
+          match self.focus {
+            LocaleFocus::Extras => self.extras.next_item(),
+            _ => self.categories.next_item(),
+          };
+          Signal::Wait
+        }
+        _ => Signal::Wait,
+      },
+    }
+  }
+}
@@ -0,0 +1,128 @@
+use std::path::PathBuf;
+
+use crate::installer::MenuPages;
+
+/// The user's arrangement of the main menu, kept between runs
+///
+/// Pages are stored by their display name, so entries for pages that no
+/// longer exist are simply ignored and new pages fall in at the end of the
+/// default order.
+#[derive(Default, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct MenuOrder {
+  /// Pinned to the top of the menu, in this order
+  pub favorites: Vec<String>,
+  /// Order of everything else
+  pub order: Vec<String>,
+  /// Width of the menu column as a percentage of the screen
+  pub menu_width: Option<u16>,
+}
+
+const DEFAULT_MENU_WIDTH: u16 = 20;
+const MENU_WIDTH_RANGE: std::ops::RangeInclusive<u16> = 10..=60;
+const MENU_WIDTH_STEP: u16 = 5;
+
+impl MenuOrder {
+  fn path() -> PathBuf {
+    let config_dir = std::env::var_os("XDG_CONFIG_HOME")
+      .map(PathBuf::from)
+      .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+      .unwrap_or_else(|| PathBuf::from("/root/.config"));
+    config_dir.join("nixos-wizard").join("menu.json")
+  }
+
+  /// A missing or unreadable file just means the default order
+  pub fn load() -> Self {
+    std::fs::read_to_string(Self::path())
+      .ok()
+      .and_then(|json| serde_json::from_str(&json).ok())
+      .unwrap_or_default()
+  }
+
+  pub fn save(&self) -> anyhow::Result<()> {
+    let path = Self::path();
+    if let Some(dir) = path.parent() {
+      std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+    Ok(())
+  }
+
+  pub fn menu_width(&self) -> u16 {
+    self
+      .menu_width
+      .unwrap_or(DEFAULT_MENU_WIDTH)
+      .clamp(*MENU_WIDTH_RANGE.start(), *MENU_WIDTH_RANGE.end())
+  }
+
+  /// Widen or narrow the menu column by one step, returns false at the limit
+  pub fn resize_menu(&mut self, wider: bool) -> bool {
+    let current = self.menu_width();
+    let width = if wider {
+      current + MENU_WIDTH_STEP
+    } else {
+      current.saturating_sub(MENU_WIDTH_STEP)
+    }
+    .clamp(*MENU_WIDTH_RANGE.start(), *MENU_WIDTH_RANGE.end());
+    self.menu_width = Some(width);
+    width != current
+  }
+
+  pub fn is_favorite(&self, page: MenuPages) -> bool {
+    self.favorites.contains(&page.to_string())
+  }
+
+  /// Sort pages into the stored order, favorites first
+  pub fn apply(&self, mut pages: Vec<MenuPages>) -> Vec<MenuPages> {
+    let rank = |page: &MenuPages| {
+      let name = page.to_string();
+      match self.favorites.iter().position(|f| *f == name) {
+        Some(idx) => (0, idx),
+        None => (
+          1,
+          self
+            .order
+            .iter()
+            .position(|o| *o == name)
+            .unwrap_or(usize::MAX),
+        ),
+      }
+    };
+    // Stable, so pages we know nothing about keep their default order
+    pages.sort_by_key(rank);
+    pages
+  }
+
+  pub fn toggle_favorite(&mut self, page: MenuPages) {
+    let name = page.to_string();
+    if let Some(idx) = self.favorites.iter().position(|f| *f == name) {
+      self.favorites.remove(idx);
+    } else {
+      self.order.retain(|o| *o != name);
+      self.favorites.push(name);
+    }
+  }
+
+  /// Record `pages` as the new order. Pages missing from it (hidden ones)
+  /// keep their previous place at the end.
+  pub fn record(&mut self, pages: &[MenuPages]) {
+    let (favorites, others): (Vec<_>, Vec<_>) = pages
+      .iter()
+      .map(|p| p.to_string())
+      .partition(|name| self.favorites.contains(name));
+    let hidden = self
+      .order
+      .iter()
+      .filter(|name| !others.contains(name))
+      .cloned()
+      .collect::<Vec<_>>();
+    let hidden_favorites = self
+      .favorites
+      .iter()
+      .filter(|name| !favorites.contains(name))
+      .cloned()
+      .collect::<Vec<_>>();
+    self.favorites = favorites.into_iter().chain(hidden_favorites).collect();
+    self.order = others.into_iter().chain(hidden).collect();
+  }
+}
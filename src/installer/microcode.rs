@@ -0,0 +1,164 @@
+use std::{fs, sync::LazyLock};
+
+use ratatui::{
+  Frame,
+  crossterm::event::{KeyCode, KeyEvent},
+  layout::Rect,
+  style::{Color, Modifier},
+  text::Line,
+};
+
+use crate::{
+  installer::{HIGHLIGHT, Installer, Page, Signal},
+  split_vert, styled_block, ui_close,
+  widget::{ConfigWidget, HelpModal, InfoBox},
+};
+
+/// Map the `vendor_id` line of `/proc/cpuinfo` to the name nixpkgs uses under
+/// `hardware.cpu`
+pub fn parse_cpu_vendor(cpuinfo: &str) -> Option<String> {
+  let vendor_id = cpuinfo.lines().find_map(|line| {
+    let (key, value) = line.split_once(':')?;
+    (key.trim() == "vendor_id").then(|| value.trim())
+  })?;
+  match vendor_id {
+    "GenuineIntel" => Some("intel".to_string()),
+    "AuthenticAMD" => Some("amd".to_string()),
+    _ => None,
+  }
+}
+
+/// The CPU vendor of the machine the installer runs on, if it's one that
+/// nixpkgs ships microcode for. Read once, the CPU doesn't change.
+pub fn detect_cpu_vendor() -> Option<String> {
+  static VENDOR: LazyLock<Option<String>> = LazyLock::new(|| {
+    let cpuinfo = fs::read_to_string("/proc/cpuinfo")
+      .inspect_err(|e| log::warn!("Couldn't read /proc/cpuinfo: {e}"))
+      .ok()?;
+    parse_cpu_vendor(&cpuinfo)
+  });
+  VENDOR.clone()
+}
+
+/// Read-only page showing which microcode updates the config will enable
+pub struct Microcode {
+  vendor: Option<String>,
+  help_modal: HelpModal<'static>,
+}
+
+impl Microcode {
+  pub fn new() -> Self {
+    let vendor = detect_cpu_vendor();
+    let help_content = styled_block(vec![
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Enter, Esc, q"),
+        (None, " - Return to menu"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "?"),
+        (None, " - Show this help"),
+      ],
+      vec![(None, "")],
+      vec![(
+        None,
+        "The vendor is read from /proc/cpuinfo, there is nothing to change here.",
+      )],
+    ]);
+    let help_modal = HelpModal::new("CPU Microcode", help_content);
+    Self { vendor, help_modal }
+  }
+  fn vendor_info<'a>(vendor: Option<&str>) -> InfoBox<'a> {
+    let lines = match vendor {
+      Some(vendor) => vec![
+        vec![(None, "Detected CPU vendor:".to_string())],
+        vec![(HIGHLIGHT, vendor.to_string())],
+        vec![(None, "The config will set:".to_string())],
+        vec![(HIGHLIGHT, format!("hardware.cpu.{vendor}.updateMicrocode"))],
+        vec![(
+          HIGHLIGHT,
+          "hardware.enableRedistributableFirmware".to_string(),
+        )],
+      ],
+      None => vec![vec![(
+        None,
+        "No Intel or AMD CPU detected, no microcode updates will be enabled.".to_string(),
+      )]],
+    };
+    InfoBox::new("", styled_block(lines))
+  }
+  pub fn display_widget(_installer: &mut Installer) -> Option<Box<dyn ConfigWidget>> {
+    let vendor = detect_cpu_vendor()?;
+    Some(Box::new(Self::vendor_info(Some(&vendor))) as Box<dyn ConfigWidget>)
+  }
+  pub fn page_info<'a>() -> (String, Vec<Line<'a>>) {
+    (
+      "CPU Microcode".to_string(),
+      styled_block(vec![
+        vec![(
+          None,
+          "Microcode updates fix CPU bugs and security issues after the firmware is loaded.",
+        )],
+        vec![(
+          None,
+          "They are enabled automatically for the detected CPU vendor.",
+        )],
+      ]),
+    )
+  }
+}
+
+impl Default for Microcode {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl Page for Microcode {
+  fn render(&mut self, _installer: &mut Installer, f: &mut Frame, area: Rect) {
+    let chunks = split_vert!(
+      area,
+      1,
+      [Constraint::Percentage(50), Constraint::Percentage(50)]
+    );
+    let (_, info) = Self::page_info();
+    InfoBox::new("", info).render(f, chunks[0]);
+    Self::vendor_info(self.vendor.as_deref()).render(f, chunks[1]);
+    self.help_modal.render(f, area);
+  }
+
+  fn get_help_content(&self) -> (String, Vec<Line<'_>>) {
+    let help_content = styled_block(vec![
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Enter, Esc, q"),
+        (None, " - Return to menu"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "?"),
+        (None, " - Show this help"),
+      ],
+      vec![(None, "")],
+      vec![(
+        None,
+        "The vendor is read from /proc/cpuinfo, there is nothing to change here.",
+      )],
+    ]);
+    ("CPU Microcode".to_string(), help_content)
+  }
+
+  fn handle_input(&mut self, _installer: &mut Installer, event: KeyEvent) -> Signal {
+    match event.code {
+      KeyCode::Char('?') => {
+        self.help_modal.toggle();
+        Signal::Wait
+      }
+      code if ui_close!(code) && self.help_modal.visible => {
+        self.help_modal.hide();
+        Signal::Wait
+      }
+      _ if self.help_modal.visible => Signal::Wait,
+      code if ui_close!(code) => Signal::Pop,
+      KeyCode::Enter => Signal::Pop,
+      _ => Signal::Wait,
+    }
+  }
+}
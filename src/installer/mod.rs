@@ -1,8 +1,11 @@
 use std::{
-  collections::VecDeque,
+  collections::{BTreeMap, VecDeque},
   fmt::{Debug, Display},
   io::Write,
   process::{Command, Stdio},
+  sync::{Arc, Mutex},
+  thread,
+  time::{Duration, Instant},
 };
 
 use ansi_to_tui::IntoText;
@@ -12,7 +15,7 @@ use ratatui::{
   layout::{Constraint, Direction, Layout, Rect},
   prelude::Alignment,
   style::{Color, Modifier, Style},
-  text::Line,
+  text::{Line, Span},
   widgets::{Block, Borders, Paragraph, Wrap},
 };
 use serde_json::Value;
@@ -20,34 +23,81 @@ use tempfile::NamedTempFile;
 
 use crate::{
   command,
-  drives::{Disk, DiskItem, part_table},
-  installer::{systempkgs::get_available_pkgs, users::User},
-  nixgen::highlight_nix,
-  split_hor, split_vert, styled_block, ui_back, ui_close, ui_down, ui_enter, ui_left, ui_right,
-  ui_up,
+  drives::{
+    Disk, DiskItem, LuksKey, PartStatus, Partition, booted_with_uefi, bytes_readable, lsblk,
+    part_table, small_partition_warning,
+  },
+  installer::{systempkgs::with_available_pkgs, users::User},
+  keys,
+  nixgen::{Configs, NixParseError, check_nix_syntax, highlight_nix},
+  split_hor, split_vert, styled_block, tools, ui_back, ui_close, ui_down, ui_enter, ui_left,
+  ui_right, ui_up,
   widget::{
-    Button, CheckBox, ConfigWidget, HelpModal, InfoBox, InstallSteps, LineEditor, LogBox,
-    ProgressBar, StrList, WidgetBox, WidgetBoxBuilder,
+    Button, CheckBox, ConfigWidget, ConfirmModal, HelpModal, InfoBox, InstallSteps, InstallSummary,
+    LineEditor, MessageModal, MultiSelectList, PickerViewState, ProgressBar, StepStatus, StrList,
+    TableWidget, WidgetBox, WidgetBoxBuilder,
   },
 };
 
 const HIGHLIGHT: Option<(Color, Modifier)> = Some((Color::Yellow, Modifier::BOLD));
+const SPINNER: [&str; 4] = ["|", "/", "-", "\\"];
 
+pub mod architecture;
+pub mod channel;
+pub mod console;
+pub mod datamounts;
 pub mod drivepages;
+pub mod etcfiles;
+pub mod exportconfig;
+pub mod extramodules;
+pub mod insecure;
+pub mod livesystem;
+pub mod loading;
+pub mod localesettings;
+pub mod menuorder;
+pub mod microcode;
 pub mod networking;
+pub mod overlays;
+pub mod preflight;
+pub mod readme;
+pub mod resume;
+pub mod saveconfig;
+pub mod substituters;
+pub mod sysctl;
 pub mod systempkgs;
 pub mod users;
-use drivepages::Drives;
+pub mod vpn;
+pub mod wifi;
+use architecture::{Architecture, evaluate_for, host_architecture};
+use channel::Channel;
+use console::ConsoleSettings;
+use datamounts::{DataMount, DataMounts};
+use drivepages::{Drives, WipeConfirm};
+use etcfiles::EtcFiles;
+use exportconfig::ExportConfig;
+use extramodules::{ExtraModules, is_flake_ref};
+use insecure::InsecurePackages;
+use localesettings::LocaleSettings;
+use menuorder::MenuOrder;
+use microcode::{Microcode, detect_cpu_vendor};
 use networking::NetworkConfig;
-use systempkgs::SystemPackages;
+use overlays::{OVERLAYS_DEFAULT_NIX, Overlays};
+use preflight::{MissingTools, Preflight, preflight_hosts};
+use readme::{ConfigReadme, generate_readme};
+use saveconfig::{SaveConfig, write_private};
+use substituters::Substituters;
+use sysctl::Sysctl;
+use systempkgs::{SystemPackages, is_unfree};
 use users::UserAccounts;
+use vpn::VpnCfg;
 
 #[derive(Clone, serde::Serialize, serde::Deserialize)]
 pub struct SshCfg {
   pub enable: bool,
   pub port: u16,
   pub password_auth: bool,
-  pub root_login: bool,
+  #[serde(deserialize_with = "RootLogin::deserialize_legacy")]
+  pub root_login: RootLogin,
 }
 
 impl Default for SshCfg {
@@ -56,39 +106,380 @@ impl Default for SshCfg {
       enable: false,
       port: 22,
       password_auth: true,
-      root_login: false,
+      root_login: RootLogin::No,
+    }
+  }
+}
+
+/// sshd's `PermitRootLogin`
+#[derive(Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RootLogin {
+  No,
+  /// Root may log in with a key, but not a password
+  ProhibitPassword,
+  Yes,
+}
+
+impl RootLogin {
+  /// The value sshd_config expects
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      Self::No => "no",
+      Self::ProhibitPassword => "prohibit-password",
+      Self::Yes => "yes",
+    }
+  }
+  pub fn next(self) -> Self {
+    match self {
+      Self::No => Self::ProhibitPassword,
+      Self::ProhibitPassword => Self::Yes,
+      Self::Yes => Self::No,
+    }
+  }
+  /// Saved settings from before root login had three choices, it used to be
+  /// a yes/no toggle
+  fn deserialize_legacy<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+  ) -> Result<Self, D::Error> {
+    #[derive(serde::Deserialize)]
+    #[serde(untagged)]
+    enum Saved {
+      Legacy(bool),
+      Choice(RootLogin),
+    }
+    Ok(
+      match <Saved as serde::Deserialize>::deserialize(deserializer)? {
+        Saved::Legacy(true) => Self::Yes,
+        Saved::Legacy(false) => Self::No,
+        Saved::Choice(choice) => choice,
+      },
+    )
+  }
+}
+
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct SudoCfg {
+  pub enable: bool,
+  pub wheel_needs_password: bool,
+  /// Absolute command paths wheel users may run without a password
+  pub nopasswd_commands: Vec<String>,
+}
+
+impl Default for SudoCfg {
+  fn default() -> Self {
+    Self {
+      enable: true,
+      wheel_needs_password: true,
+      nopasswd_commands: vec![],
+    }
+  }
+}
+
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct InputMethodCfg {
+  /// "fcitx5" or "ibus"
+  pub framework: String,
+  /// Engine ids from `InputMethod::ENGINES`
+  pub engines: Vec<String>,
+}
+
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct AutoUpgradeCfg {
+  pub enable: bool,
+  /// systemd calendar expression, e.g. "daily" or "Sun 04:00"
+  pub dates: String,
+  pub allow_reboot: bool,
+}
+
+impl Default for AutoUpgradeCfg {
+  fn default() -> Self {
+    Self {
+      enable: false,
+      dates: "04:40".to_string(),
+      allow_reboot: false,
+    }
+  }
+}
+
+/// Which parts of the system documentation get installed
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct DocsCfg {
+  /// `documentation.man.enable`
+  pub man: bool,
+  /// `documentation.nixos.enable`, the NixOS manual and `configuration.nix(5)`
+  pub nixos: bool,
+  /// `documentation.dev.enable`, section 2/3 man pages for development
+  pub dev: bool,
+}
+
+impl Default for DocsCfg {
+  /// Same as NixOS
+  fn default() -> Self {
+    Self {
+      man: true,
+      nixos: true,
+      dev: false,
+    }
+  }
+}
+
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct NixBuildCfg {
+  pub max_jobs: u32,
+  pub cores: u32,
+}
+
+/// User supplied tweaks to the install command sequence
+///
+/// There is no UI for this, it is meant to be set in a config file passed to
+/// `--load`. Commands are run with `sh -c` and their output goes to the install
+/// log. The default leaves the sequence exactly as it is.
+#[derive(Default, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct InstallTemplate {
+  /// Run before the disks are partitioned
+  pub pre_install: Vec<String>,
+  /// Extra arguments appended to `nixos-install --root /mnt`, one argument
+  /// per entry. They are quoted, so spaces and `$` reach nixos-install as is
+  pub nixos_install_flags: Vec<String>,
+  /// Run after `nixos-install` succeeds, with the new system still on /mnt
+  pub post_install: Vec<String>,
+}
+
+/// How the live system was booted, which decides the bootloaders the
+/// installed one can use
+#[derive(Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FirmwareMode {
+  Uefi,
+  Bios,
+}
+
+impl FirmwareMode {
+  pub fn detect() -> Self {
+    if booted_with_uefi() {
+      Self::Uefi
+    } else {
+      Self::Bios
+    }
+  }
+}
+
+impl Default for FirmwareMode {
+  /// Detected rather than assumed, so settings loaded from a file still
+  /// describe the machine the installer runs on
+  fn default() -> Self {
+    Self::detect()
+  }
+}
+
+/// Where the installed system gets its swap from
+#[derive(Default, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SwapKind {
+  #[default]
+  None,
+  /// A 4GB file at /swapfile
+  File,
+  /// A `linux-swap` partition from the manual layout, disko sets it up
+  Partition,
+  /// Compressed swap in RAM, `percent` of it at most
+  Zram { percent: u8 },
+}
+
+impl SwapKind {
+  pub const DEFAULT_ZRAM_PERCENT: u8 = 50;
+  /// Options on the swap page, in order
+  pub const CHOICES: [&str; 4] = ["None", "Swapfile", "Swap partition", "Zram"];
+
+  pub fn choice(&self) -> &'static str {
+    match self {
+      Self::None => "None",
+      Self::File => "Swapfile",
+      Self::Partition => "Swap partition",
+      Self::Zram { .. } => "Zram",
+    }
+  }
+  pub fn from_choice(choice: &str) -> Option<Self> {
+    match choice {
+      "None" => Some(Self::None),
+      "Swapfile" => Some(Self::File),
+      "Swap partition" => Some(Self::Partition),
+      "Zram" => Some(Self::Zram {
+        percent: Self::DEFAULT_ZRAM_PERCENT,
+      }),
+      _ => None,
+    }
+  }
+  /// Saved settings from before swap kinds only had `use_swap: bool`, which
+  /// meant a swapfile
+  fn deserialize_legacy<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+  ) -> Result<Self, D::Error> {
+    #[derive(serde::Deserialize)]
+    #[serde(untagged)]
+    enum Saved {
+      Legacy(bool),
+      Kind(SwapKind),
+    }
+    Ok(
+      match <Saved as serde::Deserialize>::deserialize(deserializer)? {
+        Saved::Legacy(true) => Self::File,
+        Saved::Legacy(false) => Self::None,
+        Saved::Kind(kind) => kind,
+      },
+    )
+  }
+}
+
+impl Display for SwapKind {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Self::None => write!(f, "disabled"),
+      Self::File => write!(f, "swapfile"),
+      Self::Partition => write!(f, "swap partition"),
+      Self::Zram { percent } => write!(f, "zram, {percent}% of RAM"),
     }
   }
 }
 
 #[derive(Default, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 pub struct Installer {
   pub flake_path: Option<String>,
   pub language: Option<String>,
+  /// XKB layout name, e.g. "us" or "gb"
   pub keyboard_layout: Option<String>,
+  /// XKB variant of `keyboard_layout`, e.g. "dvorak"
+  pub keyboard_variant: Option<String>,
+  /// `console.font`, the kernel's default when unset
+  pub console_font: Option<String>,
   pub locale: Option<String>,
+  /// `i18n.extraLocaleSettings`, e.g. LC_TIME to a locale other than `locale`
+  pub locale_settings: BTreeMap<String, String>,
+  /// Locales installed besides the ones in use, for `i18n.supportedLocales`
+  pub extra_locales: Vec<String>,
   pub enable_flakes: bool,
+  /// `nix-command` without flakes, for `nix shell` and `nix run`. Flakes
+  /// already imply it.
+  pub enable_nix_command: bool,
+  /// `nixpkgs.config.allowUnfree`, switched on by itself when something
+  /// unfree is picked, see [`Installer::unfree_selections`]
+  pub allow_unfree: bool,
+  /// Channel for non-flake installs, e.g. `nixos-25.05`. Also decides
+  /// `system.stateVersion`.
+  pub channel: Option<String>,
+  /// Nix system double the config is for, when it isn't the host's
+  pub architecture: Option<String>,
   pub bootloader: Option<String>,
-  pub use_swap: bool,
+  /// Whole disk GRUB is installed to on BIOS systems, the install target if
+  /// unset
+  pub grub_device: Option<String>,
+  /// Checked once when the installer starts, see [`FirmwareMode::default`]
+  #[serde(skip)]
+  pub firmware_mode: FirmwareMode,
+  /// `intel` or `amd`, filled in from `/proc/cpuinfo` when the config is
+  /// generated
+  #[serde(skip)]
+  pub cpu_vendor: Option<String>,
+  #[serde(alias = "use_swap", deserialize_with = "SwapKind::deserialize_legacy")]
+  pub swap: SwapKind,
   pub root_passwd_hash: Option<String>, // Hashed
+  /// Lock the root account and rely on a wheel user with sudo instead
+  pub disable_root: bool,
+  /// Leave passwords to `passwd` after install instead of keeping hashes in
+  /// the config. Any password set here only becomes the initial one.
+  pub mutable_users: bool,
   pub users: Vec<User>,
   pub profile: Option<String>,
   pub hostname: Option<String>,
   pub kernels: Option<Vec<String>>,
   pub audio_backend: Option<String>,
+  pub bluetooth: bool,
+  /// Blueman, only used with desktops that don't manage Bluetooth themselves
+  pub bluetooth_manager: bool,
+  /// One of [`GPU_DRIVERS`]
+  pub gpu_driver: Option<String>,
   pub greeter: Option<String>,
   pub system_pkgs: Vec<String>,
+  /// Extra files for `environment.etc`, keyed by path relative to /etc
+  pub etc_files: BTreeMap<String, String>,
+  /// Extra `boot.kernel.sysctl` settings, keyed by sysctl name
+  pub sysctl: BTreeMap<String, String>,
+  /// Create /etc/nixos/overlays and load it through `nixpkgs.overlays`
+  pub overlays_scaffold: bool,
+  /// Paths or flake references added to `imports`
+  pub extra_imports: Vec<String>,
+  /// Overlay files or flake references added to `nixpkgs.overlays`
+  pub overlays: Vec<String>,
+  /// `name-version` of packages allowed despite being marked insecure
+  pub permitted_insecure: Vec<String>,
+  /// Extra binary caches as (url, public key), cache.nixos.org stays enabled
+  pub substituters: Vec<(String, String)>,
   pub desktop_environment: Option<String>,
+  /// Default apps of the desktop to leave out, as nixpkgs attribute paths
+  pub desktop_excludes: Vec<String>,
   pub network_backend: Option<String>,
   pub ssh_config: Option<SshCfg>,
+  pub vpn: VpnCfg,
+  pub sudo_config: Option<SudoCfg>,
+  pub nix_build: Option<NixBuildCfg>,
+  pub boot_options: Option<BootOptionsCfg>,
+  pub auto_upgrade: Option<AutoUpgradeCfg>,
+  pub documentation: DocsCfg,
+  /// Don't write a README.md explaining the config into /etc/nixos
+  pub skip_readme: bool,
+  pub input_method: Option<InputMethodCfg>,
   pub timezone: Option<String>,
 
   pub drives: Vec<Disk>,
 
   pub drive_config: Option<Disk>,
+  /// Disks partitioned alongside `drive_config`, e.g. for a separate /home
+  pub extra_disks: Vec<Disk>,
+  /// Index into `extra_disks` of the disk the partitioning pages work on,
+  /// `None` while that is `drive_config`
+  #[serde(skip)]
+  pub editing_disk: Option<usize>,
   pub use_auto_drive_config: bool,
+  /// Filesystem pre-selected wherever one is picked, ext4 if unset
+  pub preferred_fs: Option<String>,
 
   pub drive_config_display: Option<Vec<DiskItem>>,
+  /// Existing partitions mounted as-is, outside the disko layout
+  pub data_mounts: Vec<DataMount>,
+  /// Commands that release LVM/RAID/LUKS layers on the target drive, run
+  /// before partitioning
+  pub storage_teardown: Vec<String>,
+
+  /// Filter and cursor positions of the system packages page, kept for when
+  /// it's reopened
+  #[serde(skip)]
+  pub system_pkgs_view: Option<PickerViewState>,
+
+  pub install_template: InstallTemplate,
+
+  /// Set once the install has reached the disk partitioning step, so that a
+  /// fatal error afterwards knows there may be mounts under /mnt to clean up
+  #[serde(skip)]
+  pub partitioning_started: bool,
+
+  /// Config files written by the preview, so the install runs exactly what
+  /// was shown there
+  #[serde(skip)]
+  pub staged_configs: Option<Arc<StagedConfigs>>,
+
+  /// File the settings were last loaded from or saved to, offered again by
+  /// the save page
+  #[serde(skip)]
+  pub save_path: Option<String>,
+
+  /// Directory given to `--dry-run`. The install only echoes what it would
+  /// run and copies the generated files here instead of /mnt
+  #[serde(skip)]
+  pub dry_run: Option<String>,
 
   /// Used as an escape hatch for inter-page communication
   /// If you can't find a good way to pass a value from one page to another
@@ -101,11 +492,510 @@ impl Installer {
     Self::default()
   }
 
+  /// Picked packages and drivers that won't build without allowing unfree
+  /// packages
+  pub fn unfree_selections(&self) -> Vec<String> {
+    let mut unfree = self
+      .system_pkgs
+      .iter()
+      .filter(|pkg| is_unfree(pkg))
+      .cloned()
+      .collect::<Vec<_>>();
+    if let Some(driver) = self.gpu_driver.as_ref().filter(|d| is_unfree_gpu_driver(d)) {
+      unfree.push(driver.clone());
+    }
+    unfree
+  }
+
+  pub fn channel(&self) -> &str {
+    self.channel.as_deref().unwrap_or(channel::DEFAULT_CHANNEL)
+  }
+
+  /// GRUB on a GPT disk in BIOS mode has nowhere to put its core image
+  /// without a `bios_grub` partition
+  pub fn needs_bios_grub(&self) -> bool {
+    self
+      .bootloader
+      .as_deref()
+      .is_some_and(|loader| loader.eq_ignore_ascii_case("grub"))
+      && self.firmware_mode == FirmwareMode::Bios
+  }
+
+  /// systemd-boot is an EFI application, a machine booted in BIOS mode can't
+  /// run it. Settings loaded from a file can still ask for it.
+  pub fn bootloader_needs_uefi(&self) -> bool {
+    self
+      .bootloader
+      .as_deref()
+      .is_some_and(|loader| loader.eq_ignore_ascii_case("systemd-boot"))
+      && self.firmware_mode == FirmwareMode::Bios
+  }
+
+  pub fn architecture(&self) -> String {
+    self.architecture.clone().unwrap_or_else(host_architecture)
+  }
+
+  pub fn preferred_fs(&self) -> &str {
+    self.preferred_fs.as_deref().unwrap_or("ext4")
+  }
+
   pub fn has_all_requirements(&self) -> bool {
-    self.root_passwd_hash.is_some()
+    self.has_root_access()
       && !self.users.is_empty()
       && self.drive_config.is_some()
+      && !self.all_disks().any(|d| d.luks_missing_passphrase())
       && self.bootloader.is_some()
+      && !self.bootloader_needs_uefi()
+  }
+  /// Combinations of options that are allowed but probably not what the user
+  /// wants. These don't block the install, they're only shown as warnings.
+  pub fn config_warnings(&self) -> Vec<String> {
+    let mut warnings = vec![];
+    let desktop = self
+      .desktop_environment
+      .as_deref()
+      .filter(|de| *de != "None");
+    let greeter = self.greeter.as_deref().filter(|g| *g != "None");
+    match (desktop, greeter) {
+      (None, Some(greeter)) => warnings.push(format!(
+        "{greeter} is selected but there is no desktop environment, it will have no session to log into"
+      )),
+      (Some(desktop), None) if self.greeter.is_some() => warnings.push(format!(
+        "{desktop} is selected without a greeter, it will have to be started manually after logging in"
+      )),
+      _ => {}
+    }
+    let headless = self.desktop_environment.is_none()
+      || matches!(self.profile.as_deref(), Some("Server" | "Minimal"));
+    let ssh_enabled = self.ssh_config.as_ref().is_some_and(|ssh| ssh.enable);
+    if self.profile.is_some() && headless && !ssh_enabled {
+      warnings.push(
+        "There is no desktop and SSH is off, enabling SSH under Network is recommended for headless installs"
+          .to_string(),
+      );
+    }
+    if let Some(ref ssh) = self.ssh_config
+      && ssh.enable
+      && !ssh.password_auth
+      && self.users.iter().all(|u| u.ssh_keys.is_empty())
+    {
+      warnings.push(
+        "SSH password authentication is disabled and no user has an authorized key, nobody will be able to log in over SSH"
+          .to_string(),
+      );
+    }
+    let wg = &self.vpn.wireguard;
+    if wg.enable && (wg.ips.is_empty() || wg.peers.is_empty()) {
+      warnings.push(format!(
+        "WireGuard is enabled but {} has no addresses or no peers, the tunnel won't carry any traffic",
+        wg.interface
+      ));
+    }
+    let flake_refs = self
+      .extra_imports
+      .iter()
+      .chain(&self.overlays)
+      .any(|entry| is_flake_ref(entry));
+    if flake_refs && !self.enable_flakes {
+      warnings.push(
+        "Extra modules include flake references, but flakes are not enabled so they can't be fetched"
+          .to_string(),
+      );
+    }
+    let esps = self
+      .all_disks()
+      .flat_map(|d| d.partitions())
+      .filter(|p| *p.status() != PartStatus::Delete && p.has_flag("esp"))
+      .count();
+    if esps > 1 {
+      warnings.push(format!(
+        "{esps} partitions are marked as ESP, the bootloader only installs to the one mounted at /boot"
+      ));
+    }
+    if let Some(ref disk) = self.drive_config {
+      let live = |p: &&Partition| *p.status() != PartStatus::Delete;
+      let boot = disk
+        .partitions()
+        .filter(live)
+        .find(|p| p.mount_point() == Some("/boot"));
+      let root = disk
+        .partitions()
+        .filter(live)
+        .find(|p| p.mount_point() == Some("/"));
+      match (boot, root) {
+        (Some(boot), _) if boot.luks().is_some() && !boot.has_flag("esp") => warnings.push(
+          "/boot is encrypted, the bootloader won't be able to read the kernel from it"
+            .to_string(),
+        ),
+        (None, Some(root)) if root.luks().is_some() => warnings.push(
+          "/ is encrypted but there is no separate /boot partition, the bootloader won't be able to read the kernel"
+            .to_string(),
+        ),
+        _ => {}
+      }
+      if self.swap == SwapKind::Partition && !disk.has_swap_partition() {
+        warnings.push(
+          "Swap is set to use a partition but the layout has no linux-swap partition".to_string(),
+        );
+      }
+      if self.needs_bios_grub() && !disk.has_bios_grub() {
+        warnings.push(
+          "GRUB is selected on a BIOS system but no partition is flagged bios_grub, the disk won't boot"
+            .to_string(),
+        );
+      }
+      for part in disk
+        .partitions()
+        .filter(|p| *p.status() != PartStatus::Delete)
+      {
+        let Some(mount_point) = part.mount_point() else {
+          continue;
+        };
+        warnings.extend(small_partition_warning(
+          mount_point,
+          part.size_bytes(disk.sector_size()),
+        ));
+      }
+    }
+    if !self.mutable_users {
+      for user in self.users.iter().filter(|u| u.password_hash.is_empty()) {
+        warnings.push(format!(
+          "{} has no password and passwords are managed declaratively, they won't be able to log in",
+          user.username
+        ));
+      }
+    }
+    warnings
+  }
+  /// Things that aren't required to install, but are easy to forget and
+  /// awkward to fix afterwards
+  pub fn soft_warnings(&self) -> Vec<String> {
+    let mut warnings = vec![];
+    if self.timezone.is_none() {
+      warnings.push("No timezone is set, the system clock will be shown in UTC".to_string());
+    }
+    if self.locale.is_none() {
+      warnings.push("No locale is set, the NixOS default (en_US.UTF-8) will be used".to_string());
+    }
+    if self.network_backend.is_none() {
+      warnings.push(
+        "No network backend is set, the installed system may have no way to get online".to_string(),
+      );
+    }
+    warnings.extend(self.config_warnings());
+    warnings
+  }
+  /// Chinese, Japanese and Korean need an input method to type in
+  pub fn has_cjk_locale(&self) -> bool {
+    self
+      .locale
+      .as_deref()
+      .is_some_and(|l| ["zh_", "ja_", "ko_"].iter().any(|p| l.starts_with(p)))
+  }
+  /// Whether at least one user is in the wheel group and can use sudo
+  pub fn has_admin_user(&self) -> bool {
+    self
+      .users
+      .iter()
+      .any(|user| user.groups.iter().any(|g| g == "wheel"))
+  }
+  /// Either a root password is set, or root is locked and an admin user exists
+  pub fn has_root_access(&self) -> bool {
+    if self.disable_root {
+      self.has_admin_user()
+    } else {
+      self.root_passwd_hash.is_some()
+    }
+  }
+  /// Every choice that was made, as plain language label/value pairs
+  ///
+  /// Shown before the config preview and written to the README. Settings
+  /// left at their defaults are skipped.
+  pub fn summary(&self) -> Vec<(String, String)> {
+    let mut settings = vec![];
+    let mut push = |label: &str, value: Option<String>| {
+      if let Some(value) = value {
+        settings.push((label.to_string(), value));
+      }
+    };
+    push("Hostname", self.hostname.clone());
+    push("Locale", self.locale.clone());
+    push(
+      "Locale overrides",
+      (!self.locale_settings.is_empty()).then(|| {
+        self
+          .locale_settings
+          .iter()
+          .map(|(category, locale)| format!("{category} {locale}"))
+          .collect::<Vec<_>>()
+          .join(", ")
+      }),
+    );
+    push(
+      "Extra locales",
+      (!self.extra_locales.is_empty()).then(|| self.extra_locales.join(", ")),
+    );
+    push(
+      "Keyboard layout",
+      self
+        .keyboard_layout
+        .as_ref()
+        .map(|layout| match self.keyboard_variant {
+          Some(ref variant) => format!("{layout} ({variant})"),
+          None => layout.clone(),
+        }),
+    );
+    push("Timezone", self.timezone.clone());
+    push("Drive", self.drive_summary());
+    let data_mounts = self
+      .data_mounts
+      .iter()
+      .map(|m| format!("{} at {}", m.device, m.mount_point))
+      .collect::<Vec<_>>();
+    push(
+      "Data mounts",
+      (!data_mounts.is_empty()).then(|| data_mounts.join(", ")),
+    );
+    push("Bootloader", self.bootloader.clone());
+    push(
+      "Boot menu",
+      self
+        .boot_options
+        .as_ref()
+        .map(|cfg| match cfg.configuration_limit {
+          Some(n) => format!("{}s timeout, {n} generations", cfg.timeout),
+          None => format!("{}s timeout", cfg.timeout),
+        }),
+    );
+    push(
+      "Swap",
+      (self.swap != SwapKind::None).then(|| self.swap.to_string()),
+    );
+    push(
+      "Kernels",
+      self.kernels.as_ref().map(|k| match k.as_slice() {
+        [] => "none, custom kernel".to_string(),
+        k => k.join(", "),
+      }),
+    );
+    push("Desktop environment", self.desktop_environment.clone());
+    push(
+      "Desktop apps left out",
+      (!self.desktop_excludes.is_empty()).then(|| self.desktop_excludes.len().to_string()),
+    );
+    push("Greeter", self.greeter.clone());
+    push("Audio", self.audio_backend.clone());
+    push("Bluetooth", self.bluetooth.then(|| "enabled".to_string()));
+    push("Graphics", self.gpu_driver.clone());
+    push("Networking", self.network_backend.clone());
+    push(
+      "Input method",
+      self.input_method.as_ref().map(|im| im.framework.clone()),
+    );
+    push("Flakes", self.enable_flakes.then(|| "enabled".to_string()));
+    push(
+      "Unfree packages",
+      self.allow_unfree.then(|| "allowed".to_string()),
+    );
+    push("Channel", self.channel.clone());
+    push("Architecture", self.architecture.clone());
+    push(
+      "SSH server",
+      self
+        .ssh_config
+        .as_ref()
+        .filter(|cfg| cfg.enable)
+        .map(|cfg| {
+          format!(
+            "enabled on port {}, root login {}",
+            cfg.port,
+            cfg.root_login.as_str()
+          )
+        }),
+    );
+    push(
+      "Tailscale",
+      self.vpn.tailscale.then(|| "enabled".to_string()),
+    );
+    push(
+      "WireGuard",
+      self.vpn.wireguard.enable.then(|| {
+        let wg = &self.vpn.wireguard;
+        format!("{} with {} peers", wg.interface, wg.peers.len())
+      }),
+    );
+    push(
+      "Automatic upgrades",
+      self
+        .auto_upgrade
+        .as_ref()
+        .filter(|cfg| cfg.enable)
+        .map(|cfg| format!("enabled, runs at `{}`", cfg.dates)),
+    );
+    push(
+      "Root account",
+      self
+        .disable_root
+        .then(|| "locked, use sudo from an admin user".to_string()),
+    );
+    push(
+      "Passwords",
+      self
+        .mutable_users
+        .then(|| "managed with passwd after install".to_string()),
+    );
+    let users = self
+      .users
+      .iter()
+      .map(|user| {
+        if user.groups.is_empty() {
+          format!("`{}`", user.username)
+        } else {
+          format!("`{}` ({})", user.username, user.groups.join(", "))
+        }
+      })
+      .collect::<Vec<_>>();
+    push("Users", (!users.is_empty()).then(|| users.join(", ")));
+    push(
+      "System packages",
+      (!self.system_pkgs.is_empty()).then(|| self.system_pkgs.len().to_string()),
+    );
+    let etc_files = self.etc_files.keys().cloned().collect::<Vec<_>>();
+    push(
+      "Extra /etc files",
+      (!etc_files.is_empty()).then(|| etc_files.join(", ")),
+    );
+    push(
+      "Kernel sysctl",
+      (!self.sysctl.is_empty()).then(|| format!("{} settings", self.sysctl.len())),
+    );
+    push(
+      "Overlays",
+      self
+        .overlays_scaffold
+        .then(|| "loaded from /etc/nixos/overlays".to_string()),
+    );
+    push(
+      "Extra modules",
+      (!self.extra_imports.is_empty() || !self.overlays.is_empty()).then(|| {
+        format!(
+          "{} imports, {} overlays",
+          self.extra_imports.len(),
+          self.overlays.len()
+        )
+      }),
+    );
+    push(
+      "Insecure packages allowed",
+      (!self.permitted_insecure.is_empty()).then(|| self.permitted_insecure.join(", ")),
+    );
+    push(
+      "Binary caches",
+      (!self.substituters.is_empty()).then(|| {
+        self
+          .substituters
+          .iter()
+          .map(|(url, _)| url.as_str())
+          .collect::<Vec<_>>()
+          .join(", ")
+      }),
+    );
+    settings
+  }
+  /// e.g. "/dev/nvme0n1 wiped, btrfs /, vfat /boot"
+  fn drive_summary(&self) -> Option<String> {
+    let drive = self.drive_config.as_ref()?;
+    let keeps_data = drive
+      .partitions()
+      .any(|p| matches!(p.status(), PartStatus::Exists));
+    let mut parts = vec![if keeps_data {
+      format!("/dev/{} (existing partitions kept)", drive.name())
+    } else {
+      format!("/dev/{} wiped", drive.name())
+    }];
+    parts.extend(
+      drive
+        .partitions()
+        .filter(|p| !matches!(p.status(), PartStatus::Delete))
+        .filter_map(|p| {
+          let mount_point = p.mount_point()?;
+          Some(format!("{} {mount_point}", p.fs_type().unwrap_or("?")))
+        }),
+    );
+    for disk in &self.extra_disks {
+      parts.push(format!("/dev/{} added", disk.name()));
+    }
+    Some(parts.join(", "))
+  }
+  /// Whether any existing partition is kept as is, so the disks can't be
+  /// wiped wholesale
+  pub fn keeps_partitions(&self) -> bool {
+    self
+      .all_disks()
+      .flat_map(|d| d.partitions())
+      .any(|p| *p.status() == PartStatus::Exists)
+  }
+  /// The install target followed by any additional disks
+  pub fn all_disks(&self) -> impl Iterator<Item = &Disk> {
+    self.drive_config.iter().chain(self.extra_disks.iter())
+  }
+  /// The disk the partitioning pages are working on
+  pub fn active_disk(&self) -> Option<&Disk> {
+    match self.editing_disk {
+      Some(idx) => self.extra_disks.get(idx),
+      None => self.drive_config.as_ref(),
+    }
+  }
+  pub fn active_disk_mut(&mut self) -> Option<&mut Disk> {
+    match self.editing_disk {
+      Some(idx) => self.extra_disks.get_mut(idx),
+      None => self.drive_config.as_mut(),
+    }
+  }
+  /// Replace the disk the partitioning pages are working on
+  pub fn set_active_disk(&mut self, disk: Disk) {
+    match self
+      .editing_disk
+      .and_then(|idx| self.extra_disks.get_mut(idx))
+    {
+      Some(extra) => *extra = disk,
+      None => self.drive_config = Some(disk),
+    }
+  }
+  /// Mount points used on any disk, leaving out deleted partitions and the
+  /// partition `except`
+  pub fn taken_mount_points(&self, except: Option<u64>) -> Vec<String> {
+    self
+      .all_disks()
+      .flat_map(|d| d.partitions())
+      .filter(|p| *p.status() != PartStatus::Delete && Some(p.id()) != except)
+      .filter_map(|p| p.mount_point().map(|mp| mp.to_string()))
+      .collect()
+  }
+  /// Pick a profile and fill in the settings it implies. Anything already set
+  /// is left alone, so earlier choices survive switching profiles
+  pub fn set_profile(&mut self, profile: &str) {
+    self.profile = Some(profile.to_string());
+    match profile {
+      "Server" => {
+        self
+          .network_backend
+          .get_or_insert_with(|| "systemd-networkd".to_string());
+        self.ssh_config.get_or_insert_with(|| SshCfg {
+          enable: true,
+          ..Default::default()
+        });
+      }
+      "Desktop" => {
+        self
+          .network_backend
+          .get_or_insert_with(|| "NetworkManager".to_string());
+        self
+          .audio_backend
+          .get_or_insert_with(|| "PipeWire".to_string());
+      }
+      _ => {}
+    }
   }
   pub fn make_drive_config_display(&mut self) {
     let Some(drive) = &self.drive_config else {
@@ -115,7 +1005,22 @@ impl Installer {
     self.drive_config_display = Some(drive.layout().to_vec())
   }
 
+  /// The flake `system.autoUpgrade` rebuilds from, if the system uses one
+  ///
+  /// A generated flake ends up in /etc/nixos on the new system. A local flake
+  /// path only exists on the live system, so only remote references are kept.
+  fn upgrade_flake(&self) -> Option<String> {
+    match self.flake_path {
+      Some(ref path) => is_flake_ref(path).then(|| path.clone()),
+      None => self.enable_flakes.then(|| {
+        let hostname = self.hostname.as_deref().unwrap_or("nixos");
+        format!("/etc/nixos#{hostname}")
+      }),
+    }
+  }
+
   pub fn to_json(&mut self) -> anyhow::Result<serde_json::Value> {
+    self.cpu_vendor = detect_cpu_vendor();
     // Create the installer configuration JSON
     // This is used as an intermediate representation before being serialized into
     // Nix
@@ -123,25 +1028,83 @@ impl Installer {
       "hostname": self.hostname,
       "language": self.language,
       "keyboard_layout": self.keyboard_layout,
+      "keyboard_variant": self.keyboard_variant,
       "locale": self.locale,
+      "console_font": self.console_font,
+      "locale_settings": self.locale_settings,
+      "extra_locales": self.extra_locales,
       "timezone": self.timezone,
       "enable_flakes": self.enable_flakes,
+      "enable_nix_command": self.enable_nix_command && !self.enable_flakes,
+      "allow_unfree": self.allow_unfree,
+      "state_version": channel::state_version(self.channel()),
+      "architecture": self.architecture,
       "bootloader": self.bootloader,
-      "use_swap": self.use_swap,
+      "grub_device": self.grub_device,
+      "firmware_mode": self.firmware_mode,
+      "cpu_vendor": self.cpu_vendor,
+      "swap": self.swap,
       "profile": self.profile,
-      "root_passwd_hash": self.root_passwd_hash,
+      "root_passwd_hash": if self.disable_root { None } else { self.root_passwd_hash.clone() },
+      "disable_root": self.disable_root,
+      "mutable_users": self.mutable_users,
       "audio_backend": self.audio_backend,
+      "bluetooth": self.bluetooth,
+      "bluetooth_manager": self.bluetooth
+        && self.bluetooth_manager
+        && self.desktop_environment.as_deref().is_some_and(needs_bluetooth_manager),
+      "gpu_driver": self.gpu_driver,
       "greeter": self.greeter,
       "desktop_environment": self.desktop_environment,
+      "desktop_excludes": self.desktop_environment.as_ref().map(|desktop| serde_json::json!({
+        "desktop": desktop,
+        "packages": self.desktop_excludes,
+      })),
       "network_backend": self.network_backend,
       "ssh_config": self.ssh_config,
+      "vpn": self.vpn,
+      "sudo_config": self.sudo_config.clone().map(|mut cfg| {
+        // Locking root without sudo would leave no way to administer the system
+        if self.disable_root {
+          cfg.enable = true;
+        }
+        cfg
+      }),
       "system_pkgs": self.system_pkgs,
+      "etc_files": self.etc_files,
+      "data_mounts": self.data_mounts,
+      "sysctl": self.sysctl,
+      "overlays_scaffold": self.overlays_scaffold,
+      "extra_imports": self.extra_imports,
+      "overlays": self.overlays,
+      "permitted_insecure": self.permitted_insecure,
+      "substituters": self.substituters,
+      "nix_build": self.nix_build,
+      "boot_options": self.boot_options,
+      "input_method": self.input_method,
+      "documentation": self.documentation,
+      "auto_upgrade": self.auto_upgrade.as_ref().map(|cfg| serde_json::json!({
+        "enable": cfg.enable,
+        "dates": cfg.dates,
+        "allow_reboot": cfg.allow_reboot,
+        "flake": self.upgrade_flake(),
+        "update_nixpkgs": self.flake_path.is_none(),
+      })),
       "users": self.users,
       "kernels": self.kernels
     });
 
     // drive configuration if present
-    let disko_cfg = self.drive_config.as_mut().map(|d| d.as_disko_cfg());
+    let disko_cfg = self.drive_config.as_mut().map(|d| d.as_disko_cfg("main"));
+    let extra_disks = self
+      .extra_disks
+      .iter_mut()
+      .map(|d| {
+        let name = d.name().to_string();
+        let cfg = d.as_disko_cfg(&name);
+        (name, cfg)
+      })
+      .collect::<serde_json::Map<_, _>>();
 
     // flake configuration if using flakes
     let flake_path = self.flake_path.clone();
@@ -149,7 +1112,9 @@ impl Installer {
     let config = serde_json::json!({
       "config": sys_config,
       "disko": disko_cfg,
+      "extra_disks": extra_disks,
       "flake_path": flake_path,
+      "channel": self.channel(),
     });
 
     Ok(config)
@@ -166,9 +1131,11 @@ pub enum Signal {
   Push(Box<dyn Page>),
   Pop,
   PopCount(usize),
+  Replace(Box<dyn Page>), // Swap the current page for another
   Quit,
   WriteCfg,
   Unwind,               // Pop until we get back to the menu
+  Suspend(Command),     // Leave the TUI to run an interactive command
   Error(anyhow::Error), // Propagates errors
 }
 
@@ -179,9 +1146,11 @@ impl Debug for Signal {
       Self::Push(_) => write!(f, "Signal::Push"),
       Self::Pop => write!(f, "Signal::Pop"),
       Self::PopCount(n) => write!(f, "Signal::PopCount({n})"),
+      Self::Replace(_) => write!(f, "Signal::Replace"),
       Self::Quit => write!(f, "Signal::Quit"),
       Self::WriteCfg => write!(f, "Signal::WriteCfg"),
       Self::Unwind => write!(f, "Signal::Unwind"),
+      Self::Suspend(cmd) => write!(f, "Signal::Suspend({cmd:?})"),
       Self::Error(err) => write!(f, "Signal::Error({err})"),
     }
   }
@@ -197,11 +1166,32 @@ pub trait Page {
     )
   }
 
+  /// Whether '?' opens a help modal here, the header hint is hidden if not
+  fn has_help(&self) -> bool {
+    true
+  }
+
   /// This is used as an escape hatch for pages that need to send a signal
   /// without user input This method is called on every redraw
   fn signal(&self) -> Option<Signal> {
     None
   }
+
+  /// Pasted text, delivered in one piece by bracketed paste
+  ///
+  /// By default it's handled like typing it, stopping at the first key that
+  /// does more than wait. Pages with a text field override this to insert
+  /// the whole string, so letters that double as shortcuts can't fire.
+  fn handle_paste(&mut self, installer: &mut Installer, text: &str) -> Signal {
+    for c in text.chars().filter(|c| !c.is_control()) {
+      let key = KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE);
+      match self.handle_input(installer, key) {
+        Signal::Wait => {}
+        signal => return signal,
+      }
+    }
+    Signal::Wait
+  }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -209,22 +1199,43 @@ pub enum MenuPages {
   SourceFlake,
   Language,
   KeyboardLayout,
+  Console,
   Locale,
+  InputMethod,
   EnableFlakes,
+  Channel,
+  Architecture,
+  Microcode,
   Drives,
+  DataMounts,
   Bootloader,
+  BootOptions,
   Swap,
   Hostname,
+  BuildJobs,
+  Substituters,
+  AutoUpgrade,
+  Documentation,
   RootPassword,
+  PasswordManagement,
+  Sudo,
   UserAccounts,
   Profile,
   Greeter,
   DesktopEnvironment,
   Audio,
+  Bluetooth,
+  Graphics,
   Kernels,
   SystemPackages,
+  EtcFiles,
+  Sysctl,
+  Overlays,
+  ExtraModules,
+  InsecurePackages,
   Network,
   Timezone,
+  ConfigReadme,
 }
 
 impl MenuPages {
@@ -233,40 +1244,99 @@ impl MenuPages {
       MenuPages::SourceFlake,
       MenuPages::Language,
       MenuPages::KeyboardLayout,
+      MenuPages::Console,
       MenuPages::Locale,
+      MenuPages::InputMethod,
       MenuPages::EnableFlakes,
+      MenuPages::Channel,
+      MenuPages::Architecture,
+      MenuPages::Microcode,
       MenuPages::Drives,
+      MenuPages::DataMounts,
       MenuPages::Bootloader,
+      MenuPages::BootOptions,
       MenuPages::Swap,
       MenuPages::Hostname,
+      MenuPages::BuildJobs,
+      MenuPages::Substituters,
+      MenuPages::AutoUpgrade,
+      MenuPages::Documentation,
       MenuPages::RootPassword,
+      MenuPages::PasswordManagement,
+      MenuPages::Sudo,
       MenuPages::UserAccounts,
       MenuPages::Profile,
       MenuPages::Greeter,
       MenuPages::DesktopEnvironment,
       MenuPages::Audio,
+      MenuPages::Bluetooth,
+      MenuPages::Graphics,
       MenuPages::Kernels,
       MenuPages::SystemPackages,
+      MenuPages::EtcFiles,
+      MenuPages::Sysctl,
+      MenuPages::Overlays,
+      MenuPages::ExtraModules,
+      MenuPages::InsecurePackages,
       MenuPages::Network,
       MenuPages::Timezone,
+      MenuPages::ConfigReadme,
     ]
   }
+  /// Some pages only make sense given other choices, e.g. input methods for
+  /// CJK locales
+  pub fn is_visible(&self, installer: &Installer) -> bool {
+    match self {
+      MenuPages::InputMethod => installer.has_cjk_locale() || installer.input_method.is_some(),
+      // A source flake pins its own nixpkgs
+      MenuPages::Channel => installer.flake_path.is_none(),
+      _ => true,
+    }
+  }
+  pub fn visible_pages(installer: &Installer) -> Vec<MenuPages> {
+    Self::supported_pages()
+      .iter()
+      .copied()
+      .filter(|page| page.is_visible(installer))
+      .collect()
+  }
   pub fn supported_pages() -> &'static [MenuPages] {
     &[
       MenuPages::KeyboardLayout,
+      MenuPages::Console,
       MenuPages::Locale,
+      MenuPages::InputMethod,
       MenuPages::EnableFlakes,
+      MenuPages::Channel,
+      MenuPages::Architecture,
+      MenuPages::Microcode,
       MenuPages::Drives,
+      MenuPages::DataMounts,
       MenuPages::Bootloader,
+      MenuPages::BootOptions,
       MenuPages::Swap,
       MenuPages::Hostname,
+      MenuPages::BuildJobs,
+      MenuPages::Substituters,
+      MenuPages::AutoUpgrade,
+      MenuPages::Documentation,
       MenuPages::RootPassword,
+      MenuPages::PasswordManagement,
+      MenuPages::Sudo,
       MenuPages::UserAccounts,
       MenuPages::DesktopEnvironment,
       MenuPages::Audio,
+      MenuPages::Bluetooth,
+      MenuPages::Graphics,
       MenuPages::SystemPackages,
+      MenuPages::EtcFiles,
+      MenuPages::Sysctl,
+      MenuPages::Overlays,
+      MenuPages::ExtraModules,
+      MenuPages::InsecurePackages,
       MenuPages::Network,
       MenuPages::Timezone,
+      MenuPages::ConfigReadme,
     ]
   }
 }
@@ -277,22 +1347,43 @@ impl Display for MenuPages {
       MenuPages::SourceFlake => "Source Flake",
       MenuPages::Language => "Language",
       MenuPages::KeyboardLayout => "Keyboard Layout",
+      MenuPages::Console => "Console",
       MenuPages::Locale => "Locale",
+      MenuPages::InputMethod => "Input Method",
       MenuPages::EnableFlakes => "Enable Flakes",
+      MenuPages::Channel => "Release Channel",
+      MenuPages::Architecture => "Architecture",
+      MenuPages::Microcode => "CPU Microcode",
       MenuPages::Drives => "Drives",
+      MenuPages::DataMounts => "Data Mounts",
       MenuPages::Bootloader => "Bootloader",
+      MenuPages::BootOptions => "Boot Options",
       MenuPages::Swap => "Swap",
       MenuPages::Hostname => "Hostname",
+      MenuPages::BuildJobs => "Build Jobs",
+      MenuPages::Substituters => "Binary Caches",
+      MenuPages::AutoUpgrade => "Automatic Upgrades",
+      MenuPages::Documentation => "Documentation",
       MenuPages::RootPassword => "Root Password",
+      MenuPages::PasswordManagement => "Password Management",
+      MenuPages::Sudo => "Sudo",
       MenuPages::UserAccounts => "User Accounts",
       MenuPages::Profile => "Profile",
       MenuPages::Greeter => "Greeter",
       MenuPages::DesktopEnvironment => "Desktop Environment",
       MenuPages::Audio => "Audio",
+      MenuPages::Bluetooth => "Bluetooth",
+      MenuPages::Graphics => "Graphics",
       MenuPages::Kernels => "Kernels",
       MenuPages::SystemPackages => "System Packages",
+      MenuPages::EtcFiles => "Extra /etc Files",
+      MenuPages::Sysctl => "Kernel Sysctl",
+      MenuPages::Overlays => "Nixpkgs Overlays",
+      MenuPages::ExtraModules => "Extra Modules",
+      MenuPages::InsecurePackages => "Insecure Packages",
       MenuPages::Network => "Network",
       MenuPages::Timezone => "Timezone",
+      MenuPages::ConfigReadme => "Config README",
     };
     write!(f, "{s}")
   }
@@ -305,8 +1396,13 @@ impl MenuPages {
       MenuPages::SourceFlake => SourceFlake::display_widget(installer),
       MenuPages::Language => Language::display_widget(installer),
       MenuPages::KeyboardLayout => KeyboardLayout::display_widget(installer),
+      MenuPages::Console => ConsoleSettings::display_widget(installer),
       MenuPages::Locale => Locale::display_widget(installer),
+      MenuPages::InputMethod => InputMethod::display_widget(installer),
       MenuPages::EnableFlakes => EnableFlakes::display_widget(installer),
+      MenuPages::Channel => Channel::display_widget(installer),
+      MenuPages::Architecture => Architecture::display_widget(installer),
+      MenuPages::Microcode => Microcode::display_widget(installer),
       MenuPages::Drives => {
         let sector_size = installer
           .drive_config
@@ -318,19 +1414,35 @@ impl MenuPages {
           .as_deref()
           .map(|d| Box::new(part_table(d, sector_size)) as Box<dyn ConfigWidget>)
       }
+      MenuPages::DataMounts => DataMounts::display_widget(installer),
       MenuPages::Bootloader => Bootloader::display_widget(installer),
+      MenuPages::BootOptions => BootOptions::display_widget(installer),
       MenuPages::Swap => Swap::display_widget(installer),
       MenuPages::Hostname => Hostname::display_widget(installer),
+      MenuPages::BuildJobs => BuildJobs::display_widget(installer),
+      MenuPages::Substituters => Substituters::display_widget(installer),
+      MenuPages::AutoUpgrade => AutoUpgrade::display_widget(installer),
+      MenuPages::Documentation => Documentation::display_widget(installer),
       MenuPages::RootPassword => RootPassword::display_widget(installer),
+      MenuPages::PasswordManagement => PasswordManagement::display_widget(installer),
+      MenuPages::Sudo => Sudo::display_widget(installer),
       MenuPages::UserAccounts => UserAccounts::display_widget(installer),
       MenuPages::Profile => Profile::display_widget(installer),
       MenuPages::Greeter => Greeter::display_widget(installer),
       MenuPages::DesktopEnvironment => DesktopEnvironment::display_widget(installer),
       MenuPages::Audio => Audio::display_widget(installer),
+      MenuPages::Bluetooth => Bluetooth::display_widget(installer),
+      MenuPages::Graphics => Graphics::display_widget(installer),
       MenuPages::Kernels => Kernels::display_widget(installer),
       MenuPages::SystemPackages => SystemPackages::display_widget(installer),
+      MenuPages::EtcFiles => EtcFiles::display_widget(installer),
+      MenuPages::Sysctl => Sysctl::display_widget(installer),
+      MenuPages::Overlays => Overlays::display_widget(installer),
+      MenuPages::ExtraModules => ExtraModules::display_widget(installer),
+      MenuPages::InsecurePackages => InsecurePackages::display_widget(installer),
       MenuPages::Network => NetworkConfig::display_widget(installer),
       MenuPages::Timezone => Timezone::display_widget(installer),
+      MenuPages::ConfigReadme => ConfigReadme::display_widget(installer),
     }
   }
 
@@ -340,8 +1452,13 @@ impl MenuPages {
       MenuPages::SourceFlake => SourceFlake::page_info(),
       MenuPages::Language => Language::page_info(),
       MenuPages::KeyboardLayout => KeyboardLayout::page_info(),
+      MenuPages::Console => ConsoleSettings::page_info(),
       MenuPages::Locale => Locale::page_info(),
+      MenuPages::InputMethod => InputMethod::page_info(),
       MenuPages::EnableFlakes => EnableFlakes::page_info(),
+      MenuPages::Channel => Channel::page_info(),
+      MenuPages::Architecture => Architecture::page_info(),
+      MenuPages::Microcode => Microcode::page_info(),
       MenuPages::Drives => (
         "Drives".to_string(),
         styled_block(vec![
@@ -359,19 +1476,35 @@ impl MenuPages {
           )],
         ]),
       ),
+      MenuPages::DataMounts => DataMounts::page_info(),
       MenuPages::Bootloader => Bootloader::page_info(),
+      MenuPages::BootOptions => BootOptions::page_info(),
       MenuPages::Swap => Swap::page_info(),
       MenuPages::Hostname => Hostname::page_info(),
+      MenuPages::BuildJobs => BuildJobs::page_info(),
+      MenuPages::Substituters => Substituters::page_info(),
+      MenuPages::AutoUpgrade => AutoUpgrade::page_info(),
+      MenuPages::Documentation => Documentation::page_info(),
       MenuPages::RootPassword => RootPassword::page_info(),
+      MenuPages::PasswordManagement => PasswordManagement::page_info(),
+      MenuPages::Sudo => Sudo::page_info(),
       MenuPages::UserAccounts => UserAccounts::page_info(),
       MenuPages::Profile => Profile::page_info(),
       MenuPages::Greeter => Greeter::page_info(),
       MenuPages::DesktopEnvironment => DesktopEnvironment::page_info(),
       MenuPages::Audio => Audio::page_info(),
+      MenuPages::Bluetooth => Bluetooth::page_info(),
+      MenuPages::Graphics => Graphics::page_info(),
       MenuPages::Kernels => Kernels::page_info(),
       MenuPages::SystemPackages => SystemPackages::page_info(),
+      MenuPages::EtcFiles => EtcFiles::page_info(),
+      MenuPages::Sysctl => Sysctl::page_info(),
+      MenuPages::Overlays => Overlays::page_info(),
+      MenuPages::ExtraModules => ExtraModules::page_info(),
+      MenuPages::InsecurePackages => InsecurePackages::page_info(),
       MenuPages::Network => NetworkConfig::page_info(),
       MenuPages::Timezone => Timezone::page_info(),
+      MenuPages::ConfigReadme => ConfigReadme::page_info(),
     }
   }
 
@@ -379,59 +1512,137 @@ impl MenuPages {
   pub fn navigate(self, installer: &mut Installer) -> Signal {
     match self {
       MenuPages::SourceFlake => Signal::Push(Box::new(SourceFlake::new())),
-      MenuPages::Language => Signal::Push(Box::new(Language::new())),
-      MenuPages::KeyboardLayout => Signal::Push(Box::new(KeyboardLayout::new())),
-      MenuPages::Locale => Signal::Push(Box::new(Locale::new())),
-      MenuPages::EnableFlakes => Signal::Push(Box::new(EnableFlakes::new(installer.enable_flakes))),
+      MenuPages::Language => Signal::Push(Box::new(Language::new(installer.language.as_deref()))),
+      MenuPages::KeyboardLayout => Signal::Push(Box::new(KeyboardLayout::new(
+        installer.keyboard_layout.as_deref(),
+        installer.keyboard_variant.as_deref(),
+      ))),
+      MenuPages::Console => Signal::Push(Box::new(ConsoleSettings::new(
+        installer.console_font.as_deref(),
+      ))),
+      MenuPages::Locale => Signal::Push(Box::new(Locale::new(installer.locale.as_deref()))),
+      MenuPages::InputMethod => Signal::Push(Box::new(InputMethod::new(installer))),
+      MenuPages::EnableFlakes => Signal::Push(Box::new(EnableFlakes::new(
+        installer.enable_flakes,
+        installer.enable_nix_command,
+        installer.allow_unfree,
+      ))),
+      MenuPages::Channel => Signal::Push(Box::new(Channel::new(
+        installer.channel(),
+        installer.enable_flakes,
+      ))),
+      MenuPages::Architecture => {
+        Signal::Push(Box::new(Architecture::new(&installer.architecture())))
+      }
+      MenuPages::Microcode => Signal::Push(Box::new(Microcode::new())),
       MenuPages::Drives => Signal::Push(Box::new(Drives::new())),
-      MenuPages::Bootloader => Signal::Push(Box::new(Bootloader::new())),
-      MenuPages::Swap => Signal::Push(Box::new(Swap::new(installer.use_swap))),
+      MenuPages::DataMounts => Signal::Push(Box::new(DataMounts::new(installer))),
+      MenuPages::Bootloader => Signal::Push(Box::new(Bootloader::new(installer))),
+      MenuPages::BootOptions => {
+        Signal::Push(Box::new(BootOptions::new(installer.boot_options.clone())))
+      }
+      MenuPages::Swap => Signal::Push(Box::new(Swap::new(installer.swap))),
       MenuPages::Hostname => Signal::Push(Box::new(Hostname::new())),
-      MenuPages::RootPassword => Signal::Push(Box::new(RootPassword::new())),
+      MenuPages::BuildJobs => Signal::Push(Box::new(BuildJobs::new(installer.nix_build.clone()))),
+      MenuPages::Substituters => Signal::Push(Box::new(Substituters::new(installer))),
+      MenuPages::AutoUpgrade => Signal::Push(Box::new(AutoUpgrade::new(
+        installer.auto_upgrade.clone().unwrap_or_default(),
+      ))),
+      MenuPages::Documentation => Signal::Push(Box::new(Documentation::new(
+        installer.documentation.clone(),
+      ))),
+      MenuPages::RootPassword => Signal::Push(Box::new(RootPassword::new(installer.disable_root))),
+      MenuPages::PasswordManagement => {
+        Signal::Push(Box::new(PasswordManagement::new(installer.mutable_users)))
+      }
+      MenuPages::Sudo => Signal::Push(Box::new(Sudo::new(
+        installer.sudo_config.clone().unwrap_or_default(),
+      ))),
       MenuPages::UserAccounts => Signal::Push(Box::new(UserAccounts::new(installer.users.clone()))),
-      MenuPages::Profile => Signal::Push(Box::new(Profile::new())),
-      MenuPages::Greeter => Signal::Push(Box::new(Greeter::new())),
-      MenuPages::DesktopEnvironment => Signal::Push(Box::new(DesktopEnvironment::new())),
-      MenuPages::Audio => Signal::Push(Box::new(Audio::new())),
-      MenuPages::Kernels => Signal::Push(Box::new(Kernels::new())),
+      MenuPages::Profile => Signal::Push(Box::new(Profile::new(installer.profile.as_deref()))),
+      MenuPages::Greeter => Signal::Push(Box::new(Greeter::new(installer.greeter.as_deref()))),
+      MenuPages::DesktopEnvironment => Signal::Push(Box::new(DesktopEnvironment::new(
+        installer.desktop_environment.as_deref(),
+      ))),
+      MenuPages::Audio => Signal::Push(Box::new(Audio::new(installer.audio_backend.as_deref()))),
+      MenuPages::Bluetooth => Signal::Push(Box::new(Bluetooth::new(installer))),
+      MenuPages::Graphics => Signal::Push(Box::new(Graphics::new(installer.gpu_driver.as_deref()))),
+      MenuPages::Kernels => Signal::Push(Box::new(Kernels::new(installer.kernels.as_deref()))),
       MenuPages::SystemPackages => {
         // we actually need to go ask nixpkgs what packages it has now
-        let pkgs = get_available_pkgs().unwrap_or_default();
-        Signal::Push(Box::new(SystemPackages::new(
-          installer.system_pkgs.clone(),
-          pkgs,
-        )))
+        let selected = installer.system_pkgs.clone();
+        let view_state = installer.system_pkgs_view.clone();
+        with_available_pkgs(move |pkgs| {
+          Box::new(SystemPackages::new(selected, pkgs).with_view_state(view_state))
+        })
       }
+      MenuPages::EtcFiles => Signal::Push(Box::new(EtcFiles::new(installer))),
+      MenuPages::Sysctl => Signal::Push(Box::new(Sysctl::new(installer))),
+      MenuPages::Overlays => Signal::Push(Box::new(Overlays::new(installer.overlays_scaffold))),
+      MenuPages::ExtraModules => Signal::Push(Box::new(ExtraModules::new(installer))),
+      MenuPages::InsecurePackages => Signal::Push(Box::new(InsecurePackages::new(installer))),
       MenuPages::Network => Signal::Push(Box::new(NetworkConfig::new())),
-      MenuPages::Timezone => Signal::Push(Box::new(Timezone::new())),
+      MenuPages::Timezone => Signal::Push(Box::new(Timezone::new(installer.timezone.as_deref()))),
+      MenuPages::ConfigReadme => Signal::Push(Box::new(ConfigReadme::new(!installer.skip_readme))),
+    }
+  }
+}
+
+/// Every menu page's help as plain text, for `--dump-help`
+///
+/// Pages are opened against a default installer, so anything in the help that
+/// depends on settings shows the defaults.
+pub fn help_reference() -> String {
+  let mut installer = Installer::new();
+  let mut pages: Vec<Box<dyn Page>> = vec![Box::new(Menu::new())];
+  for page in MenuPages::all_pages() {
+    if let Signal::Push(page) = page.navigate(&mut installer) {
+      pages.push(page);
+    }
+  }
+  let mut out = String::new();
+  for page in &pages {
+    let (title, lines) = page.get_help_content();
+    out.push_str(&format!("{title}\n{}\n", "=".repeat(title.chars().count())));
+    for line in lines {
+      out.push_str(line.to_string().trim_end());
+      out.push('\n');
     }
+    out.push('\n');
   }
+  out
 }
 
 /// The main menu page
 pub struct Menu {
+  pages: Vec<MenuPages>,
+  order: MenuOrder,
   menu_items: StrList,
   border_flash_timer: u32,
   button_row: WidgetBox,
   help_modal: HelpModal<'static>,
+  quit_modal: ConfirmModal,
 }
 
 impl Menu {
   pub fn new() -> Self {
-    let items = MenuPages::supported_pages()
-      .iter()
-      .map(|p| p.to_string())
-      .collect::<Vec<_>>();
+    let order = MenuOrder::load();
+    let pages = order.apply(MenuPages::supported_pages().to_vec());
+    let items = Self::item_labels(&order, &pages);
     let mut menu_items = StrList::new("Main Menu", items);
     let buttons: Vec<Box<dyn ConfigWidget>> = vec![
       Box::new(Button::new("Done")),
+      Box::new(Button::new("Save Config")),
       Box::new(Button::new("Abort")),
     ];
     let button_row = WidgetBoxBuilder::new().children(buttons).build();
     menu_items.focus();
-    let help_content = styled_block(vec![
+    let mut help_content = styled_block(vec![
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "↑/↓, j/k"),
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
         (None, " - Navigate menu options"),
       ],
       vec![
@@ -446,6 +1657,18 @@ impl Menu {
         (Some((Color::Yellow, Modifier::BOLD)), "Home, g"),
         (None, " - Return to menu options"),
       ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "f"),
+        (None, " - Pin or unpin option at the top"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "K/J"),
+        (None, " - Move option up/down"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "</>"),
+        (None, " - Narrow or widen the menu"),
+      ],
       vec![
         (Some((Color::Yellow, Modifier::BOLD)), "q"),
         (None, " - Quit installer"),
@@ -461,18 +1684,84 @@ impl Menu {
       )],
       vec![(None, "Configure all required options before proceeding.")],
     ]);
+    help_content.extend(keys::bindings().reference());
     let help_modal = HelpModal::new("Main Menu", help_content);
+    let quit_modal = ConfirmModal::new(
+      "Quit",
+      "Quit installer? Unsaved configuration will be lost.",
+    );
     Self {
+      pages,
+      order,
       menu_items,
       button_row,
       help_modal,
+      quit_modal,
       border_flash_timer: 0,
     }
   }
+  fn item_labels(order: &MenuOrder, pages: &[MenuPages]) -> Vec<String> {
+    pages
+      .iter()
+      .map(|p| {
+        if order.is_favorite(*p) {
+          format!("* {p}")
+        } else {
+          p.to_string()
+        }
+      })
+      .collect()
+  }
+  /// Put `pages` in the menu, keeping the cursor on the same page
+  fn set_pages(&mut self, pages: Vec<MenuPages>) {
+    let selected = self.pages.get(self.menu_items.selected_idx).copied();
+    self
+      .menu_items
+      .set_items(Self::item_labels(&self.order, &pages));
+    self.menu_items.selected_idx = selected
+      .and_then(|sel| pages.iter().position(|p| *p == sel))
+      .unwrap_or(0);
+    self.pages = pages;
+  }
+  /// Store the current arrangement and redraw the menu from it
+  fn save_order(&mut self) {
+    self.order.record(&self.pages);
+    if let Err(e) = self.order.save() {
+      log::warn!("Failed to save menu order: {e}");
+    }
+    let pages = self.order.apply(self.pages.clone());
+    self.set_pages(pages);
+  }
+  /// Swap the selected page with its neighbour, `up` towards the top
+  fn move_selected(&mut self, up: bool) {
+    let idx = self.menu_items.selected_idx;
+    let other = if up {
+      idx.checked_sub(1)
+    } else {
+      Some(idx + 1).filter(|i| *i < self.pages.len())
+    };
+    let Some(other) = other else {
+      return;
+    };
+    // Favorites and the rest are ordered separately
+    if self.order.is_favorite(self.pages[idx]) != self.order.is_favorite(self.pages[other]) {
+      return;
+    }
+    self.pages.swap(idx, other);
+    self.menu_items.selected_idx = other;
+    self.save_order();
+  }
+  /// Pick up pages that have become visible or hidden since the last frame,
+  /// keeping the cursor on the same page
+  fn refresh_pages(&mut self, installer: &Installer) {
+    let pages = self.order.apply(MenuPages::visible_pages(installer));
+    if pages == self.pages {
+      return;
+    }
+    self.set_pages(pages);
+  }
   pub fn info_box_for_item(&mut self, installer: &mut Installer, idx: usize) -> WidgetBox {
-    // Get the actual page from supported_pages using the index
-    let supported_pages = MenuPages::supported_pages();
-    let page = supported_pages.get(idx).copied();
+    let page = self.pages.get(idx).copied();
 
     let (display_widget, title, content) = if let Some(page) = page {
       let display_widget = page.display_widget(installer);
@@ -516,7 +1805,12 @@ impl Menu {
     border_flash_timer: u32,
   ) -> InfoBox<'_> {
     let mut lines = vec![];
-    if installer.root_passwd_hash.is_none() {
+    if installer.disable_root && !installer.has_admin_user() {
+      lines.push(vec![(
+        Some((Color::Red, Modifier::BOLD)),
+        " - An admin (wheel) user, since root is disabled",
+      )]);
+    } else if !installer.has_root_access() {
       lines.push(vec![(
         Some((Color::Red, Modifier::BOLD)),
         " - Root Password",
@@ -528,6 +1822,12 @@ impl Menu {
         " - Drive Configuration",
       )]);
     }
+    if installer.all_disks().any(|d| d.luks_missing_passphrase()) {
+      lines.push(vec![(
+        Some((Color::Red, Modifier::BOLD)),
+        " - LUKS passphrase, it isn't kept in saved settings",
+      )]);
+    }
     if installer.users.is_empty() {
       lines.push(vec![(
         Some((Color::Red, Modifier::BOLD)),
@@ -536,6 +1836,11 @@ impl Menu {
     }
     if installer.bootloader.is_none() {
       lines.push(vec![(Some((Color::Red, Modifier::BOLD)), " - Bootloader")]);
+    } else if installer.bootloader_needs_uefi() {
+      lines.push(vec![(
+        Some((Color::Red, Modifier::BOLD)),
+        " - Bootloader, systemd-boot needs UEFI but this machine booted in BIOS mode",
+      )]);
     }
     if lines.is_empty() {
       lines.push(vec![(
@@ -553,7 +1858,26 @@ impl Menu {
       lines.push(vec![(None, "Please configure them before proceeding.")]);
     }
 
-    let mut info_box = InfoBox::new("Required Config", styled_block(lines));
+    let warnings = installer.config_warnings();
+    let mut content = styled_block(lines);
+    if !warnings.is_empty() {
+      let mut warning_lines = vec![
+        vec![(None, String::new())],
+        vec![(
+          Some((Color::Yellow, Modifier::BOLD)),
+          "Warnings:".to_string(),
+        )],
+      ];
+      for warning in warnings {
+        warning_lines.push(vec![(
+          Some((Color::Yellow, Modifier::empty())),
+          format!(" - {warning}"),
+        )]);
+      }
+      content.extend(styled_block(warning_lines));
+    }
+
+    let mut info_box = InfoBox::new("Required Config", content);
     if border_flash_timer > 0 {
       match self.border_flash_timer % 2 {
         1 => info_box.highlighted(true),
@@ -573,10 +1897,16 @@ impl Default for Menu {
 
 impl Page for Menu {
   fn render(&mut self, installer: &mut Installer, f: &mut Frame, area: Rect) {
+    self.refresh_pages(installer);
+    let menu_width = self.order.menu_width();
     let chunks = split_hor!(
       area,
       1,
-      [Constraint::Percentage(20), Constraint::Percentage(80)].as_ref()
+      [
+        Constraint::Percentage(menu_width),
+        Constraint::Percentage(100 - menu_width)
+      ]
+      .as_ref()
     );
 
     // We use this for both the menu options and info box
@@ -616,6 +1946,7 @@ impl Page for Menu {
 
       // Render help modal on top of everything
       self.help_modal.render(f, area);
+      self.quit_modal.render(f, area);
     }
     {
       if decrement_timer {
@@ -625,9 +1956,12 @@ impl Page for Menu {
   }
 
   fn get_help_content(&self) -> (String, Vec<Line<'_>>) {
-    let help_content = styled_block(vec![
+    let mut help_content = styled_block(vec![
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "↑/↓, j/k"),
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
         (None, " - Navigate menu options"),
       ],
       vec![
@@ -642,6 +1976,18 @@ impl Page for Menu {
         (Some((Color::Yellow, Modifier::BOLD)), "Home, g"),
         (None, " - Return to menu options"),
       ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "f"),
+        (None, " - Pin or unpin option at the top"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "K/J"),
+        (None, " - Move option up/down"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "</>"),
+        (None, " - Narrow or widen the menu"),
+      ],
       vec![
         (Some((Color::Yellow, Modifier::BOLD)), "q"),
         (None, " - Quit installer"),
@@ -657,15 +2003,22 @@ impl Page for Menu {
       )],
       vec![(None, "Configure all required options before proceeding.")],
     ]);
+    help_content.extend(keys::bindings().reference());
     ("Main Menu".to_string(), help_content)
   }
   fn handle_input(&mut self, installer: &mut Installer, event: KeyEvent) -> Signal {
+    if self.quit_modal.visible {
+      return match self.quit_modal.handle_input(event) {
+        Some(true) => Signal::Quit,
+        _ => Signal::Wait,
+      };
+    }
     match event.code {
       KeyCode::Char('?') => {
         self.help_modal.toggle();
         Signal::Wait
       }
-      ui_close!() if self.help_modal.visible => {
+      code if ui_close!(code) && self.help_modal.visible => {
         self.help_modal.hide();
         Signal::Wait
       }
@@ -673,17 +2026,43 @@ impl Page for Menu {
         // Help modal is open, don't process other inputs
         Signal::Wait
       }
-      KeyCode::Char('q') => Signal::Quit,
-      KeyCode::Home | KeyCode::Char('g') => {
-        if self.menu_items.is_focused() {
-          self.menu_items.first_item();
-          Signal::Wait
-        } else {
-          self.menu_items.first_item();
-          self.menu_items.focus();
-          self.button_row.unfocus();
-          Signal::Wait
-        }
+      KeyCode::Char('q') => {
+        self.quit_modal.show();
+        Signal::Wait
+      }
+      KeyCode::Char('f') if self.menu_items.is_focused() => {
+        if let Some(page) = self.pages.get(self.menu_items.selected_idx).copied() {
+          self.order.toggle_favorite(page);
+          self.save_order();
+        }
+        Signal::Wait
+      }
+      KeyCode::Char(c @ ('<' | '>')) => {
+        if self.order.resize_menu(c == '>')
+          && let Err(e) = self.order.save()
+        {
+          log::warn!("Failed to save menu width: {e}");
+        }
+        Signal::Wait
+      }
+      KeyCode::Char('K') if self.menu_items.is_focused() => {
+        self.move_selected(true);
+        Signal::Wait
+      }
+      KeyCode::Char('J') if self.menu_items.is_focused() => {
+        self.move_selected(false);
+        Signal::Wait
+      }
+      KeyCode::Home | KeyCode::Char('g') => {
+        if self.menu_items.is_focused() {
+          self.menu_items.first_item();
+          Signal::Wait
+        } else {
+          self.menu_items.first_item();
+          self.menu_items.focus();
+          self.button_row.unfocus();
+          Signal::Wait
+        }
       }
       KeyCode::End | KeyCode::Char('G') => {
         if self.menu_items.is_focused() {
@@ -692,7 +2071,7 @@ impl Page for Menu {
         }
         Signal::Wait
       }
-      ui_up!() => {
+      code if ui_up!(code) => {
         if self.menu_items.is_focused() {
           if !self.menu_items.previous_item() {
             self.menu_items.unfocus();
@@ -706,7 +2085,7 @@ impl Page for Menu {
           Signal::Wait
         }
       }
-      ui_down!() => {
+      code if ui_down!(code) => {
         if self.menu_items.is_focused() {
           if !self.menu_items.next_item() {
             self.menu_items.unfocus();
@@ -721,24 +2100,22 @@ impl Page for Menu {
         }
       }
       #[allow(unreachable_patterns)]
-      ui_enter!() if self.menu_items.is_focused() => {
+      code if ui_enter!(code) && self.menu_items.is_focused() => {
         let idx = self.menu_items.selected_idx;
-        // Get the actual page from supported_pages using the index
-        let supported_pages = MenuPages::supported_pages();
-        if let Some(page) = supported_pages.get(idx).copied() {
+        if let Some(page) = self.pages.get(idx).copied() {
           page.navigate(installer)
         } else {
           Signal::Wait
         }
       }
       // Button row
-      ui_right!() => {
+      code if ui_right!(code) => {
         if self.button_row.is_focused() {
           self.button_row.next_child();
         }
         Signal::Wait
       }
-      ui_left!() => {
+      code if ui_left!(code) => {
         if self.button_row.is_focused() {
           self.button_row.prev_child();
         }
@@ -749,20 +2126,25 @@ impl Page for Menu {
           match self.button_row.selected_child() {
             Some(0) => {
               // Done - Show config preview
-              if installer.has_all_requirements() {
-                match ConfigPreview::new(installer) {
-                  Ok(preview) => Signal::Push(Box::new(preview)),
-                  Err(e) => Signal::Error(anyhow::anyhow!(
-                    "Failed to generate configuration preview: {}",
-                    e
-                  )),
-                }
+              let warnings = installer.soft_warnings();
+              let missing_tools = tools::missing_for_install(installer.keeps_partitions());
+              if installer.has_all_requirements() && !missing_tools.is_empty() {
+                Signal::Push(Box::new(MissingTools::new(missing_tools, true)))
+              } else if installer.has_all_requirements() && !warnings.is_empty() {
+                Signal::Push(Box::new(SoftWarnings::new(warnings)))
+              } else if installer.has_all_requirements() {
+                Signal::Push(Box::new(SettingsSummary::new(installer)))
               } else {
                 self.border_flash_timer = 6;
                 Signal::Wait
               }
             }
-            Some(1) => Signal::Quit, // Abort
+            Some(1) => Signal::Push(Box::new(SaveConfig::new(installer))),
+            Some(2) => {
+              // Abort
+              self.quit_modal.show();
+              Signal::Wait
+            }
             _ => Signal::Wait,
           }
         } else {
@@ -779,9 +2161,14 @@ impl Page for Menu {
       MenuPages::Language,
       MenuPages::KeyboardLayout,
       MenuPages::Locale,
+      MenuPages::InputMethod,
       MenuPages::EnableFlakes,
+      MenuPages::Channel,
+      MenuPages::Architecture,
       MenuPages::Drives,
+      MenuPages::DataMounts,
       MenuPages::Bootloader,
+      MenuPages::BootOptions,
       MenuPages::Swap,
       MenuPages::Hostname,
       MenuPages::RootPassword,
@@ -793,6 +2180,10 @@ impl Page for Menu {
       MenuPages::Kernels,
       MenuPages::Virtualization,
       MenuPages::SystemPackages,
+      MenuPages::EtcFiles,
+      MenuPages::Sysctl,
+      MenuPages::Overlays,
+      MenuPages::InsecurePackages,
       MenuPages::Network,
       MenuPages::Timezone,
 */
@@ -826,6 +2217,17 @@ impl SourceFlake {
         (Some((Color::Yellow, Modifier::BOLD)), "Home/End"),
         (None, " - Jump to beginning/end"),
       ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Ctrl+←/→"),
+        (None, " - Jump by word"),
+      ],
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          "Ctrl+W, Alt+Backspace",
+        ),
+        (None, " - Delete the previous word"),
+      ],
       vec![
         (Some((Color::Yellow, Modifier::BOLD)), "Backspace/Del"),
         (None, " - Delete characters"),
@@ -973,6 +2375,17 @@ impl Page for SourceFlake {
         (Some((Color::Yellow, Modifier::BOLD)), "Home/End"),
         (None, " - Jump to beginning/end"),
       ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Ctrl+←/→"),
+        (None, " - Jump by word"),
+      ],
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          "Ctrl+W, Alt+Backspace",
+        ),
+        (None, " - Delete the previous word"),
+      ],
       vec![
         (Some((Color::Yellow, Modifier::BOLD)), "Backspace/Del"),
         (None, " - Delete characters"),
@@ -993,18 +2406,25 @@ impl Page for SourceFlake {
     ("Source Flake".to_string(), help_content)
   }
 
+  fn handle_paste(&mut self, _installer: &mut Installer, text: &str) -> Signal {
+    if !self.help_modal.visible {
+      self.input.paste(text);
+    }
+    Signal::Wait
+  }
+
   fn handle_input(&mut self, installer: &mut Installer, event: KeyEvent) -> Signal {
     match event.code {
       KeyCode::Char('?') => {
         self.help_modal.toggle();
         Signal::Wait
       }
-      ui_close!() if self.help_modal.visible => {
+      code if ui_close!(code) && self.help_modal.visible => {
         self.help_modal.hide();
         Signal::Wait
       }
       _ if self.help_modal.visible => Signal::Wait,
-      ui_back!() => Signal::Pop,
+      code if ui_back!(code) => Signal::Pop,
       KeyCode::Enter => {
         let flake_path = self
           .input
@@ -1032,16 +2452,20 @@ pub struct Language {
 }
 
 impl Language {
-  pub fn new() -> Self {
+  pub fn new(current: Option<&str>) -> Self {
     let languages = ["English"]
       .iter()
       .map(|s| s.to_string())
       .collect::<Vec<_>>();
     let mut langs = StrList::new("Select Language", languages);
+    langs.commit_value(current);
     langs.focus();
     let help_content = styled_block(vec![
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "↑/↓, j/k"),
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
         (None, " - Navigate language options"),
       ],
       vec![
@@ -1049,7 +2473,10 @@ impl Language {
         (None, " - Select language and return"),
       ],
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "Esc, q, ←, h"),
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().back_label,
+        ),
         (None, " - Cancel and return to menu"),
       ],
       vec![
@@ -1087,7 +2514,7 @@ impl Language {
 
 impl Default for Language {
   fn default() -> Self {
-    Self::new()
+    Self::new(None)
   }
 }
 
@@ -1101,7 +2528,10 @@ impl Page for Language {
   fn get_help_content(&self) -> (String, Vec<Line<'_>>) {
     let help_content = styled_block(vec![
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "↑/↓, j/k"),
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
         (None, " - Navigate language options"),
       ],
       vec![
@@ -1109,7 +2539,10 @@ impl Page for Language {
         (None, " - Select language and return"),
       ],
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "Esc, q, ←, h"),
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().back_label,
+        ),
         (None, " - Cancel and return to menu"),
       ],
       vec![
@@ -1128,12 +2561,12 @@ impl Page for Language {
         self.help_modal.toggle();
         Signal::Wait
       }
-      ui_close!() if self.help_modal.visible => {
+      code if ui_close!(code) && self.help_modal.visible => {
         self.help_modal.hide();
         Signal::Wait
       }
       _ if self.help_modal.visible => Signal::Wait,
-      ui_back!() => Signal::Pop,
+      code if ui_back!(code) => Signal::Pop,
       KeyCode::Enter => {
         installer.language = Some(self.langs.items[self.langs.selected_idx].clone());
         Signal::Pop
@@ -1143,53 +2576,76 @@ impl Page for Language {
   }
 }
 
+/// The text in a list page's search bar, or `None` to show everything
+fn search_filter(search_bar: &LineEditor) -> Option<String> {
+  search_bar
+    .get_value()
+    .and_then(|v| v.as_str().map(|s| s.to_string()))
+    .filter(|s| !s.is_empty())
+}
+
+/// Offered when localectl can't list the layouts, e.g. on systems without
+/// xkeyboard-config's rules files
+const FALLBACK_KEYBOARD_LAYOUTS: &[&str] = &[
+  "us", "gb", "de", "fr", "es", "it", "ru", "cn", "jp", "kr", "in", "br", "nl", "se", "no", "fi",
+  "dk", "pl", "tr", "gr",
+];
+
+/// Variants of "us" offered when localectl can't list them
+const FALLBACK_US_VARIANTS: &[&str] = &["dvorak", "colemak"];
+
+/// First entry of the variant list, picking it stores no variant
+const DEFAULT_VARIANT: &str = "(default)";
+
 pub struct KeyboardLayout {
   layouts: StrList,
+  /// Variants of the chosen layout, shown once a layout is picked
+  variants: Option<StrList>,
+  current_variant: Option<String>,
+  search_bar: LineEditor,
+  /// Shown when `i` can't read the running system's setting
+  message: MessageModal,
   help_modal: HelpModal<'static>,
 }
 
 impl KeyboardLayout {
-  pub fn new() -> Self {
-    let layouts = vec![
-      "us(qwerty)",
-      "us(dvorak)",
-      "us(colemak)",
-      "uk",
-      "de",
-      "fr",
-      "es",
-      "it",
-      "ru",
-      "cn",
-      "jp",
-      "kr",
-      "in",
-      "br",
-      "nl",
-      "se",
-      "no",
-      "fi",
-      "dk",
-      "pl",
-      "tr",
-      "gr",
-    ]
-    .iter()
-    .map(|s| s.to_string())
-    .collect::<Vec<_>>();
+  pub fn new(current: Option<&str>, current_variant: Option<&str>) -> Self {
+    let layouts = livesystem::list_keyboard_layouts().unwrap_or_else(|e| {
+      log::debug!("Using the built-in keyboard layouts: {e}");
+      FALLBACK_KEYBOARD_LAYOUTS
+        .iter()
+        .map(|s| s.to_string())
+        .collect()
+    });
     let mut layouts = StrList::new("Select Keyboard Layout", layouts);
+    layouts.commit_value(current);
     layouts.focus();
+    let search_bar = LineEditor::new("Search (/)", Some("Filter layouts..."));
     let help_content = styled_block(vec![
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "↑/↓, j/k"),
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
         (None, " - Navigate keyboard layout options"),
       ],
       vec![
         (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
-        (None, " - Select keyboard layout and return"),
+        (None, " - Select keyboard layout, then its variant"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "/"),
+        (None, " - Search layouts, Esc clears the search"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "i"),
+        (None, " - Use the running system's setting"),
       ],
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "Esc, q, ←, h"),
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().back_label,
+        ),
         (None, " - Cancel and return to menu"),
       ],
       vec![
@@ -1205,19 +2661,38 @@ impl KeyboardLayout {
     let help_modal = HelpModal::new("Keyboard Layout", help_content);
     Self {
       layouts,
+      variants: None,
+      current_variant: current_variant.map(|v| v.to_string()),
+      search_bar,
+      message: MessageModal::new(),
       help_modal,
     }
   }
+  /// Variants of `layout`, with [`DEFAULT_VARIANT`] first. `None` if the
+  /// layout has none to choose from.
+  fn variant_list(layout: &str) -> Option<Vec<String>> {
+    let variants = livesystem::list_keyboard_variants(layout)
+      .ok()
+      .or_else(|| {
+        (layout == "us").then(|| FALLBACK_US_VARIANTS.iter().map(|s| s.to_string()).collect())
+      })?;
+    Some(
+      std::iter::once(DEFAULT_VARIANT.to_string())
+        .chain(variants)
+        .collect(),
+    )
+  }
   pub fn display_widget(installer: &mut Installer) -> Option<Box<dyn ConfigWidget>> {
     installer.keyboard_layout.clone().map(|s| {
-      let ib = InfoBox::new(
-        "",
-        styled_block(vec![
-          vec![(None, "Current keyboard layout set to:")],
-          vec![(HIGHLIGHT, &s)],
-        ]),
-      );
-      Box::new(ib) as Box<dyn ConfigWidget>
+      let mut lines = vec![
+        vec![(None, "Current keyboard layout set to:".to_string())],
+        vec![(HIGHLIGHT, s)],
+      ];
+      if let Some(ref variant) = installer.keyboard_variant {
+        lines.push(vec![(None, "Variant:".to_string())]);
+        lines.push(vec![(HIGHLIGHT, variant.clone())]);
+      }
+      Box::new(InfoBox::new("", styled_block(lines))) as Box<dyn ConfigWidget>
     })
   }
   pub fn page_info<'a>() -> (String, Vec<Line<'a>>) {
@@ -1233,29 +2708,61 @@ impl KeyboardLayout {
 
 impl Default for KeyboardLayout {
   fn default() -> Self {
-    Self::new()
+    Self::new(None, None)
   }
 }
 
 impl Page for KeyboardLayout {
   fn render(&mut self, _installer: &mut Installer, f: &mut Frame, area: Rect) {
-    let chunks = split_vert!(area, 1, [Constraint::Percentage(100)]);
-    self.layouts.render(f, chunks[0]);
+    let chunks = split_vert!(area, 1, [Constraint::Length(3), Constraint::Min(0)]);
+    match self.variants {
+      Some(ref mut variants) => {
+        let title = self.layouts.selected_item().cloned().unwrap_or_default();
+        InfoBox::new(
+          "",
+          styled_block(vec![vec![
+            (None, "Variant of layout ".to_string()),
+            (HIGHLIGHT, title),
+          ]]),
+        )
+        .render(f, chunks[0]);
+        variants.render(f, chunks[1]);
+      }
+      None => {
+        self.search_bar.render(f, chunks[0]);
+        self.layouts.render(f, chunks[1]);
+      }
+    }
+    self.message.render(f, area);
     self.help_modal.render(f, area);
   }
 
   fn get_help_content(&self) -> (String, Vec<Line<'_>>) {
     let help_content = styled_block(vec![
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "↑/↓, j/k"),
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
         (None, " - Navigate keyboard layout options"),
       ],
       vec![
         (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
-        (None, " - Select keyboard layout and return"),
+        (None, " - Select keyboard layout, then its variant"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "/"),
+        (None, " - Search layouts, Esc clears the search"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "i"),
+        (None, " - Use the running system's setting"),
       ],
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "Esc, q, ←, h"),
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().back_label,
+        ),
         (None, " - Cancel and return to menu"),
       ],
       vec![
@@ -1271,29 +2778,126 @@ impl Page for KeyboardLayout {
     ("Keyboard Layout".to_string(), help_content)
   }
 
+  fn handle_paste(&mut self, _installer: &mut Installer, text: &str) -> Signal {
+    if self.help_modal.visible || self.message.visible || self.variants.is_some() {
+      return Signal::Wait;
+    }
+    // Pasting into the list starts a search for it
+    self.search_bar.focus();
+    self.search_bar.paste(text);
+    self.layouts.set_filter(search_filter(&self.search_bar));
+    Signal::Wait
+  }
+
   fn handle_input(&mut self, installer: &mut Installer, event: KeyEvent) -> Signal {
+    if self.message.visible {
+      self.message.handle_input(event);
+      return Signal::Wait;
+    }
+    if let Some(ref mut variants) = self.variants {
+      return match event.code {
+        code if ui_back!(code) => {
+          self.variants = None;
+          Signal::Wait
+        }
+        KeyCode::Enter => {
+          installer.keyboard_layout = self.layouts.selected_item().cloned();
+          installer.keyboard_variant = variants
+            .selected_item()
+            .filter(|v| *v != DEFAULT_VARIANT)
+            .cloned();
+          Signal::Pop
+        }
+        code if ui_up!(code) => {
+          if !variants.previous_item() {
+            variants.last_item();
+          }
+          Signal::Wait
+        }
+        code if ui_down!(code) => {
+          if !variants.next_item() {
+            variants.first_item();
+          }
+          Signal::Wait
+        }
+        _ => variants.handle_input(event),
+      };
+    }
+
     match event.code {
       KeyCode::Char('?') => {
         self.help_modal.toggle();
         Signal::Wait
       }
-      ui_close!() if self.help_modal.visible => {
+      code if ui_close!(code) && self.help_modal.visible => {
         self.help_modal.hide();
         Signal::Wait
       }
       _ if self.help_modal.visible => Signal::Wait,
-      ui_back!() => Signal::Pop,
+      KeyCode::Esc if self.search_bar.is_focused() => {
+        self.search_bar.clear();
+        self.search_bar.unfocus();
+        self.layouts.set_filter(None::<String>);
+        Signal::Wait
+      }
+      KeyCode::Up | KeyCode::Down if self.search_bar.is_focused() => {
+        if event.code == KeyCode::Up {
+          self.layouts.previous_item();
+        } else {
+          self.layouts.next_item();
+        }
+        Signal::Wait
+      }
+      _ if self.search_bar.is_focused() && event.code != KeyCode::Enter => {
+        let signal = self.search_bar.handle_input(event);
+        self.layouts.set_filter(search_filter(&self.search_bar));
+        signal
+      }
+      KeyCode::Char('/') => {
+        self.search_bar.focus();
+        Signal::Wait
+      }
+      code if ui_back!(code) => Signal::Pop,
       KeyCode::Enter => {
-        installer.keyboard_layout = Some(self.layouts.items[self.layouts.selected_idx].clone());
-        Signal::Pop
+        let Some(layout) = self.layouts.selected_item().cloned() else {
+          return Signal::Wait;
+        };
+        let Some(variants) = Self::variant_list(&layout) else {
+          installer.keyboard_layout = Some(layout);
+          installer.keyboard_variant = None;
+          return Signal::Pop;
+        };
+        let mut variants = StrList::new("Select Variant", variants);
+        if installer.keyboard_layout.as_ref() == Some(&layout) {
+          variants.commit_value(self.current_variant.as_deref());
+        }
+        variants.focus();
+        self.search_bar.unfocus();
+        self.variants = Some(variants);
+        Signal::Wait
       }
-      ui_up!() => {
+      KeyCode::Char('i') => match livesystem::current_keyboard_layout(&self.layouts.items) {
+        Ok((layout, variant)) => {
+          installer.keyboard_layout = Some(layout);
+          installer.keyboard_variant = variant;
+          Signal::Pop
+        }
+        Err(e) => {
+          self.message.show(
+            "Couldn't Detect",
+            format!("Couldn't read the running system's keyboard layout: {e}"),
+            Color::Yellow,
+          );
+          Signal::Wait
+        }
+      },
+      code if ui_up!(code) => {
         if !self.layouts.previous_item() {
           self.layouts.last_item();
         }
         Signal::Wait
       }
-      ui_down!() => {
+      code if ui_down!(code) => {
         if !self.layouts.next_item() {
           self.layouts.first_item();
         }
@@ -1304,42 +2908,50 @@ impl Page for KeyboardLayout {
   }
 }
 
+/// Locales offered on the Locale pages
+pub const LOCALES: &[&str] = &[
+  "en_US.UTF-8",
+  "en_GB.UTF-8",
+  "de_DE.UTF-8",
+  "fr_FR.UTF-8",
+  "es_ES.UTF-8",
+  "it_IT.UTF-8",
+  "ru_RU.UTF-8",
+  "zh_CN.UTF-8",
+  "ja_JP.UTF-8",
+  "ko_KR.UTF-8",
+  "pt_BR.UTF-8",
+  "nl_NL.UTF-8",
+  "sv_SE.UTF-8",
+  "no_NO.UTF-8",
+  "fi_FI.UTF-8",
+  "da_DK.UTF-8",
+  "pl_PL.UTF-8",
+  "tr_TR.UTF-8",
+  "el_GR.UTF-8",
+];
+
 pub struct Locale {
   locales: StrList,
+  search_bar: LineEditor,
+  /// Shown when `i` can't read the running system's setting
+  message: MessageModal,
   help_modal: HelpModal<'static>,
 }
 
 impl Locale {
-  pub fn new() -> Self {
-    let locales = vec![
-      "en_US.UTF-8",
-      "en_GB.UTF-8",
-      "de_DE.UTF-8",
-      "fr_FR.UTF-8",
-      "es_ES.UTF-8",
-      "it_IT.UTF-8",
-      "ru_RU.UTF-8",
-      "zh_CN.UTF-8",
-      "ja_JP.UTF-8",
-      "ko_KR.UTF-8",
-      "pt_BR.UTF-8",
-      "nl_NL.UTF-8",
-      "sv_SE.UTF-8",
-      "no_NO.UTF-8",
-      "fi_FI.UTF-8",
-      "da_DK.UTF-8",
-      "pl_PL.UTF-8",
-      "tr_TR.UTF-8",
-      "el_GR.UTF-8",
-    ]
-    .iter()
-    .map(|s| s.to_string())
-    .collect::<Vec<_>>();
+  pub fn new(current: Option<&str>) -> Self {
+    let locales = LOCALES.iter().map(|s| s.to_string()).collect::<Vec<_>>();
     let mut locales = StrList::new("Select Locale", locales);
+    locales.commit_value(current);
     locales.focus();
+    let search_bar = LineEditor::new("Search (/)", Some("Filter locales..."));
     let help_content = styled_block(vec![
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "↑/↓, j/k"),
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
         (None, " - Navigate locale options"),
       ],
       vec![
@@ -1347,7 +2959,22 @@ impl Locale {
         (None, " - Select locale and return"),
       ],
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "Esc, q, ←, h"),
+        (Some((Color::Yellow, Modifier::BOLD)), "/"),
+        (None, " - Search locales, Esc clears the search"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "i"),
+        (None, " - Use the running system's setting"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "a"),
+        (None, " - Advanced: per-category and extra locales"),
+      ],
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().back_label,
+        ),
         (None, " - Cancel and return to menu"),
       ],
       vec![
@@ -1361,49 +2988,71 @@ impl Locale {
     let help_modal = HelpModal::new("Locale", help_content);
     Self {
       locales,
+      search_bar,
+      message: MessageModal::new(),
       help_modal,
     }
   }
   pub fn display_widget(installer: &mut Installer) -> Option<Box<dyn ConfigWidget>> {
-    installer.locale.clone().map(|s| {
-      let ib = InfoBox::new(
-        "",
-        styled_block(vec![
-          vec![(None, "Current locale set to:")],
-          vec![(HIGHLIGHT, &s)],
-        ]),
-      );
-      Box::new(ib) as Box<dyn ConfigWidget>
-    })
+    let locale = installer.locale.clone()?;
+    let mut lines = vec![
+      vec![(None, "Current locale set to:".to_string())],
+      vec![(HIGHLIGHT, locale)],
+    ];
+    for (category, value) in &installer.locale_settings {
+      lines.push(vec![
+        (None, format!("{category}: ")),
+        (HIGHLIGHT, value.clone()),
+      ]);
+    }
+    if !installer.extra_locales.is_empty() {
+      lines.push(vec![
+        (None, "Also installed: ".to_string()),
+        (HIGHLIGHT, installer.extra_locales.join(", ")),
+      ]);
+    }
+    let ib = InfoBox::new("", styled_block(lines));
+    Some(Box::new(ib) as Box<dyn ConfigWidget>)
   }
   pub fn page_info<'a>() -> (String, Vec<Line<'a>>) {
     (
       "Locale".to_string(),
-      styled_block(vec![vec![(
-        None,
-        "Set the locale for your system, which determines language and regional settings.",
-      )]]),
+      styled_block(vec![
+        vec![(
+          None,
+          "Set the locale for your system, which determines language and regional settings.",
+        )],
+        vec![(
+          None,
+          "Press 'a' on this page to pick e.g. dates or currency from another locale, or install extra locales.",
+        )],
+      ]),
     )
   }
 }
 
 impl Default for Locale {
   fn default() -> Self {
-    Self::new()
+    Self::new(None)
   }
 }
 
 impl Page for Locale {
   fn render(&mut self, _installer: &mut Installer, f: &mut Frame, area: Rect) {
-    let chunks = split_vert!(area, 1, [Constraint::Percentage(100)]);
-    self.locales.render(f, chunks[0]);
+    let chunks = split_vert!(area, 1, [Constraint::Length(3), Constraint::Min(0)]);
+    self.search_bar.render(f, chunks[0]);
+    self.locales.render(f, chunks[1]);
+    self.message.render(f, area);
     self.help_modal.render(f, area);
   }
 
   fn get_help_content(&self) -> (String, Vec<Line<'_>>) {
     let help_content = styled_block(vec![
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "↑/↓, j/k"),
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
         (None, " - Navigate locale options"),
       ],
       vec![
@@ -1411,7 +3060,22 @@ impl Page for Locale {
         (None, " - Select locale and return"),
       ],
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "Esc, q, ←, h"),
+        (Some((Color::Yellow, Modifier::BOLD)), "/"),
+        (None, " - Search locales, Esc clears the search"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "i"),
+        (None, " - Use the running system's setting"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "a"),
+        (None, " - Advanced: per-category and extra locales"),
+      ],
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().back_label,
+        ),
         (None, " - Cancel and return to menu"),
       ],
       vec![
@@ -1425,205 +3089,586 @@ impl Page for Locale {
     ("Locale".to_string(), help_content)
   }
 
+  fn handle_paste(&mut self, _installer: &mut Installer, text: &str) -> Signal {
+    if self.help_modal.visible || self.message.visible {
+      return Signal::Wait;
+    }
+    // Pasting into the list starts a search for it
+    self.search_bar.focus();
+    self.search_bar.paste(text);
+    self.locales.set_filter(search_filter(&self.search_bar));
+    Signal::Wait
+  }
+
   fn handle_input(&mut self, installer: &mut Installer, event: KeyEvent) -> Signal {
+    if self.message.visible {
+      self.message.handle_input(event);
+      return Signal::Wait;
+    }
     match event.code {
       KeyCode::Char('?') => {
         self.help_modal.toggle();
         Signal::Wait
       }
-      ui_close!() if self.help_modal.visible => {
+      code if ui_close!(code) && self.help_modal.visible => {
         self.help_modal.hide();
         Signal::Wait
       }
       _ if self.help_modal.visible => Signal::Wait,
-      ui_back!() => Signal::Pop,
-      ui_up!() => {
+      KeyCode::Esc if self.search_bar.is_focused() => {
+        self.search_bar.clear();
+        self.search_bar.unfocus();
+        self.locales.set_filter(None::<String>);
+        Signal::Wait
+      }
+      KeyCode::Up | KeyCode::Down if self.search_bar.is_focused() => {
+        if event.code == KeyCode::Up {
+          self.locales.previous_item();
+        } else {
+          self.locales.next_item();
+        }
+        Signal::Wait
+      }
+      _ if self.search_bar.is_focused() && event.code != KeyCode::Enter => {
+        let signal = self.search_bar.handle_input(event);
+        self.locales.set_filter(search_filter(&self.search_bar));
+        signal
+      }
+      KeyCode::Char('/') => {
+        self.search_bar.focus();
+        Signal::Wait
+      }
+      code if ui_back!(code) => Signal::Pop,
+      code if ui_up!(code) => {
         if !self.locales.previous_item() {
           self.locales.last_item();
         }
         Signal::Wait
       }
-      ui_down!() => {
+      code if ui_down!(code) => {
         if !self.locales.next_item() {
           self.locales.first_item();
         }
         Signal::Wait
       }
       KeyCode::Enter => {
-        installer.locale = Some(self.locales.items[self.locales.selected_idx].clone());
+        let Some(locale) = self.locales.selected_item() else {
+          return Signal::Wait;
+        };
+        installer.locale = Some(locale.clone());
         Signal::Pop
       }
+      KeyCode::Char('i') => match livesystem::current_locale() {
+        Ok(locale) => {
+          installer.locale = Some(locale);
+          Signal::Pop
+        }
+        Err(e) => {
+          self.message.show(
+            "Couldn't Detect",
+            format!("Couldn't read the running system's locale: {e}"),
+            Color::Yellow,
+          );
+          Signal::Wait
+        }
+      },
+      KeyCode::Char('a') => Signal::Push(Box::new(LocaleSettings::new(installer))),
       _ => self.locales.handle_input(event),
     }
   }
 }
 
-pub struct EnableFlakes {
-  buttons: WidgetBox,
+enum InputMethodFocus {
+  Frameworks,
+  Engines,
+}
+
+pub struct InputMethod {
+  frameworks: StrList,
+  engines: WidgetBox,
+  focus: InputMethodFocus,
   help_modal: HelpModal<'static>,
 }
 
-impl EnableFlakes {
-  pub fn new(checked: bool) -> Self {
-    let toggle = CheckBox::new("Enable Flakes Support", checked);
-    let back_btn = Button::new("Back");
-    let mut buttons = WidgetBox::button_menu(vec![Box::new(toggle), Box::new(back_btn)]);
-    buttons.focus();
+impl InputMethod {
+  const FRAMEWORKS: [&'static str; 3] = ["None", "fcitx5", "ibus"];
+  /// (id, label), the id is what nixgen maps onto a package for each framework
+  pub const ENGINES: [(&'static str, &'static str); 4] = [
+    ("mozc", "Japanese (Mozc)"),
+    ("pinyin", "Chinese (Pinyin)"),
+    ("hangul", "Korean (Hangul)"),
+    ("rime", "Rime (Chinese variants)"),
+  ];
+
+  pub fn new(installer: &Installer) -> Self {
+    let mut frameworks = StrList::new(
+      "Input Method Framework",
+      Self::FRAMEWORKS.iter().map(|s| s.to_string()).collect(),
+    );
+    let engines = match &installer.input_method {
+      Some(cfg) => {
+        if let Some(idx) = Self::FRAMEWORKS.iter().position(|f| *f == cfg.framework) {
+          frameworks.selected_idx = idx;
+        }
+        cfg.engines.clone()
+      }
+      None => Self::default_engines(installer.locale.as_deref()),
+    };
+    frameworks.focus();
+    let engines = WidgetBox::button_menu(
+      Self::ENGINES
+        .iter()
+        .map(|(id, label)| {
+          Box::new(CheckBox::new(*label, engines.iter().any(|e| e == id))) as Box<dyn ConfigWidget>
+        })
+        .collect(),
+    );
     let help_content = styled_block(vec![
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "↑/↓, j/k"),
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
         (None, " - Navigate options"),
       ],
       vec![
         (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
-        (None, " - Toggle option or select Back"),
+        (None, " - Select framework, or toggle an engine"),
       ],
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "Esc, q, ←, h"),
-        (None, " - Cancel and return to menu"),
+        (Some((Color::Yellow, Modifier::BOLD)), "Tab"),
+        (None, " - Switch between frameworks and engines"),
+      ],
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().back_label,
+        ),
+        (None, " - Save and return to menu"),
       ],
       vec![
         (Some((Color::Yellow, Modifier::BOLD)), "?"),
         (None, " - Show this help"),
       ],
       vec![(None, "")],
-      vec![(None, "Enable or disable experimental Nix flakes support.")],
       vec![(
         None,
-        "Flakes provide reproducible builds and easier dependency management.",
+        "Input methods let you type languages that need more than a keyboard layout.",
       )],
     ]);
-    let help_modal = HelpModal::new("Enable Flakes", help_content);
+    let help_modal = HelpModal::new("Input Method", help_content);
     Self {
-      buttons,
+      frameworks,
+      engines,
+      focus: InputMethodFocus::Frameworks,
       help_modal,
     }
   }
-  pub fn display_widget(installer: &mut Installer) -> Option<Box<dyn ConfigWidget>> {
-    let status = if installer.enable_flakes {
-      "enabled"
+  /// Pre-select the engine matching the chosen locale
+  fn default_engines(locale: Option<&str>) -> Vec<String> {
+    let engine = match locale {
+      Some(l) if l.starts_with("ja_") => "mozc",
+      Some(l) if l.starts_with("zh_") => "pinyin",
+      Some(l) if l.starts_with("ko_") => "hangul",
+      _ => return vec![],
+    };
+    vec![engine.to_string()]
+  }
+  fn selected_engines(&self) -> Vec<String> {
+    Self::ENGINES
+      .iter()
+      .enumerate()
+      .filter(|(i, _)| {
+        self
+          .engines
+          .widgets
+          .get(*i)
+          .and_then(|c| c.get_value())
+          .is_some_and(|v| v == Value::Bool(true))
+      })
+      .map(|(_, (id, _))| id.to_string())
+      .collect()
+  }
+  fn save(&self, installer: &mut Installer) {
+    let framework = self.frameworks.items[self.frameworks.selected_idx].clone();
+    installer.input_method = if framework == "None" {
+      None
     } else {
-      "disabled"
+      Some(InputMethodCfg {
+        framework,
+        engines: self.selected_engines(),
+      })
     };
+  }
+  pub fn display_widget(installer: &mut Installer) -> Option<Box<dyn ConfigWidget>> {
+    let cfg = installer.input_method.as_ref()?;
+    let engines = Self::ENGINES
+      .iter()
+      .filter(|(id, _)| cfg.engines.iter().any(|e| e == id))
+      .map(|(_, label)| *label)
+      .collect::<Vec<_>>()
+      .join(", ");
     let ib = InfoBox::new(
       "",
       styled_block(vec![
-        vec![(None, "Flakes support is currently:")],
-        vec![(HIGHLIGHT, status)],
+        vec![
+          (None, "Framework: ".to_string()),
+          (HIGHLIGHT, cfg.framework.clone()),
+        ],
+        vec![(None, "Engines: ".to_string()), (HIGHLIGHT, engines)],
       ]),
     );
     Some(Box::new(ib) as Box<dyn ConfigWidget>)
   }
   pub fn page_info<'a>() -> (String, Vec<Line<'a>>) {
     (
-      "Enable Flakes".to_string(),
+      "Input Method".to_string(),
       styled_block(vec![
         vec![(
           None,
-          "Nix flakes are an experimental feature of the Nix package manager that provide a new way to manage and distribute Nix packages and configurations.",
-        )],
-        vec![(
-          None,
-          "Enabling flakes support allows you to use flake-based configurations and take advantage of features like reproducible builds and easier dependency management.",
+          "Choose an input method framework and the engines used to type Chinese, Japanese or Korean.",
         )],
         vec![(
           None,
-          "Note that flakes are still considered experimental and may not be suitable for all users or use cases.",
+          "fcitx5 works well on both X11 and Wayland, ibus integrates closely with GNOME.",
         )],
       ]),
     )
   }
 }
 
-impl Default for EnableFlakes {
-  fn default() -> Self {
-    Self::new(false)
-  }
-}
-
-impl Page for EnableFlakes {
+impl Page for InputMethod {
   fn render(&mut self, _installer: &mut Installer, f: &mut Frame, area: Rect) {
-    let chunks = split_vert!(
+    let chunks = split_hor!(
       area,
       1,
       [Constraint::Percentage(40), Constraint::Percentage(60)]
     );
-    let hor_chunks = split_hor!(
-      chunks[1],
-      1,
-      [
-        Constraint::Percentage(30),
-        Constraint::Percentage(40),
-        Constraint::Percentage(30),
-      ]
-    );
-    let info_box = InfoBox::new(
-      "",
-      styled_block(vec![
-        vec![(
-          None,
-          "Nix flakes are an experimental feature of the Nix package manager that provide a new way to manage and distribute Nix packages and configurations.",
-        )],
-        vec![(
-          None,
-          "Enabling flakes support allows you to use flake-based configurations and take advantage of features like reproducible builds and easier dependency management.",
-        )],
-        vec![(
-          None,
-          "Note that flakes are still considered experimental and may not be suitable for all users or use cases.",
-        )],
-      ]),
-    );
-    info_box.render(f, chunks[0]);
-    self.buttons.render(f, hor_chunks[1]);
+    self.frameworks.render(f, chunks[0]);
+    self.engines.render(f, chunks[1]);
     self.help_modal.render(f, area);
   }
 
   fn get_help_content(&self) -> (String, Vec<Line<'_>>) {
     let help_content = styled_block(vec![
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "↑/↓, j/k"),
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
         (None, " - Navigate options"),
       ],
       vec![
         (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
-        (None, " - Toggle option or select Back"),
+        (None, " - Select framework, or toggle an engine"),
       ],
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "Esc, q, ←, h"),
-        (None, " - Cancel and return to menu"),
+        (Some((Color::Yellow, Modifier::BOLD)), "Tab"),
+        (None, " - Switch between frameworks and engines"),
+      ],
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().back_label,
+        ),
+        (None, " - Save and return to menu"),
       ],
       vec![
         (Some((Color::Yellow, Modifier::BOLD)), "?"),
         (None, " - Show this help"),
       ],
       vec![(None, "")],
-      vec![(None, "Enable or disable experimental Nix flakes support.")],
       vec![(
         None,
-        "Flakes provide reproducible builds and easier dependency management.",
+        "Input methods let you type languages that need more than a keyboard layout.",
       )],
     ]);
-    ("Enable Flakes".to_string(), help_content)
+    ("Input Method".to_string(), help_content)
   }
 
   fn handle_input(&mut self, installer: &mut Installer, event: KeyEvent) -> Signal {
     match event.code {
       KeyCode::Char('?') => {
         self.help_modal.toggle();
-        Signal::Wait
+        return Signal::Wait;
       }
-      ui_close!() if self.help_modal.visible => {
+      code if ui_close!(code) && self.help_modal.visible => {
         self.help_modal.hide();
-        Signal::Wait
+        return Signal::Wait;
       }
-      _ if self.help_modal.visible => Signal::Wait,
-      ui_back!() => Signal::Pop,
-      ui_up!() => {
-        self.buttons.prev_child();
-        Signal::Wait
+      _ if self.help_modal.visible => return Signal::Wait,
+      code if ui_back!(code) => {
+        self.save(installer);
+        return Signal::Pop;
       }
-      ui_down!() => {
+      KeyCode::Tab => {
+        self.focus = match self.focus {
+          InputMethodFocus::Frameworks => {
+            self.frameworks.unfocus();
+            self.engines.focus();
+            InputMethodFocus::Engines
+          }
+          InputMethodFocus::Engines => {
+            self.engines.unfocus();
+            self.frameworks.focus();
+            InputMethodFocus::Frameworks
+          }
+        };
+        return Signal::Wait;
+      }
+      _ => {}
+    }
+    match self.focus {
+      InputMethodFocus::Frameworks => match event.code {
+        code if ui_up!(code) => {
+          if !self.frameworks.previous_item() {
+            self.frameworks.last_item();
+          }
+          Signal::Wait
+        }
+        code if ui_down!(code) => {
+          if !self.frameworks.next_item() {
+            self.frameworks.first_item();
+          }
+          Signal::Wait
+        }
+        KeyCode::Enter => {
+          if self.frameworks.selected_idx == 0 {
+            self.save(installer);
+            return Signal::Pop;
+          }
+          self.frameworks.unfocus();
+          self.engines.focus();
+          self.focus = InputMethodFocus::Engines;
+          Signal::Wait
+        }
+        _ => Signal::Wait,
+      },
+      InputMethodFocus::Engines => match event.code {
+        code if ui_up!(code) => {
+          self.engines.prev_child();
+          Signal::Wait
+        }
+        code if ui_down!(code) => {
+          self.engines.next_child();
+          Signal::Wait
+        }
+        KeyCode::Enter => {
+          if let Some(chkbox) = self.engines.focused_child_mut() {
+            chkbox.interact();
+          }
+          Signal::Wait
+        }
+        _ => Signal::Wait,
+      },
+    }
+  }
+}
+
+pub struct EnableFlakes {
+  buttons: WidgetBox,
+  help_modal: HelpModal<'static>,
+}
+
+impl EnableFlakes {
+  pub fn new(flakes: bool, nix_command: bool, allow_unfree: bool) -> Self {
+    let toggle = CheckBox::new("Enable Flakes Support", flakes);
+    let nix_command = CheckBox::new("Enable nix-command only", nix_command);
+    let allow_unfree = CheckBox::new("Allow unfree packages", allow_unfree);
+    let back_btn = Button::new("Back");
+    let mut buttons = WidgetBox::button_menu(vec![
+      Box::new(toggle),
+      Box::new(nix_command),
+      Box::new(allow_unfree),
+      Box::new(back_btn),
+    ]);
+    buttons.focus();
+    let help_content = styled_block(vec![
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Navigate options"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
+        (None, " - Toggle option or select Back"),
+      ],
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().back_label,
+        ),
+        (None, " - Cancel and return to menu"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "?"),
+        (None, " - Show this help"),
+      ],
+      vec![(None, "")],
+      vec![(None, "Enable or disable experimental Nix flakes support.")],
+      vec![(
+        None,
+        "Flakes provide reproducible builds and easier dependency management.",
+      )],
+      vec![(
+        None,
+        "nix-command alone enables 'nix shell' and 'nix run' without flakes.",
+      )],
+      vec![(
+        None,
+        "Unfree packages like Steam or NVIDIA's driver need to be allowed to build.",
+      )],
+    ]);
+    let help_modal = HelpModal::new("Enable Flakes", help_content);
+    Self {
+      buttons,
+      help_modal,
+    }
+  }
+  pub fn display_widget(installer: &mut Installer) -> Option<Box<dyn ConfigWidget>> {
+    let status = if installer.enable_flakes {
+      "enabled"
+    } else if installer.enable_nix_command {
+      "disabled, nix-command only"
+    } else {
+      "disabled"
+    };
+    let mut lines = vec![
+      vec![(None, "Flakes support is currently:")],
+      vec![(HIGHLIGHT, status)],
+    ];
+    if installer.allow_unfree {
+      lines.push(vec![(None, "Unfree packages are allowed")]);
+    }
+    let ib = InfoBox::new("", styled_block(lines));
+    Some(Box::new(ib) as Box<dyn ConfigWidget>)
+  }
+  pub fn page_info<'a>() -> (String, Vec<Line<'a>>) {
+    (
+      "Enable Flakes".to_string(),
+      styled_block(vec![
+        vec![(
+          None,
+          "Nix flakes are an experimental feature of the Nix package manager that provide a new way to manage and distribute Nix packages and configurations.",
+        )],
+        vec![(
+          None,
+          "Enabling flakes support allows you to use flake-based configurations and take advantage of features like reproducible builds and easier dependency management.",
+        )],
+        vec![(
+          None,
+          "Note that flakes are still considered experimental and may not be suitable for all users or use cases.",
+        )],
+      ]),
+    )
+  }
+}
+
+impl Default for EnableFlakes {
+  fn default() -> Self {
+    Self::new(false, false, false)
+  }
+}
+
+impl Page for EnableFlakes {
+  fn render(&mut self, _installer: &mut Installer, f: &mut Frame, area: Rect) {
+    let chunks = split_vert!(
+      area,
+      1,
+      [Constraint::Percentage(40), Constraint::Percentage(60)]
+    );
+    let hor_chunks = split_hor!(
+      chunks[1],
+      1,
+      [
+        Constraint::Percentage(30),
+        Constraint::Percentage(40),
+        Constraint::Percentage(30),
+      ]
+    );
+    let info_box = InfoBox::new(
+      "",
+      styled_block(vec![
+        vec![(
+          None,
+          "Nix flakes are an experimental feature of the Nix package manager that provide a new way to manage and distribute Nix packages and configurations.",
+        )],
+        vec![(
+          None,
+          "Enabling flakes support allows you to use flake-based configurations and take advantage of features like reproducible builds and easier dependency management.",
+        )],
+        vec![(
+          None,
+          "Note that flakes are still considered experimental and may not be suitable for all users or use cases.",
+        )],
+      ]),
+    );
+    info_box.render(f, chunks[0]);
+    self.buttons.render(f, hor_chunks[1]);
+    self.help_modal.render(f, area);
+  }
+
+  fn get_help_content(&self) -> (String, Vec<Line<'_>>) {
+    let help_content = styled_block(vec![
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Navigate options"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
+        (None, " - Toggle option or select Back"),
+      ],
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().back_label,
+        ),
+        (None, " - Cancel and return to menu"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "?"),
+        (None, " - Show this help"),
+      ],
+      vec![(None, "")],
+      vec![(None, "Enable or disable experimental Nix flakes support.")],
+      vec![(
+        None,
+        "Flakes provide reproducible builds and easier dependency management.",
+      )],
+      vec![(
+        None,
+        "nix-command alone enables 'nix shell' and 'nix run' without flakes.",
+      )],
+      vec![(
+        None,
+        "Unfree packages like Steam or NVIDIA's driver need to be allowed to build.",
+      )],
+    ]);
+    ("Enable Flakes".to_string(), help_content)
+  }
+
+  fn handle_input(&mut self, installer: &mut Installer, event: KeyEvent) -> Signal {
+    match event.code {
+      KeyCode::Char('?') => {
+        self.help_modal.toggle();
+        Signal::Wait
+      }
+      code if ui_close!(code) && self.help_modal.visible => {
+        self.help_modal.hide();
+        Signal::Wait
+      }
+      _ if self.help_modal.visible => Signal::Wait,
+      code if ui_back!(code) => Signal::Pop,
+      code if ui_up!(code) => {
+        self.buttons.prev_child();
+        Signal::Wait
+      }
+      code if ui_down!(code) => {
         self.buttons.next_child();
         Signal::Wait
       }
@@ -1640,7 +3685,29 @@ impl Page for EnableFlakes {
             installer.enable_flakes = checked;
             Signal::Wait
           }
-          Some(1) => Signal::Pop, // Back
+          Some(1) => {
+            let Some(chkbox) = self.buttons.focused_child_mut() else {
+              return Signal::Wait;
+            };
+            chkbox.interact();
+            let Some(Value::Bool(checked)) = chkbox.get_value() else {
+              return Signal::Wait;
+            };
+            installer.enable_nix_command = checked;
+            Signal::Wait
+          }
+          Some(2) => {
+            let Some(chkbox) = self.buttons.focused_child_mut() else {
+              return Signal::Wait;
+            };
+            chkbox.interact();
+            let Some(Value::Bool(checked)) = chkbox.get_value() else {
+              return Signal::Wait;
+            };
+            installer.allow_unfree = checked;
+            Signal::Wait
+          }
+          Some(3) => Signal::Pop, // Back
           _ => Signal::Wait,
         }
       }
@@ -1651,20 +3718,57 @@ impl Page for EnableFlakes {
 
 pub struct Bootloader {
   loaders: StrList,
+  /// Whole disks GRUB can be installed to, only used on BIOS systems
+  devices: StrList,
+  /// `/dev` paths of the entries in `devices`, in the same order
+  device_paths: Vec<String>,
+  choosing_device: bool,
+  firmware_mode: FirmwareMode,
   help_modal: HelpModal<'static>,
 }
 
 impl Bootloader {
-  pub fn new() -> Self {
-    let loaders = ["GRUB", "systemd-boot"]
-      .iter()
-      .map(|s| s.to_string())
-      .collect::<Vec<_>>();
+  pub fn new(installer: &Installer) -> Self {
+    let firmware_mode = installer.firmware_mode;
+    // systemd-boot is an EFI application, there is nothing to offer it on BIOS
+    let loaders = match firmware_mode {
+      FirmwareMode::Uefi => vec!["GRUB", "systemd-boot"],
+      FirmwareMode::Bios => vec!["GRUB"],
+    };
+    let loaders = loaders.into_iter().map(String::from).collect::<Vec<_>>();
     let mut loaders = StrList::new("Select Bootloader", loaders);
+    loaders.commit_value(installer.bootloader.as_deref());
     loaders.focus();
+
+    let disks = if installer.drives.is_empty() {
+      lsblk().unwrap_or_default()
+    } else {
+      installer.drives.clone()
+    };
+    let device_paths = disks
+      .iter()
+      .map(|d| format!("/dev/{}", d.name()))
+      .collect::<Vec<_>>();
+    let device_labels = disks
+      .iter()
+      .map(|d| format!("/dev/{} ({})", d.name(), bytes_readable(d.size_bytes())))
+      .collect::<Vec<_>>();
+    let mut devices = StrList::new("Install GRUB To", device_labels);
+    let target = installer.grub_device.clone().or_else(|| {
+      installer
+        .drive_config
+        .as_ref()
+        .map(|d| format!("/dev/{}", d.name()))
+    });
+    if let Some(idx) = target.and_then(|t| device_paths.iter().position(|p| *p == t)) {
+      devices.selected_idx = idx;
+    }
     let help_content = styled_block(vec![
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "↑/↓, j/k"),
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
         (None, " - Navigate bootloader options"),
       ],
       vec![
@@ -1672,7 +3776,10 @@ impl Bootloader {
         (None, " - Select bootloader and return"),
       ],
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "Esc, q, ←, h"),
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().back_label,
+        ),
         (None, " - Cancel and return to menu"),
       ],
       vec![
@@ -1684,13 +3791,46 @@ impl Bootloader {
         None,
         "Select the bootloader responsible for loading the operating system.",
       )],
+      vec![(
+        None,
+        "On BIOS systems Enter on GRUB asks for the disk to install it to.",
+      )],
     ]);
     let help_modal = HelpModal::new("Bootloader", help_content);
     Self {
       loaders,
+      devices,
+      device_paths,
+      choosing_device: false,
+      firmware_mode,
       help_modal,
     }
   }
+  fn bios_info<'a>() -> InfoBox<'a> {
+    InfoBox::new(
+      "",
+      styled_block(vec![vec![
+        (None, "This machine booted in "),
+        (HIGHLIGHT, "BIOS mode"),
+        (None, ", systemd-boot needs UEFI and is not offered."),
+      ]]),
+    )
+  }
+  fn device_info<'a>() -> InfoBox<'a> {
+    InfoBox::new(
+      "GRUB Device",
+      styled_block(vec![
+        vec![(
+          None,
+          "On BIOS systems GRUB is written to the start of a whole disk.",
+        )],
+        vec![(
+          None,
+          "Pick the disk the firmware boots from, usually the one NixOS is installed to.",
+        )],
+      ]),
+    )
+  }
   pub fn get_bootloader_info<'a>(idx: usize) -> InfoBox<'a> {
     match idx {
       0 => InfoBox::new(
@@ -1787,7 +3927,7 @@ impl Bootloader {
 
 impl Default for Bootloader {
   fn default() -> Self {
-    Self::new()
+    Self::new(&Installer::default())
   }
 }
 
@@ -1807,10 +3947,26 @@ impl Page for Bootloader {
       ]
     );
 
-    let idx = self.loaders.selected_idx;
-    let info_box = Self::get_bootloader_info(idx);
-    self.loaders.render(f, hor_chunks[1]);
-    info_box.render(f, vert_chunks[1]);
+    let info_chunks = split_vert!(
+      vert_chunks[1],
+      0,
+      [Constraint::Length(3), Constraint::Min(0)]
+    );
+    let info_area = match self.firmware_mode {
+      FirmwareMode::Uefi => vert_chunks[1],
+      FirmwareMode::Bios => {
+        Self::bios_info().render(f, info_chunks[0]);
+        info_chunks[1]
+      }
+    };
+    if self.choosing_device {
+      self.devices.render(f, hor_chunks[1]);
+      Self::device_info().render(f, info_area);
+    } else {
+      let info_box = Self::get_bootloader_info(self.loaders.selected_idx);
+      self.loaders.render(f, hor_chunks[1]);
+      info_box.render(f, info_area);
+    }
 
     self.help_modal.render(f, area);
   }
@@ -1818,7 +3974,10 @@ impl Page for Bootloader {
   fn get_help_content(&self) -> (String, Vec<Line<'_>>) {
     let help_content = styled_block(vec![
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "↑/↓, j/k"),
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
         (None, " - Navigate bootloader options"),
       ],
       vec![
@@ -1826,7 +3985,10 @@ impl Page for Bootloader {
         (None, " - Select bootloader and return"),
       ],
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "Esc, q, ←, h"),
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().back_label,
+        ),
         (None, " - Cancel and return to menu"),
       ],
       vec![
@@ -1838,6 +4000,10 @@ impl Page for Bootloader {
         None,
         "Select the bootloader responsible for loading the operating system.",
       )],
+      vec![(
+        None,
+        "On BIOS systems Enter on GRUB asks for the disk to install it to.",
+      )],
     ]);
     ("Bootloader".to_string(), help_content)
   }
@@ -1848,55 +4014,116 @@ impl Page for Bootloader {
         self.help_modal.toggle();
         Signal::Wait
       }
-      ui_close!() if self.help_modal.visible => {
+      code if ui_close!(code) && self.help_modal.visible => {
         self.help_modal.hide();
         Signal::Wait
       }
       _ if self.help_modal.visible => Signal::Wait,
-      ui_back!() => Signal::Pop,
-      KeyCode::Enter => {
-        installer.bootloader = Some(self.loaders.items[self.loaders.selected_idx].clone());
-        Signal::Pop
-      }
-      ui_up!() => {
-        if !self.loaders.previous_item() {
-          self.loaders.last_item();
-        }
+      code if ui_back!(code) && self.choosing_device => {
+        self.devices.unfocus();
+        self.loaders.focus();
+        self.choosing_device = false;
         Signal::Wait
       }
-      ui_down!() => {
-        if !self.loaders.next_item() {
-          self.loaders.first_item();
-        }
-        Signal::Wait
+      code if ui_back!(code) => Signal::Pop,
+      KeyCode::Enter if self.choosing_device => {
+        let Some(device) = self.device_paths.get(self.devices.selected_idx) else {
+          return Signal::Wait;
+        };
+        // Leaving it unset follows the install target if the disk changes later
+        let target = installer
+          .drive_config
+          .as_ref()
+          .map(|d| format!("/dev/{}", d.name()));
+        installer.grub_device = (Some(device) != target.as_ref()).then(|| device.clone());
+        installer.bootloader = Some("GRUB".to_string());
+        Signal::Pop
+      }
+      KeyCode::Enter => {
+        let loader = self.loaders.items[self.loaders.selected_idx].clone();
+        if self.firmware_mode == FirmwareMode::Bios && !self.device_paths.is_empty() {
+          self.loaders.unfocus();
+          self.devices.focus();
+          self.choosing_device = true;
+          return Signal::Wait;
+        }
+        installer.bootloader = Some(loader);
+        Signal::Pop
+      }
+      code if ui_up!(code) => {
+        let list = if self.choosing_device {
+          &mut self.devices
+        } else {
+          &mut self.loaders
+        };
+        if !list.previous_item() {
+          list.last_item();
+        }
+        Signal::Wait
+      }
+      code if ui_down!(code) => {
+        let list = if self.choosing_device {
+          &mut self.devices
+        } else {
+          &mut self.loaders
+        };
+        if !list.next_item() {
+          list.first_item();
+        }
+        Signal::Wait
       }
+      _ if self.choosing_device => self.devices.handle_input(event),
       _ => self.loaders.handle_input(event),
     }
   }
 }
 
-pub struct Swap {
-  buttons: WidgetBox,
+/// How long the boot menu waits and how many generations it lists
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct BootOptionsCfg {
+  /// Seconds before the default entry boots, 0 skips the menu
+  pub timeout: u32,
+  /// Generations kept in the boot menu, all of them if unset
+  pub configuration_limit: Option<u32>,
+}
+
+impl Default for BootOptionsCfg {
+  fn default() -> Self {
+    // NixOS' own defaults
+    Self {
+      timeout: 5,
+      configuration_limit: None,
+    }
+  }
+}
+
+pub struct BootOptions {
+  timeout: LineEditor,
+  limit: LineEditor,
   help_modal: HelpModal<'static>,
 }
 
-impl Swap {
-  pub fn new(checked: bool) -> Self {
-    let toggle = CheckBox::new("Enable Swap", checked);
-    let back_btn = Button::new("Back");
-    let mut buttons = WidgetBox::button_menu(vec![Box::new(toggle), Box::new(back_btn)]);
-    buttons.focus();
+impl BootOptions {
+  pub fn new(cfg: Option<BootOptionsCfg>) -> Self {
+    let cfg = cfg.unwrap_or_default();
+    let mut timeout = LineEditor::new("Timeout (seconds)", Some("0 boots straight away"));
+    timeout.set_value(cfg.timeout.to_string());
+    timeout.focus();
+    let mut limit = LineEditor::new("Generations to keep", Some("Empty keeps all of them"));
+    if let Some(n) = cfg.configuration_limit {
+      limit.set_value(n.to_string());
+    }
     let help_content = styled_block(vec![
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "↑/↓, j/k"),
-        (None, " - Navigate options"),
+        (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
+        (None, " - Move to next field or save when complete"),
       ],
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
-        (None, " - Toggle option or select Back"),
+        (Some((Color::Yellow, Modifier::BOLD)), "Tab"),
+        (None, " - Switch between fields"),
       ],
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "Esc, q, ←, h"),
+        (Some((Color::Yellow, Modifier::BOLD)), "Esc"),
         (None, " - Cancel and return to menu"),
       ],
       vec![
@@ -1904,114 +4131,126 @@ impl Swap {
         (None, " - Show this help"),
       ],
       vec![(None, "")],
-      vec![(None, "Enable or disable swap space for virtual memory.")],
-      vec![(None, "Recommended for systems with less than 8GB RAM.")],
+      vec![(
+        None,
+        "Keeping fewer generations stops a small boot partition from filling up.",
+      )],
     ]);
-    let help_modal = HelpModal::new("Swap", help_content);
+    let help_modal = HelpModal::new("Boot Options", help_content);
     Self {
-      buttons,
+      timeout,
+      limit,
       help_modal,
     }
   }
   pub fn display_widget(installer: &mut Installer) -> Option<Box<dyn ConfigWidget>> {
-    let status = if installer.use_swap {
-      "enabled"
-    } else {
-      "disabled"
-    };
-    let ib = InfoBox::new(
-      "",
-      styled_block(vec![
-        vec![(None, "Swap is currently:")],
-        vec![(HIGHLIGHT, status)],
-      ]),
-    );
-    Some(Box::new(ib) as Box<dyn ConfigWidget>)
+    installer.boot_options.as_ref().map(|cfg| {
+      let limit = cfg
+        .configuration_limit
+        .map(|n| n.to_string())
+        .unwrap_or_else(|| "all".to_string());
+      let ib = InfoBox::new(
+        "",
+        styled_block(vec![
+          vec![
+            (None, "Timeout: ".to_string()),
+            (HIGHLIGHT, format!("{}s", cfg.timeout)),
+          ],
+          vec![(None, "Generations kept: ".to_string()), (HIGHLIGHT, limit)],
+        ]),
+      );
+      Box::new(ib) as Box<dyn ConfigWidget>
+    })
   }
   pub fn page_info<'a>() -> (String, Vec<Line<'a>>) {
     (
-      "Swap".to_string(),
+      "Boot Options".to_string(),
       styled_block(vec![
         vec![(
           None,
-          "Swap space is a portion of the hard drive that is used as virtual memory when the system's RAM is full.",
-        )],
-        vec![(
-          None,
-          "Enabling swap can help improve system performance and stability, especially on systems with limited RAM.",
-        )],
-        vec![(
-          None,
-          "However, using swap can also lead to slower performance compared to using RAM, as accessing data from the hard drive is slower than accessing data from RAM.",
+          "Set how long the boot menu waits before starting the default entry (boot.loader.timeout).",
         )],
         vec![(
           None,
-          "It's generally recommended to enable swap on systems with less than 8GB of RAM, but the optimal swap size and configuration can vary depending on your specific use case and workload.",
+          "You can also limit how many generations the boot menu lists (configurationLimit). Older ones stay on disk until garbage collected.",
         )],
       ]),
     )
   }
+  fn field_text(editor: &LineEditor) -> String {
+    editor
+      .get_value()
+      .and_then(|v| v.as_str().map(|s| s.trim().to_string()))
+      .unwrap_or_default()
+  }
 }
 
-impl Default for Swap {
+impl Default for BootOptions {
   fn default() -> Self {
-    Self::new(false)
+    Self::new(None)
   }
 }
 
-impl Page for Swap {
-  fn render(&mut self, _installer: &mut Installer, f: &mut Frame, area: Rect) {
+impl Page for BootOptions {
+  fn render(&mut self, installer: &mut Installer, f: &mut Frame, area: Rect) {
     let chunks = split_vert!(
       area,
       1,
-      [Constraint::Percentage(40), Constraint::Percentage(60)]
+      [
+        Constraint::Percentage(40),
+        Constraint::Length(10),
+        Constraint::Percentage(40),
+      ]
     );
     let hor_chunks = split_hor!(
       chunks[1],
       1,
       [
-        Constraint::Percentage(30),
-        Constraint::Percentage(40),
-        Constraint::Percentage(30),
+        Constraint::Percentage(20),
+        Constraint::Percentage(60),
+        Constraint::Percentage(20),
       ]
     );
+    let vert_chunks = split_vert!(
+      hor_chunks[1],
+      0,
+      [Constraint::Length(5), Constraint::Length(5)]
+    );
+    let loader = installer
+      .bootloader
+      .clone()
+      .unwrap_or_else(|| "the bootloader".to_string());
     let info_box = InfoBox::new(
       "",
       styled_block(vec![
         vec![(
           None,
-          "Swap space is a portion of the hard drive that is used as virtual memory when the system's RAM is full.",
-        )],
-        vec![(
-          None,
-          "Enabling swap can help improve system performance and stability, especially on systems with limited RAM.",
-        )],
-        vec![(
-          None,
-          "However, using swap can also lead to slower performance compared to using RAM, as accessing data from the hard drive is slower than accessing data from RAM.",
+          "Set how long the boot menu waits before starting the default entry.".to_string(),
         )],
         vec![(
           None,
-          "It's generally recommended to enable swap on systems with less than 8GB of RAM, but the optimal swap size and configuration can vary depending on your specific use case and workload.",
+          format!("The generation limit applies to {loader}'s menu."),
         )],
       ]),
     );
     info_box.render(f, chunks[0]);
-    self.buttons.render(f, hor_chunks[1]);
+    self.timeout.render(f, vert_chunks[0]);
+    self.limit.render(f, vert_chunks[1]);
     self.help_modal.render(f, area);
   }
+
   fn get_help_content(&self) -> (String, Vec<Line<'_>>) {
     let help_content = styled_block(vec![
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "↑/↓, j/k"),
-        (None, " - Navigate options"),
+        (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
+        (None, " - Move to next field or save when complete"),
       ],
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
-        (None, " - Toggle option or select Back"),
+        (Some((Color::Yellow, Modifier::BOLD)), "Tab"),
+        (None, " - Switch between fields"),
       ],
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "Esc, q, ←, h"),
+        (Some((Color::Yellow, Modifier::BOLD)), "Esc"),
         (None, " - Cancel and return to menu"),
       ],
       vec![
@@ -2019,10 +4258,24 @@ impl Page for Swap {
         (None, " - Show this help"),
       ],
       vec![(None, "")],
-      vec![(None, "Enable or disable swap space for virtual memory.")],
-      vec![(None, "Recommended for systems with less than 8GB RAM.")],
+      vec![(
+        None,
+        "Keeping fewer generations stops a small boot partition from filling up.",
+      )],
     ]);
-    ("Swap".to_string(), help_content)
+    ("Boot Options".to_string(), help_content)
+  }
+
+  fn handle_paste(&mut self, _installer: &mut Installer, text: &str) -> Signal {
+    if self.help_modal.visible {
+      return Signal::Wait;
+    }
+    if self.timeout.is_focused() {
+      self.timeout.paste(text);
+    } else if self.limit.is_focused() {
+      self.limit.paste(text);
+    }
+    Signal::Wait
   }
 
   fn handle_input(&mut self, installer: &mut Installer, event: KeyEvent) -> Signal {
@@ -2031,71 +4284,106 @@ impl Page for Swap {
         self.help_modal.toggle();
         Signal::Wait
       }
-      ui_close!() if self.help_modal.visible => {
+      code if ui_close!(code) && self.help_modal.visible => {
         self.help_modal.hide();
         Signal::Wait
       }
       _ if self.help_modal.visible => Signal::Wait,
-      ui_back!() => Signal::Pop,
-      ui_up!() => {
-        self.buttons.prev_child();
-        Signal::Wait
-      }
-      ui_down!() => {
-        self.buttons.next_child();
+      KeyCode::Esc => Signal::Pop,
+      KeyCode::Tab => {
+        if self.timeout.is_focused() {
+          self.timeout.unfocus();
+          self.limit.focus();
+        } else {
+          self.limit.unfocus();
+          self.timeout.focus();
+        }
         Signal::Wait
       }
       KeyCode::Enter => {
-        match self.buttons.selected_child() {
-          Some(0) => {
-            let Some(chkbox) = self.buttons.focused_child_mut() else {
-              return Signal::Wait;
-            };
-            chkbox.interact();
-            let Some(Value::Bool(checked)) = chkbox.get_value() else {
+        if self.timeout.is_focused() {
+          self.timeout.unfocus();
+          self.limit.focus();
+          return Signal::Wait;
+        }
+        let Ok(timeout) = Self::field_text(&self.timeout).parse::<u32>() else {
+          self.limit.unfocus();
+          self.timeout.focus();
+          self
+            .timeout
+            .error("Must be a whole number of seconds, 0 or more");
+          return Signal::Wait;
+        };
+        let limit = Self::field_text(&self.limit);
+        let configuration_limit = if limit.is_empty() {
+          None
+        } else {
+          match limit.parse::<u32>() {
+            Ok(n) if n > 0 => Some(n),
+            _ => {
+              self
+                .limit
+                .error("Must be a positive whole number, or empty");
               return Signal::Wait;
-            };
-            installer.use_swap = checked;
-            Signal::Wait
+            }
           }
-          Some(1) => Signal::Pop, // Back
-          _ => Signal::Wait,
+        };
+        installer.boot_options = Some(BootOptionsCfg {
+          timeout,
+          configuration_limit,
+        });
+        Signal::Pop
+      }
+      _ => {
+        if self.timeout.is_focused() {
+          self.timeout.handle_input(event)
+        } else {
+          self.limit.handle_input(event)
         }
       }
-      _ => Signal::Wait,
     }
   }
 }
 
-pub struct Hostname {
-  input: LineEditor,
+pub struct Swap {
+  kinds: StrList,
+  percent: LineEditor,
   help_modal: HelpModal<'static>,
 }
 
-impl Hostname {
-  pub fn new() -> Self {
-    let mut input = LineEditor::new("Set Hostname", Some("e.g. 'my-computer'"));
-    input.focus();
+impl Swap {
+  pub fn new(current: SwapKind) -> Self {
+    let choices = SwapKind::CHOICES
+      .iter()
+      .map(|s| s.to_string())
+      .collect::<Vec<_>>();
+    let mut kinds = StrList::new("Swap", choices);
+    kinds.commit_value(Some(current.choice()));
+    kinds.focus();
+    let percent = match current {
+      SwapKind::Zram { percent } => percent,
+      _ => SwapKind::DEFAULT_ZRAM_PERCENT,
+    };
+    let mut percent_input = LineEditor::new("Zram Size (% of RAM)", Some("1 to 100"));
+    percent_input.set_value(percent.to_string());
     let help_content = styled_block(vec![
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
-        (None, " - Save hostname and return"),
-      ],
-      vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "Esc"),
-        (None, " - Cancel and return to menu"),
-      ],
-      vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "←/→"),
-        (None, " - Move cursor"),
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Navigate swap options"),
       ],
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "Home/End"),
-        (None, " - Jump to beginning/end"),
+        (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
+        (None, " - Select option, zram asks for its size first"),
       ],
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "Backspace/Del"),
-        (None, " - Delete characters"),
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().back_label,
+        ),
+        (None, " - Cancel and return to menu"),
       ],
       vec![
         (Some((Color::Yellow, Modifier::BOLD)), "?"),
@@ -2104,116 +4392,148 @@ impl Hostname {
       vec![(None, "")],
       vec![(
         None,
-        "Set a unique hostname for your computer on the network.",
+        "A swap partition has to be created in the manual partitioning layout.",
       )],
     ]);
-    let help_modal = HelpModal::new("Hostname", help_content);
-    Self { input, help_modal }
+    let help_modal = HelpModal::new("Swap", help_content);
+    Self {
+      kinds,
+      percent: percent_input,
+      help_modal,
+    }
   }
   pub fn display_widget(installer: &mut Installer) -> Option<Box<dyn ConfigWidget>> {
-    installer.hostname.clone().map(|s| {
-      let ib = InfoBox::new(
-        "",
-        styled_block(vec![
-          vec![(None, "Current hostname set to:")],
-          vec![(HIGHLIGHT, &s)],
-        ]),
-      );
-      Box::new(ib) as Box<dyn ConfigWidget>
-    })
+    let ib = InfoBox::new(
+      "",
+      styled_block(vec![
+        vec![(None, "Swap is currently:".to_string())],
+        vec![(HIGHLIGHT, installer.swap.to_string())],
+      ]),
+    );
+    Some(Box::new(ib) as Box<dyn ConfigWidget>)
   }
   pub fn page_info<'a>() -> (String, Vec<Line<'a>>) {
     (
-      "Hostname".to_string(),
+      "Swap".to_string(),
       styled_block(vec![
         vec![(
           None,
-          "The hostname is a unique identifier for your computer on a network.",
-        )],
-        vec![(
-          None,
-          "It is used to distinguish your computer from other devices and can be helpful for network management and troubleshooting.",
+          "Swap is used as extra memory when RAM runs full, and is needed to hibernate.",
         )],
         vec![(
           None,
-          "Choose a hostname that is easy to remember and reflects the purpose or identity of your computer.",
+          "It's generally recommended on systems with less than 8GB of RAM.",
         )],
       ]),
     )
   }
+  /// What each kind of swap costs and gets you
+  fn tradeoffs<'a>() -> Vec<Line<'a>> {
+    styled_block(vec![
+      vec![
+        (HIGHLIGHT, "Swapfile"),
+        (
+          None,
+          " - a 4GB file on the root filesystem. Easy to resize or remove later, but doesn't work on btrfs without extra setup.",
+        ),
+      ],
+      vec![
+        (HIGHLIGHT, "Swap partition"),
+        (
+          None,
+          " - a dedicated linux-swap partition. Works with any root filesystem and is the usual choice for hibernation, but its size is fixed by the partition layout.",
+        ),
+      ],
+      vec![
+        (HIGHLIGHT, "Zram"),
+        (
+          None,
+          " - a compressed block device in RAM. Fast and needs no disk space, but can't be hibernated to and only helps as far as memory compresses.",
+        ),
+      ],
+    ])
+  }
+  fn selected_kind(&self) -> Option<SwapKind> {
+    self
+      .kinds
+      .selected_item()
+      .and_then(|choice| SwapKind::from_choice(choice))
+  }
+  fn parse_percent(&self) -> Result<u8, String> {
+    self
+      .percent
+      .get_value()
+      .and_then(|v| v.as_str().and_then(|s| s.trim().parse::<u8>().ok()))
+      .filter(|percent| (1..=100).contains(percent))
+      .ok_or_else(|| "Enter a percentage from 1 to 100".to_string())
+  }
 }
 
-impl Default for Hostname {
+impl Default for Swap {
   fn default() -> Self {
-    Self::new()
+    Self::new(SwapKind::None)
   }
 }
 
-impl Page for Hostname {
+impl Page for Swap {
   fn render(&mut self, _installer: &mut Installer, f: &mut Frame, area: Rect) {
     let chunks = split_vert!(
       area,
       1,
       [
         Constraint::Percentage(40),
-        Constraint::Length(5),
-        Constraint::Percentage(40),
+        Constraint::Length(6),
+        Constraint::Length(3),
+        Constraint::Min(0),
       ]
     );
     let hor_chunks = split_hor!(
       chunks[1],
-      0,
+      1,
       [
-        Constraint::Percentage(10),
-        Constraint::Percentage(80),
-        Constraint::Percentage(10),
+        Constraint::Percentage(30),
+        Constraint::Percentage(40),
+        Constraint::Percentage(30),
       ]
     );
-
-    let info_box = InfoBox::new(
-      "",
-      styled_block(vec![
-        vec![(
-          None,
-          "The hostname is a unique identifier for your computer on a network.",
-        )],
-        vec![(
-          None,
-          "It is used to distinguish your computer from other devices and can be helpful for network management and troubleshooting.",
-        )],
-        vec![(
-          None,
-          "Choose a hostname that is easy to remember and reflects the purpose or identity of your computer.",
-        )],
-      ]),
-    );
-
-    info_box.render(f, chunks[0]);
-    self.input.render(f, hor_chunks[1]);
+    let mut info = Self::page_info().1;
+    info.push(Line::from(""));
+    info.extend(Self::tradeoffs());
+    InfoBox::new("", info).render(f, chunks[0]);
+    self.kinds.render(f, hor_chunks[1]);
+    if let Some(SwapKind::Zram { .. }) = self.selected_kind() {
+      let input_chunks = split_hor!(
+        chunks[2],
+        0,
+        [
+          Constraint::Percentage(30),
+          Constraint::Percentage(40),
+          Constraint::Percentage(30),
+        ]
+      );
+      self.percent.render(f, input_chunks[1]);
+    }
     self.help_modal.render(f, area);
   }
-
   fn get_help_content(&self) -> (String, Vec<Line<'_>>) {
     let help_content = styled_block(vec![
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
-        (None, " - Save hostname and return"),
-      ],
-      vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "Esc"),
-        (None, " - Cancel and return to menu"),
-      ],
-      vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "←/→"),
-        (None, " - Move cursor"),
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Navigate swap options"),
       ],
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "Home/End"),
-        (None, " - Jump to beginning/end"),
+        (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
+        (None, " - Select option, zram asks for its size first"),
       ],
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "Backspace/Del"),
-        (None, " - Delete characters"),
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().back_label,
+        ),
+        (None, " - Cancel and return to menu"),
       ],
       vec![
         (Some((Color::Yellow, Modifier::BOLD)), "?"),
@@ -2222,63 +4542,94 @@ impl Page for Hostname {
       vec![(None, "")],
       vec![(
         None,
-        "Set a unique hostname for your computer on the network.",
+        "A swap partition has to be created in the manual partitioning layout.",
       )],
     ]);
-    ("Hostname".to_string(), help_content)
+    ("Swap".to_string(), help_content)
+  }
+
+  fn handle_paste(&mut self, _installer: &mut Installer, text: &str) -> Signal {
+    if !self.help_modal.visible && self.percent.is_focused() {
+      self.percent.paste(text);
+    }
+    Signal::Wait
   }
 
   fn handle_input(&mut self, installer: &mut Installer, event: KeyEvent) -> Signal {
+    if self.percent.is_focused() {
+      return match event.code {
+        KeyCode::Esc => {
+          self.percent.unfocus();
+          self.kinds.focus();
+          Signal::Wait
+        }
+        KeyCode::Enter => match self.parse_percent() {
+          Ok(percent) => {
+            installer.swap = SwapKind::Zram { percent };
+            Signal::Pop
+          }
+          Err(e) => {
+            self.percent.error(e);
+            Signal::Wait
+          }
+        },
+        _ => self.percent.handle_input(event),
+      };
+    }
+
     match event.code {
       KeyCode::Char('?') => {
         self.help_modal.toggle();
         Signal::Wait
       }
-      ui_close!() if self.help_modal.visible => {
+      code if ui_close!(code) && self.help_modal.visible => {
         self.help_modal.hide();
         Signal::Wait
       }
       _ if self.help_modal.visible => Signal::Wait,
-      KeyCode::Esc => Signal::Pop,
-      KeyCode::Enter => {
-        let hostname = self
-          .input
-          .get_value()
-          .unwrap()
-          .as_str()
-          .unwrap()
-          .trim()
-          .to_string();
-        if !hostname.is_empty() {
-          installer.hostname = Some(hostname);
+      code if ui_back!(code) => Signal::Pop,
+      code if ui_up!(code) => {
+        if !self.kinds.previous_item() {
+          self.kinds.last_item();
         }
-        Signal::Pop
+        Signal::Wait
       }
-      _ => self.input.handle_input(event),
+      code if ui_down!(code) => {
+        if !self.kinds.next_item() {
+          self.kinds.first_item();
+        }
+        Signal::Wait
+      }
+      KeyCode::Enter => match self.selected_kind() {
+        Some(SwapKind::Zram { .. }) => {
+          self.kinds.unfocus();
+          self.percent.focus();
+          Signal::Wait
+        }
+        Some(kind) => {
+          installer.swap = kind;
+          Signal::Pop
+        }
+        None => Signal::Wait,
+      },
+      _ => self.kinds.handle_input(event),
     }
   }
 }
 
-pub struct RootPassword {
+pub struct Hostname {
   input: LineEditor,
-  confirm: LineEditor,
   help_modal: HelpModal<'static>,
 }
 
-impl RootPassword {
+impl Hostname {
   pub fn new() -> Self {
-    let mut input =
-      LineEditor::new("Set Root Password", Some("Password will be hidden")).secret(true);
-    let confirm = LineEditor::new("Confirm Password", Some("Password will be hidden")).secret(true);
+    let mut input = LineEditor::new("Set Hostname", Some("e.g. 'my-computer'"));
     input.focus();
     let help_content = styled_block(vec![
       vec![
         (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
-        (None, " - Move to next field or save when complete"),
-      ],
-      vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "Tab"),
-        (None, " - Switch between password fields"),
+        (None, " - Save hostname and return"),
       ],
       vec![
         (Some((Color::Yellow, Modifier::BOLD)), "Esc"),
@@ -2292,6 +4643,17 @@ impl RootPassword {
         (Some((Color::Yellow, Modifier::BOLD)), "Home/End"),
         (None, " - Jump to beginning/end"),
       ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Ctrl+←/→"),
+        (None, " - Jump by word"),
+      ],
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          "Ctrl+W, Alt+Backspace",
+        ),
+        (None, " - Delete the previous word"),
+      ],
       vec![
         (Some((Color::Yellow, Modifier::BOLD)), "Backspace/Del"),
         (None, " - Delete characters"),
@@ -2301,132 +4663,94 @@ impl RootPassword {
         (None, " - Show this help"),
       ],
       vec![(None, "")],
-      vec![(None, "Set a strong root password for system security.")],
+      vec![(
+        None,
+        "Set a unique hostname for your computer on the network.",
+      )],
     ]);
-    let help_modal = HelpModal::new("Root Password", help_content);
-    Self {
-      input,
-      confirm,
-      help_modal,
-    }
+    let help_modal = HelpModal::new("Hostname", help_content);
+    Self { input, help_modal }
+  }
+  pub fn display_widget(installer: &mut Installer) -> Option<Box<dyn ConfigWidget>> {
+    installer.hostname.clone().map(|s| {
+      let ib = InfoBox::new(
+        "",
+        styled_block(vec![
+          vec![(None, "Current hostname set to:")],
+          vec![(HIGHLIGHT, &s)],
+        ]),
+      );
+      Box::new(ib) as Box<dyn ConfigWidget>
+    })
   }
   pub fn page_info<'a>() -> (String, Vec<Line<'a>>) {
     (
-      "Root Password".to_string(),
+      "Hostname".to_string(),
       styled_block(vec![
         vec![(
           None,
-          "The root user is the superuser account on a Unix-like operating system, including Linux.",
-        )],
-        vec![(
-          None,
-          "It has full administrative privileges and can perform any action on the system, including installing software, modifying system settings, and accessing all files and directories.",
+          "The hostname is a unique identifier for your computer on a network.",
         )],
         vec![(
           None,
-          "Setting a strong password for the root user is important for system security, as it helps prevent unauthorized access to sensitive system functions and data.",
+          "It is used to distinguish your computer from other devices and can be helpful for network management and troubleshooting.",
         )],
         vec![(
           None,
-          "Choose a password that is difficult to guess and contains a mix of uppercase and lowercase letters, numbers, and special characters.",
+          "Choose a hostname that is easy to remember and reflects the purpose or identity of your computer.",
         )],
       ]),
     )
   }
-  pub fn display_widget(installer: &mut Installer) -> Option<Box<dyn ConfigWidget>> {
-    installer.root_passwd_hash.as_ref().map(|_| {
-      let ib = InfoBox::new(
-        "",
-        styled_block(vec![vec![(HIGHLIGHT, "Root password is set.")]]),
-      );
-      Box::new(ib) as Box<dyn ConfigWidget>
-    })
-  }
-  pub fn mkpasswd(passwd: String) -> anyhow::Result<String> {
-    let mut child = Command::new("mkpasswd")
-      .arg("--method=SHA-512")
-      .arg("--rounds=4096")
-      .arg("--stdin")
-      .stdin(Stdio::piped())
-      .stdout(Stdio::piped())
-      .spawn()?;
-    {
-      let stdin = child
-        .stdin
-        .as_mut()
-        .ok_or_else(|| anyhow::anyhow!("Failed to open stdin"))?;
-      stdin.write_all(passwd.as_bytes())?;
-    }
-    let output = child.wait_with_output()?;
-    if output.status.success() {
-      let hashed = String::from_utf8_lossy(&output.stdout).trim().to_string();
-      Ok(hashed)
-    } else {
-      Err(anyhow::anyhow!(
-        "mkpasswd failed: {}",
-        String::from_utf8_lossy(&output.stderr)
-      ))
-    }
-  }
 }
 
-impl Default for RootPassword {
+impl Default for Hostname {
   fn default() -> Self {
     Self::new()
   }
 }
 
-impl Page for RootPassword {
+impl Page for Hostname {
   fn render(&mut self, _installer: &mut Installer, f: &mut Frame, area: Rect) {
     let chunks = split_vert!(
       area,
       1,
       [
         Constraint::Percentage(40),
-        Constraint::Length(12),
+        Constraint::Length(5),
         Constraint::Percentage(40),
       ]
     );
     let hor_chunks = split_hor!(
       chunks[1],
-      1,
+      0,
       [
-        Constraint::Percentage(20),
-        Constraint::Percentage(60),
-        Constraint::Percentage(20),
+        Constraint::Percentage(10),
+        Constraint::Percentage(80),
+        Constraint::Percentage(10),
       ]
     );
-    let vert_chunks = split_vert!(
-      hor_chunks[1],
-      0,
-      [Constraint::Length(5), Constraint::Length(5)]
-    );
 
     let info_box = InfoBox::new(
       "",
       styled_block(vec![
         vec![(
           None,
-          "The root user is the superuser account on a Unix-like operating system, including Linux.",
-        )],
-        vec![(
-          None,
-          "It has full administrative privileges and can perform any action on the system, including installing software, modifying system settings, and accessing all files and directories.",
+          "The hostname is a unique identifier for your computer on a network.",
         )],
         vec![(
           None,
-          "Setting a strong password for the root user is important for system security, as it helps prevent unauthorized access to sensitive system functions and data.",
+          "It is used to distinguish your computer from other devices and can be helpful for network management and troubleshooting.",
         )],
         vec![(
           None,
-          "Choose a password that is difficult to guess and contains a mix of uppercase and lowercase letters, numbers, and special characters.",
+          "Choose a hostname that is easy to remember and reflects the purpose or identity of your computer.",
         )],
       ]),
     );
 
     info_box.render(f, chunks[0]);
-    self.input.render(f, vert_chunks[0]);
-    self.confirm.render(f, vert_chunks[1]);
+    self.input.render(f, hor_chunks[1]);
     self.help_modal.render(f, area);
   }
 
@@ -2434,11 +4758,7 @@ impl Page for RootPassword {
     let help_content = styled_block(vec![
       vec![
         (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
-        (None, " - Move to next field or save when complete"),
-      ],
-      vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "Tab"),
-        (None, " - Switch between password fields"),
+        (None, " - Save hostname and return"),
       ],
       vec![
         (Some((Color::Yellow, Modifier::BOLD)), "Esc"),
@@ -2452,6 +4772,17 @@ impl Page for RootPassword {
         (Some((Color::Yellow, Modifier::BOLD)), "Home/End"),
         (None, " - Jump to beginning/end"),
       ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Ctrl+←/→"),
+        (None, " - Jump by word"),
+      ],
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          "Ctrl+W, Alt+Backspace",
+        ),
+        (None, " - Delete the previous word"),
+      ],
       vec![
         (Some((Color::Yellow, Modifier::BOLD)), "Backspace/Del"),
         (None, " - Delete characters"),
@@ -2461,9 +4792,19 @@ impl Page for RootPassword {
         (None, " - Show this help"),
       ],
       vec![(None, "")],
-      vec![(None, "Set a strong root password for system security.")],
+      vec![(
+        None,
+        "Set a unique hostname for your computer on the network.",
+      )],
     ]);
-    ("Root Password".to_string(), help_content)
+    ("Hostname".to_string(), help_content)
+  }
+
+  fn handle_paste(&mut self, _installer: &mut Installer, text: &str) -> Signal {
+    if !self.help_modal.visible {
+      self.input.paste(text);
+    }
+    Signal::Wait
   }
 
   fn handle_input(&mut self, installer: &mut Installer, event: KeyEvent) -> Signal {
@@ -2472,106 +4813,74 @@ impl Page for RootPassword {
         self.help_modal.toggle();
         Signal::Wait
       }
-      ui_close!() if self.help_modal.visible => {
+      code if ui_close!(code) && self.help_modal.visible => {
         self.help_modal.hide();
         Signal::Wait
       }
       _ if self.help_modal.visible => Signal::Wait,
       KeyCode::Esc => Signal::Pop,
-      KeyCode::Tab => {
-        if self.input.is_focused() {
-          self.input.unfocus();
-          self.confirm.focus();
-        } else {
-          self.confirm.unfocus();
-          self.input.focus();
-        }
-        Signal::Wait
-      }
-      KeyCode::Enter => {
-        if self.input.is_focused() {
-          self.input.unfocus();
-          self.confirm.focus();
-          Signal::Wait
-        } else {
-          let passwd = self
-            .input
-            .get_value()
-            .unwrap()
-            .as_str()
-            .unwrap()
-            .trim()
-            .to_string();
-          let confirm = self
-            .confirm
-            .get_value()
-            .unwrap()
-            .as_str()
-            .unwrap()
-            .trim()
-            .to_string();
-          if passwd.is_empty() {
-            Signal::Wait // Ignore empty passwords
-          } else if passwd != confirm {
-            self.input.clear();
-            self.confirm.clear();
-            self.confirm.unfocus();
-            self.input.focus();
-            self.input.error("Passwords do not match");
-            Signal::Wait // Passwords do not match
-          } else {
-            match Self::mkpasswd(passwd) {
-              Ok(hashed) => {
-                installer.root_passwd_hash = Some(hashed);
-                Signal::Pop
-              }
-              Err(e) => {
-                self.input.clear();
-                self.confirm.clear();
-                self.confirm.unfocus();
-                self.input.focus();
-                self.input.error(format!("Error hashing password: {e}"));
-                Signal::Wait
-              }
-            }
-          }
-        }
-      }
-      _ => {
-        if self.input.is_focused() {
-          self.input.handle_input(event)
-        } else {
-          self.confirm.handle_input(event)
+      KeyCode::Enter => {
+        let hostname = self
+          .input
+          .get_value()
+          .unwrap()
+          .as_str()
+          .unwrap()
+          .trim()
+          .to_string();
+        if !hostname.is_empty() {
+          installer.hostname = Some(hostname);
         }
+        Signal::Pop
       }
+      _ => self.input.handle_input(event),
     }
   }
 }
 
-pub struct Profile {
-  profiles: StrList,
+/// Number of logical CPUs, as listed in /proc/cpuinfo
+pub fn detect_cpu_count() -> u32 {
+  std::fs::read_to_string("/proc/cpuinfo")
+    .ok()
+    .map(|info| {
+      info
+        .lines()
+        .filter(|line| line.starts_with("processor"))
+        .count() as u32
+    })
+    .filter(|&n| n > 0)
+    .unwrap_or(1)
+}
+
+pub struct BuildJobs {
+  max_jobs: LineEditor,
+  cores: LineEditor,
   help_modal: HelpModal<'static>,
 }
 
-impl Profile {
-  pub fn new() -> Self {
-    let profiles = ["Minimal", "Desktop", "Server", "Custom"]
-      .iter()
-      .map(|s| s.to_string())
-      .collect::<Vec<_>>();
-    let mut profiles = StrList::new("Select Profile", profiles);
-    profiles.focus();
+impl BuildJobs {
+  pub fn new(cfg: Option<NixBuildCfg>) -> Self {
+    let cpus = detect_cpu_count();
+    let cfg = cfg.unwrap_or(NixBuildCfg {
+      max_jobs: cpus,
+      cores: cpus,
+    });
+    let mut max_jobs = LineEditor::new("Max Jobs", Some("Number of derivations built in parallel"));
+    max_jobs.set_value(cfg.max_jobs.to_string());
+    max_jobs.focus();
+    let mut cores = LineEditor::new("Cores", Some("Cores available to each build"));
+    cores.set_value(cfg.cores.to_string());
     let help_content = styled_block(vec![
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "↑/↓, j/k"),
-        (None, " - Navigate profile options"),
+        (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
+        (None, " - Move to next field or save when complete"),
       ],
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
-        (None, " - Select profile and return"),
+        (Some((Color::Yellow, Modifier::BOLD)), "Tab"),
+        (None, " - Switch between fields"),
       ],
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "Esc, q, ←, h"),
+        (Some((Color::Yellow, Modifier::BOLD)), "Esc"),
         (None, " - Cancel and return to menu"),
       ],
       vec![
@@ -2581,22 +4890,29 @@ impl Profile {
       vec![(None, "")],
       vec![(
         None,
-        "Select a predefined profile that matches your intended use case.",
+        "Limit how many builds Nix runs at once, both during install and on the new system.",
       )],
     ]);
-    let help_modal = HelpModal::new("Profile", help_content);
+    let help_modal = HelpModal::new("Build Jobs", help_content);
     Self {
-      profiles,
+      max_jobs,
+      cores,
       help_modal,
     }
   }
   pub fn display_widget(installer: &mut Installer) -> Option<Box<dyn ConfigWidget>> {
-    installer.profile.clone().map(|s| {
+    installer.nix_build.as_ref().map(|cfg| {
       let ib = InfoBox::new(
         "",
         styled_block(vec![
-          vec![(None, "Current profile set to:")],
-          vec![(HIGHLIGHT, &s)],
+          vec![
+            (None, "Max jobs: ".to_string()),
+            (HIGHLIGHT, cfg.max_jobs.to_string()),
+          ],
+          vec![
+            (None, "Cores: ".to_string()),
+            (HIGHLIGHT, cfg.cores.to_string()),
+          ],
         ]),
       );
       Box::new(ib) as Box<dyn ConfigWidget>
@@ -2604,54 +4920,89 @@ impl Profile {
   }
   pub fn page_info<'a>() -> (String, Vec<Line<'a>>) {
     (
-      "Profile".to_string(),
+      "Build Jobs".to_string(),
       styled_block(vec![
         vec![(
           None,
-          "Select a predefined profile that best matches your intended use case for the system.",
-        )],
-        vec![(
-          None,
-          "Profiles are collections of settings and packages that are tailored for specific use cases, such as desktop or server environments.",
+          "Control how many derivations Nix builds in parallel (max-jobs) and how many cores each build may use (cores).",
         )],
         vec![(
           None,
-          "Choosing a profile can help simplify the installation process and ensure that your system is configured appropriately for your needs.",
-        )],
-        vec![(
-          None,
-          "You can always customize the configuration further after the installation is complete.",
+          "Both default to the number of CPUs detected on this machine. The values are written to nix.settings and also used for the install itself.",
         )],
       ]),
     )
   }
+  fn parse_positive(editor: &LineEditor) -> Option<u32> {
+    editor
+      .get_value()
+      .and_then(|v| v.as_str().and_then(|s| s.trim().parse::<u32>().ok()))
+      .filter(|&n| n > 0)
+  }
 }
 
-impl Default for Profile {
+impl Default for BuildJobs {
   fn default() -> Self {
-    Self::new()
+    Self::new(None)
   }
 }
 
-impl Page for Profile {
+impl Page for BuildJobs {
   fn render(&mut self, _installer: &mut Installer, f: &mut Frame, area: Rect) {
-    let chunks = split_vert!(area, 1, [Constraint::Percentage(100)]);
-    self.profiles.render(f, chunks[0]);
+    let chunks = split_vert!(
+      area,
+      1,
+      [
+        Constraint::Percentage(40),
+        Constraint::Length(10),
+        Constraint::Percentage(40),
+      ]
+    );
+    let hor_chunks = split_hor!(
+      chunks[1],
+      1,
+      [
+        Constraint::Percentage(20),
+        Constraint::Percentage(60),
+        Constraint::Percentage(20),
+      ]
+    );
+    let vert_chunks = split_vert!(
+      hor_chunks[1],
+      0,
+      [Constraint::Length(5), Constraint::Length(5)]
+    );
+    let info_box = InfoBox::new(
+      "",
+      styled_block(vec![
+        vec![(
+          None,
+          "Control how many derivations Nix builds in parallel (max-jobs) and how many cores each build may use (cores).".to_string(),
+        )],
+        vec![(
+          None,
+          format!("{} CPUs were detected on this machine.", detect_cpu_count()),
+        )],
+      ]),
+    );
+    info_box.render(f, chunks[0]);
+    self.max_jobs.render(f, vert_chunks[0]);
+    self.cores.render(f, vert_chunks[1]);
     self.help_modal.render(f, area);
   }
 
   fn get_help_content(&self) -> (String, Vec<Line<'_>>) {
     let help_content = styled_block(vec![
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "↑/↓, j/k"),
-        (None, " - Navigate profile options"),
+        (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
+        (None, " - Move to next field or save when complete"),
       ],
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
-        (None, " - Select profile and return"),
+        (Some((Color::Yellow, Modifier::BOLD)), "Tab"),
+        (None, " - Switch between fields"),
       ],
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "Esc, q, ←, h"),
+        (Some((Color::Yellow, Modifier::BOLD)), "Esc"),
         (None, " - Cancel and return to menu"),
       ],
       vec![
@@ -2661,10 +5012,22 @@ impl Page for Profile {
       vec![(None, "")],
       vec![(
         None,
-        "Select a predefined profile that matches your intended use case.",
+        "Limit how many builds Nix runs at once, both during install and on the new system.",
       )],
     ]);
-    ("Profile".to_string(), help_content)
+    ("Build Jobs".to_string(), help_content)
+  }
+
+  fn handle_paste(&mut self, _installer: &mut Installer, text: &str) -> Signal {
+    if self.help_modal.visible {
+      return Signal::Wait;
+    }
+    if self.max_jobs.is_focused() {
+      self.max_jobs.paste(text);
+    } else if self.cores.is_focused() {
+      self.cores.paste(text);
+    }
+    Signal::Wait
   }
 
   fn handle_input(&mut self, installer: &mut Installer, event: KeyEvent) -> Signal {
@@ -2673,46 +5036,122 @@ impl Page for Profile {
         self.help_modal.toggle();
         Signal::Wait
       }
-      ui_close!() if self.help_modal.visible => {
+      code if ui_close!(code) && self.help_modal.visible => {
         self.help_modal.hide();
         Signal::Wait
       }
       _ if self.help_modal.visible => Signal::Wait,
-      ui_down!() => Signal::Pop,
+      KeyCode::Esc => Signal::Pop,
+      KeyCode::Tab => {
+        if self.max_jobs.is_focused() {
+          self.max_jobs.unfocus();
+          self.cores.focus();
+        } else {
+          self.cores.unfocus();
+          self.max_jobs.focus();
+        }
+        Signal::Wait
+      }
       KeyCode::Enter => {
-        installer.profile = Some(self.profiles.items[self.profiles.selected_idx].clone());
+        if self.max_jobs.is_focused() {
+          self.max_jobs.unfocus();
+          self.cores.focus();
+          return Signal::Wait;
+        }
+        let Some(max_jobs) = Self::parse_positive(&self.max_jobs) else {
+          self.cores.unfocus();
+          self.max_jobs.focus();
+          self.max_jobs.error("Must be a positive whole number");
+          return Signal::Wait;
+        };
+        let Some(cores) = Self::parse_positive(&self.cores) else {
+          self.cores.error("Must be a positive whole number");
+          return Signal::Wait;
+        };
+        installer.nix_build = Some(NixBuildCfg { max_jobs, cores });
         Signal::Pop
       }
-      _ => self.profiles.handle_input(event),
+      _ => {
+        if self.max_jobs.is_focused() {
+          self.max_jobs.handle_input(event)
+        } else {
+          self.cores.handle_input(event)
+        }
+      }
     }
   }
 }
 
-pub struct Greeter {
-  greeters: StrList,
+/// Check that a schedule looks like a systemd calendar expression
+///
+/// `systemd-analyze` is the authority when it's available, otherwise fall back
+/// to rejecting anything with characters a calendar spec can't contain.
+pub fn validate_calendar(expr: &str) -> Result<(), String> {
+  let expr = expr.trim();
+  if expr.is_empty() {
+    return Err("Schedule cannot be empty".into());
+  }
+  if let Ok(output) = command!("systemd-analyze", "calendar", expr).output() {
+    return if output.status.success() {
+      Ok(())
+    } else {
+      Err(format!(
+        "'{expr}' is not a valid systemd calendar expression"
+      ))
+    };
+  }
+  let allowed = |c: char| c.is_ascii_alphanumeric() || " *:/,.~-".contains(c);
+  if expr.chars().all(allowed) {
+    Ok(())
+  } else {
+    Err(format!(
+      "'{expr}' is not a valid systemd calendar expression"
+    ))
+  }
+}
+
+enum AutoUpgradeInputMode {
+  Buttons,
+  Schedule,
+}
+
+pub struct AutoUpgrade {
+  buttons: WidgetBox,
+  schedule_input: LineEditor,
+  input_mode: AutoUpgradeInputMode,
+  cfg: AutoUpgradeCfg,
   help_modal: HelpModal<'static>,
 }
 
-impl Greeter {
-  pub fn new() -> Self {
-    let greeters = ["LightDM", "GDM", "SDDM", "None"]
-      .iter()
-      .map(|s| s.to_string())
-      .collect::<Vec<_>>();
-    let mut greeters = StrList::new("Select Greeter", greeters);
-    greeters.focus();
+impl AutoUpgrade {
+  pub fn new(cfg: AutoUpgradeCfg) -> Self {
+    let mut buttons = WidgetBox::button_menu(vec![
+      Box::new(CheckBox::new("Enable automatic upgrades", cfg.enable)),
+      Box::new(CheckBox::new("Allow automatic reboot", cfg.allow_reboot)),
+      Box::new(Button::new("Set Schedule")),
+      Box::new(Button::new("Back")),
+    ]);
+    buttons.focus();
+    let mut schedule_input = LineEditor::new("Upgrade Schedule", Some("e.g. daily, Sun 04:00"));
+    schedule_input.set_value(&cfg.dates);
     let help_content = styled_block(vec![
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "↑/↓, j/k"),
-        (None, " - Navigate greeter options"),
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Navigate options"),
       ],
       vec![
         (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
-        (None, " - Select greeter and return"),
+        (None, " - Toggle option or select action"),
       ],
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "Esc, q, ←, h"),
-        (None, " - Cancel and return to menu"),
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().back_label,
+        ),
+        (None, " - Save and return to menu"),
       ],
       vec![
         (Some((Color::Yellow, Modifier::BOLD)), "?"),
@@ -2721,70 +5160,131 @@ impl Greeter {
       vec![(None, "")],
       vec![(
         None,
-        "Select the display manager for the graphical login screen.",
+        "The schedule uses systemd calendar syntax, see 'man systemd.time'.",
       )],
     ]);
-    let help_modal = HelpModal::new("Greeter", help_content);
+    let help_modal = HelpModal::new("Automatic Upgrades", help_content);
     Self {
-      greeters,
+      buttons,
+      schedule_input,
+      input_mode: AutoUpgradeInputMode::Buttons,
+      cfg,
       help_modal,
     }
   }
   pub fn display_widget(installer: &mut Installer) -> Option<Box<dyn ConfigWidget>> {
-    installer.greeter.clone().map(|s| {
-      let ib = InfoBox::new(
-        "",
-        styled_block(vec![
-          vec![(None, "Current greeter set to:")],
-          vec![(HIGHLIGHT, &s)],
-        ]),
-      );
-      Box::new(ib) as Box<dyn ConfigWidget>
-    })
+    let cfg = installer.auto_upgrade.as_ref()?;
+    let lines = if cfg.enable {
+      vec![
+        vec![
+          (None, "Automatic upgrades: ".to_string()),
+          (HIGHLIGHT, "enabled".to_string()),
+        ],
+        vec![
+          (None, "Schedule: ".to_string()),
+          (HIGHLIGHT, cfg.dates.clone()),
+        ],
+        vec![
+          (None, "Allow reboot: ".to_string()),
+          (
+            HIGHLIGHT,
+            if cfg.allow_reboot { "yes" } else { "no" }.to_string(),
+          ),
+        ],
+      ]
+    } else {
+      vec![vec![
+        (None, "Automatic upgrades: ".to_string()),
+        (HIGHLIGHT, "disabled".to_string()),
+      ]]
+    };
+    let ib = InfoBox::new("", styled_block(lines));
+    Some(Box::new(ib) as Box<dyn ConfigWidget>)
   }
   pub fn page_info<'a>() -> (String, Vec<Line<'a>>) {
     (
-      "Greeter".to_string(),
+      "Automatic Upgrades".to_string(),
       styled_block(vec![
         vec![(
           None,
-          "Select the display manager (greeter) to be installed on your system.",
+          "Periodically rebuild the system against the latest packages from your channel or flake.",
         )],
         vec![(
           None,
-          "The display manager is responsible for providing the graphical login screen and managing user sessions.",
+          "Useful for set-and-forget machines. Allowing reboots lets the system boot into a new kernel without intervention.",
         )],
       ]),
     )
   }
 }
 
-impl Default for Greeter {
+impl Default for AutoUpgrade {
   fn default() -> Self {
-    Self::new()
+    Self::new(AutoUpgradeCfg::default())
   }
 }
 
-impl Page for Greeter {
+impl Page for AutoUpgrade {
   fn render(&mut self, _installer: &mut Installer, f: &mut Frame, area: Rect) {
-    let chunks = split_vert!(area, 1, [Constraint::Percentage(100)]);
-    self.greeters.render(f, chunks[0]);
+    let chunks = split_vert!(
+      area,
+      1,
+      [Constraint::Percentage(40), Constraint::Percentage(60)]
+    );
+    let hor_chunks = split_hor!(
+      chunks[1],
+      1,
+      [
+        Constraint::Percentage(25),
+        Constraint::Percentage(50),
+        Constraint::Percentage(25),
+      ]
+    );
+    let info_box = InfoBox::new(
+      "Automatic Upgrades",
+      styled_block(vec![
+        vec![(
+          None,
+          "Periodically rebuild the system against the latest packages from your channel or flake.",
+        )],
+        vec![
+          (None, "Current schedule: "),
+          (HIGHLIGHT, self.cfg.dates.as_str()),
+        ],
+        vec![(None, "")],
+        vec![(
+          None,
+          "The schedule is a systemd calendar expression such as 'daily', 'weekly' or 'Sun 04:00'.",
+        )],
+      ]),
+    );
+    info_box.render(f, chunks[0]);
+    match self.input_mode {
+      AutoUpgradeInputMode::Buttons => self.buttons.render(f, hor_chunks[1]),
+      AutoUpgradeInputMode::Schedule => self.schedule_input.render(f, hor_chunks[1]),
+    }
     self.help_modal.render(f, area);
   }
 
   fn get_help_content(&self) -> (String, Vec<Line<'_>>) {
     let help_content = styled_block(vec![
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "↑/↓, j/k"),
-        (None, " - Navigate greeter options"),
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Navigate options"),
       ],
       vec![
         (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
-        (None, " - Select greeter and return"),
+        (None, " - Toggle option or select action"),
       ],
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "Esc, q, ←, h"),
-        (None, " - Cancel and return to menu"),
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().back_label,
+        ),
+        (None, " - Save and return to menu"),
       ],
       vec![
         (Some((Color::Yellow, Modifier::BOLD)), "?"),
@@ -2793,68 +5293,2411 @@ impl Page for Greeter {
       vec![(None, "")],
       vec![(
         None,
-        "Select the display manager for the graphical login screen.",
+        "The schedule uses systemd calendar syntax, see 'man systemd.time'.",
       )],
     ]);
-    ("Greeter".to_string(), help_content)
+    ("Automatic Upgrades".to_string(), help_content)
+  }
+
+  fn handle_paste(&mut self, _installer: &mut Installer, text: &str) -> Signal {
+    if !self.help_modal.visible && self.schedule_input.is_focused() {
+      self.schedule_input.paste(text);
+    }
+    Signal::Wait
   }
 
   fn handle_input(&mut self, installer: &mut Installer, event: KeyEvent) -> Signal {
     match event.code {
       KeyCode::Char('?') => {
         self.help_modal.toggle();
-        Signal::Wait
+        return Signal::Wait;
       }
-      ui_close!() if self.help_modal.visible => {
+      code if ui_close!(code) && self.help_modal.visible => {
         self.help_modal.hide();
-        Signal::Wait
-      }
-      _ if self.help_modal.visible => Signal::Wait,
-      ui_back!() => Signal::Pop,
-      KeyCode::Enter => {
-        installer.greeter = Some(self.greeters.items[self.greeters.selected_idx].clone());
-        Signal::Pop
+        return Signal::Wait;
       }
-      _ => self.greeters.handle_input(event),
+      _ if self.help_modal.visible => return Signal::Wait,
+      _ => {}
+    }
+    match self.input_mode {
+      AutoUpgradeInputMode::Schedule => match event.code {
+        KeyCode::Esc => {
+          self.input_mode = AutoUpgradeInputMode::Buttons;
+          self.schedule_input.unfocus();
+          self.buttons.focus();
+          Signal::Wait
+        }
+        KeyCode::Enter => {
+          let input = self
+            .schedule_input
+            .get_value()
+            .and_then(|v| v.as_str().map(|s| s.trim().to_string()))
+            .unwrap_or_default();
+          match validate_calendar(&input) {
+            Ok(()) => {
+              self.cfg.dates = input;
+              self.input_mode = AutoUpgradeInputMode::Buttons;
+              self.schedule_input.unfocus();
+              self.buttons.focus();
+            }
+            Err(e) => self.schedule_input.error(e),
+          }
+          Signal::Wait
+        }
+        _ => self.schedule_input.handle_input(event),
+      },
+      AutoUpgradeInputMode::Buttons => match event.code {
+        code if ui_back!(code) => {
+          installer.auto_upgrade = Some(self.cfg.clone());
+          Signal::Pop
+        }
+        code if ui_up!(code) => {
+          self.buttons.prev_child();
+          Signal::Wait
+        }
+        code if ui_down!(code) => {
+          self.buttons.next_child();
+          Signal::Wait
+        }
+        KeyCode::Enter => match self.buttons.selected_child() {
+          Some(idx @ (0 | 1)) => {
+            if let Some(chkbox) = self.buttons.focused_child_mut() {
+              chkbox.interact();
+              if let Some(Value::Bool(checked)) = chkbox.get_value() {
+                if idx == 0 {
+                  self.cfg.enable = checked;
+                } else {
+                  self.cfg.allow_reboot = checked;
+                }
+              }
+            }
+            Signal::Wait
+          }
+          Some(2) => {
+            self.input_mode = AutoUpgradeInputMode::Schedule;
+            self.buttons.unfocus();
+            self.schedule_input.focus();
+            Signal::Wait
+          }
+          Some(3) => {
+            installer.auto_upgrade = Some(self.cfg.clone());
+            Signal::Pop
+          }
+          _ => Signal::Wait,
+        },
+        _ => Signal::Wait,
+      },
     }
   }
 }
 
-pub struct DesktopEnvironment {
-  desktops: StrList,
+pub struct Documentation {
+  buttons: WidgetBox,
   help_modal: HelpModal<'static>,
 }
 
-impl DesktopEnvironment {
-  pub fn new() -> Self {
-    let desktops = [
-      "GNOME",
-      "KDE Plasma",
-      "Hyprland",
-      "XFCE",
-      "Cinnamon",
-      "MATE",
-      "lxqt",
-      "Budgie",
-      "i3",
-      "None",
-    ]
-    .iter()
-    .map(|s| s.to_string())
-    .collect::<Vec<_>>();
-    let mut desktops = StrList::new("Select Desktop Environment", desktops);
-    desktops.focus();
+impl Documentation {
+  pub fn new(cfg: DocsCfg) -> Self {
+    let mut buttons = WidgetBox::button_menu(vec![
+      Box::new(CheckBox::new("Man pages", cfg.man)),
+      Box::new(CheckBox::new("NixOS manual", cfg.nixos)),
+      Box::new(CheckBox::new("Development man pages", cfg.dev)),
+      Box::new(Button::new("Back")),
+    ]);
+    buttons.focus();
+    let help_content = styled_block(vec![
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Navigate options"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
+        (None, " - Toggle option or select Back"),
+      ],
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().back_label,
+        ),
+        (None, " - Return to menu"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "?"),
+        (None, " - Show this help"),
+      ],
+      vec![(None, "")],
+      vec![(
+        None,
+        "Turning documentation off makes the installed system smaller.",
+      )],
+    ]);
+    let help_modal = HelpModal::new("Documentation", help_content);
+    Self {
+      buttons,
+      help_modal,
+    }
+  }
+  pub fn display_widget(installer: &mut Installer) -> Option<Box<dyn ConfigWidget>> {
+    let docs = &installer.documentation;
+    let on_off = |enabled: bool| if enabled { "yes" } else { "no" };
+    let ib = InfoBox::new(
+      "",
+      styled_block(vec![
+        vec![
+          (None, "Man pages: ".to_string()),
+          (HIGHLIGHT, on_off(docs.man).to_string()),
+        ],
+        vec![
+          (None, "NixOS manual: ".to_string()),
+          (HIGHLIGHT, on_off(docs.nixos).to_string()),
+        ],
+        vec![
+          (None, "Development man pages: ".to_string()),
+          (HIGHLIGHT, on_off(docs.dev).to_string()),
+        ],
+      ]),
+    );
+    Some(Box::new(ib) as Box<dyn ConfigWidget>)
+  }
+  pub fn page_info<'a>() -> (String, Vec<Line<'a>>) {
+    (
+      "Documentation".to_string(),
+      styled_block(vec![
+        vec![(
+          None,
+          "Choose which documentation to install. Man pages cover the installed programs, the NixOS manual documents NixOS itself and every configuration option.",
+        )],
+        vec![(
+          None,
+          "Development man pages add the C library and system call references. Leaving out what you don't read saves disk space.",
+        )],
+      ]),
+    )
+  }
+}
+
+impl Default for Documentation {
+  fn default() -> Self {
+    Self::new(DocsCfg::default())
+  }
+}
+
+impl Page for Documentation {
+  fn render(&mut self, _installer: &mut Installer, f: &mut Frame, area: Rect) {
+    let chunks = split_vert!(
+      area,
+      1,
+      [Constraint::Percentage(40), Constraint::Percentage(60)]
+    );
+    let hor_chunks = split_hor!(
+      chunks[1],
+      1,
+      [
+        Constraint::Percentage(30),
+        Constraint::Percentage(40),
+        Constraint::Percentage(30),
+      ]
+    );
+    let (_, info) = Self::page_info();
+    let info_box = InfoBox::new("", info);
+    info_box.render(f, chunks[0]);
+    self.buttons.render(f, hor_chunks[1]);
+    self.help_modal.render(f, area);
+  }
+
+  fn get_help_content(&self) -> (String, Vec<Line<'_>>) {
+    let help_content = styled_block(vec![
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Navigate options"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
+        (None, " - Toggle option or select Back"),
+      ],
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().back_label,
+        ),
+        (None, " - Return to menu"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "?"),
+        (None, " - Show this help"),
+      ],
+      vec![(None, "")],
+      vec![(
+        None,
+        "Turning documentation off makes the installed system smaller.",
+      )],
+    ]);
+    ("Documentation".to_string(), help_content)
+  }
+
+  fn handle_input(&mut self, installer: &mut Installer, event: KeyEvent) -> Signal {
+    match event.code {
+      KeyCode::Char('?') => {
+        self.help_modal.toggle();
+        Signal::Wait
+      }
+      code if ui_close!(code) && self.help_modal.visible => {
+        self.help_modal.hide();
+        Signal::Wait
+      }
+      _ if self.help_modal.visible => Signal::Wait,
+      code if ui_back!(code) => Signal::Pop,
+      code if ui_up!(code) => {
+        self.buttons.prev_child();
+        Signal::Wait
+      }
+      code if ui_down!(code) => {
+        self.buttons.next_child();
+        Signal::Wait
+      }
+      KeyCode::Enter => {
+        let idx = self.buttons.selected_child();
+        if idx == Some(3) {
+          return Signal::Pop;
+        }
+        let Some(chkbox) = self.buttons.focused_child_mut() else {
+          return Signal::Wait;
+        };
+        chkbox.interact();
+        let Some(Value::Bool(checked)) = chkbox.get_value() else {
+          return Signal::Wait;
+        };
+        let docs = &mut installer.documentation;
+        match idx {
+          Some(0) => docs.man = checked,
+          Some(1) => docs.nixos = checked,
+          Some(2) => docs.dev = checked,
+          _ => {}
+        }
+        Signal::Wait
+      }
+      _ => Signal::Wait,
+    }
+  }
+}
+
+/// Rough strength of a password from its length and how many of lowercase,
+/// uppercase, digits and symbols it uses. Only advisory, nothing is rejected.
+pub fn password_strength(passwd: &str) -> (&'static str, Color, usize) {
+  let len = passwd.chars().count();
+  let classes = [
+    passwd.chars().any(|c| c.is_lowercase()),
+    passwd.chars().any(|c| c.is_uppercase()),
+    passwd.chars().any(|c| c.is_ascii_digit()),
+    passwd.chars().any(|c| !c.is_alphanumeric()),
+  ]
+  .iter()
+  .filter(|&&used| used)
+  .count();
+  if len < 8 {
+    return ("Too short", Color::Red, 0);
+  }
+  let score = 1 + usize::from(len >= 12) + usize::from(len >= 16) + usize::from(classes >= 3);
+  match score {
+    1 if classes < 2 => ("Weak", Color::Red, 1),
+    1 => ("Fair", Color::Yellow, 1),
+    2 => ("Good", Color::Yellow, 2),
+    _ => ("Strong", Color::Green, score),
+  }
+}
+
+/// One line meter for `password_strength`, empty while nothing is typed
+fn password_strength_line<'a>(passwd: &str) -> Line<'a> {
+  if passwd.is_empty() {
+    return Line::default();
+  }
+  let (label, color, score) = password_strength(passwd);
+  let filled = score.min(4);
+  Line::from(vec![
+    Span::raw("Strength: "),
+    Span::styled("■".repeat(filled), Style::default().fg(color)),
+    Span::styled("□".repeat(4 - filled), Style::default().fg(Color::DarkGray)),
+    Span::styled(
+      format!(" {label}"),
+      Style::default().fg(color).add_modifier(Modifier::BOLD),
+    ),
+    Span::raw(format!(" ({} characters)", passwd.chars().count())),
+  ])
+}
+
+pub struct RootPassword {
+  input: LineEditor,
+  confirm: LineEditor,
+  disable_root: CheckBox,
+  help_modal: HelpModal<'static>,
+}
+
+impl RootPassword {
+  pub fn new(disable_root: bool) -> Self {
+    let mut input =
+      LineEditor::new("Set Root Password", Some("Password will be hidden")).secret(true);
+    let confirm = LineEditor::new("Confirm Password", Some("Password will be hidden")).secret(true);
+    let disable_root = CheckBox::new("Disable root login (use sudo)", disable_root);
+    input.focus();
+    let help_content = styled_block(vec![
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
+        (None, " - Move to next field or save when complete"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Tab"),
+        (
+          None,
+          " - Switch between password fields and the disable root option",
+        ),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Esc"),
+        (None, " - Cancel and return to menu"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "←/→"),
+        (None, " - Move cursor"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Home/End"),
+        (None, " - Jump to beginning/end"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Ctrl+←/→"),
+        (None, " - Jump by word"),
+      ],
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          "Ctrl+W, Alt+Backspace",
+        ),
+        (None, " - Delete the previous word"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Backspace/Del"),
+        (None, " - Delete characters"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "?"),
+        (None, " - Show this help"),
+      ],
+      vec![(None, "")],
+      vec![(None, "Set a strong root password for system security.")],
+      vec![(
+        None,
+        "Alternatively, disable the root account and administer the system with sudo from a wheel user.",
+      )],
+    ]);
+    let help_modal = HelpModal::new("Root Password", help_content);
+    Self {
+      input,
+      confirm,
+      disable_root,
+      help_modal,
+    }
+  }
+  pub fn page_info<'a>() -> (String, Vec<Line<'a>>) {
+    (
+      "Root Password".to_string(),
+      styled_block(vec![
+        vec![(
+          None,
+          "The root user is the superuser account on a Unix-like operating system, including Linux.",
+        )],
+        vec![(
+          None,
+          "It has full administrative privileges and can perform any action on the system, including installing software, modifying system settings, and accessing all files and directories.",
+        )],
+        vec![(
+          None,
+          "Setting a strong password for the root user is important for system security, as it helps prevent unauthorized access to sensitive system functions and data.",
+        )],
+        vec![(
+          None,
+          "Choose a password that is difficult to guess and contains a mix of uppercase and lowercase letters, numbers, and special characters.",
+        )],
+      ]),
+    )
+  }
+  pub fn display_widget(installer: &mut Installer) -> Option<Box<dyn ConfigWidget>> {
+    if installer.disable_root {
+      let ib = InfoBox::new(
+        "",
+        styled_block(vec![vec![(
+          HIGHLIGHT,
+          "Root account is disabled, admin access is via sudo.",
+        )]]),
+      );
+      return Some(Box::new(ib) as Box<dyn ConfigWidget>);
+    }
+    installer.root_passwd_hash.as_ref().map(|_| {
+      let ib = InfoBox::new(
+        "",
+        styled_block(vec![vec![(HIGHLIGHT, "Root password is set.")]]),
+      );
+      Box::new(ib) as Box<dyn ConfigWidget>
+    })
+  }
+  pub fn mkpasswd(passwd: String) -> anyhow::Result<String> {
+    tools::require("mkpasswd")?;
+    let mut child = Command::new("mkpasswd")
+      .arg("--method=SHA-512")
+      .arg("--rounds=4096")
+      .arg("--stdin")
+      .stdin(Stdio::piped())
+      .stdout(Stdio::piped())
+      .spawn()
+      .map_err(|e| anyhow::anyhow!("Failed to run mkpasswd: {e}"))?;
+    {
+      let stdin = child
+        .stdin
+        .as_mut()
+        .ok_or_else(|| anyhow::anyhow!("Failed to open stdin"))?;
+      stdin.write_all(passwd.as_bytes())?;
+    }
+    let output = child.wait_with_output()?;
+    if output.status.success() {
+      let hashed = String::from_utf8_lossy(&output.stdout).trim().to_string();
+      Ok(hashed)
+    } else {
+      Err(anyhow::anyhow!(
+        "mkpasswd failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+      ))
+    }
+  }
+}
+
+impl Default for RootPassword {
+  fn default() -> Self {
+    Self::new(false)
+  }
+}
+
+impl Page for RootPassword {
+  fn render(&mut self, _installer: &mut Installer, f: &mut Frame, area: Rect) {
+    let chunks = split_vert!(
+      area,
+      1,
+      [
+        Constraint::Percentage(40),
+        Constraint::Length(15),
+        Constraint::Percentage(40),
+      ]
+    );
+    let hor_chunks = split_hor!(
+      chunks[1],
+      1,
+      [
+        Constraint::Percentage(20),
+        Constraint::Percentage(60),
+        Constraint::Percentage(20),
+      ]
+    );
+    let vert_chunks = split_vert!(
+      hor_chunks[1],
+      0,
+      [
+        Constraint::Length(5),
+        Constraint::Length(5),
+        Constraint::Length(1),
+        Constraint::Length(1),
+        Constraint::Length(1),
+      ]
+    );
+
+    let info_box = InfoBox::new(
+      "",
+      styled_block(vec![
+        vec![(
+          None,
+          "The root user is the superuser account on a Unix-like operating system, including Linux.",
+        )],
+        vec![(
+          None,
+          "It has full administrative privileges and can perform any action on the system, including installing software, modifying system settings, and accessing all files and directories.",
+        )],
+        vec![(
+          None,
+          "Setting a strong password for the root user is important for system security, as it helps prevent unauthorized access to sensitive system functions and data.",
+        )],
+        vec![(
+          None,
+          "Choose a password that is difficult to guess and contains a mix of uppercase and lowercase letters, numbers, and special characters.",
+        )],
+      ]),
+    );
+
+    info_box.render(f, chunks[0]);
+    self.input.render(f, vert_chunks[0]);
+    self.confirm.render(f, vert_chunks[1]);
+    let passwd = self
+      .input
+      .get_value()
+      .and_then(|v| v.as_str().map(|s| s.trim().to_string()))
+      .unwrap_or_default();
+    f.render_widget(
+      Paragraph::new(password_strength_line(&passwd)),
+      vert_chunks[2],
+    );
+    self.disable_root.render(f, vert_chunks[4]);
+    self.help_modal.render(f, area);
+  }
+
+  fn get_help_content(&self) -> (String, Vec<Line<'_>>) {
+    let help_content = styled_block(vec![
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
+        (None, " - Move to next field or save when complete"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Tab"),
+        (
+          None,
+          " - Switch between password fields and the disable root option",
+        ),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Esc"),
+        (None, " - Cancel and return to menu"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "←/→"),
+        (None, " - Move cursor"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Home/End"),
+        (None, " - Jump to beginning/end"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Ctrl+←/→"),
+        (None, " - Jump by word"),
+      ],
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          "Ctrl+W, Alt+Backspace",
+        ),
+        (None, " - Delete the previous word"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Backspace/Del"),
+        (None, " - Delete characters"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "?"),
+        (None, " - Show this help"),
+      ],
+      vec![(None, "")],
+      vec![(None, "Set a strong root password for system security.")],
+      vec![(
+        None,
+        "Alternatively, disable the root account and administer the system with sudo from a wheel user.",
+      )],
+    ]);
+    ("Root Password".to_string(), help_content)
+  }
+
+  fn handle_paste(&mut self, _installer: &mut Installer, text: &str) -> Signal {
+    if self.help_modal.visible {
+      return Signal::Wait;
+    }
+    if self.input.is_focused() {
+      self.input.paste(text);
+    } else if self.confirm.is_focused() {
+      self.confirm.paste(text);
+    }
+    Signal::Wait
+  }
+
+  fn handle_input(&mut self, installer: &mut Installer, event: KeyEvent) -> Signal {
+    match event.code {
+      KeyCode::Char('?') => {
+        self.help_modal.toggle();
+        Signal::Wait
+      }
+      code if ui_close!(code) && self.help_modal.visible => {
+        self.help_modal.hide();
+        Signal::Wait
+      }
+      _ if self.help_modal.visible => Signal::Wait,
+      KeyCode::Esc => Signal::Pop,
+      KeyCode::Tab => {
+        if self.input.is_focused() {
+          self.input.unfocus();
+          self.confirm.focus();
+        } else if self.confirm.is_focused() {
+          self.confirm.unfocus();
+          self.disable_root.focus();
+        } else {
+          self.disable_root.unfocus();
+          self.input.focus();
+        }
+        Signal::Wait
+      }
+      KeyCode::Enter | KeyCode::Char(' ') if self.disable_root.is_focused() => {
+        if !self.disable_root.is_checked() && !installer.has_admin_user() {
+          self.disable_root.unfocus();
+          self.input.focus();
+          self
+            .input
+            .error("Add a user in the wheel group before disabling root");
+          return Signal::Wait;
+        }
+        self.disable_root.toggle();
+        installer.disable_root = self.disable_root.is_checked();
+        if installer.disable_root {
+          installer.root_passwd_hash = None;
+          Signal::Pop
+        } else {
+          Signal::Wait
+        }
+      }
+      KeyCode::Enter => {
+        if self.input.is_focused() {
+          self.input.unfocus();
+          self.confirm.focus();
+          Signal::Wait
+        } else {
+          let passwd = self
+            .input
+            .get_value()
+            .unwrap()
+            .as_str()
+            .unwrap()
+            .trim()
+            .to_string();
+          let confirm = self
+            .confirm
+            .get_value()
+            .unwrap()
+            .as_str()
+            .unwrap()
+            .trim()
+            .to_string();
+          if passwd.is_empty() {
+            Signal::Wait // Ignore empty passwords
+          } else if passwd != confirm {
+            self.input.clear();
+            self.confirm.clear();
+            self.confirm.unfocus();
+            self.input.focus();
+            self.input.error("Passwords do not match");
+            Signal::Wait // Passwords do not match
+          } else {
+            match Self::mkpasswd(passwd) {
+              Ok(hashed) => {
+                installer.root_passwd_hash = Some(hashed);
+                installer.disable_root = false;
+                Signal::Pop
+              }
+              Err(e) => {
+                self.input.clear();
+                self.confirm.clear();
+                self.confirm.unfocus();
+                self.input.focus();
+                self.input.error(format!("Error hashing password: {e}"));
+                Signal::Wait
+              }
+            }
+          }
+        }
+      }
+      _ => {
+        if self.input.is_focused() {
+          self.input.handle_input(event)
+        } else if self.confirm.is_focused() {
+          self.confirm.handle_input(event)
+        } else {
+          Signal::Wait
+        }
+      }
+    }
+  }
+}
+
+enum SudoInputMode {
+  Buttons,
+  Commands,
+}
+
+pub struct PasswordManagement {
+  modes: StrList,
+  help_modal: HelpModal<'static>,
+}
+
+impl PasswordManagement {
+  pub fn new(mutable_users: bool) -> Self {
+    let modes = ["Declarative", "Imperative"]
+      .iter()
+      .map(|s| s.to_string())
+      .collect::<Vec<_>>();
+    let mut modes = StrList::new("Password Management", modes);
+    let current = if mutable_users {
+      "Imperative"
+    } else {
+      "Declarative"
+    };
+    modes.commit_value(Some(current));
+    modes.focus();
+    let help_content = styled_block(vec![
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Navigate options"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
+        (None, " - Select option and return"),
+      ],
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().back_label,
+        ),
+        (None, " - Cancel and return to menu"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "?"),
+        (None, " - Show this help"),
+      ],
+      vec![(None, "")],
+      vec![(
+        None,
+        "Choose whether passwords live in the configuration or are managed with passwd.",
+      )],
+    ]);
+    let help_modal = HelpModal::new("Password Management", help_content);
+    Self { modes, help_modal }
+  }
+  pub fn get_mode_info<'a>(idx: usize) -> InfoBox<'a> {
+    match idx {
+      0 => InfoBox::new(
+        "Declarative",
+        styled_block(vec![
+          vec![
+            (None, "Password hashes are "),
+            (HIGHLIGHT, "stored in configuration.nix"),
+            (None, " and users are fully defined by the configuration."),
+          ],
+          vec![
+            (None, "Passwords changed with "),
+            (HIGHLIGHT, "passwd"),
+            (None, " are reset on the next rebuild."),
+          ],
+        ]),
+      ),
+      _ => InfoBox::new(
+        "Imperative",
+        styled_block(vec![
+          vec![
+            (None, "Passwords are "),
+            (HIGHLIGHT, "managed with passwd"),
+            (None, " after installation and are kept across rebuilds."),
+          ],
+          vec![
+            (None, "Any password set in the installer is only used "),
+            (HIGHLIGHT, "as the initial password"),
+            (
+              None,
+              ". Users may be created without one and given a password once the install finishes.",
+            ),
+          ],
+          vec![(
+            None,
+            "This is common on servers where credentials are handled outside of the configuration.",
+          )],
+        ]),
+      ),
+    }
+  }
+  pub fn display_widget(installer: &mut Installer) -> Option<Box<dyn ConfigWidget>> {
+    let mode = if installer.mutable_users {
+      "Imperative"
+    } else {
+      "Declarative"
+    };
+    let ib = InfoBox::new(
+      "",
+      styled_block(vec![
+        vec![(None, "User passwords are managed:")],
+        vec![(HIGHLIGHT, mode)],
+      ]),
+    );
+    Some(Box::new(ib) as Box<dyn ConfigWidget>)
+  }
+  pub fn page_info<'a>() -> (String, Vec<Line<'a>>) {
+    (
+      "Password Management".to_string(),
+      styled_block(vec![
+        vec![(
+          None,
+          "Choose how user passwords are managed on the installed system.",
+        )],
+        vec![(
+          None,
+          "Declarative keeps password hashes in the configuration, imperative leaves them to passwd.",
+        )],
+      ]),
+    )
+  }
+}
+
+impl Default for PasswordManagement {
+  fn default() -> Self {
+    Self::new(false)
+  }
+}
+
+impl Page for PasswordManagement {
+  fn render(&mut self, _installer: &mut Installer, f: &mut Frame, area: Rect) {
+    let vert_chunks = split_vert!(
+      area,
+      0,
+      [Constraint::Percentage(50), Constraint::Percentage(50)]
+    );
+    let hor_chunks = split_hor!(
+      vert_chunks[0],
+      1,
+      [
+        Constraint::Percentage(40),
+        Constraint::Percentage(20),
+        Constraint::Percentage(40),
+      ]
+    );
+
+    let info_box = Self::get_mode_info(self.modes.selected_idx);
+    self.modes.render(f, hor_chunks[1]);
+    info_box.render(f, vert_chunks[1]);
+
+    self.help_modal.render(f, area);
+  }
+
+  fn get_help_content(&self) -> (String, Vec<Line<'_>>) {
+    let help_content = styled_block(vec![
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Navigate options"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
+        (None, " - Select option and return"),
+      ],
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().back_label,
+        ),
+        (None, " - Cancel and return to menu"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "?"),
+        (None, " - Show this help"),
+      ],
+      vec![(None, "")],
+      vec![(
+        None,
+        "Choose whether passwords live in the configuration or are managed with passwd.",
+      )],
+    ]);
+    ("Password Management".to_string(), help_content)
+  }
+
+  fn handle_input(&mut self, installer: &mut Installer, event: KeyEvent) -> Signal {
+    match event.code {
+      KeyCode::Char('?') => {
+        self.help_modal.toggle();
+        Signal::Wait
+      }
+      code if ui_close!(code) && self.help_modal.visible => {
+        self.help_modal.hide();
+        Signal::Wait
+      }
+      _ if self.help_modal.visible => Signal::Wait,
+      code if ui_back!(code) => Signal::Pop,
+      KeyCode::Enter => {
+        installer.mutable_users = self.modes.selected_idx == 1;
+        Signal::Pop
+      }
+      code if ui_up!(code) => {
+        if !self.modes.previous_item() {
+          self.modes.last_item();
+        }
+        Signal::Wait
+      }
+      code if ui_down!(code) => {
+        if !self.modes.next_item() {
+          self.modes.first_item();
+        }
+        Signal::Wait
+      }
+      _ => self.modes.handle_input(event),
+    }
+  }
+}
+
+pub struct Sudo {
+  buttons: WidgetBox,
+  commands_input: LineEditor,
+  input_mode: SudoInputMode,
+  cfg: SudoCfg,
+  error: Option<String>,
+  help_modal: HelpModal<'static>,
+}
+
+impl Sudo {
+  pub fn new(cfg: SudoCfg) -> Self {
+    let mut buttons = WidgetBox::button_menu(Self::buttons(&cfg));
+    buttons.focus();
+    let mut commands_input = LineEditor::new(
+      "Passwordless Commands",
+      Some("Comma separated, e.g. /run/current-system/sw/bin/systemctl"),
+    );
+    commands_input.set_value(cfg.nopasswd_commands.join(", "));
+    let help_content = styled_block(vec![
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Navigate options"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
+        (None, " - Toggle option or select action"),
+      ],
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().back_label,
+        ),
+        (None, " - Save and return to menu"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "?"),
+        (None, " - Show this help"),
+      ],
+      vec![(None, "")],
+      vec![(None, "Configure how members of the wheel group use sudo.")],
+    ]);
+    let help_modal = HelpModal::new("Sudo", help_content);
+    Self {
+      buttons,
+      commands_input,
+      input_mode: SudoInputMode::Buttons,
+      cfg,
+      error: None,
+      help_modal,
+    }
+  }
+  fn buttons(cfg: &SudoCfg) -> Vec<Box<dyn ConfigWidget>> {
+    vec![
+      Box::new(CheckBox::new("Enable sudo", cfg.enable)),
+      Box::new(CheckBox::new(
+        "Require password for wheel users",
+        cfg.wheel_needs_password,
+      )),
+      Box::new(Button::new("Passwordless Commands")),
+      Box::new(Button::new("Back")),
+    ]
+  }
+  /// Parse the comma separated command list, every entry must be an absolute
+  /// path since sudo matches on the full path
+  fn parse_commands(input: &str) -> Result<Vec<String>, String> {
+    let mut commands = vec![];
+    for cmd in input.split(',').map(|c| c.trim()).filter(|c| !c.is_empty()) {
+      if !cmd.starts_with('/') {
+        return Err(format!("'{cmd}' is not an absolute path"));
+      }
+      commands.push(cmd.to_string());
+    }
+    Ok(commands)
+  }
+  pub fn display_widget(installer: &mut Installer) -> Option<Box<dyn ConfigWidget>> {
+    let cfg = installer.sudo_config.as_ref()?;
+    let yes_no = |b: bool| if b { "yes" } else { "no" };
+    let mut lines = vec![
+      vec![
+        (None, "Sudo enabled: ".to_string()),
+        (HIGHLIGHT, yes_no(cfg.enable).to_string()),
+      ],
+      vec![
+        (None, "Wheel needs password: ".to_string()),
+        (HIGHLIGHT, yes_no(cfg.wheel_needs_password).to_string()),
+      ],
+    ];
+    if !cfg.nopasswd_commands.is_empty() {
+      lines.push(vec![(None, "Passwordless commands:".to_string())]);
+      for cmd in &cfg.nopasswd_commands {
+        lines.push(vec![(HIGHLIGHT, format!("  {cmd}"))]);
+      }
+    }
+    let ib = InfoBox::new("", styled_block(lines));
+    Some(Box::new(ib) as Box<dyn ConfigWidget>)
+  }
+  pub fn page_info<'a>() -> (String, Vec<Line<'a>>) {
+    (
+      "Sudo".to_string(),
+      styled_block(vec![
+        vec![(
+          None,
+          "Sudo lets members of the wheel group run commands as root.",
+        )],
+        vec![(
+          None,
+          "By default wheel users must enter their own password. You can also allow specific commands to run without a password.",
+        )],
+      ]),
+    )
+  }
+  fn info_lines(&self, installer: &Installer) -> Vec<Line<'static>> {
+    let mut lines = vec![
+      vec![(
+        None,
+        "Sudo lets members of the wheel group run commands as root.".to_string(),
+      )],
+      vec![(
+        None,
+        "Requiring a password for wheel users is strongly recommended. Commands listed as passwordless can always be run without one.".to_string(),
+      )],
+    ];
+    if installer.disable_root {
+      lines.push(vec![(None, String::new())]);
+      lines.push(vec![(
+        HIGHLIGHT,
+        "The root account is disabled, so sudo is the only way to administer this system and cannot be turned off.".to_string(),
+      )]);
+    }
+    if self.cfg.enable && !self.cfg.wheel_needs_password {
+      lines.push(vec![(None, String::new())]);
+      lines.push(vec![(
+        Some((Color::Red, Modifier::BOLD)),
+        "Warning: any wheel user will be able to become root without a password.".to_string(),
+      )]);
+    }
+    if let Some(ref err) = self.error {
+      lines.push(vec![(None, String::new())]);
+      lines.push(vec![(Some((Color::Red, Modifier::BOLD)), err.clone())]);
+    }
+    styled_block(lines)
+  }
+}
+
+impl Default for Sudo {
+  fn default() -> Self {
+    Self::new(SudoCfg::default())
+  }
+}
+
+impl Page for Sudo {
+  fn render(&mut self, installer: &mut Installer, f: &mut Frame, area: Rect) {
+    let chunks = split_vert!(
+      area,
+      1,
+      [Constraint::Percentage(40), Constraint::Percentage(60)]
+    );
+    let hor_chunks = split_hor!(
+      chunks[1],
+      1,
+      [
+        Constraint::Percentage(25),
+        Constraint::Percentage(50),
+        Constraint::Percentage(25),
+      ]
+    );
+    let info_box = InfoBox::new("Sudo", self.info_lines(installer));
+    info_box.render(f, chunks[0]);
+    match self.input_mode {
+      SudoInputMode::Buttons => self.buttons.render(f, hor_chunks[1]),
+      SudoInputMode::Commands => self.commands_input.render(f, hor_chunks[1]),
+    }
+    self.help_modal.render(f, area);
+  }
+
+  fn get_help_content(&self) -> (String, Vec<Line<'_>>) {
+    let help_content = styled_block(vec![
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Navigate options"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
+        (None, " - Toggle option or select action"),
+      ],
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().back_label,
+        ),
+        (None, " - Save and return to menu"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "?"),
+        (None, " - Show this help"),
+      ],
+      vec![(None, "")],
+      vec![(None, "Configure how members of the wheel group use sudo.")],
+    ]);
+    ("Sudo".to_string(), help_content)
+  }
+
+  fn handle_paste(&mut self, _installer: &mut Installer, text: &str) -> Signal {
+    if !self.help_modal.visible && self.commands_input.is_focused() {
+      self.commands_input.paste(text);
+    }
+    Signal::Wait
+  }
+
+  fn handle_input(&mut self, installer: &mut Installer, event: KeyEvent) -> Signal {
+    match event.code {
+      KeyCode::Char('?') => {
+        self.help_modal.toggle();
+        return Signal::Wait;
+      }
+      code if ui_close!(code) && self.help_modal.visible => {
+        self.help_modal.hide();
+        return Signal::Wait;
+      }
+      _ if self.help_modal.visible => return Signal::Wait,
+      _ => {}
+    }
+    match self.input_mode {
+      SudoInputMode::Commands => match event.code {
+        KeyCode::Esc => {
+          self.input_mode = SudoInputMode::Buttons;
+          self.commands_input.unfocus();
+          self.buttons.focus();
+          Signal::Wait
+        }
+        KeyCode::Enter => {
+          let input = self
+            .commands_input
+            .get_value()
+            .and_then(|v| v.as_str().map(|s| s.to_string()))
+            .unwrap_or_default();
+          match Self::parse_commands(&input) {
+            Ok(commands) => {
+              self.cfg.nopasswd_commands = commands;
+              self.input_mode = SudoInputMode::Buttons;
+              self.commands_input.unfocus();
+              self.buttons.focus();
+            }
+            Err(e) => self.commands_input.error(e),
+          }
+          Signal::Wait
+        }
+        _ => self.commands_input.handle_input(event),
+      },
+      SudoInputMode::Buttons => match event.code {
+        code if ui_back!(code) => {
+          installer.sudo_config = Some(self.cfg.clone());
+          Signal::Pop
+        }
+        code if ui_up!(code) => {
+          self.buttons.prev_child();
+          Signal::Wait
+        }
+        code if ui_down!(code) => {
+          self.buttons.next_child();
+          Signal::Wait
+        }
+        KeyCode::Enter => {
+          self.error = None;
+          match self.buttons.selected_child() {
+            Some(0) => {
+              if self.cfg.enable && installer.disable_root {
+                self.error =
+                  Some("Sudo cannot be disabled while the root account is disabled.".into());
+                return Signal::Wait;
+              }
+              if let Some(chkbox) = self.buttons.focused_child_mut() {
+                chkbox.interact();
+                if let Some(Value::Bool(checked)) = chkbox.get_value() {
+                  self.cfg.enable = checked;
+                }
+              }
+              Signal::Wait
+            }
+            Some(1) => {
+              if let Some(chkbox) = self.buttons.focused_child_mut() {
+                chkbox.interact();
+                if let Some(Value::Bool(checked)) = chkbox.get_value() {
+                  self.cfg.wheel_needs_password = checked;
+                }
+              }
+              Signal::Wait
+            }
+            Some(2) => {
+              self.input_mode = SudoInputMode::Commands;
+              self.buttons.unfocus();
+              self.commands_input.focus();
+              Signal::Wait
+            }
+            Some(3) => {
+              installer.sudo_config = Some(self.cfg.clone());
+              Signal::Pop
+            }
+            _ => Signal::Wait,
+          }
+        }
+        _ => Signal::Wait,
+      },
+    }
+  }
+}
+
+pub struct Profile {
+  profiles: StrList,
+  help_modal: HelpModal<'static>,
+}
+
+impl Profile {
+  pub fn new(current: Option<&str>) -> Self {
+    let profiles = ["Minimal", "Desktop", "Server", "Custom"]
+      .iter()
+      .map(|s| s.to_string())
+      .collect::<Vec<_>>();
+    let mut profiles = StrList::new("Select Profile", profiles);
+    profiles.commit_value(current);
+    profiles.focus();
+    let help_content = styled_block(vec![
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Navigate profile options"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
+        (None, " - Select profile and return"),
+      ],
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().back_label,
+        ),
+        (None, " - Cancel and return to menu"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "?"),
+        (None, " - Show this help"),
+      ],
+      vec![(None, "")],
+      vec![(
+        None,
+        "Server leaves out the desktop and turns on SSH, Minimal leaves out the desktop, Desktop uses the selected desktop, greeter and audio, Custom adds nothing.",
+      )],
+    ]);
+    let help_modal = HelpModal::new("Profile", help_content);
+    Self {
+      profiles,
+      help_modal,
+    }
+  }
+  pub fn display_widget(installer: &mut Installer) -> Option<Box<dyn ConfigWidget>> {
+    installer.profile.clone().map(|s| {
+      let ib = InfoBox::new(
+        "",
+        styled_block(vec![
+          vec![(None, "Current profile set to:")],
+          vec![(HIGHLIGHT, &s)],
+        ]),
+      );
+      Box::new(ib) as Box<dyn ConfigWidget>
+    })
+  }
+  pub fn page_info<'a>() -> (String, Vec<Line<'a>>) {
+    (
+      "Profile".to_string(),
+      styled_block(vec![
+        vec![(
+          None,
+          "Select a predefined profile that best matches your intended use case for the system.",
+        )],
+        vec![(
+          None,
+          "Server leaves out any desktop, greeter and audio settings and enables SSH. Minimal leaves out the desktop as well.",
+        )],
+        vec![(
+          None,
+          "Desktop uses the selected desktop environment, greeter and audio backend. Custom adds nothing on its own.",
+        )],
+        vec![(
+          None,
+          "Picking a profile also fills in defaults like the network backend, which can still be changed.",
+        )],
+        vec![(
+          None,
+          "You can always customize the configuration further after the installation is complete.",
+        )],
+      ]),
+    )
+  }
+}
+
+impl Default for Profile {
+  fn default() -> Self {
+    Self::new(None)
+  }
+}
+
+impl Page for Profile {
+  fn render(&mut self, _installer: &mut Installer, f: &mut Frame, area: Rect) {
+    let chunks = split_vert!(area, 1, [Constraint::Percentage(100)]);
+    self.profiles.render(f, chunks[0]);
+    self.help_modal.render(f, area);
+  }
+
+  fn get_help_content(&self) -> (String, Vec<Line<'_>>) {
+    let help_content = styled_block(vec![
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Navigate profile options"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
+        (None, " - Select profile and return"),
+      ],
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().back_label,
+        ),
+        (None, " - Cancel and return to menu"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "?"),
+        (None, " - Show this help"),
+      ],
+      vec![(None, "")],
+      vec![(
+        None,
+        "Server leaves out the desktop and turns on SSH, Minimal leaves out the desktop, Desktop uses the selected desktop, greeter and audio, Custom adds nothing.",
+      )],
+    ]);
+    ("Profile".to_string(), help_content)
+  }
+
+  fn handle_input(&mut self, installer: &mut Installer, event: KeyEvent) -> Signal {
+    match event.code {
+      KeyCode::Char('?') => {
+        self.help_modal.toggle();
+        Signal::Wait
+      }
+      code if ui_close!(code) && self.help_modal.visible => {
+        self.help_modal.hide();
+        Signal::Wait
+      }
+      _ if self.help_modal.visible => Signal::Wait,
+      code if ui_back!(code) => Signal::Pop,
+      KeyCode::Enter => {
+        let profile = self.profiles.items[self.profiles.selected_idx].clone();
+        installer.set_profile(&profile);
+        Signal::Pop
+      }
+      _ => self.profiles.handle_input(event),
+    }
+  }
+}
+
+pub struct Greeter {
+  greeters: StrList,
+  help_modal: HelpModal<'static>,
+}
+
+impl Greeter {
+  pub fn new(current: Option<&str>) -> Self {
+    let greeters = ["LightDM", "GDM", "SDDM", "None"]
+      .iter()
+      .map(|s| s.to_string())
+      .collect::<Vec<_>>();
+    let mut greeters = StrList::new("Select Greeter", greeters);
+    greeters.commit_value(current);
+    greeters.focus();
+    let help_content = styled_block(vec![
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Navigate greeter options"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
+        (None, " - Select greeter and return"),
+      ],
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().back_label,
+        ),
+        (None, " - Cancel and return to menu"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "?"),
+        (None, " - Show this help"),
+      ],
+      vec![(None, "")],
+      vec![(
+        None,
+        "Select the display manager for the graphical login screen.",
+      )],
+    ]);
+    let help_modal = HelpModal::new("Greeter", help_content);
+    Self {
+      greeters,
+      help_modal,
+    }
+  }
+  pub fn display_widget(installer: &mut Installer) -> Option<Box<dyn ConfigWidget>> {
+    installer.greeter.clone().map(|s| {
+      let ib = InfoBox::new(
+        "",
+        styled_block(vec![
+          vec![(None, "Current greeter set to:")],
+          vec![(HIGHLIGHT, &s)],
+        ]),
+      );
+      Box::new(ib) as Box<dyn ConfigWidget>
+    })
+  }
+  pub fn page_info<'a>() -> (String, Vec<Line<'a>>) {
+    (
+      "Greeter".to_string(),
+      styled_block(vec![
+        vec![(
+          None,
+          "Select the display manager (greeter) to be installed on your system.",
+        )],
+        vec![(
+          None,
+          "The display manager is responsible for providing the graphical login screen and managing user sessions.",
+        )],
+      ]),
+    )
+  }
+}
+
+impl Default for Greeter {
+  fn default() -> Self {
+    Self::new(None)
+  }
+}
+
+impl Page for Greeter {
+  fn render(&mut self, _installer: &mut Installer, f: &mut Frame, area: Rect) {
+    let chunks = split_vert!(area, 1, [Constraint::Percentage(100)]);
+    self.greeters.render(f, chunks[0]);
+    self.help_modal.render(f, area);
+  }
+
+  fn get_help_content(&self) -> (String, Vec<Line<'_>>) {
+    let help_content = styled_block(vec![
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Navigate greeter options"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
+        (None, " - Select greeter and return"),
+      ],
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().back_label,
+        ),
+        (None, " - Cancel and return to menu"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "?"),
+        (None, " - Show this help"),
+      ],
+      vec![(None, "")],
+      vec![(
+        None,
+        "Select the display manager for the graphical login screen.",
+      )],
+    ]);
+    ("Greeter".to_string(), help_content)
+  }
+
+  fn handle_input(&mut self, installer: &mut Installer, event: KeyEvent) -> Signal {
+    match event.code {
+      KeyCode::Char('?') => {
+        self.help_modal.toggle();
+        Signal::Wait
+      }
+      code if ui_close!(code) && self.help_modal.visible => {
+        self.help_modal.hide();
+        Signal::Wait
+      }
+      _ if self.help_modal.visible => Signal::Wait,
+      code if ui_back!(code) => Signal::Pop,
+      KeyCode::Enter => {
+        installer.greeter = Some(self.greeters.items[self.greeters.selected_idx].clone());
+        Signal::Pop
+      }
+      _ => self.greeters.handle_input(event),
+    }
+  }
+}
+
+pub struct DesktopEnvironment {
+  desktops: StrList,
+  search_bar: LineEditor,
+  help_modal: HelpModal<'static>,
+}
+
+impl DesktopEnvironment {
+  /// Full desktop environments first, then standalone window managers
+  const DESKTOPS: [(&'static str, &'static str); 10] = [
+    ("GNOME", "Desktop Environment"),
+    ("KDE Plasma", "Desktop Environment"),
+    ("XFCE", "Desktop Environment"),
+    ("Cinnamon", "Desktop Environment"),
+    ("MATE", "Desktop Environment"),
+    ("lxqt", "Desktop Environment"),
+    ("Budgie", "Desktop Environment"),
+    ("Hyprland", "Window Manager"),
+    ("i3", "Window Manager"),
+    ("None", "No graphical environment"),
+  ];
+
+  pub fn new(current: Option<&str>) -> Self {
+    let desktops = Self::DESKTOPS
+      .iter()
+      .map(|(name, _)| name.to_string())
+      .collect::<Vec<_>>();
+    let mut desktops = StrList::new("Select Desktop Environment", desktops);
+    desktops.commit_value(current);
+    desktops.focus();
+    let search_bar = LineEditor::new("Search (/)", Some("Filter desktops..."));
+    let help_content = styled_block(vec![
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Navigate desktop environment options"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
+        (None, " - Select desktop environment and return"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "/"),
+        (None, " - Search desktops, Esc clears the search"),
+      ],
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().back_label,
+        ),
+        (None, " - Cancel and return to menu"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "?"),
+        (None, " - Show this help"),
+      ],
+      vec![(None, "")],
+      vec![(
+        None,
+        "Select the desktop environment for your graphical interface.",
+      )],
+    ]);
+    let help_modal = HelpModal::new("Desktop Environment", help_content);
+    Self {
+      desktops,
+      search_bar,
+      help_modal,
+    }
+  }
+  pub fn desktop_category(name: &str) -> Option<&'static str> {
+    Self::DESKTOPS
+      .iter()
+      .find(|(desktop, _)| *desktop == name)
+      .map(|(_, category)| *category)
+  }
+  pub fn get_desktop_info<'a>(name: &str) -> InfoBox<'a> {
+    match name {
+      "GNOME" => InfoBox::new(
+        "GNOME",
+        styled_block(vec![
+          vec![
+            (HIGHLIGHT, "GNOME"),
+            (None, " is a "),
+            (HIGHLIGHT, "modern and popular desktop environment"),
+            (None, " that provides a "),
+            (HIGHLIGHT, "user-friendly experience"),
+            (None, " with a focus on "),
+            (HIGHLIGHT, "simplicity and elegance"),
+            (None, "."),
+          ],
+          vec![
+            (None, "It features a "),
+            (HIGHLIGHT, "clean interface"),
+            (None, " with "),
+            (HIGHLIGHT, "activities overview"),
+            (None, ", "),
+            (HIGHLIGHT, "workspaces"),
+            (None, ", and extensive "),
+            (HIGHLIGHT, "customization options"),
+            (None, " through extensions."),
+          ],
+          vec![
+            (None, "GNOME is "),
+            (HIGHLIGHT, "resource-intensive"),
+            (None, " but offers "),
+            (HIGHLIGHT, "excellent accessibility"),
+            (None, " and "),
+            (HIGHLIGHT, "touch support"),
+            (None, "."),
+          ],
+        ]),
+      ),
+      "KDE Plasma" => InfoBox::new(
+        "KDE Plasma",
+        styled_block(vec![
+          vec![
+            (HIGHLIGHT, "KDE Plasma"),
+            (None, " is a "),
+            (HIGHLIGHT, "highly customizable desktop environment"),
+            (None, " that offers "),
+            (HIGHLIGHT, "extensive configuration options"),
+            (None, " and a "),
+            (HIGHLIGHT, "traditional desktop experience"),
+            (None, "."),
+          ],
+          vec![
+            (None, "It provides "),
+            (HIGHLIGHT, "powerful widgets"),
+            (None, ", "),
+            (HIGHLIGHT, "multiple panel layouts"),
+            (None, ", and "),
+            (HIGHLIGHT, "advanced system settings"),
+            (None, " with a familiar Windows-like interface."),
+          ],
+          vec![
+            (None, "KDE Plasma is "),
+            (HIGHLIGHT, "feature-rich"),
+            (None, " and "),
+            (HIGHLIGHT, "resource-efficient"),
+            (
+              None,
+              ", making it suitable for both power users and beginners.",
+            ),
+          ],
+        ]),
+      ),
+      "Hyprland" => InfoBox::new(
+        "Hyprland",
+        styled_block(vec![
+          vec![
+            (HIGHLIGHT, "Hyprland"),
+            (None, " is a "),
+            (HIGHLIGHT, "dynamic tiling Wayland compositor"),
+            (None, " that focuses on "),
+            (HIGHLIGHT, "eye candy and customization"),
+            (None, "."),
+          ],
+          vec![
+            (None, "It features "),
+            (HIGHLIGHT, "beautiful animations"),
+            (None, ", "),
+            (HIGHLIGHT, "automatic window tiling"),
+            (None, ", and "),
+            (HIGHLIGHT, "extensive configuration"),
+            (None, " through text files."),
+          ],
+          vec![
+            (None, "Hyprland is "),
+            (HIGHLIGHT, "highly efficient"),
+            (None, " and perfect for users who prefer "),
+            (HIGHLIGHT, "keyboard-driven workflows"),
+            (None, " and "),
+            (HIGHLIGHT, "minimal resource usage"),
+            (None, "."),
+          ],
+        ]),
+      ),
+      "XFCE" => InfoBox::new(
+        "XFCE",
+        styled_block(vec![
+          vec![
+            (HIGHLIGHT, "XFCE"),
+            (None, " is a "),
+            (HIGHLIGHT, "lightweight and fast desktop environment"),
+            (None, " that aims to be "),
+            (HIGHLIGHT, "visually appealing and user-friendly"),
+            (None, " while being "),
+            (HIGHLIGHT, "resource-efficient"),
+            (None, "."),
+          ],
+          vec![
+            (None, "It provides a "),
+            (HIGHLIGHT, "traditional desktop experience"),
+            (None, " with "),
+            (HIGHLIGHT, "customizable panels"),
+            (None, ", "),
+            (HIGHLIGHT, "file manager"),
+            (None, ", and "),
+            (HIGHLIGHT, "application menu"),
+            (None, "."),
+          ],
+          vec![
+            (None, "XFCE is "),
+            (HIGHLIGHT, "perfect for older hardware"),
+            (None, " or users who want a "),
+            (HIGHLIGHT, "simple, stable"),
+            (None, " desktop without sacrificing functionality."),
+          ],
+        ]),
+      ),
+      "Cinnamon" => InfoBox::new(
+        "Cinnamon",
+        styled_block(vec![
+          vec![
+            (HIGHLIGHT, "Cinnamon"),
+            (None, " is a "),
+            (HIGHLIGHT, "modern desktop environment"),
+            (None, " that provides a "),
+            (HIGHLIGHT, "familiar and intuitive experience"),
+            (None, " similar to traditional desktops."),
+          ],
+          vec![
+            (None, "It features a "),
+            (HIGHLIGHT, "taskbar-style panel"),
+            (None, ", "),
+            (HIGHLIGHT, "system tray"),
+            (None, ", and "),
+            (HIGHLIGHT, "start menu"),
+            (None, " with "),
+            (HIGHLIGHT, "smooth animations"),
+            (None, " and effects."),
+          ],
+          vec![
+            (None, "Cinnamon balances "),
+            (HIGHLIGHT, "modern features"),
+            (None, " with "),
+            (HIGHLIGHT, "traditional usability"),
+            (
+              None,
+              ", making it great for users transitioning from other operating systems.",
+            ),
+          ],
+        ]),
+      ),
+      "MATE" => InfoBox::new(
+        "MATE",
+        styled_block(vec![
+          vec![
+            (HIGHLIGHT, "MATE"),
+            (None, " is a "),
+            (HIGHLIGHT, "traditional desktop environment"),
+            (None, " that continues the legacy of "),
+            (HIGHLIGHT, "GNOME 2"),
+            (None, " with a "),
+            (HIGHLIGHT, "classic interface"),
+            (None, "."),
+          ],
+          vec![
+            (None, "It provides "),
+            (HIGHLIGHT, "stability"),
+            (None, ", "),
+            (HIGHLIGHT, "reliability"),
+            (None, ", and "),
+            (HIGHLIGHT, "low resource usage"),
+            (None, " while maintaining "),
+            (HIGHLIGHT, "familiar desktop metaphors"),
+            (None, "."),
+          ],
+          vec![
+            (None, "MATE is "),
+            (HIGHLIGHT, "ideal for users"),
+            (None, " who prefer "),
+            (HIGHLIGHT, "conventional desktop layouts"),
+            (None, " and "),
+            (HIGHLIGHT, "proven workflows"),
+            (None, "."),
+          ],
+        ]),
+      ),
+      "lxqt" => InfoBox::new(
+        "LXQt",
+        styled_block(vec![
+          vec![
+            (HIGHLIGHT, "LXQt"),
+            (None, " is a "),
+            (HIGHLIGHT, "lightweight Qt-based desktop environment"),
+            (None, " that focuses on "),
+            (HIGHLIGHT, "efficiency and performance"),
+            (None, "."),
+          ],
+          vec![
+            (None, "It provides a "),
+            (HIGHLIGHT, "simple and clean interface"),
+            (None, " with "),
+            (HIGHLIGHT, "low memory footprint"),
+            (None, " and "),
+            (HIGHLIGHT, "fast startup times"),
+            (None, "."),
+          ],
+          vec![
+            (None, "LXQt is "),
+            (HIGHLIGHT, "perfect for older computers"),
+            (None, " or users who want "),
+            (HIGHLIGHT, "minimal resource consumption"),
+            (None, " without sacrificing modern features."),
+          ],
+        ]),
+      ),
+      "Budgie" => InfoBox::new(
+        "Budgie",
+        styled_block(vec![
+          vec![
+            (HIGHLIGHT, "Budgie"),
+            (None, " is a "),
+            (HIGHLIGHT, "elegant and modern desktop environment"),
+            (None, " that emphasizes "),
+            (HIGHLIGHT, "simplicity and user experience"),
+            (None, "."),
+          ],
+          vec![
+            (None, "It features a "),
+            (HIGHLIGHT, "clean sidebar panel"),
+            (None, ", "),
+            (HIGHLIGHT, "notification system"),
+            (None, ", and "),
+            (HIGHLIGHT, "built-in applets"),
+            (None, " with a focus on productivity."),
+          ],
+          vec![
+            (None, "Budgie offers a "),
+            (HIGHLIGHT, "balance between simplicity and functionality"),
+            (None, ", making it suitable for users who want a "),
+            (HIGHLIGHT, "modern desktop"),
+            (None, " without complexity."),
+          ],
+        ]),
+      ),
+      "i3" => InfoBox::new(
+        "i3",
+        styled_block(vec![
+          vec![
+            (HIGHLIGHT, "i3"),
+            (None, " is a "),
+            (HIGHLIGHT, "tiling window manager"),
+            (None, " designed for "),
+            (HIGHLIGHT, "power users and developers"),
+            (None, " who prefer "),
+            (HIGHLIGHT, "keyboard-driven workflows"),
+            (None, "."),
+          ],
+          vec![
+            (None, "It automatically "),
+            (HIGHLIGHT, "tiles windows"),
+            (None, " to make "),
+            (HIGHLIGHT, "efficient use of screen space"),
+            (None, " and provides "),
+            (HIGHLIGHT, "extensive customization"),
+            (None, " through configuration files."),
+          ],
+          vec![
+            (None, "i3 has "),
+            (HIGHLIGHT, "minimal resource usage"),
+            (None, " and offers "),
+            (HIGHLIGHT, "maximum productivity"),
+            (None, " for users comfortable with command-line interfaces."),
+          ],
+        ]),
+      ),
+      _ => InfoBox::new(
+        "Unknown Desktop Environment",
+        styled_block(vec![vec![(
+          None,
+          "No information available for this desktop environment.",
+        )]]),
+      ),
+    }
+  }
+  pub fn display_widget(installer: &mut Installer) -> Option<Box<dyn ConfigWidget>> {
+    let excluded = installer.desktop_excludes.len();
+    installer.desktop_environment.clone().map(|s| {
+      let mut lines = vec![
+        vec![(None, "Current desktop environment set to:".to_string())],
+        vec![(HIGHLIGHT, s)],
+      ];
+      if excluded > 0 {
+        lines.push(vec![(None, format!("{excluded} default apps left out"))]);
+      }
+      let ib = InfoBox::new("", styled_block(lines));
+      Box::new(ib) as Box<dyn ConfigWidget>
+    })
+  }
+  pub fn page_info<'a>() -> (String, Vec<Line<'a>>) {
+    (
+      "Desktop Environment".to_string(),
+      styled_block(vec![
+        vec![(
+          None,
+          "Select the desktop environment to be installed on your system.",
+        )],
+        vec![(
+          None,
+          "The desktop environment provides the graphical user interface (GUI) for your system, including the window manager, panels, and application launchers.",
+        )],
+        vec![(
+          None,
+          "Choosing a desktop environment can help tailor the user experience to your preferences and workflow.",
+        )],
+      ]),
+    )
+  }
+}
+
+impl Default for DesktopEnvironment {
+  fn default() -> Self {
+    Self::new(None)
+  }
+}
+
+impl Page for DesktopEnvironment {
+  fn render(&mut self, _installer: &mut Installer, f: &mut Frame, area: Rect) {
+    let vert_chunks = Layout::default()
+      .direction(Direction::Vertical)
+      .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
+      .split(area);
+    let hor_chunks = split_hor!(
+      vert_chunks[0],
+      1,
+      [
+        Constraint::Percentage(38),
+        Constraint::Length(28),
+        Constraint::Percentage(38),
+      ]
+    );
+    let list_chunks = split_vert!(
+      hor_chunks[1],
+      0,
+      [Constraint::Length(3), Constraint::Min(0)]
+    );
+
+    self.search_bar.render(f, list_chunks[0]);
+    self.desktops.render(f, list_chunks[1]);
+    if let Some(name) = self.desktops.selected_item()
+      && let Some(category) = Self::desktop_category(name)
+      && name != "None"
+    {
+      let info_box = Self::get_desktop_info(name);
+      let category_box = InfoBox::new(
+        "",
+        styled_block(vec![vec![(None, "Type: "), (HIGHLIGHT, category)]]),
+      );
+      let info_chunks = split_vert!(
+        vert_chunks[1],
+        0,
+        [Constraint::Length(3), Constraint::Min(0)]
+      );
+      category_box.render(f, info_chunks[0]);
+      info_box.render(f, info_chunks[1]);
+    }
+
+    self.help_modal.render(f, area);
+  }
+
+  fn get_help_content(&self) -> (String, Vec<Line<'_>>) {
+    let help_content = styled_block(vec![
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Navigate desktop environment options"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
+        (None, " - Select desktop environment and return"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "/"),
+        (None, " - Search desktops, Esc clears the search"),
+      ],
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().back_label,
+        ),
+        (None, " - Cancel and return to menu"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "?"),
+        (None, " - Show this help"),
+      ],
+      vec![(None, "")],
+      vec![(
+        None,
+        "Select the desktop environment for your graphical interface.",
+      )],
+    ]);
+    ("Desktop Environment".to_string(), help_content)
+  }
+
+  fn handle_paste(&mut self, _installer: &mut Installer, text: &str) -> Signal {
+    if self.help_modal.visible {
+      return Signal::Wait;
+    }
+    // Pasting into the list starts a search for it
+    self.search_bar.focus();
+    self.search_bar.paste(text);
+    self.desktops.set_filter(search_filter(&self.search_bar));
+    Signal::Wait
+  }
+
+  fn handle_input(&mut self, installer: &mut Installer, event: KeyEvent) -> Signal {
+    match event.code {
+      KeyCode::Char('?') => {
+        self.help_modal.toggle();
+        Signal::Wait
+      }
+      code if ui_close!(code) && self.help_modal.visible => {
+        self.help_modal.hide();
+        Signal::Wait
+      }
+      _ if self.help_modal.visible => Signal::Wait,
+      KeyCode::Esc if self.search_bar.is_focused() => {
+        self.search_bar.clear();
+        self.search_bar.unfocus();
+        self.desktops.set_filter(None::<String>);
+        Signal::Wait
+      }
+      KeyCode::Enter => {
+        let Some(desktop) = self.desktops.selected_item() else {
+          return Signal::Wait;
+        };
+        if installer.desktop_environment.as_ref() != Some(desktop) {
+          installer.desktop_excludes.clear();
+        }
+        installer.desktop_environment = Some(desktop.clone());
+        if DesktopApps::optional_apps(desktop).is_empty() {
+          Signal::Pop
+        } else {
+          Signal::Push(Box::new(DesktopApps::new(
+            desktop,
+            &installer.desktop_excludes,
+          )))
+        }
+      }
+      KeyCode::Up | KeyCode::Down if self.search_bar.is_focused() => {
+        if event.code == KeyCode::Up {
+          self.desktops.previous_item();
+        } else {
+          self.desktops.next_item();
+        }
+        Signal::Wait
+      }
+      _ if self.search_bar.is_focused() => {
+        let signal = self.search_bar.handle_input(event);
+        self.desktops.set_filter(search_filter(&self.search_bar));
+        signal
+      }
+      KeyCode::Char('/') => {
+        self.search_bar.focus();
+        Signal::Wait
+      }
+      code if ui_back!(code) => Signal::Pop,
+      code if ui_up!(code) => {
+        if !self.desktops.previous_item() {
+          self.desktops.last_item();
+        }
+        Signal::Wait
+      }
+      code if ui_down!(code) => {
+        if !self.desktops.next_item() {
+          self.desktops.first_item();
+        }
+        Signal::Wait
+      }
+      _ => self.desktops.handle_input(event),
+    }
+  }
+}
+
+/// Default apps that can be left out of a desktop environment's install
+pub struct DesktopApps {
+  apps: &'static [(&'static str, &'static str)],
+  buttons: WidgetBox,
+  desktop: String,
+  help_modal: HelpModal<'static>,
+}
+
+impl DesktopApps {
+  const GNOME_APPS: [(&'static str, &'static str); 10] = [
+    ("GNOME Tour", "gnome-tour"),
+    ("Web browser (Epiphany)", "epiphany"),
+    ("Mail (Geary)", "geary"),
+    ("Music", "gnome-music"),
+    ("Videos (Totem)", "totem"),
+    ("Maps", "gnome-maps"),
+    ("Weather", "gnome-weather"),
+    ("Contacts", "gnome-contacts"),
+    ("Connections", "gnome-connections"),
+    ("Help (Yelp)", "yelp"),
+  ];
+  const PLASMA_APPS: [(&'static str, &'static str); 8] = [
+    ("Music player (Elisa)", "libsForQt5.elisa"),
+    ("Oxygen theme", "libsForQt5.oxygen"),
+    ("Help center", "libsForQt5.khelpcenter"),
+    (
+      "Browser integration",
+      "libsForQt5.plasma-browser-integration",
+    ),
+    ("Print manager", "libsForQt5.print-manager"),
+    ("Document viewer (Okular)", "libsForQt5.okular"),
+    ("Image viewer (Gwenview)", "libsForQt5.gwenview"),
+    ("Text editor (Kate)", "libsForQt5.kate"),
+  ];
+
+  /// Label and package of each app `desktop` installs by default that can be
+  /// excluded. Empty for desktops without an exclude option.
+  pub fn optional_apps(desktop: &str) -> &'static [(&'static str, &'static str)] {
+    match desktop {
+      "GNOME" => &Self::GNOME_APPS,
+      "KDE Plasma" => &Self::PLASMA_APPS,
+      _ => &[],
+    }
+  }
+
+  pub fn new(desktop: &str, excluded: &[String]) -> Self {
+    let apps = Self::optional_apps(desktop);
+    let mut children: Vec<Box<dyn ConfigWidget>> = apps
+      .iter()
+      .map(|(label, pkg)| {
+        Box::new(CheckBox::new(*label, excluded.iter().any(|e| e == pkg))) as Box<dyn ConfigWidget>
+      })
+      .collect();
+    children.push(Box::new(Button::new("Done")));
+    let mut buttons = WidgetBox::button_menu(children);
+    buttons.focus();
+    let help_content = styled_block(vec![
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Navigate apps"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
+        (None, " - Toggle app or select Done"),
+      ],
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().back_label,
+        ),
+        (None, " - Return to menu"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "?"),
+        (None, " - Show this help"),
+      ],
+      vec![(None, "")],
+      vec![(
+        None,
+        "Checked apps are left out of the install. They can still be installed later.",
+      )],
+    ]);
+    let help_modal = HelpModal::new("Desktop Apps", help_content);
+    Self {
+      apps,
+      buttons,
+      desktop: desktop.to_string(),
+      help_modal,
+    }
+  }
+}
+
+impl Page for DesktopApps {
+  fn render(&mut self, _installer: &mut Installer, f: &mut Frame, area: Rect) {
+    let chunks = split_vert!(area, 1, [Constraint::Length(5), Constraint::Min(0)]);
+    let hor_chunks = split_hor!(
+      chunks[1],
+      1,
+      [
+        Constraint::Percentage(30),
+        Constraint::Percentage(40),
+        Constraint::Percentage(30),
+      ]
+    );
+    let info_box = InfoBox::new(
+      "",
+      styled_block(vec![
+        vec![(
+          None,
+          format!("{} comes with a set of default apps.", self.desktop),
+        )],
+        vec![(
+          None,
+          "Check the ones you don't want for a more minimal install.".to_string(),
+        )],
+      ]),
+    );
+    info_box.render(f, chunks[0]);
+    self.buttons.render(f, hor_chunks[1]);
+    self.help_modal.render(f, area);
+  }
+
+  fn get_help_content(&self) -> (String, Vec<Line<'_>>) {
     let help_content = styled_block(vec![
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "↑/↓, j/k"),
-        (None, " - Navigate desktop environment options"),
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Navigate apps"),
       ],
       vec![
         (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
-        (None, " - Select desktop environment and return"),
+        (None, " - Toggle app or select Done"),
+      ],
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().back_label,
+        ),
+        (None, " - Return to menu"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "?"),
+        (None, " - Show this help"),
+      ],
+      vec![(None, "")],
+      vec![(
+        None,
+        "Checked apps are left out of the install. They can still be installed later.",
+      )],
+    ]);
+    ("Desktop Apps".to_string(), help_content)
+  }
+
+  fn handle_input(&mut self, installer: &mut Installer, event: KeyEvent) -> Signal {
+    match event.code {
+      KeyCode::Char('?') => {
+        self.help_modal.toggle();
+        Signal::Wait
+      }
+      code if ui_close!(code) && self.help_modal.visible => {
+        self.help_modal.hide();
+        Signal::Wait
+      }
+      _ if self.help_modal.visible => Signal::Wait,
+      // The desktop itself was already chosen on the previous page
+      code if ui_back!(code) => Signal::PopCount(2),
+      code if ui_up!(code) => {
+        self.buttons.prev_child();
+        Signal::Wait
+      }
+      code if ui_down!(code) => {
+        self.buttons.next_child();
+        Signal::Wait
+      }
+      KeyCode::Enter => {
+        let Some(idx) = self.buttons.selected_child() else {
+          return Signal::Wait;
+        };
+        let Some((_, pkg)) = self.apps.get(idx) else {
+          return Signal::PopCount(2);
+        };
+        let Some(chkbox) = self.buttons.focused_child_mut() else {
+          return Signal::Wait;
+        };
+        chkbox.interact();
+        let Some(Value::Bool(checked)) = chkbox.get_value() else {
+          return Signal::Wait;
+        };
+        installer.desktop_excludes.retain(|e| e != pkg);
+        if checked {
+          installer.desktop_excludes.push(pkg.to_string());
+        }
+        Signal::Wait
+      }
+      _ => Signal::Wait,
+    }
+  }
+}
+
+pub struct Kernels {
+  kernels: MultiSelectList,
+  help_modal: HelpModal<'static>,
+}
+
+impl Kernels {
+  pub const KERNELS: [&str; 5] = [
+    "linux",
+    "linux-lts",
+    "linux-zen",
+    "linux-hardened",
+    Self::NO_KERNEL,
+  ];
+  /// Leaves `boot.kernelPackages` to the user, can't be combined with the
+  /// others
+  const NO_KERNEL: &str = "None (custom kernel)";
+
+  pub fn new(current: Option<&[String]>) -> Self {
+    let kernels = Self::KERNELS
+      .iter()
+      .map(|s| s.to_string())
+      .collect::<Vec<_>>();
+    let mut kernels = MultiSelectList::new("Select Kernels", kernels);
+    match current {
+      Some([]) => kernels.set_checked(&[Self::NO_KERNEL.to_string()]),
+      Some(current) => kernels.set_checked(current),
+      None => {}
+    }
+    kernels.focus();
+    let help_content = styled_block(vec![
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Navigate kernel options"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Space"),
+        (None, " - Check or uncheck a kernel"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
+        (None, " - Save the checked kernels and return"),
       ],
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "Esc, q, ←, h"),
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().back_label,
+        ),
         (None, " - Cancel and return to menu"),
       ],
       vec![
@@ -2864,323 +7707,288 @@ impl DesktopEnvironment {
       vec![(None, "")],
       vec![(
         None,
-        "Select the desktop environment for your graphical interface.",
+        "The first kernel checked boots by default, the others get their own boot entries.",
       )],
     ]);
-    let help_modal = HelpModal::new("Desktop Environment", help_content);
+    let help_modal = HelpModal::new("Kernel", help_content);
     Self {
-      desktops,
+      kernels,
       help_modal,
     }
   }
-  pub fn get_desktop_info<'a>(idx: usize) -> InfoBox<'a> {
-    match idx {
-      0 => InfoBox::new(
-        "GNOME",
-        styled_block(vec![
-          vec![
-            (HIGHLIGHT, "GNOME"),
-            (None, " is a "),
-            (HIGHLIGHT, "modern and popular desktop environment"),
-            (None, " that provides a "),
-            (HIGHLIGHT, "user-friendly experience"),
-            (None, " with a focus on "),
-            (HIGHLIGHT, "simplicity and elegance"),
-            (None, "."),
-          ],
-          vec![
-            (None, "It features a "),
-            (HIGHLIGHT, "clean interface"),
-            (None, " with "),
-            (HIGHLIGHT, "activities overview"),
-            (None, ", "),
-            (HIGHLIGHT, "workspaces"),
-            (None, ", and extensive "),
-            (HIGHLIGHT, "customization options"),
-            (None, " through extensions."),
-          ],
-          vec![
-            (None, "GNOME is "),
-            (HIGHLIGHT, "resource-intensive"),
-            (None, " but offers "),
-            (HIGHLIGHT, "excellent accessibility"),
-            (None, " and "),
-            (HIGHLIGHT, "touch support"),
-            (None, "."),
-          ],
-        ]),
-      ),
-      1 => InfoBox::new(
-        "KDE Plasma",
+  pub fn display_widget(installer: &mut Installer) -> Option<Box<dyn ConfigWidget>> {
+    installer.kernels.clone().map(|kernels| {
+      let mut lines = vec![vec![(None, "Currently selected kernels:".to_string())]];
+      if kernels.is_empty() {
+        lines.push(vec![(HIGHLIGHT, Self::NO_KERNEL.to_string())]);
+      }
+      for (i, kernel) in kernels.into_iter().enumerate() {
+        let mut line = vec![(HIGHLIGHT, kernel)];
+        if i == 0 {
+          line.push((None, " (default)".to_string()));
+        }
+        lines.push(line);
+      }
+      Box::new(InfoBox::new("", styled_block(lines))) as Box<dyn ConfigWidget>
+    })
+  }
+  pub fn page_info<'a>() -> (String, Vec<Line<'a>>) {
+    (
+      "Kernel".to_string(),
+      styled_block(vec![
+        vec![(
+          None,
+          "Select the Linux kernels to be installed on your system.",
+        )],
+        vec![(
+          None,
+          "The kernel is the core component of the operating system that manages hardware resources and provides essential services for other software.",
+        )],
+        vec![(
+          None,
+          "Choosing a kernel can help optimize system performance and compatibility with your hardware. Installing a second one, e.g. linux-lts next to linux, leaves a fallback in the boot menu.",
+        )],
+      ]),
+    )
+  }
+  /// Check or uncheck the highlighted kernel, keeping "None" exclusive
+  fn toggle(&mut self) {
+    if !self.kernels.toggle_selected() {
+      return;
+    }
+    let selected = self.kernels.selected_idx;
+    let no_kernel = Self::KERNELS.len() - 1;
+    if selected == no_kernel {
+      self.kernels.checked = vec![no_kernel];
+    } else {
+      self.kernels.uncheck(no_kernel);
+    }
+  }
+}
+
+impl Default for Kernels {
+  fn default() -> Self {
+    Self::new(None)
+  }
+}
+
+impl Page for Kernels {
+  fn render(&mut self, _installer: &mut Installer, f: &mut Frame, area: Rect) {
+    let chunks = split_vert!(area, 1, [Constraint::Percentage(100)]);
+    self.kernels.render(f, chunks[0]);
+    self.help_modal.render(f, area);
+  }
+
+  fn get_help_content(&self) -> (String, Vec<Line<'_>>) {
+    let help_content = styled_block(vec![
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Navigate kernel options"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Space"),
+        (None, " - Check or uncheck a kernel"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
+        (None, " - Save the checked kernels and return"),
+      ],
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().back_label,
+        ),
+        (None, " - Cancel and return to menu"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "?"),
+        (None, " - Show this help"),
+      ],
+      vec![(None, "")],
+      vec![(
+        None,
+        "The first kernel checked boots by default, the others get their own boot entries.",
+      )],
+    ]);
+    ("Kernel".to_string(), help_content)
+  }
+
+  fn handle_input(&mut self, installer: &mut Installer, event: KeyEvent) -> Signal {
+    match event.code {
+      KeyCode::Char('?') => {
+        self.help_modal.toggle();
+        Signal::Wait
+      }
+      code if ui_close!(code) && self.help_modal.visible => {
+        self.help_modal.hide();
+        Signal::Wait
+      }
+      _ if self.help_modal.visible => Signal::Wait,
+      code if ui_back!(code) => Signal::Pop,
+      KeyCode::Char(' ') => {
+        self.toggle();
+        Signal::Wait
+      }
+      KeyCode::Enter => {
+        // Nothing checked yet takes the highlighted one, like a single choice
+        if self.kernels.checked.is_empty() {
+          self.toggle();
+        }
+        let kernels = self.kernels.checked_items();
+        installer.kernels = Some(if kernels == [Self::NO_KERNEL] {
+          vec![]
+        } else {
+          kernels
+        });
+        Signal::Pop
+      }
+      code if ui_up!(code) => {
+        if !self.kernels.previous_item() {
+          self.kernels.last_item();
+        }
+        Signal::Wait
+      }
+      code if ui_down!(code) => {
+        if !self.kernels.next_item() {
+          self.kernels.first_item();
+        }
+        Signal::Wait
+      }
+      _ => self.kernels.handle_input(event),
+    }
+  }
+}
+
+pub struct Audio {
+  backends: StrList,
+  help_modal: HelpModal<'static>,
+}
+
+impl Audio {
+  pub fn new(current: Option<&str>) -> Self {
+    let backends = ["PipeWire", "PulseAudio", "None"]
+      .iter()
+      .map(|s| s.to_string())
+      .collect::<Vec<_>>();
+    let mut backends = StrList::new("Select Audio Backend", backends);
+    backends.commit_value(current);
+    backends.focus();
+    let help_content = styled_block(vec![
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Navigate audio backend options"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
+        (None, " - Select audio backend and return"),
+      ],
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().back_label,
+        ),
+        (None, " - Cancel and return to menu"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "?"),
+        (None, " - Show this help"),
+      ],
+      vec![(None, "")],
+      vec![(
+        None,
+        "Select the audio management backend for sound devices.",
+      )],
+    ]);
+    let help_modal = HelpModal::new("Audio", help_content);
+    Self {
+      backends,
+      help_modal,
+    }
+  }
+  pub fn get_audio_info<'a>(idx: usize) -> InfoBox<'a> {
+    match idx {
+      0 => InfoBox::new(
+        "PipeWire",
         styled_block(vec![
           vec![
-            (HIGHLIGHT, "KDE Plasma"),
+            (HIGHLIGHT, "PipeWire"),
             (None, " is a "),
-            (HIGHLIGHT, "highly customizable desktop environment"),
-            (None, " that offers "),
-            (HIGHLIGHT, "extensive configuration options"),
-            (None, " and a "),
-            (HIGHLIGHT, "traditional desktop experience"),
+            (HIGHLIGHT, "modern, low-latency audio server"),
+            (None, " that serves as a replacement for both "),
+            (HIGHLIGHT, "PulseAudio and JACK"),
             (None, "."),
           ],
           vec![
             (None, "It provides "),
-            (HIGHLIGHT, "powerful widgets"),
-            (None, ", "),
-            (HIGHLIGHT, "multiple panel layouts"),
-            (None, ", and "),
-            (HIGHLIGHT, "advanced system settings"),
-            (None, " with a familiar Windows-like interface."),
-          ],
-          vec![
-            (None, "KDE Plasma is "),
-            (HIGHLIGHT, "feature-rich"),
-            (None, " and "),
-            (HIGHLIGHT, "resource-efficient"),
-            (
-              None,
-              ", making it suitable for both power users and beginners.",
-            ),
-          ],
-        ]),
-      ),
-      2 => InfoBox::new(
-        "Hyprland",
-        styled_block(vec![
-          vec![
-            (HIGHLIGHT, "Hyprland"),
-            (None, " is a "),
-            (HIGHLIGHT, "dynamic tiling Wayland compositor"),
-            (None, " that focuses on "),
-            (HIGHLIGHT, "eye candy and customization"),
-            (None, "."),
-          ],
-          vec![
-            (None, "It features "),
-            (HIGHLIGHT, "beautiful animations"),
-            (None, ", "),
-            (HIGHLIGHT, "automatic window tiling"),
-            (None, ", and "),
-            (HIGHLIGHT, "extensive configuration"),
-            (None, " through text files."),
-          ],
-          vec![
-            (None, "Hyprland is "),
-            (HIGHLIGHT, "highly efficient"),
-            (None, " and perfect for users who prefer "),
-            (HIGHLIGHT, "keyboard-driven workflows"),
-            (None, " and "),
-            (HIGHLIGHT, "minimal resource usage"),
-            (None, "."),
-          ],
-        ]),
-      ),
-      3 => InfoBox::new(
-        "XFCE",
-        styled_block(vec![
-          vec![
-            (HIGHLIGHT, "XFCE"),
-            (None, " is a "),
-            (HIGHLIGHT, "lightweight and fast desktop environment"),
-            (None, " that aims to be "),
-            (HIGHLIGHT, "visually appealing and user-friendly"),
-            (None, " while being "),
-            (HIGHLIGHT, "resource-efficient"),
-            (None, "."),
-          ],
-          vec![
-            (None, "It provides a "),
-            (HIGHLIGHT, "traditional desktop experience"),
+            (HIGHLIGHT, "professional audio capabilities"),
             (None, " with "),
-            (HIGHLIGHT, "customizable panels"),
-            (None, ", "),
-            (HIGHLIGHT, "file manager"),
-            (None, ", and "),
-            (HIGHLIGHT, "application menu"),
+            (HIGHLIGHT, "minimal latency"),
+            (None, " and supports "),
+            (HIGHLIGHT, "advanced audio routing"),
+            (None, " and "),
+            (HIGHLIGHT, "real-time processing"),
             (None, "."),
           ],
           vec![
-            (None, "XFCE is "),
-            (HIGHLIGHT, "perfect for older hardware"),
-            (None, " or users who want a "),
-            (HIGHLIGHT, "simple, stable"),
-            (None, " desktop without sacrificing functionality."),
+            (None, "PipeWire offers "),
+            (HIGHLIGHT, "excellent compatibility"),
+            (None, " with existing applications and is the "),
+            (HIGHLIGHT, "recommended choice"),
+            (None, " for modern Linux systems."),
           ],
         ]),
       ),
-      4 => InfoBox::new(
-        "Cinnamon",
+      1 => InfoBox::new(
+        "PulseAudio",
         styled_block(vec![
           vec![
-            (HIGHLIGHT, "Cinnamon"),
+            (HIGHLIGHT, "PulseAudio"),
             (None, " is a "),
-            (HIGHLIGHT, "modern desktop environment"),
-            (None, " that provides a "),
-            (HIGHLIGHT, "familiar and intuitive experience"),
-            (None, " similar to traditional desktops."),
-          ],
-          vec![
-            (None, "It features a "),
-            (HIGHLIGHT, "taskbar-style panel"),
-            (None, ", "),
-            (HIGHLIGHT, "system tray"),
-            (None, ", and "),
-            (HIGHLIGHT, "start menu"),
-            (None, " with "),
-            (HIGHLIGHT, "smooth animations"),
-            (None, " and effects."),
-          ],
-          vec![
-            (None, "Cinnamon balances "),
-            (HIGHLIGHT, "modern features"),
-            (None, " with "),
-            (HIGHLIGHT, "traditional usability"),
+            (HIGHLIGHT, "mature and stable audio server"),
             (
               None,
-              ", making it great for users transitioning from other operating systems.",
+              " that has been the standard audio system on Linux for many years.",
             ),
           ],
-        ]),
-      ),
-      5 => InfoBox::new(
-        "MATE",
-        styled_block(vec![
-          vec![
-            (HIGHLIGHT, "MATE"),
-            (None, " is a "),
-            (HIGHLIGHT, "traditional desktop environment"),
-            (None, " that continues the legacy of "),
-            (HIGHLIGHT, "GNOME 2"),
-            (None, " with a "),
-            (HIGHLIGHT, "classic interface"),
-            (None, "."),
-          ],
           vec![
             (None, "It provides "),
-            (HIGHLIGHT, "stability"),
-            (None, ", "),
-            (HIGHLIGHT, "reliability"),
-            (None, ", and "),
-            (HIGHLIGHT, "low resource usage"),
-            (None, " while maintaining "),
-            (HIGHLIGHT, "familiar desktop metaphors"),
-            (None, "."),
-          ],
-          vec![
-            (None, "MATE is "),
-            (HIGHLIGHT, "ideal for users"),
-            (None, " who prefer "),
-            (HIGHLIGHT, "conventional desktop layouts"),
-            (None, " and "),
-            (HIGHLIGHT, "proven workflows"),
-            (None, "."),
-          ],
-        ]),
-      ),
-      6 => InfoBox::new(
-        "LXQt",
-        styled_block(vec![
-          vec![
-            (HIGHLIGHT, "LXQt"),
-            (None, " is a "),
-            (HIGHLIGHT, "lightweight Qt-based desktop environment"),
-            (None, " that focuses on "),
-            (HIGHLIGHT, "efficiency and performance"),
-            (None, "."),
-          ],
-          vec![
-            (None, "It provides a "),
-            (HIGHLIGHT, "simple and clean interface"),
-            (None, " with "),
-            (HIGHLIGHT, "low memory footprint"),
-            (None, " and "),
-            (HIGHLIGHT, "fast startup times"),
-            (None, "."),
-          ],
-          vec![
-            (None, "LXQt is "),
-            (HIGHLIGHT, "perfect for older computers"),
-            (None, " or users who want "),
-            (HIGHLIGHT, "minimal resource consumption"),
-            (None, " without sacrificing modern features."),
-          ],
-        ]),
-      ),
-      7 => InfoBox::new(
-        "Budgie",
-        styled_block(vec![
-          vec![
-            (HIGHLIGHT, "Budgie"),
-            (None, " is a "),
-            (HIGHLIGHT, "elegant and modern desktop environment"),
-            (None, " that emphasizes "),
-            (HIGHLIGHT, "simplicity and user experience"),
-            (None, "."),
-          ],
-          vec![
-            (None, "It features a "),
-            (HIGHLIGHT, "clean sidebar panel"),
+            (HIGHLIGHT, "network audio streaming"),
             (None, ", "),
-            (HIGHLIGHT, "notification system"),
+            (HIGHLIGHT, "device management"),
             (None, ", and "),
-            (HIGHLIGHT, "built-in applets"),
-            (None, " with a focus on productivity."),
-          ],
-          vec![
-            (None, "Budgie offers a "),
-            (HIGHLIGHT, "balance between simplicity and functionality"),
-            (None, ", making it suitable for users who want a "),
-            (HIGHLIGHT, "modern desktop"),
-            (None, " without complexity."),
-          ],
-        ]),
-      ),
-      8 => InfoBox::new(
-        "i3",
-        styled_block(vec![
-          vec![
-            (HIGHLIGHT, "i3"),
-            (None, " is a "),
-            (HIGHLIGHT, "tiling window manager"),
-            (None, " designed for "),
-            (HIGHLIGHT, "power users and developers"),
-            (None, " who prefer "),
-            (HIGHLIGHT, "keyboard-driven workflows"),
-            (None, "."),
-          ],
-          vec![
-            (None, "It automatically "),
-            (HIGHLIGHT, "tiles windows"),
-            (None, " to make "),
-            (HIGHLIGHT, "efficient use of screen space"),
-            (None, " and provides "),
-            (HIGHLIGHT, "extensive customization"),
-            (None, " through configuration files."),
+            (HIGHLIGHT, "per-application volume control"),
+            (None, " with good application compatibility."),
           ],
           vec![
-            (None, "i3 has "),
-            (HIGHLIGHT, "minimal resource usage"),
-            (None, " and offers "),
-            (HIGHLIGHT, "maximum productivity"),
-            (None, " for users comfortable with command-line interfaces."),
+            (None, "PulseAudio is "),
+            (HIGHLIGHT, "well-tested and reliable"),
+            (None, " but may have "),
+            (HIGHLIGHT, "higher latency"),
+            (None, " compared to newer solutions like PipeWire."),
           ],
         ]),
       ),
       _ => InfoBox::new(
-        "Unknown Desktop Environment",
+        "Unknown Audio Backend",
         styled_block(vec![vec![(
           None,
-          "No information available for this desktop environment.",
+          "No information available for this audio backend.",
         )]]),
       ),
     }
   }
   pub fn display_widget(installer: &mut Installer) -> Option<Box<dyn ConfigWidget>> {
-    installer.desktop_environment.clone().map(|s| {
+    installer.audio_backend.clone().map(|s| {
       let ib = InfoBox::new(
         "",
         styled_block(vec![
-          vec![(None, "Current desktop environment set to:")],
+          vec![(None, "Current audio backend set to:")],
           vec![(HIGHLIGHT, &s)],
         ]),
       );
@@ -3189,32 +7997,32 @@ impl DesktopEnvironment {
   }
   pub fn page_info<'a>() -> (String, Vec<Line<'a>>) {
     (
-      "Desktop Environment".to_string(),
+      "Audio".to_string(),
       styled_block(vec![
         vec![(
           None,
-          "Select the desktop environment to be installed on your system.",
+          "Select the audio management backend to be installed on your system.",
         )],
         vec![(
           None,
-          "The desktop environment provides the graphical user interface (GUI) for your system, including the window manager, panels, and application launchers.",
+          "The audio backend is responsible for managing sound devices and providing audio services to applications.",
         )],
         vec![(
           None,
-          "Choosing a desktop environment can help tailor the user experience to your preferences and workflow.",
+          "Choosing an audio backend can help ensure that your system is able to handle audio playback and recording effectively.",
         )],
       ]),
     )
   }
 }
 
-impl Default for DesktopEnvironment {
+impl Default for Audio {
   fn default() -> Self {
-    Self::new()
+    Self::new(None)
   }
 }
 
-impl Page for DesktopEnvironment {
+impl Page for Audio {
   fn render(&mut self, _installer: &mut Installer, f: &mut Frame, area: Rect) {
     let vert_chunks = Layout::default()
       .direction(Direction::Vertical)
@@ -3224,16 +8032,16 @@ impl Page for DesktopEnvironment {
       vert_chunks[0],
       1,
       [
-        Constraint::Percentage(38),
-        Constraint::Length(28),
-        Constraint::Percentage(38),
+        Constraint::Percentage(40),
+        Constraint::Percentage(20),
+        Constraint::Percentage(40),
       ]
     );
 
-    let idx = self.desktops.selected_idx;
-    let info_box = Self::get_desktop_info(idx);
-    self.desktops.render(f, hor_chunks[1]);
-    if idx < 9 {
+    let idx = self.backends.selected_idx;
+    let info_box = Self::get_audio_info(idx);
+    self.backends.render(f, hor_chunks[1]);
+    if idx < 2 {
       info_box.render(f, vert_chunks[1]);
     }
 
@@ -3243,15 +8051,21 @@ impl Page for DesktopEnvironment {
   fn get_help_content(&self) -> (String, Vec<Line<'_>>) {
     let help_content = styled_block(vec![
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "↑/↓, j/k"),
-        (None, " - Navigate desktop environment options"),
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Navigate audio backend options"),
       ],
       vec![
         (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
-        (None, " - Select desktop environment and return"),
+        (None, " - Select audio backend and return"),
       ],
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "Esc, q, ←, h"),
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().back_label,
+        ),
         (None, " - Cancel and return to menu"),
       ],
       vec![
@@ -3261,10 +8075,10 @@ impl Page for DesktopEnvironment {
       vec![(None, "")],
       vec![(
         None,
-        "Select the desktop environment for your graphical interface.",
+        "Select the audio management backend for sound devices.",
       )],
     ]);
-    ("Desktop Environment".to_string(), help_content)
+    ("Audio".to_string(), help_content)
   }
 
   fn handle_input(&mut self, installer: &mut Installer, event: KeyEvent) -> Signal {
@@ -3273,59 +8087,83 @@ impl Page for DesktopEnvironment {
         self.help_modal.toggle();
         Signal::Wait
       }
-      ui_close!() if self.help_modal.visible => {
+      code if ui_close!(code) && self.help_modal.visible => {
         self.help_modal.hide();
         Signal::Wait
       }
       _ if self.help_modal.visible => Signal::Wait,
-      ui_back!() => Signal::Pop,
+      code if ui_back!(code) => Signal::Pop,
       KeyCode::Enter => {
-        installer.desktop_environment =
-          Some(self.desktops.items[self.desktops.selected_idx].clone());
+        installer.audio_backend = Some(self.backends.items[self.backends.selected_idx].clone());
         Signal::Pop
       }
-      ui_up!() => {
-        if !self.desktops.previous_item() {
-          self.desktops.last_item();
+      code if ui_up!(code) => {
+        if !self.backends.previous_item() {
+          self.backends.last_item();
         }
         Signal::Wait
       }
-      ui_down!() => {
-        if !self.desktops.next_item() {
-          self.desktops.first_item();
+      code if ui_down!(code) => {
+        if !self.backends.next_item() {
+          self.backends.first_item();
         }
         Signal::Wait
       }
-      _ => self.desktops.handle_input(event),
+      _ => self.backends.handle_input(event),
     }
   }
 }
 
-pub struct Kernels {
-  kernels: StrList,
+/// Desktops that come with their own Bluetooth settings, everything else gets
+/// Blueman if asked for
+pub fn needs_bluetooth_manager(desktop: &str) -> bool {
+  !matches!(desktop, "GNOME" | "KDE Plasma" | "None")
+}
+
+pub struct Bluetooth {
+  buttons: WidgetBox,
+  /// The selected desktop has no Bluetooth settings of its own
+  offers_manager: bool,
   help_modal: HelpModal<'static>,
 }
 
-impl Kernels {
-  pub fn new() -> Self {
-    let kernels = ["linux", "linux-lts", "linux-zen", "linux-hardened", "None"]
-      .iter()
-      .map(|s| s.to_string())
-      .collect::<Vec<_>>();
-    let mut kernels = StrList::new("Select Kernel", kernels);
-    kernels.focus();
+impl Bluetooth {
+  pub fn new(installer: &Installer) -> Self {
+    let offers_manager = installer
+      .desktop_environment
+      .as_deref()
+      .is_some_and(needs_bluetooth_manager);
+    let mut widgets: Vec<Box<dyn ConfigWidget>> = vec![Box::new(CheckBox::new(
+      "Enable Bluetooth",
+      installer.bluetooth,
+    ))];
+    if offers_manager {
+      widgets.push(Box::new(CheckBox::new(
+        "Add Blueman for managing devices",
+        installer.bluetooth_manager,
+      )));
+    }
+    widgets.push(Box::new(Button::new("Back")));
+    let mut buttons = WidgetBox::button_menu(widgets);
+    buttons.focus();
     let help_content = styled_block(vec![
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "↑/↓, j/k"),
-        (None, " - Navigate kernel options"),
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Navigate options"),
       ],
       vec![
         (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
-        (None, " - Select kernel and return"),
+        (None, " - Toggle option or select Back"),
       ],
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "Esc, q, ←, h"),
-        (None, " - Cancel and return to menu"),
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().back_label,
+        ),
+        (None, " - Return to menu"),
       ],
       vec![
         (Some((Color::Yellow, Modifier::BOLD)), "?"),
@@ -3334,77 +8172,97 @@ impl Kernels {
       vec![(None, "")],
       vec![(
         None,
-        "Select the Linux kernel to optimize system performance.",
+        "GNOME and KDE Plasma have Bluetooth settings built in, other desktops can use Blueman.",
       )],
     ]);
-    let help_modal = HelpModal::new("Kernel", help_content);
+    let help_modal = HelpModal::new("Bluetooth", help_content);
     Self {
-      kernels,
+      buttons,
+      offers_manager,
       help_modal,
     }
   }
   pub fn display_widget(installer: &mut Installer) -> Option<Box<dyn ConfigWidget>> {
-    installer.kernels.clone().map(|s| {
-      let ib = InfoBox::new(
-        "",
-        styled_block(vec![
-          vec![(None, "Currently selected kernels:".to_string())],
-          s.clone()
-            .into_iter()
-            .map(|k| (HIGHLIGHT, k))
-            .collect::<Vec<_>>(),
-        ]),
-      );
-      Box::new(ib) as Box<dyn ConfigWidget>
-    })
+    let status = match (installer.bluetooth, installer.bluetooth_manager) {
+      (false, _) => "disabled",
+      (true, true) => "enabled, with Blueman",
+      (true, false) => "enabled",
+    };
+    let ib = InfoBox::new(
+      "",
+      styled_block(vec![
+        vec![(None, "Bluetooth is currently:")],
+        vec![(HIGHLIGHT, status)],
+      ]),
+    );
+    Some(Box::new(ib) as Box<dyn ConfigWidget>)
   }
   pub fn page_info<'a>() -> (String, Vec<Line<'a>>) {
     (
-      "Kernel".to_string(),
+      "Bluetooth".to_string(),
       styled_block(vec![
         vec![(
           None,
-          "Select the Linux kernel to be installed on your system.",
-        )],
-        vec![(
-          None,
-          "The kernel is the core component of the operating system that manages hardware resources and provides essential services for other software.",
+          "Turn on Bluetooth support, powered on at boot. Most laptops want this for headsets, mice and keyboards.",
         )],
         vec![(
           None,
-          "Choosing a kernel can help optimize system performance and compatibility with your hardware.",
+          "Desktops without their own Bluetooth settings can add Blueman to pair and manage devices.",
         )],
       ]),
     )
   }
 }
 
-impl Default for Kernels {
-  fn default() -> Self {
-    Self::new()
-  }
-}
-
-impl Page for Kernels {
-  fn render(&mut self, _installer: &mut Installer, f: &mut Frame, area: Rect) {
-    let chunks = split_vert!(area, 1, [Constraint::Percentage(100)]);
-    self.kernels.render(f, chunks[0]);
+impl Page for Bluetooth {
+  fn render(&mut self, installer: &mut Installer, f: &mut Frame, area: Rect) {
+    let chunks = split_vert!(
+      area,
+      1,
+      [Constraint::Percentage(40), Constraint::Percentage(60)]
+    );
+    let hor_chunks = split_hor!(
+      chunks[1],
+      1,
+      [
+        Constraint::Percentage(30),
+        Constraint::Percentage(40),
+        Constraint::Percentage(30),
+      ]
+    );
+    let (_, mut info) = Self::page_info();
+    if let Some(ref desktop) = installer.desktop_environment
+      && !self.offers_manager
+    {
+      info.extend(styled_block(vec![vec![(
+        None,
+        format!("{desktop} manages Bluetooth devices itself, no extra manager is needed."),
+      )]]));
+    }
+    InfoBox::new("", info).render(f, chunks[0]);
+    self.buttons.render(f, hor_chunks[1]);
     self.help_modal.render(f, area);
   }
 
   fn get_help_content(&self) -> (String, Vec<Line<'_>>) {
     let help_content = styled_block(vec![
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "↑/↓, j/k"),
-        (None, " - Navigate kernel options"),
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Navigate options"),
       ],
       vec![
         (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
-        (None, " - Select kernel and return"),
+        (None, " - Toggle option or select Back"),
       ],
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "Esc, q, ←, h"),
-        (None, " - Cancel and return to menu"),
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().back_label,
+        ),
+        (None, " - Return to menu"),
       ],
       vec![
         (Some((Color::Yellow, Modifier::BOLD)), "?"),
@@ -3413,10 +8271,10 @@ impl Page for Kernels {
       vec![(None, "")],
       vec![(
         None,
-        "Select the Linux kernel to optimize system performance.",
+        "GNOME and KDE Plasma have Bluetooth settings built in, other desktops can use Blueman.",
       )],
     ]);
-    ("Kernel".to_string(), help_content)
+    ("Bluetooth".to_string(), help_content)
   }
 
   fn handle_input(&mut self, installer: &mut Installer, event: KeyEvent) -> Signal {
@@ -3425,46 +8283,89 @@ impl Page for Kernels {
         self.help_modal.toggle();
         Signal::Wait
       }
-      ui_close!() if self.help_modal.visible => {
+      code if ui_close!(code) && self.help_modal.visible => {
         self.help_modal.hide();
         Signal::Wait
       }
       _ if self.help_modal.visible => Signal::Wait,
-      ui_back!() => Signal::Pop,
+      code if ui_back!(code) => Signal::Pop,
+      code if ui_up!(code) => {
+        self.buttons.prev_child();
+        Signal::Wait
+      }
+      code if ui_down!(code) => {
+        self.buttons.next_child();
+        Signal::Wait
+      }
       KeyCode::Enter => {
-        // TODO: Implement multi selection for StrList
-        installer.kernels = Some(vec![self.kernels.items[self.kernels.selected_idx].clone()]);
-        Signal::Pop
+        let idx = self.buttons.selected_child();
+        let back_idx = if self.offers_manager { 2 } else { 1 };
+        if idx == Some(back_idx) {
+          return Signal::Pop;
+        }
+        let Some(chkbox) = self.buttons.focused_child_mut() else {
+          return Signal::Wait;
+        };
+        chkbox.interact();
+        let Some(Value::Bool(checked)) = chkbox.get_value() else {
+          return Signal::Wait;
+        };
+        match idx {
+          Some(0) => installer.bluetooth = checked,
+          Some(1) => installer.bluetooth_manager = checked,
+          _ => {}
+        }
+        Signal::Wait
       }
-      _ => self.kernels.handle_input(event),
+      _ => Signal::Wait,
     }
   }
 }
 
-pub struct Audio {
-  backends: StrList,
+pub const GPU_DRIVERS: &[&str] = &[
+  "NVIDIA (proprietary)",
+  "NVIDIA (open)",
+  "AMD",
+  "Intel",
+  "None/VM",
+];
+
+/// NVIDIA's userspace driver is unfree, whichever kernel module is used
+pub fn is_unfree_gpu_driver(driver: &str) -> bool {
+  driver.starts_with("NVIDIA")
+}
+
+pub struct Graphics {
+  drivers: StrList,
   help_modal: HelpModal<'static>,
 }
 
-impl Audio {
-  pub fn new() -> Self {
-    let backends = ["PipeWire", "PulseAudio", "None"]
+impl Graphics {
+  pub fn new(current: Option<&str>) -> Self {
+    let drivers = GPU_DRIVERS
       .iter()
       .map(|s| s.to_string())
       .collect::<Vec<_>>();
-    let mut backends = StrList::new("Select Audio Backend", backends);
-    backends.focus();
+    let mut drivers = StrList::new("Select GPU Driver", drivers);
+    drivers.commit_value(current);
+    drivers.focus();
     let help_content = styled_block(vec![
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "↑/↓, j/k"),
-        (None, " - Navigate audio backend options"),
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Navigate GPU driver options"),
       ],
       vec![
         (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
-        (None, " - Select audio backend and return"),
+        (None, " - Select GPU driver and return"),
       ],
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "Esc, q, ←, h"),
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().back_label,
+        ),
         (None, " - Cancel and return to menu"),
       ],
       vec![
@@ -3474,93 +8375,109 @@ impl Audio {
       vec![(None, "")],
       vec![(
         None,
-        "Select the audio management backend for sound devices.",
+        "Select the driver for the graphics card the system will run on.",
       )],
     ]);
-    let help_modal = HelpModal::new("Audio", help_content);
+    let help_modal = HelpModal::new("Graphics", help_content);
     Self {
-      backends,
+      drivers,
       help_modal,
     }
   }
-  pub fn get_audio_info<'a>(idx: usize) -> InfoBox<'a> {
+  pub fn get_driver_info<'a>(idx: usize) -> InfoBox<'a> {
     match idx {
       0 => InfoBox::new(
-        "PipeWire",
+        "NVIDIA (proprietary)",
         styled_block(vec![
           vec![
-            (HIGHLIGHT, "PipeWire"),
-            (None, " is a "),
-            (HIGHLIGHT, "modern, low-latency audio server"),
-            (None, " that serves as a replacement for both "),
-            (HIGHLIGHT, "PulseAudio and JACK"),
-            (None, "."),
+            (None, "NVIDIA's "),
+            (HIGHLIGHT, "closed-source kernel module"),
+            (None, ", the one that supports "),
+            (HIGHLIGHT, "every NVIDIA card"),
+            (None, " the driver still covers."),
           ],
           vec![
-            (None, "It provides "),
-            (HIGHLIGHT, "professional audio capabilities"),
-            (None, " with "),
-            (HIGHLIGHT, "minimal latency"),
-            (None, " and supports "),
-            (HIGHLIGHT, "advanced audio routing"),
-            (None, " and "),
-            (HIGHLIGHT, "real-time processing"),
-            (None, "."),
+            (None, "Pick this for cards "),
+            (HIGHLIGHT, "older than Turing"),
+            (
+              None,
+              " (GTX 16xx / RTX 20xx). Needs unfree packages to be allowed.",
+            ),
+          ],
+        ]),
+      ),
+      1 => InfoBox::new(
+        "NVIDIA (open)",
+        styled_block(vec![
+          vec![
+            (None, "NVIDIA's "),
+            (HIGHLIGHT, "open-source kernel module"),
+            (None, " with the same proprietary userspace driver."),
           ],
           vec![
-            (None, "PipeWire offers "),
-            (HIGHLIGHT, "excellent compatibility"),
-            (None, " with existing applications and is the "),
-            (HIGHLIGHT, "recommended choice"),
-            (None, " for modern Linux systems."),
+            (None, "Only works on "),
+            (HIGHLIGHT, "Turing and newer"),
+            (
+              None,
+              " cards (GTX 16xx / RTX 20xx and up), where NVIDIA recommends it.",
+            ),
           ],
         ]),
       ),
-      1 => InfoBox::new(
-        "PulseAudio",
+      2 => InfoBox::new(
+        "AMD",
         styled_block(vec![
           vec![
-            (HIGHLIGHT, "PulseAudio"),
-            (None, " is a "),
-            (HIGHLIGHT, "mature and stable audio server"),
+            (None, "The "),
+            (HIGHLIGHT, "amdgpu"),
             (
               None,
-              " that has been the standard audio system on Linux for many years.",
+              " driver, open-source and part of the kernel and Mesa.",
             ),
           ],
+          vec![(
+            None,
+            "Covers Radeon cards from GCN onwards as well as AMD integrated graphics.",
+          )],
+        ]),
+      ),
+      3 => InfoBox::new(
+        "Intel",
+        styled_block(vec![
           vec![
-            (None, "It provides "),
-            (HIGHLIGHT, "network audio streaming"),
-            (None, ", "),
-            (HIGHLIGHT, "device management"),
-            (None, ", and "),
-            (HIGHLIGHT, "per-application volume control"),
-            (None, " with good application compatibility."),
+            (None, "Intel graphics through the kernel's "),
+            (HIGHLIGHT, "modesetting"),
+            (None, " driver and Mesa."),
           ],
+          vec![(None, "Covers integrated graphics and Arc cards.")],
+        ]),
+      ),
+      4 => InfoBox::new(
+        "None/VM",
+        styled_block(vec![
           vec![
-            (None, "PulseAudio is "),
-            (HIGHLIGHT, "well-tested and reliable"),
-            (None, " but may have "),
-            (HIGHLIGHT, "higher latency"),
-            (None, " compared to newer solutions like PipeWire."),
+            (None, "Leave the choice to NixOS, which "),
+            (HIGHLIGHT, "works out of the box"),
+            (None, " in virtual machines and on most hardware."),
           ],
+          vec![(None, "No driver options are written to the config.")],
         ]),
       ),
       _ => InfoBox::new(
-        "Unknown Audio Backend",
+        "Unknown GPU Driver",
         styled_block(vec![vec![(
           None,
-          "No information available for this audio backend.",
+          "No information available for this GPU driver.",
         )]]),
       ),
     }
   }
   pub fn display_widget(installer: &mut Installer) -> Option<Box<dyn ConfigWidget>> {
-    installer.audio_backend.clone().map(|s| {
+    installer.gpu_driver.clone().map(|s| {
       let ib = InfoBox::new(
         "",
         styled_block(vec![
-          vec![(None, "Current audio backend set to:")],
+          vec![(None, "Current GPU driver set to:")],
           vec![(HIGHLIGHT, &s)],
         ]),
       );
@@ -3569,32 +8486,25 @@ impl Audio {
   }
   pub fn page_info<'a>() -> (String, Vec<Line<'a>>) {
     (
-      "Audio".to_string(),
+      "Graphics".to_string(),
       styled_block(vec![
+        vec![(None, "Select the driver for your graphics card.")],
         vec![(
           None,
-          "Select the audio management backend to be installed on your system.",
-        )],
-        vec![(
-          None,
-          "The audio backend is responsible for managing sound devices and providing audio services to applications.",
-        )],
-        vec![(
-          None,
-          "Choosing an audio backend can help ensure that your system is able to handle audio playback and recording effectively.",
+          "NVIDIA cards need their driver picked here, AMD and Intel mostly work without it.",
         )],
       ]),
     )
   }
 }
 
-impl Default for Audio {
+impl Default for Graphics {
   fn default() -> Self {
-    Self::new()
+    Self::new(None)
   }
 }
 
-impl Page for Audio {
+impl Page for Graphics {
   fn render(&mut self, _installer: &mut Installer, f: &mut Frame, area: Rect) {
     let vert_chunks = Layout::default()
       .direction(Direction::Vertical)
@@ -3604,18 +8514,15 @@ impl Page for Audio {
       vert_chunks[0],
       1,
       [
-        Constraint::Percentage(40),
-        Constraint::Percentage(20),
-        Constraint::Percentage(40),
+        Constraint::Percentage(35),
+        Constraint::Percentage(30),
+        Constraint::Percentage(35),
       ]
     );
 
-    let idx = self.backends.selected_idx;
-    let info_box = Self::get_audio_info(idx);
-    self.backends.render(f, hor_chunks[1]);
-    if idx < 2 {
-      info_box.render(f, vert_chunks[1]);
-    }
+    let info_box = Self::get_driver_info(self.drivers.selected_idx);
+    self.drivers.render(f, hor_chunks[1]);
+    info_box.render(f, vert_chunks[1]);
 
     self.help_modal.render(f, area);
   }
@@ -3623,15 +8530,21 @@ impl Page for Audio {
   fn get_help_content(&self) -> (String, Vec<Line<'_>>) {
     let help_content = styled_block(vec![
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "↑/↓, j/k"),
-        (None, " - Navigate audio backend options"),
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Navigate GPU driver options"),
       ],
       vec![
         (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
-        (None, " - Select audio backend and return"),
+        (None, " - Select GPU driver and return"),
       ],
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "Esc, q, ←, h"),
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().back_label,
+        ),
         (None, " - Cancel and return to menu"),
       ],
       vec![
@@ -3641,10 +8554,10 @@ impl Page for Audio {
       vec![(None, "")],
       vec![(
         None,
-        "Select the audio management backend for sound devices.",
+        "Select the driver for the graphics card the system will run on.",
       )],
     ]);
-    ("Audio".to_string(), help_content)
+    ("Graphics".to_string(), help_content)
   }
 
   fn handle_input(&mut self, installer: &mut Installer, event: KeyEvent) -> Signal {
@@ -3653,29 +8566,36 @@ impl Page for Audio {
         self.help_modal.toggle();
         Signal::Wait
       }
-      ui_close!() if self.help_modal.visible => {
+      code if ui_close!(code) && self.help_modal.visible => {
         self.help_modal.hide();
         Signal::Wait
       }
       _ if self.help_modal.visible => Signal::Wait,
-      ui_back!() => Signal::Pop,
+      code if ui_back!(code) => Signal::Pop,
       KeyCode::Enter => {
-        installer.audio_backend = Some(self.backends.items[self.backends.selected_idx].clone());
+        installer.gpu_driver = self.drivers.selected_item().cloned();
+        if installer
+          .gpu_driver
+          .as_deref()
+          .is_some_and(is_unfree_gpu_driver)
+        {
+          installer.allow_unfree = true;
+        }
         Signal::Pop
       }
-      ui_up!() => {
-        if !self.backends.previous_item() {
-          self.backends.last_item();
+      code if ui_up!(code) => {
+        if !self.drivers.previous_item() {
+          self.drivers.last_item();
         }
         Signal::Wait
       }
-      ui_down!() => {
-        if !self.backends.next_item() {
-          self.backends.first_item();
+      code if ui_down!(code) => {
+        if !self.drivers.next_item() {
+          self.drivers.first_item();
         }
         Signal::Wait
       }
-      _ => self.backends.handle_input(event),
+      _ => self.drivers.handle_input(event),
     }
   }
 }
@@ -3700,7 +8620,10 @@ impl Network {
     backends.focus();
     let help_content = styled_block(vec![
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "↑/↓, j/k"),
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
         (None, " - Navigate network backend options"),
       ],
       vec![
@@ -3708,7 +8631,10 @@ impl Network {
         (None, " - Select network backend and return"),
       ],
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "Esc, q, ←, h"),
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().back_label,
+        ),
         (None, " - Cancel and return to menu"),
       ],
       vec![
@@ -3868,45 +8794,468 @@ impl Default for Network {
   }
 }
 
-impl Page for Network {
+impl Page for Network {
+  fn render(&mut self, _installer: &mut Installer, f: &mut Frame, area: Rect) {
+    let vert_chunks = Layout::default()
+      .direction(Direction::Vertical)
+      .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
+      .split(area);
+    let hor_chunks = split_hor!(
+      vert_chunks[0],
+      1,
+      [
+        Constraint::Percentage(40),
+        Constraint::Percentage(20),
+        Constraint::Percentage(40),
+      ]
+    );
+
+    let idx = self.backends.selected_idx;
+    let info_box = Self::get_network_info(idx);
+    self.backends.render(f, hor_chunks[1]);
+    if idx < 3 {
+      info_box.render(f, vert_chunks[1]);
+    }
+
+    self.help_modal.render(f, area);
+  }
+
+  fn get_help_content(&self) -> (String, Vec<Line<'_>>) {
+    let help_content = styled_block(vec![
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Navigate network backend options"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
+        (None, " - Select network backend and return"),
+      ],
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().back_label,
+        ),
+        (None, " - Cancel and return to menu"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "?"),
+        (None, " - Show this help"),
+      ],
+      vec![(None, "")],
+      vec![(
+        None,
+        "Select the network management backend for connections.",
+      )],
+    ]);
+    ("Network".to_string(), help_content)
+  }
+
+  fn handle_input(&mut self, installer: &mut Installer, event: KeyEvent) -> Signal {
+    match event.code {
+      KeyCode::Char('?') => {
+        self.help_modal.toggle();
+        Signal::Wait
+      }
+      code if ui_close!(code) && self.help_modal.visible => {
+        self.help_modal.hide();
+        Signal::Wait
+      }
+      _ if self.help_modal.visible => Signal::Wait,
+      code if ui_back!(code) => Signal::Pop,
+      KeyCode::Enter => {
+        installer.network_backend = Some(self.backends.items[self.backends.selected_idx].clone());
+        Signal::Pop
+      }
+      code if ui_up!(code) => {
+        if !self.backends.previous_item() {
+          self.backends.last_item();
+        }
+        Signal::Wait
+      }
+      code if ui_down!(code) => {
+        if !self.backends.next_item() {
+          self.backends.first_item();
+        }
+        Signal::Wait
+      }
+      _ => self.backends.handle_input(event),
+    }
+  }
+}
+
+/// Offered when the system's tzdata can't be listed
+const FALLBACK_TIMEZONES: &[&str] = &[
+  "UTC",
+  "America/New_York",
+  "America/Los_Angeles",
+  "America/Chicago",
+  "America/Denver",
+  "Europe/London",
+  "Europe/Berlin",
+  "Europe/Paris",
+  "Europe/Moscow",
+  "Asia/Tokyo",
+  "Asia/Shanghai",
+  "Asia/Kolkata",
+  "Asia/Dubai",
+  "Australia/Sydney",
+  "Australia/Melbourne",
+];
+
+pub struct Timezone {
+  timezones: StrList,
+  search_bar: LineEditor,
+  /// Shown when `i` can't read the running system's setting
+  message: MessageModal,
+  help_modal: HelpModal<'static>,
+}
+
+impl Timezone {
+  pub fn new(current: Option<&str>) -> Self {
+    let mut timezones = livesystem::list_timezones();
+    if timezones.is_empty() {
+      timezones = FALLBACK_TIMEZONES.iter().map(|s| s.to_string()).collect();
+    }
+    let mut timezones = StrList::new("Select Timezone", timezones);
+    timezones.commit_value(current);
+    timezones.focus();
+    let search_bar = LineEditor::new("Search (/)", Some("Filter timezones..."));
+    let help_content = styled_block(vec![
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Navigate timezone options"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
+        (None, " - Select timezone and return"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "/"),
+        (None, " - Search timezones, Esc clears the search"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "i"),
+        (None, " - Use the running system's setting"),
+      ],
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().back_label,
+        ),
+        (None, " - Cancel and return to menu"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "?"),
+        (None, " - Show this help"),
+      ],
+      vec![(None, "")],
+      vec![(
+        None,
+        "Select the timezone that matches your physical location.",
+      )],
+    ]);
+    let help_modal = HelpModal::new("Timezone", help_content);
+    Self {
+      timezones,
+      search_bar,
+      message: MessageModal::new(),
+      help_modal,
+    }
+  }
+  pub fn display_widget(installer: &mut Installer) -> Option<Box<dyn ConfigWidget>> {
+    installer.timezone.clone().map(|s| {
+      let ib = InfoBox::new(
+        "",
+        styled_block(vec![
+          vec![(None, "Current timezone set to:")],
+          vec![(HIGHLIGHT, &s)],
+        ]),
+      );
+      Box::new(ib) as Box<dyn ConfigWidget>
+    })
+  }
+  pub fn page_info<'a>() -> (String, Vec<Line<'a>>) {
+    (
+      "Timezone".to_string(),
+      styled_block(vec![
+        vec![(None, "Select the timezone for your system.")],
+        vec![(
+          None,
+          "The timezone setting determines the local time displayed on your system and is important for scheduling tasks and logging events.",
+        )],
+        vec![(
+          None,
+          "Choose a timezone that matches your physical location or the location where the system will primarily be used.",
+        )],
+      ]),
+    )
+  }
+}
+
+impl Default for Timezone {
+  fn default() -> Self {
+    Self::new(None)
+  }
+}
+
+impl Page for Timezone {
+  fn render(&mut self, _installer: &mut Installer, f: &mut Frame, area: Rect) {
+    let chunks = split_vert!(area, 1, [Constraint::Length(3), Constraint::Min(0)]);
+    self.search_bar.render(f, chunks[0]);
+    self.timezones.render(f, chunks[1]);
+    self.message.render(f, area);
+    self.help_modal.render(f, area);
+  }
+
+  fn get_help_content(&self) -> (String, Vec<Line<'_>>) {
+    let help_content = styled_block(vec![
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Navigate timezone options"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
+        (None, " - Select timezone and return"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "/"),
+        (None, " - Search timezones, Esc clears the search"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "i"),
+        (None, " - Use the running system's setting"),
+      ],
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().back_label,
+        ),
+        (None, " - Cancel and return to menu"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "?"),
+        (None, " - Show this help"),
+      ],
+      vec![(None, "")],
+      vec![(
+        None,
+        "Select the timezone that matches your physical location.",
+      )],
+    ]);
+    ("Timezone".to_string(), help_content)
+  }
+
+  fn handle_paste(&mut self, _installer: &mut Installer, text: &str) -> Signal {
+    if self.help_modal.visible || self.message.visible {
+      return Signal::Wait;
+    }
+    // Pasting into the list starts a search for it
+    self.search_bar.focus();
+    self.search_bar.paste(text);
+    self.timezones.set_filter(search_filter(&self.search_bar));
+    Signal::Wait
+  }
+
+  fn handle_input(&mut self, installer: &mut Installer, event: KeyEvent) -> Signal {
+    if self.message.visible {
+      self.message.handle_input(event);
+      return Signal::Wait;
+    }
+    match event.code {
+      KeyCode::Char('?') => {
+        self.help_modal.toggle();
+        Signal::Wait
+      }
+      code if ui_close!(code) && self.help_modal.visible => {
+        self.help_modal.hide();
+        Signal::Wait
+      }
+      _ if self.help_modal.visible => Signal::Wait,
+      KeyCode::Esc if self.search_bar.is_focused() => {
+        self.search_bar.clear();
+        self.search_bar.unfocus();
+        self.timezones.set_filter(None::<String>);
+        Signal::Wait
+      }
+      KeyCode::Up | KeyCode::Down if self.search_bar.is_focused() => {
+        if event.code == KeyCode::Up {
+          self.timezones.previous_item();
+        } else {
+          self.timezones.next_item();
+        }
+        Signal::Wait
+      }
+      _ if self.search_bar.is_focused() && event.code != KeyCode::Enter => {
+        let signal = self.search_bar.handle_input(event);
+        self.timezones.set_filter(search_filter(&self.search_bar));
+        signal
+      }
+      KeyCode::Char('/') => {
+        self.search_bar.focus();
+        Signal::Wait
+      }
+      code if ui_back!(code) => Signal::Pop,
+      KeyCode::Enter => {
+        let Some(timezone) = self.timezones.selected_item() else {
+          return Signal::Wait;
+        };
+        installer.timezone = Some(timezone.clone());
+        Signal::Pop
+      }
+      KeyCode::Char('i') => match livesystem::current_timezone() {
+        Ok(timezone) => {
+          installer.timezone = Some(timezone);
+          Signal::Pop
+        }
+        Err(e) => {
+          self.message.show(
+            "Couldn't Detect",
+            format!("Couldn't read the running system's timezone: {e}"),
+            Color::Yellow,
+          );
+          Signal::Wait
+        }
+      },
+      code if ui_up!(code) => {
+        if !self.timezones.previous_item() {
+          self.timezones.last_item();
+        }
+        Signal::Wait
+      }
+      code if ui_down!(code) => {
+        if !self.timezones.next_item() {
+          self.timezones.first_item();
+        }
+        Signal::Wait
+      }
+      _ => self.timezones.handle_input(event),
+    }
+  }
+}
+
+/// Shown between "Done" and the config preview when optional but
+/// recommended settings are missing
+pub struct SoftWarnings {
+  warnings: Vec<String>,
+  buttons: WidgetBox,
+  help_modal: HelpModal<'static>,
+  /// Set once the preview has been pushed, so we close when it's popped
+  continued: bool,
+}
+
+impl SoftWarnings {
+  pub fn new(warnings: Vec<String>) -> Self {
+    let mut buttons = WidgetBox::button_menu(vec![
+      Box::new(Button::new("Continue anyway")) as Box<dyn ConfigWidget>,
+      Box::new(Button::new("Back to menu")),
+    ]);
+    buttons.focus();
+    let help_content = styled_block(vec![
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Navigate options"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
+        (None, " - Select option"),
+      ],
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().back_label,
+        ),
+        (None, " - Return to menu"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "?"),
+        (None, " - Show this help"),
+      ],
+      vec![(None, "")],
+      vec![(
+        None,
+        "None of these block the install, but they are easy to overlook.",
+      )],
+    ]);
+    let help_modal = HelpModal::new("Before You Continue", help_content);
+    Self {
+      warnings,
+      buttons,
+      help_modal,
+      continued: false,
+    }
+  }
+}
+
+impl Page for SoftWarnings {
   fn render(&mut self, _installer: &mut Installer, f: &mut Frame, area: Rect) {
-    let vert_chunks = Layout::default()
-      .direction(Direction::Vertical)
-      .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
-      .split(area);
+    let chunks = split_vert!(
+      area,
+      1,
+      [Constraint::Percentage(60), Constraint::Percentage(40)]
+    );
     let hor_chunks = split_hor!(
-      vert_chunks[0],
+      chunks[1],
       1,
       [
+        Constraint::Percentage(30),
         Constraint::Percentage(40),
-        Constraint::Percentage(20),
-        Constraint::Percentage(40),
+        Constraint::Percentage(30),
       ]
     );
-
-    let idx = self.backends.selected_idx;
-    let info_box = Self::get_network_info(idx);
-    self.backends.render(f, hor_chunks[1]);
-    if idx < 3 {
-      info_box.render(f, vert_chunks[1]);
+    let mut lines = vec![
+      vec![(
+        None,
+        "The following recommended settings have not been configured:".to_string(),
+      )],
+      vec![(None, String::new())],
+    ];
+    for warning in &self.warnings {
+      lines.push(vec![
+        (None, " - ".to_string()),
+        (Some((Color::Yellow, Modifier::BOLD)), warning.clone()),
+      ]);
     }
-
+    let info_box = InfoBox::new("Before You Continue", styled_block(lines));
+    info_box.render(f, chunks[0]);
+    self.buttons.render(f, hor_chunks[1]);
     self.help_modal.render(f, area);
   }
 
+  fn signal(&self) -> Option<Signal> {
+    self.continued.then_some(Signal::Pop)
+  }
+
   fn get_help_content(&self) -> (String, Vec<Line<'_>>) {
     let help_content = styled_block(vec![
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "↑/↓, j/k"),
-        (None, " - Navigate network backend options"),
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Navigate options"),
       ],
       vec![
         (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
-        (None, " - Select network backend and return"),
+        (None, " - Select option"),
       ],
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "Esc, q, ←, h"),
-        (None, " - Cancel and return to menu"),
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().back_label,
+        ),
+        (None, " - Return to menu"),
       ],
       vec![
         (Some((Color::Yellow, Modifier::BOLD)), "?"),
@@ -3915,10 +9264,10 @@ impl Page for Network {
       vec![(None, "")],
       vec![(
         None,
-        "Select the network management backend for connections.",
+        "None of these block the install, but they are easy to overlook.",
       )],
     ]);
-    ("Network".to_string(), help_content)
+    ("Before You Continue".to_string(), help_content)
   }
 
   fn handle_input(&mut self, installer: &mut Installer, event: KeyEvent) -> Signal {
@@ -3927,74 +9276,84 @@ impl Page for Network {
         self.help_modal.toggle();
         Signal::Wait
       }
-      ui_close!() if self.help_modal.visible => {
+      code if ui_close!(code) && self.help_modal.visible => {
         self.help_modal.hide();
         Signal::Wait
       }
       _ if self.help_modal.visible => Signal::Wait,
-      ui_back!() => Signal::Pop,
-      KeyCode::Enter => {
-        installer.network_backend = Some(self.backends.items[self.backends.selected_idx].clone());
-        Signal::Pop
-      }
-      ui_up!() => {
-        if !self.backends.previous_item() {
-          self.backends.last_item();
-        }
+      code if ui_back!(code) => Signal::Pop,
+      code if ui_up!(code) => {
+        self.buttons.prev_child();
         Signal::Wait
       }
-      ui_down!() => {
-        if !self.backends.next_item() {
-          self.backends.first_item();
-        }
+      code if ui_down!(code) => {
+        self.buttons.next_child();
         Signal::Wait
       }
-      _ => self.backends.handle_input(event),
+      KeyCode::Enter => match self.buttons.selected_child() {
+        Some(0) => {
+          self.continued = true;
+          Signal::Push(Box::new(SettingsSummary::new(installer)))
+        }
+        Some(1) => Signal::Pop,
+        _ => Signal::Wait,
+      },
+      _ => Signal::Wait,
     }
   }
 }
 
-pub struct Timezone {
-  timezones: StrList,
+/// Plain language list of everything that was chosen, shown before the raw
+/// config preview
+pub struct SettingsSummary {
+  table: TableWidget,
+  buttons: WidgetBox,
   help_modal: HelpModal<'static>,
 }
 
-impl Timezone {
-  pub fn new() -> Self {
-    let timezones = vec![
-      "UTC",
-      "America/New_York",
-      "America/Los_Angeles",
-      "America/Chicago",
-      "America/Denver",
-      "Europe/London",
-      "Europe/Berlin",
-      "Europe/Paris",
-      "Europe/Moscow",
-      "Asia/Tokyo",
-      "Asia/Shanghai",
-      "Asia/Kolkata",
-      "Asia/Dubai",
-      "Australia/Sydney",
-      "Australia/Melbourne",
-    ]
-    .iter()
-    .map(|s| s.to_string())
-    .collect::<Vec<_>>();
-    let mut timezones = StrList::new("Select Timezone", timezones);
-    timezones.focus();
+impl SettingsSummary {
+  pub fn new(installer: &Installer) -> Self {
+    let rows = installer
+      .summary()
+      .into_iter()
+      .map(|(label, value)| vec![label, value])
+      .collect();
+    let mut table = TableWidget::new(
+      "Your Choices",
+      vec![Constraint::Percentage(25), Constraint::Percentage(75)],
+      vec!["Setting".to_string(), "Value".to_string()],
+      rows,
+    );
+    table.focus();
+    table.first_row();
+    let buttons = WidgetBoxBuilder::new()
+      .children(vec![
+        Box::new(Button::new("Review Config")) as Box<dyn ConfigWidget>,
+        Box::new(Button::new("Back")),
+      ])
+      .build();
     let help_content = styled_block(vec![
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "↑/↓, j/k"),
-        (None, " - Navigate timezone options"),
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Scroll through settings"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Tab"),
+        (None, " - Switch between the list and the buttons"),
       ],
       vec![
         (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
-        (None, " - Select timezone and return"),
+        (None, " - Continue to the generated config"),
       ],
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "Esc, q, ←, h"),
-        (None, " - Cancel and return to menu"),
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().back_label,
+        ),
+        (None, " - Return to menu"),
       ],
       vec![
         (Some((Color::Yellow, Modifier::BOLD)), "?"),
@@ -4003,71 +9362,68 @@ impl Timezone {
       vec![(None, "")],
       vec![(
         None,
-        "Select the timezone that matches your physical location.",
+        "Check your choices here before reading the Nix code they turn into.",
       )],
     ]);
-    let help_modal = HelpModal::new("Timezone", help_content);
+    let help_modal = HelpModal::new("Summary", help_content);
     Self {
-      timezones,
+      table,
+      buttons,
       help_modal,
     }
   }
-  pub fn display_widget(installer: &mut Installer) -> Option<Box<dyn ConfigWidget>> {
-    installer.timezone.clone().map(|s| {
-      let ib = InfoBox::new(
-        "",
-        styled_block(vec![
-          vec![(None, "Current timezone set to:")],
-          vec![(HIGHLIGHT, &s)],
-        ]),
-      );
-      Box::new(ib) as Box<dyn ConfigWidget>
-    })
-  }
-  pub fn page_info<'a>() -> (String, Vec<Line<'a>>) {
-    (
-      "Timezone".to_string(),
-      styled_block(vec![
-        vec![(None, "Select the timezone for your system.")],
-        vec![(
-          None,
-          "The timezone setting determines the local time displayed on your system and is important for scheduling tasks and logging events.",
-        )],
-        vec![(
-          None,
-          "Choose a timezone that matches your physical location or the location where the system will primarily be used.",
-        )],
-      ]),
-    )
-  }
-}
 
-impl Default for Timezone {
-  fn default() -> Self {
-    Self::new()
+  fn continue_to_preview(installer: &mut Installer) -> Signal {
+    match ConfigPreview::new(installer) {
+      Ok(preview) => Signal::Push(Box::new(preview)),
+      Err(e) => Signal::Error(anyhow::anyhow!(
+        "Failed to generate configuration preview: {}",
+        e
+      )),
+    }
   }
 }
 
-impl Page for Timezone {
+impl Page for SettingsSummary {
   fn render(&mut self, _installer: &mut Installer, f: &mut Frame, area: Rect) {
-    let chunks = split_vert!(area, 1, [Constraint::Percentage(100)]);
-    self.timezones.render(f, chunks[0]);
+    let chunks = split_vert!(area, 1, [Constraint::Min(0), Constraint::Length(3)]);
+    let hor_chunks = split_hor!(
+      chunks[1],
+      1,
+      [
+        Constraint::Percentage(30),
+        Constraint::Percentage(40),
+        Constraint::Percentage(30),
+      ]
+    );
+    self.table.render(f, chunks[0]);
+    self.buttons.render(f, hor_chunks[1]);
     self.help_modal.render(f, area);
   }
 
   fn get_help_content(&self) -> (String, Vec<Line<'_>>) {
     let help_content = styled_block(vec![
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "↑/↓, j/k"),
-        (None, " - Navigate timezone options"),
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Scroll through settings"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Tab"),
+        (None, " - Switch between the list and the buttons"),
       ],
       vec![
         (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
-        (None, " - Select timezone and return"),
+        (None, " - Continue to the generated config"),
       ],
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "Esc, q, ←, h"),
-        (None, " - Cancel and return to menu"),
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().back_label,
+        ),
+        (None, " - Return to menu"),
       ],
       vec![
         (Some((Color::Yellow, Modifier::BOLD)), "?"),
@@ -4076,10 +9432,10 @@ impl Page for Timezone {
       vec![(None, "")],
       vec![(
         None,
-        "Select the timezone that matches your physical location.",
+        "Check your choices here before reading the Nix code they turn into.",
       )],
     ]);
-    ("Timezone".to_string(), help_content)
+    ("Summary".to_string(), help_content)
   }
 
   fn handle_input(&mut self, installer: &mut Installer, event: KeyEvent) -> Signal {
@@ -4088,29 +9444,46 @@ impl Page for Timezone {
         self.help_modal.toggle();
         Signal::Wait
       }
-      ui_close!() if self.help_modal.visible => {
+      code if ui_close!(code) && self.help_modal.visible => {
         self.help_modal.hide();
         Signal::Wait
       }
       _ if self.help_modal.visible => Signal::Wait,
-      ui_back!() => Signal::Pop,
-      KeyCode::Enter => {
-        installer.timezone = Some(self.timezones.items[self.timezones.selected_idx].clone());
-        Signal::Pop
-      }
-      ui_up!() => {
-        if !self.timezones.previous_item() {
-          self.timezones.last_item();
+      KeyCode::Tab => {
+        if self.table.is_focused() {
+          self.table.unfocus();
+          self.buttons.focus();
+        } else {
+          self.buttons.unfocus();
+          self.table.focus();
         }
         Signal::Wait
       }
-      ui_down!() => {
-        if !self.timezones.next_item() {
-          self.timezones.first_item();
-        }
+      code if self.buttons.is_focused() && ui_left!(code) => {
+        self.buttons.prev_child();
         Signal::Wait
       }
-      _ => self.timezones.handle_input(event),
+      code if self.buttons.is_focused() && ui_right!(code) => {
+        self.buttons.next_child();
+        Signal::Wait
+      }
+      code if ui_back!(code) => Signal::Pop,
+      code if ui_up!(code) => {
+        self.table.previous_row();
+        Signal::Wait
+      }
+      code if ui_down!(code) => {
+        self.table.next_row();
+        Signal::Wait
+      }
+      KeyCode::Enter => {
+        if self.buttons.is_focused() && self.buttons.selected_child() == Some(1) {
+          Signal::Pop
+        } else {
+          Self::continue_to_preview(installer)
+        }
+      }
+      _ => Signal::Wait,
     }
   }
 }
@@ -4118,50 +9491,268 @@ impl Page for Timezone {
 pub struct ConfigPreview {
   system_config: String,
   disko_config: String,
+  /// Generated flake.nix, only when flakes are enabled
+  flake_config: Option<String>,
+  /// Install steps and the shell commands each one runs
+  commands: Vec<(&'static str, Vec<String>)>,
+  disko_command: String,
+  /// Why unfree packages got allowed, if something picked needs them
+  unfree_note: Option<String>,
   _flake_path: Option<String>,
   scroll_position: usize,
   button_row: WidgetBox,
   current_view: ConfigView,
   help_modal: HelpModal<'static>,
   visible_lines: usize,
+  /// Architecture being verified and the outcome once it's known
+  verify: Option<(String, VerifyResult)>,
+  /// Why the generated system config doesn't parse, if it doesn't
+  parse_error: Option<NixParseError>,
+  /// Kept alive so X11 keeps serving what was copied
+  clipboard: Option<arboard::Clipboard>,
+  /// Outcome of the last `y`, and when it stops being shown
+  copy_note: Option<(Line<'static>, Option<Instant>)>,
 }
 
+/// How long "Copied!" stays up
+const COPY_NOTE_TIMEOUT: Duration = Duration::from_secs(3);
+
+type VerifyResult = Arc<Mutex<Option<Result<(), String>>>>;
+
 #[derive(Clone, Copy, PartialEq)]
 enum ConfigView {
   System,
   Disko,
+  Flake,
+  Commands,
+}
+
+impl ConfigView {
+  fn next(self) -> Self {
+    match self {
+      ConfigView::System => ConfigView::Disko,
+      ConfigView::Disko => ConfigView::Flake,
+      ConfigView::Flake => ConfigView::Commands,
+      ConfigView::Commands => ConfigView::System,
+    }
+  }
+
+  fn prev(self) -> Self {
+    match self {
+      ConfigView::System => ConfigView::Commands,
+      ConfigView::Disko => ConfigView::System,
+      ConfigView::Flake => ConfigView::Disko,
+      ConfigView::Commands => ConfigView::Flake,
+    }
+  }
 }
 
+/// Shown in the flake tab when no flake.nix is generated
+const NO_FLAKE: &str = "# No flake.nix is generated, enable flakes to get one";
+
 impl ConfigPreview {
   /// Maximum scroll distance for config preview window
   fn get_max_scroll(&self, visible_lines: usize) -> usize {
-    let config_content = match self.current_view {
-      ConfigView::System => &self.system_config,
-      ConfigView::Disko => &self.disko_config,
+    let lines = match self.current_view {
+      ConfigView::System => self.system_config.lines().count(),
+      ConfigView::Disko => self.disko_config.lines().count(),
+      ConfigView::Flake => self.flake_text().lines().count(),
+      ConfigView::Commands => self.command_lines().len(),
     };
-    let lines = config_content.lines().count();
     lines.saturating_sub(visible_lines)
   }
 
+  fn flake_text(&self) -> &str {
+    self.flake_config.as_deref().unwrap_or(NO_FLAKE)
+  }
+
+  fn command_lines(&self) -> Vec<Line<'static>> {
+    let mut lines = vec![];
+    for (title, cmds) in &self.commands {
+      lines.push(Line::styled(
+        title.to_string(),
+        Style::default()
+          .fg(Color::Yellow)
+          .add_modifier(Modifier::BOLD),
+      ));
+      for cmd in cmds {
+        lines.push(Line::from(format!("  $ {cmd}")));
+      }
+      lines.push(Line::from(""));
+    }
+    lines
+  }
+
+  /// Files the Export button writes, named as they are in /etc/nixos
+  fn export_files(&self, installer: &Installer) -> Vec<(&'static str, String)> {
+    let mut files = vec![
+      ("configuration.nix", self.system_config.clone()),
+      ("disko-config.nix", self.disko_config.clone()),
+    ];
+    if let Some(ref flake) = self.flake_config {
+      files.push(("flake.nix", flake.clone()));
+    }
+    if installer.overlays_scaffold {
+      files.push(("overlays/default.nix", OVERLAYS_DEFAULT_NIX.to_string()));
+    }
+    files
+  }
+
+  /// The text of the current tab, and where it goes when there's no clipboard
+  fn current_text(&self) -> Option<(String, &'static str)> {
+    match self.current_view {
+      ConfigView::System => Some((self.system_config.clone(), "/tmp/nixos-wizard-config.nix")),
+      ConfigView::Disko => Some((self.disko_config.clone(), "/tmp/nixos-wizard-disko.nix")),
+      ConfigView::Flake => self
+        .flake_config
+        .clone()
+        .map(|flake| (flake, "/tmp/nixos-wizard-flake.nix")),
+      ConfigView::Commands => {
+        let script = self
+          .commands
+          .iter()
+          .flat_map(|(_, cmds)| cmds.iter().map(String::as_str))
+          .collect::<Vec<_>>()
+          .join("\n");
+        Some((script, "/tmp/nixos-wizard-commands.sh"))
+      }
+    }
+  }
+
+  /// Copy the current tab to the clipboard, or to a file on a bare TTY
+  fn copy_current(&mut self) {
+    let Some((text, fallback)) = self.current_text() else {
+      self.copy_note = Some((
+        Line::styled("No flake.nix to copy", Style::default().fg(Color::Yellow)),
+        Some(Instant::now() + COPY_NOTE_TIMEOUT),
+      ));
+      return;
+    };
+    if self.clipboard.is_none() {
+      self.clipboard = arboard::Clipboard::new()
+        .map_err(|e| log::info!("No clipboard available: {e}"))
+        .ok();
+    }
+    let copied = self.clipboard.as_mut().is_some_and(|clipboard| {
+      clipboard
+        .set_text(text.clone())
+        .map_err(|e| log::warn!("Copying to the clipboard failed: {e}"))
+        .is_ok()
+    });
+    self.copy_note = Some(if copied {
+      (
+        Line::styled("Copied!", Style::default().fg(Color::Green)),
+        Some(Instant::now() + COPY_NOTE_TIMEOUT),
+      )
+    } else {
+      match write_private(fallback, &text) {
+        Ok(()) => (
+          Line::styled(
+            format!("No clipboard available, wrote {fallback} instead"),
+            Style::default().fg(Color::Yellow),
+          ),
+          None,
+        ),
+        Err(e) => (
+          Line::styled(
+            format!("No clipboard available and writing {fallback} failed: {e}"),
+            Style::default().fg(Color::Red),
+          ),
+          None,
+        ),
+      }
+    });
+  }
+
+  /// The copy note while it's fresh, otherwise the verify outcome
+  fn status_line(&mut self) -> Line<'static> {
+    if let Some((_, Some(until))) = self.copy_note
+      && Instant::now() >= until
+    {
+      self.copy_note = None;
+    }
+    match self.copy_note {
+      Some((ref line, _)) => line.clone(),
+      None => self.verify_line(),
+    }
+  }
+
+  fn start_verify(&mut self, installer: &Installer) {
+    let running = self
+      .verify
+      .as_ref()
+      .is_some_and(|(_, result)| result.lock().is_ok_and(|r| r.is_none()));
+    if running {
+      return;
+    }
+    let Some(staged) = installer.staged_configs.clone() else {
+      return;
+    };
+    let arch = installer.architecture();
+    let result: VerifyResult = Arc::new(Mutex::new(None));
+    self.verify = Some((arch.clone(), result.clone()));
+    thread::spawn(move || {
+      let outcome = evaluate_for(&staged, &arch).map_err(|e| e.to_string());
+      if let Ok(mut guard) = result.lock() {
+        *guard = Some(outcome);
+      }
+    });
+  }
+
+  fn verify_line(&self) -> Line<'static> {
+    let Some((arch, result)) = &self.verify else {
+      return Line::from("");
+    };
+    match result.lock().ok().and_then(|r| r.clone()) {
+      None => Line::from(format!("Evaluating the config for {arch}...")),
+      Some(Ok(())) => Line::styled(
+        format!("The config evaluates for {arch}"),
+        Style::default().fg(Color::Green),
+      ),
+      Some(Err(e)) => Line::styled(
+        format!("{arch}: {e}"),
+        Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+      ),
+    }
+  }
+
   pub fn new(installer: &mut Installer) -> anyhow::Result<Self> {
     // Generate the configuration like the main app does
     let config_json = installer.to_json()?;
     let serializer = crate::nixgen::NixWriter::new(config_json);
 
     let configs = serializer.write_configs()?;
+    // Written now rather than at install time, so the paths shown are real
+    let staged = StagedConfigs::new(installer, &configs)?;
+    let commands = InstallProgress::install_script(installer, &staged)?;
+    let disko_command =
+      InstallProgress::disko_command(&staged.disko_path()?, installer.keeps_partitions());
+    installer.staged_configs = Some(Arc::new(staged));
+    let parse_error = check_nix_syntax(&configs.system);
+    let unfree = installer.unfree_selections();
+    let unfree_note = (installer.allow_unfree && !unfree.is_empty())
+      .then(|| format!("Unfree packages are allowed for: {}", unfree.join(", ")));
 
     let buttons: Vec<Box<dyn ConfigWidget>> = vec![
       Box::new(Button::new("Begin Installation")),
+      Box::new(Button::new("Verify Build")),
+      Box::new(Button::new("Export")),
       Box::new(Button::new("Back")),
     ];
     let button_row = WidgetBox::button_menu(buttons);
     let help_content = styled_block(vec![
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "1/2"),
-        (None, " - Switch between System/Disko config"),
+        (Some((Color::Yellow, Modifier::BOLD)), "1/2/3/4"),
+        (
+          None,
+          " - Switch between System/Disko/Flake config and install commands",
+        ),
       ],
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "↑/↓, j/k"),
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
         (None, " - Scroll config content"),
       ],
       vec![
@@ -4176,6 +9767,20 @@ impl ConfigPreview {
         (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
         (None, " - Activate selected button"),
       ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "v"),
+        (
+          None,
+          " - Check the config evaluates for the target architecture",
+        ),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "y"),
+        (
+          None,
+          " - Copy the current tab, to a file under /tmp without a clipboard",
+        ),
+      ],
       vec![
         (Some((Color::Yellow, Modifier::BOLD)), "Esc"),
         (None, " - Go back to menu"),
@@ -4189,18 +9794,34 @@ impl ConfigPreview {
         None,
         "Review the generated NixOS configuration before saving.",
       )],
+      vec![(
+        None,
+        "configuration.nix imports hardware-configuration.nix, a hardware scan without filesystems, and the Disko config, which declares every filesystem.",
+      )],
+      vec![(
+        None,
+        "If the generated config doesn't parse, the failing line is shown in red and the parser error above the buttons.",
+      )],
     ]);
     let help_modal = HelpModal::new("Config Preview", help_content);
 
     Ok(Self {
       system_config: configs.system,
       disko_config: configs.disko,
+      flake_config: configs.flake,
+      commands,
+      disko_command,
+      unfree_note,
       _flake_path: configs.flake_path,
       scroll_position: 0,
       button_row,
       current_view: ConfigView::System,
       help_modal,
       visible_lines: 10, // Default value, will be updated during rendering
+      verify: None,
+      parse_error,
+      clipboard: None,
+      copy_note: None,
     })
   }
 }
@@ -4211,9 +9832,13 @@ impl Page for ConfigPreview {
       area,
       1,
       [
-        Constraint::Length(3), // Tab bar
-        Constraint::Min(0),    // Config content
-        Constraint::Length(3), // Buttons
+        Constraint::Length(3),                                 // Tab bar
+        Constraint::Min(0),                                    // Config content
+        Constraint::Length(1),                                 // Disko command
+        Constraint::Length(self.unfree_note.is_some() as u16), // Unfree note
+        Constraint::Length(1),                                 // Verify result
+        Constraint::Length(self.parse_error.is_some() as u16), // Parse error
+        Constraint::Length(3),                                 // Buttons
       ]
     );
 
@@ -4221,7 +9846,12 @@ impl Page for ConfigPreview {
     let tab_chunks = split_hor!(
       chunks[0],
       0,
-      [Constraint::Percentage(50), Constraint::Percentage(50)]
+      [
+        Constraint::Ratio(1, 4),
+        Constraint::Ratio(1, 4),
+        Constraint::Ratio(1, 4),
+        Constraint::Ratio(1, 4),
+      ]
     );
 
     // System config tab
@@ -4252,14 +9882,69 @@ impl Page for ConfigPreview {
       .block(Block::default().borders(Borders::ALL));
     f.render_widget(disko_tab, tab_chunks[1]);
 
+    // Flake tab, dimmed when there is no flake to show
+    let flake_tab_style = match (self.current_view, &self.flake_config) {
+      (ConfigView::Flake, _) => Style::default()
+        .fg(Color::Yellow)
+        .add_modifier(Modifier::BOLD),
+      (_, Some(_)) => Style::default().fg(Color::Gray),
+      (_, None) => Style::default().fg(Color::DarkGray),
+    };
+    let flake_tab = Paragraph::new("Flake [3]")
+      .style(flake_tab_style)
+      .alignment(Alignment::Center)
+      .block(Block::default().borders(Borders::ALL));
+    f.render_widget(flake_tab, tab_chunks[2]);
+
+    // Install commands tab
+    let commands_tab_style = if self.current_view == ConfigView::Commands {
+      Style::default()
+        .fg(Color::Yellow)
+        .add_modifier(Modifier::BOLD)
+    } else {
+      Style::default().fg(Color::Gray)
+    };
+    let commands_tab = Paragraph::new("Install Commands [4]")
+      .style(commands_tab_style)
+      .alignment(Alignment::Center)
+      .block(Block::default().borders(Borders::ALL));
+    f.render_widget(commands_tab, tab_chunks[3]);
+
     // Config content
-    let config_content = match self.current_view {
-      ConfigView::System => highlight_nix(&self.system_config).unwrap_or_default(),
-      ConfigView::Disko => highlight_nix(&self.disko_config).unwrap_or_default(),
+    let lines: Vec<Line<'_>> = match self.current_view {
+      ConfigView::System => {
+        let mut lines = highlight_nix(&self.system_config)
+          .unwrap_or_default()
+          .into_text()
+          .unwrap()
+          .lines;
+        let error_line = self.parse_error.as_ref().and_then(|e| e.line);
+        if let Some(line) = error_line.and_then(|n| lines.get_mut(n.saturating_sub(1))) {
+          let red = Style::default().fg(Color::Red).add_modifier(Modifier::BOLD);
+          for span in line.spans.iter_mut() {
+            span.style = span.style.patch(red);
+          }
+        }
+        lines
+      }
+      ConfigView::Disko => {
+        highlight_nix(&self.disko_config)
+          .unwrap_or_default()
+          .into_text()
+          .unwrap()
+          .lines
+      }
+      ConfigView::Flake => {
+        highlight_nix(self.flake_text())
+          .unwrap_or_default()
+          .into_text()
+          .unwrap()
+          .lines
+      }
+      ConfigView::Commands => self.command_lines(),
     };
-    log::debug!("Rendering config preview with text {config_content:?}");
+    log::debug!("Rendering config preview with {} lines", lines.len());
 
-    let lines: Vec<Line<'_>> = config_content.into_text().unwrap().lines;
     let visible_lines = chunks[1].height as usize - 2; // Account for borders
     self.visible_lines = visible_lines;
 
@@ -4269,10 +9954,12 @@ impl Page for ConfigPreview {
 
     let config_paragraph = Paragraph::new(display_lines)
       .block(Block::default().borders(Borders::ALL).title(format!(
-        "Preview - {} Config (Scroll: {}/{})",
+        "Preview - {} (Scroll: {}/{})",
         match self.current_view {
-          ConfigView::System => "System",
-          ConfigView::Disko => "Disko",
+          ConfigView::System => "System Config",
+          ConfigView::Disko => "Disko Config",
+          ConfigView::Flake => "Flake",
+          ConfigView::Commands => "Install Commands",
         },
         start_line + 1,
         self.get_max_scroll(visible_lines) + 1
@@ -4280,8 +9967,36 @@ impl Page for ConfigPreview {
       .wrap(Wrap { trim: false });
     f.render_widget(config_paragraph, chunks[1]);
 
+    let disko_line = Paragraph::new(Line::from(vec![
+      Span::styled(
+        "Wipes disks: ",
+        Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+      ),
+      Span::raw(self.disko_command.clone()),
+    ]));
+    f.render_widget(disko_line, chunks[2]);
+    if let Some(ref note) = self.unfree_note {
+      let note_line = Paragraph::new(Line::styled(
+        note.clone(),
+        Style::default().fg(Color::Yellow),
+      ));
+      f.render_widget(note_line, chunks[3]);
+    }
+    f.render_widget(Paragraph::new(self.status_line()), chunks[4]);
+    if let Some(ref error) = self.parse_error {
+      let location = error
+        .line
+        .map(|n| format!("configuration.nix line {n}: "))
+        .unwrap_or_default();
+      let error_line = Paragraph::new(Line::styled(
+        format!("{location}{}", error.message),
+        Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+      ));
+      f.render_widget(error_line, chunks[5]);
+    }
+
     // Buttons
-    self.button_row.render(f, chunks[2]);
+    self.button_row.render(f, chunks[6]);
 
     // Help modal
     self.help_modal.render(f, area);
@@ -4290,11 +10005,17 @@ impl Page for ConfigPreview {
   fn get_help_content(&self) -> (String, Vec<Line<'_>>) {
     let help_content = styled_block(vec![
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "1/2"),
-        (None, " - Switch between System/Disko config"),
+        (Some((Color::Yellow, Modifier::BOLD)), "1/2/3/4"),
+        (
+          None,
+          " - Switch between System/Disko/Flake config and install commands",
+        ),
       ],
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "↑/↓, j/k"),
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
         (None, " - Scroll config content"),
       ],
       vec![
@@ -4309,6 +10030,20 @@ impl Page for ConfigPreview {
         (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
         (None, " - Activate selected button"),
       ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "v"),
+        (
+          None,
+          " - Check the config evaluates for the target architecture",
+        ),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "y"),
+        (
+          None,
+          " - Copy the current tab, to a file under /tmp without a clipboard",
+        ),
+      ],
       vec![
         (Some((Color::Yellow, Modifier::BOLD)), "Esc"),
         (None, " - Go back to menu"),
@@ -4322,17 +10057,25 @@ impl Page for ConfigPreview {
         None,
         "Review the generated NixOS configuration before saving.",
       )],
+      vec![(
+        None,
+        "configuration.nix imports hardware-configuration.nix, a hardware scan without filesystems, and the Disko config, which declares every filesystem.",
+      )],
+      vec![(
+        None,
+        "If the generated config doesn't parse, the failing line is shown in red and the parser error above the buttons.",
+      )],
     ]);
     ("Config Preview".to_string(), help_content)
   }
 
-  fn handle_input(&mut self, _installer: &mut Installer, event: KeyEvent) -> Signal {
+  fn handle_input(&mut self, installer: &mut Installer, event: KeyEvent) -> Signal {
     match event.code {
       KeyCode::Char('?') => {
         self.help_modal.toggle();
         Signal::Wait
       }
-      ui_close!() if self.help_modal.visible => {
+      code if ui_close!(code) && self.help_modal.visible => {
         self.help_modal.hide();
         Signal::Wait
       }
@@ -4349,7 +10092,27 @@ impl Page for ConfigPreview {
         self.scroll_position = 0;
         Signal::Wait
       }
-      ui_up!() => {
+      KeyCode::Char('3') => {
+        self.button_row.unfocus();
+        self.current_view = ConfigView::Flake;
+        self.scroll_position = 0;
+        Signal::Wait
+      }
+      KeyCode::Char('4') => {
+        self.button_row.unfocus();
+        self.current_view = ConfigView::Commands;
+        self.scroll_position = 0;
+        Signal::Wait
+      }
+      KeyCode::Char('v') => {
+        self.start_verify(installer);
+        Signal::Wait
+      }
+      KeyCode::Char('y') => {
+        self.copy_current();
+        Signal::Wait
+      }
+      code if ui_up!(code) => {
         if self.button_row.is_focused() {
           if !self.button_row.prev_child() {
             self.button_row.unfocus();
@@ -4359,7 +10122,7 @@ impl Page for ConfigPreview {
         }
         Signal::Wait
       }
-      ui_down!() => {
+      code if ui_down!(code) => {
         if self.button_row.is_focused() {
           self.button_row.next_child();
         } else {
@@ -4372,31 +10135,25 @@ impl Page for ConfigPreview {
         }
         Signal::Wait
       }
-      ui_right!() => {
+      code if ui_right!(code) => {
         if self.button_row.is_focused() {
           if !self.button_row.next_child() {
             self.button_row.first_child();
           }
-        } else if self.current_view == ConfigView::System {
-          self.current_view = ConfigView::Disko;
-          self.scroll_position = 0;
-        } else if self.current_view == ConfigView::Disko {
-          self.current_view = ConfigView::System;
+        } else {
+          self.current_view = self.current_view.next();
           self.scroll_position = 0;
         }
 
         Signal::Wait
       }
-      ui_left!() => {
+      code if ui_left!(code) => {
         if self.button_row.is_focused() {
           if !self.button_row.prev_child() {
             self.button_row.last_child();
           }
-        } else if self.current_view == ConfigView::Disko {
-          self.current_view = ConfigView::System;
-          self.scroll_position = 0;
-        } else if self.current_view == ConfigView::System {
-          self.current_view = ConfigView::Disko;
+        } else {
+          self.current_view = self.current_view.prev();
           self.scroll_position = 0;
         }
 
@@ -4418,8 +10175,21 @@ impl Page for ConfigPreview {
       KeyCode::Enter => {
         if self.button_row.is_focused() {
           match self.button_row.selected_child() {
-            Some(0) => Signal::WriteCfg, // Save & Exit
-            Some(1) => Signal::Pop,      // Back
+            Some(0) => {
+              // One confirmation per disk, the install target first
+              let preflight: Box<dyn Page> = Box::new(Preflight::new(preflight_hosts(installer)));
+              let disks = installer.all_disks().collect::<Vec<_>>();
+              let next = disks.iter().rev().fold(preflight, |next, disk| {
+                Box::new(WipeConfirm::new(disk, next))
+              });
+              Signal::Push(next)
+            }
+            Some(1) => {
+              self.start_verify(installer);
+              Signal::Wait
+            }
+            Some(2) => Signal::Push(Box::new(ExportConfig::new(self.export_files(installer)))),
+            Some(3) => Signal::Pop, // Back
             _ => Signal::Wait,
           }
         } else {
@@ -4438,87 +10208,208 @@ impl Page for ConfigPreview {
   }
 }
 
+/// Generated configs written out to temp files for the install steps
+pub struct StagedConfigs {
+  pub system: NamedTempFile,
+  pub disko: NamedTempFile,
+  pub readme: Option<NamedTempFile>,
+  /// `overlays/default.nix`, when the overlays scaffold is enabled
+  pub overlays: Option<NamedTempFile>,
+  /// Generated `flake.nix`, the install goes through it when present
+  pub flake: Option<NamedTempFile>,
+  /// Passphrases disko reads while formatting encrypted partitions
+  pub luks_keys: Vec<SecretFile>,
+}
+
+/// A file only root can read, removed again when it's dropped
+pub struct SecretFile(std::path::PathBuf);
+
+impl SecretFile {
+  pub fn new(path: impl Into<std::path::PathBuf>, contents: &str) -> anyhow::Result<Self> {
+    let path = path.into();
+    write_private(&path, contents)
+      .map_err(|e| anyhow::anyhow!("Failed to write '{}': {e}", path.display()))?;
+    Ok(Self(path))
+  }
+}
+
+impl Drop for SecretFile {
+  fn drop(&mut self) {
+    let _ = std::fs::remove_file(&self.0);
+  }
+}
+
+impl StagedConfigs {
+  pub fn new(installer: &Installer, configs: &Configs) -> anyhow::Result<Self> {
+    let mut system = NamedTempFile::new()?;
+    let mut disko = NamedTempFile::new()?;
+    write!(system, "{}", configs.system)?;
+    write!(disko, "{}", configs.disko)?;
+    let readme = if installer.skip_readme {
+      None
+    } else {
+      let mut file = NamedTempFile::new()?;
+      write!(file, "{}", generate_readme(installer))?;
+      Some(file)
+    };
+    let overlays = if installer.overlays_scaffold {
+      let mut file = NamedTempFile::new()?;
+      write!(file, "{OVERLAYS_DEFAULT_NIX}")?;
+      Some(file)
+    } else {
+      None
+    };
+    let flake = match configs.flake {
+      Some(ref flake) => {
+        let mut file = NamedTempFile::new()?;
+        write!(file, "{flake}")?;
+        Some(file)
+      }
+      None => None,
+    };
+    let mut luks_keys = vec![];
+    for disk in installer.all_disks() {
+      let passphrases = disk
+        .partitions()
+        .filter(|p| matches!(p.status(), PartStatus::Create | PartStatus::Modify))
+        .filter_map(|p| p.luks());
+      for luks in passphrases {
+        let LuksKey::Passphrase { ref passphrase } = luks.key else {
+          continue;
+        };
+        if passphrase.is_empty() {
+          return Err(anyhow::anyhow!(
+            "No passphrase set for LUKS volume '{}'",
+            luks.name
+          ));
+        }
+        luks_keys.push(SecretFile::new(luks.passphrase_path(), passphrase)?);
+      }
+    }
+    Ok(Self {
+      system,
+      disko,
+      readme,
+      overlays,
+      flake,
+      luks_keys,
+    })
+  }
+
+  fn path_str(file: &NamedTempFile) -> anyhow::Result<String> {
+    file
+      .path()
+      .to_str()
+      .map(|s| s.to_string())
+      .ok_or_else(|| anyhow::anyhow!("Invalid temp file path: {}", file.path().display()))
+  }
+
+  pub fn system_path(&self) -> anyhow::Result<String> {
+    Self::path_str(&self.system)
+  }
+
+  pub fn disko_path(&self) -> anyhow::Result<String> {
+    Self::path_str(&self.disko)
+  }
+
+  pub fn readme_path(&self) -> anyhow::Result<Option<String>> {
+    self.readme.as_ref().map(Self::path_str).transpose()
+  }
+
+  pub fn overlays_path(&self) -> anyhow::Result<Option<String>> {
+    self.overlays.as_ref().map(Self::path_str).transpose()
+  }
+
+  pub fn flake_path(&self) -> anyhow::Result<Option<String>> {
+    self.flake.as_ref().map(Self::path_str).transpose()
+  }
+}
+
+/// Output lines of a failed step copied into the log
+const FAILURE_LOG_LINES: usize = 50;
+
 pub struct InstallProgress<'a> {
   _installer: Installer,
   steps: InstallSteps<'a>,
-  log_box: LogBox<'a>,
   progress_bar: ProgressBar,
   help_modal: HelpModal<'static>,
   signal: Option<Signal>,
-
-  // we only hold onto these to keep them alive during installation
-  _system_cfg: NamedTempFile,
-  _disko_cfg: NamedTempFile,
-  _log_file: NamedTempFile,
+  /// Index of the step that runs disko
+  partition_step: usize,
+  /// Height of the output pane at the last render, for paging
+  output_rows: usize,
+  /// Set once the failed step's output has been written to the log
+  failure_logged: bool,
+
+  // we only hold onto this to keep it alive during installation
+  _configs: Arc<StagedConfigs>,
 }
 
 impl<'a> InstallProgress<'a> {
-  pub fn new(
-    installer: Installer,
-    system_cfg: NamedTempFile,
-    disko_cfg: NamedTempFile,
-  ) -> anyhow::Result<Self> {
-    let log_file = NamedTempFile::new()?;
-    let log_path = log_file
-      .path()
-      .to_str()
-      .ok_or_else(|| anyhow::anyhow!("Invalid log file path"))?
-      .to_string();
-    let install_steps = Self::install_commands(
-      &installer,
-      system_cfg
-        .path()
-        .to_str()
-        .ok_or_else(|| anyhow::anyhow!("Invalid system config path"))?
-        .to_string(),
-      disko_cfg
-        .path()
-        .to_str()
-        .ok_or_else(|| anyhow::anyhow!("Invalid disko config path"))?
-        .to_string(),
-      log_path.clone(),
-    )?;
-    let steps = InstallSteps::new("Install Steps", install_steps);
+  pub fn new(installer: Installer, configs: Arc<StagedConfigs>) -> anyhow::Result<Self> {
+    let install_steps = Self::install_commands(&installer, &configs)?;
+    let partition_step = install_steps
+      .iter()
+      .position(|(line, _)| *line == Line::from(Self::PARTITION_STEP))
+      .unwrap_or(0);
+    // The new system boots fine without a channel, it can be added afterwards
+    let channel_step = install_steps
+      .iter()
+      .position(|(line, _)| *line == Line::from(Self::CHANNEL_STEP));
+    let title = match installer.dry_run {
+      Some(_) => "Install Steps - DRY RUN",
+      None => "Install Steps",
+    };
+    let mut steps = InstallSteps::new(title, install_steps);
+    if let Some(step) = channel_step {
+      steps.set_optional(step);
+    }
     let progress_bar = ProgressBar::new("Progress", 0);
 
     let help_content = styled_block(vec![
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "↑/↓, j/k"),
-        (None, " - Navigate through installation steps"),
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Scroll through command output"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Page Up/Down"),
+        (None, " - Scroll output page by page"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Home/End"),
+        (None, " - Jump to beginning/end of output"),
       ],
       vec![
         (Some((Color::Yellow, Modifier::BOLD)), "Esc"),
         (None, " - Exit installation (if completed)"),
       ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "s"),
+        (None, " - Open a shell (if installation failed)"),
+      ],
       vec![
         (Some((Color::Yellow, Modifier::BOLD)), "?"),
         (None, " - Show this help"),
       ],
       vec![(None, "")],
-      vec![(
-        None,
-        "This page shows the progress of the NixOS installation process.",
-      )],
-      vec![(
-        None,
-        "Installation steps are executed sequentially and their status is shown above.",
-      )],
+      vec![(None, "Watch the progress as NixOS installs. Commands run")],
+      vec![(None, "sequentially and their output is shown below them.")],
     ]);
     let help_modal = HelpModal::new("Installation Progress", help_content);
 
-    let mut log_box = LogBox::new("Logs".into());
-    log_box.open_log(log_path)?;
-
     Ok(Self {
       _installer: installer,
       steps,
       progress_bar,
-      log_box,
       help_modal,
       signal: None,
-      _system_cfg: system_cfg,
-      _disko_cfg: disko_cfg,
-      _log_file: log_file,
+      partition_step,
+      output_rows: 0,
+      failure_logged: false,
+      _configs: configs,
     })
   }
 
@@ -4530,74 +10421,292 @@ impl<'a> InstallProgress<'a> {
     self.steps.has_error()
   }
 
+  const PARTITION_STEP: &'static str = "Configuring disk layout...";
+  const CHANNEL_STEP: &'static str = "Importing channels...";
+
+  /// The disko invocation that wipes and partitions the target disks
+  ///
+  /// Destroy mode wipes whole disks, so it's left out when existing
+  /// partitions like a Windows ESP have to survive. Format mode only creates
+  /// what is missing and leaves partitions without content alone.
+  pub fn disko_command(disk_cfg_path: &str, keeps_partitions: bool) -> String {
+    if keeps_partitions {
+      format!("disko --mode format,mount {disk_cfg_path}")
+    } else {
+      format!("disko --yes-wipe-all-disks --mode destroy,format,mount {disk_cfg_path}")
+    }
+  }
+
+  /// The shell commands run by each install step, for display
+  ///
+  /// `install_commands` runs exactly these with `sh -c`. Their output is
+  /// captured for the output pane, only the last lines of a failed step end
+  /// up in the log.
+  pub fn install_script(
+    installer: &Installer,
+    configs: &StagedConfigs,
+  ) -> anyhow::Result<Vec<(&'static str, Vec<String>)>> {
+    let template = &installer.install_template;
+    let system_cfg_path = configs.system_path()?;
+    let disk_cfg_path = configs.disko_path()?;
+    let hook_step = |title: &'static str, hooks: &[String]| {
+      let mut cmds = vec![format!("echo {title}")];
+      cmds.extend(hooks.iter().cloned());
+      (title, cmds)
+    };
+    let mut install_flags = String::new();
+    if let Some(ref build) = installer.nix_build {
+      install_flags += &format!(
+        " --option max-jobs {} --option cores {}",
+        build.max_jobs, build.cores
+      );
+    }
+    install_flags += &substituters::install_flags(&installer.substituters);
+    let install_flags = template
+      .nixos_install_flags
+      .iter()
+      .fold(install_flags, |acc, flag| {
+        format!("{acc} {}", Self::shell_quote(flag))
+      });
+
+    let mut steps = vec![(
+      "Beginning NixOS Installation...",
+      vec![
+        "echo Beginning NixOS Installation...".to_string(),
+        "sleep 1".to_string(),
+      ],
+    )];
+    if !template.pre_install.is_empty() {
+      steps.push(hook_step(
+        "Running pre-install commands...",
+        &template.pre_install,
+      ));
+    }
+    if !installer.storage_teardown.is_empty() {
+      let mut cmds = vec!["echo Tearing down existing storage layers...".to_string()];
+      cmds.extend(installer.storage_teardown.iter().cloned());
+      steps.push(("Tearing down existing storage...", cmds));
+    }
+    // Format mode leaves the partition table alone, so partitions that are
+    // going away have to be removed by hand first
+    let removal_cmds = installer
+      .all_disks()
+      .flat_map(|d| d.removal_commands())
+      .collect::<Vec<_>>();
+    if installer.keeps_partitions() && !removal_cmds.is_empty() {
+      let mut cmds = vec!["echo Removing old partitions...".to_string()];
+      cmds.extend(removal_cmds);
+      steps.push(("Removing old partitions...", cmds));
+    }
+    let flake_path = configs.flake_path()?;
+    // configuration.nix imports disko-config.nix, which declares the
+    // filesystems, so the hardware scan must leave them out or every mount
+    // would be defined twice
+    let mut build_cmds = vec![
+      "echo Building NixOS configuration...".to_string(),
+      "nixos-generate-config --no-filesystems --root /mnt".to_string(),
+      format!("cp -v {system_cfg_path} /mnt/etc/nixos/configuration.nix"),
+      format!("cp -v {disk_cfg_path} /mnt/etc/nixos/disko-config.nix"),
+    ];
+    if let Some(ref flake_path) = flake_path {
+      build_cmds.push(format!("cp -v {flake_path} /mnt/etc/nixos/flake.nix"));
+    }
+    // configuration.nix imports this, so it has to be there before the build
+    if let Some(overlays_path) = configs.overlays_path()? {
+      build_cmds.push("mkdir -p /mnt/etc/nixos/overlays".to_string());
+      build_cmds.push(format!(
+        "cp -v {overlays_path} /mnt/etc/nixos/overlays/default.nix"
+      ));
+    }
+    build_cmds.push("echo Build completed".to_string());
+    let mut partition_cmds = vec![
+      "echo Partitioning disks...".to_string(),
+      Self::disko_command(&disk_cfg_path, installer.keeps_partitions()),
+    ];
+    partition_cmds.extend(Self::kept_mount_commands(installer));
+    steps.extend(vec![
+      (Self::PARTITION_STEP, partition_cmds),
+      ("Building NixOS configuration...", build_cmds),
+      (
+        "Installing NixOS...",
+        vec![
+          "echo Installing NixOS...".to_string(),
+          Self::nixos_install_command(installer, flake_path.is_some(), &install_flags),
+        ],
+      ),
+    ]);
+    if let Some(readme_path) = configs.readme_path()? {
+      steps.push((
+        "Writing README...",
+        vec![format!("cp -v {readme_path} /mnt/etc/nixos/README.md")],
+      ));
+    }
+    if !template.post_install.is_empty() {
+      steps.push(hook_step(
+        "Running post-install commands...",
+        &template.post_install,
+      ));
+    }
+    // A flake pins nixpkgs in its lock file, there is no channel to set up
+    if flake_path.is_none() {
+      steps.push((
+        Self::CHANNEL_STEP,
+        vec![
+          "echo Importing NixOS channels...".to_string(),
+          format!(
+            "nixos-enter -- nix-channel --add {} nixos",
+            channel::channel_url(installer.channel())
+          ),
+          "nixos-enter -- nix-channel --update".to_string(),
+        ],
+      ));
+    }
+    steps.push((
+      "Finalizing installation...",
+      vec![
+        "sleep 1".to_string(),
+        "echo Installation complete!".to_string(),
+      ],
+    ));
+    Ok(steps)
+  }
+
+  fn nixos_install_command(installer: &Installer, flake: bool, install_flags: &str) -> String {
+    if !flake {
+      // Build from the chosen channel, not whatever the live system has
+      let nixpkgs = channel::nixexprs_url(installer.channel());
+      return format!("nixos-install --root /mnt -I nixpkgs={nixpkgs}{install_flags}");
+    }
+    let hostname = installer.hostname.as_deref().unwrap_or("nixos");
+    // Home Manager is fetched without a hash, which pure evaluation refuses
+    let impure = if installer.users.iter().any(|u| u.home_manager_cfg.is_some()) {
+      " --impure"
+    } else {
+      ""
+    };
+    format!("nixos-install --root /mnt --flake /mnt/etc/nixos#{hostname}{impure}{install_flags}")
+  }
+
+  /// Mount the partitions that are kept as is under /mnt
+  ///
+  /// disko only mounts what it formats, but nixos-install still needs e.g. a
+  /// reused ESP at /mnt/boot. Parents go first so nested mounts land on them.
+  fn kept_mount_commands(installer: &Installer) -> Vec<String> {
+    let mut kept = installer
+      .all_disks()
+      .flat_map(|d| d.partitions())
+      .filter(|p| *p.status() == PartStatus::Exists && p.disko_fs_type() != Some("swap"))
+      .filter_map(|p| Some((p.mount_point()?, p.name()?, p.mount_options())))
+      .collect::<Vec<_>>();
+    kept.sort_by_key(|(mount_point, _, _)| mount_point.trim_end_matches('/').matches('/').count());
+    kept
+      .into_iter()
+      .map(|(mount_point, name, options)| {
+        let target = Self::shell_quote(&format!("/mnt{}", mount_point.trim_end_matches('/')));
+        let options = if options.is_empty() {
+          String::new()
+        } else {
+          format!(" -o {}", Self::shell_quote(&options.join(",")))
+        };
+        format!("mkdir -p {target} && mount{options} /dev/{name} {target}")
+      })
+      .collect()
+  }
+
+  /// Quote `arg` for `sh -c` if it has anything the shell would interpret
+  fn shell_quote(arg: &str) -> String {
+    let plain = !arg.is_empty()
+      && arg
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || "-_=+.,:/@%".contains(c));
+    if plain {
+      arg.to_string()
+    } else {
+      format!("'{}'", arg.replace('\'', r"'\''"))
+    }
+  }
+
+  /// What `cmd` becomes in a dry run. Copies of the generated files go to
+  /// `dir`, anything else that could touch the system is only printed
+  fn dry_run_command(cmd: &str, dir: &str) -> String {
+    // User commands may start with echo too, so only pass through plain ones
+    let plain = !cmd.contains(['>', '|', ';', '&', '`', '$']);
+    if plain && (cmd.starts_with("echo ") || cmd.starts_with("sleep ")) {
+      return cmd.to_string();
+    }
+    if (cmd.starts_with("cp -v ") || cmd.starts_with("mkdir -p ")) && cmd.contains("/mnt/etc/nixos")
+    {
+      return cmd.replace("/mnt/etc/nixos", dir);
+    }
+    let quoted = format!("[dry run] {cmd}").replace('\'', r"'\''");
+    format!("echo '{quoted}'")
+  }
+
   /// The actual installation steps
   fn install_commands(
-    _installer: &Installer,
-    system_cfg_path: String,
-    disk_cfg_path: String,
-    log_file_path: String,
+    installer: &Installer,
+    configs: &StagedConfigs,
   ) -> anyhow::Result<Vec<(Line<'static>, VecDeque<Command>)>> {
-    Ok(vec![
-			(Line::from("Beginning NixOS Installation..."),
-			vec![
-			command!("sh", "-c", format!("echo Beginning NixOS Installation... 2>&1 > {log_file_path}")),
-			command!("sleep", "1"),
-			].into()),
-			(Line::from("Configuring disk layout..."),
-			vec![
-			command!("sh", "-c", format!("echo Partitioning disks... 2>&1 > {log_file_path}")),
-			command!("sh", "-c", format!("disko --yes-wipe-all-disks --mode destroy,format,mount {disk_cfg_path} 2>&1 > {log_file_path}")),
-			].into()),
-			(Line::from("Building NixOS configuration..."),
-			vec![
-			command!("sh", "-c", format!("echo Building NixOS configuration... 2>&1 > {log_file_path}")),
-			command!("sh", "-c", format!("nixos-generate-config --root /mnt 2>&1 > {log_file_path}")),
-			command!("sh", "-c", format!("cp -v {system_cfg_path} /mnt/etc/nixos/configuration.nix 2>&1 > {log_file_path}")),
-			command!("sh", "-c", format!("echo Build completed 2>&1 > {log_file_path}")),
-			].into()),
-			(Line::from("Installing NixOS..."),
-			vec![
-			command!("sh", "-c", format!("echo Installing NixOS... 2>&1 > {log_file_path}")),
-			command!("sh", "-c", format!("nixos-install --root /mnt 2>&1 > {log_file_path}")),
-			].into()),
-			(Line::from("Importing channels..."),
-			vec![
-			command!("sh", "-c", format!("echo Importing NixOS channels... 2>&1 > {log_file_path}")),
-			command!("sh", "-c", format!("nixos-enter -- nix-channel --add https://nixos.org/channels/nixos-unstable nixos 2>&1 > {log_file_path}")),
-			command!("sh", "-c", format!("nixos-enter -- nix-channel --update 2>&1 > {log_file_path}")),
-			].into()),
-			(Line::from("Finalizing installation..."),
-			vec![
-			command!("sleep", "1"),
-			command!("sh", "-c", format!("echo Installation complete! 2>&1 > {log_file_path}")),
-			].into()),
-			])
+    let steps = Self::install_script(installer, configs)?
+      .into_iter()
+      .map(|(title, cmds)| {
+        let cmds = cmds
+          .into_iter()
+          .map(|cmd| match installer.dry_run {
+            Some(ref dir) => Self::dry_run_command(&cmd, dir),
+            None => cmd,
+          })
+          .map(|cmd| command!("sh", "-c", cmd))
+          .collect();
+        (Line::from(title), cmds)
+      })
+      .collect();
+    Ok(steps)
   }
 }
 
 impl<'a> Page for InstallProgress<'a> {
-  fn render(&mut self, _installer: &mut Installer, f: &mut Frame, area: Rect) {
+  fn render(&mut self, installer: &mut Installer, f: &mut Frame, area: Rect) {
     // Tick the steps to update animation and process commands
     let _ = self.steps.tick();
-    let _ = self.log_box.poll_log();
+    if self.steps.has_error() && !self.failure_logged {
+      self.failure_logged = true;
+      let step = self.steps.current_step_index;
+      let tail = self.steps.output_tail(step, FAILURE_LOG_LINES);
+      log::error!(
+        "Install step {step} failed, last output:\n{}",
+        tail.join("\n")
+      );
+    }
+
+    // Anything from the disko run onwards may have mounted /mnt
+    if self.steps.current_step_index >= self.partition_step && installer.dry_run.is_none() {
+      installer.partitioning_started = true;
+    }
 
     let chunks = split_vert!(area, 1, [Constraint::Min(0), Constraint::Length(3)]);
-    let hor_chunks = split_hor!(
+    let step_rows = self.steps.steps.len() as u16 + 2;
+    let main_chunks = split_vert!(
       chunks[0],
-      1,
-      [Constraint::Percentage(30), Constraint::Percentage(70)]
+      0,
+      [Constraint::Length(step_rows), Constraint::Min(0)]
     );
 
-    // Render InstallSteps widget in the main area
-    self.steps.render(f, hor_chunks[0]);
-    self.log_box.render(f, hor_chunks[1]);
+    // Step list on top, output of the running step below it
+    self.steps.render(f, main_chunks[0]);
+    self.output_rows = main_chunks[1].height.saturating_sub(2) as usize;
+    self.steps.set_output_rows(self.output_rows);
+    self.steps.render_output(f, main_chunks[1]);
 
     // Update progress bar with completion percentage
     let progress = (self.steps.progress() * 100.0) as u32;
     if progress == 100 || self.steps.is_complete() {
-      self.signal = Some(Signal::Push(Box::new(InstallComplete::new())));
+      self.signal = Some(Signal::Push(Box::new(InstallComplete::new(
+        self.steps.summary(),
+      ))));
     }
     self.progress_bar.set_progress(progress);
+    self.progress_bar.set_message(self.steps.status_line());
     self.progress_bar.render(f, chunks[1]);
 
     // Help modal
@@ -4609,13 +10718,15 @@ impl<'a> Page for InstallProgress<'a> {
     if let Some(ref signal) = self.signal {
       match signal {
         Signal::Wait => Some(Signal::Wait),
-        Signal::Push(_) => Some(Signal::Push(Box::new(InstallComplete::new()))),
+        Signal::Push(_) => Some(Signal::Push(Box::new(InstallComplete::new(
+          self.steps.summary(),
+        )))),
         Signal::Pop => Some(Signal::Pop),
         Signal::PopCount(n) => Some(Signal::PopCount(*n)),
         Signal::Quit => Some(Signal::Quit),
         Signal::WriteCfg => Some(Signal::WriteCfg),
         Signal::Unwind => Some(Signal::Unwind),
-        Signal::Error(_) => Some(Signal::Wait),
+        Signal::Replace(_) | Signal::Suspend(_) | Signal::Error(_) => Some(Signal::Wait),
       }
     } else {
       None
@@ -4625,7 +10736,10 @@ impl<'a> Page for InstallProgress<'a> {
   fn get_help_content(&self) -> (String, Vec<Line<'_>>) {
     let help_content = styled_block(vec![
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "↑/↓, j/k"),
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
         (None, " - Scroll through command output"),
       ],
       vec![
@@ -4640,13 +10754,17 @@ impl<'a> Page for InstallProgress<'a> {
         (Some((Color::Yellow, Modifier::BOLD)), "Esc"),
         (None, " - Exit installation (if completed)"),
       ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "s"),
+        (None, " - Open a shell (if installation failed)"),
+      ],
       vec![
         (Some((Color::Yellow, Modifier::BOLD)), "?"),
         (None, " - Show this help"),
       ],
       vec![(None, "")],
       vec![(None, "Watch the progress as NixOS installs. Commands run")],
-      vec![(None, "sequentially and their output is logged above.")],
+      vec![(None, "sequentially and their output is shown below them.")],
     ]);
     ("Installation Progress".to_string(), help_content)
   }
@@ -4655,10 +10773,48 @@ impl<'a> Page for InstallProgress<'a> {
     if event.code == KeyCode::Char('c') && event.modifiers.contains(KeyModifiers::CONTROL) {
       return Signal::Quit;
     }
+    match event.code {
+      KeyCode::Char('?') => {
+        self.help_modal.toggle();
+        return Signal::Wait;
+      }
+      code if ui_close!(code) && self.help_modal.visible => {
+        self.help_modal.hide();
+        return Signal::Wait;
+      }
+      _ if self.help_modal.visible => return Signal::Wait,
+      code if ui_up!(code) => {
+        self.steps.scroll_output_up(1);
+        return Signal::Wait;
+      }
+      code if ui_down!(code) => {
+        self.steps.scroll_output_down(1);
+        return Signal::Wait;
+      }
+      KeyCode::PageUp => {
+        self.steps.scroll_output_up(self.output_rows.max(1));
+        return Signal::Wait;
+      }
+      KeyCode::PageDown => {
+        self.steps.scroll_output_down(self.output_rows.max(1));
+        return Signal::Wait;
+      }
+      KeyCode::Home => {
+        self.steps.scroll_output_top();
+        return Signal::Wait;
+      }
+      KeyCode::End => {
+        self.steps.scroll_output_bottom();
+        return Signal::Wait;
+      }
+      _ => {}
+    }
     if self.has_error() {
       match event.code {
         KeyCode::Esc => Signal::Pop,
         KeyCode::Char('q') => Signal::Pop,
+        // Leave everything mounted so the failure can be inspected
+        KeyCode::Char('s') => Signal::Suspend(command!("/bin/sh")),
         _ => Signal::Wait,
       }
     } else {
@@ -4669,10 +10825,13 @@ impl<'a> Page for InstallProgress<'a> {
 
 pub struct InstallComplete {
   text_box: InfoBox<'static>,
+  summary: InstallSummary,
+  /// Users created without a password under imperative password management
+  passwordless: Vec<String>,
 }
 
 impl InstallComplete {
-  pub fn new() -> Self {
+  pub fn new(summary: InstallSummary) -> Self {
     let content = styled_block(vec![
       vec![(
         None,
@@ -4696,23 +10855,276 @@ impl InstallComplete {
       vec![(None, "Press any key to exit the installer.")],
     ]);
     let text_box = InfoBox::new("Installation Complete", content);
-    Self { text_box }
+    Self {
+      text_box,
+      summary,
+      passwordless: vec![],
+    }
+  }
+
+  fn fmt_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    if secs >= 60 {
+      format!("{}m {:02}s", secs / 60, secs % 60)
+    } else {
+      format!("{:.1}s", duration.as_secs_f64())
+    }
+  }
+
+  fn summary_lines(&self) -> Vec<Line<'static>> {
+    let mut lines = vec![];
+    for step in &self.summary.steps {
+      let (mark, style) = match step.status {
+        StepStatus::Completed => ("✓ ", Some((Color::Green, Modifier::BOLD))),
+        StepStatus::Skipped => ("! ", HIGHLIGHT),
+        StepStatus::Failed => ("✗ ", Some((Color::Red, Modifier::BOLD))),
+        StepStatus::Inactive | StepStatus::Running => ("  ", None),
+      };
+      let duration = step
+        .duration
+        .map(Self::fmt_duration)
+        .unwrap_or_else(|| "-".to_string());
+      lines.push(vec![
+        (style, mark.to_string()),
+        (None, format!("{:<40}", step.title)),
+        (None, duration),
+      ]);
+    }
+    lines.push(vec![(None, String::new())]);
+    lines.push(vec![
+      (None, "Total: ".to_string()),
+      (HIGHLIGHT, Self::fmt_duration(self.summary.total)),
+    ]);
+    if !self.summary.warnings.is_empty() {
+      lines.push(vec![(None, String::new())]);
+      lines.push(vec![(HIGHLIGHT, "Warnings:".to_string())]);
+      for warning in &self.summary.warnings {
+        lines.push(vec![(None, format!(" - {warning}"))]);
+      }
+    }
+    styled_block(lines)
   }
 }
 
 impl Default for InstallComplete {
   fn default() -> Self {
-    Self::new()
+    Self::new(InstallSummary::default())
   }
 }
 
 impl Page for InstallComplete {
-  fn render(&mut self, _installer: &mut Installer, f: &mut Frame, area: Rect) {
-    let chunks = split_vert!(area, 1, [Constraint::Percentage(100)]);
+  fn render(&mut self, installer: &mut Installer, f: &mut Frame, area: Rect) {
+    self.passwordless = installer
+      .users
+      .iter()
+      .filter(|user| user.password_hash.is_empty())
+      .map(|user| user.username.clone())
+      .collect();
+    let summary_box = InfoBox::new("Summary", self.summary_lines());
+    if self.passwordless.is_empty() {
+      let chunks = split_vert!(
+        area,
+        1,
+        [Constraint::Percentage(40), Constraint::Percentage(60)]
+      );
+      self.text_box.render(f, chunks[0]);
+      summary_box.render(f, chunks[1]);
+      return;
+    }
+
+    let chunks = split_vert!(
+      area,
+      1,
+      [
+        Constraint::Percentage(35),
+        Constraint::Percentage(45),
+        Constraint::Percentage(20),
+      ]
+    );
     self.text_box.render(f, chunks[0]);
+    summary_box.render(f, chunks[1]);
+    let passwd_box = InfoBox::new(
+      "Passwords",
+      styled_block(vec![
+        vec![
+          (None, "These users do not have a password yet: "),
+          (HIGHLIGHT, &self.passwordless.join(", ")),
+        ],
+        vec![
+          (None, "Press "),
+          (HIGHLIGHT, "p"),
+          (None, " to set them now with passwd."),
+        ],
+      ]),
+    );
+    passwd_box.render(f, chunks[2]);
+  }
+
+  fn has_help(&self) -> bool {
+    false
+  }
+
+  fn handle_input(&mut self, _installer: &mut Installer, event: KeyEvent) -> Signal {
+    match event.code {
+      KeyCode::Char('p') if !self.passwordless.is_empty() => {
+        let script = self
+          .passwordless
+          .iter()
+          .map(|user| format!("passwd {user}"))
+          .collect::<Vec<_>>()
+          .join(" && ");
+        Signal::Suspend(command!("nixos-enter", "--root", "/mnt", "-c", script))
+      }
+      _ => Signal::Quit,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn secret_file_does_not_follow_planted_symlinks() {
+    let dir = tempfile::tempdir().unwrap();
+    let target = dir.path().join("target");
+    std::fs::write(&target, "").unwrap();
+    let path = dir.path().join("luks.key");
+    std::os::unix::fs::symlink(&target, &path).unwrap();
+
+    let secret = SecretFile::new(&path, "passphrase").unwrap();
+    assert_eq!(std::fs::read_to_string(&target).unwrap(), "");
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "passphrase");
+    drop(secret);
+    assert!(!path.exists());
+  }
+
+  #[test]
+  fn paste_fills_the_focused_password_field() {
+    let mut installer = Installer::default();
+    let mut page = RootPassword::new(false);
+    page.handle_paste(&mut installer, "hunter2?qj\n");
+    assert_eq!(page.input.value, "hunter2?qj");
+    assert!(!page.help_modal.visible);
+
+    page.handle_input(
+      &mut installer,
+      KeyEvent::new(KeyCode::Tab, KeyModifiers::NONE),
+    );
+    page.handle_paste(&mut installer, "hunter2?qj");
+    assert_eq!(page.confirm.value, "hunter2?qj");
+  }
+
+  #[test]
+  fn install_flags_are_shell_quoted() {
+    assert_eq!(
+      InstallProgress::shell_quote("--no-root-passwd"),
+      "--no-root-passwd"
+    );
+    assert_eq!(
+      InstallProgress::shell_quote("--substituters=https://a https://b"),
+      "'--substituters=https://a https://b'"
+    );
+    assert_eq!(InstallProgress::shell_quote("a b"), "'a b'");
+    assert_eq!(InstallProgress::shell_quote("$(reboot)"), "'$(reboot)'");
+    assert_eq!(InstallProgress::shell_quote("it's"), r"'it'\''s'");
+    assert_eq!(InstallProgress::shell_quote(""), "''");
+  }
+
+  #[test]
+  fn release_channel_is_used_throughout() {
+    let mut installer = Installer {
+      channel: Some("nixos-24.11".into()),
+      ..Default::default()
+    };
+    let json = installer.to_json().unwrap();
+    assert_eq!(json["config"]["state_version"], "24.11");
+
+    let configs = Configs {
+      system: String::new(),
+      disko: String::new(),
+      flake_path: None,
+      flake: None,
+    };
+    let staged = StagedConfigs::new(&installer, &configs).unwrap();
+    let script = InstallProgress::install_script(&installer, &staged)
+      .unwrap()
+      .into_iter()
+      .flat_map(|(_, cmds)| cmds)
+      .collect::<Vec<_>>();
+    assert!(script.iter().any(|cmd| cmd.starts_with("nixos-install")
+      && cmd.contains("-I nixpkgs=https://nixos.org/channels/nixos-24.11/nixexprs.tar.xz")));
+    assert!(
+      script.iter().any(|cmd| cmd
+        == "nixos-enter -- nix-channel --add https://nixos.org/channels/nixos-24.11 nixos")
+    );
+  }
+
+  #[test]
+  fn kept_partitions_are_mounted_parents_first() {
+    let gib = 2 * 1024 * 1024;
+    let kept = |n: u64, mount_point: &str| {
+      DiskItem::Partition(Partition::new(
+        2048 + (n - 1) * gib,
+        gib,
+        512,
+        PartStatus::Exists,
+        Some(format!("sda{n}")),
+        Some("ext4".into()),
+        Some(mount_point.into()),
+        None,
+        false,
+        vec![],
+      ))
+    };
+    let layout = vec![kept(1, "/boot/efi"), kept(2, "/home"), kept(3, "/boot")];
+    let installer = Installer {
+      drive_config: Some(Disk::new("sda".into(), 8 * gib, 512, layout)),
+      ..Default::default()
+    };
+    assert_eq!(
+      InstallProgress::kept_mount_commands(&installer),
+      vec![
+        "mkdir -p /mnt/home && mount /dev/sda2 /mnt/home",
+        "mkdir -p /mnt/boot && mount /dev/sda3 /mnt/boot",
+        "mkdir -p /mnt/boot/efi && mount /dev/sda1 /mnt/boot/efi",
+      ]
+    );
   }
 
-  fn handle_input(&mut self, _installer: &mut Installer, _event: KeyEvent) -> Signal {
-    Signal::Quit
+  #[test]
+  fn systemd_boot_blocks_a_bios_install() {
+    let mut installer = Installer {
+      bootloader: Some("systemd-boot".into()),
+      firmware_mode: FirmwareMode::Uefi,
+      ..Default::default()
+    };
+    assert!(!installer.bootloader_needs_uefi());
+    installer.firmware_mode = FirmwareMode::Bios;
+    assert!(installer.bootloader_needs_uefi());
+    assert!(!installer.has_all_requirements());
+    installer.bootloader = Some("GRUB".into());
+    assert!(!installer.bootloader_needs_uefi());
+  }
+
+  #[test]
+  fn auto_upgrade_uses_installed_flake() {
+    let mut installer = Installer {
+      hostname: Some("box".into()),
+      ..Default::default()
+    };
+    assert_eq!(installer.upgrade_flake(), None);
+
+    installer.enable_flakes = true;
+    assert_eq!(installer.upgrade_flake().as_deref(), Some("/etc/nixos#box"));
+
+    installer.flake_path = Some("/home/nixos/config#box".into());
+    assert_eq!(installer.upgrade_flake(), None);
+
+    installer.flake_path = Some("github:user/repo#box".into());
+    assert_eq!(
+      installer.upgrade_flake().as_deref(),
+      Some("github:user/repo#box")
+    );
   }
 }
@@ -8,8 +8,8 @@ use ratatui::{
 use serde_json::Value;
 
 use crate::{
-  installer::{Installer, Page, Signal, SshCfg},
-  split_hor, split_vert, styled_block, ui_back, ui_close, ui_down, ui_up,
+  installer::{Installer, Page, RootLogin, Signal, SshCfg, vpn::Vpn},
+  keys, split_hor, split_vert, styled_block, ui_back, ui_close, ui_down, ui_up,
   widget::{Button, CheckBox, ConfigWidget, HelpModal, InfoBox, LineEditor, StrList, WidgetBox},
 };
 
@@ -25,6 +25,7 @@ impl NetworkConfig {
     let items = vec![
       "Network Backend".to_string(),
       "SSH Configuration".to_string(),
+      "VPN".to_string(),
       "Back".to_string(),
     ];
     let mut menu_items = StrList::new("Network Configuration", items);
@@ -32,7 +33,10 @@ impl NetworkConfig {
 
     let help_content = styled_block(vec![
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "↑/↓, j/k"),
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
         (None, " - Navigate menu items"),
       ],
       vec![
@@ -40,7 +44,10 @@ impl NetworkConfig {
         (None, " - Select menu item"),
       ],
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "Esc, q, ←, h"),
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().back_label,
+        ),
         (None, " - Return to main menu"),
       ],
       vec![
@@ -50,7 +57,7 @@ impl NetworkConfig {
       vec![(None, "")],
       vec![(
         None,
-        "Configure network settings including backend and SSH.",
+        "Configure network settings including backend, SSH and VPN.",
       )],
     ]);
     let help_modal = HelpModal::new("Network Configuration", help_content);
@@ -99,6 +106,12 @@ impl NetworkConfig {
       lines.push(vec![(HIGHLIGHT, "Not configured".into())]);
     }
 
+    let vpn_lines = Vpn::display_lines(installer);
+    if !vpn_lines.is_empty() {
+      lines.push(vec![(None, "".into())]);
+      lines.extend(vpn_lines);
+    }
+
     let ib = InfoBox::new("", styled_block(lines));
     Some(Box::new(ib) as Box<dyn ConfigWidget>)
   }
@@ -110,7 +123,7 @@ impl NetworkConfig {
         vec![(None, "Configure network settings for your system.")],
         vec![(
           None,
-          "This includes selecting a network backend and configuring SSH access.",
+          "This includes selecting a network backend, configuring SSH access and setting up a VPN.",
         )],
       ]),
     )
@@ -182,14 +195,7 @@ impl Page for NetworkConfig {
         ]);
         info_lines.push(vec![
           (None, "  Root Login: ".into()),
-          (
-            HIGHLIGHT,
-            if ssh.root_login {
-              "Yes".into()
-            } else {
-              "No".into()
-            },
-          ),
+          (HIGHLIGHT, ssh.root_login.as_str().into()),
         ]);
       } else {
         info_lines.push(vec![
@@ -204,6 +210,8 @@ impl Page for NetworkConfig {
       ]);
     }
 
+    info_lines.extend(Vpn::display_lines(installer));
+
     let info_box = InfoBox::new("", styled_block(info_lines));
     info_box.render(f, chunks[0]);
 
@@ -214,7 +222,10 @@ impl Page for NetworkConfig {
   fn get_help_content(&self) -> (String, Vec<Line<'_>>) {
     let help_content = styled_block(vec![
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "↑/↓, j/k"),
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
         (None, " - Navigate menu items"),
       ],
       vec![
@@ -222,7 +233,10 @@ impl Page for NetworkConfig {
         (None, " - Select menu item"),
       ],
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "Esc, q, ←, h"),
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().back_label,
+        ),
         (None, " - Return to main menu"),
       ],
       vec![
@@ -232,39 +246,42 @@ impl Page for NetworkConfig {
       vec![(None, "")],
       vec![(
         None,
-        "Configure network settings including backend and SSH.",
+        "Configure network settings including backend, SSH and VPN.",
       )],
     ]);
     ("Network Configuration".to_string(), help_content)
   }
 
-  fn handle_input(&mut self, _installer: &mut Installer, event: KeyEvent) -> Signal {
+  fn handle_input(&mut self, installer: &mut Installer, event: KeyEvent) -> Signal {
     match event.code {
       KeyCode::Char('?') => {
         self.help_modal.toggle();
         Signal::Wait
       }
-      ui_close!() if self.help_modal.visible => {
+      code if ui_close!(code) && self.help_modal.visible => {
         self.help_modal.hide();
         Signal::Wait
       }
       _ if self.help_modal.visible => Signal::Wait,
-      ui_back!() => Signal::Pop,
+      code if ui_back!(code) => Signal::Pop,
       KeyCode::Enter => {
         match self.menu_items.selected_idx {
-          0 => Signal::Push(Box::new(NetworkBackend::new())),
+          0 => Signal::Push(Box::new(NetworkBackend::new(
+            installer.network_backend.as_deref(),
+          ))),
           1 => Signal::Push(Box::new(SshConfig::new())),
-          2 => Signal::Pop, // Back
+          2 => Signal::Push(Box::new(Vpn::new(installer))),
+          3 => Signal::Pop, // Back
           _ => Signal::Wait,
         }
       }
-      ui_up!() => {
+      code if ui_up!(code) => {
         if !self.menu_items.previous_item() {
           self.menu_items.last_item();
         }
         Signal::Wait
       }
-      ui_down!() => {
+      code if ui_down!(code) => {
         if !self.menu_items.next_item() {
           self.menu_items.first_item();
         }
@@ -282,7 +299,7 @@ pub struct NetworkBackend {
 }
 
 impl NetworkBackend {
-  pub fn new() -> Self {
+  pub fn new(current: Option<&str>) -> Self {
     let backends = [
       "NetworkManager",
       "wpa_supplicant",
@@ -293,11 +310,15 @@ impl NetworkBackend {
     .map(|s| s.to_string())
     .collect::<Vec<_>>();
     let mut backends = StrList::new("Select Network Backend", backends);
+    backends.commit_value(current);
     backends.focus();
 
     let help_content = styled_block(vec![
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "↑/↓, j/k"),
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
         (None, " - Navigate network backend options"),
       ],
       vec![
@@ -305,7 +326,10 @@ impl NetworkBackend {
         (None, " - Select network backend and return"),
       ],
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "Esc, q, ←, h"),
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().back_label,
+        ),
         (None, " - Cancel and return"),
       ],
       vec![
@@ -457,7 +481,10 @@ impl Page for NetworkBackend {
   fn get_help_content(&self) -> (String, Vec<Line<'_>>) {
     let help_content = styled_block(vec![
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "↑/↓, j/k"),
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
         (None, " - Navigate network backend options"),
       ],
       vec![
@@ -465,7 +492,10 @@ impl Page for NetworkBackend {
         (None, " - Select network backend and return"),
       ],
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "Esc, q, ←, h"),
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().back_label,
+        ),
         (None, " - Cancel and return"),
       ],
       vec![
@@ -487,12 +517,12 @@ impl Page for NetworkBackend {
         self.help_modal.toggle();
         Signal::Wait
       }
-      ui_close!() if self.help_modal.visible => {
+      code if ui_close!(code) && self.help_modal.visible => {
         self.help_modal.hide();
         Signal::Wait
       }
       _ if self.help_modal.visible => Signal::Wait,
-      ui_back!() => Signal::Pop,
+      code if ui_back!(code) => Signal::Pop,
       KeyCode::Enter => {
         let backend = if self.backends.selected_idx == 3 {
           None
@@ -502,13 +532,13 @@ impl Page for NetworkBackend {
         installer.network_backend = backend;
         Signal::Pop
       }
-      ui_up!() => {
+      code if ui_up!(code) => {
         if !self.backends.previous_item() {
           self.backends.last_item();
         }
         Signal::Wait
       }
-      ui_down!() => {
+      code if ui_down!(code) => {
         if !self.backends.next_item() {
           self.backends.first_item();
         }
@@ -519,6 +549,14 @@ impl Page for NetworkBackend {
   }
 }
 
+/// Parse a TCP port for sshd, 0 is rejected since it isn't a real port
+pub fn parse_port(input: &str) -> Result<u16, String> {
+  match input.parse::<u16>() {
+    Ok(0) | Err(_) => Err("Port must be a number between 1 and 65535".to_string()),
+    Ok(port) => Ok(port),
+  }
+}
+
 // Simplified SSH Configuration page (no authorized keys)
 pub struct SshConfig {
   buttons: WidgetBox,
@@ -528,7 +566,7 @@ pub struct SshConfig {
   // State tracking
   enable_ssh: bool,
   password_auth: bool,
-  root_login: bool,
+  root_login: RootLogin,
   initialized: bool,
 }
 
@@ -541,7 +579,7 @@ impl SshConfig {
   pub fn new() -> Self {
     let enable_ssh = CheckBox::new("Enable SSH", false);
     let password_auth = CheckBox::new("Allow Password Authentication", true);
-    let root_login = CheckBox::new("Allow Root Login", false);
+    let root_login = Button::new(Self::root_login_label(RootLogin::No));
     let port_btn = Button::new("Configure Port");
     let back_btn = Button::new("Back");
 
@@ -558,7 +596,10 @@ impl SshConfig {
 
     let help_content = styled_block(vec![
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "↑/↓, j/k"),
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
         (None, " - Navigate options"),
       ],
       vec![
@@ -570,7 +611,10 @@ impl SshConfig {
         (None, " - Move to port input"),
       ],
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "Esc, q, ←, h"),
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().back_label,
+        ),
         (None, " - Cancel and return"),
       ],
       vec![
@@ -589,7 +633,7 @@ impl SshConfig {
       input_mode: SshInputMode::Buttons,
       enable_ssh: false,
       password_auth: true,
-      root_login: false,
+      root_login: RootLogin::No,
       initialized: false,
     }
   }
@@ -604,11 +648,18 @@ impl SshConfig {
       // Update inputs
       self.port_input.set_value(&cfg.port.to_string());
     }
+    self.rebuild_buttons();
+  }
+
+  fn root_login_label(root_login: RootLogin) -> String {
+    format!("Root Login: {}", root_login.as_str())
+  }
 
-    // Always recreate buttons with current state values
+  /// Recreate the buttons from the current state, keeping the selection
+  fn rebuild_buttons(&mut self) {
     let enable_ssh = CheckBox::new("Enable SSH", self.enable_ssh);
     let password_auth = CheckBox::new("Allow Password Authentication", self.password_auth);
-    let root_login = CheckBox::new("Allow Root Login", self.root_login);
+    let root_login = Button::new(Self::root_login_label(self.root_login));
     let port_btn = Button::new("Configure Port");
     let back_btn = Button::new("Back");
 
@@ -635,7 +686,7 @@ impl SshConfig {
           None
         }
       })
-      .and_then(|s| s.parse::<u16>().ok())
+      .and_then(|s| parse_port(s.trim()).ok())
       .unwrap_or(22);
 
     installer.ssh_config = Some(SshCfg {
@@ -675,7 +726,10 @@ impl Page for SshConfig {
       vec![(None, "")],
       vec![(HIGHLIGHT, "Security Recommendations:")],
       vec![(None, "• Use key-based authentication when possible")],
-      vec![(None, "• Disable root login for better security")],
+      vec![(
+        None,
+        "• Disable root login, or use prohibit-password to allow keys only",
+      )],
       vec![(None, "• Consider changing the default port")],
     ];
 
@@ -716,7 +770,10 @@ impl Page for SshConfig {
   fn get_help_content(&self) -> (String, Vec<Line<'_>>) {
     let help_content = styled_block(vec![
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "↑/↓, j/k"),
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
         (None, " - Navigate options"),
       ],
       vec![
@@ -728,7 +785,10 @@ impl Page for SshConfig {
         (None, " - Move to port input"),
       ],
       vec![
-        (Some((Color::Yellow, Modifier::BOLD)), "Esc, q, ←, h"),
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().back_label,
+        ),
         (None, " - Cancel and return"),
       ],
       vec![
@@ -741,18 +801,25 @@ impl Page for SshConfig {
     ("SSH Configuration".to_string(), help_content)
   }
 
+  fn handle_paste(&mut self, _installer: &mut Installer, text: &str) -> Signal {
+    if !self.help_modal.visible && matches!(self.input_mode, SshInputMode::Port) {
+      self.port_input.paste(text);
+    }
+    Signal::Wait
+  }
+
   fn handle_input(&mut self, installer: &mut Installer, event: KeyEvent) -> Signal {
     match event.code {
       KeyCode::Char('?') => {
         self.help_modal.toggle();
         Signal::Wait
       }
-      ui_close!() if self.help_modal.visible => {
+      code if ui_close!(code) && self.help_modal.visible => {
         self.help_modal.hide();
         Signal::Wait
       }
       _ if self.help_modal.visible => Signal::Wait,
-      ui_back!() => match self.input_mode {
+      code if ui_back!(code) => match self.input_mode {
         SshInputMode::Port => {
           self.input_mode = SshInputMode::Buttons;
           self.port_input.unfocus();
@@ -768,11 +835,11 @@ impl Page for SshConfig {
         match self.input_mode {
           SshInputMode::Buttons => {
             match event.code {
-              ui_up!() => {
+              code if ui_up!(code) => {
                 self.buttons.prev_child();
                 Signal::Wait
               }
-              ui_down!() => {
+              code if ui_down!(code) => {
                 self.buttons.next_child();
                 Signal::Wait
               }
@@ -799,13 +866,9 @@ impl Page for SshConfig {
                     Signal::Wait
                   }
                   Some(2) => {
-                    // Toggle Root Login checkbox
-                    if let Some(checkbox) = self.buttons.focused_child_mut() {
-                      checkbox.interact();
-                      if let Some(Value::Bool(enabled)) = checkbox.get_value() {
-                        self.root_login = enabled;
-                      }
-                    }
+                    // Cycle through no, prohibit-password and yes
+                    self.root_login = self.root_login.next();
+                    self.rebuild_buttons();
                     Signal::Wait
                   }
                   Some(3) => {
@@ -828,6 +891,17 @@ impl Page for SshConfig {
           }
           SshInputMode::Port => match event.code {
             KeyCode::Enter | KeyCode::Tab => {
+              let input = self
+                .port_input
+                .get_value()
+                .and_then(|v| v.as_str().map(|s| s.trim().to_string()))
+                .unwrap_or_default();
+              if !input.is_empty()
+                && let Err(e) = parse_port(&input)
+              {
+                self.port_input.error(e);
+                return Signal::Wait;
+              }
               // Save the current port value and return to buttons
               self.input_mode = SshInputMode::Buttons;
               self.port_input.unfocus();
@@ -0,0 +1,212 @@
+use ratatui::{
+  Frame,
+  crossterm::event::{KeyCode, KeyEvent},
+  layout::Rect,
+  style::{Color, Modifier},
+  text::Line,
+};
+use serde_json::Value;
+
+use crate::{
+  installer::{HIGHLIGHT, Installer, Page, Signal},
+  keys, split_hor, split_vert, styled_block, ui_back, ui_close, ui_down, ui_up,
+  widget::{Button, CheckBox, ConfigWidget, HelpModal, InfoBox, WidgetBox},
+};
+
+/// `overlays/default.nix`, imported by `nixpkgs.overlays` in the generated
+/// config
+///
+/// It loads every other `.nix` file next to it as an overlay, so adding one
+/// never means touching configuration.nix. With no other files it's an empty
+/// list and changes nothing.
+pub const OVERLAYS_DEFAULT_NIX: &str = r#"# Every other .nix file in this directory is loaded as a nixpkgs overlay.
+# For example, overlays/hello.nix could contain:
+#
+#   final: prev: {
+#     hello = prev.hello.overrideAttrs (old: { doCheck = false; });
+#   }
+#
+# Then run `sudo nixos-rebuild switch` to apply it.
+let
+  files = builtins.attrNames (builtins.readDir ./.);
+  isOverlay = name: name != "default.nix" && builtins.match ".*\\.nix" name != null;
+in
+map (name: import (./. + "/${name}")) (builtins.filter isOverlay files)
+"#;
+
+pub struct Overlays {
+  buttons: WidgetBox,
+  help_modal: HelpModal<'static>,
+}
+
+impl Overlays {
+  pub fn new(checked: bool) -> Self {
+    let toggle = CheckBox::new("Create overlays/ directory", checked);
+    let back_btn = Button::new("Back");
+    let mut buttons = WidgetBox::button_menu(vec![Box::new(toggle), Box::new(back_btn)]);
+    buttons.focus();
+    let help_content = styled_block(vec![
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Navigate options"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
+        (None, " - Toggle option or select Back"),
+      ],
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().back_label,
+        ),
+        (None, " - Return to menu"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "?"),
+        (None, " - Show this help"),
+      ],
+      vec![(None, "")],
+      vec![(
+        None,
+        "Any .nix file dropped into /etc/nixos/overlays is applied as an overlay on the next rebuild.",
+      )],
+    ]);
+    let help_modal = HelpModal::new("Nixpkgs Overlays", help_content);
+    Self {
+      buttons,
+      help_modal,
+    }
+  }
+  pub fn display_widget(installer: &mut Installer) -> Option<Box<dyn ConfigWidget>> {
+    if !installer.overlays_scaffold {
+      return None;
+    }
+    let ib = InfoBox::new(
+      "",
+      styled_block(vec![
+        vec![(None, "Overlays are loaded from:")],
+        vec![(HIGHLIGHT, "/etc/nixos/overlays")],
+      ]),
+    );
+    Some(Box::new(ib) as Box<dyn ConfigWidget>)
+  }
+  pub fn page_info<'a>() -> (String, Vec<Line<'a>>) {
+    (
+      "Nixpkgs Overlays".to_string(),
+      styled_block(vec![
+        vec![(
+          None,
+          "Advanced: set up /etc/nixos/overlays and point nixpkgs.overlays at it.",
+        )],
+        vec![(
+          None,
+          "It starts out empty. Overlays let you patch or replace packages without forking nixpkgs.",
+        )],
+      ]),
+    )
+  }
+}
+
+impl Default for Overlays {
+  fn default() -> Self {
+    Self::new(false)
+  }
+}
+
+impl Page for Overlays {
+  fn render(&mut self, _installer: &mut Installer, f: &mut Frame, area: Rect) {
+    let chunks = split_vert!(
+      area,
+      1,
+      [Constraint::Percentage(40), Constraint::Percentage(60)]
+    );
+    let hor_chunks = split_hor!(
+      chunks[1],
+      1,
+      [
+        Constraint::Percentage(30),
+        Constraint::Percentage(40),
+        Constraint::Percentage(30),
+      ]
+    );
+    let (_, info) = Self::page_info();
+    let info_box = InfoBox::new("", info);
+    info_box.render(f, chunks[0]);
+    self.buttons.render(f, hor_chunks[1]);
+    self.help_modal.render(f, area);
+  }
+
+  fn get_help_content(&self) -> (String, Vec<Line<'_>>) {
+    let help_content = styled_block(vec![
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Navigate options"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
+        (None, " - Toggle option or select Back"),
+      ],
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().back_label,
+        ),
+        (None, " - Return to menu"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "?"),
+        (None, " - Show this help"),
+      ],
+      vec![(None, "")],
+      vec![(
+        None,
+        "Any .nix file dropped into /etc/nixos/overlays is applied as an overlay on the next rebuild.",
+      )],
+    ]);
+    ("Nixpkgs Overlays".to_string(), help_content)
+  }
+
+  fn handle_input(&mut self, installer: &mut Installer, event: KeyEvent) -> Signal {
+    match event.code {
+      KeyCode::Char('?') => {
+        self.help_modal.toggle();
+        Signal::Wait
+      }
+      code if ui_close!(code) && self.help_modal.visible => {
+        self.help_modal.hide();
+        Signal::Wait
+      }
+      _ if self.help_modal.visible => Signal::Wait,
+      code if ui_back!(code) => Signal::Pop,
+      code if ui_up!(code) => {
+        self.buttons.prev_child();
+        Signal::Wait
+      }
+      code if ui_down!(code) => {
+        self.buttons.next_child();
+        Signal::Wait
+      }
+      KeyCode::Enter => match self.buttons.selected_child() {
+        Some(0) => {
+          let Some(chkbox) = self.buttons.focused_child_mut() else {
+            return Signal::Wait;
+          };
+          chkbox.interact();
+          if let Some(Value::Bool(checked)) = chkbox.get_value() {
+            installer.overlays_scaffold = checked;
+          }
+          Signal::Wait
+        }
+        Some(1) => Signal::Pop,
+        _ => Signal::Wait,
+      },
+      _ => Signal::Wait,
+    }
+  }
+}
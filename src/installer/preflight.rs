@@ -0,0 +1,526 @@
+use std::{
+  net::{TcpStream, ToSocketAddrs},
+  sync::{Arc, Mutex},
+  thread,
+  time::Duration,
+};
+
+use ratatui::{
+  Frame,
+  crossterm::event::{KeyCode, KeyEvent},
+  layout::Rect,
+  style::{Color, Modifier},
+  text::Line,
+};
+
+use crate::{
+  command,
+  installer::{HIGHLIGHT, Installer, Page, Signal, channel, wifi::WifiSetup},
+  keys, split_hor, split_vert, styled_block,
+  tools::Tool,
+  ui_back, ui_close, ui_down, ui_up,
+  widget::{Button, ConfigWidget, HelpModal, InfoBox, WidgetBox},
+};
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+type CheckResults = Vec<(String, Result<(), String>)>;
+
+/// Hosts the install needs to reach, the binary cache plus any configured
+/// substituters
+///
+/// Without a source flake the channel comes from nixos.org, and nixpkgs, disko
+/// and home-manager are fetched from GitHub.
+pub fn preflight_hosts(installer: &Installer) -> Vec<String> {
+  let mut hosts = vec!["https://cache.nixos.org".to_string()];
+  hosts.extend(installer.substituters.iter().map(|(url, _)| url.clone()));
+  if installer.flake_path.is_none() {
+    hosts.push(channel::channel_url(installer.channel()));
+    hosts.push("https://github.com".to_string());
+  }
+  hosts.dedup();
+  hosts
+}
+
+/// Split a substituter URL like `https://cache.example.org:8443/path` into a
+/// host and port we can open a TCP connection to
+fn host_port(url: &str) -> (String, u16) {
+  let (default_port, rest) = match url.split_once("://") {
+    Some(("http", rest)) => (80, rest),
+    Some((_, rest)) => (443, rest),
+    None => (443, url),
+  };
+  let authority = rest.split('/').next().unwrap_or(rest);
+  match authority.rsplit_once(':') {
+    Some((host, port)) => match port.parse() {
+      Ok(port) => (host.to_string(), port),
+      Err(_) => (authority.to_string(), default_port),
+    },
+    None => (authority.to_string(), default_port),
+  }
+}
+
+fn check_host(url: &str) -> Result<(), String> {
+  let (host, port) = host_port(url);
+  let addr = (host.as_str(), port)
+    .to_socket_addrs()
+    .map_err(|e| format!("could not resolve {host}: {e}"))?
+    .next()
+    .ok_or_else(|| format!("no addresses found for {host}"))?;
+  TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT)
+    .map(|_| ())
+    .map_err(|e| format!("could not connect to {host}:{port}: {e}"))
+}
+
+enum PreflightState {
+  /// Checks will be started on the next render
+  Pending,
+  Checking,
+  /// All hosts reachable, install is about to start
+  Passed,
+  /// We've already handed off to the install, just get out of the way
+  Done,
+  Failed,
+}
+
+/// Connectivity check run between "Begin Installation" and the actual install
+///
+/// The checks run on a background thread so the UI stays responsive while we
+/// wait on timeouts.
+pub struct Preflight {
+  hosts: Vec<String>,
+  results: Arc<Mutex<Option<CheckResults>>>,
+  state: PreflightState,
+  failures: Vec<(String, String)>,
+  buttons: WidgetBox,
+  help_modal: HelpModal<'static>,
+}
+
+impl Preflight {
+  pub fn new(hosts: Vec<String>) -> Self {
+    let mut buttons = WidgetBox::button_menu(vec![
+      Box::new(Button::new("Retry")) as Box<dyn ConfigWidget>,
+      Box::new(Button::new("Join Wi-Fi")),
+      Box::new(Button::new("Set up network (nmtui)")),
+      Box::new(Button::new("Install anyway")),
+      Box::new(Button::new("Back")),
+    ]);
+    buttons.focus();
+    let help_content = styled_block(vec![
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Navigate options"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
+        (None, " - Select option"),
+      ],
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().back_label,
+        ),
+        (None, " - Return to the config preview"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "?"),
+        (None, " - Show this help"),
+      ],
+      vec![(None, "")],
+      vec![(
+        None,
+        "The installer needs network access to download packages.",
+      )],
+    ]);
+    let help_modal = HelpModal::new("Network Check", help_content);
+    Self {
+      hosts,
+      results: Arc::new(Mutex::new(None)),
+      state: PreflightState::Pending,
+      failures: vec![],
+      buttons,
+      help_modal,
+    }
+  }
+
+  fn start_checks(&mut self) {
+    self.state = PreflightState::Checking;
+    self.failures.clear();
+    let hosts = self.hosts.clone();
+    let results = Arc::new(Mutex::new(None));
+    self.results = results.clone();
+    thread::spawn(move || {
+      // All at once, so a dead network costs one timeout rather than one per
+      // host
+      let checked = thread::scope(|s| {
+        let handles = hosts
+          .iter()
+          .map(|host| s.spawn(move || check_host(host)))
+          .collect::<Vec<_>>();
+        hosts
+          .iter()
+          .zip(handles)
+          .map(|(host, handle)| {
+            let res = handle
+              .join()
+              .unwrap_or_else(|_| Err("check panicked".to_string()));
+            (host.clone(), res)
+          })
+          .collect()
+      });
+      if let Ok(mut guard) = results.lock() {
+        *guard = Some(checked);
+      }
+    });
+  }
+
+  fn poll_results(&mut self) {
+    let Some(results) = self.results.lock().ok().and_then(|mut guard| guard.take()) else {
+      return;
+    };
+    self.failures = results
+      .into_iter()
+      .filter_map(|(host, res)| res.err().map(|e| (host, e)))
+      .collect();
+    if self.failures.is_empty() {
+      self.state = PreflightState::Passed;
+    } else {
+      log::warn!("Network preflight failed: {:?}", self.failures);
+      self.state = PreflightState::Failed;
+    }
+  }
+
+  fn info_lines(&self) -> Vec<Line<'static>> {
+    match self.state {
+      PreflightState::Pending
+      | PreflightState::Checking
+      | PreflightState::Passed
+      | PreflightState::Done => {
+        let mut lines = vec![vec![(None, "Checking network connectivity...".to_string())]];
+        for host in &self.hosts {
+          lines.push(vec![(None, " - ".to_string()), (HIGHLIGHT, host.clone())]);
+        }
+        styled_block(lines)
+      }
+      PreflightState::Failed => {
+        let mut lines = vec![
+          vec![(
+            Some((Color::Red, Modifier::BOLD)),
+            "The installer could not reach the following hosts:".to_string(),
+          )],
+          vec![(None, String::new())],
+        ];
+        for (host, err) in &self.failures {
+          lines.push(vec![(None, " - ".to_string()), (HIGHLIGHT, host.clone())]);
+          lines.push(vec![(None, format!("   {err}"))]);
+        }
+        lines.push(vec![(None, String::new())]);
+        lines.push(vec![(
+          None,
+          "Without network access nixos-install will fail while downloading packages. Connect to a network and retry, or continue anyway if everything needed is already available locally.".to_string(),
+        )]);
+        styled_block(lines)
+      }
+    }
+  }
+}
+
+impl Page for Preflight {
+  fn render(&mut self, _installer: &mut Installer, f: &mut Frame, area: Rect) {
+    match self.state {
+      PreflightState::Pending => self.start_checks(),
+      PreflightState::Checking => self.poll_results(),
+      // If we're being drawn again after passing, the install page has been
+      // popped off the stack and we should go with it
+      PreflightState::Passed => self.state = PreflightState::Done,
+      _ => {}
+    }
+
+    let chunks = split_vert!(
+      area,
+      1,
+      [Constraint::Percentage(60), Constraint::Percentage(40)]
+    );
+    let hor_chunks = split_hor!(
+      chunks[1],
+      1,
+      [
+        Constraint::Percentage(30),
+        Constraint::Percentage(40),
+        Constraint::Percentage(30),
+      ]
+    );
+    let info_box = InfoBox::new("Network Check", self.info_lines());
+    info_box.render(f, chunks[0]);
+    if let PreflightState::Failed = self.state {
+      self.buttons.render(f, hor_chunks[1]);
+    }
+    self.help_modal.render(f, area);
+  }
+
+  fn signal(&self) -> Option<Signal> {
+    match self.state {
+      PreflightState::Passed => Some(Signal::WriteCfg),
+      PreflightState::Done => Some(Signal::Pop),
+      _ => None,
+    }
+  }
+
+  fn get_help_content(&self) -> (String, Vec<Line<'_>>) {
+    let help_content = styled_block(vec![
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Navigate options"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
+        (None, " - Select option"),
+      ],
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().back_label,
+        ),
+        (None, " - Return to the config preview"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "?"),
+        (None, " - Show this help"),
+      ],
+      vec![(None, "")],
+      vec![(
+        None,
+        "The installer needs network access to download packages.",
+      )],
+    ]);
+    ("Network Check".to_string(), help_content)
+  }
+
+  fn handle_input(&mut self, _installer: &mut Installer, event: KeyEvent) -> Signal {
+    match event.code {
+      KeyCode::Char('?') => {
+        self.help_modal.toggle();
+        Signal::Wait
+      }
+      code if ui_close!(code) && self.help_modal.visible => {
+        self.help_modal.hide();
+        Signal::Wait
+      }
+      _ if self.help_modal.visible => Signal::Wait,
+      code if ui_back!(code) => Signal::Pop,
+      _ if !matches!(self.state, PreflightState::Failed) => Signal::Wait,
+      code if ui_up!(code) => {
+        self.buttons.prev_child();
+        Signal::Wait
+      }
+      code if ui_down!(code) => {
+        self.buttons.next_child();
+        Signal::Wait
+      }
+      KeyCode::Enter => match self.buttons.selected_child() {
+        Some(0) => {
+          self.state = PreflightState::Pending;
+          Signal::Wait
+        }
+        Some(1) => {
+          // Re-check once the user comes back
+          self.state = PreflightState::Pending;
+          Signal::Push(Box::new(WifiSetup::new()))
+        }
+        Some(2) => {
+          self.state = PreflightState::Pending;
+          Signal::Suspend(command!("nmtui"))
+        }
+        Some(3) => {
+          self.state = PreflightState::Passed;
+          Signal::WriteCfg
+        }
+        Some(4) => Signal::Pop,
+        _ => Signal::Wait,
+      },
+      _ => Signal::Wait,
+    }
+  }
+}
+
+/// Lists programs the installer needs but can't find
+///
+/// At startup this is only a heads up. Before installing it's `blocking`, and
+/// the only way out is back to the menu.
+pub struct MissingTools {
+  missing: Vec<&'static Tool>,
+  blocking: bool,
+  buttons: WidgetBox,
+  help_modal: HelpModal<'static>,
+}
+
+impl MissingTools {
+  pub fn new(missing: Vec<&'static Tool>, blocking: bool) -> Self {
+    let buttons: Vec<Box<dyn ConfigWidget>> = if blocking {
+      vec![Box::new(Button::new("Back to menu"))]
+    } else {
+      vec![
+        Box::new(Button::new("Continue")),
+        Box::new(Button::new("Quit")),
+      ]
+    };
+    let mut buttons = WidgetBox::button_menu(buttons);
+    buttons.focus();
+    let help_content = styled_block(vec![
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Navigate options"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
+        (None, " - Select option"),
+      ],
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().back_label,
+        ),
+        (None, " - Return to menu"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "?"),
+        (None, " - Show this help"),
+      ],
+      vec![(None, "")],
+      vec![(
+        None,
+        "These programs come with the NixOS installer ISO. Elsewhere they have to be installed before running the installer.",
+      )],
+    ]);
+    let help_modal = HelpModal::new("Missing Programs", help_content);
+    Self {
+      missing,
+      blocking,
+      buttons,
+      help_modal,
+    }
+  }
+
+  fn info_lines(&self) -> Vec<Line<'static>> {
+    let intro = if self.blocking {
+      "The install can't start, these programs were not found:"
+    } else {
+      "Some programs the installer relies on were not found:"
+    };
+    let mut lines = vec![
+      vec![(Some((Color::Red, Modifier::BOLD)), intro.to_string())],
+      vec![(None, String::new())],
+    ];
+    for tool in &self.missing {
+      lines.push(vec![
+        (None, " - ".to_string()),
+        (HIGHLIGHT, tool.name.to_string()),
+        (None, format!(", needed for {}", tool.needed_for)),
+      ]);
+      lines.push(vec![(None, format!("   {}", tool.hint))]);
+    }
+    lines.push(vec![(None, String::new())]);
+    let outro = if self.blocking {
+      "Install them and try again, your settings are kept."
+    } else if self.missing.iter().any(|tool| tool.install) {
+      "You can still go through the settings, but installing will be refused until they are available."
+    } else {
+      "You can continue, only the features listed above are affected."
+    };
+    lines.push(vec![(None, outro.to_string())]);
+    styled_block(lines)
+  }
+}
+
+impl Page for MissingTools {
+  fn render(&mut self, _installer: &mut Installer, f: &mut Frame, area: Rect) {
+    let chunks = split_vert!(
+      area,
+      1,
+      [Constraint::Percentage(60), Constraint::Percentage(40)]
+    );
+    let hor_chunks = split_hor!(
+      chunks[1],
+      1,
+      [
+        Constraint::Percentage(30),
+        Constraint::Percentage(40),
+        Constraint::Percentage(30),
+      ]
+    );
+    let info_box = InfoBox::new("Missing Programs", self.info_lines());
+    info_box.render(f, chunks[0]);
+    self.buttons.render(f, hor_chunks[1]);
+    self.help_modal.render(f, area);
+  }
+
+  fn get_help_content(&self) -> (String, Vec<Line<'_>>) {
+    let help_content = styled_block(vec![
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Navigate options"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
+        (None, " - Select option"),
+      ],
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().back_label,
+        ),
+        (None, " - Return to menu"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "?"),
+        (None, " - Show this help"),
+      ],
+      vec![(None, "")],
+      vec![(
+        None,
+        "These programs come with the NixOS installer ISO. Elsewhere they have to be installed before running the installer.",
+      )],
+    ]);
+    ("Missing Programs".to_string(), help_content)
+  }
+
+  fn handle_input(&mut self, _installer: &mut Installer, event: KeyEvent) -> Signal {
+    match event.code {
+      KeyCode::Char('?') => {
+        self.help_modal.toggle();
+        Signal::Wait
+      }
+      code if ui_close!(code) && self.help_modal.visible => {
+        self.help_modal.hide();
+        Signal::Wait
+      }
+      _ if self.help_modal.visible => Signal::Wait,
+      code if ui_back!(code) => Signal::Pop,
+      code if ui_up!(code) => {
+        self.buttons.prev_child();
+        Signal::Wait
+      }
+      code if ui_down!(code) => {
+        self.buttons.next_child();
+        Signal::Wait
+      }
+      KeyCode::Enter => match self.buttons.selected_child() {
+        Some(1) if !self.blocking => Signal::Quit,
+        _ => Signal::Pop,
+      },
+      _ => Signal::Wait,
+    }
+  }
+}
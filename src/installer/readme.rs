@@ -0,0 +1,306 @@
+use ratatui::{
+  Frame,
+  crossterm::event::{KeyCode, KeyEvent},
+  layout::Rect,
+  style::{Color, Modifier},
+  text::Line,
+};
+use serde_json::Value;
+
+use crate::{
+  installer::{HIGHLIGHT, Installer, Page, Signal, extramodules::is_flake_ref},
+  keys, split_hor, split_vert, styled_block, ui_back, ui_close, ui_down, ui_up,
+  widget::{Button, CheckBox, ConfigWidget, HelpModal, InfoBox, WidgetBox},
+};
+
+/// Compose the `README.md` placed next to the generated configuration
+///
+/// This is written for someone who has never used NixOS before, so it sticks
+/// to plain Markdown and explains the basics before listing what was chosen.
+pub fn generate_readme(installer: &Installer) -> String {
+  let hostname = installer.hostname.as_deref().unwrap_or("nixos");
+  let mut out = String::new();
+
+  out.push_str(&format!("# NixOS configuration for {hostname}\n\n"));
+  out.push_str(
+    "This directory was generated by nixos-wizard during installation. \
+     Everything about the system, from installed packages to user accounts, \
+     is described by the files here.\n\n",
+  );
+
+  out.push_str("## Files\n\n");
+  out
+    .push_str("- `configuration.nix`: the system configuration. This is the file you will edit.\n");
+  out.push_str(
+    "- `hardware-configuration.nix`: generated by `nixos-generate-config --no-filesystems`. \
+     It describes your kernel modules and other hardware. You normally don't edit it by hand.\n",
+  );
+  out.push_str(
+    "- `disko-config.nix`: the disk layout, which also declares the filesystems. \
+     `configuration.nix` imports it next to `hardware-configuration.nix`.\n",
+  );
+  if installer.enable_flakes && installer.flake_path.is_none() {
+    out.push_str(
+      "- `flake.nix`: the entry point `nixos-rebuild` uses. It pins nixpkgs in \
+       `flake.lock` and pulls in the disko module and `configuration.nix`.\n",
+    );
+  }
+  if installer.overlays_scaffold {
+    out.push_str(
+      "- `overlays/`: nixpkgs overlays. Every `.nix` file in it except `default.nix` \
+       is applied on the next rebuild.\n",
+    );
+  }
+  out.push_str("- `README.md`: this file.\n\n");
+
+  out.push_str("## Applying changes\n\n");
+  out.push_str("After editing `configuration.nix`, build and switch to the new system with:\n\n");
+  match installer.flake_path {
+    Some(ref flake) if is_flake_ref(flake) => {
+      out.push_str("```sh\n");
+      out.push_str(&format!("sudo nixos-rebuild switch --flake {flake}\n"));
+      out.push_str("```\n\n");
+      out.push_str(&format!(
+        "This system was installed from the flake `{flake}`, so changes made there \
+         take precedence over the files in this directory.\n\n"
+      ));
+    }
+    // A local flake path only existed on the installer, so point at the copy
+    // that belongs in /etc/nixos
+    Some(ref flake) => {
+      out.push_str("```sh\n");
+      out.push_str(&format!(
+        "sudo nixos-rebuild switch --flake /etc/nixos#{hostname}\n"
+      ));
+      out.push_str("```\n\n");
+      out.push_str(&format!(
+        "This system was installed from the flake at `{flake}` on the installer. \
+         Copy that flake into this directory to keep making changes to it.\n\n"
+      ));
+    }
+    None if installer.enable_flakes => {
+      out.push_str("```sh\n");
+      out.push_str(&format!(
+        "sudo nixos-rebuild switch --flake /etc/nixos#{hostname}\n"
+      ));
+      out.push_str("```\n\n");
+    }
+    None => {
+      out.push_str("```sh\nsudo nixos-rebuild switch\n```\n\n");
+    }
+  }
+  out.push_str(
+    "Use `nixos-rebuild test` to try a change without making it the boot default, \
+     or `nixos-rebuild boot` to apply it on the next reboot. Every rebuild adds a new \
+     entry to the boot menu, so you can always boot back into a previous generation \
+     if something goes wrong.\n\n",
+  );
+  if installer.enable_flakes && installer.flake_path.is_none() {
+    out.push_str("To update installed packages, update the flake inputs and rebuild:\n\n");
+    out.push_str(&format!(
+      "```sh\ncd /etc/nixos\nsudo nix flake update\nsudo nixos-rebuild switch --flake /etc/nixos#{hostname}\n```\n\n"
+    ));
+  } else {
+    out.push_str("To update installed packages, refresh the channel and rebuild:\n\n");
+    out.push_str("```sh\nsudo nix-channel --update\nsudo nixos-rebuild switch\n```\n\n");
+  }
+  out.push_str(
+    "Available options are documented at https://search.nixos.org/options, or locally \
+     with `man configuration.nix`.\n\n",
+  );
+
+  out.push_str("## What the installer configured\n\n");
+  for (label, value) in installer.summary() {
+    out.push_str(&format!("- {label}: {value}\n"));
+  }
+  out.push('\n');
+
+  if !installer.system_pkgs.is_empty() {
+    out.push_str("### System packages\n\n");
+    for pkg in &installer.system_pkgs {
+      out.push_str(&format!("- `{pkg}`\n"));
+    }
+    out.push('\n');
+  }
+
+  out
+}
+
+pub struct ConfigReadme {
+  buttons: WidgetBox,
+  help_modal: HelpModal<'static>,
+}
+
+impl ConfigReadme {
+  pub fn new(checked: bool) -> Self {
+    let toggle = CheckBox::new("Write README.md", checked);
+    let back_btn = Button::new("Back");
+    let mut buttons = WidgetBox::button_menu(vec![Box::new(toggle), Box::new(back_btn)]);
+    buttons.focus();
+    let help_content = styled_block(vec![
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Navigate options"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
+        (None, " - Toggle option or select Back"),
+      ],
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().back_label,
+        ),
+        (None, " - Return to menu"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "?"),
+        (None, " - Show this help"),
+      ],
+      vec![(None, "")],
+      vec![(
+        None,
+        "Write a README.md into /etc/nixos explaining the generated configuration.",
+      )],
+    ]);
+    let help_modal = HelpModal::new("Config README", help_content);
+    Self {
+      buttons,
+      help_modal,
+    }
+  }
+  pub fn display_widget(installer: &mut Installer) -> Option<Box<dyn ConfigWidget>> {
+    let status = if installer.skip_readme {
+      "disabled"
+    } else {
+      "enabled"
+    };
+    let ib = InfoBox::new(
+      "",
+      styled_block(vec![
+        vec![(None, "Writing a README is currently:")],
+        vec![(HIGHLIGHT, status)],
+      ]),
+    );
+    Some(Box::new(ib) as Box<dyn ConfigWidget>)
+  }
+  pub fn page_info<'a>() -> (String, Vec<Line<'a>>) {
+    (
+      "Config README".to_string(),
+      styled_block(vec![
+        vec![(
+          None,
+          "Write a README.md into /etc/nixos on the installed system.",
+        )],
+        vec![(
+          None,
+          "It explains how to rebuild the system, what each file is for, and lists the settings chosen in this installer.",
+        )],
+      ]),
+    )
+  }
+}
+
+impl Default for ConfigReadme {
+  fn default() -> Self {
+    Self::new(true)
+  }
+}
+
+impl Page for ConfigReadme {
+  fn render(&mut self, _installer: &mut Installer, f: &mut Frame, area: Rect) {
+    let chunks = split_vert!(
+      area,
+      1,
+      [Constraint::Percentage(40), Constraint::Percentage(60)]
+    );
+    let hor_chunks = split_hor!(
+      chunks[1],
+      1,
+      [
+        Constraint::Percentage(30),
+        Constraint::Percentage(40),
+        Constraint::Percentage(30),
+      ]
+    );
+    let (_, info) = Self::page_info();
+    let info_box = InfoBox::new("", info);
+    info_box.render(f, chunks[0]);
+    self.buttons.render(f, hor_chunks[1]);
+    self.help_modal.render(f, area);
+  }
+
+  fn get_help_content(&self) -> (String, Vec<Line<'_>>) {
+    let help_content = styled_block(vec![
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Navigate options"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
+        (None, " - Toggle option or select Back"),
+      ],
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().back_label,
+        ),
+        (None, " - Return to menu"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "?"),
+        (None, " - Show this help"),
+      ],
+      vec![(None, "")],
+      vec![(
+        None,
+        "Write a README.md into /etc/nixos explaining the generated configuration.",
+      )],
+    ]);
+    ("Config README".to_string(), help_content)
+  }
+
+  fn handle_input(&mut self, installer: &mut Installer, event: KeyEvent) -> Signal {
+    match event.code {
+      KeyCode::Char('?') => {
+        self.help_modal.toggle();
+        Signal::Wait
+      }
+      code if ui_close!(code) && self.help_modal.visible => {
+        self.help_modal.hide();
+        Signal::Wait
+      }
+      _ if self.help_modal.visible => Signal::Wait,
+      code if ui_back!(code) => Signal::Pop,
+      code if ui_up!(code) => {
+        self.buttons.prev_child();
+        Signal::Wait
+      }
+      code if ui_down!(code) => {
+        self.buttons.next_child();
+        Signal::Wait
+      }
+      KeyCode::Enter => match self.buttons.selected_child() {
+        Some(0) => {
+          let Some(chkbox) = self.buttons.focused_child_mut() else {
+            return Signal::Wait;
+          };
+          chkbox.interact();
+          if let Some(Value::Bool(checked)) = chkbox.get_value() {
+            installer.skip_readme = !checked;
+          }
+          Signal::Wait
+        }
+        Some(1) => Signal::Pop,
+        _ => Signal::Wait,
+      },
+      _ => Signal::Wait,
+    }
+  }
+}
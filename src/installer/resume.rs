@@ -0,0 +1,283 @@
+use std::fmt::Display;
+
+use ratatui::{
+  Frame,
+  crossterm::event::{KeyCode, KeyEvent},
+  layout::Rect,
+  style::{Color, Modifier},
+  text::Line,
+};
+
+use crate::{
+  drives::{Disk, PartStatus, bytes_readable},
+  installer::{HIGHLIGHT, Installer, Page, Signal, drivepages::Drives},
+  keys, split_hor, split_vert, styled_block, ui_close, ui_down, ui_up,
+  widget::{Button, ConfigWidget, HelpModal, InfoBox, WidgetBox},
+};
+
+/// A difference between the drive config in a loaded file and the hardware
+/// that is actually present now
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Mismatch {
+  DeviceMissing {
+    device: String,
+  },
+  SizeChanged {
+    device: String,
+    saved: u64,   // bytes
+    current: u64, // bytes
+  },
+  SectorSizeChanged {
+    device: String,
+    saved: u64,
+    current: u64,
+  },
+  PartitionChanged {
+    device: String,
+    start: u64, // sectors
+  },
+}
+
+impl Display for Mismatch {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Mismatch::DeviceMissing { device } => write!(f, "/dev/{device} is no longer present"),
+      Mismatch::SizeChanged {
+        device,
+        saved,
+        current,
+      } => write!(
+        f,
+        "/dev/{device} changed size: {} saved, {} now",
+        bytes_readable(*saved),
+        bytes_readable(*current)
+      ),
+      Mismatch::SectorSizeChanged {
+        device,
+        saved,
+        current,
+      } => write!(
+        f,
+        "/dev/{device} changed sector size: {saved} saved, {current} now"
+      ),
+      Mismatch::PartitionChanged { device, start } => write!(
+        f,
+        "/dev/{device}: existing partition at sector {start} no longer matches"
+      ),
+    }
+  }
+}
+
+/// Compare the drive config of a loaded installer against the disks found on
+/// this machine
+///
+/// Partitions the saved config expects to already exist (anything not marked
+/// for creation) must still be present at the same start and size, otherwise
+/// the saved plan would operate on the wrong data.
+pub fn reconcile(loaded: &Installer, current_disks: &[Disk]) -> Vec<Mismatch> {
+  loaded
+    .all_disks()
+    .flat_map(|saved| reconcile_disk(saved, current_disks))
+    .collect()
+}
+
+/// Compare a single saved disk layout against the disks on this machine
+pub fn reconcile_disk(saved: &Disk, current_disks: &[Disk]) -> Vec<Mismatch> {
+  let device = saved.name().to_string();
+  let Some(current) = current_disks.iter().find(|d| d.name() == saved.name()) else {
+    return vec![Mismatch::DeviceMissing { device }];
+  };
+
+  let mut mismatches = vec![];
+  if saved.sector_size() != current.sector_size() {
+    mismatches.push(Mismatch::SectorSizeChanged {
+      device: device.clone(),
+      saved: saved.sector_size(),
+      current: current.sector_size(),
+    });
+  }
+  if saved.size_bytes() != current.size_bytes() {
+    mismatches.push(Mismatch::SizeChanged {
+      device: device.clone(),
+      saved: saved.size_bytes(),
+      current: current.size_bytes(),
+    });
+  }
+  for part in saved.partitions() {
+    if *part.status() == PartStatus::Create {
+      continue;
+    }
+    let still_there = current
+      .partitions()
+      .any(|p| p.start() == part.start() && p.size() == part.size());
+    if !still_there {
+      mismatches.push(Mismatch::PartitionChanged {
+        device: device.clone(),
+        start: part.start(),
+      });
+    }
+  }
+  mismatches
+}
+
+/// Shown on startup when a loaded config no longer matches the hardware
+pub struct ResumeReport {
+  mismatches: Vec<Mismatch>,
+  current_disks: Vec<Disk>,
+  buttons: WidgetBox,
+  help_modal: HelpModal<'static>,
+  signal: Option<Signal>,
+}
+
+impl ResumeReport {
+  pub fn new(mismatches: Vec<Mismatch>, current_disks: Vec<Disk>) -> Self {
+    let mut buttons = WidgetBox::button_menu(vec![
+      Box::new(Button::new("Re-run drive configuration")) as Box<dyn ConfigWidget>,
+      Box::new(Button::new("Keep loaded drive configuration")) as Box<dyn ConfigWidget>,
+    ]);
+    buttons.focus();
+    let help_content = styled_block(vec![
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Navigate options"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
+        (None, " - Select option"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "?"),
+        (None, " - Show this help"),
+      ],
+      vec![(None, "")],
+      vec![(
+        None,
+        "The loaded configuration was saved on different hardware.",
+      )],
+    ]);
+    let help_modal = HelpModal::new("Saved Config Mismatch", help_content);
+    Self {
+      mismatches,
+      current_disks,
+      buttons,
+      help_modal,
+      signal: None,
+    }
+  }
+}
+
+impl Page for ResumeReport {
+  fn render(&mut self, _installer: &mut Installer, f: &mut Frame, area: Rect) {
+    let chunks = split_vert!(
+      area,
+      1,
+      [Constraint::Percentage(60), Constraint::Percentage(40)]
+    );
+    let hor_chunks = split_hor!(
+      chunks[1],
+      1,
+      [
+        Constraint::Percentage(25),
+        Constraint::Percentage(50),
+        Constraint::Percentage(25),
+      ]
+    );
+
+    let mut lines = vec![
+      vec![(
+        None,
+        "The drive configuration in the loaded file does not match the disks on this machine:"
+          .to_string(),
+      )],
+      vec![(None, String::new())],
+    ];
+    for mismatch in &self.mismatches {
+      lines.push(vec![
+        (None, " - ".to_string()),
+        (HIGHLIGHT, mismatch.to_string()),
+      ]);
+    }
+    lines.push(vec![(None, String::new())]);
+    lines.push(vec![(
+      Some((Color::Red, Modifier::BOLD)),
+      "Keeping the loaded layout may wipe or mount the wrong data.".to_string(),
+    )]);
+    let info_box = InfoBox::new("Saved Config Mismatch", styled_block(lines));
+    info_box.render(f, chunks[0]);
+    self.buttons.render(f, hor_chunks[1]);
+    self.help_modal.render(f, area);
+  }
+
+  fn signal(&self) -> Option<Signal> {
+    // Once we've sent the user to the drive pages, close as soon as they return
+    self.signal.as_ref().map(|_| Signal::Pop)
+  }
+
+  fn get_help_content(&self) -> (String, Vec<Line<'_>>) {
+    let help_content = styled_block(vec![
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Navigate options"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
+        (None, " - Select option"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "?"),
+        (None, " - Show this help"),
+      ],
+      vec![(None, "")],
+      vec![(
+        None,
+        "The loaded configuration was saved on different hardware.",
+      )],
+    ]);
+    ("Saved Config Mismatch".to_string(), help_content)
+  }
+
+  fn handle_input(&mut self, installer: &mut Installer, event: KeyEvent) -> Signal {
+    match event.code {
+      KeyCode::Char('?') => {
+        self.help_modal.toggle();
+        Signal::Wait
+      }
+      code if ui_close!(code) && self.help_modal.visible => {
+        self.help_modal.hide();
+        Signal::Wait
+      }
+      _ if self.help_modal.visible => Signal::Wait,
+      code if ui_up!(code) => {
+        self.buttons.prev_child();
+        Signal::Wait
+      }
+      code if ui_down!(code) => {
+        self.buttons.next_child();
+        Signal::Wait
+      }
+      KeyCode::Enter => match self.buttons.selected_child() {
+        Some(0) => {
+          installer.drives = std::mem::take(&mut self.current_disks);
+          installer.drive_config = None;
+          installer.extra_disks.clear();
+          installer.storage_teardown.clear();
+          installer.make_drive_config_display();
+          self.signal = Some(Signal::Pop);
+          Signal::Push(Box::new(Drives::new()))
+        }
+        Some(1) => {
+          installer.drives = std::mem::take(&mut self.current_disks);
+          Signal::Pop
+        }
+        _ => Signal::Wait,
+      },
+      _ => Signal::Wait,
+    }
+  }
+}
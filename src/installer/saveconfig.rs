@@ -0,0 +1,219 @@
+use std::{
+  fs::OpenOptions,
+  io::{self, Write},
+  os::unix::fs::OpenOptionsExt,
+  path::Path,
+};
+
+use ratatui::{
+  Frame,
+  crossterm::event::{KeyCode, KeyEvent},
+  layout::Rect,
+  style::{Color, Modifier},
+  text::Line,
+};
+
+use crate::{
+  installer::{Installer, Page, Signal},
+  split_hor, split_vert, styled_block, ui_close,
+  widget::{ConfigWidget, HelpModal, InfoBox, LineEditor},
+};
+
+/// Write `contents` readable by root only, saved configs hold password hashes
+///
+/// Whatever is at `path` is removed and a fresh file created in its place, so
+/// a symlink or an existing file with looser permissions is never written
+/// through. If something else recreates the path in between, this fails.
+pub fn write_private(path: impl AsRef<Path>, contents: &str) -> io::Result<()> {
+  let path = path.as_ref();
+  match std::fs::remove_file(path) {
+    Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+    _ => {}
+  }
+  let mut file = OpenOptions::new()
+    .write(true)
+    .create_new(true)
+    .custom_flags(nix::fcntl::OFlag::O_NOFOLLOW.bits())
+    .mode(0o600)
+    .open(path)?;
+  file.write_all(contents.as_bytes())
+}
+
+/// Write the whole installer state to `path`, for `--load` to pick up later
+pub fn save_installer(installer: &Installer, path: &str) -> anyhow::Result<()> {
+  let json = serde_json::to_string_pretty(installer)?;
+  write_private(path, &json).map_err(|e| anyhow::anyhow!("Failed to write '{path}': {e}"))
+}
+
+/// Read a file written by [`save_installer`]
+pub fn load_installer(path: &str) -> anyhow::Result<Installer> {
+  let contents = std::fs::read_to_string(path)
+    .map_err(|e| anyhow::anyhow!("Failed to read saved config '{path}': {e}"))?;
+  Installer::from_json(serde_json::from_str(&contents)?)
+}
+
+/// Asks for a path and saves the current settings there
+pub struct SaveConfig {
+  input: LineEditor,
+  help_modal: HelpModal<'static>,
+}
+
+impl SaveConfig {
+  pub fn new(installer: &Installer) -> Self {
+    let mut input = LineEditor::new("Save To", Some("e.g. /mnt/usb/nixos-wizard.json"));
+    if let Some(ref path) = installer.save_path {
+      input.set_value(path);
+    }
+    input.focus();
+    let help_content = styled_block(vec![
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
+        (None, " - Save settings and return"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Esc"),
+        (None, " - Cancel and return to menu"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "?"),
+        (None, " - Show this help"),
+      ],
+      vec![(None, "")],
+      vec![(None, "Resume later with: nixos-wizard --load <path>")],
+    ]);
+    let help_modal = HelpModal::new("Save Config", help_content);
+    Self { input, help_modal }
+  }
+  fn info<'a>() -> Vec<Line<'a>> {
+    styled_block(vec![
+      vec![(None, "Save every setting made so far to a JSON file.")],
+      vec![(
+        None,
+        "The live system forgets everything on reboot, so pick a USB stick or another persistent disk.",
+      )],
+      vec![(None, "Resume later with: nixos-wizard --load <path>")],
+      vec![(
+        None,
+        "The file contains password hashes and is only readable by root.",
+      )],
+    ])
+  }
+}
+
+impl Page for SaveConfig {
+  fn render(&mut self, _installer: &mut Installer, f: &mut Frame, area: Rect) {
+    let chunks = split_vert!(
+      area,
+      1,
+      [
+        Constraint::Percentage(40),
+        Constraint::Length(5),
+        Constraint::Percentage(40),
+      ]
+    );
+    let hor_chunks = split_hor!(
+      chunks[1],
+      0,
+      [
+        Constraint::Percentage(10),
+        Constraint::Percentage(80),
+        Constraint::Percentage(10),
+      ]
+    );
+    InfoBox::new("", Self::info()).render(f, chunks[0]);
+    self.input.render(f, hor_chunks[1]);
+    self.help_modal.render(f, area);
+  }
+
+  fn get_help_content(&self) -> (String, Vec<Line<'_>>) {
+    let help_content = styled_block(vec![
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
+        (None, " - Save settings and return"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Esc"),
+        (None, " - Cancel and return to menu"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "?"),
+        (None, " - Show this help"),
+      ],
+      vec![(None, "")],
+      vec![(None, "Resume later with: nixos-wizard --load <path>")],
+    ]);
+    ("Save Config".to_string(), help_content)
+  }
+
+  fn handle_paste(&mut self, _installer: &mut Installer, text: &str) -> Signal {
+    if !self.help_modal.visible {
+      self.input.paste(text);
+    }
+    Signal::Wait
+  }
+
+  fn handle_input(&mut self, installer: &mut Installer, event: KeyEvent) -> Signal {
+    match event.code {
+      KeyCode::Char('?') => {
+        self.help_modal.toggle();
+        Signal::Wait
+      }
+      code if ui_close!(code) && self.help_modal.visible => {
+        self.help_modal.hide();
+        Signal::Wait
+      }
+      _ if self.help_modal.visible => Signal::Wait,
+      KeyCode::Esc => Signal::Pop,
+      KeyCode::Enter => {
+        let path = self
+          .input
+          .get_value()
+          .and_then(|v| v.as_str().map(|s| s.trim().to_string()))
+          .unwrap_or_default();
+        if path.is_empty() {
+          self.input.error("Enter a file to save to");
+          return Signal::Wait;
+        }
+        match save_installer(installer, &path) {
+          Ok(()) => {
+            log::info!("Saved installer config to {path}");
+            installer.save_path = Some(path);
+            Signal::Pop
+          }
+          Err(e) => {
+            self.input.error(e);
+            Signal::Wait
+          }
+        }
+      }
+      _ => self.input.handle_input(event),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::os::unix::fs::PermissionsExt;
+
+  use super::*;
+
+  #[test]
+  fn write_private_replaces_loose_files_and_symlinks() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("config.json");
+    std::fs::write(&path, "old").unwrap();
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+    write_private(&path, "secret").unwrap();
+    let meta = std::fs::metadata(&path).unwrap();
+    assert_eq!(meta.permissions().mode() & 0o777, 0o600);
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "secret");
+
+    let target = dir.path().join("target");
+    std::fs::write(&target, "untouched").unwrap();
+    let link = dir.path().join("link");
+    std::os::unix::fs::symlink(&target, &link).unwrap();
+    write_private(&link, "secret").unwrap();
+    assert_eq!(std::fs::read_to_string(&target).unwrap(), "untouched");
+    assert!(!std::fs::symlink_metadata(&link).unwrap().is_symlink());
+  }
+}
@@ -0,0 +1,410 @@
+use ratatui::{
+  Frame,
+  crossterm::event::{KeyCode, KeyEvent},
+  layout::Rect,
+  style::{Color, Modifier},
+  text::Line,
+};
+
+use crate::{
+  installer::{HIGHLIGHT, InstallProgress, Installer, Page, Signal},
+  keys, split_hor, split_vert, styled_block, ui_close, ui_down, ui_up,
+  widget::{ConfigWidget, HelpModal, InfoBox, LineEditor, StrList},
+};
+
+/// Check a cache URL and its public key, e.g.
+/// `https://nix-community.cachix.org` and
+/// `nix-community.cachix.org-1:mB9FSh9qf2dCimDSUo8Zy7bkq5CX+/rkCWyvRCYg3Fs=`
+pub fn parse_substituter(url: &str, key: &str) -> Result<(String, String), String> {
+  let url = url.trim().trim_end_matches('/');
+  let key = key.trim();
+  let host = url
+    .strip_prefix("https://")
+    .or_else(|| url.strip_prefix("http://"))
+    .ok_or_else(|| format!("'{url}' is not an http(s) URL"))?;
+  // These end up in a shell command and configuration.nix, so only allow
+  // characters a cache URL actually needs
+  let is_url_char = |c: char| c.is_ascii_alphanumeric() || "-._~:/?&=%@+,".contains(c);
+  if host.is_empty() || !host.chars().all(is_url_char) {
+    return Err(format!("'{url}' is not a valid URL"));
+  }
+  // Keys are `name:base64`, the base64 part is a 32 byte ed25519 key
+  let Some((name, data)) = key.split_once(':') else {
+    return Err("Public key should look like name-1:base64data".to_string());
+  };
+  let is_base64 = |c: char| c.is_ascii_alphanumeric() || "+/=".contains(c);
+  let is_name_char = |c: char| c.is_ascii_alphanumeric() || "-._".contains(c);
+  if name.is_empty()
+    || !name.chars().all(is_name_char)
+    || data.len() != 44
+    || !data.chars().all(is_base64)
+  {
+    return Err(format!("'{key}' is not a valid public key"));
+  }
+  Ok((url.to_string(), key.to_string()))
+}
+
+/// `--option` flags for nixos-install, so the initial build uses the caches
+pub fn install_flags(substituters: &[(String, String)]) -> String {
+  if substituters.is_empty() {
+    return String::new();
+  }
+  let urls = substituters
+    .iter()
+    .map(|(url, _)| url.as_str())
+    .collect::<Vec<_>>()
+    .join(" ");
+  let keys = substituters
+    .iter()
+    .map(|(_, key)| key.as_str())
+    .collect::<Vec<_>>()
+    .join(" ");
+  format!(
+    " --option extra-substituters {} --option extra-trusted-public-keys {}",
+    InstallProgress::shell_quote(&urls),
+    InstallProgress::shell_quote(&keys)
+  )
+}
+
+enum SubstituterMode {
+  List,
+  Url,
+  Key,
+}
+
+/// Extra binary caches for `nix.settings.substituters`
+pub struct Substituters {
+  entries: StrList,
+  url_input: LineEditor,
+  key_input: LineEditor,
+  mode: SubstituterMode,
+  help_modal: HelpModal<'static>,
+}
+
+impl Substituters {
+  pub fn new(installer: &Installer) -> Self {
+    let mut entries = StrList::new("Binary Caches", Self::urls(installer));
+    entries.focus();
+    let url_input = LineEditor::new("Cache URL", Some("e.g. https://nix-community.cachix.org"));
+    let key_input = LineEditor::new("Public key", Some("e.g. nix-community.cachix.org-1:..."));
+    let help_content = styled_block(vec![
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Navigate caches"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "a"),
+        (None, " - Add a cache"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "d"),
+        (None, " - Remove the selected cache"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Tab"),
+        (None, " - Switch between URL and key while adding"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Esc, q"),
+        (None, " - Return to menu"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "?"),
+        (None, " - Show this help"),
+      ],
+      vec![(None, "")],
+      vec![(
+        None,
+        "The cache's page usually lists its URL and public key together.",
+      )],
+    ]);
+    let help_modal = HelpModal::new("Binary Caches", help_content);
+    Self {
+      entries,
+      url_input,
+      key_input,
+      mode: SubstituterMode::List,
+      help_modal,
+    }
+  }
+  fn urls(installer: &Installer) -> Vec<String> {
+    installer
+      .substituters
+      .iter()
+      .map(|(url, _)| url.clone())
+      .collect()
+  }
+  fn open_form(&mut self) {
+    self.url_input.clear();
+    self.key_input.clear();
+    self.entries.unfocus();
+    self.url_input.focus();
+    self.mode = SubstituterMode::Url;
+  }
+  fn close_form(&mut self) {
+    self.url_input.clear();
+    self.key_input.clear();
+    self.url_input.unfocus();
+    self.key_input.unfocus();
+    self.entries.focus();
+    self.mode = SubstituterMode::List;
+  }
+  fn switch_field(&mut self) {
+    match self.mode {
+      SubstituterMode::Url => {
+        self.url_input.unfocus();
+        self.key_input.focus();
+        self.mode = SubstituterMode::Key;
+      }
+      SubstituterMode::Key => {
+        self.key_input.unfocus();
+        self.url_input.focus();
+        self.mode = SubstituterMode::Url;
+      }
+      SubstituterMode::List => {}
+    }
+  }
+  fn input_value(input: &LineEditor) -> String {
+    input
+      .get_value()
+      .and_then(|v| v.as_str().map(|s| s.to_string()))
+      .unwrap_or_default()
+  }
+  pub fn display_widget(installer: &mut Installer) -> Option<Box<dyn ConfigWidget>> {
+    if installer.substituters.is_empty() {
+      return None;
+    }
+    let mut lines = vec![vec![(None, "Extra binary caches:".to_string())]];
+    for (url, _) in &installer.substituters {
+      lines.push(vec![(HIGHLIGHT, url.clone())]);
+    }
+    Some(Box::new(InfoBox::new("", styled_block(lines))) as Box<dyn ConfigWidget>)
+  }
+  pub fn page_info<'a>() -> (String, Vec<Line<'a>>) {
+    (
+      "Binary Caches".to_string(),
+      styled_block(vec![
+        vec![(
+          None,
+          "Extra caches to download prebuilt packages from, besides cache.nixos.org.",
+        )],
+        vec![(
+          None,
+          "They are used for the install itself as well as on the new system.",
+        )],
+      ]),
+    )
+  }
+}
+
+impl Page for Substituters {
+  fn render(&mut self, installer: &mut Installer, f: &mut Frame, area: Rect) {
+    let chunks = split_vert!(
+      area,
+      1,
+      [
+        Constraint::Min(0),
+        Constraint::Length(3),
+        Constraint::Length(3),
+      ]
+    );
+    let hor_chunks = split_hor!(
+      chunks[0],
+      1,
+      [
+        Constraint::Percentage(20),
+        Constraint::Percentage(60),
+        Constraint::Percentage(20),
+      ]
+    );
+    self.entries.render(f, hor_chunks[1]);
+    match self.mode {
+      SubstituterMode::List => {
+        let key = installer
+          .substituters
+          .get(self.entries.selected_idx)
+          .map(|(_, key)| key.clone());
+        if let Some(key) = key {
+          InfoBox::new(
+            "",
+            styled_block(vec![vec![(None, "Key: ".to_string()), (HIGHLIGHT, key)]]),
+          )
+          .render(f, chunks[1]);
+        }
+      }
+      SubstituterMode::Url | SubstituterMode::Key => {
+        self.url_input.render(f, chunks[1]);
+        self.key_input.render(f, chunks[2]);
+      }
+    }
+    self.help_modal.render(f, area);
+  }
+
+  fn get_help_content(&self) -> (String, Vec<Line<'_>>) {
+    let help_content = styled_block(vec![
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Navigate caches"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "a"),
+        (None, " - Add a cache"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "d"),
+        (None, " - Remove the selected cache"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Tab"),
+        (None, " - Switch between URL and key while adding"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Esc, q"),
+        (None, " - Return to menu"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "?"),
+        (None, " - Show this help"),
+      ],
+      vec![(None, "")],
+      vec![(
+        None,
+        "The cache's page usually lists its URL and public key together.",
+      )],
+    ]);
+    ("Binary Caches".to_string(), help_content)
+  }
+
+  fn handle_paste(&mut self, _installer: &mut Installer, text: &str) -> Signal {
+    match self.mode {
+      SubstituterMode::Url => self.url_input.paste(text),
+      SubstituterMode::Key => self.key_input.paste(text),
+      SubstituterMode::List => {}
+    }
+    Signal::Wait
+  }
+
+  fn handle_input(&mut self, installer: &mut Installer, event: KeyEvent) -> Signal {
+    match self.mode {
+      SubstituterMode::Url | SubstituterMode::Key => {
+        return match event.code {
+          KeyCode::Esc => {
+            self.close_form();
+            Signal::Wait
+          }
+          KeyCode::Tab | KeyCode::BackTab => {
+            self.switch_field();
+            Signal::Wait
+          }
+          KeyCode::Enter if matches!(self.mode, SubstituterMode::Url) => {
+            self.switch_field();
+            Signal::Wait
+          }
+          KeyCode::Enter => {
+            let url = Self::input_value(&self.url_input);
+            let key = Self::input_value(&self.key_input);
+            match parse_substituter(&url, &key) {
+              Ok(entry) => {
+                if installer
+                  .substituters
+                  .iter()
+                  .any(|(url, _)| *url == entry.0)
+                {
+                  self.url_input.error("This cache is already in the list");
+                  self.switch_field();
+                  return Signal::Wait;
+                }
+                installer.substituters.push(entry);
+                self.entries.set_items(Self::urls(installer));
+                self.close_form();
+                Signal::Wait
+              }
+              Err(e) => {
+                self.key_input.error(e);
+                Signal::Wait
+              }
+            }
+          }
+          _ if matches!(self.mode, SubstituterMode::Url) => self.url_input.handle_input(event),
+          _ => self.key_input.handle_input(event),
+        };
+      }
+      SubstituterMode::List => {}
+    }
+
+    match event.code {
+      KeyCode::Char('?') => {
+        self.help_modal.toggle();
+        Signal::Wait
+      }
+      code if ui_close!(code) && self.help_modal.visible => {
+        self.help_modal.hide();
+        Signal::Wait
+      }
+      _ if self.help_modal.visible => Signal::Wait,
+      code if ui_close!(code) => Signal::Pop,
+      KeyCode::Char('a') => {
+        self.open_form();
+        Signal::Wait
+      }
+      KeyCode::Char('d') => {
+        if let Some(url) = self.entries.remove_selected() {
+          installer.substituters.retain(|(u, _)| *u != url);
+        }
+        Signal::Wait
+      }
+      code if ui_up!(code) => {
+        self.entries.previous_item();
+        Signal::Wait
+      }
+      code if ui_down!(code) => {
+        self.entries.next_item();
+        Signal::Wait
+      }
+      _ => Signal::Wait,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const KEY: &str = "nix-community.cachix.org-1:mB9FSh9qf2dCimDSUo8Zy7bkq5CX+/rkCWyvRCYg3Fs=";
+
+  #[test]
+  fn rejects_shell_and_nix_metacharacters() {
+    assert!(parse_substituter("https://nix-community.cachix.org/", KEY).is_ok());
+    for url in [
+      "https://x'$(reboot)'",
+      "https://x\"y",
+      "https://x${y}",
+      "https://x`y`",
+      "https://x y",
+    ] {
+      assert!(parse_substituter(url, KEY).is_err(), "{url}");
+    }
+    let bad_name = KEY.replacen("nix-community", "x'$(reboot)'", 1);
+    assert!(parse_substituter("https://x.org", &bad_name).is_err());
+  }
+
+  #[test]
+  fn install_flags_are_quoted() {
+    let caches = vec![
+      ("https://a.org".to_string(), KEY.to_string()),
+      ("https://b.org".to_string(), KEY.to_string()),
+    ];
+    assert_eq!(
+      install_flags(&caches),
+      format!(
+        " --option extra-substituters 'https://a.org https://b.org' --option extra-trusted-public-keys '{KEY} {KEY}'"
+      )
+    );
+  }
+}
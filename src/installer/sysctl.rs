@@ -0,0 +1,316 @@
+use ratatui::{
+  Frame,
+  crossterm::event::{KeyCode, KeyEvent},
+  layout::Rect,
+  style::{Color, Modifier},
+  text::Line,
+};
+
+use crate::{
+  installer::{HIGHLIGHT, Installer, Page, Signal},
+  keys, split_hor, split_vert, styled_block, ui_close, ui_down, ui_up,
+  widget::{ConfigWidget, HelpModal, InfoBox, LineEditor, StrList},
+};
+
+/// Split a `key = value` line into a sysctl name and value
+///
+/// Names are dotted paths like `net.ipv4.ip_forward`. Values can be anything
+/// printable, numbers are written to the config unquoted.
+pub fn parse_sysctl(input: &str) -> Result<(String, String), String> {
+  let Some((key, value)) = input.split_once('=') else {
+    return Err("Expected key = value, e.g. vm.swappiness = 10".to_string());
+  };
+  let (key, value) = (key.trim(), value.trim());
+  let valid_part = |part: &str| {
+    !part.is_empty()
+      && part
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+  };
+  if !key.contains('.') || !key.split('.').all(valid_part) {
+    return Err(format!("'{key}' is not a valid sysctl name"));
+  }
+  if value.is_empty() {
+    return Err(format!("No value given for {key}"));
+  }
+  if value
+    .chars()
+    .any(|c| c.is_control() || c == '"' || c == '\\')
+  {
+    return Err("Values cannot contain quotes, backslashes or control characters".to_string());
+  }
+  Ok((key.to_string(), value.to_string()))
+}
+
+enum SysctlMode {
+  List,
+  Entry,
+}
+
+pub struct Sysctl {
+  entries: StrList,
+  input: LineEditor,
+  mode: SysctlMode,
+  /// Key of the entry being edited, replaced when the edit is saved
+  editing: Option<String>,
+  help_modal: HelpModal<'static>,
+}
+
+impl Sysctl {
+  pub fn new(installer: &Installer) -> Self {
+    let mut entries = StrList::new("Sysctl Settings", Self::entry_lines(installer));
+    entries.focus();
+    let input = LineEditor::new("key = value", Some("e.g. vm.swappiness = 10"));
+    let help_content = styled_block(vec![
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Navigate settings"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "a"),
+        (None, " - Add a setting"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
+        (None, " - Edit the selected setting"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "d"),
+        (None, " - Delete the selected setting"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Esc, q"),
+        (None, " - Return to menu"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "?"),
+        (None, " - Show this help"),
+      ],
+      vec![(None, "")],
+      vec![(
+        None,
+        "Settings are applied at boot through boot.kernel.sysctl.",
+      )],
+    ]);
+    let help_modal = HelpModal::new("Kernel Sysctl", help_content);
+    Self {
+      entries,
+      input,
+      mode: SysctlMode::List,
+      editing: None,
+      help_modal,
+    }
+  }
+  fn entry_lines(installer: &Installer) -> Vec<String> {
+    installer
+      .sysctl
+      .iter()
+      .map(|(key, value)| format!("{key} = {value}"))
+      .collect()
+  }
+  fn selected_key(&self) -> Option<String> {
+    self
+      .entries
+      .selected_item()
+      .and_then(|line| line.split_once(" = "))
+      .map(|(key, _)| key.to_string())
+  }
+  fn open_input(&mut self, editing: Option<(String, String)>) {
+    match editing {
+      Some((key, value)) => {
+        self.input.set_value(format!("{key} = {value}"));
+        self.editing = Some(key);
+      }
+      None => {
+        self.input.clear();
+        self.editing = None;
+      }
+    }
+    self.entries.unfocus();
+    self.input.focus();
+    self.mode = SysctlMode::Entry;
+  }
+  fn close_input(&mut self) {
+    self.input.clear();
+    self.input.unfocus();
+    self.entries.focus();
+    self.editing = None;
+    self.mode = SysctlMode::List;
+  }
+  pub fn display_widget(installer: &mut Installer) -> Option<Box<dyn ConfigWidget>> {
+    if installer.sysctl.is_empty() {
+      return None;
+    }
+    let mut lines = vec![vec![(None, "Sysctl settings:".to_string())]];
+    for (key, value) in &installer.sysctl {
+      lines.push(vec![(HIGHLIGHT, format!("{key} = {value}"))]);
+    }
+    Some(Box::new(InfoBox::new("", styled_block(lines))) as Box<dyn ConfigWidget>)
+  }
+  pub fn page_info<'a>() -> (String, Vec<Line<'a>>) {
+    (
+      "Kernel Sysctl".to_string(),
+      styled_block(vec![
+        vec![(
+          None,
+          "Set kernel parameters such as vm.swappiness or net.ipv4.ip_forward.",
+        )],
+        vec![(
+          None,
+          "These are applied on every boot of the installed system.",
+        )],
+      ]),
+    )
+  }
+}
+
+impl Page for Sysctl {
+  fn render(&mut self, _installer: &mut Installer, f: &mut Frame, area: Rect) {
+    let chunks = split_vert!(area, 1, [Constraint::Min(0), Constraint::Length(3)]);
+    let hor_chunks = split_hor!(
+      chunks[0],
+      1,
+      [
+        Constraint::Percentage(20),
+        Constraint::Percentage(60),
+        Constraint::Percentage(20),
+      ]
+    );
+    self.entries.render(f, hor_chunks[1]);
+    if let SysctlMode::Entry = self.mode {
+      let input_chunks = split_hor!(
+        chunks[1],
+        0,
+        [
+          Constraint::Percentage(20),
+          Constraint::Percentage(60),
+          Constraint::Percentage(20),
+        ]
+      );
+      self.input.render(f, input_chunks[1]);
+    }
+    self.help_modal.render(f, area);
+  }
+
+  fn get_help_content(&self) -> (String, Vec<Line<'_>>) {
+    let help_content = styled_block(vec![
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Navigate settings"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "a"),
+        (None, " - Add a setting"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
+        (None, " - Edit the selected setting"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "d"),
+        (None, " - Delete the selected setting"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Esc, q"),
+        (None, " - Return to menu"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "?"),
+        (None, " - Show this help"),
+      ],
+      vec![(None, "")],
+      vec![(
+        None,
+        "Settings are applied at boot through boot.kernel.sysctl.",
+      )],
+    ]);
+    ("Kernel Sysctl".to_string(), help_content)
+  }
+
+  fn handle_paste(&mut self, _installer: &mut Installer, text: &str) -> Signal {
+    if let SysctlMode::Entry = self.mode {
+      self.input.paste(text);
+    }
+    Signal::Wait
+  }
+
+  fn handle_input(&mut self, installer: &mut Installer, event: KeyEvent) -> Signal {
+    if let SysctlMode::Entry = self.mode {
+      return match event.code {
+        KeyCode::Esc => {
+          self.close_input();
+          Signal::Wait
+        }
+        KeyCode::Enter => {
+          let input = self
+            .input
+            .get_value()
+            .and_then(|v| v.as_str().map(|s| s.to_string()))
+            .unwrap_or_default();
+          match parse_sysctl(&input) {
+            Ok((key, value)) => {
+              if let Some(old) = self.editing.take() {
+                installer.sysctl.remove(&old);
+              }
+              installer.sysctl.insert(key, value);
+              self.entries.set_items(Self::entry_lines(installer));
+              self.close_input();
+              Signal::Wait
+            }
+            Err(e) => {
+              self.input.error(e);
+              Signal::Wait
+            }
+          }
+        }
+        _ => self.input.handle_input(event),
+      };
+    }
+
+    match event.code {
+      KeyCode::Char('?') => {
+        self.help_modal.toggle();
+        Signal::Wait
+      }
+      code if ui_close!(code) && self.help_modal.visible => {
+        self.help_modal.hide();
+        Signal::Wait
+      }
+      _ if self.help_modal.visible => Signal::Wait,
+      code if ui_close!(code) => Signal::Pop,
+      KeyCode::Char('a') => {
+        self.open_input(None);
+        Signal::Wait
+      }
+      KeyCode::Char('d') => {
+        if let Some(key) = self.selected_key() {
+          installer.sysctl.remove(&key);
+          self.entries.remove_selected();
+        }
+        Signal::Wait
+      }
+      KeyCode::Enter => {
+        if let Some(key) = self.selected_key() {
+          let value = installer.sysctl.get(&key).cloned().unwrap_or_default();
+          self.open_input(Some((key, value)));
+        }
+        Signal::Wait
+      }
+      code if ui_up!(code) => {
+        self.entries.previous_item();
+        Signal::Wait
+      }
+      code if ui_down!(code) => {
+        self.entries.next_item();
+        Signal::Wait
+      }
+      _ => Signal::Wait,
+    }
+  }
+}
@@ -1,19 +1,62 @@
-use std::{collections::HashSet, sync::LazyLock};
+use std::{
+  collections::{HashMap, HashSet},
+  sync::LazyLock,
+};
 
-use ratatui::{layout::Constraint, text::Line};
+use ratatui::{
+  layout::Constraint,
+  style::{Color, Modifier},
+  text::Line,
+};
 use serde_json::Value;
 
 use crate::{
-  installer::{Installer, Page, Signal},
-  styled_block,
-  widget::{ConfigWidget, PackagePicker, TableWidget},
+  command,
+  drives::bytes_readable,
+  installer::{Installer, Page, Signal, loading::Loading},
+  keys, styled_block,
+  widget::{ConfigWidget, PackagePicker, PickerViewState, TableWidget},
 };
 
 use std::{
-  sync::{Arc, RwLock},
+  sync::{
+    Arc, Mutex, RwLock,
+    atomic::{AtomicBool, Ordering},
+  },
   thread,
 };
 
+/// Commonly installed packages with unfree licenses
+///
+/// The bundled package list has no license information, so this only catches
+/// the usual suspects. Anything else unfree still fails the build with
+/// nixpkgs' own message pointing at `allowUnfree`.
+const UNFREE_PACKAGES: &[&str] = &[
+  "steam",
+  "discord",
+  "spotify",
+  "slack",
+  "zoom-us",
+  "vscode",
+  "google-chrome",
+  "obsidian",
+  "teams-for-linux",
+  "unrar",
+  "vivaldi",
+  "android-studio",
+  "jetbrains.idea-ultimate",
+  "jetbrains.pycharm-professional",
+  "nvidia-docker",
+  "teamviewer",
+  "skypeforlinux",
+  "postman",
+  "sublime4",
+];
+
+pub fn is_unfree(pkg: &str) -> bool {
+  UNFREE_PACKAGES.contains(&pkg)
+}
+
 pub static NIXPKGS: LazyLock<Arc<RwLock<Option<Vec<String>>>>> =
   LazyLock::new(|| Arc::new(RwLock::new(None)));
 
@@ -59,9 +102,14 @@ pub fn fetch_nixpkgs() -> anyhow::Result<Vec<String>> {
   Ok(pkgs)
 }
 
-pub fn get_available_pkgs() -> anyhow::Result<Vec<String>> {
+pub fn get_available_pkgs(cancel: &AtomicBool) -> anyhow::Result<Vec<String>> {
   let mut retries = 0;
   loop {
+    if cancel.load(Ordering::Relaxed) {
+      break Err(anyhow::anyhow!(
+        "Cancelled while waiting for the package list"
+      ));
+    }
     let guard = NIXPKGS.read().unwrap();
     if let Some(nixpkgs) = guard.as_ref() {
       // Great, the package list has been populated
@@ -79,20 +127,154 @@ pub fn get_available_pkgs() -> anyhow::Result<Vec<String>> {
   }
 }
 
+/// Open a page that needs the package list, with a cancellable loading page
+/// in between if the list isn't there yet
+pub fn with_available_pkgs(build: impl FnOnce(Vec<String>) -> Box<dyn Page> + 'static) -> Signal {
+  if let Some(pkgs) = NIXPKGS.read().ok().and_then(|guard| guard.clone()) {
+    return Signal::Push(build(pkgs));
+  }
+  Signal::Push(Box::new(Loading::new(
+    "Loading the package list...",
+    |cancel| get_available_pkgs(cancel).unwrap_or_default(),
+    build,
+  )))
+}
+
+/// Closure size of a package, as far as the binary cache knows
+#[derive(Clone, Copy)]
+enum ClosureSize {
+  Pending,
+  Known(u64),
+  /// Not in the cache, or evaluating it failed
+  Unknown,
+}
+
+type ClosureSizes = Arc<Mutex<HashMap<String, ClosureSize>>>;
+
+/// Ask cache.nixos.org for the closure size of `pkg` from nixpkgs
+///
+/// Evaluates the package locally to find its store path, nothing is built
+/// or downloaded beyond the nixpkgs source.
+fn query_closure_size(pkg: &str) -> Option<u64> {
+  let output = command!(
+    "nix",
+    "--extra-experimental-features",
+    "nix-command flakes",
+    "path-info",
+    "--closure-size",
+    "--json",
+    "--store",
+    "https://cache.nixos.org",
+    format!("nixpkgs#{pkg}")
+  )
+  .stderr(std::process::Stdio::null())
+  .output()
+  .ok()?;
+  if !output.status.success() {
+    return None;
+  }
+  let json: Value = serde_json::from_slice(&output.stdout).ok()?;
+  // Older nix prints a list of path infos, newer nix an object keyed by path
+  let infos: Vec<&Value> = match json {
+    Value::Array(ref infos) => infos.iter().collect(),
+    Value::Object(ref infos) => infos.values().collect(),
+    _ => return None,
+  };
+  infos
+    .iter()
+    .map(|info| info.get("closureSize").and_then(Value::as_u64))
+    .sum()
+}
+
 pub struct SystemPackages {
   package_picker: PackagePicker,
+  /// Filled in the background once an estimate is asked for
+  sizes: Option<ClosureSizes>,
 }
 
 impl SystemPackages {
   pub fn new(selected_pkgs: Vec<String>, available_pkgs: Vec<String>) -> Self {
-    let package_picker = PackagePicker::new(
+    let mut package_picker = PackagePicker::new(
       "Selected Packages",
       "Available Packages",
       selected_pkgs,
       available_pkgs,
     );
+    package_picker.extend_help(styled_block(vec![vec![
+      (Some((Color::Yellow, Modifier::BOLD)), "s"),
+      (
+        None,
+        " - Estimate the closure size of the selection (needs network)",
+      ),
+    ]]));
 
-    Self { package_picker }
+    Self {
+      package_picker,
+      sizes: None,
+    }
+  }
+  /// Look up sizes for selected packages that haven't been looked up yet
+  fn estimate_sizes(&mut self) {
+    let sizes = self.sizes.get_or_insert_default().clone();
+    let todo = {
+      let Ok(mut known) = sizes.lock() else {
+        return;
+      };
+      let todo = self
+        .package_picker
+        .get_selected_packages()
+        .into_iter()
+        .filter(|pkg| !known.contains_key(pkg))
+        .collect::<Vec<_>>();
+      for pkg in &todo {
+        known.insert(pkg.clone(), ClosureSize::Pending);
+      }
+      todo
+    };
+    if todo.is_empty() {
+      return;
+    }
+    thread::spawn(move || {
+      for pkg in todo {
+        let size = query_closure_size(&pkg).map_or(ClosureSize::Unknown, ClosureSize::Known);
+        if let Ok(mut known) = sizes.lock() {
+          known.insert(pkg, size);
+        }
+      }
+    });
+  }
+  /// Summary of the estimate for the current selection
+  fn size_note(&self) -> Option<Line<'static>> {
+    let sizes = self.sizes.as_ref()?.lock().ok()?;
+    let (mut total, mut pending, mut unknown, mut missing) = (0, 0, 0, 0);
+    for pkg in self.package_picker.get_selected_packages() {
+      match sizes.get(&pkg) {
+        Some(ClosureSize::Known(size)) => total += size,
+        Some(ClosureSize::Pending) => pending += 1,
+        Some(ClosureSize::Unknown) => unknown += 1,
+        None => missing += 1,
+      }
+    }
+    // Shared dependencies are counted once per package, so this overshoots
+    let mut note = format!("Closure size: up to {}", bytes_readable(total));
+    if pending > 0 {
+      note += &format!(", {pending} still checking");
+    }
+    if unknown > 0 {
+      note += &format!(", {unknown} unknown");
+    }
+    if missing > 0 {
+      note += &format!(", {missing} new (press s)");
+    }
+    Some(Line::from(note))
+  }
+  /// Put the filter and cursor positions back where they were when the page
+  /// was last closed
+  pub fn with_view_state(mut self, state: Option<PickerViewState>) -> Self {
+    if let Some(state) = state {
+      self.package_picker.restore_view_state(state);
+    }
+    self
   }
   pub fn display_widget(installer: &mut Installer) -> Option<Box<dyn ConfigWidget>> {
     let sys_pkgs: Vec<Vec<String>> = installer
@@ -129,9 +311,15 @@ impl Page for SystemPackages {
     f: &mut ratatui::Frame,
     area: ratatui::prelude::Rect,
   ) {
+    self.package_picker.status_note = self.size_note();
     self.package_picker.render(f, area);
   }
 
+  fn handle_paste(&mut self, _installer: &mut super::Installer, text: &str) -> super::Signal {
+    self.package_picker.paste(text);
+    Signal::Wait
+  }
+
   fn handle_input(
     &mut self,
     installer: &mut super::Installer,
@@ -141,7 +329,14 @@ impl Page for SystemPackages {
 
     // Handle quit/escape at the top level
     match event.code {
-      KeyCode::Esc | KeyCode::Char('q') => return Signal::Pop,
+      KeyCode::Esc | KeyCode::Char('q') => {
+        installer.system_pkgs_view = Some(self.package_picker.view_state());
+        return Signal::Pop;
+      }
+      KeyCode::Char('s') if self.package_picker.lists_focused() => {
+        self.estimate_sizes();
+        return Signal::Wait;
+      }
       _ => {}
     }
 
@@ -154,6 +349,9 @@ impl Page for SystemPackages {
     // Update installer's system_pkgs if the selection changed
     let current_selection = self.package_picker.get_selected_packages();
     if previous_selection != current_selection {
+      if current_selection.iter().any(|pkg| is_unfree(pkg)) {
+        installer.allow_unfree = true;
+      }
       installer.system_pkgs = current_selection;
     }
 
@@ -178,7 +376,7 @@ impl Page for SystemPackages {
             ratatui::style::Color::Yellow,
             ratatui::style::Modifier::BOLD,
           )),
-          "↑/↓, j/k",
+          keys::bindings().nav_label,
         ),
         (None, " - Navigate package lists"),
       ],
@@ -202,6 +400,19 @@ impl Page for SystemPackages {
         ),
         (None, " - Focus search bar"),
       ],
+      vec![
+        (
+          Some((
+            ratatui::style::Color::Yellow,
+            ratatui::style::Modifier::BOLD,
+          )),
+          "s",
+        ),
+        (
+          None,
+          " - Estimate the closure size of the selection (needs network)",
+        ),
+      ],
       vec![
         (
           Some((
@@ -1,20 +1,39 @@
+use std::{
+  sync::{Arc, Mutex},
+  thread,
+};
+
 use ratatui::{crossterm::event::KeyCode, layout::Constraint, text::Line};
 
 use crate::{
-  installer::{HIGHLIGHT, Installer, Page, Signal, systempkgs::get_available_pkgs},
-  split_hor, split_vert, styled_block, ui_back, ui_close, ui_down, ui_enter, ui_up,
+  installer::{HIGHLIGHT, Installer, Page, SPINNER, Signal, systempkgs::with_available_pkgs},
+  keys, split_hor, split_vert, styled_block, ui_back, ui_close, ui_down, ui_enter, ui_up,
   widget::{
-    Button, ConfigWidget, HelpModal, InfoBox, LineEditor, PackagePicker, StrList, TableWidget,
-    WidgetBox,
+    Button, CheckBox, ConfigWidget, HelpModal, InfoBox, LineEditor, PackagePicker, StrList,
+    TableWidget, WidgetBox,
   },
 };
+use serde_json::Value;
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct User {
   pub username: String,
   pub password_hash: String,
   pub groups: Vec<String>,
+  /// Login shell, one of [`SHELLS`]. The system default (bash) if unset.
+  #[serde(default)]
+  pub shell: Option<String>,
+  /// Public keys allowed to log in as this user over SSH
+  #[serde(default)]
+  pub ssh_keys: Vec<String>,
   pub home_manager_cfg: Option<HomeManagerCfg>,
+  /// Password typed in but not hashed yet
+  ///
+  /// Hashing is put off until the user accounts page is left, so several
+  /// accounts can be hashed at once instead of freezing on each one. Never
+  /// saved anywhere.
+  #[serde(skip)]
+  pub pending_password: Option<String>,
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -39,16 +58,93 @@ impl User {
   }
 }
 
+type HashResults = Arc<Mutex<Vec<Option<anyhow::Result<String>>>>>;
+
+/// Passwords being hashed in the background, one thread per account
+struct PasswordHashing {
+  /// (index into `Installer::users`, username) for each result slot
+  jobs: Vec<(usize, String)>,
+  results: HashResults,
+}
+
+impl PasswordHashing {
+  fn start(users: &[User]) -> Self {
+    let pending = users
+      .iter()
+      .enumerate()
+      .filter_map(|(idx, user)| {
+        let password = user.pending_password.clone()?;
+        Some((idx, user.username.clone(), password))
+      })
+      .collect::<Vec<_>>();
+    let results: HashResults = Arc::new(Mutex::new(
+      std::iter::repeat_with(|| None)
+        .take(pending.len())
+        .collect(),
+    ));
+    let mut jobs = vec![];
+    for (slot, (idx, username, password)) in pending.into_iter().enumerate() {
+      jobs.push((idx, username));
+      let results = results.clone();
+      thread::spawn(move || {
+        let hashed = super::RootPassword::mkpasswd(password);
+        if let Ok(mut guard) = results.lock() {
+          guard[slot] = Some(hashed);
+        }
+      });
+    }
+    Self { jobs, results }
+  }
+
+  /// Write the hashes into `users` once every account is done, returning the
+  /// accounts that failed
+  fn finish(&self, users: &mut [User]) -> Option<Vec<(String, String)>> {
+    let mut guard = self.results.lock().ok()?;
+    if guard.iter().any(Option::is_none) {
+      return None;
+    }
+    let mut failures = vec![];
+    for ((idx, username), result) in self.jobs.iter().zip(guard.iter_mut()) {
+      // The list can't change while hashing, but check the name anyway so a
+      // hash can never land on the wrong account
+      let Some(user) = users.get_mut(*idx).filter(|u| u.username == *username) else {
+        continue;
+      };
+      user.pending_password = None;
+      match result.take() {
+        Some(Ok(hash)) => user.password_hash = hash,
+        Some(Err(e)) => failures.push((username.clone(), e.to_string())),
+        None => {}
+      }
+    }
+    Some(failures)
+  }
+}
+
 pub struct UserAccounts {
   pub user_table: TableWidget,
   pub buttons: WidgetBox,
+  /// Account the remove button is waiting on a second press for
+  confirming_remove: Option<usize>,
+  hashing: Option<PasswordHashing>,
+  /// Accounts whose password couldn't be hashed, with the reason
+  hash_failures: Vec<(String, String)>,
+  spinner_frame: usize,
+  done: bool,
   help_modal: HelpModal<'static>,
 }
 
 impl UserAccounts {
+  fn button_row(remove_label: &str) -> Vec<Box<dyn ConfigWidget>> {
+    vec![
+      Box::new(Button::new("Add user")) as Box<dyn ConfigWidget>,
+      Box::new(Button::new("Edit user")) as Box<dyn ConfigWidget>,
+      Box::new(Button::new(remove_label)) as Box<dyn ConfigWidget>,
+      Box::new(Button::new("Back")) as Box<dyn ConfigWidget>,
+    ]
+  }
   pub fn new(users: Vec<User>) -> Self {
-    let buttons = vec![Box::new(Button::new("Back")) as Box<dyn ConfigWidget>];
-    let buttons = WidgetBox::button_menu(buttons);
+    let buttons = WidgetBox::button_menu(Self::button_row("Remove user"));
     let widths = vec![
       Constraint::Percentage(33),
       Constraint::Percentage(33),
@@ -70,7 +166,7 @@ impl UserAccounts {
             ratatui::style::Color::Yellow,
             ratatui::style::Modifier::BOLD,
           )),
-          "↑/↓, j/k",
+          keys::bindings().nav_label,
         ),
         (None, " - Navigate user list"),
       ],
@@ -84,6 +180,26 @@ impl UserAccounts {
         ),
         (None, " - Add new user or edit selected user"),
       ],
+      vec![
+        (
+          Some((
+            ratatui::style::Color::Yellow,
+            ratatui::style::Modifier::BOLD,
+          )),
+          "c",
+        ),
+        (None, " - Add a user with the selected user's settings"),
+      ],
+      vec![
+        (
+          Some((
+            ratatui::style::Color::Yellow,
+            ratatui::style::Modifier::BOLD,
+          )),
+          "d",
+        ),
+        (None, " - Remove the selected user, press twice to confirm"),
+      ],
       vec![
         (
           Some((
@@ -100,7 +216,7 @@ impl UserAccounts {
             ratatui::style::Color::Yellow,
             ratatui::style::Modifier::BOLD,
           )),
-          "Esc, q, ←, h",
+          keys::bindings().back_label,
         ),
         (None, " - Return to main menu"),
       ],
@@ -126,9 +242,128 @@ impl UserAccounts {
     Self {
       user_table,
       buttons,
+      confirming_remove: None,
+      hashing: None,
+      hash_failures: vec![],
+      spinner_frame: 0,
+      done: false,
       help_modal,
     }
   }
+  /// Index into `Installer::users` of the highlighted row, the first row is
+  /// "Add a new user"
+  fn selected_user(&self) -> Option<usize> {
+    self.user_table.selected_row()?.checked_sub(1)
+  }
+  fn add_user(installer: &Installer) -> Signal {
+    // The first admin is easy to forget, so suggest it until someone has it
+    let mut page = AddUser::new();
+    if !installer
+      .users
+      .iter()
+      .any(|u| u.groups.iter().any(|g| g == "wheel"))
+    {
+      page.set_groups(&["wheel".to_string()]);
+    }
+    Signal::Push(Box::new(page))
+  }
+  fn edit_selected(&self, installer: &Installer) -> Signal {
+    let Some(idx) = self.selected_user() else {
+      return Signal::Wait;
+    };
+    match installer.users.get(idx) {
+      Some(user) => Signal::Push(Box::new(AlterUser::new(idx, user))),
+      None => Signal::Wait,
+    }
+  }
+  /// Ask for confirmation on the first press, remove on the second
+  fn remove_selected(&mut self, installer: &mut Installer) -> Signal {
+    let Some(idx) = self.selected_user() else {
+      return Signal::Wait;
+    };
+    let Some(user) = installer.users.get(idx) else {
+      return Signal::Wait;
+    };
+    if self.confirming_remove == Some(idx) {
+      installer.users.remove(idx);
+      self.reset_remove();
+    } else {
+      self.confirming_remove = Some(idx);
+      let label = format!("Really remove {}?", user.username);
+      self.buttons.set_children_inplace(Self::button_row(&label));
+    }
+    Signal::Wait
+  }
+  fn reset_remove(&mut self) {
+    if self.confirming_remove.take().is_some() {
+      self
+        .buttons
+        .set_children_inplace(Self::button_row("Remove user"));
+    }
+  }
+  /// Leave the page, hashing any new passwords first
+  fn leave(&mut self, installer: &mut Installer) -> Signal {
+    if installer.users.iter().any(|u| u.pending_password.is_some()) {
+      self.hash_failures.clear();
+      self.hashing = Some(PasswordHashing::start(&installer.users));
+      Signal::Wait
+    } else {
+      Signal::Pop
+    }
+  }
+  fn poll_hashing(&mut self, installer: &mut Installer) {
+    let Some(hashing) = &self.hashing else {
+      return;
+    };
+    let Some(failures) = hashing.finish(&mut installer.users) else {
+      self.spinner_frame = self.spinner_frame.wrapping_add(1);
+      return;
+    };
+    self.hashing = None;
+    if failures.is_empty() {
+      self.done = true;
+    } else {
+      log::warn!(
+        "Failed to hash passwords for: {}",
+        failures
+          .iter()
+          .map(|(name, _)| name.as_str())
+          .collect::<Vec<_>>()
+          .join(", ")
+      );
+      self.hash_failures = failures;
+    }
+  }
+  fn status_box(&self) -> Option<InfoBox<'static>> {
+    if let Some(hashing) = &self.hashing {
+      let count = hashing.jobs.len();
+      let noun = if count == 1 { "password" } else { "passwords" };
+      return Some(InfoBox::new(
+        "",
+        styled_block(vec![vec![
+          (
+            HIGHLIGHT,
+            SPINNER[self.spinner_frame % SPINNER.len()].to_string(),
+          ),
+          (None, format!(" Hashing {count} {noun}...")),
+        ]]),
+      ));
+    }
+    if self.hash_failures.is_empty() {
+      return None;
+    }
+    let mut lines = vec![vec![(
+      Some((ratatui::style::Color::Red, ratatui::style::Modifier::BOLD)),
+      "These passwords could not be hashed, set them again:".to_string(),
+    )]];
+    for (username, err) in &self.hash_failures {
+      lines.push(vec![
+        (HIGHLIGHT, username.clone()),
+        (None, format!(": {err}")),
+      ]);
+    }
+    Some(InfoBox::new("Password Errors", styled_block(lines)))
+  }
   pub fn display_widget(installer: &mut Installer) -> Option<Box<dyn ConfigWidget>> {
     let users = installer.users.clone();
     if users.is_empty() {
@@ -157,6 +392,10 @@ impl UserAccounts {
 }
 
 impl Page for UserAccounts {
+  fn signal(&self) -> Option<Signal> {
+    self.done.then_some(Signal::Pop)
+  }
+
   fn render(
     &mut self,
     installer: &mut super::Installer,
@@ -178,7 +417,15 @@ impl Page for UserAccounts {
     self.user_table.set_rows(rows);
     self.user_table.fix_selection();
     self.user_table.render(f, chunks[0]);
-    self.buttons.render(f, chunks[1]);
+    self.poll_hashing(installer);
+    match self.status_box() {
+      Some(status) => {
+        let bottom = split_vert!(chunks[1], 0, [Constraint::Length(3), Constraint::Min(0)]);
+        self.buttons.render(f, bottom[0]);
+        status.render(f, bottom[1]);
+      }
+      None => self.buttons.render(f, chunks[1]),
+    }
 
     // Render help modal on top
     self.help_modal.render(f, area);
@@ -194,19 +441,29 @@ impl Page for UserAccounts {
         self.help_modal.toggle();
         return Signal::Wait;
       }
-      ui_close!() if self.help_modal.visible => {
+      code if ui_close!(code) && self.help_modal.visible => {
         self.help_modal.hide();
         return Signal::Wait;
       }
       _ if self.help_modal.visible => {
         return Signal::Wait;
       }
+      _ if self.hashing.is_some() => return Signal::Wait,
       _ => {}
     }
 
+    // Any key other than a second remove press cancels the confirmation
+    let remove_pressed = event.code == KeyCode::Char('d')
+      || (self.buttons.is_focused()
+        && ui_enter!(event.code)
+        && self.buttons.selected_child() == Some(2));
+    if !remove_pressed {
+      self.reset_remove();
+    }
+
     if self.user_table.is_focused() {
       match event.code {
-        ui_down!() => {
+        code if ui_down!(code) => {
           if !self.user_table.next_row() {
             self.user_table.unfocus();
             self.buttons.focus();
@@ -214,7 +471,7 @@ impl Page for UserAccounts {
           }
           Signal::Wait
         }
-        ui_up!() => {
+        code if ui_up!(code) => {
           if !self.user_table.previous_row() {
             self.user_table.unfocus();
             self.buttons.focus();
@@ -222,28 +479,33 @@ impl Page for UserAccounts {
           }
           Signal::Wait
         }
-        ui_enter!() => {
-          let Some(selected_user) = self.user_table.selected_row() else {
-            return Signal::Error(anyhow::anyhow!("No user selected"));
-          };
-          if selected_user == 0 {
-            // Add a new user
-            Signal::Push(Box::new(AddUser::new()))
+        code if ui_enter!(code) => {
+          if self.user_table.selected_row() == Some(0) {
+            Self::add_user(installer)
           } else {
-            let groups = installer
-              .users
-              .get(selected_user - 1)
-              .map(|u| u.groups.clone())
-              .unwrap_or_default();
-            Signal::Push(Box::new(AlterUser::new(selected_user - 1, groups)))
+            self.edit_selected(installer)
           }
         }
-        ui_back!() => Signal::Pop,
+        KeyCode::Char('d') => self.remove_selected(installer),
+        KeyCode::Char('c') => {
+          // Duplicate the selected user's settings into a new one
+          let Some(selected_user) = self.user_table.selected_row() else {
+            return Signal::Wait;
+          };
+          let Some(template) = selected_user
+            .checked_sub(1)
+            .and_then(|idx| installer.users.get(idx))
+          else {
+            return Signal::Wait;
+          };
+          Signal::Push(Box::new(AddUser::from_template(template.clone())))
+        }
+        code if ui_back!(code) => self.leave(installer),
         _ => Signal::Wait,
       }
     } else if self.buttons.is_focused() {
       match event.code {
-        ui_down!() => {
+        code if ui_down!(code) => {
           if !self.buttons.next_child() {
             self.buttons.unfocus();
             self.user_table.focus();
@@ -251,7 +513,7 @@ impl Page for UserAccounts {
           }
           Signal::Wait
         }
-        ui_up!() => {
+        code if ui_up!(code) => {
           if !self.buttons.prev_child() {
             self.buttons.unfocus();
             self.user_table.focus();
@@ -259,16 +521,19 @@ impl Page for UserAccounts {
           }
           Signal::Wait
         }
-        ui_enter!() => {
+        code if ui_enter!(code) => {
           match self.buttons.selected_child() {
-            Some(0) => {
+            Some(0) => Self::add_user(installer),
+            Some(1) => self.edit_selected(installer),
+            Some(2) => self.remove_selected(installer),
+            Some(3) => {
               // Back
-              Signal::Pop
+              self.leave(installer)
             }
             _ => Signal::Wait,
           }
         }
-        ui_back!() => Signal::Pop,
+        code if ui_back!(code) => self.leave(installer),
         _ => Signal::Wait,
       }
     } else {
@@ -285,7 +550,7 @@ impl Page for UserAccounts {
             ratatui::style::Color::Yellow,
             ratatui::style::Modifier::BOLD,
           )),
-          "↑/↓, j/k",
+          keys::bindings().nav_label,
         ),
         (None, " - Navigate user list"),
       ],
@@ -299,6 +564,26 @@ impl Page for UserAccounts {
         ),
         (None, " - Add new user or edit selected user"),
       ],
+      vec![
+        (
+          Some((
+            ratatui::style::Color::Yellow,
+            ratatui::style::Modifier::BOLD,
+          )),
+          "c",
+        ),
+        (None, " - Add a user with the selected user's settings"),
+      ],
+      vec![
+        (
+          Some((
+            ratatui::style::Color::Yellow,
+            ratatui::style::Modifier::BOLD,
+          )),
+          "d",
+        ),
+        (None, " - Remove the selected user, press twice to confirm"),
+      ],
       vec![
         (
           Some((
@@ -315,7 +600,7 @@ impl Page for UserAccounts {
             ratatui::style::Color::Yellow,
             ratatui::style::Modifier::BOLD,
           )),
-          "Esc, q, ←, h",
+          keys::bindings().back_label,
         ),
         (None, " - Return to main menu"),
       ],
@@ -341,16 +626,130 @@ impl Page for UserAccounts {
   }
 }
 
+/// Login shells a user can be given
+pub const SHELLS: [&str; 4] = ["bash", "zsh", "fish", "nushell"];
+
+/// Shown above [`SHELLS`] for leaving `users.users.<name>.shell` unset
+const DEFAULT_SHELL: &str = "System default";
+
+/// Groups offered as checkboxes when adding a user, with what membership
+/// gives. Anything else can be added from the user's edit page.
+const COMMON_GROUPS: [(&str, &str); 6] = [
+  ("wheel", "administrator, can use sudo"),
+  ("networkmanager", "change network settings"),
+  ("docker", "use Docker without sudo"),
+  ("libvirtd", "manage virtual machines"),
+  ("audio", "direct access to sound devices"),
+  ("video", "direct access to video devices"),
+];
+
 pub struct AddUser {
   name_input: LineEditor,
+  groups: WidgetBox,
+  shell_list: StrList,
+  /// Groups from a template that have no checkbox, kept as they are
+  extra_groups: Vec<String>,
   pass_input: LineEditor,
   pass_confirm: LineEditor,
   help_modal: HelpModal<'static>,
 
   username: Option<String>,
+  /// Existing user whose groups and Home Manager setup are copied
+  template: Option<User>,
 }
 
 impl AddUser {
+  /// Add a user with the same settings as `template`, apart from the name and
+  /// password
+  pub fn from_template(template: User) -> Self {
+    let mut page = Self::new();
+    page.set_groups(&template.groups);
+    page
+      .shell_list
+      .commit_value(Some(template.shell.as_deref().unwrap_or(DEFAULT_SHELL)));
+    page.template = Some(template);
+    page
+  }
+  fn group_checkboxes(groups: &[String]) -> Vec<Box<dyn ConfigWidget>> {
+    COMMON_GROUPS
+      .iter()
+      .map(|(group, desc)| {
+        let checked = groups.iter().any(|g| g == group);
+        Box::new(CheckBox::new(format!("{group} - {desc}"), checked)) as Box<dyn ConfigWidget>
+      })
+      .collect()
+  }
+  /// Check the boxes for `groups`, keeping any without a box as they are
+  pub fn set_groups(&mut self, groups: &[String]) {
+    self
+      .groups
+      .set_children_inplace(Self::group_checkboxes(groups));
+    self.extra_groups = groups
+      .iter()
+      .filter(|g| !COMMON_GROUPS.iter().any(|(common, _)| common == g))
+      .cloned()
+      .collect();
+  }
+  fn selected_groups(&self) -> Vec<String> {
+    let checked = COMMON_GROUPS
+      .iter()
+      .enumerate()
+      .filter(|(i, _)| {
+        self
+          .groups
+          .widgets
+          .get(*i)
+          .and_then(|c| c.get_value())
+          .is_some_and(|v| v == Value::Bool(true))
+      })
+      .map(|(_, (group, _))| group.to_string());
+    checked.chain(self.extra_groups.iter().cloned()).collect()
+  }
+  /// Take the name from the username field, or send focus back to it with
+  /// the reason it can't be used
+  ///
+  /// Tab can skip past the field without Enter, so this runs again on save.
+  fn check_username(&mut self, installer: &Installer) -> bool {
+    let name = self
+      .name_input
+      .get_value()
+      .and_then(|v| v.as_str().map(|s| s.trim().to_string()))
+      .unwrap_or_default();
+    let error = if name.is_empty() {
+      "Username cannot be empty"
+    } else if installer.users.iter().any(|u| u.username == name) {
+      "A user with that name already exists"
+    } else {
+      self.username = Some(name);
+      return true;
+    };
+    self.groups.unfocus();
+    self.shell_list.unfocus();
+    self.pass_input.unfocus();
+    self.pass_confirm.unfocus();
+    self.name_input.focus();
+    self.name_input.error(error);
+    false
+  }
+  fn new_user(&self, pending_password: Option<String>) -> User {
+    let home_manager_cfg = self
+      .template
+      .as_ref()
+      .and_then(|template| template.home_manager_cfg.clone());
+    User {
+      username: self.username.clone().unwrap_or_default(),
+      password_hash: String::new(),
+      groups: self.selected_groups(),
+      ssh_keys: vec![],
+      shell: self
+        .shell_list
+        .committed
+        .clone()
+        .filter(|shell| shell != DEFAULT_SHELL),
+      home_manager_cfg,
+      pending_password,
+    }
+  }
   pub fn new() -> Self {
     let mut name_input = LineEditor::new("Username", None::<&str>);
     name_input.focus();
@@ -383,7 +782,27 @@ impl AddUser {
           )),
           "Enter",
         ),
-        (None, " - Create user account"),
+        (None, " - Next field, create the account from the last one"),
+      ],
+      vec![
+        (
+          Some((
+            ratatui::style::Color::Yellow,
+            ratatui::style::Modifier::BOLD,
+          )),
+          "Space",
+        ),
+        (None, " - Toggle the highlighted group"),
+      ],
+      vec![
+        (
+          Some((
+            ratatui::style::Color::Yellow,
+            ratatui::style::Modifier::BOLD,
+          )),
+          "Enter in Shell",
+        ),
+        (None, " - Pick the highlighted login shell"),
       ],
       vec![
         (
@@ -427,22 +846,42 @@ impl AddUser {
       ],
       vec![(None, "")],
       vec![(None, "Create a new user account for your NixOS system.")],
-      vec![(None, "Enter username, password, and confirm password.")],
+      vec![(
+        None,
+        "Enter username, pick groups and a shell, then password and confirm password.",
+      )],
       vec![(None, "Passwords are hidden during entry for security.")],
     ]);
     let help_modal = HelpModal::new("Add User", help_content);
+    let groups = WidgetBox::button_menu(Self::group_checkboxes(&[]));
+    let shells = std::iter::once(DEFAULT_SHELL)
+      .chain(SHELLS)
+      .map(String::from)
+      .collect();
+    let mut shell_list = StrList::new("Shell", shells);
+    shell_list.commit_value(Some(DEFAULT_SHELL));
     Self {
       name_input,
+      groups,
+      shell_list,
+      extra_groups: vec![],
       pass_input: LineEditor::new("Password", None::<&str>).secret(true),
       pass_confirm: LineEditor::new("Confirm Password", None::<&str>).secret(true),
       help_modal,
       username: None,
+      template: None,
     }
   }
   pub fn cycle_forward(&mut self) {
     // Tab was pressed
     if self.name_input.is_focused() {
       self.name_input.unfocus();
+      self.groups.focus();
+    } else if self.groups.is_focused() {
+      self.groups.unfocus();
+      self.shell_list.focus();
+    } else if self.shell_list.is_focused() {
+      self.shell_list.unfocus();
       self.pass_input.focus();
     } else if self.pass_input.is_focused() {
       self.pass_input.unfocus();
@@ -459,9 +898,15 @@ impl AddUser {
     if self.name_input.is_focused() {
       self.name_input.unfocus();
       self.pass_confirm.focus();
+    } else if self.groups.is_focused() {
+      self.groups.unfocus();
+      self.name_input.focus();
+    } else if self.shell_list.is_focused() {
+      self.shell_list.unfocus();
+      self.groups.focus();
     } else if self.pass_input.is_focused() {
       self.pass_input.unfocus();
-      self.name_input.focus();
+      self.shell_list.focus();
     } else if self.pass_confirm.is_focused() {
       self.pass_confirm.unfocus();
       self.pass_input.focus();
@@ -498,19 +943,61 @@ impl Page for AddUser {
       0,
       [
         Constraint::Length(5),
+        Constraint::Length(COMMON_GROUPS.len().max(SHELLS.len() + 1) as u16 + 2),
         Constraint::Length(5),
         Constraint::Length(5),
         Constraint::Min(0),
       ]
     );
+    let choice_chunks = split_hor!(
+      chunks[1],
+      0,
+      [Constraint::Percentage(65), Constraint::Percentage(35)]
+    );
     self.name_input.render(f, chunks[0]);
-    self.pass_input.render(f, chunks[1]);
-    self.pass_confirm.render(f, chunks[2]);
+    self.groups.render(f, choice_chunks[0]);
+    self.shell_list.render(f, choice_chunks[1]);
+    self.pass_input.render(f, chunks[2]);
+    self.pass_confirm.render(f, chunks[3]);
+    if let Some(template) = &self.template {
+      let home_manager = if template.home_manager_cfg.is_some() {
+        "yes"
+      } else {
+        "no"
+      };
+      let mut lines = vec![vec![
+        (None, "Based on ".to_string()),
+        (HIGHLIGHT, template.username.clone()),
+      ]];
+      if !self.extra_groups.is_empty() {
+        lines.push(vec![(
+          None,
+          format!("Other groups: {}", self.extra_groups.join(", ")),
+        )]);
+      }
+      lines.push(vec![(None, format!("Home Manager: {home_manager}"))]);
+      let info = InfoBox::new("Copied Settings", styled_block(lines));
+      info.render(f, chunks[4]);
+    }
 
     // Render help modal on top
     self.help_modal.render(f, area);
   }
 
+  fn handle_paste(&mut self, _installer: &mut super::Installer, text: &str) -> Signal {
+    if self.help_modal.visible {
+      return Signal::Wait;
+    }
+    if self.name_input.is_focused() {
+      self.name_input.paste(text);
+    } else if self.pass_input.is_focused() {
+      self.pass_input.paste(text);
+    } else if self.pass_confirm.is_focused() {
+      self.pass_confirm.paste(text);
+    }
+    Signal::Wait
+  }
+
   fn handle_input(
     &mut self,
     installer: &mut super::Installer,
@@ -521,7 +1008,7 @@ impl Page for AddUser {
         self.help_modal.toggle();
         return Signal::Wait;
       }
-      ui_close!() if self.help_modal.visible => {
+      code if ui_close!(code) && self.help_modal.visible => {
         self.help_modal.hide();
         return Signal::Wait;
       }
@@ -543,27 +1030,64 @@ impl Page for AddUser {
     if self.name_input.is_focused() {
       match event.code {
         KeyCode::Enter => {
-          if let Some(name) = self.name_input.get_value() {
-            let Some(name) = name.as_str() else {
-              self.name_input.error("Username cannot be empty");
-              return Signal::Wait;
-            };
-            if name.is_empty() {
-              self.name_input.error("Username cannot be empty");
-              return Signal::Wait;
-            }
-            self.username = Some(name.to_string());
+          if self.check_username(installer) {
             self.name_input.unfocus();
-            self.pass_input.focus();
-            Signal::Wait
-          } else {
-            self.name_input.error("Username cannot be empty");
-            Signal::Wait
+            self.groups.focus();
           }
+          Signal::Wait
         }
         KeyCode::Esc => Signal::Pop,
         _ => self.name_input.handle_input(event),
       }
+    } else if self.groups.is_focused() {
+      match event.code {
+        code if ui_down!(code) => {
+          if !self.groups.next_child() {
+            self.groups.first_child();
+          }
+          Signal::Wait
+        }
+        code if ui_up!(code) => {
+          if !self.groups.prev_child() {
+            self.groups.last_child();
+          }
+          Signal::Wait
+        }
+        KeyCode::Char(' ') => {
+          if let Some(checkbox) = self.groups.focused_child_mut() {
+            checkbox.interact();
+          }
+          Signal::Wait
+        }
+        KeyCode::Enter => {
+          self.groups.unfocus();
+          self.shell_list.focus();
+          Signal::Wait
+        }
+        _ => Signal::Wait,
+      }
+    } else if self.shell_list.is_focused() {
+      match event.code {
+        code if ui_down!(code) => {
+          if !self.shell_list.next_item() {
+            self.shell_list.first_item();
+          }
+          Signal::Wait
+        }
+        code if ui_up!(code) => {
+          if !self.shell_list.previous_item() {
+            self.shell_list.last_item();
+          }
+          Signal::Wait
+        }
+        KeyCode::Enter => {
+          self.shell_list.handle_input(event);
+          self.shell_list.unfocus();
+          self.pass_input.focus();
+          Signal::Wait
+        }
+        _ => Signal::Wait,
+      }
     } else if self.pass_input.is_focused() {
       match event.code {
         KeyCode::Enter => {
@@ -573,6 +1097,14 @@ impl Page for AddUser {
               return Signal::Wait;
             };
             if pass.is_empty() {
+              // Imperative users can be given a password with passwd later
+              if installer.mutable_users {
+                if !self.check_username(installer) {
+                  return Signal::Wait;
+                }
+                installer.users.push(self.new_user(None));
+                return Signal::Pop;
+              }
               self.pass_input.error("Password cannot be empty");
               return Signal::Wait;
             }
@@ -608,19 +1140,10 @@ impl Page for AddUser {
                 self.pass_confirm.unfocus();
                 return Signal::Wait;
               }
-              let hashed = match super::RootPassword::mkpasswd(pass.to_string()) {
-                Ok(h) => h,
-                Err(e) => {
-                  return Signal::Error(anyhow::anyhow!("Failed to hash password: {}", e));
-                }
-              };
-
-              installer.users.push(User {
-                username: self.username.clone().unwrap_or_default(),
-                password_hash: hashed,
-                groups: vec![],
-                home_manager_cfg: None,
-              });
+              if !self.check_username(installer) {
+                return Signal::Wait;
+              }
+              installer.users.push(self.new_user(Some(pass.to_string())));
               Signal::Pop
             } else {
               self
@@ -671,7 +1194,27 @@ impl Page for AddUser {
           )),
           "Enter",
         ),
-        (None, " - Create user account"),
+        (None, " - Next field, create the account from the last one"),
+      ],
+      vec![
+        (
+          Some((
+            ratatui::style::Color::Yellow,
+            ratatui::style::Modifier::BOLD,
+          )),
+          "Space",
+        ),
+        (None, " - Toggle the highlighted group"),
+      ],
+      vec![
+        (
+          Some((
+            ratatui::style::Color::Yellow,
+            ratatui::style::Modifier::BOLD,
+          )),
+          "Enter in Shell",
+        ),
+        (None, " - Pick the highlighted login shell"),
       ],
       vec![
         (
@@ -715,7 +1258,10 @@ impl Page for AddUser {
       ],
       vec![(None, "")],
       vec![(None, "Create a new user account for your NixOS system.")],
-      vec![(None, "Enter username, password, and confirm password.")],
+      vec![(
+        None,
+        "Enter username, pick groups and a shell, then password and confirm password.",
+      )],
       vec![(None, "Passwords are hidden during entry for security.")],
     ]);
     ("Add User".to_string(), help_content)
@@ -743,15 +1289,18 @@ pub struct AlterUser {
 }
 
 impl AlterUser {
-  pub fn new(selected_user_idx: usize, groups: Vec<String>) -> Self {
-    let buttons = vec![
+  fn menu_buttons(delete_label: &str) -> Vec<Box<dyn ConfigWidget>> {
+    vec![
       Box::new(Button::new("Change username")) as Box<dyn ConfigWidget>,
       Box::new(Button::new("Change password")) as Box<dyn ConfigWidget>,
       Box::new(Button::new("Edit Groups")) as Box<dyn ConfigWidget>,
+      Box::new(Button::new("SSH authorized keys")) as Box<dyn ConfigWidget>,
       Box::new(Button::new("Configure Home Manager")) as Box<dyn ConfigWidget>,
-      Box::new(Button::new("Delete user")) as Box<dyn ConfigWidget>,
-    ];
-    let mut buttons = WidgetBox::button_menu(buttons);
+      Box::new(Button::new(delete_label)) as Box<dyn ConfigWidget>,
+    ]
+  }
+  pub fn new(selected_user_idx: usize, user: &User) -> Self {
+    let mut buttons = WidgetBox::button_menu(Self::menu_buttons("Delete user"));
     buttons.focus();
     let help_content = styled_block(vec![
       vec![
@@ -760,7 +1309,7 @@ impl AlterUser {
             ratatui::style::Color::Yellow,
             ratatui::style::Modifier::BOLD,
           )),
-          "↑/↓, j/k",
+          keys::bindings().nav_label,
         ),
         (None, " - Navigate menu options"),
       ],
@@ -790,7 +1339,7 @@ impl AlterUser {
             ratatui::style::Color::Yellow,
             ratatui::style::Modifier::BOLD,
           )),
-          "Esc, q, ←, h",
+          keys::bindings().back_label,
         ),
         (None, " - Return to previous menu"),
       ],
@@ -809,17 +1358,22 @@ impl AlterUser {
         None,
         "Modify an existing user account. Choose from changing",
       )],
-      vec![(None, "username, password, groups, or deleting the user.")],
+      vec![(
+        None,
+        "username, password, groups, SSH keys, or deleting the user.",
+      )],
     ]);
     let help_modal = HelpModal::new("Alter User", help_content);
+    let mut name_input = LineEditor::new("New username", None::<&str>);
+    name_input.set_value(&user.username);
     Self {
       selected_user: selected_user_idx,
       buttons,
-      name_input: LineEditor::new("New username", None::<&str>),
+      name_input,
       pass_input: LineEditor::new("New password", None::<&str>).secret(true),
       pass_confirm: LineEditor::new("Confirm password", None::<&str>).secret(true),
       group_name_input: LineEditor::new("Add group", None::<&str>),
-      group_list: StrList::new("Groups", groups),
+      group_list: StrList::new("Groups", user.groups.clone()),
       help_modal,
       confirming_delete: false,
     }
@@ -938,23 +1492,18 @@ impl AlterUser {
   ) -> Signal {
     if self.confirming_delete && event.code != KeyCode::Enter {
       self.confirming_delete = false;
-      let buttons = vec![
-        Box::new(Button::new("Change username")) as Box<dyn ConfigWidget>,
-        Box::new(Button::new("Change password")) as Box<dyn ConfigWidget>,
-        Box::new(Button::new("Edit Groups")) as Box<dyn ConfigWidget>,
-        Box::new(Button::new("Configure Home Manager")) as Box<dyn ConfigWidget>,
-        Box::new(Button::new("Delete user")) as Box<dyn ConfigWidget>,
-      ];
-      self.buttons.set_children_inplace(buttons);
+      self
+        .buttons
+        .set_children_inplace(Self::menu_buttons("Delete user"));
     }
     match event.code {
-      ui_down!() => {
+      code if ui_down!(code) => {
         if !self.buttons.next_child() {
           self.buttons.first_child();
         }
         Signal::Wait
       }
-      ui_up!() => {
+      code if ui_up!(code) => {
         if !self.buttons.prev_child() {
           self.buttons.last_child();
         }
@@ -980,28 +1529,35 @@ impl AlterUser {
             self.group_name_input.focus();
             Signal::Wait
           }
-          Some(3) => {
+          Some(3) => Signal::Push(Box::new(SshKeys::new(
+            self.selected_user,
+            installer
+              .users
+              .get(self.selected_user)
+              .map(|u| u.ssh_keys.clone())
+              .unwrap_or_default(),
+          ))),
+          Some(4) => {
             let existing_config = installer
               .users
               .get(self.selected_user)
               .and_then(|user| user.home_manager_cfg.clone());
-            Signal::Push(Box::new(ConfigureHomeManager::new(
-              self.selected_user,
-              existing_config,
-            )))
+            let selected_user = self.selected_user;
+            with_available_pkgs(move |pkgs| {
+              Box::new(ConfigureHomeManager::new(
+                selected_user,
+                existing_config,
+                pkgs,
+              ))
+            })
           }
-          Some(4) => {
+          Some(5) => {
             // Delete user
             if !self.confirming_delete {
               self.confirming_delete = true;
-              let buttons = vec![
-                Box::new(Button::new("Change username")) as Box<dyn ConfigWidget>,
-                Box::new(Button::new("Change password")) as Box<dyn ConfigWidget>,
-                Box::new(Button::new("Edit Groups")) as Box<dyn ConfigWidget>,
-                Box::new(Button::new("Configure Home Manager")) as Box<dyn ConfigWidget>,
-                Box::new(Button::new("Really?")) as Box<dyn ConfigWidget>,
-              ];
-              self.buttons.set_children_inplace(buttons);
+              self
+                .buttons
+                .set_children_inplace(Self::menu_buttons("Really?"));
               Signal::Wait
             } else {
               if self.selected_user < installer.users.len() {
@@ -1013,7 +1569,7 @@ impl AlterUser {
           _ => Signal::Wait,
         }
       }
-      ui_back!() => Signal::Pop,
+      code if ui_back!(code) => Signal::Pop,
       _ => Signal::Wait,
     }
   }
@@ -1033,6 +1589,17 @@ impl AlterUser {
             self.name_input.error("Username cannot be empty");
             return Signal::Wait;
           }
+          let taken = installer
+            .users
+            .iter()
+            .enumerate()
+            .any(|(idx, u)| idx != self.selected_user && u.username == name);
+          if taken {
+            self
+              .name_input
+              .error("A user with that name already exists");
+            return Signal::Wait;
+          }
           if self.selected_user < installer.users.len() {
             installer.users[self.selected_user].username = name.to_string();
           }
@@ -1044,7 +1611,7 @@ impl AlterUser {
           Signal::Wait
         }
       }
-      ui_close!() => {
+      code if ui_close!(code) => {
         self.name_input.unfocus();
         self.buttons.focus();
         Signal::Wait
@@ -1120,14 +1687,8 @@ impl AlterUser {
                 self.pass_confirm.clear();
                 return Signal::Wait;
               }
-              let hashed = match super::RootPassword::mkpasswd(pass.to_string()) {
-                Ok(h) => h,
-                Err(e) => {
-                  return Signal::Error(anyhow::anyhow!("Failed to hash password: {}", e));
-                }
-              };
-              if self.selected_user < installer.users.len() {
-                installer.users[self.selected_user].password_hash = hashed;
+              if let Some(user) = installer.users.get_mut(self.selected_user) {
+                user.pending_password = Some(pass.to_string());
               }
               self.pass_confirm.unfocus();
               self.buttons.focus();
@@ -1203,13 +1764,13 @@ impl AlterUser {
     } else if self.group_list.is_focused() {
       // Enter deletes items from the list
       match event.code {
-        ui_down!() => {
+        code if ui_down!(code) => {
           if !self.group_list.next_item() {
             self.group_list.first_item();
           }
           Signal::Wait
         }
-        ui_up!() => {
+        code if ui_up!(code) => {
           if !self.group_list.previous_item() {
             self.group_list.last_item();
           }
@@ -1235,7 +1796,7 @@ impl AlterUser {
           self.group_name_input.focus();
           Signal::Wait
         }
-        ui_close!() => {
+        code if ui_close!(code) => {
           self.group_list.unfocus();
           self.buttons.focus();
           Signal::Wait
@@ -1273,6 +1834,22 @@ impl Page for AlterUser {
     self.help_modal.render(f, area);
   }
 
+  fn handle_paste(&mut self, _installer: &mut super::Installer, text: &str) -> Signal {
+    if self.help_modal.visible {
+      return Signal::Wait;
+    }
+    if self.name_input.is_focused() {
+      self.name_input.paste(text);
+    } else if self.pass_input.is_focused() {
+      self.pass_input.paste(text);
+    } else if self.pass_confirm.is_focused() {
+      self.pass_confirm.paste(text);
+    } else if self.group_name_input.is_focused() {
+      self.group_name_input.paste(text);
+    }
+    Signal::Wait
+  }
+
   fn handle_input(
     &mut self,
     installer: &mut super::Installer,
@@ -1283,7 +1860,7 @@ impl Page for AlterUser {
         self.help_modal.toggle();
         return Signal::Wait;
       }
-      ui_close!() if self.help_modal.visible => {
+      code if ui_close!(code) && self.help_modal.visible => {
         self.help_modal.hide();
         return Signal::Wait;
       }
@@ -1315,7 +1892,7 @@ impl Page for AlterUser {
             ratatui::style::Color::Yellow,
             ratatui::style::Modifier::BOLD,
           )),
-          "↑/↓, j/k",
+          keys::bindings().nav_label,
         ),
         (None, " - Navigate menu options"),
       ],
@@ -1345,7 +1922,7 @@ impl Page for AlterUser {
             ratatui::style::Color::Yellow,
             ratatui::style::Modifier::BOLD,
           )),
-          "Esc, q, ←, h",
+          keys::bindings().back_label,
         ),
         (None, " - Return to previous menu"),
       ],
@@ -1364,12 +1941,408 @@ impl Page for AlterUser {
         None,
         "Modify an existing user account. Choose from changing",
       )],
-      vec![(None, "username, password, groups, or deleting the user.")],
+      vec![(
+        None,
+        "username, password, groups, SSH keys, or deleting the user.",
+      )],
     ]);
     ("Alter User".to_string(), help_content)
   }
 }
 
+/// Key types `ssh-keygen` produces, as they start a public key line
+const SSH_KEY_TYPES: [&str; 7] = [
+  "ssh-ed25519",
+  "ssh-rsa",
+  "ecdsa-sha2-nistp256",
+  "ecdsa-sha2-nistp384",
+  "ecdsa-sha2-nistp521",
+  "sk-ssh-ed25519@openssh.com",
+  "sk-ecdsa-sha2-nistp256@openssh.com",
+];
+
+/// Check that `line` looks like an OpenSSH public key
+///
+/// Only the shape is checked: a known key type followed by base64 data and an
+/// optional comment. That's enough to catch a private key or a fingerprint
+/// pasted by mistake.
+pub fn parse_ssh_key(line: &str) -> Result<String, String> {
+  let line = line.trim();
+  if line.is_empty() {
+    return Err("Key cannot be empty".to_string());
+  }
+  let mut fields = line.split_whitespace();
+  let key_type = fields.next().unwrap_or_default();
+  if !SSH_KEY_TYPES.contains(&key_type) {
+    return Err(format!(
+      "'{key_type}' is not a known key type, expected e.g. ssh-ed25519 or ssh-rsa"
+    ));
+  }
+  let data = fields.next().unwrap_or_default();
+  let is_base64 = data
+    .chars()
+    .all(|c| c.is_ascii_alphanumeric() || "+/=".contains(c));
+  // The shortest real key, ed25519, is 68 characters of base64
+  if data.len() < 68 || !is_base64 {
+    return Err(format!("The {key_type} key data is missing or malformed"));
+  }
+  Ok(line.to_string())
+}
+
+/// Read the public keys in an `authorized_keys` style file, skipping blank
+/// lines and comments
+pub fn load_ssh_keys(path: &str) -> Result<Vec<String>, String> {
+  let text = std::fs::read_to_string(path.trim()).map_err(|e| format!("Can't read {path}: {e}"))?;
+  let keys = text
+    .lines()
+    .enumerate()
+    .filter(|(_, line)| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+    .map(|(i, line)| parse_ssh_key(line).map_err(|e| format!("Line {}: {e}", i + 1)))
+    .collect::<Result<Vec<_>, _>>()?;
+  if keys.is_empty() {
+    return Err(format!("{path} has no keys in it"));
+  }
+  Ok(keys)
+}
+
+/// A key shortened to fit a list row, the type, the end of the data and the
+/// comment
+fn key_summary(key: &str) -> String {
+  let mut fields = key.split_whitespace();
+  let key_type = fields.next().unwrap_or_default();
+  let data = fields.next().unwrap_or_default();
+  let tail = &data[data.len().saturating_sub(12)..];
+  let comment = fields.collect::<Vec<_>>().join(" ");
+  format!("{key_type} …{tail} {comment}")
+    .trim_end()
+    .to_string()
+}
+
+enum SshKeysMode {
+  List,
+  /// Typing or pasting a single key
+  Key,
+  /// Typing the path of a file to read keys from
+  File,
+}
+
+/// `users.users.<name>.openssh.authorizedKeys.keys` for one user
+pub struct SshKeys {
+  selected_user: usize,
+  keys: Vec<String>,
+  key_list: StrList,
+  input: LineEditor,
+  mode: SshKeysMode,
+  help_modal: HelpModal<'static>,
+}
+
+impl SshKeys {
+  pub fn new(selected_user: usize, keys: Vec<String>) -> Self {
+    let mut key_list = StrList::new(
+      "Authorized Keys",
+      keys.iter().map(|k| key_summary(k)).collect(),
+    );
+    key_list.focus();
+    let help_content = styled_block(vec![
+      vec![
+        (
+          Some((
+            ratatui::style::Color::Yellow,
+            ratatui::style::Modifier::BOLD,
+          )),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Navigate keys"),
+      ],
+      vec![
+        (
+          Some((
+            ratatui::style::Color::Yellow,
+            ratatui::style::Modifier::BOLD,
+          )),
+          "a",
+        ),
+        (None, " - Add a key by pasting it"),
+      ],
+      vec![
+        (
+          Some((
+            ratatui::style::Color::Yellow,
+            ratatui::style::Modifier::BOLD,
+          )),
+          "f",
+        ),
+        (None, " - Load keys from a file, e.g. an authorized_keys"),
+      ],
+      vec![
+        (
+          Some((
+            ratatui::style::Color::Yellow,
+            ratatui::style::Modifier::BOLD,
+          )),
+          "d",
+        ),
+        (None, " - Remove the selected key"),
+      ],
+      vec![
+        (
+          Some((
+            ratatui::style::Color::Yellow,
+            ratatui::style::Modifier::BOLD,
+          )),
+          "Esc, q",
+        ),
+        (None, " - Return to the user menu"),
+      ],
+      vec![
+        (
+          Some((
+            ratatui::style::Color::Yellow,
+            ratatui::style::Modifier::BOLD,
+          )),
+          "?",
+        ),
+        (None, " - Show this help"),
+      ],
+      vec![(None, "")],
+      vec![(
+        None,
+        "Keys only work if the SSH server is enabled in the network settings.",
+      )],
+    ]);
+    let help_modal = HelpModal::new("SSH Authorized Keys", help_content);
+    Self {
+      selected_user,
+      keys,
+      key_list,
+      input: LineEditor::new("Public key", Some("ssh-ed25519 AAAA... user@host")),
+      mode: SshKeysMode::List,
+      help_modal,
+    }
+  }
+  fn open_input(&mut self, mode: SshKeysMode) {
+    self.input = match mode {
+      SshKeysMode::File => LineEditor::new("Key file", Some("e.g. /root/.ssh/authorized_keys")),
+      _ => LineEditor::new("Public key", Some("ssh-ed25519 AAAA... user@host")),
+    };
+    self.key_list.unfocus();
+    self.input.focus();
+    self.mode = mode;
+  }
+  fn close_input(&mut self) {
+    self.input.unfocus();
+    self.key_list.focus();
+    self.mode = SshKeysMode::List;
+  }
+  /// Add keys that aren't there yet and write the list back to the user
+  fn add_keys(&mut self, installer: &mut Installer, keys: Vec<String>) {
+    for key in keys {
+      if !self.keys.contains(&key) {
+        self.keys.push(key);
+      }
+    }
+    self.save(installer);
+  }
+  fn save(&mut self, installer: &mut Installer) {
+    self
+      .key_list
+      .set_items(self.keys.iter().map(|k| key_summary(k)).collect());
+    if let Some(user) = installer.users.get_mut(self.selected_user) {
+      user.ssh_keys = self.keys.clone();
+    }
+  }
+}
+
+impl Page for SshKeys {
+  fn render(
+    &mut self,
+    _installer: &mut super::Installer,
+    f: &mut ratatui::Frame,
+    area: ratatui::prelude::Rect,
+  ) {
+    let chunks = split_vert!(area, 1, [Constraint::Min(0), Constraint::Length(3)]);
+    let hor_chunks = split_hor!(
+      chunks[0],
+      1,
+      [
+        Constraint::Percentage(15),
+        Constraint::Percentage(70),
+        Constraint::Percentage(15),
+      ]
+    );
+    self.key_list.render(f, hor_chunks[1]);
+    if !matches!(self.mode, SshKeysMode::List) {
+      let input_chunks = split_hor!(
+        chunks[1],
+        0,
+        [
+          Constraint::Percentage(15),
+          Constraint::Percentage(70),
+          Constraint::Percentage(15),
+        ]
+      );
+      self.input.render(f, input_chunks[1]);
+    }
+    self.help_modal.render(f, area);
+  }
+
+  fn handle_paste(&mut self, _installer: &mut super::Installer, text: &str) -> Signal {
+    if self.help_modal.visible {
+      return Signal::Wait;
+    }
+    // Pasting a key straight into the list starts adding it
+    if matches!(self.mode, SshKeysMode::List) {
+      self.open_input(SshKeysMode::Key);
+    }
+    self.input.paste(text);
+    Signal::Wait
+  }
+
+  fn handle_input(
+    &mut self,
+    installer: &mut super::Installer,
+    event: ratatui::crossterm::event::KeyEvent,
+  ) -> Signal {
+    if !matches!(self.mode, SshKeysMode::List) {
+      return match event.code {
+        KeyCode::Esc => {
+          self.close_input();
+          Signal::Wait
+        }
+        KeyCode::Enter => {
+          let input = self
+            .input
+            .get_value()
+            .and_then(|v| v.as_str().map(|s| s.to_string()))
+            .unwrap_or_default();
+          let parsed = match self.mode {
+            SshKeysMode::File => load_ssh_keys(&input),
+            _ => parse_ssh_key(&input).map(|key| vec![key]),
+          };
+          match parsed {
+            Ok(keys) => {
+              self.add_keys(installer, keys);
+              self.close_input();
+            }
+            Err(e) => self.input.error(e),
+          }
+          Signal::Wait
+        }
+        _ => self.input.handle_input(event),
+      };
+    }
+
+    match event.code {
+      KeyCode::Char('?') => {
+        self.help_modal.toggle();
+        Signal::Wait
+      }
+      code if ui_close!(code) && self.help_modal.visible => {
+        self.help_modal.hide();
+        Signal::Wait
+      }
+      _ if self.help_modal.visible => Signal::Wait,
+      code if ui_close!(code) => Signal::Pop,
+      KeyCode::Char('a') => {
+        self.open_input(SshKeysMode::Key);
+        Signal::Wait
+      }
+      KeyCode::Char('f') => {
+        self.open_input(SshKeysMode::File);
+        Signal::Wait
+      }
+      KeyCode::Char('d') => {
+        let idx = self.key_list.selected_idx;
+        if idx < self.keys.len() {
+          self.keys.remove(idx);
+          self.save(installer);
+        }
+        Signal::Wait
+      }
+      code if ui_up!(code) => {
+        self.key_list.previous_item();
+        Signal::Wait
+      }
+      code if ui_down!(code) => {
+        self.key_list.next_item();
+        Signal::Wait
+      }
+      _ => Signal::Wait,
+    }
+  }
+
+  fn get_help_content(&self) -> (String, Vec<Line<'_>>) {
+    let help_content = styled_block(vec![
+      vec![
+        (
+          Some((
+            ratatui::style::Color::Yellow,
+            ratatui::style::Modifier::BOLD,
+          )),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Navigate keys"),
+      ],
+      vec![
+        (
+          Some((
+            ratatui::style::Color::Yellow,
+            ratatui::style::Modifier::BOLD,
+          )),
+          "a",
+        ),
+        (None, " - Add a key by pasting it"),
+      ],
+      vec![
+        (
+          Some((
+            ratatui::style::Color::Yellow,
+            ratatui::style::Modifier::BOLD,
+          )),
+          "f",
+        ),
+        (None, " - Load keys from a file, e.g. an authorized_keys"),
+      ],
+      vec![
+        (
+          Some((
+            ratatui::style::Color::Yellow,
+            ratatui::style::Modifier::BOLD,
+          )),
+          "d",
+        ),
+        (None, " - Remove the selected key"),
+      ],
+      vec![
+        (
+          Some((
+            ratatui::style::Color::Yellow,
+            ratatui::style::Modifier::BOLD,
+          )),
+          "Esc, q",
+        ),
+        (None, " - Return to the user menu"),
+      ],
+      vec![
+        (
+          Some((
+            ratatui::style::Color::Yellow,
+            ratatui::style::Modifier::BOLD,
+          )),
+          "?",
+        ),
+        (None, " - Show this help"),
+      ],
+      vec![(None, "")],
+      vec![(
+        None,
+        "Keys only work if the SSH server is enabled in the network settings.",
+      )],
+    ]);
+    ("SSH Authorized Keys".to_string(), help_content)
+  }
+}
+
 pub struct ConfigureHomeManager {
   pub confirmed: bool,
   pub picking_pkgs: bool,
@@ -1381,7 +2354,11 @@ pub struct ConfigureHomeManager {
 }
 
 impl ConfigureHomeManager {
-  pub fn new(selected_user: usize, existing_config: Option<HomeManagerCfg>) -> Self {
+  pub fn new(
+    selected_user: usize,
+    existing_config: Option<HomeManagerCfg>,
+    pkgs: Vec<String>,
+  ) -> Self {
     let buttons = vec![
       Box::new(Button::new("Yes")) as Box<dyn ConfigWidget>,
       Box::new(Button::new("No")) as Box<dyn ConfigWidget>,
@@ -1395,7 +2372,6 @@ impl ConfigureHomeManager {
     let mut configuration_options = WidgetBox::button_menu(config_options);
     if let Some(cfg) = existing_config {
       configuration_options.focus();
-      let pkgs = get_available_pkgs().unwrap_or_default();
       let selected_pkgs = cfg.packages.clone();
       let package_picker = PackagePicker::new(
         "Selected User Packages",
@@ -1414,7 +2390,6 @@ impl ConfigureHomeManager {
       }
     } else {
       confirm_buttons.focus();
-      let pkgs = get_available_pkgs().unwrap_or_default();
       let package_picker =
         PackagePicker::new("Selected User Packages", "Available Packages", vec![], pkgs);
       Self {
@@ -1514,6 +2489,13 @@ impl Page for ConfigureHomeManager {
       table.unwrap().render(f, vert_chunks[1]);
     }
   }
+  fn handle_paste(&mut self, _installer: &mut Installer, text: &str) -> Signal {
+    if self.picking_pkgs {
+      self.package_picker.paste(text);
+    }
+    Signal::Wait
+  }
+
   fn handle_input(
     &mut self,
     installer: &mut Installer,
@@ -1521,13 +2503,13 @@ impl Page for ConfigureHomeManager {
   ) -> Signal {
     if !self.confirmed {
       match event.code {
-        ui_down!() => {
+        code if ui_down!(code) => {
           if !self.confirm_buttons.next_child() {
             self.confirm_buttons.first_child();
           }
           Signal::Wait
         }
-        ui_up!() => {
+        code if ui_up!(code) => {
           if !self.confirm_buttons.prev_child() {
             self.confirm_buttons.last_child();
           }
@@ -1563,7 +2545,7 @@ impl Page for ConfigureHomeManager {
       }
     } else if self.picking_pkgs {
       match event.code {
-        ui_close!() => {
+        code if ui_close!(code) => {
           if self.package_picker.search_bar.is_focused() {
             self.package_picker.handle_input(event)
           } else {
@@ -1591,19 +2573,19 @@ impl Page for ConfigureHomeManager {
           .set_children_inplace(config_options);
       }
       match event.code {
-        ui_down!() => {
+        code if ui_down!(code) => {
           if !self.configuration_options.next_child() {
             self.configuration_options.first_child();
           }
           Signal::Wait
         }
-        ui_up!() => {
+        code if ui_up!(code) => {
           if !self.configuration_options.prev_child() {
             self.configuration_options.last_child();
           }
           Signal::Wait
         }
-        ui_close!() => Signal::Pop,
+        code if ui_close!(code) => Signal::Pop,
         KeyCode::Enter => {
           match self.configuration_options.selected_child() {
             Some(0) => {
@@ -0,0 +1,602 @@
+use std::net::IpAddr;
+
+use ratatui::{
+  Frame,
+  crossterm::event::{KeyCode, KeyEvent},
+  layout::Rect,
+  style::{Color, Modifier},
+  text::Line,
+};
+use serde_json::Value;
+
+use crate::{
+  LineStyle,
+  installer::{HIGHLIGHT, Installer, Page, Signal, networking::parse_port},
+  keys, split_hor, split_vert, styled_block, ui_back, ui_close, ui_down, ui_up,
+  widget::{Button, CheckBox, ConfigWidget, HelpModal, InfoBox, LineEditor, StrList, WidgetBox},
+};
+
+#[derive(Default, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct VpnCfg {
+  /// `services.tailscale.enable`, logging in is left to `tailscale up`
+  pub tailscale: bool,
+  pub wireguard: WireguardCfg,
+}
+
+/// A single WireGuard interface
+///
+/// The private key is referenced by path and generated on first boot if it
+/// doesn't exist, so it never ends up in the config or the nix store.
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct WireguardCfg {
+  pub enable: bool,
+  pub interface: String,
+  /// Addresses of this machine on the tunnel, in CIDR notation
+  pub ips: Vec<String>,
+  pub listen_port: Option<u16>,
+  pub private_key_file: String,
+  pub peers: Vec<WgPeer>,
+}
+
+impl Default for WireguardCfg {
+  fn default() -> Self {
+    Self {
+      enable: false,
+      interface: "wg0".to_string(),
+      ips: vec![],
+      listen_port: None,
+      private_key_file: "/etc/wireguard/private.key".to_string(),
+      peers: vec![],
+    }
+  }
+}
+
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct WgPeer {
+  pub public_key: String,
+  pub allowed_ips: Vec<String>,
+  pub endpoint: Option<String>,
+}
+
+impl WgPeer {
+  fn summary(&self) -> String {
+    let key: String = self.public_key.chars().take(8).collect();
+    let ips = self.allowed_ips.join(", ");
+    match self.endpoint {
+      Some(ref endpoint) => format!("{key}… {ips} via {endpoint}"),
+      None => format!("{key}… {ips}"),
+    }
+  }
+}
+
+/// Interface names are limited to 15 characters by the kernel
+pub fn parse_wg_interface(input: &str) -> Result<String, String> {
+  let input = input.trim();
+  if input.is_empty() || input.len() > 15 {
+    return Err("Interface name must be 1 to 15 characters".to_string());
+  }
+  if !input
+    .chars()
+    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+  {
+    return Err("Interface name can only contain letters, digits, '-' and '_'".to_string());
+  }
+  Ok(input.to_string())
+}
+
+/// Parse a comma or space separated list of addresses like `10.0.0.2/24`
+pub fn parse_cidr_list(input: &str) -> Result<Vec<String>, String> {
+  let mut cidrs = vec![];
+  for item in input
+    .split(|c: char| c == ',' || c.is_whitespace())
+    .filter(|s| !s.is_empty())
+  {
+    let Some((addr, prefix)) = item.split_once('/') else {
+      return Err(format!(
+        "'{item}' is missing a prefix length, e.g. {item}/32"
+      ));
+    };
+    let Ok(addr) = addr.parse::<IpAddr>() else {
+      return Err(format!("'{addr}' is not an IP address"));
+    };
+    let max_prefix = if addr.is_ipv4() { 32 } else { 128 };
+    match prefix.parse::<u8>() {
+      Ok(len) if len <= max_prefix => cidrs.push(format!("{addr}/{len}")),
+      _ => {
+        return Err(format!(
+          "'{prefix}' is not a valid prefix length for {addr}"
+        ));
+      }
+    }
+  }
+  if cidrs.is_empty() {
+    return Err("Enter at least one address, e.g. 10.0.0.2/24".to_string());
+  }
+  Ok(cidrs)
+}
+
+pub fn parse_key_file(input: &str) -> Result<String, String> {
+  let input = input.trim();
+  if !input.starts_with('/') {
+    return Err("Key file must be an absolute path".to_string());
+  }
+  if input
+    .chars()
+    .any(|c| c.is_whitespace() || c.is_control() || c == '"' || c == '\\')
+  {
+    return Err("Key file path cannot contain spaces, quotes or backslashes".to_string());
+  }
+  Ok(input.to_string())
+}
+
+/// Parse `<public key> <allowed ips> [host:port]`
+pub fn parse_wg_peer(input: &str) -> Result<WgPeer, String> {
+  let mut parts = input.split_whitespace();
+  let (Some(public_key), Some(allowed_ips)) = (parts.next(), parts.next()) else {
+    return Err("Expected <public key> <allowed ips> [endpoint]".to_string());
+  };
+  let endpoint = parts.next();
+  if parts.next().is_some() {
+    return Err("Too many fields, separate allowed IPs with commas".to_string());
+  }
+  let valid_key = public_key.len() == 44
+    && public_key.ends_with('=')
+    && public_key[..43]
+      .chars()
+      .all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '/');
+  if !valid_key {
+    return Err(
+      "Public key should be 44 characters of base64, as printed by wg pubkey".to_string(),
+    );
+  }
+  let allowed_ips = parse_cidr_list(allowed_ips)?;
+  let endpoint = match endpoint {
+    Some(endpoint) => {
+      let Some((host, port)) = endpoint.rsplit_once(':') else {
+        return Err("Endpoint must be host:port, e.g. vpn.example.com:51820".to_string());
+      };
+      let valid_host = !host.is_empty()
+        && host
+          .chars()
+          .all(|c| c.is_ascii_alphanumeric() || ".-:[]".contains(c));
+      if !valid_host {
+        return Err(format!("'{host}' is not a valid endpoint host"));
+      }
+      parse_port(port)?;
+      Some(endpoint.to_string())
+    }
+    None => None,
+  };
+  Ok(WgPeer {
+    public_key: public_key.to_string(),
+    allowed_ips,
+    endpoint,
+  })
+}
+
+#[derive(Clone, Copy)]
+enum WgField {
+  Interface,
+  Addresses,
+  ListenPort,
+  KeyFile,
+}
+
+enum VpnMode {
+  Buttons,
+  Field(WgField),
+  Peers,
+  PeerEntry,
+}
+
+pub struct Vpn {
+  buttons: WidgetBox,
+  input: LineEditor,
+  peers: StrList,
+  mode: VpnMode,
+  help_modal: HelpModal<'static>,
+}
+
+impl Vpn {
+  pub fn new(installer: &Installer) -> Self {
+    let mut buttons = WidgetBox::button_menu(Self::button_widgets(&installer.vpn));
+    buttons.focus();
+    let peers = StrList::new("WireGuard Peers", Self::peer_lines(installer));
+    let help_content = styled_block(vec![
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Navigate options"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
+        (None, " - Toggle option or edit setting"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "a, d"),
+        (None, " - Add or delete peers in the peer list"),
+      ],
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().back_label,
+        ),
+        (None, " - Return"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "?"),
+        (None, " - Show this help"),
+      ],
+      vec![(None, "")],
+      vec![(
+        None,
+        "The WireGuard private key is created at the key file path on first boot.",
+      )],
+    ]);
+    let help_modal = HelpModal::new("VPN", help_content);
+    Self {
+      buttons,
+      input: LineEditor::new("", None::<&str>),
+      peers,
+      mode: VpnMode::Buttons,
+      help_modal,
+    }
+  }
+  fn button_widgets(cfg: &VpnCfg) -> Vec<Box<dyn ConfigWidget>> {
+    let wg = &cfg.wireguard;
+    let ips = if wg.ips.is_empty() {
+      "none".to_string()
+    } else {
+      wg.ips.join(", ")
+    };
+    let port = wg
+      .listen_port
+      .map(|p| p.to_string())
+      .unwrap_or_else(|| "none".to_string());
+    vec![
+      Box::new(CheckBox::new("Enable Tailscale", cfg.tailscale)),
+      Box::new(CheckBox::new("Enable WireGuard", wg.enable)),
+      Box::new(Button::new(format!("Interface: {}", wg.interface))),
+      Box::new(Button::new(format!("Addresses: {ips}"))),
+      Box::new(Button::new(format!("Listen Port: {port}"))),
+      Box::new(Button::new(format!("Key File: {}", wg.private_key_file))),
+      Box::new(Button::new(format!("Peers ({})", wg.peers.len()))),
+      Box::new(Button::new("Back")),
+    ]
+  }
+  fn refresh_buttons(&mut self, installer: &Installer) {
+    self
+      .buttons
+      .set_children_inplace(Self::button_widgets(&installer.vpn));
+  }
+  fn peer_lines(installer: &Installer) -> Vec<String> {
+    installer
+      .vpn
+      .wireguard
+      .peers
+      .iter()
+      .map(WgPeer::summary)
+      .collect()
+  }
+  fn open_field(&mut self, field: WgField, installer: &Installer) {
+    let wg = &installer.vpn.wireguard;
+    let (title, placeholder, value) = match field {
+      WgField::Interface => ("Interface Name", "e.g. wg0", wg.interface.clone()),
+      WgField::Addresses => (
+        "Addresses",
+        "e.g. 10.0.0.2/24, fd00::2/64",
+        wg.ips.join(", "),
+      ),
+      WgField::ListenPort => (
+        "Listen Port",
+        "e.g. 51820, leave empty for clients",
+        wg.listen_port.map(|p| p.to_string()).unwrap_or_default(),
+      ),
+      WgField::KeyFile => (
+        "Private Key File",
+        "e.g. /etc/wireguard/private.key",
+        wg.private_key_file.clone(),
+      ),
+    };
+    self.input = LineEditor::new(title, Some(placeholder));
+    self.input.set_value(value);
+    self.buttons.unfocus();
+    self.input.focus();
+    self.mode = VpnMode::Field(field);
+  }
+  fn back_to_buttons(&mut self, installer: &Installer) {
+    self.input.unfocus();
+    self.peers.unfocus();
+    self.refresh_buttons(installer);
+    self.buttons.focus();
+    self.mode = VpnMode::Buttons;
+  }
+  fn save_field(&mut self, field: WgField, installer: &mut Installer) -> Signal {
+    let input = self
+      .input
+      .get_value()
+      .and_then(|v| v.as_str().map(|s| s.trim().to_string()))
+      .unwrap_or_default();
+    let wg = &mut installer.vpn.wireguard;
+    let result = match field {
+      WgField::Interface => parse_wg_interface(&input).map(|name| wg.interface = name),
+      WgField::Addresses if input.is_empty() => {
+        wg.ips.clear();
+        Ok(())
+      }
+      WgField::Addresses => parse_cidr_list(&input).map(|ips| wg.ips = ips),
+      WgField::ListenPort if input.is_empty() => {
+        wg.listen_port = None;
+        Ok(())
+      }
+      WgField::ListenPort => parse_port(&input).map(|port| wg.listen_port = Some(port)),
+      WgField::KeyFile => parse_key_file(&input).map(|path| wg.private_key_file = path),
+    };
+    match result {
+      Ok(()) => self.back_to_buttons(installer),
+      Err(e) => self.input.error(e),
+    }
+    Signal::Wait
+  }
+  pub fn display_lines(installer: &Installer) -> Vec<Vec<(LineStyle, String)>> {
+    let vpn = &installer.vpn;
+    let mut lines = vec![];
+    if vpn.tailscale {
+      lines.push(vec![
+        (None, "Tailscale: ".into()),
+        (HIGHLIGHT, "Enabled".into()),
+      ]);
+    }
+    if vpn.wireguard.enable {
+      let wg = &vpn.wireguard;
+      lines.push(vec![
+        (None, "WireGuard: ".into()),
+        (
+          HIGHLIGHT,
+          format!("{} ({} peers)", wg.interface, wg.peers.len()),
+        ),
+      ]);
+    }
+    lines
+  }
+}
+
+impl Page for Vpn {
+  fn render(&mut self, _installer: &mut Installer, f: &mut Frame, area: Rect) {
+    let chunks = split_vert!(
+      area,
+      1,
+      [
+        Constraint::Percentage(30),
+        Constraint::Min(0),
+        Constraint::Length(3)
+      ]
+    );
+    let info_lines = vec![
+      vec![(
+        None,
+        "Tailscale joins this machine to your tailnet, run 'tailscale up' after install to log in.",
+      )],
+      vec![(None, "")],
+      vec![(
+        None,
+        "WireGuard sets up a single interface. Its private key is kept in the key file and generated on first boot if missing, get the public key with 'wg show'.",
+      )],
+    ];
+    InfoBox::new("VPN", styled_block(info_lines)).render(f, chunks[0]);
+
+    let hor_chunks = split_hor!(
+      chunks[1],
+      1,
+      [
+        Constraint::Percentage(20),
+        Constraint::Percentage(60),
+        Constraint::Percentage(20),
+      ]
+    );
+    match self.mode {
+      VpnMode::Buttons => self.buttons.render(f, hor_chunks[1]),
+      VpnMode::Field(_) => {
+        self.buttons.render(f, hor_chunks[1]);
+        self.input.render(f, chunks[2]);
+      }
+      VpnMode::Peers => self.peers.render(f, hor_chunks[1]),
+      VpnMode::PeerEntry => {
+        self.peers.render(f, hor_chunks[1]);
+        self.input.render(f, chunks[2]);
+      }
+    }
+    self.help_modal.render(f, area);
+  }
+
+  fn get_help_content(&self) -> (String, Vec<Line<'_>>) {
+    let help_content = styled_block(vec![
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Navigate options"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
+        (None, " - Toggle option or edit setting"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "a, d"),
+        (None, " - Add or delete peers in the peer list"),
+      ],
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().back_label,
+        ),
+        (None, " - Return"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "?"),
+        (None, " - Show this help"),
+      ],
+      vec![(None, "")],
+      vec![(
+        None,
+        "The WireGuard private key is created at the key file path on first boot.",
+      )],
+    ]);
+    ("VPN".to_string(), help_content)
+  }
+
+  fn handle_paste(&mut self, _installer: &mut Installer, text: &str) -> Signal {
+    if matches!(self.mode, VpnMode::Field(_) | VpnMode::PeerEntry) {
+      self.input.paste(text);
+    }
+    Signal::Wait
+  }
+
+  fn handle_input(&mut self, installer: &mut Installer, event: KeyEvent) -> Signal {
+    match self.mode {
+      VpnMode::Field(field) => {
+        return match event.code {
+          KeyCode::Esc => {
+            self.back_to_buttons(installer);
+            Signal::Wait
+          }
+          KeyCode::Enter => self.save_field(field, installer),
+          _ => self.input.handle_input(event),
+        };
+      }
+      VpnMode::PeerEntry => {
+        return match event.code {
+          KeyCode::Esc => {
+            self.input.unfocus();
+            self.peers.focus();
+            self.mode = VpnMode::Peers;
+            Signal::Wait
+          }
+          KeyCode::Enter => {
+            let input = self
+              .input
+              .get_value()
+              .and_then(|v| v.as_str().map(|s| s.to_string()))
+              .unwrap_or_default();
+            match parse_wg_peer(&input) {
+              Ok(peer) => {
+                installer.vpn.wireguard.peers.push(peer);
+                self.peers.set_items(Self::peer_lines(installer));
+                self.peers.selected_idx = self.peers.len().saturating_sub(1);
+                self.input.unfocus();
+                self.peers.focus();
+                self.mode = VpnMode::Peers;
+              }
+              Err(e) => self.input.error(e),
+            }
+            Signal::Wait
+          }
+          _ => self.input.handle_input(event),
+        };
+      }
+      VpnMode::Buttons | VpnMode::Peers => {}
+    }
+
+    match event.code {
+      KeyCode::Char('?') => {
+        self.help_modal.toggle();
+        return Signal::Wait;
+      }
+      code if ui_close!(code) && self.help_modal.visible => {
+        self.help_modal.hide();
+        return Signal::Wait;
+      }
+      _ if self.help_modal.visible => return Signal::Wait,
+      _ => {}
+    }
+
+    if let VpnMode::Peers = self.mode {
+      return match event.code {
+        code if ui_close!(code) => {
+          self.back_to_buttons(installer);
+          Signal::Wait
+        }
+        KeyCode::Char('a') => {
+          self.input = LineEditor::new(
+            "Peer",
+            Some(
+              "<public key> <allowed ips> [host:port], e.g. abc...= 10.0.0.1/32 vpn.example.com:51820",
+            ),
+          );
+          self.peers.unfocus();
+          self.input.focus();
+          self.mode = VpnMode::PeerEntry;
+          Signal::Wait
+        }
+        KeyCode::Char('d') => {
+          let idx = self.peers.selected_idx;
+          if idx < installer.vpn.wireguard.peers.len() {
+            installer.vpn.wireguard.peers.remove(idx);
+            self.peers.set_items(Self::peer_lines(installer));
+            self.peers.selected_idx = idx.min(self.peers.len().saturating_sub(1));
+          }
+          Signal::Wait
+        }
+        code if ui_up!(code) => {
+          self.peers.previous_item();
+          Signal::Wait
+        }
+        code if ui_down!(code) => {
+          self.peers.next_item();
+          Signal::Wait
+        }
+        _ => Signal::Wait,
+      };
+    }
+
+    match event.code {
+      code if ui_back!(code) => Signal::Pop,
+      code if ui_up!(code) => {
+        self.buttons.prev_child();
+        Signal::Wait
+      }
+      code if ui_down!(code) => {
+        self.buttons.next_child();
+        Signal::Wait
+      }
+      KeyCode::Enter => {
+        match self.buttons.selected_child() {
+          Some(0) => {
+            if let Some(checkbox) = self.buttons.focused_child_mut() {
+              checkbox.interact();
+              if let Some(Value::Bool(enabled)) = checkbox.get_value() {
+                installer.vpn.tailscale = enabled;
+              }
+            }
+          }
+          Some(1) => {
+            if let Some(checkbox) = self.buttons.focused_child_mut() {
+              checkbox.interact();
+              if let Some(Value::Bool(enabled)) = checkbox.get_value() {
+                installer.vpn.wireguard.enable = enabled;
+              }
+            }
+          }
+          Some(2) => self.open_field(WgField::Interface, installer),
+          Some(3) => self.open_field(WgField::Addresses, installer),
+          Some(4) => self.open_field(WgField::ListenPort, installer),
+          Some(5) => self.open_field(WgField::KeyFile, installer),
+          Some(6) => {
+            self.buttons.unfocus();
+            self.peers.focus();
+            self.mode = VpnMode::Peers;
+          }
+          Some(7) => return Signal::Pop,
+          _ => {}
+        }
+        Signal::Wait
+      }
+      _ => Signal::Wait,
+    }
+  }
+}
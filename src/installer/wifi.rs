@@ -0,0 +1,539 @@
+//! Joining a Wi-Fi network from the live environment, so the install can
+//! download packages. Nothing here ends up in the generated config
+
+use std::{
+  sync::{Arc, Mutex},
+  thread,
+};
+
+use ratatui::{
+  Frame,
+  crossterm::event::{KeyCode, KeyEvent},
+  layout::Rect,
+  style::{Color, Modifier},
+  text::Line,
+};
+
+use crate::{
+  command,
+  installer::{HIGHLIGHT, Installer, Page, Signal},
+  keys, split_hor, split_vert, styled_block, ui_close, ui_down, ui_up,
+  widget::{ConfigWidget, HelpModal, InfoBox, LineEditor, StrList},
+};
+
+/// How long nmcli waits for the connection to come up
+const CONNECT_WAIT_SECS: u32 = 30;
+
+#[derive(Clone)]
+pub struct WifiNetwork {
+  pub ssid: String,
+  /// 0-100
+  pub signal: u8,
+  /// e.g. "WPA2", empty for open networks
+  pub security: String,
+  pub in_use: bool,
+}
+
+impl WifiNetwork {
+  pub fn is_open(&self) -> bool {
+    self.security.is_empty() || self.security == "--"
+  }
+  fn label(&self) -> String {
+    let marker = if self.in_use { "* " } else { "  " };
+    let security = if self.is_open() {
+      "open"
+    } else {
+      &self.security
+    };
+    format!("{marker}{} ({}%, {security})", self.ssid, self.signal)
+  }
+}
+
+/// Split a line of `nmcli -t` output, where `:` separates fields and literal
+/// colons are escaped as `\:`
+fn split_terse(line: &str) -> Vec<String> {
+  let mut fields = vec![String::new()];
+  let mut chars = line.chars();
+  while let Some(c) = chars.next() {
+    match c {
+      '\\' => {
+        if let Some(next) = chars.next() {
+          fields.last_mut().unwrap().push(next);
+        }
+      }
+      ':' => fields.push(String::new()),
+      _ => fields.last_mut().unwrap().push(c),
+    }
+  }
+  fields
+}
+
+/// Nearby networks, strongest first. Hidden networks are left out, and an SSID
+/// seen from several access points is listed once
+pub fn scan_networks() -> anyhow::Result<Vec<WifiNetwork>> {
+  let output = command!(
+    "nmcli",
+    "-t",
+    "-f",
+    "IN-USE,SSID,SIGNAL,SECURITY",
+    "device",
+    "wifi",
+    "list",
+    "--rescan",
+    "yes"
+  )
+  .output()
+  .map_err(|e| anyhow::anyhow!("Failed to run nmcli: {e}"))?;
+  if !output.status.success() {
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    return Err(anyhow::anyhow!("nmcli failed: {}", stderr.trim()));
+  }
+  let mut networks: Vec<WifiNetwork> = vec![];
+  for line in String::from_utf8_lossy(&output.stdout).lines() {
+    let fields = split_terse(line);
+    let [in_use, ssid, signal, security] = fields.as_slice() else {
+      continue;
+    };
+    if ssid.is_empty() {
+      continue;
+    }
+    let network = WifiNetwork {
+      ssid: ssid.clone(),
+      signal: signal.parse().unwrap_or(0),
+      security: security.clone(),
+      in_use: in_use == "*",
+    };
+    match networks.iter_mut().find(|n| n.ssid == network.ssid) {
+      Some(seen) => {
+        seen.in_use |= network.in_use;
+        seen.signal = seen.signal.max(network.signal);
+      }
+      None => networks.push(network),
+    }
+  }
+  networks.sort_by_key(|n| std::cmp::Reverse(n.signal));
+  Ok(networks)
+}
+
+/// Connect to `ssid`, blocks until NetworkManager gives up or succeeds
+pub fn connect(ssid: &str, passphrase: Option<&str>) -> Result<(), String> {
+  let mut cmd = command!(
+    "nmcli",
+    "--wait",
+    CONNECT_WAIT_SECS,
+    "device",
+    "wifi",
+    "connect",
+    ssid
+  );
+  if let Some(passphrase) = passphrase {
+    cmd.args(["password", passphrase]);
+  }
+  let output = cmd
+    .output()
+    .map_err(|e| format!("Failed to run nmcli: {e}"))?;
+  if output.status.success() {
+    return Ok(());
+  }
+  let stderr = String::from_utf8_lossy(&output.stderr);
+  let message = stderr.trim().trim_start_matches("Error: ");
+  Err(if message.is_empty() {
+    format!("nmcli exited with {}", output.status)
+  } else {
+    message.to_string()
+  })
+}
+
+enum WifiState {
+  /// A scan will be started on the next render
+  Pending,
+  Scanning,
+  List,
+  Passphrase,
+  Connecting,
+  Connected,
+  Failed(String),
+}
+
+type Pending<T> = Arc<Mutex<Option<T>>>;
+
+/// Pick a nearby network and join it with nmcli
+///
+/// Scans and connection attempts run on a background thread, connecting can
+/// take a while when the passphrase is wrong.
+pub struct WifiSetup {
+  state: WifiState,
+  networks: Vec<WifiNetwork>,
+  list: StrList,
+  passphrase: LineEditor,
+  /// Network being connected to
+  chosen: Option<WifiNetwork>,
+  scan_result: Pending<anyhow::Result<Vec<WifiNetwork>>>,
+  connect_result: Pending<Result<(), String>>,
+  help_modal: HelpModal<'static>,
+}
+
+impl WifiSetup {
+  pub fn new() -> Self {
+    let mut list = StrList::new("Networks", vec![]);
+    list.focus();
+    let passphrase = LineEditor::new("Passphrase", None::<&str>).secret(true);
+    let help_content = styled_block(vec![
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Navigate networks"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
+        (None, " - Connect to the selected network"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "r"),
+        (None, " - Scan again"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Esc, q"),
+        (None, " - Go back"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "?"),
+        (None, " - Show this help"),
+      ],
+      vec![(None, "")],
+      vec![(
+        None,
+        "This only connects the live system, the installed system is set up under Network.",
+      )],
+    ]);
+    let help_modal = HelpModal::new("Wi-Fi", help_content);
+    Self {
+      state: WifiState::Pending,
+      networks: vec![],
+      list,
+      passphrase,
+      chosen: None,
+      scan_result: Arc::new(Mutex::new(None)),
+      connect_result: Arc::new(Mutex::new(None)),
+      help_modal,
+    }
+  }
+
+  fn start_scan(&mut self) {
+    self.state = WifiState::Scanning;
+    let result = Arc::new(Mutex::new(None));
+    self.scan_result = result.clone();
+    thread::spawn(move || {
+      let networks = scan_networks();
+      if let Ok(mut guard) = result.lock() {
+        *guard = Some(networks);
+      }
+    });
+  }
+
+  fn start_connect(&mut self, passphrase: Option<String>) {
+    let Some(network) = self.chosen.clone() else {
+      return;
+    };
+    self.state = WifiState::Connecting;
+    let result = Arc::new(Mutex::new(None));
+    self.connect_result = result.clone();
+    thread::spawn(move || {
+      let res = connect(&network.ssid, passphrase.as_deref());
+      if let Ok(mut guard) = result.lock() {
+        *guard = Some(res);
+      }
+    });
+  }
+
+  fn poll(&mut self) {
+    match self.state {
+      WifiState::Pending => self.start_scan(),
+      WifiState::Scanning => {
+        let Some(result) = self.scan_result.lock().ok().and_then(|mut g| g.take()) else {
+          return;
+        };
+        match result {
+          Ok(networks) => {
+            self
+              .list
+              .set_items(networks.iter().map(WifiNetwork::label).collect());
+            self.networks = networks;
+            self.state = WifiState::List;
+          }
+          Err(e) => self.state = WifiState::Failed(e.to_string()),
+        }
+      }
+      WifiState::Connecting => {
+        let Some(result) = self.connect_result.lock().ok().and_then(|mut g| g.take()) else {
+          return;
+        };
+        match result {
+          Ok(()) => {
+            log::info!(
+              "Connected to Wi-Fi network {}",
+              self.chosen.as_ref().map_or("", |n| n.ssid.as_str())
+            );
+            self.state = WifiState::Connected;
+          }
+          Err(e) => {
+            log::warn!("Wi-Fi connection failed: {e}");
+            self.state = WifiState::Failed(e);
+          }
+        }
+      }
+      _ => {}
+    }
+  }
+
+  fn choose_selected(&mut self) {
+    let Some(network) = self.networks.get(self.list.selected_idx).cloned() else {
+      return;
+    };
+    let open = network.is_open();
+    self.chosen = Some(network);
+    if open {
+      self.start_connect(None);
+    } else {
+      self.open_passphrase();
+    }
+  }
+
+  fn open_passphrase(&mut self) {
+    self.passphrase.clear();
+    self.list.unfocus();
+    self.passphrase.focus();
+    self.state = WifiState::Passphrase;
+  }
+
+  fn back_to_list(&mut self) {
+    self.passphrase.clear();
+    self.passphrase.unfocus();
+    self.list.focus();
+    self.state = WifiState::List;
+  }
+
+  fn status_lines(&self) -> Vec<Line<'static>> {
+    let ssid = self
+      .chosen
+      .as_ref()
+      .map(|n| n.ssid.clone())
+      .unwrap_or_default();
+    let lines = match &self.state {
+      WifiState::Pending | WifiState::Scanning => {
+        vec![vec![(None, "Scanning for networks...".to_string())]]
+      }
+      WifiState::List if self.networks.is_empty() => vec![vec![(
+        None,
+        "No networks found, press r to scan again.".to_string(),
+      )]],
+      WifiState::List => vec![vec![(
+        None,
+        "Pick a network to connect the live system to. * marks the current one.".to_string(),
+      )]],
+      WifiState::Passphrase => vec![vec![
+        (None, "Passphrase for ".to_string()),
+        (HIGHLIGHT, ssid),
+      ]],
+      WifiState::Connecting => vec![vec![
+        (None, "Connecting to ".to_string()),
+        (HIGHLIGHT, ssid),
+        (None, "...".to_string()),
+      ]],
+      WifiState::Connected => vec![
+        vec![
+          (
+            Some((Color::Green, Modifier::BOLD)),
+            "Connected to ".to_string(),
+          ),
+          (HIGHLIGHT, ssid),
+        ],
+        vec![(None, "Press Enter to go back.".to_string())],
+      ],
+      WifiState::Failed(e) => {
+        let mut lines = vec![
+          vec![(Some((Color::Red, Modifier::BOLD)), e.clone())],
+          vec![(None, String::new())],
+        ];
+        if self.chosen.is_some() {
+          lines.push(vec![(
+            None,
+            "Enter to try again, r to scan again, Esc to go back.".to_string(),
+          )]);
+        } else {
+          lines.push(vec![(None, "r to scan again, Esc to go back.".to_string())]);
+        }
+        lines
+      }
+    };
+    styled_block(lines)
+  }
+}
+
+impl Default for WifiSetup {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl Page for WifiSetup {
+  fn render(&mut self, _installer: &mut Installer, f: &mut Frame, area: Rect) {
+    self.poll();
+    let chunks = split_vert!(
+      area,
+      1,
+      [
+        Constraint::Length(6),
+        Constraint::Min(0),
+        Constraint::Length(3),
+      ]
+    );
+    let hor_chunks = split_hor!(
+      chunks[1],
+      1,
+      [
+        Constraint::Percentage(20),
+        Constraint::Percentage(60),
+        Constraint::Percentage(20),
+      ]
+    );
+    InfoBox::new("Wi-Fi", self.status_lines()).render(f, chunks[0]);
+    self.list.render(f, hor_chunks[1]);
+    if let WifiState::Passphrase = self.state {
+      let input_chunks = split_hor!(
+        chunks[2],
+        0,
+        [
+          Constraint::Percentage(20),
+          Constraint::Percentage(60),
+          Constraint::Percentage(20),
+        ]
+      );
+      self.passphrase.render(f, input_chunks[1]);
+    }
+    self.help_modal.render(f, area);
+  }
+
+  fn get_help_content(&self) -> (String, Vec<Line<'_>>) {
+    let help_content = styled_block(vec![
+      vec![
+        (
+          Some((Color::Yellow, Modifier::BOLD)),
+          keys::bindings().nav_label,
+        ),
+        (None, " - Navigate networks"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Enter"),
+        (None, " - Connect to the selected network"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "r"),
+        (None, " - Scan again"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "Esc, q"),
+        (None, " - Go back"),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), "?"),
+        (None, " - Show this help"),
+      ],
+      vec![(None, "")],
+      vec![(
+        None,
+        "This only connects the live system, the installed system is set up under Network.",
+      )],
+    ]);
+    ("Wi-Fi".to_string(), help_content)
+  }
+
+  fn handle_paste(&mut self, _installer: &mut Installer, text: &str) -> Signal {
+    if let WifiState::Passphrase = self.state {
+      self.passphrase.paste(text);
+    }
+    Signal::Wait
+  }
+
+  fn handle_input(&mut self, _installer: &mut Installer, event: KeyEvent) -> Signal {
+    if let WifiState::Passphrase = self.state {
+      return match event.code {
+        KeyCode::Esc => {
+          self.back_to_list();
+          Signal::Wait
+        }
+        KeyCode::Enter => {
+          let passphrase = self
+            .passphrase
+            .get_value()
+            .and_then(|v| v.as_str().map(|s| s.to_string()))
+            .unwrap_or_default();
+          // WPA passphrases are 8 to 63 characters
+          if !(8..=63).contains(&passphrase.len()) {
+            self
+              .passphrase
+              .error("Passphrases are 8 to 63 characters long");
+            return Signal::Wait;
+          }
+          self.passphrase.unfocus();
+          self.start_connect(Some(passphrase));
+          Signal::Wait
+        }
+        _ => self.passphrase.handle_input(event),
+      };
+    }
+
+    match event.code {
+      KeyCode::Char('?') => {
+        self.help_modal.toggle();
+        Signal::Wait
+      }
+      code if ui_close!(code) && self.help_modal.visible => {
+        self.help_modal.hide();
+        Signal::Wait
+      }
+      _ if self.help_modal.visible => Signal::Wait,
+      _ if matches!(self.state, WifiState::Scanning | WifiState::Connecting) => Signal::Wait,
+      code if ui_close!(code) => match self.state {
+        WifiState::Failed(_) if !self.networks.is_empty() => {
+          self.back_to_list();
+          Signal::Wait
+        }
+        _ => Signal::Pop,
+      },
+      KeyCode::Char('r') => {
+        self.chosen = None;
+        self.back_to_list();
+        self.start_scan();
+        Signal::Wait
+      }
+      KeyCode::Enter => match self.state {
+        WifiState::Connected => Signal::Pop,
+        WifiState::Failed(_) => {
+          match self.chosen.as_ref().map(WifiNetwork::is_open) {
+            Some(true) => self.start_connect(None),
+            Some(false) => self.open_passphrase(),
+            None => {}
+          }
+          Signal::Wait
+        }
+        WifiState::List => {
+          self.choose_selected();
+          Signal::Wait
+        }
+        _ => Signal::Wait,
+      },
+      code if ui_up!(code) => {
+        self.list.previous_item();
+        Signal::Wait
+      }
+      code if ui_down!(code) => {
+        self.list.next_item();
+        Signal::Wait
+      }
+      _ => Signal::Wait,
+    }
+  }
+}
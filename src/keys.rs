@@ -0,0 +1,178 @@
+use std::{fmt::Display, str::FromStr, sync::OnceLock};
+
+use ratatui::{
+  crossterm::event::{KeyCode, KeyEvent, KeyModifiers},
+  style::{Color, Modifier},
+  text::Line,
+};
+
+use crate::styled_block;
+
+/// Which keys the `ui_*!` navigation macros respond to
+///
+/// Chosen once at startup with `--keys`. Arrow keys, Enter and Esc work in
+/// every scheme, the schemes only differ in the extra keys layered on top.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum KeyScheme {
+  /// Arrows plus h/j/k/l, the original behavior
+  #[default]
+  Vim,
+  /// Arrows only, so letters never move the cursor
+  Arrows,
+  /// Arrows plus Ctrl+P/N/B/F, and Ctrl+G to back out
+  Emacs,
+}
+
+impl KeyScheme {
+  pub fn bindings(self) -> &'static KeyBindings {
+    match self {
+      KeyScheme::Vim => &VIM,
+      KeyScheme::Arrows => &ARROWS,
+      KeyScheme::Emacs => &EMACS,
+    }
+  }
+}
+
+impl FromStr for KeyScheme {
+  type Err = anyhow::Error;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s {
+      "vim" => Ok(KeyScheme::Vim),
+      "arrows" => Ok(KeyScheme::Arrows),
+      "emacs" => Ok(KeyScheme::Emacs),
+      _ => Err(anyhow::anyhow!(
+        "Unknown key scheme '{s}', expected vim, arrows or emacs"
+      )),
+    }
+  }
+}
+
+impl Display for KeyScheme {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      KeyScheme::Vim => write!(f, "vim"),
+      KeyScheme::Arrows => write!(f, "arrows"),
+      KeyScheme::Emacs => write!(f, "emacs"),
+    }
+  }
+}
+
+/// The keys behind each navigation macro
+pub struct KeyBindings {
+  pub scheme: KeyScheme,
+  pub up: &'static [KeyCode],
+  pub down: &'static [KeyCode],
+  pub left: &'static [KeyCode],
+  pub right: &'static [KeyCode],
+  pub back: &'static [KeyCode],
+  pub enter: &'static [KeyCode],
+  pub close: &'static [KeyCode],
+  /// Ctrl chords turned into plain keys before any page sees them, so text
+  /// fields get them too
+  pub chords: &'static [(char, KeyCode)],
+  /// Shown in help text for moving up and down
+  pub nav_label: &'static str,
+  /// Shown in help text for going back
+  pub back_label: &'static str,
+}
+
+static VIM: KeyBindings = KeyBindings {
+  scheme: KeyScheme::Vim,
+  up: &[KeyCode::Up, KeyCode::Char('k')],
+  down: &[KeyCode::Down, KeyCode::Char('j')],
+  left: &[KeyCode::Left, KeyCode::Char('h')],
+  right: &[KeyCode::Right, KeyCode::Char('l')],
+  back: &[
+    KeyCode::Esc,
+    KeyCode::Char('q'),
+    KeyCode::Left,
+    KeyCode::Char('h'),
+  ],
+  enter: &[KeyCode::Enter, KeyCode::Right, KeyCode::Char('l')],
+  close: &[KeyCode::Esc, KeyCode::Char('q')],
+  chords: &[],
+  nav_label: "↑/↓, j/k",
+  back_label: "Esc, q, ←, h",
+};
+
+static ARROWS: KeyBindings = KeyBindings {
+  scheme: KeyScheme::Arrows,
+  up: &[KeyCode::Up],
+  down: &[KeyCode::Down],
+  left: &[KeyCode::Left],
+  right: &[KeyCode::Right],
+  back: &[KeyCode::Esc, KeyCode::Char('q'), KeyCode::Left],
+  enter: &[KeyCode::Enter, KeyCode::Right],
+  close: &[KeyCode::Esc, KeyCode::Char('q')],
+  chords: &[],
+  nav_label: "↑/↓",
+  back_label: "Esc, q, ←",
+};
+
+static EMACS: KeyBindings = KeyBindings {
+  scheme: KeyScheme::Emacs,
+  chords: &[
+    ('p', KeyCode::Up),
+    ('n', KeyCode::Down),
+    ('b', KeyCode::Left),
+    ('f', KeyCode::Right),
+    ('g', KeyCode::Esc),
+  ],
+  nav_label: "↑/↓, C-p/C-n",
+  back_label: "Esc, q, ←, C-g",
+  ..ARROWS
+};
+
+static SCHEME: OnceLock<KeyScheme> = OnceLock::new();
+
+/// Pick the scheme for this run. Only the first call has any effect.
+pub fn set_scheme(scheme: KeyScheme) {
+  let _ = SCHEME.set(scheme);
+}
+
+pub fn bindings() -> &'static KeyBindings {
+  SCHEME.get().copied().unwrap_or_default().bindings()
+}
+
+impl KeyBindings {
+  /// Rewrite Ctrl chords from `chords` into the keys they stand for
+  pub fn translate(&self, event: KeyEvent) -> KeyEvent {
+    if !event.modifiers.contains(KeyModifiers::CONTROL) {
+      return event;
+    }
+    let KeyCode::Char(c) = event.code else {
+      return event;
+    };
+    match self.chords.iter().find(|(chord, _)| *chord == c) {
+      Some((_, code)) => KeyEvent::new(*code, KeyModifiers::NONE),
+      None => event,
+    }
+  }
+
+  /// A short reference of the active scheme for help screens
+  pub fn reference(&self) -> Vec<Line<'static>> {
+    let (move_keys, back_keys) = match self.scheme {
+      KeyScheme::Vim => ("arrows or h/j/k/l", "Esc, q, ←, h"),
+      KeyScheme::Arrows => ("arrows", "Esc, q, ←"),
+      KeyScheme::Emacs => ("arrows or Ctrl+P/N/B/F", "Esc, q, ←, Ctrl+G"),
+    };
+    let scheme = format!("Key scheme: {}", self.scheme);
+    styled_block(vec![
+      vec![(None, String::new())],
+      vec![(Some((Color::Cyan, Modifier::BOLD)), scheme)],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), move_keys.to_string()),
+        (None, " - Move".to_string()),
+      ],
+      vec![
+        (Some((Color::Yellow, Modifier::BOLD)), back_keys.to_string()),
+        (None, " - Go back".to_string()),
+      ],
+      vec![(
+        None,
+        "Start with --keys vim|arrows|emacs to change it.".to_string(),
+      )],
+    ])
+  }
+}
@@ -116,60 +116,62 @@ macro_rules! list {
 }
 
 // UI Navigation Macros
-// These macros provide consistent keyboard shortcuts across the TUI
+// These macros provide consistent keyboard shortcuts across the TUI. Each one
+// checks a KeyCode against the active key scheme (see `keys.rs`), so they are
+// used as match guards: `code if ui_down!(code) => ...`
 
 #[macro_export]
 /// Keys for closing/quitting: Escape or 'q'
 macro_rules! ui_close {
-  () => {
-    KeyCode::Esc | KeyCode::Char('q')
+  ($code:expr) => {
+    $crate::keys::bindings().close.contains(&$code)
   };
 }
 
 #[macro_export]
 /// Keys for going back: Escape, 'q', Left arrow, or 'h' (vi-style)
 macro_rules! ui_back {
-  () => {
-    KeyCode::Esc | KeyCode::Char('q') | KeyCode::Left | KeyCode::Char('h')
+  ($code:expr) => {
+    $crate::keys::bindings().back.contains(&$code)
   };
 }
 
 #[macro_export]
 /// Keys for entering/selecting: Enter, Right arrow, or 'l' (vi-style)
 macro_rules! ui_enter {
-  () => {
-    KeyCode::Enter | KeyCode::Right | KeyCode::Char('l')
+  ($code:expr) => {
+    $crate::keys::bindings().enter.contains(&$code)
   };
 }
 
 #[macro_export]
 /// Keys for moving down: Down arrow or 'j' (vi-style)
 macro_rules! ui_down {
-  () => {
-    KeyCode::Down | KeyCode::Char('j')
+  ($code:expr) => {
+    $crate::keys::bindings().down.contains(&$code)
   };
 }
 
 #[macro_export]
 /// Keys for moving up: Up arrow or 'k' (vi-style)
 macro_rules! ui_up {
-  () => {
-    KeyCode::Up | KeyCode::Char('k')
+  ($code:expr) => {
+    $crate::keys::bindings().up.contains(&$code)
   };
 }
 
 #[macro_export]
 /// Keys for moving left: Left arrow or 'h' (vi-style)
 macro_rules! ui_left {
-  () => {
-    KeyCode::Left | KeyCode::Char('h')
+  ($code:expr) => {
+    $crate::keys::bindings().left.contains(&$code)
   };
 }
 #[macro_export]
 /// Keys for moving right: Right arrow or 'l' (vi-style)
 macro_rules! ui_right {
-  () => {
-    KeyCode::Right | KeyCode::Char('l')
+  ($code:expr) => {
+    $crate::keys::bindings().right.contains(&$code)
   };
 }
 
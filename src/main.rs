@@ -1,7 +1,10 @@
+// serde_json::json! recurses once per key, Installer::to_json has a lot of them
+#![recursion_limit = "256"]
+
 use std::{env, io};
 
 use log::debug;
-use ratatui::crossterm::event::{self, Event};
+use ratatui::crossterm::event::{self, DisableBracketedPaste, EnableBracketedPaste, Event};
 use ratatui::{
   Terminal,
   crossterm::{
@@ -17,19 +20,37 @@ use ratatui::{
   text::Line,
   widgets::Paragraph,
 };
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
-use tempfile::NamedTempFile;
 
-use crate::installer::{InstallProgress, Installer, Menu, Page, Signal, systempkgs::init_nixpkgs};
+use crate::drives::lsblk;
+use crate::installer::{
+  InstallProgress, Installer, Menu, Page, Signal, StagedConfigs,
+  preflight::MissingTools,
+  resume::{ResumeReport, reconcile},
+  saveconfig::{load_installer, save_installer, write_private},
+  systempkgs::init_nixpkgs,
+};
+use crate::keys::KeyScheme;
 
 pub mod drives;
 pub mod installer;
+pub mod keys;
 #[macro_use]
 pub mod macros;
 pub mod nixgen;
+pub mod tools;
 pub mod widget;
 
-type LineStyle = Option<(Color, Modifier)>;
+/// Where the panic hook dumps the installer state, so it can be `--load`ed
+const RECOVERY_PATH: &str = "/tmp/nixos-wizard-recovery.json";
+
+/// Latest serialized installer state, kept up to date by the event loop so the
+/// panic hook has something to save without touching the (possibly broken)
+/// installer itself
+static RECOVERY_SNAPSHOT: Mutex<Option<String>> = Mutex::new(None);
+
+pub type LineStyle = Option<(Color, Modifier)>;
 pub fn styled_block<'a>(lines: Vec<Vec<(LineStyle, impl ToString)>>) -> Vec<Line<'a>> {
   lines
     .into_iter()
@@ -67,6 +88,9 @@ impl RawModeGuard {
 
     // Enter alternate screen buffer to preserve user's terminal content
     execute!(stdout, EnterAlternateScreen)?;
+
+    // Pasted text arrives as one event instead of a stream of key presses
+    execute!(stdout, EnableBracketedPaste)?;
     Ok(Self)
   }
 }
@@ -77,7 +101,7 @@ impl Drop for RawModeGuard {
   fn drop(&mut self) {
     // Ignore errors during cleanup - we're likely panicking or shutting down
     let _ = disable_raw_mode();
-    let _ = execute!(io::stdout(), LeaveAlternateScreen);
+    let _ = execute!(io::stdout(), DisableBracketedPaste, LeaveAlternateScreen);
   }
 }
 
@@ -88,9 +112,23 @@ fn main() -> anyhow::Result<()> {
     return Ok(());
   }
 
+  // Only reads help text, so this doesn't need root
+  if let Some(path) = dump_help_path()? {
+    if let Some(scheme) = key_scheme()? {
+      keys::set_scheme(scheme);
+    }
+    init_nixpkgs();
+    std::fs::write(&path, installer::help_reference())
+      .map_err(|e| anyhow::anyhow!("Failed to write help to '{path}': {e}"))?;
+    println!("Wrote help for every page to {path}");
+    return Ok(());
+  }
+
+  // A dry run doesn't touch the disks, so it can be tried without root
+  let dry_run = dry_run_dir()?;
   let uid = nix::unistd::getuid();
   log::debug!("UID: {uid}");
-  if uid.as_raw() != 0 {
+  if uid.as_raw() != 0 && dry_run.is_none() {
     return Err(anyhow::anyhow!(
       "nixos-wizard: This installer must be run as root."
     ));
@@ -106,38 +144,218 @@ fn main() -> anyhow::Result<()> {
 
     // Attempt to restore terminal state - ignore errors since we're panicking
     let _ = disable_raw_mode();
-    let _ = execute!(io::stdout(), LeaveAlternateScreen);
+    let _ = execute!(io::stdout(), DisableBracketedPaste, LeaveAlternateScreen);
 
     // Print user-friendly panic information to stderr
     eprintln!("==================================================");
     eprintln!("NIXOS INSTALLER PANIC - Terminal state restored!");
     eprintln!("==================================================");
     eprintln!("Panic occurred: {panic_info}");
+    // The lock may be poisoned if we panicked while holding it, the data is
+    // still fine to read in that case
+    let snapshot = match RECOVERY_SNAPSHOT.lock() {
+      Ok(guard) => guard.clone(),
+      Err(poisoned) => poisoned.into_inner().clone(),
+    };
+    if let Some(json) = snapshot {
+      match write_recovery_file(&json) {
+        Ok(()) => {
+          eprintln!("Your configuration was saved to {RECOVERY_PATH}");
+          eprintln!("Resume with: nixos-wizard --load {RECOVERY_PATH}");
+        }
+        Err(e) => eprintln!("Failed to save configuration to {RECOVERY_PATH}: {e}"),
+      }
+    }
     eprintln!("==================================================");
   }));
 
   env_logger::init();
   debug!("Logger initialized");
+  if let Some(scheme) = key_scheme()? {
+    debug!("Using {scheme} key scheme");
+    keys::set_scheme(scheme);
+  }
   init_nixpkgs();
 
+  let save_path = save_path()?;
+  let mut installer = match load_path() {
+    Some(path) => {
+      debug!("Loading saved config from {path}");
+      let mut installer = load_installer(&path)?;
+      installer.save_path = Some(path);
+      installer
+    }
+    None => Installer::new(),
+  };
+  if save_path.is_some() {
+    installer.save_path = save_path.clone();
+  }
+  installer.dry_run = dry_run;
   let mut stdout = io::stdout();
   let res = {
     let _raw_guard = RawModeGuard::new(&mut stdout)?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
     debug!("Running TUI");
-    run_app(&mut terminal)
+    run_app(&mut terminal, &mut installer)
   };
 
   debug!("Exiting TUI");
 
+  // Leaving by any route, including backing out of a failed step, must not
+  // leave the target mounted
+  if installer.partitioning_started {
+    unmount_target(&installer);
+  }
+
+  if let Some(path) = save_path {
+    save_installer(&installer, &path)?;
+    println!("Saved configuration to {path}, resume with: nixos-wizard --load {path}");
+  }
+
   res
 }
 
+/// The snapshot contains password hashes, so keep it readable by root only
+fn write_recovery_file(json: &str) -> io::Result<()> {
+  write_private(RECOVERY_PATH, json)
+}
+
+fn save_recovery_snapshot(installer: &Installer) {
+  let Ok(json) = serde_json::to_string(installer) else {
+    return;
+  };
+  if let Ok(mut guard) = RECOVERY_SNAPSHOT.lock() {
+    *guard = Some(json);
+  }
+}
+
+/// Path given to `--load`, if any
+fn load_path() -> Option<String> {
+  let mut args = env::args().skip_while(|arg| arg != "--load");
+  args.next()?;
+  args.next()
+}
+
+/// File given to `--save`, written with the installer state when the TUI exits
+fn save_path() -> anyhow::Result<Option<String>> {
+  let mut args = env::args().skip_while(|arg| arg != "--save");
+  if args.next().is_none() {
+    return Ok(None);
+  }
+  match args.next() {
+    Some(path) => Ok(Some(path)),
+    None => Err(anyhow::anyhow!("--save needs a file to write to")),
+  }
+}
+
+/// Directory given to `--dry-run`, created if needed
+fn dry_run_dir() -> anyhow::Result<Option<String>> {
+  let mut args = env::args().skip_while(|arg| arg != "--dry-run");
+  if args.next().is_none() {
+    return Ok(None);
+  }
+  let Some(dir) = args.next() else {
+    return Err(anyhow::anyhow!(
+      "--dry-run needs a directory to write the generated configs to"
+    ));
+  };
+  std::fs::create_dir_all(&dir).map_err(|e| anyhow::anyhow!("Failed to create '{dir}': {e}"))?;
+  let dir = std::fs::canonicalize(&dir)?;
+  let dir = dir
+    .to_str()
+    .ok_or_else(|| anyhow::anyhow!("--dry-run directory must be valid UTF-8"))?;
+  if dir.contains(|c: char| c.is_whitespace() || "'\"$`\\".contains(c)) {
+    return Err(anyhow::anyhow!(
+      "--dry-run directory can't contain spaces or quotes"
+    ));
+  }
+  Ok(Some(dir.to_string()))
+}
+
+/// Scheme given to `--keys`, if any
+fn key_scheme() -> anyhow::Result<Option<KeyScheme>> {
+  let mut args = env::args().skip_while(|arg| arg != "--keys");
+  if args.next().is_none() {
+    return Ok(None);
+  }
+  match args.next() {
+    Some(scheme) => Ok(Some(scheme.parse()?)),
+    None => Err(anyhow::anyhow!(
+      "--keys needs a scheme: vim, arrows or emacs"
+    )),
+  }
+}
+
+/// File given to `--dump-help`, if any
+fn dump_help_path() -> anyhow::Result<Option<String>> {
+  let mut args = env::args().skip_while(|arg| arg != "--dump-help");
+  if args.next().is_none() {
+    return Ok(None);
+  }
+  match args.next() {
+    Some(path) => Ok(Some(path)),
+    None => Err(anyhow::anyhow!("--dump-help needs a file to write to")),
+  }
+}
+
+/// Best-effort cleanup once installation has started
+///
+/// Once disko has run, the target filesystems are mounted under /mnt and any
+/// LUKS volumes are open. Leaving them that way makes the next attempt fail,
+/// so we try to recursively unmount /mnt, close the volumes and report what
+/// happened. The terminal has already been restored at this point, so
+/// printing to stderr is safe.
+fn unmount_target(installer: &Installer) {
+  eprintln!("nixos-wizard: unmounting /mnt");
+  match command!("umount", "-R", "/mnt").output() {
+    Ok(output) if output.status.success() => {
+      log::info!("Unmounted /mnt on exit");
+      eprintln!("nixos-wizard: /mnt unmounted");
+    }
+    Ok(output) => {
+      let stderr = String::from_utf8_lossy(&output.stderr);
+      log::warn!("umount -R /mnt failed: {}", stderr.trim());
+      eprintln!("nixos-wizard: failed to unmount /mnt: {}", stderr.trim());
+    }
+    Err(e) => {
+      log::warn!("Failed to run umount: {e}");
+      eprintln!("nixos-wizard: failed to run umount: {e}");
+    }
+  }
+  let mappings = installer
+    .all_disks()
+    .flat_map(|d| d.partitions())
+    .filter_map(|p| p.luks().map(|luks| luks.name.clone()));
+  for name in mappings {
+    if !std::path::Path::new("/dev/mapper").join(&name).exists() {
+      continue;
+    }
+    match command!("cryptsetup", "close", name).output() {
+      Ok(output) if output.status.success() => {
+        log::info!("Closed LUKS volume {name} on exit");
+      }
+      Ok(output) => {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        log::warn!("cryptsetup close {name} failed: {}", stderr.trim());
+        eprintln!(
+          "nixos-wizard: failed to close LUKS volume {name}: {}",
+          stderr.trim()
+        );
+      }
+      Err(e) => {
+        log::warn!("Failed to run cryptsetup: {e}");
+        eprintln!("nixos-wizard: failed to run cryptsetup: {e}");
+      }
+    }
+  }
+}
+
 /// Processes signals from UI pages to control navigation and installer actions
 /// Returns Ok(true) if the application should quit, Ok(false) to continue
 fn handle_signal(
   signal: Signal,
+  terminal: &mut Terminal<CrosstermBackend<std::io::Stdout>>,
   page_stack: &mut Vec<Box<dyn Page>>,
   installer: &mut Installer,
 ) -> anyhow::Result<bool> {
@@ -151,6 +369,10 @@ fn handle_signal(
     Signal::Pop => {
       page_stack.pop();
     }
+    Signal::Replace(new_page) => {
+      page_stack.pop();
+      page_stack.push(new_page);
+    }
     Signal::PopCount(n) => {
       // Pop n pages from the stack, but never remove the root page
       for _ in 0..n {
@@ -170,7 +392,6 @@ fn handle_signal(
       return Ok(true); // Signal to quit
     }
     Signal::WriteCfg => {
-      use std::io::Write;
       debug!("WriteCfg signal received - starting installation process");
 
       // Convert installer state to JSON for the Nix configuration generator
@@ -189,19 +410,15 @@ fn handle_signal(
           debug!("disko config: {}", cfg.disko);
           debug!("flake_path: {:?}", cfg.flake_path);
 
-          // Create temporary files to hold the generated configurations
-          let mut system_cfg = NamedTempFile::new()?;
-          let mut disko_cfg = NamedTempFile::new()?;
-
-          write!(system_cfg, "{}", cfg.system)?;
-          write!(disko_cfg, "{}", cfg.disko)?;
+          // Use the files from the config preview if there are any, so the
+          // install runs exactly what was shown there
+          let configs = match installer.staged_configs.take() {
+            Some(configs) => configs,
+            None => Arc::new(StagedConfigs::new(installer, &cfg)?),
+          };
 
           // Navigate to the installation progress page
-          page_stack.push(Box::new(InstallProgress::new(
-            installer.clone(),
-            system_cfg,
-            disko_cfg,
-          )?));
+          page_stack.push(Box::new(InstallProgress::new(installer.clone(), configs)?));
         }
         Err(e) => {
           debug!("Failed to write configuration files: {e}");
@@ -209,6 +426,20 @@ fn handle_signal(
         }
       }
     }
+    Signal::Suspend(mut cmd) => {
+      debug!("Suspending TUI to run {cmd:?}");
+      // Hand the real terminal over to the command, then take it back
+      disable_raw_mode()?;
+      execute!(io::stdout(), DisableBracketedPaste, LeaveAlternateScreen)?;
+      let status = cmd.status();
+      enable_raw_mode()?;
+      execute!(io::stdout(), EnterAlternateScreen, EnableBracketedPaste)?;
+      // The screen contents are gone, so force a full redraw
+      terminal.clear()?;
+      if let Err(e) = status {
+        debug!("Failed to run suspended command: {e}");
+      }
+    }
     Signal::Error(err) => {
       return Err(anyhow::anyhow!("{}", err));
     }
@@ -222,16 +453,44 @@ fn handle_signal(
 /// - Pages are pushed/popped based on user navigation
 /// - Each page can send signals to control the overall application flow
 /// - The event loop handles both user input and periodic updates (ticks)
-pub fn run_app(terminal: &mut Terminal<CrosstermBackend<std::io::Stdout>>) -> anyhow::Result<()> {
-  let mut installer = Installer::new();
+pub fn run_app(
+  terminal: &mut Terminal<CrosstermBackend<std::io::Stdout>>,
+  installer: &mut Installer,
+) -> anyhow::Result<()> {
   let mut page_stack: Vec<Box<dyn Page>> = vec![];
   page_stack.push(Box::new(Menu::new()));
 
+  // A loaded config may describe disks that have since changed, so check
+  // before the user gets anywhere near the install button
+  if installer.drive_config.is_some() && tools::on_path("lsblk") {
+    let current_disks = lsblk()?;
+    let mismatches = reconcile(installer, &current_disks);
+    if mismatches.is_empty() {
+      installer.drives = current_disks;
+    } else {
+      page_stack.push(Box::new(ResumeReport::new(mismatches, current_disks)));
+    }
+  }
+
+  let missing_tools = tools::missing();
+  if !missing_tools.is_empty() {
+    log::warn!(
+      "Missing programs: {}",
+      missing_tools
+        .iter()
+        .map(|tool| tool.name)
+        .collect::<Vec<_>>()
+        .join(", ")
+    );
+    page_stack.push(Box::new(MissingTools::new(missing_tools, false)));
+  }
+
   // Set up timing for periodic updates (10 FPS)
   let tick_rate = Duration::from_millis(100);
   let mut last_tick = Instant::now();
 
   loop {
+    let has_help = page_stack.last().is_none_or(|page| page.has_help());
     // Render the current UI state
     terminal.draw(|f| {
       let chunks = split_vert!(
@@ -254,11 +513,13 @@ pub fn run_app(terminal: &mut Terminal<CrosstermBackend<std::io::Stdout>>) -> an
         ]
       );
 
-      // Help text on left
-      let help_text = Paragraph::new("Press '?' for help")
-        .style(Style::default().fg(Color::Gray))
-        .alignment(Alignment::Center);
-      f.render_widget(help_text, header_chunks[0]);
+      // Help text on left, only where '?' actually does something
+      if has_help {
+        let help_text = Paragraph::new("Press '?' for help")
+          .style(Style::default().fg(Color::Gray))
+          .alignment(Alignment::Center);
+        f.render_widget(help_text, header_chunks[0]);
+      }
 
       // Title in center
       let title = Paragraph::new("Install NixOS")
@@ -268,7 +529,7 @@ pub fn run_app(terminal: &mut Terminal<CrosstermBackend<std::io::Stdout>>) -> an
 
       // Render the current page (top of the navigation stack)
       if let Some(page) = page_stack.last_mut() {
-        page.render(&mut installer, f, chunks[1]);
+        page.render(installer, f, chunks[1]);
       }
     })?;
 
@@ -276,7 +537,7 @@ pub fn run_app(terminal: &mut Terminal<CrosstermBackend<std::io::Stdout>>) -> an
     // Signals control navigation, installation, and application lifecycle
     if let Some(page) = page_stack.last()
       && let Some(signal) = page.signal()
-      && handle_signal(signal, &mut page_stack, &mut installer)?
+      && handle_signal(signal, terminal, &mut page_stack, installer)?
     {
       // handle_signal returned true, meaning we should quit
       break;
@@ -289,12 +550,18 @@ pub fn run_app(terminal: &mut Terminal<CrosstermBackend<std::io::Stdout>>) -> an
 
     // Wait for user input or timeout
     if event::poll(timeout)? {
-      if let Event::Key(key) = event::read()? {
+      let event = event::read()?;
+      if matches!(event, Event::Key(_) | Event::Paste(_)) {
         if let Some(page) = page_stack.last_mut() {
-          // Forward keyboard input to the current page
-          let signal = page.handle_input(&mut installer, key);
-
-          if handle_signal(signal, &mut page_stack, &mut installer)? {
+          // Forward keyboard input and pasted text to the current page
+          let signal = match event {
+            Event::Paste(text) => page.handle_paste(installer, &text),
+            Event::Key(key) => page.handle_input(installer, keys::bindings().translate(key)),
+            _ => Signal::Wait,
+          };
+          save_recovery_snapshot(installer);
+
+          if handle_signal(signal, terminal, &mut page_stack, installer)? {
             // Page requested application quit
             break;
           }
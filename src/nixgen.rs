@@ -1,7 +1,16 @@
 use serde_json::{Map, Value};
-use std::process::{Command, Stdio};
+use std::{
+  collections::BTreeSet,
+  process::{Command, Stdio},
+};
 
-use crate::{attrset, installer::users::User, merge_attrs};
+use crate::{
+  attrset,
+  installer::{
+    SwapKind, channel, console::is_terminus_font, extramodules::is_flake_ref, users::User,
+  },
+  merge_attrs,
+};
 
 /// Convert a value to a properly quoted Nix string literal
 ///
@@ -11,10 +20,23 @@ pub fn nixstr(val: impl ToString) -> String {
   let val = val.to_string();
   format!("\"{val}\"")
 }
+/// Like `nixstr`, but for arbitrary text that may contain quotes, newlines
+/// or `${`
+pub fn nixstr_escaped(val: &str) -> String {
+  let escaped = val
+    .replace('\\', "\\\\")
+    .replace('"', "\\\"")
+    .replace("${", "\\${")
+    .replace('\n', "\\n")
+    .replace('\t', "\\t")
+    .replace('\r', "\\r");
+  format!("\"{escaped}\"")
+}
 /// Format Nix code using the nixfmt tool for proper indentation and style
 ///
 /// Assumes nixfmt is available in the environment (provided by the Nix flake)
 pub fn fmt_nix(nix: String) -> anyhow::Result<String> {
+  crate::tools::require("nixfmt")?;
   // Spawn nixfmt process with piped input/output for formatting
   let mut nixfmt_child = Command::new("nixfmt")
     .stdin(Stdio::piped())
@@ -64,6 +86,62 @@ pub fn highlight_nix(nix: &str) -> anyhow::Result<String> {
     Err(anyhow::anyhow!("bat failed: {}", err))
   }
 }
+/// Where and why `nix-instantiate --parse` rejected some generated Nix
+#[derive(Clone, Debug)]
+pub struct NixParseError {
+  /// 1-based line of the error, when the parser gave one
+  pub line: Option<usize>,
+  pub message: String,
+}
+/// Check `nix` parses, catching `NixWriter` bugs before anything is installed
+///
+/// Returns `None` when it parses or when nix-instantiate isn't available, the
+/// check is only a safety net.
+pub fn check_nix_syntax(nix: &str) -> Option<NixParseError> {
+  if !crate::tools::on_path("nix-instantiate") {
+    return None;
+  }
+  let mut child = Command::new("nix-instantiate")
+    .args(["--parse", "-"])
+    .stdin(Stdio::piped())
+    .stdout(Stdio::null())
+    .stderr(Stdio::piped())
+    .spawn()
+    .ok()?;
+  if let Some(mut stdin) = child.stdin.take() {
+    use std::io::Write;
+    stdin.write_all(nix.as_bytes()).ok()?;
+  }
+  let output = child.wait_with_output().ok()?;
+  if output.status.success() {
+    return None;
+  }
+  let stderr = String::from_utf8_lossy(&output.stderr);
+  log::error!("Generated config failed to parse:\n{stderr}");
+  let message = stderr
+    .lines()
+    .find(|line| line.trim_start().starts_with("error:"))
+    .unwrap_or("the generated config doesn't parse")
+    .trim()
+    .to_string();
+  Some(NixParseError {
+    line: stderr.lines().find_map(error_line),
+    message,
+  })
+}
+/// The line number out of a `... «stdin»:12:5:` style position
+fn error_line(line: &str) -> Option<usize> {
+  let parts = line
+    .trim_end()
+    .trim_end_matches(':')
+    .split(':')
+    .collect::<Vec<_>>();
+  parts.windows(2).find_map(|pair| {
+    let row = pair[0].trim().parse::<usize>().ok()?;
+    pair[1].trim().parse::<usize>().ok()?;
+    Some(row)
+  })
+}
 // Example JSON configuration structure that this module processes:
 // {
 //   "config": {
@@ -79,7 +157,8 @@ pub fn highlight_nix(nix: &str) -> anyhow::Result<String> {
 //     "users": [...],
 //     "system_pkgs": [...]
 //   },
-//   "disko": { ... }
+//   "disko": { ... },
+//   "extra_disks": { "sdb": { ... } }
 // }
 /// Container for generated NixOS configuration files
 #[derive(Debug)]
@@ -87,6 +166,7 @@ pub struct Configs {
   pub system: String,             // NixOS system configuration
   pub disko: String,              // Disk partitioning configuration
   pub flake_path: Option<String>, // Optional flake path for advanced users
+  pub flake: Option<String>,      // Generated flake.nix, when flakes are enabled
 }
 
 /// Converts JSON configuration to NixOS configuration files
@@ -105,10 +185,7 @@ impl NixWriter {
   /// Generate both system and disko configurations from the JSON config
   pub fn write_configs(&self) -> anyhow::Result<Configs> {
     // Generate disko (disk partitioning) configuration
-    let disko = {
-      let config = self.config["disko"].clone();
-      self.write_disko_config(config)?
-    };
+    let disko = self.write_disko_config()?;
 
     // Generate NixOS system configuration
     let sys_cfg = {
@@ -122,12 +199,57 @@ impl NixWriter {
       .get("flake_path")
       .and_then(|v| v.as_str().map(|s| s.to_string()));
 
+    let flake = if flake_path.is_none() {
+      self.write_flake()?
+    } else {
+      None
+    };
+
     Ok(Configs {
       system: sys_cfg,
       disko,
       flake_path,
+      flake,
     })
   }
+  /// Generate a flake.nix wrapping configuration.nix and the disko module
+  ///
+  /// Only for installs with flakes enabled that don't come from a flake of
+  /// their own. The output is named after the hostname, so
+  /// `nixos-rebuild --flake /etc/nixos` picks it up on the new system.
+  pub fn write_flake(&self) -> anyhow::Result<Option<String>> {
+    let cfg = &self.config["config"];
+    if !cfg["enable_flakes"].as_bool().unwrap_or(false) {
+      return Ok(None);
+    }
+    let hostname = cfg["hostname"].as_str().unwrap_or("nixos");
+    let channel = self.config["channel"]
+      .as_str()
+      .unwrap_or(channel::DEFAULT_CHANNEL);
+    let nixpkgs_url = channel::flake_url(channel);
+    let raw = format!(
+      r#"{{
+        description = {description};
+        inputs = {{
+          nixpkgs.url = {nixpkgs_url};
+          disko.url = "github:nix-community/disko";
+          disko.inputs.nixpkgs.follows = "nixpkgs";
+        }};
+        outputs = {{ nixpkgs, disko, ... }}: {{
+          nixosConfigurations.{hostname} = nixpkgs.lib.nixosSystem {{
+            modules = [
+              disko.nixosModules.disko
+              ./configuration.nix
+            ];
+          }};
+        }};
+      }}"#,
+      description = nixstr(format!("NixOS configuration for {hostname}")),
+      nixpkgs_url = nixstr(nixpkgs_url),
+      hostname = nixstr(hostname),
+    );
+    fmt_nix(raw).map(Some)
+  }
   /// Generate the main NixOS system configuration (configuration.nix)
   ///
   /// Processes each configuration option and converts it to appropriate Nix
@@ -140,6 +262,28 @@ impl NixWriter {
 
     let mut cfg_attrs = String::from("{}"); // Start with empty attribute set
     let mut install_home_manager = false; // Track if home-manager is needed
+    // Password hashes are emitted differently when users are managed with passwd
+    let mutable_users = cfg
+      .get("mutable_users")
+      .and_then(Value::as_bool)
+      .unwrap_or(false);
+    // Minimal and Server installs leave out the desktop even if one was picked
+    // before switching profiles
+    let headless = cfg
+      .get("profile")
+      .and_then(Value::as_str)
+      .is_some_and(|profile| matches!(profile.to_lowercase().as_str(), "minimal" | "server"));
+    let desktop = cfg
+      .get("desktop_environment")
+      .and_then(Value::as_str)
+      .map(str::to_lowercase);
+    // The system is installed from the release matching its state version
+    let state_version = cfg
+      .get("state_version")
+      .and_then(Value::as_str)
+      .unwrap_or_else(|| channel::state_version(channel::DEFAULT_CHANNEL));
+    // Some options were renamed in 24.11, older releases need the old names
+    let pre_24_11 = channel::release_before(state_version, "24.11");
     // Process each configuration key and generate corresponding Nix attributes
     for (key, value) in cfg.iter() {
       log::debug!("Processing config key: {key}");
@@ -147,10 +291,39 @@ impl NixWriter {
 
       // Match configuration keys to their Nix configuration generators
       let parsed_config = match key.trim().to_lowercase().as_str() {
+        "audio_backend"
+        | "desktop_environment"
+        | "desktop_excludes"
+        | "greeter"
+        | "bluetooth_manager"
+          if headless =>
+        {
+          None
+        }
         "audio_backend" => value.as_str().map(Self::parse_audio),
+        "gpu_driver" => value
+          .as_str()
+          .and_then(|driver| Self::parse_gpu_driver(driver, pre_24_11)),
+        "bluetooth" => value
+          .as_bool()
+          .filter(|&b| b)
+          .map(|_| Self::parse_bluetooth()),
+        "bluetooth_manager" => value
+          .as_bool()
+          .filter(|&b| b)
+          .map(|_| Self::parse_bluetooth_manager()),
         "bootloader" => {
           // Bootloader parsing can fail, so handle errors explicitly
-          let res = value.as_str().map(Self::parse_bootloader);
+          let disko = &self.config["disko"];
+          let bios = cfg.get("firmware_mode").and_then(Value::as_str) == Some("bios");
+          let bios_device = cfg
+            .get("grub_device")
+            .and_then(Value::as_str)
+            .or_else(|| Self::parse_bios_grub_device(disko))
+            .or_else(|| bios.then(|| disko["device"].as_str()).flatten());
+          let res = value
+            .as_str()
+            .map(|loader| Self::parse_bootloader(loader, bios, bios_device, Self::has_esp(disko)));
           match res {
             Some(Ok(cfg)) => Some(cfg),
             Some(Err(e)) => return Err(e),
@@ -158,27 +331,84 @@ impl NixWriter {
           }
         }
         "desktop_environment" => value.as_str().map(Self::parse_desktop_environment),
+        "desktop_excludes" => value.as_object().and_then(Self::parse_desktop_excludes),
         "enable_flakes" => value
           .as_bool()
           .filter(|&b| b)
           .map(|_| Self::parse_enable_flakes()),
-        "greeter" => None,
+        "state_version" => None,
+        "firmware_mode" | "grub_device" => None,
+        "cpu_vendor" => value.as_str().map(Self::parse_microcode),
+        "architecture" => value.as_str().map(Self::parse_host_platform),
+        "allow_unfree" => value
+          .as_bool()
+          .filter(|&b| b)
+          .map(|_| Self::parse_allow_unfree()),
+        "enable_nix_command" => value
+          .as_bool()
+          .filter(|&b| b)
+          .map(|_| Self::parse_enable_nix_command()),
+        "greeter" => value
+          .as_str()
+          .map(|greeter| Self::parse_greeter(greeter, desktop.as_deref())),
         "hostname" => value.as_str().map(Self::parse_hostname),
         "kernels" => value.as_array().map(Self::parse_kernels),
-        "keyboard_layout" => value.as_str().map(Self::parse_kb_layout),
+        "keyboard_layout" => value.as_str().map(|layout| {
+          let variant = cfg.get("keyboard_variant").and_then(Value::as_str);
+          Self::parse_kb_layout(layout, variant)
+        }),
+        "keyboard_variant" => None,
+        "console_font" => value.as_str().map(Self::parse_console_font),
         "locale" => value.as_str().map(Self::parse_locale),
+        "locale_settings" => value.as_object().and_then(Self::parse_locale_settings),
+        "extra_locales" => value
+          .as_array()
+          .and_then(|extras| Self::parse_supported_locales(extras, cfg)),
         "network_backend" => value.as_str().map(Self::parse_network_backend),
-        "profile" => None,
-        "root_passwd_hash" => Some(Self::parse_root_pass_hash(value)?),
+        "profile" => value
+          .as_str()
+          .and_then(|profile| Self::parse_profile(profile, cfg.get("ssh_config"))),
+        "root_passwd_hash" => value
+          .as_str()
+          .map(|hash| Self::parse_root_pass_hash(hash, mutable_users)),
+        "mutable_users" => value.as_bool().map(Self::parse_mutable_users),
+        "disable_root" => value
+          .as_bool()
+          .filter(|&b| b)
+          .map(|_| Self::parse_disable_root()),
         "ssh_config" => value.as_object().and_then(Self::parse_ssh_config),
+        "vpn" => value.as_object().and_then(Self::parse_vpn),
+        "sudo_config" => value.as_object().map(Self::parse_sudo_config),
+        "nix_build" => value.as_object().map(Self::parse_nix_build),
+        "boot_options" => value
+          .as_object()
+          .map(|boot| Self::parse_boot_options(boot, cfg.get("bootloader"))),
+        "auto_upgrade" => value.as_object().and_then(Self::parse_auto_upgrade),
+        "documentation" => value.as_object().and_then(Self::parse_documentation),
+        "input_method" => value
+          .as_object()
+          .and_then(|method| Self::parse_input_method(method, pre_24_11)),
         "system_pkgs" => value.as_array().map(Self::parse_system_packages),
+        "etc_files" => value.as_object().and_then(Self::parse_etc_files),
+        "data_mounts" => value.as_array().and_then(Self::parse_data_mounts),
+        "sysctl" => value.as_object().and_then(Self::parse_sysctl),
+        // Both end up in nixpkgs.overlays, handled together below
+        "overlays_scaffold" | "overlays" => None,
+        // Spliced into imports below
+        "extra_imports" => None,
+        "permitted_insecure" => value
+          .as_array()
+          .and_then(|packages| Self::parse_permitted_insecure(packages)),
+        "substituters" => value
+          .as_array()
+          .and_then(|entries| Self::parse_substituters(entries)),
         "timezone" => value.as_str().map(Self::parse_timezone),
-        "use_swap" => value.as_bool().filter(|&b| b).map(|_| Self::parse_swap()),
+        "swap" => Self::parse_swap(value),
         "users" => {
           // Parse user configurations and check if home-manager is needed
           let users: Vec<User> = serde_json::from_value(value.clone())?;
           install_home_manager = users.iter().any(|user| user.home_manager_cfg.is_some());
-          Some(self.parse_users(users)?)
+          Some(self.parse_users(users, mutable_users)?)
         }
         _ => {
           log::warn!("Unknown configuration key '{key}' - skipping");
@@ -191,18 +421,53 @@ impl NixWriter {
         cfg_attrs = merge_attrs!(cfg_attrs, config);
       }
     }
-    // Set up imports based on whether home-manager is needed
-    let imports = if install_home_manager {
-      String::from(
-        r#"{imports = [ (import "${home-manager}/nixos") ./hardware-configuration.nix ];}"#,
-      )
-    } else {
-      String::from("{imports = [./hardware-configuration.nix];}")
-    };
+    if let Some(overlays) = Self::parse_overlays(cfg) {
+      cfg_attrs = merge_attrs!(cfg_attrs, overlays);
+    }
+    let disks = self
+      .disko_disks()
+      .into_iter()
+      .map(|(_, d)| d)
+      .collect::<Vec<_>>();
+    if let Some(boot_mounts) = Self::parse_needed_for_boot(&disks) {
+      cfg_attrs = merge_attrs!(cfg_attrs, boot_mounts);
+    }
+    for disko in disks {
+      if let Some(luks_devices) = Self::parse_luks_devices(disko) {
+        cfg_attrs = merge_attrs!(cfg_attrs, luks_devices);
+      }
+    }
+    // The generated flake already provides the disko module, otherwise it's
+    // fetched here. disko-config.nix declares the filesystems, the hardware
+    // scan is generated without them
+    let disko_from_flake = cfg
+      .get("enable_flakes")
+      .and_then(Value::as_bool)
+      .unwrap_or(false)
+      && self.config.get("flake_path").is_none_or(Value::is_null);
+    let mut import_paths = vec![];
+    if install_home_manager {
+      import_paths.push(r#"(import "${home-manager}/nixos")"#.to_string());
+    }
+    if !disko_from_flake {
+      import_paths.push(r#""${disko}/module.nix""#.to_string());
+    }
+    import_paths.push("./hardware-configuration.nix".to_string());
+    import_paths.push("./disko-config.nix".to_string());
+    if let Some(extra) = cfg.get("extra_imports").and_then(Value::as_array) {
+      import_paths.extend(
+        extra
+          .iter()
+          .filter_map(Value::as_str)
+          .map(|entry| Self::module_ref(entry, "nixosModules.default")),
+      );
+    }
+    let imports = format!("{{imports = [ {} ];}}", import_paths.join(" "));
 
-    // Set the NixOS state version (required for all configurations)
+    // Set the NixOS state version (required for all configurations), matching
+    // the channel the system is installed from
     let state_version = attrset! {
-      "system.stateVersion" = nixstr("25.11");
+      "system.stateVersion" = nixstr(state_version);
     };
 
     // Combine all configuration attributes
@@ -216,6 +481,10 @@ impl NixWriter {
         "home-manager = builtins.fetchTarball https://github.com/nix-community/home-manager/archive/release-25.05.tar.gz;"
       )
     }
+    if !disko_from_flake {
+      let_statement_declarations
+        .push("disko = builtins.fetchTarball https://github.com/nix-community/disko/archive/master.tar.gz;")
+    }
 
     // Construct the let-in statement if we have dependencies
     let let_stmt = if !let_statement_declarations.is_empty() {
@@ -226,43 +495,72 @@ impl NixWriter {
     };
 
     // Generate the final Nix function and format it
-    let raw = if install_home_manager {
-      format!("{{ config, pkgs, ... }}: {let_stmt} {cfg_attrs}")
-    } else {
-      format!("{{ config, pkgs, ... }}: {cfg_attrs}")
-    };
+    let raw = format!("{{ config, pkgs, ... }}: {let_stmt} {cfg_attrs}");
 
     // Format the generated Nix code for readability
     fmt_nix(raw)
   }
+  /// Every disk in the layout with its name under `disko.devices.disk`
+  ///
+  /// The install target is always `main`, it holds root and the bootloader.
+  /// Additional disks are named after their device.
+  fn disko_disks(&self) -> Vec<(&str, &Value)> {
+    let mut disks = vec![("main", &self.config["disko"])];
+    if let Some(extra) = self.config["extra_disks"].as_object() {
+      disks.extend(extra.iter().map(|(name, disk)| (name.as_str(), disk)));
+    }
+    disks
+  }
   /// Generate Disko configuration for disk partitioning
   ///
   /// Converts the disk layout into Disko's declarative partition format
-  pub fn write_disko_config(&self, config: Value) -> anyhow::Result<String> {
-    log::debug!("Writing Disko config: {config}");
+  pub fn write_disko_config(&self) -> anyhow::Result<String> {
+    let mut entries = vec![];
+    for (name, config) in self.disko_disks() {
+      log::debug!("Writing Disko config for {name}: {config}");
 
-    // Extract basic disk information
-    let device = config["device"].as_str().unwrap_or("/dev/sda");
-    let disk_type = config["type"].as_str().unwrap_or("disk");
-    let content = Self::parse_disko_content(&config["content"])?;
+      // Extract basic disk information
+      let device = config["device"].as_str().unwrap_or("/dev/sda");
+      let disk_type = config["type"].as_str().unwrap_or("disk");
+      let content = Self::parse_disko_content(&config["content"])?;
 
-    let disko_config = attrset! {
-      "device" = nixstr(device);
-      "type" = nixstr(disk_type);
-      "content" = content;
-    };
+      let disko_config = attrset! {
+        "device" = nixstr(device);
+        "type" = nixstr(disk_type);
+        "content" = content;
+      };
+      entries.push(format!("disko.devices.disk.{name} = {disko_config};"));
+      entries.extend(Self::parse_kept_mounts(&config["content"]));
+    }
+    fmt_nix(format!("{{ {} }}", entries.join(" ")))
+  }
 
-    let raw = format!("{{ disko.devices.disk.main = {disko_config}; }}");
-    fmt_nix(raw)
+  /// With mutable users the hash is only the starting point, `passwd` owns
+  /// it after the first boot
+  fn parse_root_pass_hash(hash: &str, mutable_users: bool) -> String {
+    if mutable_users {
+      attrset! {
+        "users.users.root.initialHashedPassword" = nixstr(hash);
+      }
+    } else {
+      attrset! {
+        "users.users.root.hashedPassword" = nixstr(hash);
+      }
+    }
   }
 
-  fn parse_root_pass_hash(content: &Value) -> anyhow::Result<String> {
-    let hash = content
-      .as_str()
-      .ok_or_else(|| anyhow::anyhow!("Root password hash must be a string"))?;
-    Ok(attrset! {
-      "users.users.root.hashedPassword" = nixstr(hash);
-    })
+  fn parse_mutable_users(mutable: bool) -> String {
+    attrset! {
+      "users.mutableUsers" = mutable;
+    }
+  }
+
+  /// Lock the root account, "!" is never a valid hash so password login is
+  /// impossible. Administration goes through sudo from a wheel user.
+  fn parse_disable_root() -> String {
+    attrset! {
+      "users.users.root.hashedPassword" = nixstr("!");
+    }
   }
 
   /// Parse the disk content structure for Disko
@@ -294,55 +592,297 @@ impl NixWriter {
     }
   }
 
+  /// Kept partitions carry no disko content, so mount them with plain
+  /// `fileSystems` entries instead
+  fn parse_kept_mounts(content: &Value) -> Vec<String> {
+    let Some(partitions) = content["partitions"].as_object() else {
+      return vec![];
+    };
+    partitions
+      .values()
+      .filter(|p| p["keep"].as_bool().unwrap_or(false))
+      .filter_map(|p| {
+        let mountpoint = p["mountpoint"].as_str()?;
+        let device = p["device"].as_str()?;
+        let fs_type = p["fs_type"].as_str().unwrap_or("auto");
+        let fs = attrset! {
+          device = nixstr(device);
+          fsType = nixstr(fs_type);
+        };
+        let fs = match Self::parse_mount_options(p) {
+          Some(options) => {
+            let options = attrset! {
+              options = options;
+            };
+            merge_attrs!(fs, options)
+          }
+          None => fs,
+        };
+        Some(format!("fileSystems.{} = {fs};", nixstr(mountpoint)))
+      })
+      .collect()
+  }
+
+  /// Unlock encrypted partitions in the initrd
+  ///
+  /// nixos-generate-config usually finds these too and names the device by
+  /// UUID, so the partition label is only a default that gives way to it.
+  fn parse_luks_devices(disko: &Value) -> Option<String> {
+    let partitions = disko["content"]["partitions"].as_object()?;
+    let entries = partitions
+      .values()
+      .filter_map(|p| {
+        let luks = p.get("luks")?;
+        let name = luks["name"].as_str()?;
+        let device = luks["device"].as_str()?;
+        let mut attrs = attrset! {
+          device = format!("pkgs.lib.mkDefault {}", nixstr(device));
+        };
+        if let Some(key_file) = luks["key_file"].as_str() {
+          let key_attrs = attrset! {
+            keyFile = nixstr(key_file);
+          };
+          attrs = merge_attrs!(attrs, key_attrs);
+        }
+        Some(format!(
+          "boot.initrd.luks.devices.{} = {attrs};",
+          nixstr(name)
+        ))
+      })
+      .collect::<Vec<_>>();
+    if entries.is_empty() {
+      return None;
+    }
+    Some(format!("{{ {} }}", entries.join(" ")))
+  }
+
+  /// Mark partitions that stage 1 has to mount before switching to the real
+  /// root
+  ///
+  /// That is root and /boot, the paths activation needs, and whatever
+  /// partition holds a key file for unlocking an encrypted one.
+  fn parse_needed_for_boot(disks: &[&Value]) -> Option<String> {
+    const NEEDED_FOR_BOOT: [&str; 10] = [
+      "/",
+      "/boot",
+      "/nix",
+      "/nix/store",
+      "/var",
+      "/var/log",
+      "/var/lib",
+      "/var/lib/nixos",
+      "/etc",
+      "/usr",
+    ];
+    let partitions = disks
+      .iter()
+      .filter_map(|d| d["content"]["partitions"].as_object())
+      .flat_map(|p| p.values())
+      .collect::<Vec<_>>();
+    let mountpoints = partitions
+      .iter()
+      .filter_map(|p| p["mountpoint"].as_str())
+      .collect::<Vec<_>>();
+    // The deepest mount point a key file lives under is the one holding it
+    let key_mounts = partitions
+      .iter()
+      .filter_map(|p| p["luks"]["key_file"].as_str())
+      .filter_map(|key| {
+        mountpoints
+          .iter()
+          .filter(|mp| {
+            **mp == "/"
+              || key
+                .strip_prefix(**mp)
+                .is_some_and(|rest| rest.starts_with('/'))
+          })
+          .max_by_key(|mp| mp.len())
+          .copied()
+      })
+      .collect::<BTreeSet<_>>();
+    let entries = mountpoints
+      .iter()
+      .filter(|mp| NEEDED_FOR_BOOT.contains(mp) || key_mounts.contains(*mp))
+      .map(|mp| format!("fileSystems.{}.neededForBoot = true;", nixstr(mp)))
+      .collect::<Vec<_>>();
+    if entries.is_empty() {
+      return None;
+    }
+    Some(format!("{{ {} }}", entries.join(" ")))
+  }
+
+  /// A partition's extra mount options as a nix list, if it has any
+  fn parse_mount_options(partition: &Value) -> Option<String> {
+    let options = partition["mount_options"]
+      .as_array()?
+      .iter()
+      .filter_map(|o| o.as_str())
+      .map(nixstr)
+      .collect::<Vec<_>>();
+    if options.is_empty() {
+      return None;
+    }
+    Some(format!("[ {} ]", options.join(" ")))
+  }
+  /// Exact placement of a partition next to kept ones, see
+  /// [`crate::drives::Disk::as_disko_cfg`]
+  fn parse_placement(partition: &Value) -> Option<String> {
+    let start = partition["start"].as_str()?;
+    let end = partition["end"].as_str()?;
+    let priority = partition["priority"].as_u64()?;
+    Some(attrset! {
+      start = nixstr(start);
+      end = nixstr(end);
+      priority = priority;
+    })
+  }
   fn parse_partition(partition: &Value) -> anyhow::Result<String> {
-    let format = partition["format"]
-      .as_str()
-      .ok_or_else(|| anyhow::anyhow!("Missing required 'format' field in partition"))?;
-    let mountpoint = partition["mountpoint"]
-      .as_str()
-      .ok_or_else(|| anyhow::anyhow!("Missing required 'mountpoint' field in partition"))?;
+    let attrs = Self::parse_partition_attrs(partition)?;
+    Ok(match Self::parse_placement(partition) {
+      Some(placement) => merge_attrs!(attrs, placement),
+      None => attrs,
+    })
+  }
+  fn parse_partition_attrs(partition: &Value) -> anyhow::Result<String> {
+    let part_type = partition.get("type").and_then(|v| v.as_str());
+    if partition["keep"].as_bool().unwrap_or(false) {
+      // No content means disko leaves the existing filesystem alone. With the
+      // device set it finds the partition there and doesn't recreate it.
+      let size = partition["size"].as_str().unwrap_or("100%");
+      let attrs = match part_type {
+        Some(part_type) => attrset! {
+          type = nixstr(part_type);
+          size = nixstr(size);
+        },
+        None => attrset! {
+          size = nixstr(size);
+        },
+      };
+      return Ok(match partition["device"].as_str() {
+        Some(device) => {
+          let device = attrset! {
+            device = nixstr(device);
+          };
+          merge_attrs!(attrs, device)
+        }
+        None => attrs,
+      });
+    }
+    if part_type == Some("EF02") {
+      // GRUB writes its core image straight into a BIOS boot partition
+      let size = partition["size"].as_str().unwrap_or("1M");
+      return Ok(attrset! {
+        type = nixstr("EF02");
+        size = nixstr(size);
+      });
+    }
     let size = partition["size"]
       .as_str()
       .ok_or_else(|| anyhow::anyhow!("Missing required 'size' field in partition"))?;
-    let part_type = partition.get("type").and_then(|v| v.as_str());
-    log::debug!(
-      "Parsing partition: format={format}, mountpoint={mountpoint}, size={size}, type={part_type:?}"
-    );
+    let format = if partition["content"]["type"].as_str() == Some("swap") {
+      "swap"
+    } else {
+      partition["format"]
+        .as_str()
+        .ok_or_else(|| anyhow::anyhow!("Missing required 'format' field in partition"))?
+    };
+
+    let content = if format == "swap" {
+      log::debug!("Parsing swap partition: size={size}, type={part_type:?}");
+      attrset! {
+        type = nixstr("swap");
+      }
+    } else {
+      let mountpoint = partition["mountpoint"]
+        .as_str()
+        .ok_or_else(|| anyhow::anyhow!("Missing required 'mountpoint' field in partition"))?;
+      log::debug!(
+        "Parsing partition: format={format}, mountpoint={mountpoint}, size={size}, type={part_type:?}"
+      );
+      let content = attrset! {
+        type = nixstr("filesystem");
+        format = nixstr(format);
+        mountpoint = nixstr(mountpoint);
+      };
+      match Self::parse_mount_options(partition) {
+        Some(options) => {
+          let options = attrset! {
+            mountOptions = options;
+          };
+          merge_attrs!(content, options)
+        }
+        None => content,
+      }
+    };
+    let content = match partition["label"].as_str() {
+      Some(label) => {
+        // mkfs.vfat takes the label with -n, everything else (mkswap too) uses -L
+        let flag = if format == "vfat" { "-n" } else { "-L" };
+        let extra_args = attrset! {
+          extraArgs = list![nixstr(flag), nixstr(label)];
+        };
+        merge_attrs!(content, extra_args)
+      }
+      None => content,
+    };
+    let content = match partition.get("luks") {
+      Some(luks) => Self::parse_luks_content(luks, content)?,
+      None => content,
+    };
 
     if let Some(part_type) = part_type {
       Ok(attrset! {
         type = nixstr(part_type);
         size = nixstr(size);
-        content = attrset! {
-          type = nixstr("filesystem");
-          format = nixstr(format);
-          mountpoint = nixstr(mountpoint);
-        };
+        content = content;
       })
     } else {
       Ok(attrset! {
         size = nixstr(size);
-        content = attrset! {
-          type = nixstr("filesystem");
-          format = nixstr(format);
-          mountpoint = nixstr(mountpoint);
-        };
+        content = content;
       })
     }
   }
+  /// Wrap a partition's filesystem in a disko `luks` block
+  fn parse_luks_content(luks: &Value, content: String) -> anyhow::Result<String> {
+    let name = luks["name"]
+      .as_str()
+      .ok_or_else(|| anyhow::anyhow!("Missing required 'name' field in LUKS config"))?;
+    let cipher = luks["cipher"].as_str().unwrap_or("aes-xts-plain64");
+    let luks_attrs = attrset! {
+      type = nixstr("luks");
+      name = nixstr(name);
+      extraFormatArgs = list![nixstr("--cipher"), nixstr(cipher)];
+      content = content;
+    };
+    let key_attrs = match (luks["password_file"].as_str(), luks["key_file"].as_str()) {
+      (Some(password_file), _) => attrset! {
+        passwordFile = nixstr(password_file);
+      },
+      (None, Some(key_file)) => attrset! {
+        "settings.keyFile" = nixstr(key_file);
+      },
+      (None, None) => {
+        return Err(anyhow::anyhow!(
+          "LUKS volume '{name}' has neither a passphrase nor a key file"
+        ));
+      }
+    };
+    Ok(merge_attrs!(luks_attrs, key_attrs))
+  }
   fn parse_ssh_config(value: &Map<String, Value>) -> Option<String> {
     /*
     The SshCfg struct has these fields:
     - enable: bool → services.openssh.enable
     - port: u16 → services.openssh.ports
     - password_auth: bool → services.openssh.settings.PasswordAuthentication
-    - root_login: bool → services.openssh.settings.PermitRootLogin
+    - root_login: "no" | "prohibit-password" | "yes" → services.openssh.settings.PermitRootLogin
 
     With default values of:
     - enable: false
     - port: 22
     - password_auth: true
-    - root_login: false
+    - root_login: "no"
     {
       # SSH Configuration
       services.openssh = {
@@ -364,11 +904,7 @@ impl NixWriter {
     }
     let port = value["port"].as_u64().unwrap_or(22) as u16;
     let password_auth = value["password_auth"].as_bool().unwrap_or(true);
-    let root_login = value["root_login"].as_bool().unwrap_or(false);
-    let root_login_option = match root_login {
-      true => "yes".to_string(),
-      false => "no".to_string(),
-    };
+    let root_login_option = value["root_login"].as_str().unwrap_or("no").to_string();
 
     let options = attrset! {
       enable = enable;
@@ -381,6 +917,111 @@ impl NixWriter {
 
     Some(format!("{{ services.openssh = {options}; }}"))
   }
+  /// Tailscale and a single WireGuard interface. The WireGuard key is only
+  /// referenced by path and generated there on first boot if missing.
+  fn parse_vpn(value: &Map<String, Value>) -> Option<String> {
+    let mut entries = vec![];
+    if value["tailscale"].as_bool().unwrap_or(false) {
+      entries.push("services.tailscale.enable = true;".to_string());
+    }
+    let wg = &value["wireguard"];
+    if wg["enable"].as_bool().unwrap_or(false) {
+      let interface = wg["interface"].as_str().unwrap_or("wg0");
+      let str_list = |v: &Value| {
+        let items = v
+          .as_array()
+          .map(|items| {
+            items
+              .iter()
+              .filter_map(Value::as_str)
+              .map(nixstr)
+              .collect::<Vec<_>>()
+          })
+          .unwrap_or_default();
+        format!("[ {} ]", items.join(" "))
+      };
+      let peers = wg["peers"]
+        .as_array()
+        .map(|peers| {
+          peers
+            .iter()
+            .filter_map(|peer| {
+              let public_key = peer["public_key"].as_str()?;
+              let endpoint = peer["endpoint"]
+                .as_str()
+                .map(|e| format!(" endpoint = {};", nixstr(e)))
+                .unwrap_or_default();
+              Some(format!(
+                "{{ publicKey = {}; allowedIPs = {};{endpoint} }}",
+                nixstr(public_key),
+                str_list(&peer["allowed_ips"])
+              ))
+            })
+            .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+      let mut iface = attrset! {
+        ips = str_list(&wg["ips"]);
+        privateKeyFile = nixstr(wg["private_key_file"].as_str().unwrap_or("/etc/wireguard/private.key"));
+        generatePrivateKeyFile = true;
+        peers = format!("[ {} ]", peers.join(" "));
+      };
+      if let Some(port) = wg["listen_port"].as_u64() {
+        let listen_port = attrset! { listenPort = port; };
+        iface = merge_attrs!(iface, listen_port);
+        entries.push(format!("networking.firewall.allowedUDPPorts = [ {port} ];"));
+      }
+      entries.push(format!(
+        "networking.wireguard.interfaces.{} = {iface};",
+        nixstr(interface)
+      ));
+    }
+    if entries.is_empty() {
+      return None;
+    }
+    Some(format!("{{ {} }}", entries.join(" ")))
+  }
+  fn parse_sudo_config(value: &Map<String, Value>) -> String {
+    let enable = value["enable"].as_bool().unwrap_or(true);
+    if !enable {
+      return attrset! {
+        "security.sudo.enable" = false;
+      };
+    }
+    let wheel_needs_password = value["wheel_needs_password"].as_bool().unwrap_or(true);
+    let commands: Vec<String> = value["nopasswd_commands"]
+      .as_array()
+      .map(|cmds| {
+        cmds
+          .iter()
+          .filter_map(|c| c.as_str())
+          .map(|c| {
+            attrset! {
+              command = nixstr(c);
+              options = list![nixstr("NOPASSWD")];
+            }
+          })
+          .collect()
+      })
+      .unwrap_or_default();
+
+    let mut options = attrset! {
+      enable = true;
+      wheelNeedsPassword = wheel_needs_password;
+    };
+    if !commands.is_empty() {
+      let rule = attrset! {
+        groups = list![nixstr("wheel")];
+        commands = format!("[{}]", commands.join(" "));
+      };
+      let extra_rules = attrset! {
+        extraRules = list![rule];
+      };
+      options = merge_attrs!(options, extra_rules);
+    }
+
+    format!("{{ security.sudo = {options}; }}")
+  }
   fn parse_timezone(value: &str) -> String {
     attrset! {
       "time.timeZone" = nixstr(value);
@@ -406,67 +1047,236 @@ impl NixWriter {
       "i18n.defaultLocale" = nixstr(value);
     }
   }
-  fn parse_kb_layout(value: &str) -> String {
-    let (xkb, console) = match value {
-      "us(qwerty)" => ("us", "us"),
-      "us(dvorak)" => ("us", "dvorak"),
-      "us(colemak)" => ("us", "colemak"),
-      "uk" => ("gb", "uk"),
-      "de" => ("de", "de"),
-      "fr" => ("fr", "fr"),
-      "es" => ("es", "es"),
-      "it" => ("it", "it"),
-      "ru" => ("ru", "ru"),
-      "cn" => ("cn", "us"),
-      "jp" => ("jp", "us"),
-      "kr" => ("kr", "us"),
-      "in" => ("in", "us"),
-      "br" => ("br", "br-abnt2"),
-      "nl" => ("nl", "nl"),
-      "se" => ("se", "us"),
-      "no" => ("no", "no"),
-      "fi" => ("fi", "fi"),
-      "dk" => ("dk", "dk"),
-      "pl" => ("pl", "pl"),
-      "tr" => ("tr", "trq"),
-      "gr" => ("gr", "gr"),
-      _ => ("us", "us"),
+  fn parse_locale_settings(settings: &Map<String, Value>) -> Option<String> {
+    if settings.is_empty() {
+      return None;
+    }
+    let entries = settings
+      .iter()
+      .filter_map(|(category, locale)| Some(format!("{category} = {};", nixstr(locale.as_str()?))))
+      .collect::<Vec<_>>()
+      .join(" ");
+    Some(attrset! {
+      "i18n.extraLocaleSettings" = format!("{{ {entries} }}");
+    })
+  }
+  /// Setting `supportedLocales` replaces the NixOS default, so the locales in
+  /// use have to be listed again alongside the extra ones
+  fn parse_supported_locales(extras: &[Value], cfg: &Map<String, Value>) -> Option<String> {
+    if extras.is_empty() {
+      return None;
+    }
+    let mut locales = vec!["C.UTF-8", "en_US.UTF-8"];
+    locales.extend(cfg.get("locale").and_then(Value::as_str));
+    if let Some(settings) = cfg.get("locale_settings").and_then(Value::as_object) {
+      locales.extend(settings.values().filter_map(Value::as_str));
+    }
+    locales.extend(extras.iter().filter_map(Value::as_str));
+    let mut entries: Vec<String> = vec![];
+    for locale in locales {
+      // glibc names the charset after the slash, e.g. de_DE.UTF-8/UTF-8
+      let charset = locale.split_once('.').map_or("UTF-8", |(_, c)| c);
+      let entry = nixstr(format!("{locale}/{charset}"));
+      if !entries.contains(&entry) {
+        entries.push(entry);
+      }
+    }
+    Some(attrset! {
+      "i18n.supportedLocales" = format!("[ {} ]", entries.join(" "));
+    })
+  }
+  /// Before 24.11 the framework was picked with `enabled` instead of
+  /// `enable` and `type`
+  fn parse_input_method(value: &Map<String, Value>, pre_24_11: bool) -> Option<String> {
+    let framework = value["framework"].as_str()?;
+    let engines = value["engines"]
+      .as_array()
+      .map(|a| a.iter().filter_map(|e| e.as_str()).collect::<Vec<_>>())
+      .unwrap_or_default();
+    let (engine_attr, engine_pkgs) = match framework {
+      "fcitx5" => {
+        let pkgs = engines
+          .iter()
+          .filter_map(|e| match *e {
+            "mozc" => Some("fcitx5-mozc"),
+            "pinyin" => Some("fcitx5-chinese-addons"),
+            "hangul" => Some("fcitx5-hangul"),
+            "rime" => Some("fcitx5-rime"),
+            _ => None,
+          })
+          .collect::<Vec<_>>();
+        (
+          "fcitx5.addons",
+          format!("with pkgs; [ {} ]", pkgs.join(" ")),
+        )
+      }
+      "ibus" => {
+        let pkgs = engines
+          .iter()
+          .filter_map(|e| match *e {
+            "mozc" => Some("mozc"),
+            "pinyin" => Some("libpinyin"),
+            "hangul" => Some("hangul"),
+            "rime" => Some("rime"),
+            _ => None,
+          })
+          .collect::<Vec<_>>();
+        (
+          "ibus.engines",
+          format!("with pkgs.ibus-engines; [ {} ]", pkgs.join(" ")),
+        )
+      }
+      _ => return None,
+    };
+    let selection = if pre_24_11 {
+      format!("enabled = {};", nixstr(framework))
+    } else {
+      format!("enable = true; type = {};", nixstr(framework))
+    };
+    Some(format!(
+      "{{ i18n.inputMethod = {{ {selection} {engine_attr} = {engine_pkgs}; }}; }}"
+    ))
+  }
+  /// Terminus fonts aren't part of kbd, and need loading early so the initrd
+  /// prompts (e.g. for a LUKS passphrase) use them too
+  fn parse_console_font(font: &str) -> String {
+    let console = attrset! {
+      "console.font" = nixstr(font);
+    };
+    if !is_terminus_font(font) {
+      return console;
+    }
+    let terminus = attrset! {
+      "console.packages" = "[ pkgs.terminus_font ]";
+      "console.earlySetup" = true;
+    };
+    merge_attrs!(console, terminus)
+  }
+  /// The kbd keymap matching an XKB layout and variant, if there is one
+  pub fn console_keymap(layout: &str, variant: Option<&str>) -> Option<&'static str> {
+    let keymap = match (layout, variant) {
+      ("us", Some("dvorak")) => "dvorak",
+      ("us", Some("colemak")) => "colemak",
+      (_, Some(_)) => return None,
+      ("us" | "cn" | "jp" | "kr" | "in" | "se", None) => "us",
+      ("gb", None) => "uk",
+      ("de", None) => "de",
+      ("fr", None) => "fr",
+      ("es", None) => "es",
+      ("it", None) => "it",
+      ("ru", None) => "ru",
+      ("br", None) => "br-abnt2",
+      ("nl", None) => "nl",
+      ("no", None) => "no",
+      ("fi", None) => "fi",
+      ("dk", None) => "dk",
+      ("pl", None) => "pl",
+      ("tr", None) => "trq",
+      ("gr", None) => "gr",
+      _ => return None,
     };
+    Some(keymap)
+  }
+  fn parse_kb_layout(layout: &str, variant: Option<&str>) -> String {
+    // Older settings files stored e.g. "us(dvorak)" and "uk" as the layout
+    let (layout, variant) = match layout.split_once('(') {
+      Some((layout, "qwerty)")) => (layout, variant),
+      Some((layout, legacy)) => (layout, Some(legacy.trim_end_matches(')'))),
+      None if layout == "uk" => ("gb", variant),
+      None => (layout, variant),
+    };
+    let xkb = attrset! {
+      "services.xserver.xkb.layout" = nixstr(layout);
+    };
+    let xkb = match variant {
+      Some(variant) => {
+        let xkb_variant = attrset! {
+          "services.xserver.xkb.variant" = nixstr(variant);
+        };
+        merge_attrs!(xkb, xkb_variant)
+      }
+      None => xkb,
+    };
+    // A made-up keyMap breaks the console, so anything without a known kbd
+    // keymap has one generated from the XKB settings instead
+    let console = match Self::console_keymap(layout, variant) {
+      Some(keymap) => attrset! {
+        "console.keyMap" = nixstr(keymap);
+      },
+      None => attrset! {
+        "console.useXkbConfig" = true;
+      },
+    };
+    merge_attrs!(xkb, console)
+  }
 
-    attrset! {
-      "services.xserver.xkb.layout" = nixstr(xkb);
-      "console.keyMap" = nixstr(console);
+  /// The package set for a kernel from the kernels page
+  ///
+  /// Named like Arch does, so `linux` is the latest stable kernel and
+  /// `linux-lts` is nixpkgs' default, which follows an LTS release.
+  fn kernel_packages(kernel: &str) -> Option<&'static str> {
+    match kernel.to_lowercase().replace('_', "-").as_str() {
+      "linux" => Some("pkgs.linuxPackages_latest"),
+      "linux-lts" => Some("pkgs.linuxPackages"),
+      "linux-zen" => Some("pkgs.linuxPackages_zen"),
+      "linux-hardened" => Some("pkgs.linuxPackages_hardened"),
+      _ => None,
     }
   }
 
+  /// The first kernel is the default, every other one gets a specialisation
+  /// so it shows up as its own boot entry
   #[allow(clippy::ptr_arg)]
   fn parse_kernels(kernels: &Vec<Value>) -> String {
-    if kernels.is_empty() {
+    let kernels = kernels
+      .iter()
+      .filter_map(|kernel| {
+        let kernel = kernel.as_str()?;
+        let pkgs = Self::kernel_packages(kernel);
+        if pkgs.is_none() {
+          log::warn!("Unknown kernel '{kernel}', leaving it out");
+        }
+        pkgs.map(|pkgs| (kernel, pkgs))
+      })
+      .collect::<Vec<_>>();
+    let Some((&(_, default), others)) = kernels.split_first() else {
       return String::from("{}");
+    };
+    let mut options = vec![format!("boot.kernelPackages = {default};")];
+    for (kernel, pkgs) in others {
+      options.push(format!(
+        "specialisation.{}.configuration.boot.kernelPackages = pkgs.lib.mkForce {pkgs};",
+        nixstr(kernel)
+      ));
     }
-
-    // Take the first kernel as the primary one
-    if let Some(Value::String(kernel)) = kernels.first() {
-      let kernel_pkg = match kernel.to_lowercase().as_str() {
-        "linux" => "pkgs.linuxPackages",
-        "linux_zen" => "pkgs.linuxPackages_zen",
-        "linux_hardened" => "pkgs.linuxPackages_hardened",
-        "linux_lts" => "pkgs.linuxPackages_lts",
-        _ => "pkgs.linuxPackages", // Default fallback
-      };
-      attrset! {
-        "boot.kernelPackages" = kernel_pkg;
-      }
-    } else {
-      String::from("{}")
-    }
+    format!("{{ {} }}", options.join(" "))
   }
   fn parse_hostname(value: &str) -> String {
     attrset! {
       "networking.hostName" = nixstr(value);
     }
   }
-  fn _parse_greeter(value: &str, de: Option<&str>) -> String {
+  /// Settings implied by the profile itself, the desktop pieces are left out
+  /// above for the headless ones
+  fn parse_profile(value: &str, ssh_config: Option<&Value>) -> Option<String> {
+    match value.to_lowercase().as_str() {
+      "server" => {
+        let mut cfg = attrset! {
+          "services.xserver.enable" = false;
+        };
+        // An explicit SSH setting wins, even if it turns the server off
+        if ssh_config.is_none_or(Value::is_null) {
+          let ssh = attrset! {
+            "services.openssh.enable" = true;
+          };
+          cfg = merge_attrs!(cfg, ssh);
+        }
+        Some(cfg)
+      }
+      _ => None,
+    }
+  }
+  fn parse_greeter(value: &str, de: Option<&str>) -> String {
     match value.to_lowercase().as_str() {
       "sddm" => {
         if let Some(de) = de {
@@ -536,6 +1346,58 @@ impl NixWriter {
       _ => String::new(),
     }
   }
+  fn parse_desktop_excludes(value: &Map<String, Value>) -> Option<String> {
+    let option = match value.get("desktop")?.as_str()?.to_lowercase().as_str() {
+      "gnome" => "environment.gnome.excludePackages",
+      "plasma" | "kde plasma" => "environment.plasma5.excludePackages",
+      _ => return None,
+    };
+    let packages = value
+      .get("packages")?
+      .as_array()?
+      .iter()
+      .filter_map(|p| p.as_str())
+      .map(|p| format!("pkgs.{p}"))
+      .collect::<Vec<_>>();
+    if packages.is_empty() {
+      return None;
+    }
+    Some(format!("{{ {option} = [ {} ]; }}", packages.join(" ")))
+  }
+  /// Takes the labels from [`crate::installer::GPU_DRIVERS`], "None/VM" leaves
+  /// the defaults
+  ///
+  /// `hardware.graphics` replaced `hardware.opengl` in 24.11
+  fn parse_gpu_driver(value: &str, pre_24_11: bool) -> Option<String> {
+    let (driver, nvidia_open) = match value {
+      "NVIDIA (proprietary)" => ("nvidia", Some(false)),
+      "NVIDIA (open)" => ("nvidia", Some(true)),
+      "AMD" => ("amdgpu", None),
+      "Intel" => ("modesetting", None),
+      _ => return None,
+    };
+    let drivers = format!("[ {} ]", nixstr(driver));
+    let attrs = if pre_24_11 {
+      attrset! {
+        "services.xserver.videoDrivers" = drivers;
+        "hardware.opengl.enable" = true;
+      }
+    } else {
+      attrset! {
+        "services.xserver.videoDrivers" = drivers;
+        "hardware.graphics.enable" = true;
+      }
+    };
+    Some(match nvidia_open {
+      Some(open) => {
+        let nvidia = attrset! {
+          "hardware.nvidia.open" = open;
+        };
+        merge_attrs!(attrs, nvidia)
+      }
+      None => attrs,
+    })
+  }
   fn parse_audio(value: &str) -> String {
     match value.to_lowercase().as_str() {
       "pulseaudio" => attrset! {
@@ -548,14 +1410,74 @@ impl NixWriter {
       _ => String::new(),
     }
   }
-  fn parse_bootloader(value: &str) -> anyhow::Result<String> {
-    let bootloader_attrs = match value.to_lowercase().as_str() {
-      "systemd-boot" => attrset! {
+  fn parse_bluetooth() -> String {
+    attrset! {
+      "hardware.bluetooth.enable" = true;
+      "hardware.bluetooth.powerOnBoot" = true;
+    }
+  }
+  fn parse_bluetooth_manager() -> String {
+    attrset! {
+      "services.blueman.enable" = true;
+    }
+  }
+  /// The disk GRUB should be installed to for BIOS boot, if the layout has a
+  /// BIOS boot partition
+  fn parse_bios_grub_device(disko: &Value) -> Option<&str> {
+    let partitions = disko["content"]["partitions"].as_object()?;
+    partitions
+      .values()
+      .any(|p| p["type"].as_str() == Some("EF02"))
+      .then(|| disko["device"].as_str())
+      .flatten()
+  }
+  fn has_esp(disko: &Value) -> bool {
+    disko["content"]["partitions"]
+      .as_object()
+      .is_some_and(|parts| parts.values().any(|p| p["type"].as_str() == Some("EF00")))
+  }
+  /// `bios` is set when the live system booted without UEFI, in which case
+  /// GRUB has to go to the MBR of `bios_device`
+  fn parse_bootloader(
+    value: &str,
+    bios: bool,
+    bios_device: Option<&str>,
+    has_esp: bool,
+  ) -> anyhow::Result<String> {
+    let bootloader_attrs = match (value.to_lowercase().as_str(), bios_device) {
+      ("systemd-boot", _) if bios => {
+        return Err(anyhow::anyhow!(
+          "systemd-boot needs UEFI, but this machine booted in BIOS mode"
+        ));
+      }
+      ("systemd-boot", _) => attrset! {
         "systemd-boot.enable" = true;
         "efi.canTouchEfiVariables" = true;
       },
+      ("grub", None) if bios => {
+        return Err(anyhow::anyhow!(
+          "GRUB on a BIOS system needs a disk to install to"
+        ));
+      }
+      // Without an ESP there is nothing to put an EFI image on
+      ("grub", Some(device)) if bios && !has_esp => attrset! {
+        grub = attrset! {
+          device = nixstr(device);
+          enable = true;
+        };
+      },
 
-      "grub" => attrset! {
+      // Installed for both BIOS and UEFI. EFI variables may not exist, so the
+      // EFI image goes to the removable media path instead.
+      ("grub", Some(device)) => attrset! {
+        grub = attrset! {
+          device = nixstr(device);
+          enable = true;
+          efiSupport = true;
+          efiInstallAsRemovable = true;
+        };
+      },
+      ("grub", None) => attrset! {
         grub = attrset! {
           device = nixstr("nodev");
           enable = true;
@@ -570,13 +1492,14 @@ impl NixWriter {
     })
   }
 
-  fn parse_users(&self, users: Vec<User>) -> anyhow::Result<String> {
+  fn parse_users(&self, users: Vec<User>, mutable_users: bool) -> anyhow::Result<String> {
     if users.is_empty() {
       return Ok(String::from("{}"));
     }
 
     let mut user_configs = Vec::new();
     let mut hm_configs = Vec::new();
+    let mut shells = BTreeSet::new();
 
     for user in users {
       let groups_list = if user.groups.is_empty() {
@@ -588,7 +1511,57 @@ impl NixWriter {
       let user_config = attrset! {
         "isNormalUser" = "true";
         "extraGroups" = groups_list;
-        "hashedPassword" = nixstr(user.password_hash);
+      };
+      // Users without a password are expected to get one from passwd later
+      let user_config = match (user.password_hash.is_empty(), mutable_users) {
+        (true, _) => user_config,
+        (false, true) => {
+          let password = attrset! {
+            "initialHashedPassword" = nixstr(&user.password_hash);
+          };
+          merge_attrs!(user_config, password)
+        }
+        (false, false) => {
+          let password = attrset! {
+            "hashedPassword" = nixstr(&user.password_hash);
+          };
+          merge_attrs!(user_config, password)
+        }
+      };
+      let user_config = if user.ssh_keys.is_empty() {
+        user_config
+      } else {
+        let keys = user
+          .ssh_keys
+          .iter()
+          .map(|key| nixstr_escaped(key))
+          .collect::<Vec<_>>();
+        let ssh_keys = attrset! {
+          "openssh.authorizedKeys.keys" = format!("[ {} ]", keys.join(" "));
+        };
+        merge_attrs!(user_config, ssh_keys)
+      };
+      let user_config = match user.shell.as_deref() {
+        Some(shell) => {
+          let (package, program) = Self::shell_package(shell)?;
+          let shell_attrs = match program {
+            Some(_) => attrset! {
+              shell = package;
+            },
+            None if shell == "bash" => attrset! {
+              shell = package;
+            },
+            // The NixOS check for a matching programs.<shell>.enable would
+            // fail for shells that have no such module
+            None => attrset! {
+              shell = package;
+              ignoreShellProgramCheck = "true";
+            },
+          };
+          shells.insert(shell.to_string());
+          merge_attrs!(user_config, shell_attrs)
+        }
+        None => user_config,
       };
       user_configs.push(format!("\"{}\" = {};", user.username, user_config));
 
@@ -621,12 +1594,198 @@ impl NixWriter {
         "users.users" = format!("{{ {} }}", user_configs.join(" "));
       }
     };
+    // A login shell has to be enabled system-wide, or the build fails
+    let mut shell_options = vec![];
+    for shell in shells.iter().filter(|shell| *shell != "bash") {
+      match Self::shell_package(shell)? {
+        (_, Some(program)) => shell_options.push(format!("{program} = true;")),
+        (package, None) => shell_options.push(format!("environment.shells = [ {package} ];")),
+      }
+    }
+    let shell_options = format!("{{ {} }}", shell_options.join(" "));
+    let users = merge_attrs!(users, shell_options);
 
     log::debug!("Parsed users config: {users}");
 
     Ok(users)
   }
 
+  /// The package for a login shell and the option that enables it
+  /// system-wide, if NixOS has one
+  ///
+  /// bash is always enabled, the others without a module only need to be
+  /// listed in `environment.shells`.
+  fn shell_package(shell: &str) -> anyhow::Result<(&'static str, Option<&'static str>)> {
+    match shell {
+      "bash" => Ok(("pkgs.bashInteractive", None)),
+      "zsh" => Ok(("pkgs.zsh", Some("programs.zsh.enable"))),
+      "fish" => Ok(("pkgs.fish", Some("programs.fish.enable"))),
+      "nushell" => Ok(("pkgs.nushell", None)),
+      _ => Err(anyhow::anyhow!("Unsupported login shell '{shell}'")),
+    }
+  }
+
+  fn parse_etc_files(files: &Map<String, Value>) -> Option<String> {
+    if files.is_empty() {
+      return None;
+    }
+    let entries = files
+      .iter()
+      .filter_map(|(path, text)| {
+        let text = nixstr_escaped(text.as_str()?);
+        Some(format!("{} = {{ text = {text}; }};", nixstr_escaped(path)))
+      })
+      .collect::<Vec<_>>()
+      .join(" ");
+    Some(attrset! {
+      "environment.etc" = format!("{{ {entries} }}");
+    })
+  }
+
+  /// Existing partitions mounted by UUID. `nofail` keeps boot going if the
+  /// disk is ever removed.
+  #[allow(clippy::ptr_arg)]
+  fn parse_data_mounts(mounts: &Vec<Value>) -> Option<String> {
+    let entries = mounts
+      .iter()
+      .filter_map(|mount| {
+        let mount_point = mount["mount_point"].as_str()?;
+        let uuid = mount["uuid"].as_str()?;
+        let fs_type = mount["fs_type"].as_str().unwrap_or("auto");
+        let options = if mount["read_only"].as_bool().unwrap_or(true) {
+          list![nixstr("ro"), nixstr("nofail")]
+        } else {
+          list![nixstr("nofail")]
+        };
+        let fs = attrset! {
+          device = nixstr(format!("/dev/disk/by-uuid/{uuid}"));
+          fsType = nixstr(fs_type);
+          options = options;
+        };
+        Some(format!("fileSystems.{} = {fs};", nixstr(mount_point)))
+      })
+      .collect::<Vec<_>>();
+    if entries.is_empty() {
+      return None;
+    }
+    Some(format!("{{ {} }}", entries.join(" ")))
+  }
+
+  fn parse_sysctl(settings: &Map<String, Value>) -> Option<String> {
+    if settings.is_empty() {
+      return None;
+    }
+    let entries = settings
+      .iter()
+      .filter_map(|(key, value)| {
+        let value = value.as_str()?;
+        // Numbers go in bare so the option type matches what NixOS expects
+        let value = match value.parse::<i64>() {
+          Ok(num) => num.to_string(),
+          Err(_) => nixstr_escaped(value),
+        };
+        Some(format!("{} = {value};", nixstr(key)))
+      })
+      .collect::<Vec<_>>()
+      .join(" ");
+    Some(attrset! {
+      "boot.kernel.sysctl" = format!("{{ {entries} }}");
+    })
+  }
+
+  /// The directory itself is written by the install, see `OVERLAYS_DEFAULT_NIX`
+  /// Overrides the `lib.mkDefault` that nixos-generate-config writes for the
+  /// machine doing the install
+  fn parse_microcode(vendor: &str) -> String {
+    format!(
+      "{{ hardware.cpu.{vendor}.updateMicrocode = true; hardware.enableRedistributableFirmware = true; }}"
+    )
+  }
+
+  fn parse_host_platform(system: &str) -> String {
+    attrset! {
+      "nixpkgs.hostPlatform" = nixstr(system);
+    }
+  }
+
+  /// A path from the extra modules page as is, or a flake reference as the
+  /// flake output after `#`, `default_output` if there is none
+  fn module_ref(entry: &str, default_output: &str) -> String {
+    if !is_flake_ref(entry) {
+      return entry.to_string();
+    }
+    let (flake, output) = entry.split_once('#').unwrap_or((entry, default_output));
+    format!("(builtins.getFlake {}).{output}", nixstr(flake))
+  }
+  /// The overlays scaffold and any extra overlays share `nixpkgs.overlays`
+  fn parse_overlays(cfg: &serde_json::Map<String, Value>) -> Option<String> {
+    let mut parts = vec![];
+    if cfg
+      .get("overlays_scaffold")
+      .and_then(Value::as_bool)
+      .unwrap_or(false)
+    {
+      parts.push("(import ./overlays)".to_string());
+    }
+    let extra = cfg
+      .get("overlays")
+      .and_then(Value::as_array)
+      .into_iter()
+      .flatten()
+      .filter_map(Value::as_str)
+      .map(|entry| {
+        if is_flake_ref(entry) {
+          Self::module_ref(entry, "overlays.default")
+        } else {
+          format!("(import {entry})")
+        }
+      })
+      .collect::<Vec<_>>();
+    if !extra.is_empty() {
+      parts.push(format!("[ {} ]", extra.join(" ")));
+    }
+    if parts.is_empty() {
+      return None;
+    }
+    Some(attrset! {
+      "nixpkgs.overlays" = parts.join(" ++ ");
+    })
+  }
+
+  fn parse_permitted_insecure(packages: &[Value]) -> Option<String> {
+    let names = packages
+      .iter()
+      .filter_map(|name| name.as_str())
+      .map(nixstr)
+      .collect::<Vec<_>>();
+    if names.is_empty() {
+      return None;
+    }
+    Some(attrset! {
+      "nixpkgs.config.permittedInsecurePackages" = format!("[{}]", names.join(" "));
+    })
+  }
+
+  /// Entries are `[url, key]` pairs. The NixOS module appends cache.nixos.org
+  /// and its key to both lists, so only the extra caches are written.
+  fn parse_substituters(entries: &[Value]) -> Option<String> {
+    let (urls, keys): (Vec<_>, Vec<_>) = entries
+      .iter()
+      .filter_map(|entry| {
+        let url = entry.get(0)?.as_str()?;
+        let key = entry.get(1)?.as_str()?;
+        Some((nixstr_escaped(url), nixstr_escaped(key)))
+      })
+      .unzip();
+    if urls.is_empty() {
+      return None;
+    }
+    Some(attrset! {
+      "nix.settings.substituters" = format!("[{}]", urls.join(" "));
+      "nix.settings.trusted-public-keys" = format!("[{}]", keys.join(" "));
+    })
+  }
+
   #[allow(clippy::ptr_arg)]
   fn parse_system_packages(packages: &Vec<Value>) -> String {
     if packages.is_empty() {
@@ -649,15 +1808,260 @@ impl NixWriter {
     }
   }
 
+  fn parse_allow_unfree() -> String {
+    attrset! {
+      "nixpkgs.config.allowUnfree" = true;
+    }
+  }
+
   fn parse_enable_flakes() -> String {
     attrset! {
       "nix.settings.experimental-features" = "[ \"nix-command\" \"flakes\" ]";
     }
   }
 
-  fn parse_swap() -> String {
+  /// Only emitted when flakes are off, flakes already include nix-command
+  fn parse_enable_nix_command() -> String {
     attrset! {
-      "swapDevices" = "[ { device = \"/swapfile\"; size = 4096; } ]";
+      "nix.settings.experimental-features" = "[ \"nix-command\" ]";
+    }
+  }
+
+  /// The generation limit is an option of the bootloader itself, so it goes
+  /// under whichever one was picked
+  fn parse_boot_options(value: &Map<String, Value>, bootloader: Option<&Value>) -> String {
+    let timeout = value["timeout"].as_u64().unwrap_or(5);
+    let timeout = attrset! {
+      "boot.loader.timeout" = timeout;
+    };
+    let loader = match bootloader.and_then(Value::as_str).map(str::to_lowercase) {
+      Some(loader) if loader == "systemd-boot" => Some("systemd-boot"),
+      Some(loader) if loader == "grub" => Some("grub"),
+      _ => None,
+    };
+    match (loader, value["configuration_limit"].as_u64()) {
+      (Some(loader), Some(limit)) => {
+        let limit = format!("{{ boot.loader.{loader}.configurationLimit = {limit}; }}");
+        merge_attrs!(timeout, limit)
+      }
+      _ => timeout,
     }
   }
+
+  fn parse_nix_build(value: &Map<String, Value>) -> String {
+    let max_jobs = value["max_jobs"].as_u64().unwrap_or(1);
+    let cores = value["cores"].as_u64().unwrap_or(0);
+    attrset! {
+      "nix.settings.max-jobs" = max_jobs;
+      "nix.settings.cores" = cores;
+    }
+  }
+
+  fn parse_auto_upgrade(value: &Map<String, Value>) -> Option<String> {
+    if !value["enable"].as_bool().unwrap_or(false) {
+      return None;
+    }
+    let dates = value["dates"].as_str().unwrap_or("04:40");
+    let allow_reboot = value["allow_reboot"].as_bool().unwrap_or(false);
+    let mut options = attrset! {
+      enable = true;
+      dates = nixstr(dates);
+      allowReboot = allow_reboot;
+    };
+    if let Some(flake) = value["flake"].as_str() {
+      let flake_attr = attrset! {
+        flake = nixstr(flake);
+      };
+      options = merge_attrs!(options, flake_attr);
+      // The flake's lock file pins nixpkgs, without this every upgrade
+      // rebuilds the same revision
+      if value["update_nixpkgs"].as_bool().unwrap_or(false) {
+        let flags_attr = attrset! {
+          flags = "[ \"--update-input\" \"nixpkgs\" \"--print-build-logs\" ]";
+        };
+        options = merge_attrs!(options, flags_attr);
+      }
+    }
+    Some(format!("{{ system.autoUpgrade = {options}; }}"))
+  }
+
+  /// Only settings that differ from the NixOS defaults are written
+  fn parse_documentation(value: &Map<String, Value>) -> Option<String> {
+    let options = [("man", true), ("nixos", true), ("dev", false)]
+      .into_iter()
+      .filter_map(|(key, default)| {
+        let enabled = value.get(key).and_then(|v| v.as_bool()).unwrap_or(default);
+        (enabled != default).then(|| format!("documentation.{key}.enable = {enabled};"))
+      })
+      .collect::<Vec<_>>();
+    if options.is_empty() {
+      return None;
+    }
+    Some(format!("{{ {} }}", options.join(" ")))
+  }
+
+  /// A swap partition needs nothing here, disko adds it to swapDevices
+  fn parse_swap(value: &Value) -> Option<String> {
+    match value["kind"].as_str()? {
+      "file" => Some(attrset! {
+        "swapDevices" = "[ { device = \"/swapfile\"; size = 4096; } ]";
+      }),
+      "zram" => {
+        let percent = value["percent"]
+          .as_u64()
+          .unwrap_or(SwapKind::DEFAULT_ZRAM_PERCENT.into());
+        Some(attrset! {
+          "zramSwap.enable" = "true";
+          "zramSwap.memoryPercent" = percent;
+        })
+      }
+      _ => None,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn etc_file_paths_are_escaped() {
+    let files = serde_json::json!({ "a\"${b}\\c": "x" });
+    let nix = NixWriter::parse_etc_files(files.as_object().unwrap()).unwrap();
+    assert!(nix.contains(r#""a\"\${b}\\c" = { text = "x"; };"#), "{nix}");
+  }
+
+  #[test]
+  fn releases_before_24_11_use_the_old_option_names() {
+    assert!(channel::release_before("24.05", "24.11"));
+    assert!(!channel::release_before("24.11", "24.11"));
+    assert!(!channel::release_before("25.05", "24.11"));
+
+    let method = serde_json::json!({ "framework": "fcitx5", "engines": ["mozc"] });
+    let method = method.as_object().unwrap();
+    let old = NixWriter::parse_input_method(method, true).unwrap();
+    assert!(old.contains(r#"enabled = "fcitx5";"#));
+    assert!(!old.contains("type ="));
+    let new = NixWriter::parse_input_method(method, false).unwrap();
+    assert!(new.contains(r#"enable = true; type = "fcitx5";"#));
+
+    let old = NixWriter::parse_gpu_driver("AMD", true).unwrap();
+    assert!(old.contains("hardware.opengl.enable = true;"));
+    assert!(!old.contains("hardware.graphics"));
+    let new = NixWriter::parse_gpu_driver("AMD", false).unwrap();
+    assert!(new.contains("hardware.graphics.enable = true;"));
+  }
+
+  #[test]
+  fn kept_partition_has_no_content() {
+    let part = serde_json::json!({
+      "size": "1G",
+      "keep": true,
+      "device": "/dev/sda1",
+      "fs_type": "ext4",
+      "mountpoint": "/home",
+    });
+    let nix = NixWriter::parse_partition(&part).unwrap();
+    assert!(!nix.contains("content"), "{nix}");
+    assert!(!nix.contains("format"), "{nix}");
+  }
+
+  #[test]
+  fn kept_partition_is_found_by_device_and_position() {
+    let part = serde_json::json!({
+      "size": "1G",
+      "keep": true,
+      "device": "/dev/sda1",
+      "start": "2048",
+      "end": "2099199",
+      "priority": 1,
+    });
+    let nix = NixWriter::parse_partition(&part).unwrap();
+    assert!(nix.contains("device = \"/dev/sda1\";"), "{nix}");
+    assert!(nix.contains("start = \"2048\";"), "{nix}");
+    assert!(nix.contains("end = \"2099199\";"), "{nix}");
+    assert!(nix.contains("priority = 1;"), "{nix}");
+  }
+
+  #[test]
+  fn formatted_partition_gets_a_filesystem() {
+    let part = serde_json::json!({
+      "size": "1G",
+      "format": "ext4",
+      "mountpoint": "/",
+      "label": "nixos",
+    });
+    let nix = NixWriter::parse_partition(&part).unwrap();
+    assert!(nix.contains("format = \"ext4\""), "{nix}");
+    assert!(nix.contains("mountpoint = \"/\""), "{nix}");
+    assert!(nix.contains("\"-L\""), "{nix}");
+  }
+
+  fn needed_for_boot(disk: Value) -> String {
+    NixWriter::parse_needed_for_boot(&[&disk]).unwrap_or_default()
+  }
+
+  #[test]
+  fn boot_is_needed_for_boot_but_home_is_not() {
+    let nix = needed_for_boot(serde_json::json!({
+      "content": { "partitions": {
+        "BOOT": { "size": "500M", "format": "vfat", "mountpoint": "/boot" },
+        "HOME": { "size": "10G", "format": "ext4", "mountpoint": "/home" },
+        "nixos": { "size": "100%", "format": "ext4", "mountpoint": "/" },
+      }}
+    }));
+    assert!(
+      nix.contains("fileSystems.\"/boot\".neededForBoot = true;"),
+      "{nix}"
+    );
+    assert!(
+      nix.contains("fileSystems.\"/\".neededForBoot = true;"),
+      "{nix}"
+    );
+    assert!(!nix.contains("/home"), "{nix}");
+  }
+
+  #[test]
+  fn partition_holding_a_luks_key_is_needed_for_boot() {
+    let nix = needed_for_boot(serde_json::json!({
+      "content": { "partitions": {
+        "KEYS": { "size": "100M", "format": "ext4", "mountpoint": "/keys" },
+        "HOME": {
+          "size": "10G",
+          "format": "ext4",
+          "mountpoint": "/home",
+          "luks": { "name": "home", "key_file": "/keys/home.key" },
+        },
+      }}
+    }));
+    assert!(
+      nix.contains("fileSystems.\"/keys\".neededForBoot = true;"),
+      "{nix}"
+    );
+    assert!(!nix.contains("/home"), "{nix}");
+  }
+
+  #[test]
+  fn kept_partition_is_mounted_through_file_systems() {
+    let content = serde_json::json!({
+      "partitions": {
+        "home": {
+          "size": "1G",
+          "keep": true,
+          "device": "/dev/sda1",
+          "fs_type": "ext4",
+          "mountpoint": "/home",
+        },
+        "root": { "size": "100%", "format": "ext4", "mountpoint": "/" },
+      }
+    });
+    let mounts = NixWriter::parse_kept_mounts(&content);
+    assert_eq!(mounts.len(), 1);
+    assert!(
+      mounts[0].starts_with("fileSystems.\"/home\""),
+      "{}",
+      mounts[0]
+    );
+    assert!(mounts[0].contains("/dev/sda1"), "{}", mounts[0]);
+  }
 }
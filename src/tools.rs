@@ -0,0 +1,120 @@
+use std::os::unix::fs::PermissionsExt;
+
+/// An external program the installer runs
+pub struct Tool {
+  pub name: &'static str,
+  /// What stops working without it
+  pub needed_for: &'static str,
+  /// How to get it outside the NixOS installer ISO
+  pub hint: &'static str,
+  /// Whether installing is impossible without it
+  pub install: bool,
+  /// Whether installing is impossible without it when existing partitions
+  /// are kept
+  pub keep_partitions: bool,
+}
+
+pub const TOOLS: &[Tool] = &[
+  Tool {
+    name: "lsblk",
+    needed_for: "finding drives",
+    hint: "part of util-linux",
+    install: true,
+    keep_partitions: false,
+  },
+  Tool {
+    name: "mkpasswd",
+    needed_for: "hashing passwords",
+    hint: "nix-shell -p mkpasswd",
+    install: false,
+    keep_partitions: false,
+  },
+  Tool {
+    name: "nixfmt",
+    needed_for: "generating the configuration",
+    hint: "nix-shell -p nixfmt-rfc-style",
+    install: true,
+    keep_partitions: false,
+  },
+  Tool {
+    name: "disko",
+    needed_for: "partitioning and formatting",
+    hint: "nix-shell -p disko",
+    install: true,
+    keep_partitions: false,
+  },
+  Tool {
+    name: "sgdisk",
+    needed_for: "removing partitions next to kept ones",
+    hint: "nix-shell -p gptfdisk",
+    install: false,
+    keep_partitions: true,
+  },
+  Tool {
+    name: "nixos-generate-config",
+    needed_for: "detecting hardware",
+    hint: "nix-shell -p nixos-install-tools",
+    install: true,
+    keep_partitions: false,
+  },
+  Tool {
+    name: "nixos-install",
+    needed_for: "installing the system",
+    hint: "nix-shell -p nixos-install-tools",
+    install: true,
+    keep_partitions: false,
+  },
+  Tool {
+    name: "nixos-enter",
+    needed_for: "setting up channels on the new system",
+    hint: "nix-shell -p nixos-install-tools",
+    install: true,
+    keep_partitions: false,
+  },
+  Tool {
+    name: "nix-instantiate",
+    needed_for: "verifying the config evaluates",
+    hint: "part of nix",
+    install: false,
+    keep_partitions: false,
+  },
+];
+
+/// Whether an executable called `name` is in one of the PATH directories
+pub fn on_path(name: &str) -> bool {
+  let Some(path) = std::env::var_os("PATH") else {
+    return false;
+  };
+  std::env::split_paths(&path).any(|dir| {
+    std::fs::metadata(dir.join(name))
+      .is_ok_and(|meta| meta.is_file() && meta.permissions().mode() & 0o111 != 0)
+  })
+}
+
+pub fn missing() -> Vec<&'static Tool> {
+  TOOLS.iter().filter(|tool| !on_path(tool.name)).collect()
+}
+
+/// Missing tools that the install steps can't do without
+pub fn missing_for_install(keeps_partitions: bool) -> Vec<&'static Tool> {
+  missing()
+    .into_iter()
+    .filter(|tool| tool.install || (keeps_partitions && tool.keep_partitions))
+    .collect()
+}
+
+/// Fail with an explanation if `name` isn't available, instead of the bare
+/// "No such file or directory" from spawning it
+pub fn require(name: &str) -> anyhow::Result<()> {
+  if on_path(name) {
+    return Ok(());
+  }
+  match TOOLS.iter().find(|tool| tool.name == name) {
+    Some(tool) => Err(anyhow::anyhow!(
+      "{name} was not found, it is needed for {}. Try: {}",
+      tool.needed_for,
+      tool.hint
+    )),
+    None => Err(anyhow::anyhow!("{name} was not found on PATH")),
+  }
+}
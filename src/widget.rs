@@ -1,10 +1,11 @@
-use crate::{split_hor, split_vert};
+use crate::{keys, split_hor, split_vert};
 use std::{
   collections::VecDeque,
-  fs::{File, OpenOptions},
-  io::{BufRead, BufReader, Seek, SeekFrom},
-  path::PathBuf,
+  io::{BufRead, BufReader, Read},
   process::{Child, Command, Stdio},
+  sync::mpsc::{self, Receiver, Sender},
+  thread,
+  time::{Duration, Instant},
 };
 use throbber_widgets_tui::{BOX_DRAWING, ThrobberState};
 
@@ -12,7 +13,7 @@ use ansi_to_tui::IntoText;
 use fuzzy_matcher::{FuzzyMatcher, skim::SkimMatcherV2};
 use ratatui::{
   Frame,
-  crossterm::event::{KeyCode, KeyEvent},
+  crossterm::event::{KeyCode, KeyEvent, KeyModifiers},
   layout::{Alignment, Constraint, Layout, Rect},
   style::{Color, Modifier, Style},
   text::{Line, Span},
@@ -22,7 +23,7 @@ use ratatui::{
 };
 use serde_json::Value;
 
-use crate::{installer::Signal, ui_down, ui_left, ui_up};
+use crate::{drives::bytes_readable, installer::Signal, ui_down, ui_left, ui_up};
 use std::collections::BTreeMap;
 
 /// Manages package selection with efficient fuzzy search and filtering
@@ -598,6 +599,7 @@ pub struct LineEditor {
   pub title: String,
   pub value: String,
   pub error: Option<String>,
+  /// Position in chars, converted to a byte offset only to edit `value`
   pub cursor: usize,
 }
 
@@ -751,9 +753,7 @@ impl LineEditor {
   }
   pub fn set_value(&mut self, value: impl ToString) {
     self.value = value.to_string();
-    if self.cursor > self.value.len() {
-      self.cursor = self.value.len();
-    }
+    self.cursor = self.cursor.min(self.char_count());
     self.error = None;
   }
   pub fn error(&mut self, msg: impl ToString) {
@@ -761,47 +761,118 @@ impl LineEditor {
     self.value.clear();
     self.cursor = 0;
   }
+  /// Word separators, `/` included so path segments count as words
+  fn is_word_sep(c: char) -> bool {
+    c.is_whitespace() || c == '/'
+  }
+  /// Length of the value in chars, which is what `cursor` counts
+  fn char_count(&self) -> usize {
+    self.value.chars().count()
+  }
+  /// Byte offset of the char at `idx`, for the `String` methods
+  fn byte_offset(&self, idx: usize) -> usize {
+    self
+      .value
+      .char_indices()
+      .nth(idx)
+      .map_or(self.value.len(), |(i, _)| i)
+  }
+  /// Start of the word before the cursor, skipping separators first
+  fn prev_word_start(&self) -> usize {
+    let chars = self.value.chars().collect::<Vec<_>>();
+    let mut idx = self.cursor.min(chars.len());
+    while idx > 0 && Self::is_word_sep(chars[idx - 1]) {
+      idx -= 1;
+    }
+    while idx > 0 && !Self::is_word_sep(chars[idx - 1]) {
+      idx -= 1;
+    }
+    idx
+  }
+  /// End of the word after the cursor, skipping separators first
+  fn next_word_end(&self) -> usize {
+    let chars = self.value.chars().collect::<Vec<_>>();
+    let mut idx = self.cursor.min(chars.len());
+    while idx < chars.len() && Self::is_word_sep(chars[idx]) {
+      idx += 1;
+    }
+    while idx < chars.len() && !Self::is_word_sep(chars[idx]) {
+      idx += 1;
+    }
+    idx
+  }
+  /// Insert pasted text at the cursor. Line breaks and tabs become spaces,
+  /// a trailing newline is dropped
+  pub fn paste(&mut self, text: &str) {
+    let text = text
+      .trim_end_matches(['\r', '\n'])
+      .chars()
+      .filter(|c| *c != '\r')
+      .map(|c| if c.is_control() { ' ' } else { c })
+      .collect::<String>();
+    let idx = self.byte_offset(self.cursor);
+    self.value.insert_str(idx, &text);
+    self.cursor += text.chars().count();
+  }
+  /// Delete from the start of the previous word up to the cursor
+  fn delete_prev_word(&mut self) {
+    let start = self.prev_word_start();
+    let range = self.byte_offset(start)..self.byte_offset(self.cursor);
+    self.value.replace_range(range, "");
+    self.cursor = start;
+  }
 }
 
 impl ConfigWidget for LineEditor {
   fn handle_input(&mut self, key: KeyEvent) -> Signal {
+    let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+    let alt = key.modifiers.contains(KeyModifiers::ALT);
     match key.code {
+      KeyCode::Left if ctrl || alt => {
+        self.cursor = self.prev_word_start();
+      }
+      KeyCode::Right if ctrl || alt => {
+        self.cursor = self.next_word_end();
+      }
+      KeyCode::Char('w') if ctrl => self.delete_prev_word(),
+      KeyCode::Backspace if alt || ctrl => self.delete_prev_word(),
       KeyCode::Left => {
         if self.cursor > 0 {
           self.cursor -= 1;
         }
       }
       KeyCode::Right => {
-        if self.cursor < self.value.len() {
+        if self.cursor < self.char_count() {
           self.cursor += 1;
         }
       }
       KeyCode::Backspace => {
         if self.cursor > 0 && !self.value.is_empty() {
-          self.value.remove(self.cursor - 1);
+          let idx = self.byte_offset(self.cursor - 1);
+          self.value.remove(idx);
           self.cursor -= 1;
         }
       }
       KeyCode::Delete => {
-        if self.cursor < self.value.len() && !self.value.is_empty() {
-          self.value.remove(self.cursor);
+        if self.cursor < self.char_count() {
+          let idx = self.byte_offset(self.cursor);
+          self.value.remove(idx);
         }
       }
       KeyCode::Char(c) => {
-        self.value.insert(self.cursor, c);
+        let idx = self.byte_offset(self.cursor);
+        self.value.insert(idx, c);
         self.cursor += 1;
       }
       KeyCode::Home => {
         self.cursor = 0;
       }
       KeyCode::End => {
-        self.cursor = self.value.len();
+        self.cursor = self.char_count();
       }
       _ => {}
     }
-    if self.cursor > self.value.len() {
-      self.cursor = self.value.len();
-    }
+    self.cursor = self.cursor.min(self.char_count());
     Signal::Wait
   }
 
@@ -821,9 +892,7 @@ impl ConfigWidget for LineEditor {
 
   fn focus(&mut self) {
     self.focused = true;
-    if self.cursor > self.value.len() {
-      self.cursor = self.value.len();
-    }
+    self.cursor = self.cursor.min(self.char_count());
   }
 
   fn is_focused(&self) -> bool {
@@ -873,6 +942,23 @@ impl StrList {
       committed: None,
     }
   }
+  /// Select and mark the item equal to `value`, e.g. the one currently
+  /// stored on the installer
+  pub fn commit_value(&mut self, value: Option<&str>) {
+    let Some(value) = value else {
+      return;
+    };
+    let Some(pos) = self
+      .filtered_items
+      .iter()
+      .position(|item| self.items[item.idx] == value)
+    else {
+      return;
+    };
+    self.selected_idx = pos;
+    self.committed_idx = Some(self.filtered_items[pos].idx);
+    self.committed = Some(value.to_string());
+  }
   pub fn selected_item(&self) -> Option<&String> {
     let item_idx = self.filtered_items.get(self.selected_idx)?;
     self.items.get(item_idx.idx)
@@ -897,7 +983,7 @@ impl StrList {
     self.selected_idx = 0;
   }
   pub fn last_item(&mut self) {
-    self.selected_idx = self.items.len().saturating_sub(1);
+    self.selected_idx = self.filtered_items.len().saturating_sub(1);
   }
   pub fn len(&self) -> usize {
     self.items.len()
@@ -1121,19 +1207,21 @@ impl ConfigWidget for OptimizedStrList {
 impl ConfigWidget for StrList {
   fn handle_input(&mut self, key: KeyEvent) -> Signal {
     match key.code {
-      KeyCode::Up | KeyCode::Char('k') => {
+      code if ui_up!(code) => {
         if self.selected_idx > 0 {
           self.selected_idx -= 1;
         }
       }
-      KeyCode::Down | KeyCode::Char('j') => {
+      code if ui_down!(code) => {
         if self.selected_idx + 1 < self.items.len() {
           self.selected_idx += 1;
         }
       }
       KeyCode::Enter => {
-        self.committed = Some(self.items[self.selected_idx].clone());
-        self.committed_idx = Some(self.selected_idx);
+        if let Some(item) = self.filtered_items.get(self.selected_idx) {
+          self.committed = Some(self.items[item.idx].clone());
+          self.committed_idx = Some(item.idx);
+        }
       }
       _ => {}
     }
@@ -1143,14 +1231,13 @@ impl ConfigWidget for StrList {
     let items: Vec<ListItem> = self
       .filtered_items
       .iter()
-      .enumerate()
-      .map(|(i, item)| {
-        let prefix = if Some(i) == self.committed_idx {
+      .map(|item| {
+        let idx = item.idx;
+        let prefix = if Some(idx) == self.committed_idx {
           "> "
         } else {
           "  "
         };
-        let idx = item.idx;
         let item = &self.items[idx];
         ListItem::new(Span::raw(format!("{prefix}{item}")))
       })
@@ -1195,6 +1282,161 @@ impl ConfigWidget for StrList {
   }
 }
 
+/// A list where any number of items can be checked
+///
+/// Space toggles the highlighted item. Checked items are kept in the order
+/// they were checked in, so callers can treat the first one as the primary.
+pub struct MultiSelectList {
+  pub focused: bool,
+  pub title: String,
+  pub items: Vec<String>,
+  pub selected_idx: usize,
+  /// Indices into `items`, in checking order
+  pub checked: Vec<usize>,
+}
+
+impl MultiSelectList {
+  pub fn new(title: impl Into<String>, items: Vec<String>) -> Self {
+    Self {
+      focused: false,
+      title: title.into(),
+      items,
+      selected_idx: 0,
+      checked: vec![],
+    }
+  }
+  /// Check the items equal to `values`, e.g. the ones currently stored on the
+  /// installer, and move to the first of them
+  pub fn set_checked(&mut self, values: &[String]) {
+    self.checked = values
+      .iter()
+      .filter_map(|value| self.items.iter().position(|item| item == value))
+      .collect();
+    if let Some(&first) = self.checked.first() {
+      self.selected_idx = first;
+    }
+  }
+  pub fn selected_item(&self) -> Option<&String> {
+    self.items.get(self.selected_idx)
+  }
+  pub fn is_checked(&self, idx: usize) -> bool {
+    self.checked.contains(&idx)
+  }
+  /// Check or uncheck the highlighted item, returns whether it's now checked
+  pub fn toggle_selected(&mut self) -> bool {
+    let idx = self.selected_idx;
+    if self.is_checked(idx) {
+      self.checked.retain(|&i| i != idx);
+      false
+    } else if idx < self.items.len() {
+      self.checked.push(idx);
+      true
+    } else {
+      false
+    }
+  }
+  pub fn uncheck(&mut self, idx: usize) {
+    self.checked.retain(|&i| i != idx);
+  }
+  pub fn checked_items(&self) -> Vec<String> {
+    self
+      .checked
+      .iter()
+      .filter_map(|&idx| self.items.get(idx).cloned())
+      .collect()
+  }
+  pub fn next_item(&mut self) -> bool {
+    if self.selected_idx + 1 < self.items.len() {
+      self.selected_idx += 1;
+      true
+    } else {
+      false
+    }
+  }
+  pub fn previous_item(&mut self) -> bool {
+    if self.selected_idx > 0 {
+      self.selected_idx -= 1;
+      true
+    } else {
+      false
+    }
+  }
+  pub fn first_item(&mut self) {
+    self.selected_idx = 0;
+  }
+  pub fn last_item(&mut self) {
+    self.selected_idx = self.items.len().saturating_sub(1);
+  }
+}
+
+impl ConfigWidget for MultiSelectList {
+  fn handle_input(&mut self, key: KeyEvent) -> Signal {
+    match key.code {
+      code if ui_up!(code) => {
+        self.previous_item();
+      }
+      code if ui_down!(code) => {
+        self.next_item();
+      }
+      KeyCode::Char(' ') => {
+        self.toggle_selected();
+      }
+      _ => {}
+    }
+    Signal::Wait
+  }
+  fn render(&self, f: &mut Frame, area: Rect) {
+    let items: Vec<ListItem> = self
+      .items
+      .iter()
+      .enumerate()
+      .map(|(idx, item)| {
+        let marker = if self.is_checked(idx) { "[x]" } else { "[ ]" };
+        ListItem::new(Span::raw(format!("{marker} {item}")))
+      })
+      .collect();
+
+    let mut state = ListState::default();
+    state.select(Some(self.selected_idx));
+
+    let highlight = if self.focused {
+      Style::default()
+        .bg(Color::Cyan)
+        .fg(Color::Black)
+        .add_modifier(Modifier::BOLD)
+    } else {
+      Style::default()
+    };
+    let list = List::new(items)
+      .block(
+        Block::default()
+          .title(self.title.clone())
+          .borders(Borders::ALL),
+      )
+      .highlight_style(highlight);
+
+    f.render_stateful_widget(list, area, &mut state);
+  }
+  fn focus(&mut self) {
+    self.focused = true;
+  }
+  fn unfocus(&mut self) {
+    self.focused = false;
+  }
+  fn is_focused(&self) -> bool {
+    self.focused
+  }
+  fn get_value(&self) -> Option<Value> {
+    Some(Value::Array(
+      self
+        .checked_items()
+        .into_iter()
+        .map(Value::String)
+        .collect(),
+    ))
+  }
+}
+
 pub struct InfoBox<'a> {
   pub title: String,
   pub content: Vec<Line<'a>>,
@@ -1245,14 +1487,139 @@ impl<'a> ConfigWidget for InfoBox<'a> {
   }
 }
 
+pub struct BarSegment {
+  pub label: String,
+  pub size: u64,
+  pub color: Color,
+}
+
+/// Horizontal bar that splits its width between segments by size, used to
+/// give a picture of a disk's partition layout
+pub struct LayoutBar {
+  pub title: String,
+  pub segments: Vec<BarSegment>,
+}
+
+impl LayoutBar {
+  pub fn new(title: impl Into<String>, segments: Vec<BarSegment>) -> Self {
+    Self {
+      title: title.into(),
+      segments,
+    }
+  }
+  /// Column widths for each segment. Every segment gets at least one column
+  /// so tiny partitions don't vanish. Any overshoot that causes comes out of
+  /// the widest segments, and with more segments than columns the trailing
+  /// ones are dropped. The last one soaks up rounding.
+  fn segment_widths(&self, width: u16) -> Vec<u16> {
+    let total: u64 = self.segments.iter().map(|s| s.size).sum();
+    if total == 0 || self.segments.is_empty() {
+      return vec![0; self.segments.len()];
+    }
+    let mut widths: Vec<u16> = self
+      .segments
+      .iter()
+      .map(|s| ((s.size as u128 * width as u128 / total as u128) as u16).max(1))
+      .collect();
+    let mut used: u16 = widths.iter().sum();
+    while used > width {
+      let idx = widths
+        .iter()
+        .enumerate()
+        .filter(|(_, w)| **w > 1)
+        .max_by_key(|(_, w)| **w)
+        .map(|(i, _)| i)
+        .or_else(|| widths.iter().rposition(|w| *w > 0));
+      let Some(idx) = idx else {
+        break;
+      };
+      widths[idx] -= 1;
+      used -= 1;
+    }
+    if let Some(last) = widths.last_mut() {
+      *last += width - used;
+    }
+    widths
+  }
+}
+
+impl ConfigWidget for LayoutBar {
+  fn handle_input(&mut self, _key: KeyEvent) -> Signal {
+    Signal::Wait
+  }
+  fn render(&self, f: &mut Frame, area: Rect) {
+    let block = Block::default()
+      .title(self.title.clone())
+      .borders(Borders::ALL);
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+    if inner.width == 0 || inner.height == 0 {
+      return;
+    }
+    let widths = self.segment_widths(inner.width);
+    let mut bar = vec![];
+    let mut labels = vec![];
+    for (segment, width) in self.segments.iter().zip(widths) {
+      let width = width as usize;
+      if width == 0 {
+        continue;
+      }
+      let fit = |text: &str| {
+        let text: String = text.chars().take(width).collect();
+        format!("{text:^width$}")
+      };
+      bar.push(Span::styled(
+        fit(&segment.label),
+        Style::default()
+          .bg(segment.color)
+          .fg(Color::Black)
+          .add_modifier(Modifier::BOLD),
+      ));
+      labels.push(Span::raw(fit(&bytes_readable(segment.size))));
+    }
+    let paragraph = Paragraph::new(vec![Line::from(bar), Line::from(labels)]);
+    f.render_widget(paragraph, inner);
+  }
+  fn focus(&mut self) {
+    // LayoutBar is display only
+  }
+  fn unfocus(&mut self) {
+    // LayoutBar is display only
+  }
+  fn is_focused(&self) -> bool {
+    false
+  }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum StepStatus {
   Inactive,
   Running,
   Completed,
   Failed,
+  /// An optional step failed and the install carried on without it
+  Skipped,
+}
+
+/// How a single install step went, for the summary shown at the end
+#[derive(Debug, Clone)]
+pub struct StepReport {
+  pub title: String,
+  pub status: StepStatus,
+  pub duration: Option<Duration>,
 }
 
+#[derive(Debug, Clone, Default)]
+pub struct InstallSummary {
+  pub steps: Vec<StepReport>,
+  /// Problems that didn't stop the install
+  pub warnings: Vec<String>,
+  pub total: Duration,
+}
+
+/// Lines of output kept per install step
+const MAX_OUTPUT_LINES: usize = 500;
+
 pub struct InstallSteps<'a> {
   pub title: String,
   pub commands: VecDeque<(Line<'a>, VecDeque<Command>)>,
@@ -1262,8 +1629,27 @@ pub struct InstallSteps<'a> {
   pub throbber_state: ThrobberState,
   pub running: bool,
   pub error: bool,
+  /// Steps whose failure is recorded as a warning instead of aborting
+  optional: Vec<bool>,
+  durations: Vec<Option<Duration>>,
+  warnings: Vec<String>,
+  started: Option<Instant>,
+  step_started: Option<Instant>,
+  /// Time since the first step started, frozen once the install stops
+  elapsed: Duration,
   current_step_commands: Option<VecDeque<Command>>,
   current_command: Option<Child>,
+  /// Recent stdout/stderr lines of each step, oldest first
+  output: Vec<VecDeque<Line<'static>>>,
+  /// How many lines the output pane is scrolled up from the bottom
+  output_scroll: usize,
+  /// Rows the output pane had when last rendered, scrolling stops once the
+  /// first line is at the top
+  output_rows: usize,
+  /// Reader threads send `(step, line)` here, the receiving end is drained on
+  /// every tick so late lines still land in the right step
+  output_tx: Sender<(usize, String)>,
+  output_rx: Receiver<(usize, String)>,
 }
 
 impl<'a> InstallSteps<'a> {
@@ -1277,6 +1663,7 @@ impl<'a> InstallSteps<'a> {
       .map(|(line, _)| (line.clone(), StepStatus::Inactive))
       .collect();
     let num_steps = commands.len();
+    let (output_tx, output_rx) = mpsc::channel();
 
     Self {
       title: title.into(),
@@ -1287,8 +1674,61 @@ impl<'a> InstallSteps<'a> {
       throbber_state: ThrobberState::default(),
       running: false,
       error: false,
+      optional: vec![false; num_steps],
+      durations: vec![None; num_steps],
+      warnings: vec![],
+      started: None,
+      step_started: None,
+      elapsed: Duration::ZERO,
       current_step_commands: None,
       current_command: None,
+      output: vec![VecDeque::new(); num_steps],
+      output_scroll: 0,
+      output_rows: 0,
+      output_tx,
+      output_rx,
+    }
+  }
+
+  /// Let the install continue if this step fails
+  pub fn set_optional(&mut self, step: usize) {
+    if let Some(optional) = self.optional.get_mut(step) {
+      *optional = true;
+    }
+  }
+
+  pub fn summary(&self) -> InstallSummary {
+    let steps = self
+      .steps
+      .iter()
+      .zip(&self.durations)
+      .enumerate()
+      .map(|(i, ((_, status), duration))| StepReport {
+        title: self.step_title(i),
+        status: *status,
+        duration: *duration,
+      })
+      .collect();
+    InstallSummary {
+      steps,
+      warnings: self.warnings.clone(),
+      total: self.started.map(|t| t.elapsed()).unwrap_or_default(),
+    }
+  }
+
+  fn step_title(&self, step: usize) -> String {
+    self.steps[step]
+      .0
+      .spans
+      .iter()
+      .map(|span| span.content.as_ref())
+      .collect()
+  }
+
+  fn finish_step(&mut self, status: StepStatus) {
+    if self.current_step_index < self.steps.len() {
+      self.steps[self.current_step_index].1 = status;
+      self.durations[self.current_step_index] = self.step_started.take().map(|t| t.elapsed());
     }
   }
 
@@ -1299,13 +1739,44 @@ impl<'a> InstallSteps<'a> {
       let num_completed = self
         .steps
         .iter()
-        .filter(|step| step.1 == StepStatus::Completed)
+        .filter(|step| matches!(step.1, StepStatus::Completed | StepStatus::Skipped))
         .count();
 
       num_completed as f64 / self.num_steps as f64
     }
   }
 
+  /// Rough time left, assuming the remaining steps take as long as the
+  /// finished ones did on average. Unknown until a step has finished
+  pub fn eta(&self) -> Option<Duration> {
+    let finished = self.durations.iter().flatten().collect::<Vec<_>>();
+    if finished.is_empty() {
+      return None;
+    }
+    let average = finished.iter().copied().sum::<Duration>() / finished.len() as u32;
+    let remaining = self.num_steps.saturating_sub(finished.len()) as u32;
+    let in_current = self.step_started.map(|t| t.elapsed()).unwrap_or_default();
+    Some((average * remaining).saturating_sub(in_current))
+  }
+
+  /// e.g. "Elapsed: 04:12 - Step 4/6 - about 02:30 left"
+  pub fn status_line(&self) -> String {
+    let mmss = |d: Duration| format!("{:02}:{:02}", d.as_secs() / 60, d.as_secs() % 60);
+    let step = (self.current_step_index + 1).min(self.num_steps);
+    let mut line = format!(
+      "Elapsed: {} - Step {step}/{}",
+      mmss(self.elapsed),
+      self.num_steps
+    );
+    if !self.error
+      && !self.is_complete()
+      && let Some(eta) = self.eta()
+    {
+      line.push_str(&format!(" - about {} left", mmss(eta)));
+    }
+    line
+  }
+
   pub fn start_next_step(&mut self) -> anyhow::Result<()> {
     // If we have a current step still running, don't start a new one
     if self.current_step_commands.is_some() {
@@ -1318,6 +1789,9 @@ impl<'a> InstallSteps<'a> {
       if self.current_step_index < self.steps.len() {
         self.steps[self.current_step_index].1 = StepStatus::Running;
       }
+      self.started.get_or_insert_with(Instant::now);
+      self.step_started = Some(Instant::now());
+      self.output_scroll = 0;
 
       // Store the commands for this step
       self.current_step_commands = Some(commands);
@@ -1329,20 +1803,133 @@ impl<'a> InstallSteps<'a> {
     // Get the next command from the current step
     if let Some(commands) = self.current_step_commands.as_mut() {
       if let Some(mut cmd) = commands.pop_front() {
-        // Redirect all output to /dev/null
-        let null = std::fs::File::create("/dev/null")?;
         cmd
-          .stdout(Stdio::from(null.try_clone()?))
-          .stderr(Stdio::from(null))
+          .stdout(Stdio::piped())
+          .stderr(Stdio::piped())
           .stdin(Stdio::null());
 
-        let child = cmd.spawn()?;
+        let mut child = cmd.spawn()?;
+        let step = self.current_step_index;
+        if let Some(stdout) = child.stdout.take() {
+          self.forward_output(step, stdout);
+        }
+        if let Some(stderr) = child.stderr.take() {
+          self.forward_output(step, stderr);
+        }
         self.current_command = Some(child);
       }
     }
     Ok(())
   }
 
+  /// Read `pipe` line by line on its own thread until the command closes it
+  fn forward_output(&self, step: usize, pipe: impl Read + Send + 'static) {
+    let tx = self.output_tx.clone();
+    thread::spawn(move || {
+      for line in BufReader::new(pipe).split(b'\n') {
+        let Ok(line) = line else { break };
+        let line = String::from_utf8_lossy(&line);
+        // Progress bars redraw with \r, only the last state is worth keeping
+        let line = line.trim_end().rsplit('\r').next().unwrap_or_default();
+        if tx.send((step, line.to_string())).is_err() {
+          break;
+        }
+      }
+    });
+  }
+
+  fn poll_output(&mut self) {
+    let shown = self.shown_step();
+    while let Ok((step, line)) = self.output_rx.try_recv() {
+      let Some(buf) = self.output.get_mut(step) else {
+        continue;
+      };
+      let parsed = line
+        .into_text()
+        .map(|text| text.lines)
+        .unwrap_or_else(|_| vec![Line::from(line)]);
+      for parsed_line in parsed {
+        buf.push_back(parsed_line);
+        if buf.len() > MAX_OUTPUT_LINES {
+          buf.pop_front();
+        }
+      }
+      // Keep a scrolled view in place while lines come in
+      if self.output_scroll > 0 && step == shown {
+        self.output_scroll += 1;
+      }
+    }
+    self.output_scroll = self.output_scroll.min(self.max_output_scroll());
+  }
+
+  fn max_output_scroll(&self) -> usize {
+    let len = self.output.get(self.shown_step()).map_or(0, |b| b.len());
+    len.saturating_sub(self.output_rows)
+  }
+
+  pub fn set_output_rows(&mut self, rows: usize) {
+    self.output_rows = rows;
+    self.output_scroll = self.output_scroll.min(self.max_output_scroll());
+  }
+
+  /// The step whose output is shown, the failed one if the install stopped
+  fn shown_step(&self) -> usize {
+    self
+      .current_step_index
+      .min(self.steps.len().saturating_sub(1))
+  }
+
+  /// Last `count` output lines of `step`, for reports after a failure
+  pub fn output_tail(&self, step: usize, count: usize) -> Vec<String> {
+    let Some(buf) = self.output.get(step) else {
+      return vec![];
+    };
+    buf
+      .iter()
+      .skip(buf.len().saturating_sub(count))
+      .map(|line| line.spans.iter().map(|s| s.content.as_ref()).collect())
+      .collect()
+  }
+
+  pub fn scroll_output_up(&mut self, lines: usize) {
+    self.output_scroll = (self.output_scroll + lines).min(self.max_output_scroll());
+  }
+
+  pub fn scroll_output_down(&mut self, lines: usize) {
+    self.output_scroll = self.output_scroll.saturating_sub(lines);
+  }
+
+  pub fn scroll_output_top(&mut self) {
+    self.output_scroll = self.max_output_scroll();
+  }
+
+  /// Back to following new output
+  pub fn scroll_output_bottom(&mut self) {
+    self.output_scroll = 0;
+  }
+
+  /// Output of the running (or failed) step
+  pub fn render_output(&self, f: &mut Frame, area: Rect) {
+    let step = self.shown_step();
+    let empty = VecDeque::new();
+    let buf = self.output.get(step).unwrap_or(&empty);
+    let rows = area.height.saturating_sub(2) as usize;
+    let end = buf.len() - self.output_scroll.min(buf.len());
+    let start = end.saturating_sub(rows);
+    let visible = buf.range(start..end).cloned().collect::<Vec<_>>();
+    let mut title = if self.steps.is_empty() {
+      "Output".to_string()
+    } else {
+      format!("Output: {}", self.step_title(step))
+    };
+    if self.output_scroll > 0 {
+      title.push_str(" (scrolled, End to follow)");
+    }
+    let paragraph =
+      Paragraph::new(visible).block(Block::default().title(title).borders(Borders::ALL));
+    f.render_widget(paragraph, area);
+  }
+
   pub fn tick(&mut self) -> anyhow::Result<()> {
     if !self.running && !self.error {
       self.start_next_step()?;
@@ -1351,7 +1938,11 @@ impl<'a> InstallSteps<'a> {
 
     if self.running {
       self.throbber_state.calc_next();
+      if let Some(started) = self.started {
+        self.elapsed = started.elapsed();
+      }
     }
+    self.poll_output();
 
     // If no command is currently running, try to start the next one
     if self.current_command.is_none() && self.current_step_commands.is_some() {
@@ -1363,10 +1954,20 @@ impl<'a> InstallSteps<'a> {
         self.current_command = None;
 
         if !status.success() {
-          // Command failed - mark current step as failed
-          if self.current_step_index < self.steps.len() {
-            self.steps[self.current_step_index].1 = StepStatus::Failed;
+          if self.optional.get(self.current_step_index) == Some(&true) {
+            // Drop the rest of this step and move on to the next one
+            let title = self.step_title(self.current_step_index);
+            self
+              .warnings
+              .push(format!("{title} failed ({status}), skipped"));
+            self.finish_step(StepStatus::Skipped);
+            self.current_step_commands = None;
+            self.current_step_index += 1;
+            self.running = false;
+            return Ok(());
           }
+          // Command failed - mark current step as failed
+          self.finish_step(StepStatus::Failed);
           self.error = true;
           self.running = false;
           return Ok(());
@@ -1376,9 +1977,7 @@ impl<'a> InstallSteps<'a> {
         if let Some(commands) = &self.current_step_commands {
           if commands.is_empty() {
             // Step completed successfully
-            if self.current_step_index < self.steps.len() {
-              self.steps[self.current_step_index].1 = StepStatus::Completed;
-            }
+            self.finish_step(StepStatus::Completed);
             self.current_step_commands = None;
             self.current_step_index += 1;
             self.running = false;
@@ -1431,6 +2030,12 @@ impl<'a> ConfigWidget for InstallSteps<'a> {
           "✗ ",
           Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
         ),
+        StepStatus::Skipped => (
+          "! ",
+          Style::default()
+            .fg(Color::Yellow)
+            .add_modifier(Modifier::BOLD),
+        ),
       };
 
       let mut step_spans = vec![Span::styled(prefix, style)];
@@ -1609,10 +2214,10 @@ impl ConfigWidget for TableWidget {
   fn handle_input(&mut self, key: KeyEvent) -> Signal {
     if let Some(_idx) = self.selected_row.as_mut() {
       match key.code {
-        ui_up!() => {
+        code if ui_up!(code) => {
           self.next_row();
         }
-        ui_down!() => {
+        code if ui_down!(code) => {
           self.previous_row();
         }
         _ => {}
@@ -1753,6 +2358,187 @@ impl<'a> HelpModal<'a> {
   }
 }
 
+/// Yes/No question drawn over the current page, the same way as `HelpModal`
+///
+/// "No" is selected when it opens so a stray Enter doesn't confirm.
+pub struct ConfirmModal {
+  pub visible: bool,
+  pub title: String,
+  pub message: String,
+  buttons: WidgetBox,
+}
+
+impl ConfirmModal {
+  pub fn new(title: impl Into<String>, message: impl Into<String>) -> Self {
+    let buttons: Vec<Box<dyn ConfigWidget>> =
+      vec![Box::new(Button::new("Yes")), Box::new(Button::new("No"))];
+    let mut buttons = WidgetBoxBuilder::new().children(buttons).build();
+    buttons.focus();
+    Self {
+      visible: false,
+      title: title.into(),
+      message: message.into(),
+      buttons,
+    }
+  }
+
+  pub fn show(&mut self) {
+    self.buttons.select_child(1);
+    self.visible = true;
+  }
+
+  pub fn hide(&mut self) {
+    self.visible = false;
+  }
+
+  /// `Some(answer)` once the question has been answered, which also closes
+  /// the modal
+  pub fn handle_input(&mut self, key: KeyEvent) -> Option<bool> {
+    let answer = match key.code {
+      KeyCode::Char('y') => Some(true),
+      KeyCode::Char('n') | KeyCode::Esc => Some(false),
+      KeyCode::Enter => Some(self.buttons.selected_child() == Some(0)),
+      code if ui_left!(code) => {
+        self.buttons.prev_child();
+        None
+      }
+      code if ui_right!(code) || code == KeyCode::Tab => {
+        self.buttons.next_child();
+        None
+      }
+      _ => None,
+    };
+    if answer.is_some() {
+      self.hide();
+    }
+    answer
+  }
+
+  pub fn render(&self, f: &mut Frame, area: Rect) {
+    if !self.visible {
+      return;
+    }
+
+    let popup_width = (area.width / 2).max(40).min(area.width);
+    let popup_height = 7.min(area.height);
+    let popup_area = Rect {
+      x: area.x + area.width.saturating_sub(popup_width) / 2,
+      y: area.y + area.height.saturating_sub(popup_height) / 2,
+      width: popup_width,
+      height: popup_height,
+    };
+
+    f.render_widget(Clear, popup_area);
+    let block = Block::default()
+      .title(self.title.clone())
+      .borders(Borders::ALL)
+      .border_style(Style::default().fg(Color::Yellow))
+      .style(Style::default().bg(Color::Black));
+    let inner = block.inner(popup_area);
+    f.render_widget(block, popup_area);
+
+    let chunks = Layout::default()
+      .direction(ratatui::layout::Direction::Vertical)
+      .margin(1)
+      .constraints([Constraint::Min(1), Constraint::Length(1)])
+      .split(inner);
+    let message = Paragraph::new(self.message.clone())
+      .style(Style::default().bg(Color::Black).fg(Color::White))
+      .alignment(Alignment::Center)
+      .wrap(ratatui::widgets::Wrap { trim: true });
+    f.render_widget(message, chunks[0]);
+    self.buttons.render(f, chunks[1]);
+  }
+}
+
+/// A message drawn over the current page, closed with Enter or Esc
+pub struct MessageModal {
+  pub visible: bool,
+  pub title: String,
+  pub message: String,
+  /// Border colour, red for errors
+  pub color: Color,
+}
+
+impl MessageModal {
+  pub fn new() -> Self {
+    Self {
+      visible: false,
+      title: String::new(),
+      message: String::new(),
+      color: Color::Yellow,
+    }
+  }
+
+  pub fn show(&mut self, title: impl Into<String>, message: impl Into<String>, color: Color) {
+    self.title = title.into();
+    self.message = message.into();
+    self.color = color;
+    self.visible = true;
+  }
+
+  pub fn hide(&mut self) {
+    self.visible = false;
+  }
+
+  /// Whether `key` closed the modal
+  pub fn handle_input(&mut self, key: KeyEvent) -> bool {
+    if matches!(key.code, KeyCode::Enter | KeyCode::Esc) {
+      self.hide();
+      return true;
+    }
+    false
+  }
+
+  pub fn render(&self, f: &mut Frame, area: Rect) {
+    if !self.visible {
+      return;
+    }
+
+    let popup_width = (area.width / 2).max(40).min(area.width);
+    let popup_height = 9.min(area.height);
+    let popup_area = Rect {
+      x: area.x + area.width.saturating_sub(popup_width) / 2,
+      y: area.y + area.height.saturating_sub(popup_height) / 2,
+      width: popup_width,
+      height: popup_height,
+    };
+
+    f.render_widget(Clear, popup_area);
+    let message = Paragraph::new(vec![
+      Line::from(self.message.clone()),
+      Line::from(""),
+      Line::styled("Press Enter to close", Style::default().fg(Color::Gray)),
+    ])
+    .block(
+      Block::default()
+        .title(self.title.clone())
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(self.color))
+        .style(Style::default().bg(Color::Black)),
+    )
+    .style(Style::default().bg(Color::Black).fg(Color::White))
+    .alignment(Alignment::Center)
+    .wrap(ratatui::widgets::Wrap { trim: true });
+    f.render_widget(message, popup_area);
+  }
+}
+
+impl Default for MessageModal {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+/// Where the user left a `PackagePicker`, so reopening it can pick up from
+/// the same spot
+#[derive(Clone, Default, Debug)]
+pub struct PickerViewState {
+  pub filter: Option<String>,
+  pub available_idx: usize,
+  pub selected_idx: usize,
+}
+
 /// Complete package selection interface with dual-pane layout
 ///
 /// Provides a sophisticated package picker with:
@@ -1774,6 +2560,8 @@ pub struct PackagePicker {
   pub search_bar: LineEditor,
   help_modal: HelpModal<'static>,
   pub current_filter: Option<String>,
+  /// Shown under the selection count, e.g. a size estimate
+  pub status_note: Option<Line<'static>>,
 }
 
 impl PackagePicker {
@@ -1808,7 +2596,7 @@ impl PackagePicker {
             ratatui::style::Color::Yellow,
             ratatui::style::Modifier::BOLD,
           )),
-          "↑/↓, j/k",
+          keys::bindings().nav_label,
         ),
         (None, " - Navigate package lists"),
       ],
@@ -1859,13 +2647,53 @@ impl PackagePicker {
       search_bar,
       help_modal,
       current_filter: None,
+      status_note: None,
     }
   }
 
+  /// Add page-specific keys to the help modal
+  pub fn extend_help(&mut self, lines: Vec<Line<'static>>) {
+    self.help_modal.content.extend(lines);
+  }
+
+  /// Whether keys are going to a list rather than the search bar or help
+  pub fn lists_focused(&self) -> bool {
+    !self.help_modal.visible && !self.search_bar.is_focused()
+  }
+
+  fn status_lines(&self) -> Vec<Line<'static>> {
+    let count = self.package_manager.get_selected_packages().len();
+    let noun = if count == 1 { "package" } else { "packages" };
+    let mut lines = vec![Line::from(format!("{count} {noun} selected"))];
+    lines.extend(self.status_note.clone());
+    lines
+  }
+
   pub fn get_selected_packages(&self) -> Vec<String> {
     self.package_manager.get_selected_packages()
   }
 
+  pub fn view_state(&self) -> PickerViewState {
+    PickerViewState {
+      filter: self.current_filter.clone(),
+      available_idx: self.available.selected_idx,
+      selected_idx: self.selected.selected_idx,
+    }
+  }
+
+  pub fn restore_view_state(&mut self, state: PickerViewState) {
+    if let Some(ref filter) = state.filter {
+      self.search_bar.set_value(filter);
+    }
+    self.set_filter(state.filter);
+    self.available.selected_idx = state
+      .available_idx
+      .min(self.available.len().saturating_sub(1));
+    self.selected.selected_idx = state
+      .selected_idx
+      .min(self.selected.len().saturating_sub(1));
+  }
+
   pub fn get_available_packages(&self) -> Vec<String> {
     self.package_manager.get_available_packages()
   }
@@ -1887,6 +2715,38 @@ impl PackagePicker {
     self.available.set_items(items);
   }
 
+  /// Search for pasted text, opening the search bar if it isn't already
+  pub fn paste(&mut self, text: &str) {
+    if self.help_modal.visible {
+      return;
+    }
+    if !self.search_bar.is_focused() {
+      self.search_bar.focus();
+      self.search_bar.clear();
+      self.available.unfocus();
+      self.selected.unfocus();
+    }
+    self.search_bar.paste(text);
+    self.filter_from_search();
+  }
+
+  fn filter_from_search(&mut self) {
+    let filter_text = self
+      .search_bar
+      .get_value()
+      .and_then(|v| v.as_str().map(|s| s.to_string()));
+
+    if let Some(filter) = filter_text {
+      if !filter.is_empty() {
+        self.set_filter(Some(filter));
+      } else {
+        self.set_filter(None);
+      }
+    } else {
+      self.set_filter(None);
+    }
+  }
+
   fn set_filter(&mut self, filter: Option<String>) {
     self.current_filter = filter.clone();
     let items = if let Some(filter) = filter {
@@ -1916,6 +2776,7 @@ impl ConfigWidget for PackagePicker {
       [Constraint::Length(5), Constraint::Min(0),]
     );
 
+    InfoBox::new("Selection", self.status_lines()).render(f, vert_chunks_left[0]);
     self.selected.render(f, vert_chunks_left[1]);
     self.search_bar.render(f, vert_chunks_right[0]);
     self.available.render(f, vert_chunks_right[1]);
@@ -1965,34 +2826,21 @@ impl ConfigWidget for PackagePicker {
         }
         _ => {
           let signal = self.search_bar.handle_input(event);
-          let filter_text = self
-            .search_bar
-            .get_value()
-            .and_then(|v| v.as_str().map(|s| s.to_string()));
-
-          if let Some(filter) = filter_text {
-            if !filter.is_empty() {
-              self.set_filter(Some(filter));
-            } else {
-              self.set_filter(None);
-            }
-          } else {
-            self.set_filter(None);
-          }
+          self.filter_from_search();
           signal
         }
       }
     } else if self.selected.is_focused() {
       match event.code {
-        crate::ui_right!() => {
+        code if crate::ui_right!(code) => {
           self.focus_available();
           Signal::Wait
         }
-        crate::ui_down!() => {
+        code if crate::ui_down!(code) => {
           self.selected.next_item();
           Signal::Wait
         }
-        crate::ui_up!() => {
+        code if crate::ui_up!(code) => {
           if !self.selected.previous_item() {
             self.search_bar.focus();
             self.selected.unfocus();
@@ -2020,15 +2868,15 @@ impl ConfigWidget for PackagePicker {
       }
     } else if self.available.is_focused() {
       match event.code {
-        ui_left!() => {
+        code if ui_left!(code) => {
           self.focus_selected();
           Signal::Wait
         }
-        ui_down!() => {
+        code if ui_down!(code) => {
           self.available.next_item();
           Signal::Wait
         }
-        ui_up!() => {
+        code if ui_up!(code) => {
           if !self.available.previous_item() {
             self.search_bar.focus();
             self.available.unfocus();
@@ -2139,196 +2987,119 @@ impl ConfigWidget for ProgressBar {
   }
 }
 
-/// Widget that displays streaming log output from a file
-///
-/// Features:
-/// - Real-time log file monitoring (similar to 'tail -f')
-/// - ANSI color code support for highlighted output
-/// - Circular buffer to prevent memory growth
-/// - Handles log rotation and file truncation
-/// - Efficient incremental reading
-pub struct LogBox<'a> {
-  title: String,
-  focused: bool,
-  pub line_buf: VecDeque<Line<'a>>, // Circular buffer of log lines
-  max_buf_size: usize,              // Maximum lines to keep in memory
-  log_file: Option<File>,           // File handle for writing
-  reader: Option<BufReader<File>>,  // File reader for monitoring
-  file_pos: u64,                    // Current read position in file
-  log_path: Option<PathBuf>,        // Path to the log file
-}
-
-impl<'a> LogBox<'a> {
-  pub fn new(title: String) -> Self {
-    let line_buf = std::iter::repeat_n(String::new().into(), 100)
-      .collect::<Vec<_>>()
-      .into();
-    Self {
-      title,
-      focused: false,
-      line_buf,
-      max_buf_size: 100,
-      log_file: None,
-      reader: None,
-      file_pos: 0,
-      log_path: None,
-    }
-  }
-
-  pub fn open_log<P: Into<PathBuf>>(&mut self, path: P) -> anyhow::Result<()> {
-    let path = path.into();
-
-    // Open the file for read/write, create if missing, but don't truncate
-    let file = OpenOptions::new()
-      .read(true)
-      .write(true)
-      .create(true)
-      .truncate(false)
-      .open(&path)?;
-
-    let file_pos = file.metadata()?.len(); // start reading at EOF
-    let reader = BufReader::new(file.try_clone()?);
-    self.log_file = Some(file);
-    self.reader = Some(reader);
-    self.file_pos = file_pos;
-    self.log_path = Some(path);
-    Ok(())
-  }
-
-  /// Poll the log file for new content - call this each render cycle
-  ///
-  /// Efficiently reads only new lines since last poll:
-  /// - Detects file truncation/rotation and handles gracefully
-  /// - Parses ANSI escape codes for colored output
-  /// - Maintains circular buffer to prevent memory bloat
-  pub fn poll_log(&mut self) -> anyhow::Result<()> {
-    let Some(path) = &self.log_path else {
-      return Ok(());
-    };
+#[cfg(test)]
+mod tests {
+  use super::*;
 
-    // Early return if log file doesn't exist yet
-    if !path.exists() {
-      return Ok(());
-    }
-
-    // Check current file size for changes
-    let current_size = std::fs::metadata(path)?.len();
-
-    // Handle log rotation or truncation
-    if current_size < self.file_pos {
-      self.file_pos = 0;
-      // Reopen the file since it was truncated or rotated
-      let file = OpenOptions::new()
-        .read(true)
-        .write(true)
-        .create(true)
-        .truncate(false)
-        .open(path)?;
-      let reader = BufReader::new(file.try_clone()?);
-      self.log_file = Some(file);
-      self.reader = Some(reader);
-    }
-
-    // No new data to read
-    if current_size <= self.file_pos {
-      return Ok(());
-    }
-
-    // Use existing reader, seeking to our position if needed
-    let reader = match &mut self.reader {
-      Some(reader) => reader,
-      None => {
-        // If no reader exists, create one
-        let file = OpenOptions::new()
-          .read(true)
-          .write(true)
-          .create(true)
-          .truncate(false)
-          .open(path)?;
-        let reader = BufReader::new(file.try_clone()?);
-        self.log_file = Some(file);
-        self.reader = Some(reader);
-        self.reader.as_mut().unwrap()
-      }
-    };
-
-    reader.seek(SeekFrom::Start(self.file_pos))?;
-
-    // Read and process all new lines
-    let mut line = String::new();
-    while reader.read_line(&mut line)? > 0 {
-      let trimmed = strip_ansi_escapes::strip_str(line.trim_end());
-
-      // Parse ANSI escape codes for colored terminal output
-      if let Ok(text) = trimmed.into_text() {
-        for parsed_line in text.lines {
-          self.line_buf.push_back(parsed_line);
-          // Maintain circular buffer size
-          if self.line_buf.len() > self.max_buf_size {
-            self.line_buf.pop_front();
-          }
-        }
-      } else {
-        // Fallback for lines that can't be parsed
-        self.line_buf.push_back(trimmed.to_string().into());
-        if self.line_buf.len() > self.max_buf_size {
-          self.line_buf.pop_front();
-        }
-      }
-
-      line.clear();
-    }
-
-    // Update file position to track what we've read
-    self.file_pos = current_size;
-    Ok(())
-  }
-  pub fn write_log(&mut self, log_output: &str) {
-    if let Ok(text) = log_output.into_text() {
-      for line in text.lines {
-        self.line_buf.push_back(line);
-        if self.line_buf.len() > self.max_buf_size {
-          self.line_buf.pop_front();
-        }
-      }
-    } else {
-      self.line_buf.push_back(log_output.to_string().into());
-      if self.line_buf.len() > self.max_buf_size {
-        self.line_buf.pop_front();
-      }
-    }
-  }
-}
-
-impl<'a> ConfigWidget for LogBox<'a> {
-  fn handle_input(&mut self, _key: KeyEvent) -> Signal {
-    Signal::Wait
-  }
-  fn render(&self, f: &mut Frame, area: Rect) {
-    let lines = self.line_buf.iter().cloned().collect::<Vec<Line>>();
-    let rows = area.height.saturating_sub(2) as usize;
-    let visible_lines = lines
+  fn layout_bar(sizes: &[u64]) -> LayoutBar {
+    let segments = sizes
       .iter()
-      .skip(lines.len().saturating_sub(rows))
-      .cloned()
-      .collect::<Vec<Line>>();
-    let paragraph = Paragraph::new(visible_lines)
-      .block(
-        Block::default()
-          .title(self.title.clone())
-          .borders(Borders::ALL),
-      )
-      .wrap(ratatui::widgets::Wrap { trim: true });
-
-    f.render_widget(paragraph, area);
-  }
-  fn focus(&mut self) {
-    self.focused = true;
-  }
-  fn unfocus(&mut self) {
-    self.focused = false;
-  }
-  fn is_focused(&self) -> bool {
-    self.focused
+      .map(|&size| BarSegment {
+        label: String::new(),
+        size,
+        color: Color::Reset,
+      })
+      .collect();
+    LayoutBar::new("", segments)
+  }
+
+  #[test]
+  fn layout_bar_stays_within_its_width() {
+    // Ten tiny partitions each take a column, the big one gives them up
+    let mut sizes = vec![1; 10];
+    sizes.insert(0, 1_000_000);
+    let widths = layout_bar(&sizes).segment_widths(20);
+    assert_eq!(widths.iter().sum::<u16>(), 20);
+    assert_eq!(widths[0], 10);
+    assert!(widths[1..].iter().all(|w| *w == 1));
+
+    let widths = layout_bar(&[1; 8]).segment_widths(5);
+    assert_eq!(widths, vec![1, 1, 1, 1, 1, 0, 0, 0]);
+
+    let widths = layout_bar(&[1, 1, 2]).segment_widths(10);
+    assert_eq!(widths.iter().sum::<u16>(), 10);
+  }
+
+  fn press(editor: &mut LineEditor, code: KeyCode, modifiers: KeyModifiers) {
+    editor.handle_input(KeyEvent::new(code, modifiers));
+  }
+
+  fn editor_at_end(value: &str) -> LineEditor {
+    let mut editor = LineEditor::new("Test", None::<&str>);
+    editor.set_value(value);
+    press(&mut editor, KeyCode::End, KeyModifiers::NONE);
+    editor
+  }
+
+  #[test]
+  fn word_jumps_skip_repeated_slashes() {
+    let mut editor = editor_at_end("/nix//store///pkg");
+    press(&mut editor, KeyCode::Left, KeyModifiers::CONTROL);
+    assert_eq!(editor.cursor, 14);
+    press(&mut editor, KeyCode::Left, KeyModifiers::CONTROL);
+    assert_eq!(editor.cursor, 6);
+    press(&mut editor, KeyCode::Left, KeyModifiers::ALT);
+    assert_eq!(editor.cursor, 1);
+    press(&mut editor, KeyCode::Left, KeyModifiers::ALT);
+    assert_eq!(editor.cursor, 0);
+    press(&mut editor, KeyCode::Right, KeyModifiers::CONTROL);
+    assert_eq!(editor.cursor, 4);
+    press(&mut editor, KeyCode::Right, KeyModifiers::CONTROL);
+    assert_eq!(editor.cursor, 11);
+    press(&mut editor, KeyCode::Right, KeyModifiers::ALT);
+    assert_eq!(editor.cursor, 17);
+    press(&mut editor, KeyCode::Right, KeyModifiers::ALT);
+    assert_eq!(editor.cursor, 17);
+  }
+
+  #[test]
+  fn word_jumps_skip_repeated_spaces() {
+    let mut editor = editor_at_end("one   two  ");
+    press(&mut editor, KeyCode::Left, KeyModifiers::CONTROL);
+    assert_eq!(editor.cursor, 6);
+    press(&mut editor, KeyCode::Left, KeyModifiers::CONTROL);
+    assert_eq!(editor.cursor, 0);
+    press(&mut editor, KeyCode::Right, KeyModifiers::CONTROL);
+    assert_eq!(editor.cursor, 3);
+    press(&mut editor, KeyCode::Right, KeyModifiers::CONTROL);
+    assert_eq!(editor.cursor, 9);
+  }
+
+  #[test]
+  fn multibyte_text_is_edited_by_char() {
+    let mut editor = editor_at_end("café");
+    press(&mut editor, KeyCode::Left, KeyModifiers::NONE);
+    press(&mut editor, KeyCode::Char('ß'), KeyModifiers::NONE);
+    assert_eq!(editor.value, "cafßé");
+    assert_eq!(editor.cursor, 4);
+    press(&mut editor, KeyCode::Delete, KeyModifiers::NONE);
+    assert_eq!(editor.value, "cafß");
+    press(&mut editor, KeyCode::Backspace, KeyModifiers::NONE);
+    press(&mut editor, KeyCode::Backspace, KeyModifiers::NONE);
+    assert_eq!(editor.value, "ca");
+    assert_eq!(editor.cursor, 2);
+    editor.paste("日本 ");
+    press(&mut editor, KeyCode::Char('🦀'), KeyModifiers::NONE);
+    assert_eq!(editor.value, "ca日本 🦀");
+    assert_eq!(editor.cursor, 6);
+    press(&mut editor, KeyCode::Char('w'), KeyModifiers::CONTROL);
+    assert_eq!(editor.value, "ca日本 ");
+    press(&mut editor, KeyCode::Home, KeyModifiers::NONE);
+    press(&mut editor, KeyCode::Right, KeyModifiers::NONE);
+    press(&mut editor, KeyCode::Right, KeyModifiers::NONE);
+    press(&mut editor, KeyCode::Delete, KeyModifiers::NONE);
+    assert_eq!(editor.value, "ca本 ");
+  }
+
+  #[test]
+  fn delete_word_takes_trailing_separators_with_it() {
+    let mut editor = editor_at_end("/etc/nixos//  ");
+    press(&mut editor, KeyCode::Char('w'), KeyModifiers::CONTROL);
+    assert_eq!(editor.value, "/etc/");
+    press(&mut editor, KeyCode::Backspace, KeyModifiers::ALT);
+    assert_eq!(editor.value, "/");
+    press(&mut editor, KeyCode::Backspace, KeyModifiers::CONTROL);
+    assert_eq!(editor.value, "");
+    assert_eq!(editor.cursor, 0);
   }
 }